@@ -4,16 +4,20 @@ from .base_adapter import LanguageAdapter
 from .python_adapter import PythonAstGrepAdapter
 from .javascript_adapter import JavaScriptAstGrepAdapter
 from .java_adapter import JavaAdapter
+from .c_adapter import CAdapter
 from .cpp_adapter import CppAdapter
 from .rust_adapter import RustAdapter
 from .go_adapter import GoAdapter
+from .ruby_adapter import RubyAdapter
 
 __all__ = [
     "LanguageAdapter",
     "PythonAstGrepAdapter",
     "JavaScriptAstGrepAdapter",
     "JavaAdapter",
+    "CAdapter",
     "CppAdapter",
     "RustAdapter",
     "GoAdapter",
+    "RubyAdapter",
 ]
@@ -1,14 +1,18 @@
 """Base adapter interface for multi-language AST parsing using ast-grep."""
 
 from abc import ABC, abstractmethod
-from typing import Dict, List, Any, Tuple, Set
+from typing import Dict, List, Any, Optional, Tuple, Set
 import sys
 import os
 
 # Add parent directory to path for imports
 sys.path.insert(0, os.path.abspath(os.path.join(os.path.dirname(__file__), "../..")))
 
+from ast_parser.diagnostics import ParseError, ast_grep_errors, read_error
+from ast_parser.line_metrics import count_lines
 from ast_parser.parser import CodeNode, CodeRelation
+from ast_parser.positions import read_text
+from ast_parser.workspace import workspace_properties
 
 
 class LanguageAdapter(ABC):
@@ -37,8 +41,10 @@ class LanguageAdapter(ABC):
         self.pending_imports: List[Dict[str, Any]] = []
         self.module_to_file: Dict[str, str] = {}
         self.established_relations: Set[str] = set()
+        
+        # Syntax errors of the file parsed last
+        self.parse_errors: List[ParseError] = []
     
-    @abstractmethod
     def parse_file(self, file_path: str, build_index: bool = False) -> Tuple[Dict[str, CodeNode], List[CodeRelation]]:
         """
         Parse a single source file and extract nodes and relations.
@@ -47,11 +53,49 @@ class LanguageAdapter(ABC):
             file_path: Absolute path to the source file
             build_index: If True, populate module_definitions for cross-file resolution
         
+        Returns:
+            Tuple of (nodes dict, relations list); empty, with parse_errors
+            set, when the file cannot be read
+        """
+        try:
+            source = read_text(file_path)
+        except (OSError, UnicodeDecodeError) as e:
+            print(f"Error reading file {file_path}: {e}")
+            self._record_read_failure(e)
+            return {}, []
+        
+        return self.parse_source(source, file_path, build_index=build_index)
+    
+    @abstractmethod
+    def parse_source(self, source: str, file_path: str, build_index: bool = False) -> Tuple[Dict[str, CodeNode], List[CodeRelation]]:
+        """
+        Parse source text that may not exist on disk.
+        
+        ``file_path`` is used for node IDs and locations only; nothing is read
+        from the filesystem.
+        
+        Args:
+            source: Source code to parse
+            file_path: Path the nodes are attributed to
+            build_index: If True, populate module_definitions for cross-file resolution
+        
         Returns:
             Tuple of (nodes dict, relations list)
         """
         pass
     
+    def _record_syntax_errors(self, root: Any) -> None:
+        """Keep the ERROR nodes of a freshly parsed tree; declarations outside them are still extracted."""
+        self.parse_errors = ast_grep_errors(root)
+    
+    def _record_failure(self, message: str) -> None:
+        """Record a file that produced nothing because the adapter failed."""
+        self.parse_errors = [ParseError(message)]
+    
+    def _record_read_failure(self, error: Exception) -> None:
+        """Record a file that produced nothing because it could not be read."""
+        self.parse_errors = [read_error(error)]
+    
     def _get_node_id(self, node_type: str, name: str, file_path: str, line_no: int) -> str:
         """
         Generate unique node ID matching legacy parser format.
@@ -61,9 +105,9 @@ class LanguageAdapter(ABC):
         """
         return f"{node_type}:{file_path}:{name}:{line_no}"
     
-    def _create_file_node(self, file_path: str) -> str:
+    def _create_file_node(self, file_path: str, source: str = "") -> str:
         """
-        Create a file node for the given path.
+        Create a file node for the given path, with the line counts of its source.
         
         Returns the file node ID.
         """
@@ -76,9 +120,18 @@ class LanguageAdapter(ABC):
             name=file_name,
             file_path=file_path,
             line_no=0,
+            properties={**count_lines(source, self.language), **workspace_properties(file_path)},
         )
         return node_id
     
+    @staticmethod
+    def _same_node(a: Optional[Any], b: Any) -> bool:
+        """Compare two ast-grep nodes by kind and source range."""
+        if a is None:
+            return False
+        ra, rb = a.range(), b.range()
+        return a.kind() == b.kind() and (ra.start.index, ra.end.index) == (rb.start.index, rb.end.index)
+    
     def _add_relation(self, relation: CodeRelation) -> None:
         """
         Add a relation, preventing duplicates.
@@ -96,8 +149,38 @@ class LanguageAdapter(ABC):
             # Symbol import: include symbol name in key
             symbol = relation.properties.get("symbol", "")
             relation_key = f"{relation.source_id}|{relation.relation_type}|{relation.target_id}|{symbol}"
+        elif relation.relation_type == "REFERENCES":
+            # Every access site is a separate reference
+            relation_key += f"|{relation.properties.get('line_no', '')}|{relation.properties.get('access', '')}"
         
         # Only add if not already present
         if relation_key not in self.established_relations:
             self.relations.append(relation)
             self.established_relations.add(relation_key)
+    
+    def _find_local_node(self, node_type: str, name: str) -> Optional[str]:
+        """Find a node of the given type and name among this file's nodes."""
+        for node_id, node in self.nodes.items():
+            if node.node_type == node_type and node.name == name:
+                return node_id
+        return None
+    
+    def _add_member_call(self, caller_id: str, type_name: str, member_name: str,
+                         site: Dict[str, object], module_hints: List[str], raw_name: str) -> None:
+        """Link a call to a method of a type, deferring when the method is not in this file."""
+        for node_id, node in self.nodes.items():
+            if node.node_type == "Method" and node.name == member_name \
+                    and node.properties.get("method_of") == type_name:
+                self._add_relation(CodeRelation(caller_id, node_id, "CALLS",
+                                                properties={"class": type_name, **site}))
+                return
+        
+        self.pending_imports.append({
+            "type": "CALLS_MEMBER",
+            "source_id": caller_id,
+            "type_name": type_name,
+            "member_name": member_name,
+            "module_hints": module_hints,
+            "raw_name": raw_name,
+            **site,
+        })
@@ -0,0 +1,548 @@
+"""C language adapter using ast-grep for AST parsing."""
+
+import os
+import re
+from typing import Dict, Iterator, List, Optional, Tuple
+
+from ast_grep_py import SgRoot, SgNode
+
+from .base_adapter import LanguageAdapter
+from ast_parser.complexity import sg_complexity
+from ast_parser.parser import CodeNode, CodeRelation
+
+# Nodes whose children are declared at the same level as the node itself:
+# conditional compilation (include guards, #ifdef __cplusplus) and extern "C" blocks
+CONTAINER_KINDS = ("preproc_if", "preproc_ifdef", "preproc_else", "preproc_elif", "preproc_elifdef",
+                   "linkage_specification", "declaration_list")
+
+# Preprocessor conditionals; with an #else or #elif, every branch is indexed as if compiled together
+CONDITIONAL_KINDS = ("preproc_if", "preproc_ifdef")
+
+# Record specifiers and the type_kind of their Class node
+RECORD_KINDS = {"struct_specifier": "struct", "union_specifier": "union"}
+
+# Declarators wrapping the declared name; the name is their "declarator" field
+WRAPPER_KINDS = ("pointer_declarator", "reference_declarator", "array_declarator", "parenthesized_declarator",
+                 "function_declarator", "init_declarator", "attributed_declarator")
+
+# Nodes that are the declared name itself
+NAME_KINDS = ("identifier", "field_identifier", "type_identifier")
+
+# Names a function declarator may declare: free functions, methods in a class
+# body and out-of-line definitions such as ``Circle::area``
+FUNCTION_NAME_KINDS = ("identifier", "field_identifier", "qualified_identifier")
+
+# Parameter nodes of a parameter list
+PARAMETER_KINDS = ("parameter_declaration", "optional_parameter_declaration")
+
+# Characters of a macro body kept in its value property
+MAX_MACRO_VALUE = 200
+
+
+class CAdapter(LanguageAdapter):
+    """
+    C adapter using ast-grep library.
+
+    Extracts:
+    - File, Function, Class (structs and unions, with a ``type_kind``),
+      Enum, TypeAlias (typedef), Macro and Field nodes
+    - CONTAINS, DEFINES, CALLS relations
+    - Include tracking (#include directives), resolved to the included file
+
+    Function prototypes (``int printf(const char *, ...);``) are Function
+    nodes with ``prototype`` set, so a header declares its API even when no
+    definition is indexed. A definition is linked to the prototypes
+    declaring it, in its own file or an included header, by a DEFINES edge
+    with ``declaration`` set. Every function keeps its parameter types as
+    its ``signature`` (``(const char *, ...)``), so overloads are distinct
+    nodes told apart by it. ``static`` functions are Private, the rest
+    Public. Object-like and function-like macros keep their ``parameters``
+    and ``value``. An anonymous struct named by a typedef
+    (``typedef struct { .. } Point;``) takes the typedef's name.
+
+    Declarations inside include guards, ``#ifdef`` blocks and
+    ``extern "C" { }`` count as top level. The file's module is its base
+    name with the extension, so ``#include "shapes.h"`` links to shapes.h
+    rather than shapes.c. ``#include <stdio.h>`` is a system include: it is
+    marked ``system`` and never resolved, even when a stdio.h is indexed.
+    Calls are resolved against the file itself and then the headers it
+    includes with quotes, in order; among overloads only those taking the
+    call's number of arguments count, and a call matching several
+    signatures is left unresolved.
+
+    Code the adapter only partly understands is not resolved further but
+    marked: the File node lists its ``partial_regions`` (syntax errors,
+    often a macro used as syntax, C++ templates, and conditionals with
+    ``#else`` branches) and the declarations overlapping one are
+    ``partially_parsed``.
+
+    Supports C source files and headers (.c, .h). CppAdapter builds on it.
+    """
+
+    # Name used in error messages
+    LANGUAGE_NAME = "C"
+
+    # Container kinds descended into when listing top-level declarations
+    CONTAINER_KINDS = CONTAINER_KINDS
+
+    def __init__(self, language: str = "c"):
+        super().__init__(language)
+        self.current_file: str = ""
+        # Modules of the current file's quoted includes, in order
+        self._included_modules: List[str] = []
+        # The same includes as written, ``codec/buffer.h``
+        self._included_paths: List[str] = []
+        # Function definitions of the current file: (definition, node ID, class of a method)
+        self._definitions: List[Tuple[SgNode, str, Optional[str]]] = []
+
+    def parse_source(self, source: str, file_path: str, build_index: bool = False) -> Tuple[Dict[str, CodeNode], List[CodeRelation]]:
+        """
+        Parse source in this adapter's language with ast-grep.
+
+        Extracts functions, records, typedefs, macros and includes.
+        """
+        self.current_file = file_path
+        self._included_modules = []
+        self._included_paths = []
+        self._definitions = []
+
+        try:
+            # Parse with ast-grep in this adapter's language
+            root = SgRoot(source, self.language).root()
+            self._record_syntax_errors(root)
+
+            # Create file node
+            file_node_id = self._create_file_node(file_path, source)
+
+            # Headers and sources are told apart by the extension
+            module_name = os.path.basename(file_path)
+            if build_index:
+                if module_name not in self.module_definitions:
+                    self.module_definitions[module_name] = {}
+                self.module_to_file[module_name] = file_node_id
+
+            self._parse_declarations(root, file_node_id, build_index, module_name)
+            self._mark_partial_regions(root, file_node_id)
+            self._parse_calls(source)
+
+            return self.nodes, self.relations
+
+        except Exception as e:
+            print(f"Error parsing {self.LANGUAGE_NAME} file {file_path}: {e}")
+            self._record_failure(f"Parser failed: {e}")
+            return {}, []
+
+    def _parse_declarations(self, root: SgNode, file_node_id: str, build_index: bool, module_name: str) -> None:
+        """Extract every kind of declaration this adapter knows."""
+        self._parse_includes(root, file_node_id)
+        self._parse_macros(root, file_node_id, build_index, module_name)
+        self._parse_records(root, file_node_id, build_index, module_name)
+        self._parse_typedefs(root, file_node_id, build_index, module_name)
+        self._parse_functions(root, file_node_id, build_index, module_name)
+
+    def _mark_partial_regions(self, root: SgNode, file_node_id: str) -> None:
+        """
+        Record the regions of the file that were not fully understood, as
+        ``{start_line, end_line, reason}``, and flag the declarations
+        overlapping them. The reason is one of:
+
+        - ``syntax_error``: code the grammar could not parse
+        - ``template``: a C++ template, whose parameters are not substituted
+        - ``conditional``: an ``#if`` or ``#ifdef`` with ``#else`` or
+          ``#elif`` branches, all of them indexed
+        """
+        regions = [(error.line_no, error.end_line_no or error.line_no, "syntax_error")
+                   for error in self.parse_errors if error.line_no]
+        template_end = -1
+        for template in sorted(root.find_all(kind="template_declaration"), key=lambda n: n.range().start.index):
+            span = template.range()
+            # Templates nested in another one are part of its region
+            if span.start.line > template_end:
+                regions.append((span.start.line + 1, span.end.line + 1, "template"))
+                template_end = span.end.line
+        for kind in CONDITIONAL_KINDS:
+            for conditional in root.find_all(kind=kind):
+                if conditional.field("alternative") is not None:
+                    regions.append((conditional.range().start.line + 1, conditional.range().end.line + 1,
+                                    "conditional"))
+        if not regions:
+            return
+
+        regions.sort()
+        self.nodes[file_node_id].properties["partial_regions"] = [
+            {"start_line": start, "end_line": end, "reason": reason} for start, end, reason in regions]
+        for node in self.nodes.values():
+            if node.node_type == "File" or node.file_path != self.current_file:
+                continue
+            end_line = node.end_line_no or node.line_no
+            if any(start <= end_line and node.line_no <= end for start, end, _ in regions):
+                node.properties["partially_parsed"] = True
+
+    def _top_level_items(self, root: SgNode) -> Iterator[SgNode]:
+        """Top-level declarations in source order, looking through preprocessor conditionals and extern blocks."""
+        stack = list(reversed(root.children()))
+        while stack:
+            item = stack.pop()
+            if item.kind() in self.CONTAINER_KINDS:
+                stack.extend(reversed(item.children()))
+            else:
+                yield item
+
+    def _parse_includes(self, root: SgNode, file_node_id: str) -> None:
+        """Extract #include directives; ``<system>`` includes are marked and left unresolved."""
+        for include_node in root.find_all(kind="preproc_include"):
+            path = include_node.field("path")
+            if not path:
+                continue
+            written = path.text().strip()
+            # Remove quotes or angle brackets
+            include_path = written.strip('<>"')
+            if not include_path:
+                continue
+
+            module = include_path.split("/")[-1]
+            system = written.startswith("<")
+            if not system and module not in self._included_modules:
+                self._included_modules.append(module)
+            if not system and include_path not in self._included_paths:
+                self._included_paths.append(include_path)
+            self.pending_imports.append({
+                "type": "IMPORTS_MODULE",
+                "source_id": file_node_id,
+                "imported_module": module,
+                "full_module_path": include_path,
+                "system": system,
+                "line_no": include_node.range().start.line + 1,
+            })
+
+    def _parse_macros(self, root: SgNode, file_node_id: str, build_index: bool, module_name: str) -> None:
+        """Extract #define macros, object-like and function-like."""
+        for kind in ("preproc_def", "preproc_function_def"):
+            for macro_node in root.find_all(kind=kind):
+                name_field = macro_node.field("name")
+                if not name_field:
+                    continue
+
+                properties: Dict[str, object] = {}
+                parameters = macro_node.field("parameters")
+                if parameters:
+                    properties["parameters"] = [p.text() for p in parameters.children()
+                                                if p.kind() in ("identifier", "...")]
+                value = macro_node.field("value")
+                if value and value.text().strip():
+                    properties["value"] = " ".join(value.text().split())[:MAX_MACRO_VALUE]
+                self._create_node(macro_node, "Macro", name_field.text(), file_node_id, build_index,
+                                  module_name, properties)
+
+    def _parse_records(self, root: SgNode, file_node_id: str, build_index: bool, module_name: str) -> None:
+        """Extract struct, union and enum definitions; declarations without a body are skipped."""
+        for kind in list(RECORD_KINDS) + ["enum_specifier"]:
+            for specifier in root.find_all(kind=kind):
+                body = specifier.field("body")
+                if not body:
+                    continue
+                name = self._specifier_name(specifier)
+                if not name:
+                    continue
+
+                if kind == "enum_specifier":
+                    self._create_node(specifier, "Enum", name, file_node_id, build_index, module_name, {})
+                    continue
+                record_id = self._create_node(specifier, "Class", name, file_node_id, build_index, module_name,
+                                              {"type_kind": RECORD_KINDS[kind]})
+                self._parse_record_members(body, name, record_id)
+
+    def _specifier_name(self, specifier: SgNode) -> Optional[str]:
+        """Name of a struct, union or enum; an anonymous one takes the name of the typedef declaring it."""
+        name_field = specifier.field("name")
+        if name_field:
+            return name_field.text()
+        parent = specifier.parent()
+        if parent is not None and parent.kind() == "type_definition" and parent.field("declarator"):
+            return self._declarator_name(parent.field("declarator"))
+        return None
+
+    def _parse_record_members(self, body: SgNode, record_name: str, record_id: str) -> None:
+        """Extract the fields of a struct or union as Field nodes."""
+        for field_decl in body.children():
+            if field_decl.kind() != "field_declaration":
+                continue
+            base_type = self._type_text(field_decl.field("type"))
+            for declarator in self._declarators(field_decl):
+                field_name = self._declarator_name(declarator)
+                # A method prototype in a C++ struct is not a field
+                if not field_name or self._function_declarator(declarator) is not None:
+                    continue
+                line_no = field_decl.range().start.line + 1
+                field_node_id = self._get_node_id("Field", field_name, self.current_file, line_no)
+                self.nodes[field_node_id] = CodeNode(
+                    node_id=field_node_id,
+                    node_type="Field",
+                    name=field_name,
+                    file_path=self.current_file,
+                    line_no=line_no,
+                    end_line_no=field_decl.range().end.line + 1,
+                    properties={"field_of": record_name,
+                                "type": self._declared_type(base_type, declarator, field_name)},
+                )
+                self._add_relation(CodeRelation(record_id, field_node_id, "DEFINES"))
+
+    def _parse_typedefs(self, root: SgNode, file_node_id: str, build_index: bool, module_name: str) -> None:
+        """Extract typedefs as TypeAlias nodes with the aliased type."""
+        for item in self._top_level_items(root):
+            if item.kind() != "type_definition":
+                continue
+            base_type = self._type_text(item.field("type"))
+            for declarator in self._declarators(item):
+                name = self._declarator_name(declarator)
+                if name:
+                    self._create_node(item, "TypeAlias", name, file_node_id, build_index, module_name,
+                                      {"type": self._declared_type(base_type, declarator, name)})
+
+    def _parse_functions(self, root: SgNode, file_node_id: str, build_index: bool, module_name: str) -> None:
+        """Extract top-level function definitions and prototypes."""
+        for item in self._top_level_items(root):
+            if item.kind() == "function_definition":
+                declarators = [item.field("declarator")] if item.field("declarator") else []
+                prototype = False
+            elif item.kind() == "declaration":
+                declarators = self._declarators(item)
+                prototype = True
+            else:
+                continue
+
+            for declarator in declarators:
+                function = self._function_declarator(declarator)
+                if function is None:
+                    continue
+                scope, _, name = function.field("declarator").text().rpartition("::")
+                properties: Dict[str, object] = {"visibility": self._visibility(item),
+                                                 **self._signature(function)}
+                if not prototype:
+                    properties["complexity"] = sg_complexity(item, self.language)
+                if scope:
+                    # Out-of-line method definition, Circle::area: a method of the last scope
+                    if not prototype:
+                        properties["method_of"] = scope.rpartition("::")[2]
+                        self._create_method(item, name, file_node_id, properties)
+                    continue
+                if prototype:
+                    properties["prototype"] = True
+                    # A definition elsewhere in the module takes precedence in the index
+                    index = build_index and name not in self.module_definitions[module_name]
+                else:
+                    index = build_index
+                node_id = self._create_node(item, "Function", name, file_node_id, index, module_name, properties)
+                if not prototype:
+                    self._definitions.append((item, node_id, None))
+
+    def _create_method(self, item: SgNode, name: str, file_node_id: str, properties: Dict[str, object]) -> None:
+        """
+        A method defined outside its class body, contained by the file.
+
+        Its class may be declared in a header, so the DEFINES edge from the
+        class is left to the second pass.
+        """
+        node_id = self._create_node(item, "Method", name, file_node_id, False, "", properties)
+        self.pending_imports.append({
+            "type": "IMPL_METHOD",
+            "source_id": node_id,
+            "type_name": properties["method_of"],
+            "method_id": node_id,
+            "module_hints": self._call_modules(),
+        })
+        self._definitions.append((item, node_id, properties["method_of"]))
+
+    def _call_modules(self) -> List[str]:
+        """Modules a name used in the current file may come from: the file itself, then its quoted includes."""
+        module_name = os.path.basename(self.current_file)
+        return [module_name] + [m for m in self._included_modules if m != module_name]
+
+    def _parse_calls(self, source: str) -> None:
+        """
+        Queue the calls made by every function definition of the file, and
+        the link from each definition to the prototypes declaring it.
+
+        ``f(x)`` and ``ns::f(x)`` are resolved in the second pass by name and
+        number of arguments; calls through an object or a pointer
+        (``shape.area()``, ``r->fn()``) cannot be and stay unresolved.
+        """
+        modules = self._call_modules()
+        includes = list(self._included_paths)
+        source_lines = source.splitlines()
+        for definition, caller_id, method_of in self._definitions:
+            self.pending_imports.append({
+                "type": "DECLARED_BY",
+                "source_id": caller_id,
+                "modules": modules,
+                "includes": includes,
+            })
+            body = definition.field("body")
+            if body is None:
+                continue
+            for call in body.find_all(kind="call_expression"):
+                function = call.field("function")
+                if function is None:
+                    continue
+                line_no = call.range().start.line + 1
+                site = {
+                    "line_no": line_no,
+                    "call_site": source_lines[line_no - 1].strip() if line_no <= len(source_lines) else "",
+                }
+                if function.kind() not in ("identifier", "qualified_identifier"):
+                    self.pending_imports.append({
+                        "type": "CALLS_UNRESOLVED",
+                        "source_id": caller_id,
+                        "raw_name": function.text(),
+                        **site,
+                    })
+                    continue
+                arguments = call.field("arguments")
+                entry = {
+                    "type": "CALLS_C",
+                    "source_id": caller_id,
+                    "name": function.text().rpartition("::")[2],
+                    "raw_name": function.text(),
+                    "modules": modules,
+                    "includes": includes,
+                    "arg_count": len([a for a in arguments.children() if a.is_named() and a.kind() != "comment"])
+                    if arguments else 0,
+                    **site,
+                }
+                if method_of:
+                    entry["method_of"] = method_of
+                self.pending_imports.append(entry)
+
+    def _create_node(self, item: SgNode, node_type: str, name: str, file_node_id: str, build_index: bool,
+                     module_name: str, properties: Dict[str, object]) -> str:
+        """Create a top-level node contained by the file, indexed for cross-file resolution when asked."""
+        line_no = item.range().start.line + 1
+        node_id = self._get_node_id(node_type, name, self.current_file, line_no)
+        self.nodes[node_id] = CodeNode(
+            node_id=node_id,
+            node_type=node_type,
+            name=name,
+            file_path=self.current_file,
+            line_no=line_no,
+            end_line_no=item.range().end.line + 1,
+            properties=properties,
+        )
+        self._add_relation(CodeRelation(file_node_id, node_id, "CONTAINS"))
+        if build_index:
+            self.module_definitions[module_name][name] = node_id
+        return node_id
+
+    def _signature(self, function: SgNode) -> Dict[str, object]:
+        """
+        ``signature`` of a function declarator: its parameter types without
+        names or default values, ``(const char *, ...)``; ``(void)`` is
+        ``()``. Parameters with a default value are counted in ``default_args``.
+        """
+        types: List[str] = []
+        defaults = 0
+        parameters = function.field("parameters")
+        for parameter in parameters.children() if parameters else []:
+            if parameter.kind() in ("variadic_parameter", "variadic_parameter_declaration") \
+                    or parameter.text() == "...":
+                types.append("...")
+                continue
+            if parameter.kind() not in PARAMETER_KINDS:
+                continue
+            text = parameter.text()
+            default = parameter.field("default_value")
+            if default is not None:
+                defaults += 1
+                text = text[:text.rfind(default.text())].rstrip().rstrip("=")
+            declarator = parameter.field("declarator")
+            name = self._declarator_name(declarator) if declarator is not None else None
+            if name:
+                written = declarator.text()
+                start = text.rfind(written)
+                text = text[:start] + written.replace(name, "", 1) + text[start + len(written):]
+            types.append(self._normalize_type(text))
+        if types == ["void"]:
+            types = []
+        signature: Dict[str, object] = {"signature": f"({', '.join(types)})"}
+        if defaults:
+            signature["default_args"] = defaults
+        return signature
+
+    @staticmethod
+    def _normalize_type(text: str) -> str:
+        """One spelling of a type: single spaces, and a space before pointers and references (``char *``)."""
+        text = " ".join(text.split())
+        return re.sub(r"(\w)\s*([*&]+)", r"\1 \2", text).strip()
+
+    @staticmethod
+    def _declarators(declaration: SgNode) -> List[SgNode]:
+        """
+        Every declarator of a declaration, typedef or field: ``int x, *y;`` has two.
+
+        The type and any storage class or qualifier come before them, so
+        the declarators are the named children after the type.
+        """
+        type_node = declaration.field("type")
+        type_end = (type_node.range().end.line, type_node.range().end.column) if type_node else (-1, -1)
+        return [child for child in declaration.children()
+                if child.kind() in WRAPPER_KINDS + NAME_KINDS
+                and (child.range().start.line, child.range().start.column) >= type_end]
+
+    @staticmethod
+    def _declarator_name(declarator: SgNode) -> Optional[str]:
+        """The name a declarator declares, under any pointers, arrays and parentheses around it."""
+        node: Optional[SgNode] = declarator
+        while node is not None and node.kind() not in NAME_KINDS:
+            if node.kind() not in WRAPPER_KINDS:
+                return None
+            inner = node.field("declarator")
+            if inner is None:
+                # parenthesized_declarator has no field for what it wraps
+                inner = next((c for c in node.children() if c.is_named()), None)
+            node = inner
+        return node.text() if node is not None else None
+
+    @staticmethod
+    def _function_declarator(declarator: SgNode) -> Optional[SgNode]:
+        """
+        The function declarator of a function, under the pointers of its return type.
+
+        ``char *strdup(const char *)`` is a function; ``int (*handler)(int)``
+        is a pointer to one, a variable, and gives None.
+        """
+        node: Optional[SgNode] = declarator
+        while node is not None and node.kind() in ("pointer_declarator", "reference_declarator",
+                                                   "attributed_declarator"):
+            node = node.field("declarator") or next((c for c in node.children() if c.is_named()), None)
+        if node is None or node.kind() != "function_declarator":
+            return None
+        name = node.field("declarator")
+        return node if name is not None and name.kind() in FUNCTION_NAME_KINDS else None
+
+    @staticmethod
+    def _type_text(type_node: Optional[SgNode]) -> str:
+        """A type as written, or just ``struct Name`` for a record defined in place."""
+        if type_node is None:
+            return ""
+        if type_node.field("body") is not None:
+            keyword = type_node.kind().replace("_specifier", "")
+            name = type_node.field("name")
+            return f"{keyword} {name.text()}" if name else keyword
+        return " ".join(type_node.text().split())
+
+    @staticmethod
+    def _declared_type(base_type: str, declarator: SgNode, name: str) -> str:
+        """
+        Full type of a declarator: the base type and what the declarator adds.
+
+        ``char *name`` -> ``char *``, ``int buf[16]`` -> ``int [16]``.
+        """
+        rest = " ".join(declarator.text().replace(name, "", 1).split())
+        return f"{base_type} {rest}".strip()
+
+    @staticmethod
+    def _visibility(item: SgNode) -> str:
+        """``static`` gives internal linkage, Private; anything else links from other files, Public."""
+        for child in item.children():
+            if child.kind() == "storage_class_specifier" and child.text() == "static":
+                return "Private"
+        return "Public"
@@ -1,15 +1,15 @@
 """C++ language adapter using ast-grep for AST parsing."""
 
-import os
-from typing import Dict, List, Tuple, Optional
+from typing import Optional
 
-from ast_grep_py import SgRoot, SgNode
+from ast_grep_py import SgNode
 
-from .base_adapter import LanguageAdapter
+from .c_adapter import CAdapter, CONTAINER_KINDS
+from ast_parser.complexity import sg_complexity
 from ast_parser.parser import CodeNode, CodeRelation
 
 
-class CppAdapter(LanguageAdapter):
+class CppAdapter(CAdapter):
     """
     C++ adapter using ast-grep library.
     
@@ -19,74 +19,33 @@ class CppAdapter(LanguageAdapter):
     - CONTAINS, DEFINES relations
     - Include tracking (#include directives)
     
+    Everything CAdapter extracts from C is extracted from C++ too: structs,
+    unions, enums, typedefs, macros and function prototypes, also inside
+    namespaces and templates, calls and includes. Methods defined or
+    declared in a class or struct body are Method nodes with ``method_of``
+    and a ``signature``; a definition outside the body (``Circle::area``)
+    is defined by its class and linked to the prototype in the body.
+    
     Supports C++ source files (.cpp, .cc, .cxx, .hpp, .h).
     """
     
+    LANGUAGE_NAME = "C++"
+    
+    CONTAINER_KINDS = CONTAINER_KINDS + ("namespace_definition", "template_declaration")
+    
     def __init__(self):
         super().__init__("cpp")
-        self.current_file: str = ""
         self.current_class: Optional[str] = None
     
-    def parse_file(self, file_path: str, build_index: bool = False) -> Tuple[Dict[str, CodeNode], List[CodeRelation]]:
-        """
-        Parse a C++ file using ast-grep.
-        
-        Extracts classes, functions, fields, and includes.
-        """
-        self.current_file = file_path
-        self.current_class = None
-        
-        try:
-            # Read source code
-            with open(file_path, "r", encoding="utf-8") as f:
-                source = f.read()
-            
-            # Parse with ast-grep (C++ language)
-            root = SgRoot(source, "cpp").root()
-            
-            # Create file node
-            file_node_id = self._create_file_node(file_path)
-            
-            # Generate module name for indexing
-            module_name = os.path.splitext(os.path.basename(file_path))[0]
-            if build_index:
-                if module_name not in self.module_definitions:
-                    self.module_definitions[module_name] = {}
-                self.module_to_file[module_name] = file_node_id
-            
-            # Extract C++ structures
-            self._parse_includes(root, file_node_id)
-            self._parse_classes(root, file_node_id, build_index, module_name)
-            self._parse_functions(root, file_node_id)
-            
-            return self.nodes, self.relations
-            
-        except Exception as e:
-            print(f"Error parsing C++ file {file_path}: {e}")
-            return {}, []
+    def _parse_declarations(self, root: SgNode, file_node_id: str, build_index: bool, module_name: str) -> None:
+        """Extract the C declarations, then classes and their methods."""
+        super()._parse_declarations(root, file_node_id, build_index, module_name)
+        self._parse_classes(root, file_node_id, build_index, module_name)
     
-    def _parse_includes(self, root: SgNode, file_node_id: str) -> None:
-        """Extract #include directives."""
-        # Find all preproc_include nodes
-        for include_node in root.find_all(kind="preproc_include"):
-            # Get the included file path
-            include_text = include_node.text().strip()
-            if include_text.startswith("#include"):
-                include_text = include_text[8:].strip()
-                # Remove quotes or angle brackets
-                include_text = include_text.strip('<>"')
-                
-                # Extract module name (filename without extension)
-                if "/" in include_text:
-                    include_text = include_text.split("/")[-1]
-                module_name = os.path.splitext(include_text)[0]
-                
-                self.pending_imports.append({
-                    "type": "IMPORTS_MODULE",
-                    "source_id": file_node_id,
-                    "imported_module": module_name,
-                    "full_module_path": include_text,
-                })
+    def _parse_record_members(self, body: SgNode, record_name: str, record_id: str) -> None:
+        """A C++ struct has methods as well as fields."""
+        super()._parse_record_members(body, record_name, record_id)
+        self._parse_class_methods(body, record_id)
     
     def _parse_classes(self, root: SgNode, file_node_id: str, build_index: bool, module_name: str) -> None:
         """Extract class declarations and their methods/fields."""
@@ -129,7 +88,8 @@ class CppAdapter(LanguageAdapter):
             self.current_class = None
     
     def _parse_class_methods(self, class_body: SgNode, class_node_id: str) -> None:
-        """Extract method definitions from class body."""
+        """Extract the methods of a class body: definitions, and prototypes of methods defined elsewhere."""
+        class_name = self.nodes[class_node_id].name
         # Find all function_definition nodes within class body
         for method_node in class_body.find_all(kind="function_definition"):
             # Extract function name from declarator
@@ -142,49 +102,40 @@ class CppAdapter(LanguageAdapter):
             if not method_name:
                 continue
             
-            line_no = method_node.range().start.line + 1
-            
-            # Create method node
-            method_node_id = self._get_node_id("Method", method_name, self.current_file, line_no)
-            self.nodes[method_node_id] = CodeNode(
-                node_id=method_node_id,
-                node_type="Method",
-                name=method_name,
-                file_path=self.current_file,
-                line_no=line_no,
-            )
-            
-            # Add DEFINES relation from class to method
-            self._add_relation(CodeRelation(class_node_id, method_node_id, "DEFINES"))
-    
-    def _parse_functions(self, root: SgNode, file_node_id: str) -> None:
-        """Extract top-level function definitions."""
-        # Find all function_definition nodes at top level
-        for func_node in root.children():
-            if func_node.kind() == "function_definition":
-                # Extract function name from declarator
-                declarator = func_node.field("declarator")
-                if not declarator:
-                    continue
-                
-                func_name = self._extract_function_name(declarator)
-                if not func_name:
+            function = self._function_declarator(declarator)
+            properties = {"method_of": class_name, **(self._signature(function) if function else {}),
+                          "complexity": sg_complexity(method_node, "cpp")}
+            method_node_id = self._create_member(method_node, method_name, class_node_id, properties)
+            self._definitions.append((method_node, method_node_id, class_name))
+        
+        # Prototypes, `double area() const;`, are linked to their out-of-line definitions
+        for member in class_body.children():
+            if member.kind() not in ("field_declaration", "declaration"):
+                continue
+            for declarator in self._declarators(member):
+                function = self._function_declarator(declarator)
+                if function is None:
                     continue
-                
-                line_no = func_node.range().start.line + 1
-                
-                # Create function node
-                func_node_id = self._get_node_id("Function", func_name, self.current_file, line_no)
-                self.nodes[func_node_id] = CodeNode(
-                    node_id=func_node_id,
-                    node_type="Function",
-                    name=func_name,
-                    file_path=self.current_file,
-                    line_no=line_no,
-                )
-                
-                # Add CONTAINS relation from file to function
-                self._add_relation(CodeRelation(file_node_id, func_node_id, "CONTAINS"))
+                self._create_member(member, function.field("declarator").text(), class_node_id,
+                                    {"method_of": class_name, "prototype": True, **self._signature(function)})
+    
+    def _create_member(self, item: SgNode, method_name: str, class_node_id: str, properties: dict) -> str:
+        """Create a Method node defined by its class."""
+        line_no = item.range().start.line + 1
+        method_node_id = self._get_node_id("Method", method_name, self.current_file, line_no)
+        self.nodes[method_node_id] = CodeNode(
+            node_id=method_node_id,
+            node_type="Method",
+            name=method_name,
+            file_path=self.current_file,
+            line_no=line_no,
+            end_line_no=item.range().end.line + 1,
+            properties=properties,
+        )
+        
+        # Add DEFINES relation from class to method
+        self._add_relation(CodeRelation(class_node_id, method_node_id, "DEFINES"))
+        return method_node_id
     
     def _extract_function_name(self, declarator: SgNode) -> Optional[str]:
         """
@@ -1,23 +1,49 @@
 """Go language adapter using ast-grep for AST parsing."""
 
 import os
+import json
 from typing import Dict, List, Tuple, Optional
 
 from ast_grep_py import SgRoot, SgNode
 
 from .base_adapter import LanguageAdapter
+from ast_parser.complexity import sg_complexity
 from ast_parser.parser import CodeNode, CodeRelation
 
 
+# Predeclared functions and conversion types; calls to them are not graph edges
+GO_PREDECLARED = frozenset({
+    "append", "cap", "clear", "close", "complex", "copy", "delete", "imag", "len", "make",
+    "max", "min", "new", "panic", "print", "println", "real", "recover",
+    "any", "bool", "byte", "complex64", "complex128", "error", "float32", "float64",
+    "int", "int8", "int16", "int32", "int64", "rune", "string",
+    "uint", "uint8", "uint16", "uint32", "uint64", "uintptr",
+})
+
+
 class GoAdapter(LanguageAdapter):
     """
     Go adapter using ast-grep library.
     
     Extracts minimal Go structures for proof of concept:
-    - File, Struct, Function, Method nodes
-    - CONTAINS, DEFINES relations
+    - File, Struct, Interface, Function, Method, GlobalVariable nodes
+    - CONTAINS, DEFINES, CALLS, SATISFIES relations
     - Import tracking (import declarations)
     
+    Methods carry a ``method_of`` property naming their receiver type and a
+    ``receiver`` of ``pointer`` or ``value``, and are attached to the type
+    with DEFINES. Receivers declared in another file of the
+    package are linked in the second pass. Package-level ``var`` and
+    ``const`` declarations become GlobalVariable nodes.
+    
+    Functions and methods record their parameters in ``args`` and their
+    result types in ``returns``, one entry per returned value. Go interfaces
+    are satisfied implicitly, so every interface queues a SATISFIES entry
+    that the second pass matches against the method sets of the package's
+    structs. Interfaces also store their ``method_set`` (one normalized
+    signature per method, sorted) and the types they ``embeds``, so method
+    sets can be compared without re-parsing.
+    
     Supports Go source files (.go).
     """
     
@@ -25,24 +51,21 @@ class GoAdapter(LanguageAdapter):
         super().__init__("go")
         self.current_file: str = ""
     
-    def parse_file(self, file_path: str, build_index: bool = False) -> Tuple[Dict[str, CodeNode], List[CodeRelation]]:
+    def parse_source(self, source: str, file_path: str, build_index: bool = False) -> Tuple[Dict[str, CodeNode], List[CodeRelation]]:
         """
-        Parse a Go file using ast-grep.
-        
+        Parse Go source with ast-grep.
+
         Extracts type declarations (structs), functions, methods, and imports.
         """
         self.current_file = file_path
         
         try:
-            # Read source code
-            with open(file_path, "r", encoding="utf-8") as f:
-                source = f.read()
-            
             # Parse with ast-grep (Go language)
             root = SgRoot(source, "go").root()
+            self._record_syntax_errors(root)
             
             # Create file node
-            file_node_id = self._create_file_node(file_path)
+            file_node_id = self._create_file_node(file_path, source)
             
             # Generate module name for indexing
             module_name = os.path.splitext(os.path.basename(file_path))[0]
@@ -51,16 +74,26 @@ class GoAdapter(LanguageAdapter):
                     self.module_definitions[module_name] = {}
                 self.module_to_file[module_name] = file_node_id
             
+            # Record the package name on the file node
+            for child in root.children():
+                if child.kind() == "package_clause":
+                    for name_node in child.children():
+                        if name_node.kind() == "package_identifier":
+                            self.nodes[file_node_id].properties["package"] = name_node.text()
+            
             # Extract Go structures
             self._parse_imports(root, file_node_id)
             self._parse_type_declarations(root, file_node_id, build_index, module_name)
-            self._parse_functions(root, file_node_id)
-            self._parse_methods(root)
+            self._parse_package_variables(root, file_node_id, build_index, module_name)
+            self._parse_functions(root, file_node_id, build_index, module_name)
+            self._parse_methods(root, file_node_id)
+            self._parse_calls(root)
             
             return self.nodes, self.relations
             
         except Exception as e:
             print(f"Error parsing Go file {file_path}: {e}")
+            self._record_failure(f"Parser failed: {e}")
             return {}, []
     
     def _parse_imports(self, root: SgNode, file_node_id: str) -> None:
@@ -103,52 +136,164 @@ class GoAdapter(LanguageAdapter):
                                 })
     
     def _parse_type_declarations(self, root: SgNode, file_node_id: str, build_index: bool, module_name: str) -> None:
-        """Extract type declarations (primarily structs)."""
-        # Find all type_declaration nodes
+        """Extract struct and interface type declarations."""
+        # Find all type_declaration nodes; a grouped ``type ( ... )`` holds several specs
         for type_node in root.find_all(kind="type_declaration"):
-            # Type declaration has a type_spec
-            type_spec = type_node.field("type_spec")
-            if not type_spec:
-                # Try to find type_spec in children
-                for child in type_node.children():
-                    if child.kind() == "type_spec":
-                        type_spec = child
-                        break
-            
-            if not type_spec:
-                continue
-            
-            # Get type name
-            name_field = type_spec.field("name")
-            if not name_field:
-                continue
-            
-            type_name = name_field.text()
-            
-            # Get the type definition
-            type_def = type_spec.field("type")
-            if type_def and type_def.kind() == "struct_type":
-                # This is a struct declaration
-                line_no = type_node.range().start.line + 1
+            for type_spec in type_node.children():
+                if type_spec.kind() != "type_spec":
+                    continue
                 
-                # Create struct node (using "Class" type for consistency)
-                struct_node_id = self._get_node_id("Class", type_name, self.current_file, line_no)
-                self.nodes[struct_node_id] = CodeNode(
-                    node_id=struct_node_id,
-                    node_type="Class",
+                # Get type name
+                name_field = type_spec.field("name")
+                type_def = type_spec.field("type")
+                if not name_field or not type_def:
+                    continue
+                
+                type_name = name_field.text()
+                line_no = type_spec.range().start.line + 1
+                
+                if type_def.kind() == "struct_type":
+                    # Structs use the "Class" type for consistency with other languages
+                    node_type = "Class"
+                elif type_def.kind() == "interface_type":
+                    node_type = "Interface"
+                else:
+                    continue
+                
+                type_node_id = self._get_node_id(node_type, type_name, self.current_file, line_no)
+                self.nodes[type_node_id] = CodeNode(
+                    node_id=type_node_id,
+                    node_type=node_type,
                     name=type_name,
                     file_path=self.current_file,
                     line_no=line_no,
+                    properties={"visibility": self._visibility(type_name)},
                 )
                 
-                # Add CONTAINS relation from file to struct
-                self._add_relation(CodeRelation(file_node_id, struct_node_id, "CONTAINS"))
+                # Add CONTAINS relation from file to type
+                self._add_relation(CodeRelation(file_node_id, type_node_id, "CONTAINS"))
                 
-                # Index the struct for cross-file resolution
+                # Index the type for cross-file resolution
                 if build_index:
-                    self.module_definitions[module_name][type_name] = struct_node_id
+                    self.module_definitions[module_name][type_name] = type_node_id
+                
+                if node_type == "Interface":
+                    methods = self._parse_interface_methods(type_def, type_name, type_node_id)
+                    self.nodes[type_node_id].properties.update(self._method_set(type_def))
+                    self._queue_satisfies(type_def, type_node_id, file_node_id, methods)
+    
+    def _parse_interface_methods(self, interface: SgNode, interface_name: str,
+                                 interface_node_id: str) -> List[str]:
+        """Extract the methods declared by an interface type; returns their names."""
+        names = []
+        for child in interface.children():
+            # method_elem in current grammars, method_spec in older ones
+            if child.kind() not in ("method_elem", "method_spec"):
+                continue
+            name_field = child.field("name")
+            if not name_field:
+                continue
+            
+            method_name = name_field.text()
+            line_no = child.range().start.line + 1
+            method_node_id = self._get_node_id("Method", method_name, self.current_file, line_no)
+            self.nodes[method_node_id] = CodeNode(
+                node_id=method_node_id,
+                node_type="Method",
+                name=method_name,
+                file_path=self.current_file,
+                line_no=line_no,
+                properties={"method_of": interface_name, "visibility": self._visibility(method_name)},
+            )
+            self._add_relation(CodeRelation(interface_node_id, method_node_id, "DEFINES"))
+            names.append(method_name)
+        return names
+    
+    @staticmethod
+    def _method_set(interface: SgNode) -> Dict[str, List[str]]:
+        """
+        The declared method signatures and embedded types of an interface.
+        
+        Signatures keep the source text with whitespace collapsed, e.g.
+        ``Read(p []byte) (n int, err error)``. Embedded interfaces and type
+        constraints are listed as written, not expanded.
+        """
+        signatures, embeds = [], []
+        for child in interface.children():
+            if not child.is_named() or child.kind() == "comment":
+                continue
+            text = " ".join(child.text().split())
+            if child.kind() in ("method_elem", "method_spec"):
+                signatures.append(text)
+            else:
+                embeds.append(text)
+        return {"method_set": sorted(signatures), "embeds": embeds}
+    
+    def _queue_satisfies(self, interface: SgNode, interface_node_id: str, file_node_id: str,
+                         methods: List[str]) -> None:
+        """
+        Queue the implicit-satisfaction check for an interface.
+        
+        The structs of the package may have their methods in any of its
+        files, so the match is left to the second pass. Interfaces that embed
+        other interfaces or type constraints are skipped, since their full
+        method set is not known here, and so is the empty interface, which
+        every type satisfies.
+        """
+        embeds = any(
+            child.is_named() and child.kind() not in ("method_elem", "method_spec", "comment")
+            for child in interface.children()
+        )
+        if embeds or not methods:
+            return
+        self.pending_imports.append({
+            "type": "SATISFIES",
+            "source_id": file_node_id,
+            "interface_id": interface_node_id,
+            "methods": sorted(set(methods)),
+            "package": self.nodes[file_node_id].properties.get("package"),
+            "package_dir": os.path.dirname(self.current_file),
+        })
     
-    def _parse_functions(self, root: SgNode, file_node_id: str) -> None:
+    def _parse_package_variables(self, root: SgNode, file_node_id: str, build_index: bool, module_name: str) -> None:
+        """Extract package-level var and const declarations."""
+        for decl in root.children():
+            if decl.kind() == "var_declaration":
+                kind, spec_kind = "var", "var_spec"
+            elif decl.kind() == "const_declaration":
+                kind, spec_kind = "const", "const_spec"
+            else:
+                continue
+            
+            for spec in decl.find_all(kind=spec_kind):
+                type_field = spec.field("type")
+                line_no = spec.range().start.line + 1
+                # ``var a, b int`` declares several names in one spec
+                for name_node in spec.children():
+                    if name_node.kind() != "identifier":
+                        continue
+                    
+                    var_name = name_node.text()
+                    # Use "Variable" prefix for ID, as the Python parsers do for globals
+                    var_node_id = self._get_node_id("Variable", var_name, self.current_file, line_no)
+                    self.nodes[var_node_id] = CodeNode(
+                        node_id=var_node_id,
+                        node_type="GlobalVariable",
+                        name=var_name,
+                        file_path=self.current_file,
+                        line_no=line_no,
+                        properties={
+                            "kind": kind,
+                            "type": type_field.text() if type_field else None,
+                            "visibility": self._visibility(var_name),
+                        },
+                    )
+                    self._add_relation(CodeRelation(file_node_id, var_node_id, "DEFINES"))
+                    
+                    if build_index:
+                        self.module_definitions[module_name][var_name] = var_node_id
+    
+    def _parse_functions(self, root: SgNode, file_node_id: str, build_index: bool, module_name: str) -> None:
         """Extract top-level function declarations."""
         # Find all function_declaration nodes
         for func_node in root.find_all(kind="function_declaration"):
@@ -168,13 +313,24 @@ class GoAdapter(LanguageAdapter):
                 name=func_name,
                 file_path=self.current_file,
                 line_no=line_no,
+                properties={"visibility": self._visibility(func_name), **self._signature(func_node),
+                            "complexity": sg_complexity(func_node, "go")},
             )
             
             # Add CONTAINS relation from file to function
             self._add_relation(CodeRelation(file_node_id, func_node_id, "CONTAINS"))
+            
+            if build_index:
+                self.module_definitions[module_name][func_name] = func_node_id
     
-    def _parse_methods(self, root: SgNode) -> None:
-        """Extract method declarations (functions with receivers)."""
+    def _parse_methods(self, root: SgNode, file_node_id: str) -> None:
+        """
+        Extract method declarations (functions with receivers).
+        
+        Each method is attached to its receiver type with DEFINES. When the
+        type is declared in another file of the package the link is deferred
+        to the second pass.
+        """
         # Find all method_declaration nodes
         for method_node in root.find_all(kind="method_declaration"):
             # Get method name
@@ -195,17 +351,6 @@ class GoAdapter(LanguageAdapter):
             if not receiver_type:
                 continue
             
-            # Find the corresponding struct node
-            struct_node_id = None
-            for node_id, node in self.nodes.items():
-                if node.node_type == "Class" and node.name == receiver_type:
-                    struct_node_id = node_id
-                    break
-            
-            # If struct not found, skip (might be external type or pointer receiver)
-            if not struct_node_id:
-                continue
-            
             # Create method node
             method_node_id = self._get_node_id("Method", method_name, self.current_file, line_no)
             self.nodes[method_node_id] = CodeNode(
@@ -214,30 +359,260 @@ class GoAdapter(LanguageAdapter):
                 name=method_name,
                 file_path=self.current_file,
                 line_no=line_no,
+                properties={
+                    "method_of": receiver_type,
+                    "receiver": self._receiver_kind(receiver),
+                    "visibility": self._visibility(method_name),
+                    **self._signature(method_node),
+                    "complexity": sg_complexity(method_node, "go"),
+                },
             )
             
-            # Add DEFINES relation from struct to method
-            self._add_relation(CodeRelation(struct_node_id, method_node_id, "DEFINES"))
+            # Find the corresponding struct node
+            struct_node_id = None
+            for node_id, node in self.nodes.items():
+                if node.node_type == "Class" and node.name == receiver_type:
+                    struct_node_id = node_id
+                    break
+            
+            if struct_node_id:
+                # Add DEFINES relation from struct to method
+                self._add_relation(CodeRelation(struct_node_id, method_node_id, "DEFINES"))
+            else:
+                # Receiver declared in another file of the package
+                self.pending_imports.append({
+                    "type": "IMPL_METHOD",
+                    "source_id": file_node_id,
+                    "type_name": receiver_type,
+                    "method_id": method_node_id,
+                    "module_hints": [],
+                })
     
     def _extract_receiver_type(self, receiver: SgNode) -> Optional[str]:
         """
         Extract the receiver type name from a method receiver.
         
-        Receivers can be (Type) or (*Type).
+        Receivers can be (Type), (*Type) or generic (*Type[T]).
         """
         # Look for parameter_declaration in receiver
         for child in receiver.children():
             if child.kind() == "parameter_declaration":
                 # Get the type
                 type_node = child.field("type")
-                if type_node:
-                    # Handle pointer types
-                    if type_node.kind() == "pointer_type":
-                        # Get the pointee type
-                        for type_child in type_node.children():
-                            if type_child.kind() == "type_identifier":
-                                return type_child.text()
-                    elif type_node.kind() == "type_identifier":
-                        return type_node.text()
+                # Handle pointer types by unwrapping to the pointee type
+                while type_node and type_node.kind() == "pointer_type":
+                    pointee = [c for c in type_node.children() if c.is_named()]
+                    type_node = pointee[0] if pointee else None
+                if type_node and type_node.kind() == "generic_type":
+                    type_node = type_node.field("type")
+                if type_node and type_node.kind() == "type_identifier":
+                    return type_node.text()
         
         return None
+    
+    @staticmethod
+    def _receiver_kind(receiver: SgNode) -> str:
+        """"pointer" for a ``(p *T)`` receiver, "value" for ``(t T)``."""
+        for child in receiver.children():
+            if child.kind() == "parameter_declaration":
+                type_node = child.field("type")
+                if type_node and type_node.kind() == "pointer_type":
+                    return "pointer"
+        return "value"
+    
+    def _signature(self, func_node: SgNode) -> Dict[str, object]:
+        """
+        Parameter and result properties of a function or method declaration.
+        
+        ``args`` is a JSON list of ``{"name", "type"}`` entries, as in the
+        Python parser. ``returns`` has one type per returned value, so both
+        ``(int, error)`` and the named ``(x, y int)`` give two entries; named
+        results are also listed in ``named_results``.
+        """
+        args = [{"name": name, "type": type_text}
+                for name, type_text in self._parameters(func_node.field("parameters"))]
+        properties: Dict[str, object] = {"args": json.dumps(args)}
+        
+        result = func_node.field("result")
+        if result is None:
+            properties["returns"] = []
+        elif result.kind() == "parameter_list":
+            results = self._parameters(result)
+            properties["returns"] = [type_text for _, type_text in results]
+            named = [name for name, _ in results if name]
+            if named:
+                properties["named_results"] = named
+        else:
+            properties["returns"] = [result.text()]
+        return properties
+    
+    @staticmethod
+    def _parameters(parameter_list: Optional[SgNode]) -> List[Tuple[Optional[str], str]]:
+        """
+        (name, type) for every value of a parameter or result list.
+        
+        ``a, b int`` declares two values of one type; unnamed entries such as
+        the results in ``(int, error)`` have no name.
+        """
+        entries: List[Tuple[Optional[str], str]] = []
+        if parameter_list is None:
+            return entries
+        for decl in parameter_list.children():
+            if decl.kind() not in ("parameter_declaration", "variadic_parameter_declaration"):
+                continue
+            type_node = decl.field("type")
+            type_text = type_node.text() if type_node else ""
+            if decl.kind() == "variadic_parameter_declaration":
+                type_text = "..." + type_text
+            names = [child.text() for child in decl.children() if child.kind() == "identifier"]
+            if names:
+                entries.extend((name, type_text) for name in names)
+            else:
+                entries.append((None, type_text))
+        return entries
+    
+    def _parse_calls(self, root: SgNode) -> None:
+        """
+        Emit CALLS edges from every function and method to what it calls.
+        
+        ``f()`` links to a function of this file, or of another file of the
+        package in the second pass. ``x.M()`` links to a method when ``x`` is
+        the receiver, a parameter or a composite literal of known type, and
+        ``pkg.F()`` is resolved against the imported package. Calls to
+        predeclared functions and conversions are ignored.
+        """
+        packages = self._imported_packages(root)
+        package_dir = os.path.dirname(self.current_file)
+        source_lines = root.text().splitlines()
+        
+        declarations = list(root.find_all(kind="function_declaration")) \
+            + list(root.find_all(kind="method_declaration"))
+        for func in declarations:
+            caller_id = self._declaration_node_id(func)
+            body = func.field("body")
+            if not caller_id or not body:
+                continue
+            local_types = self._local_types(func, body)
+            
+            for call in body.find_all(kind="call_expression"):
+                function = call.field("function")
+                if not function:
+                    continue
+                
+                line_no = call.range().start.line + 1
+                site = {
+                    "line_no": line_no,
+                    "call_site": source_lines[line_no - 1].strip() if line_no <= len(source_lines) else "",
+                }
+                kind = function.kind()
+                
+                if kind == "identifier":
+                    name = function.text()
+                    if name in GO_PREDECLARED or name in local_types \
+                            or self._find_local_node("Class", name) or self._find_local_node("Interface", name):
+                        continue
+                    target_id = self._find_local_node("Function", name)
+                    if target_id:
+                        self._add_relation(CodeRelation(caller_id, target_id, "CALLS", properties=site))
+                    else:
+                        self.pending_imports.append({
+                            "type": "CALLS_PACKAGE",
+                            "source_id": caller_id,
+                            "name": name,
+                            "package_dir": package_dir,
+                            "raw_name": name,
+                            **site,
+                        })
+                
+                elif kind == "selector_expression":
+                    operand = function.field("operand")
+                    field = function.field("field")
+                    if not operand or not field:
+                        continue
+                    if operand.kind() == "identifier" and operand.text() in local_types:
+                        self._add_member_call(caller_id, local_types[operand.text()], field.text(),
+                                              site, [], function.text())
+                    elif operand.kind() == "identifier" and operand.text() in packages:
+                        import_path = packages[operand.text()]
+                        self.pending_imports.append({
+                            "type": "CALLS",
+                            "source_id": caller_id,
+                            "imported_module": import_path.split("/")[-1],
+                            "imported_name": field.text(),
+                            "original_name": function.text(),
+                            "raw_name": f"{import_path}.{field.text()}",
+                            **site,
+                        })
+                    else:
+                        self.pending_imports.append({
+                            "type": "CALLS_UNRESOLVED",
+                            "source_id": caller_id,
+                            "raw_name": function.text(),
+                            **site,
+                        })
+    
+    @staticmethod
+    def _imported_packages(root: SgNode) -> Dict[str, str]:
+        """Map the name each import is referred to by (its alias or last path segment) to its path."""
+        packages: Dict[str, str] = {}
+        for spec in root.find_all(kind="import_spec"):
+            path_node = spec.field("path")
+            if not path_node:
+                continue
+            import_path = path_node.text().strip('"')
+            alias = spec.field("name")
+            if alias and alias.kind() != "package_identifier":
+                # Dot and blank imports bind no name
+                continue
+            packages[alias.text() if alias else import_path.split("/")[-1]] = import_path
+        return packages
+    
+    def _local_types(self, func: SgNode, body: SgNode) -> Dict[str, str]:
+        """
+        Map the receiver, parameters and ``:=`` bindings of a function to type names.
+        
+        Bindings only get a type when it can be read off a composite literal,
+        ``T{..}`` or ``&T{..}``. Types from other packages are left out.
+        """
+        types: Dict[str, str] = {}
+        for field_name in ("receiver", "parameters"):
+            for name, type_text in self._parameters(func.field(field_name)):
+                type_name = self._base_type_name(type_text)
+                if name and type_name:
+                    types[name] = type_name
+        
+        for decl in body.find_all(kind="short_var_declaration"):
+            left, right = decl.field("left"), decl.field("right")
+            if not left or not right:
+                continue
+            names = [c for c in left.children() if c.is_named()]
+            values = [c for c in right.children() if c.is_named()]
+            for name_node, value in zip(names, values):
+                if value.kind() == "unary_expression" and value.field("operand"):
+                    value = value.field("operand")
+                type_node = value.field("type") if value.kind() == "composite_literal" else None
+                type_name = self._base_type_name(type_node.text()) if type_node else None
+                if name_node.kind() == "identifier" and type_name:
+                    types[name_node.text()] = type_name
+        return types
+    
+    @staticmethod
+    def _base_type_name(type_text: str) -> Optional[str]:
+        """``*Stack[T]`` -> ``Stack``; None for qualified, slice, map and func types."""
+        text = type_text.lstrip("*").split("[", 1)[0].strip()
+        return text if text.isidentifier() else None
+    
+    def _declaration_node_id(self, func: SgNode) -> Optional[str]:
+        """Node ID of the Function or Method created for a declaration."""
+        name_field = func.field("name")
+        if not name_field:
+            return None
+        node_type = "Method" if func.kind() == "method_declaration" else "Function"
+        node_id = self._get_node_id(node_type, name_field.text(), self.current_file,
+                                    func.range().start.line + 1)
+        return node_id if node_id in self.nodes else None
+    
+    @staticmethod
+    def _visibility(name: str) -> str:
+        """Exported (capitalized) names are Public, the rest are package-private."""
+        return "Public" if name[:1].isupper() else "Private"
@@ -1,181 +1,817 @@
 """Java language adapter using ast-grep for AST parsing."""
 
 import os
-from typing import Dict, List, Tuple, Optional
+from typing import Any, Dict, Iterator, List, Optional, Tuple
 
 from ast_grep_py import SgRoot, SgNode
 
 from .base_adapter import LanguageAdapter
-from ast_parser.parser import CodeNode, CodeRelation
+from ast_parser.complexity import sg_complexity
+from ast_parser.parser import CodeNode, CodeRelation, clean_doc_comment, set_doc
+
+
+# Type declarations and the node type and type_kind they become
+TYPE_KINDS = {
+    "class_declaration": ("Class", "class"),
+    "record_declaration": ("Class", "record"),
+    "interface_declaration": ("Interface", "interface"),
+    "annotation_type_declaration": ("Interface", "annotation"),
+    "enum_declaration": ("Enum", "enum"),
+}
+
+# Children of a type declaration listing its supertypes
+SUPERTYPE_KINDS = ("superclass", "super_interfaces", "extends_interfaces")
+
+ANNOTATION_KINDS = ("marker_annotation", "annotation")
+
+# Annotation names that mark a declaration deprecated
+DEPRECATED_ANNOTATIONS = ("Deprecated", "java.lang.Deprecated")
+
+# Types whose values have no methods to call; var takes the type of its initializer
+PRIMITIVE_TYPES = ("boolean", "byte", "char", "short", "int", "long", "float", "double", "void", "var")
 
 
 class JavaAdapter(LanguageAdapter):
     """
     Java adapter using ast-grep library.
-    
-    Extracts minimal Java structures for proof of concept:
-    - File, Class, Method nodes
-    - Field (Variable) nodes
-    - CONTAINS, DEFINES relations
-    - Import tracking for cross-file dependency resolution
-    
+
+    Extracts:
+    - File, Package, Class (classes and records, with a ``type_kind``),
+      Interface (interfaces and ``@interface`` annotation types), Enum,
+      Variant (enum constants), Method and Field nodes
+    - CONTAINS, DEFINES, EXTENDS, IMPLEMENTS, CALLS, REFERENCES relations
+    - Import tracking (import declarations), resolved to the imported type,
+      or for ``import pkg.*`` to every file of the package
+
+    The package declaration becomes a Package node contained by the file and
+    a ``package`` property on the file node. Nested types are defined by
+    their enclosing type; anonymous classes (``new Runnable() { .. }``) and
+    classes declared inside a method are defined by the enclosing class too,
+    anonymous ones named as javac does (``Outer$1``, ``Outer$Inner$1``) and
+    keeping the type they instantiate in ``base``.
+
+    Annotations on a type, method, constructor or field are stored by name
+    in its ``annotations`` property (``@RestController`` -> ``RestController``),
+    and ``@Deprecated`` ones are flagged ``deprecated`` as well.
+    Methods carry ``method_of`` and a ``method_kind`` of ``constructor``,
+    ``static`` or ``instance``; fields carry ``field_of`` and their
+    declared ``type``. Members without an access modifier are package-private
+    (Crate), except in interfaces, where they are Public.
+
+    ``extends`` and ``implements`` clauses are resolved within the file
+    first; other supertypes are looked up in the second pass through the
+    file's imports and package. A method implementing a method of the
+    interface also gets an IMPLEMENTS edge to it.
+
+    ``this.field`` accesses in a method, constructor or initializer become
+    REFERENCES edges to the field of the enclosing type, with an ``access``
+    of Read, Write or ReadWrite as for Rust. Accesses on any other receiver,
+    and to fields the type does not declare itself, stay unresolved.
+
+    Method calls become CALLS edges from the calling method (or, in a field
+    initializer, the type). The receiver's type is read off a parameter,
+    local variable or field declaration, or the receiver names a type for
+    a static call; ``foo()`` and ``this.foo()`` call a method of the
+    enclosing types, innermost first, or one brought in by
+    ``import static``. Types of the file are linked directly; the others
+    are looked up in the second pass through the file's imports and
+    package, and calls that cannot be, such as ``a.b().c()``, point at an
+    Unresolved placeholder.
+
     Supports Java source files (.java).
     """
-    
+
     def __init__(self):
         super().__init__("java")
         self.current_file: str = ""
-        self.current_class: Optional[str] = None
-    
-    def parse_file(self, file_path: str, build_index: bool = False) -> Tuple[Dict[str, CodeNode], List[CodeRelation]]:
+        self.current_package: str = ""
+        # Types declared in the current file by simple name: (node ID, node type)
+        self._local_types: Dict[str, List[Tuple[str, str]]] = {}
+        # Supertype clauses to link once every type of the file exists
+        self._supertypes: List[Dict[str, Any]] = []
+        # Source lines of the current file, for the call_site of field accesses
+        self._source_lines: List[str] = []
+        # Method calls to link once every type of the file exists
+        self._calls: List[Dict[str, Any]] = []
+
+    def parse_source(self, source: str, file_path: str, build_index: bool = False) -> Tuple[Dict[str, CodeNode], List[CodeRelation]]:
         """
-        Parse a Java file using ast-grep.
-        
-        Extracts classes, methods, fields, and imports.
+        Parse Java source with ast-grep.
+
+        Extracts the package, types with their members, and imports.
         """
         self.current_file = file_path
-        self.current_class = None
-        
+        self.current_package = ""
+        self._local_types = {}
+        self._supertypes = []
+        self._source_lines = source.splitlines()
+        self._calls = []
+
         try:
-            # Read source code
-            with open(file_path, "r", encoding="utf-8") as f:
-                source = f.read()
-            
             # Parse with ast-grep (Java language)
             root = SgRoot(source, "java").root()
-            
+            self._record_syntax_errors(root)
+
             # Create file node
-            file_node_id = self._create_file_node(file_path)
-            
-            # Generate module name for indexing
-            module_name = os.path.splitext(os.path.basename(file_path))[0]
+            file_node_id = self._create_file_node(file_path, source)
+            self._parse_package(root, file_node_id)
+
+            # One module per file, qualified by the package: com.example.Dog
+            base_name = os.path.splitext(os.path.basename(file_path))[0]
+            module_name = f"{self.current_package}.{base_name}" if self.current_package else base_name
             if build_index:
                 if module_name not in self.module_definitions:
                     self.module_definitions[module_name] = {}
                 self.module_to_file[module_name] = file_node_id
-            
+
             # Extract Java structures
-            self._parse_imports(root, file_node_id)
-            self._parse_classes(root, file_node_id, build_index, module_name)
-            
+            imports = self._parse_imports(root, file_node_id)
+            for child in root.children():
+                if child.kind() in TYPE_KINDS:
+                    self._parse_type(child, file_node_id, None, build_index, module_name)
+            self._link_supertypes(file_node_id, imports)
+            self._link_calls(imports)
+
             return self.nodes, self.relations
-            
+
         except Exception as e:
             print(f"Error parsing Java file {file_path}: {e}")
+            self._record_failure(f"Parser failed: {e}")
             return {}, []
-    
-    def _parse_imports(self, root: SgNode, file_node_id: str) -> None:
-        """Extract import declarations."""
-        # Find all import_declaration nodes
+
+    def _parse_package(self, root: SgNode, file_node_id: str) -> None:
+        """Extract the package declaration as a Package node and a property of the file."""
+        for child in root.children():
+            if child.kind() != "package_declaration":
+                continue
+            name_node = next((c for c in child.children() if c.kind() in ("identifier", "scoped_identifier")), None)
+            if name_node is None:
+                continue
+            self.current_package = name_node.text()
+            self.nodes[file_node_id].properties["package"] = self.current_package
+            line_no = child.range().start.line + 1
+            package_node_id = self._get_node_id("Package", self.current_package, self.current_file, line_no)
+            self.nodes[package_node_id] = CodeNode(
+                node_id=package_node_id,
+                node_type="Package",
+                name=self.current_package,
+                file_path=self.current_file,
+                line_no=line_no,
+                end_line_no=child.range().end.line + 1,
+                properties={},
+            )
+            self._add_relation(CodeRelation(file_node_id, package_node_id, "CONTAINS"))
+            return
+
+    def _parse_imports(self, root: SgNode, file_node_id: str) -> List[Tuple[str, bool, bool]]:
+        """
+        Extract import declarations for the second pass.
+
+        Returns:
+            (path, static, wildcard) of every import, ``java.util.*`` as path ``java.util``
+        """
+        imports = []
         for import_node in root.find_all(kind="import_declaration"):
-            # Get the imported name (could be wildcard import or specific class)
-            # import_declaration contains either scoped_identifier or identifier
             import_text = import_node.text().strip()
-            if import_text.startswith("import "):
+            if not import_text.startswith("import "):
+                continue
+            import_text = import_text[7:].strip().rstrip(";").strip()
+            static = import_text.startswith("static ")
+            if static:
                 import_text = import_text[7:].strip()
-                if import_text.endswith(";"):
-                    import_text = import_text[:-1].strip()
-                
-                # Extract module for tracking
-                parts = import_text.split(".")
-                if len(parts) > 0:
-                    root_module = parts[0]
+            path = "".join(import_text.split())
+            wildcard = path.endswith(".*")
+            if wildcard:
+                path = path[:-2]
+            if not path:
+                continue
+
+            imports.append((path, static, wildcard))
+            self.pending_imports.append({
+                "type": "IMPORTS_JAVA",
+                "source_id": file_node_id,
+                "path": path,
+                "static": static,
+                "wildcard": wildcard,
+                "line_no": import_node.range().start.line + 1,
+            })
+        return imports
+
+    def _parse_type(self, declaration: SgNode, file_node_id: str, enclosing: Optional[Tuple[str, str, str]],
+                    build_index: bool, module_name: str, visibility: Optional[str] = None) -> Optional[str]:
+        """
+        Extract a class, record, interface, annotation type or enum, and its members.
+
+        Args:
+            declaration: The type declaration
+            file_node_id: File node, containing top-level types
+            enclosing: (node ID, name, node type) of the enclosing type, None at top level
+            build_index: Index the type for cross-file resolution
+            module_name: Module of the file
+            visibility: Visibility when the declaration has no access modifier;
+                package-private by default
+
+        Returns:
+            Node ID of the type, or None when it has no name
+        """
+        name_field = declaration.field("name")
+        if not name_field:
+            return None
+        node_type, type_kind = TYPE_KINDS[declaration.kind()]
+        name = name_field.text()
+
+        properties: Dict[str, Any] = {"visibility": self._visibility(declaration, visibility or "Crate")}
+        if node_type == "Class":
+            properties["type_kind"] = type_kind
+        elif node_type == "Interface" and type_kind == "annotation":
+            properties["type_kind"] = type_kind
+        type_node_id = self._create_type_node(declaration, node_type, name, properties)
+
+        if enclosing is None:
+            self._add_relation(CodeRelation(file_node_id, type_node_id, "CONTAINS"))
+            qualified = name
+        else:
+            self._add_relation(CodeRelation(enclosing[0], type_node_id, "DEFINES"))
+            qualified = f"{enclosing[1]}.{name}"
+        if build_index:
+            self.module_definitions[module_name][qualified] = type_node_id
+
+        supertypes = [(clause.kind(), written) for clause in declaration.children()
+                      if clause.kind() in SUPERTYPE_KINDS for written in self._type_names(clause)]
+        methods: Dict[str, str] = {}
+        self._supertypes.append({"id": type_node_id, "name": name, "node_type": node_type,
+                                 "supertypes": supertypes, "methods": methods})
+
+        # Record components are the fields of a record
+        fields: Dict[str, str] = {}
+        parameters = declaration.field("parameters") if node_type == "Class" else None
+        for component in parameters.children() if parameters else []:
+            if component.kind() == "formal_parameter" and component.field("name"):
+                component_name = component.field("name").text()
+                fields[component_name] = self._create_field(component, component_name, component.field("type"),
+                                                            type_node_id, name, "Private")
+
+        body = declaration.field("body")
+        if body is not None:
+            self._parse_body(body, (type_node_id, qualified, node_type), name, file_node_id, build_index,
+                             module_name, methods, fields)
+        return type_node_id
+
+    def _parse_body(self, body: SgNode, owner: Tuple[str, str, str], binary_name: str, file_node_id: str,
+                    build_index: bool, module_name: str, methods: Dict[str, str],
+                    fields: Optional[Dict[str, str]] = None) -> None:
+        """
+        Extract the members of a class, interface, enum or anonymous class body.
+
+        Args:
+            body: The body node
+            owner: (node ID, qualified name, node type) of the type the body belongs to
+            binary_name: Name javac gives the type, used to name its anonymous classes
+            file_node_id: File node of the current file
+            build_index: Index nested types for cross-file resolution
+            module_name: Module of the file
+            methods: Filled with the names and node IDs of the methods declared
+            fields: Node IDs by name of the fields declared outside the body
+                (record components), which this.name accesses may reach too
+        """
+        owner_id, _, owner_type = owner
+        fields = dict(fields or {})
+        # (accessing node ID, member) of the members whose this.field accesses are linked
+        accessors: List[Tuple[str, SgNode]] = []
+        owner_name = self.nodes[owner_id].name
+        default_visibility = "Public" if owner_type == "Interface" else "Crate"
+        anonymous_count = 0
+
+        members = []
+        for child in body.children():
+            if child.kind() == "enum_body_declarations":
+                members.extend(child.children())
+            else:
+                members.append(child)
+
+        for member in members:
+            kind = member.kind()
+            if kind in TYPE_KINDS:
+                self._parse_type(member, file_node_id, owner, build_index, module_name, default_visibility)
+                continue
+            if kind == "enum_constant" and member.field("name"):
+                self._create_variant(member, owner_id, owner_name)
+            elif kind in ("method_declaration", "constructor_declaration", "compact_constructor_declaration"):
+                name_field = member.field("name")
+                method_name = name_field.text() if name_field else owner_name
+                method_id = self._create_method(member, method_name, owner_id, owner_name, default_visibility)
+                methods.setdefault(method_name, method_id)
+                accessors.append((method_id, member))
+            elif kind in ("field_declaration", "constant_declaration"):
+                visibility = "Public" if kind == "constant_declaration" else default_visibility
+                for declarator in member.children():
+                    if declarator.kind() == "variable_declarator" and declarator.field("name"):
+                        field_name = declarator.field("name").text()
+                        fields.setdefault(field_name, self._create_field(member, field_name, member.field("type"),
+                                                                         owner_id, owner_name, visibility))
+                # Field initializers (int b = this.a + 1) belong to the type
+                accessors.append((owner_id, member))
+            elif kind in ("block", "static_initializer"):
+                accessors.append((owner_id, member))
+
+            # Anonymous and local classes inside the member belong to this type
+            for inner in self._inner_types(member):
+                if inner.kind() == "object_creation_expression":
+                    anonymous_count += 1
+                    self._parse_anonymous(inner, f"{binary_name}${anonymous_count}", owner, file_node_id,
+                                          module_name)
+                else:
+                    self._parse_type(inner, file_node_id, owner, False, module_name, "Private")
+
+        for accessor_id, member in accessors:
+            self._link_field_accesses(accessor_id, member, fields)
+            self._collect_calls(accessor_id, member, owner_id, fields)
+
+    def _link_field_accesses(self, accessor_id: str, member: SgNode, fields: Dict[str, str]) -> None:
+        """
+        Create REFERENCES edges for the ``this.field`` accesses in a member.
+
+        Anonymous and local classes are skipped: their ``this`` is themselves,
+        and their bodies are parsed as types of their own.
+        """
+        stack = list(member.children())
+        while stack:
+            node = stack.pop()
+            kind = node.kind()
+            if kind in TYPE_KINDS or kind == "class_body":
+                continue
+            stack.extend(node.children())
+            target = node.field("object") if kind == "field_access" else None
+            if target is None or target.kind() != "this" or node.field("field") is None:
+                continue
+            field_id = fields.get(node.field("field").text())
+            if field_id is None:
+                continue
+            self._add_relation(CodeRelation(accessor_id, field_id, "REFERENCES", properties={
+                "access": self._classify_access(node), **self._site(node)}))
+
+    def _collect_calls(self, caller_id: str, member: SgNode, owner_id: str, fields: Dict[str, str]) -> None:
+        """
+        Queue the method calls in a member for _link_calls.
+
+        Each call keeps the type its receiver was declared with: None for
+        ``foo()`` and ``this.foo()``, empty when the receiver's type is not
+        known. Anonymous and local classes are skipped, as for field accesses.
+        """
+        field_types = {name: self._call_type(self.nodes[field_id].properties.get("type", ""))
+                       for name, field_id in fields.items()}
+        variables = self._variable_types(member)
+        # In source order, a chained call before the calls of its receiver
+        stack = list(reversed(member.children()))
+        while stack:
+            node = stack.pop()
+            kind = node.kind()
+            if kind in TYPE_KINDS or kind == "class_body":
+                continue
+            stack.extend(reversed(node.children()))
+            name = node.field("name") if kind == "method_invocation" else None
+            if name is None:
+                continue
+
+            receiver = node.field("object")
+            receiver_kind = receiver.kind() if receiver is not None else None
+            if receiver is None or receiver_kind == "this":
+                written = None
+            elif receiver_kind == "identifier":
+                text = receiver.text()
+                if text in variables:
+                    written = variables[text]
+                elif text in field_types:
+                    written = field_types[text]
+                else:
+                    # A name that is no variable is a type when it looks like one
+                    written = text if text in self._local_types or text[:1].isupper() else ""
+            elif receiver_kind == "field_access" and self._is_this(receiver.field("object")) \
+                    and receiver.field("field") is not None:
+                written = field_types.get(receiver.field("field").text(), "")
+            else:
+                written = ""
+            self._calls.append({
+                "caller_id": caller_id,
+                "owner_id": owner_id,
+                "type": written,
+                "member": name.text(),
+                "raw_name": " ".join(f"{receiver.text()}.{name.text()}".split()) if receiver else name.text(),
+                "site": self._site(node),
+            })
+
+    def _variable_types(self, member: SgNode) -> Dict[str, str]:
+        """Types of the parameters and local variables of a member by name, as _call_type gives them."""
+        types: Dict[str, str] = {}
+        parameters = member.field("parameters")
+        for parameter in parameters.children() if parameters else []:
+            if parameter.kind() == "formal_parameter" and parameter.field("name") and parameter.field("type"):
+                types[parameter.field("name").text()] = self._call_type(parameter.field("type").text())
+        stack = list(member.children())
+        while stack:
+            node = stack.pop()
+            kind = node.kind()
+            if kind in TYPE_KINDS or kind == "class_body":
+                continue
+            stack.extend(node.children())
+            if kind == "local_variable_declaration" and node.field("type"):
+                for declarator in node.children():
+                    if declarator.kind() != "variable_declarator" or not declarator.field("name"):
+                        continue
+                    type_text = node.field("type").text()
+                    value = declarator.field("value")
+                    # var dog = new Dog(..) has the type it creates
+                    if type_text == "var" and value is not None and value.kind() == "object_creation_expression" \
+                            and value.field("type"):
+                        type_text = value.field("type").text()
+                    types[declarator.field("name").text()] = self._call_type(type_text)
+            elif kind == "enhanced_for_statement" and node.field("name") and node.field("type"):
+                types[node.field("name").text()] = self._call_type(node.field("type").text())
+        return types
+
+    def _call_type(self, type_text: str) -> str:
+        """A declared type without type arguments, or empty for primitives, arrays and ``var``."""
+        written = self._base_name(type_text)
+        if not written or "[" in written or written in PRIMITIVE_TYPES:
+            return ""
+        return written
+
+    @staticmethod
+    def _is_this(node: Optional[SgNode]) -> bool:
+        return node is not None and node.kind() == "this"
+
+    def _site(self, node: SgNode) -> Dict[str, Any]:
+        """line_no and call_site (the stripped source line) of an access or call."""
+        line_no = node.range().start.line + 1
+        return {"line_no": line_no,
+                "call_site": self._source_lines[line_no - 1].strip() if line_no <= len(self._source_lines) else ""}
+
+    def _classify_access(self, access: SgNode) -> str:
+        """
+        Classify a field access as Read, Write or ReadWrite.
+
+        As for Rust, the access is widened to the whole place first, so
+        ``this.pos.x = 1`` and ``this.items[i] = v`` write the field.
+        Assignment targets are writes, compound assignments and ``++``/``--``
+        are ReadWrite, and everything else is a Read.
+        """
+        place = access
+        parent = place.parent()
+        while parent is not None and (
+                (parent.kind() == "field_access" and self._same_node(parent.field("object"), place))
+                or (parent.kind() == "array_access" and self._same_node(parent.field("array"), place))
+                or parent.kind() == "parenthesized_expression"):
+            place, parent = parent, parent.parent()
+        if parent is None:
+            return "Read"
+        if parent.kind() == "assignment_expression" and self._same_node(parent.field("left"), place):
+            operator = parent.field("operator")
+            return "Write" if operator is None or operator.text() == "=" else "ReadWrite"
+        if parent.kind() == "update_expression":
+            return "ReadWrite"
+        return "Read"
+
+    def _parse_anonymous(self, creation: SgNode, name: str, enclosing: Tuple[str, str, str], file_node_id: str,
+                         module_name: str) -> None:
+        """Extract an anonymous class as a Class node defined by the enclosing type."""
+        base = creation.field("type")
+        properties: Dict[str, Any] = {"visibility": "Private", "type_kind": "class", "anonymous": True}
+        if base is not None:
+            properties["base"] = self._base_name(base.text())
+        class_node_id = self._create_type_node(creation, "Class", name, properties)
+        self._add_relation(CodeRelation(enclosing[0], class_node_id, "DEFINES"))
+
+        body = next((c for c in creation.children() if c.kind() == "class_body"), None)
+        if body is not None:
+            # Nothing in an anonymous class can be named from another file
+            self._parse_body(body, (class_node_id, name, "Class"), name, file_node_id, False, module_name, {})
+
+    @staticmethod
+    def _inner_types(member: SgNode) -> Iterator[SgNode]:
+        """
+        Anonymous class creations and local type declarations inside a member, in source order.
+
+        Their own bodies are not searched; they are parsed as types of their own.
+        """
+        stack = list(reversed(member.children()))
+        while stack:
+            node = stack.pop()
+            kind = node.kind()
+            if kind in TYPE_KINDS:
+                yield node
+            elif kind == "object_creation_expression" and any(c.kind() == "class_body" for c in node.children()):
+                yield node
+                # Arguments of the constructor come before the body and may hold classes too
+                arguments = node.field("arguments")
+                if arguments is not None:
+                    stack.extend(reversed(arguments.children()))
+            else:
+                stack.extend(reversed(node.children()))
+
+    def _create_type_node(self, declaration: SgNode, node_type: str, name: str, properties: Dict[str, Any]) -> str:
+        """Create the node of a type declared in the current file."""
+        line_no = declaration.range().start.line + 1
+        self._set_annotations(properties, declaration)
+        node_id = self._get_node_id(node_type, name, self.current_file, line_no)
+        self.nodes[node_id] = CodeNode(
+            node_id=node_id,
+            node_type=node_type,
+            name=name,
+            file_path=self.current_file,
+            line_no=line_no,
+            end_line_no=declaration.range().end.line + 1,
+            properties=properties,
+        )
+        self._set_doc(node_id, declaration)
+        self._local_types.setdefault(name, []).append((node_id, node_type))
+        return node_id
+
+    def _create_method(self, declaration: SgNode, name: str, owner_id: str, owner_name: str,
+                       default_visibility: str) -> str:
+        """Create a method or constructor node defined by its type."""
+        line_no = declaration.range().start.line + 1
+        if declaration.kind() != "method_declaration":
+            method_kind = "constructor"
+        elif "static" in self._modifier_keywords(declaration):
+            method_kind = "static"
+        else:
+            method_kind = "instance"
+        properties: Dict[str, Any] = {
+            "method_of": owner_name,
+            "method_kind": method_kind,
+            "visibility": self._visibility(declaration, default_visibility),
+        }
+        self._set_annotations(properties, declaration)
+        if declaration.field("body"):
+            # Abstract and interface methods have no body to measure
+            properties["complexity"] = sg_complexity(declaration, "java")
+
+        method_node_id = self._get_node_id("Method", name, self.current_file, line_no)
+        self.nodes[method_node_id] = CodeNode(
+            node_id=method_node_id,
+            node_type="Method",
+            name=name,
+            file_path=self.current_file,
+            line_no=line_no,
+            end_line_no=declaration.range().end.line + 1,
+            properties=properties,
+        )
+        self._set_doc(method_node_id, declaration)
+        self._add_relation(CodeRelation(owner_id, method_node_id, "DEFINES"))
+        return method_node_id
+
+    def _create_field(self, declaration: SgNode, name: str, type_node: Optional[SgNode], owner_id: str,
+                      owner_name: str, default_visibility: str) -> str:
+        """Create a field (or record component, or interface constant) node defined by its type."""
+        line_no = declaration.range().start.line + 1
+        properties: Dict[str, Any] = {
+            "field_of": owner_name,
+            "visibility": self._visibility(declaration, default_visibility),
+        }
+        if type_node is not None:
+            properties["type"] = " ".join(type_node.text().split())
+        self._set_annotations(properties, declaration)
+
+        field_node_id = self._get_node_id("Field", name, self.current_file, line_no)
+        self.nodes[field_node_id] = CodeNode(
+            node_id=field_node_id,
+            node_type="Field",
+            name=name,
+            file_path=self.current_file,
+            line_no=line_no,
+            end_line_no=declaration.range().end.line + 1,
+            properties=properties,
+        )
+        self._add_relation(CodeRelation(owner_id, field_node_id, "DEFINES"))
+        return field_node_id
+
+    def _create_variant(self, constant: SgNode, enum_id: str, enum_name: str) -> None:
+        """Create a Variant node for an enum constant."""
+        name = constant.field("name").text()
+        line_no = constant.range().start.line + 1
+        properties: Dict[str, Any] = {"variant_of": enum_name, "visibility": "Public"}
+        self._set_annotations(properties, constant)
+        variant_node_id = self._get_node_id("Variant", name, self.current_file, line_no)
+        self.nodes[variant_node_id] = CodeNode(
+            node_id=variant_node_id,
+            node_type="Variant",
+            name=name,
+            file_path=self.current_file,
+            line_no=line_no,
+            end_line_no=constant.range().end.line + 1,
+            properties=properties,
+        )
+        self._add_relation(CodeRelation(enum_id, variant_node_id, "DEFINES"))
+
+    def _link_supertypes(self, file_node_id: str, imports: List[Tuple[str, bool, bool]]) -> None:
+        """
+        Add the EXTENDS and IMPLEMENTS edges of the file's types.
+
+        A class extends a class and implements interfaces, an interface
+        extends interfaces; supertypes declared in the file are linked here,
+        the others are queued with the modules they may come from.
+        """
+        for declared in self._supertypes:
+            for clause, written in declared["supertypes"]:
+                name = written.split(".")[-1]
+                if clause == "super_interfaces":
+                    relation_type, target_type = "IMPLEMENTS", "Interface"
+                else:
+                    relation_type, target_type = "EXTENDS", declared["node_type"]
+                local_id = next((node_id for node_id, node_type in self._local_types.get(name, [])
+                                 if node_type == target_type and node_id != declared["id"]), None)
+                hints = self._module_hints(written, imports)
+
+                if relation_type == "EXTENDS":
+                    if local_id:
+                        self._add_relation(CodeRelation(declared["id"], local_id, "EXTENDS",
+                                                        properties={"original_name": written}))
+                    else:
+                        self.pending_imports.append({
+                            "type": "EXTENDS",
+                            "source_id": declared["id"],
+                            "imported_name": name,
+                            "original_name": written,
+                            "module_hints": hints,
+                        })
+                elif local_id:
+                    self._add_relation(CodeRelation(declared["id"], local_id, "IMPLEMENTS",
+                                                    properties={"trait_path": written}))
+                    for method_name, method_id in declared["methods"].items():
+                        interface_method_id = self._interface_method(local_id, method_name)
+                        if interface_method_id:
+                            self._add_relation(CodeRelation(method_id, interface_method_id, "IMPLEMENTS",
+                                                            properties={"type": declared["name"]}))
+                else:
                     self.pending_imports.append({
-                        "type": "IMPORTS_MODULE",
+                        "type": "IMPLEMENTS",
                         "source_id": file_node_id,
-                        "imported_module": root_module,
-                        "full_module_path": import_text,
+                        "type_name": declared["name"],
+                        "type_node_id": declared["id"],
+                        "trait_name": name,
+                        "trait_path": written,
+                        "methods": dict(declared["methods"]),
+                        "module_hints": hints,
                     })
-    
-    def _parse_classes(self, root: SgNode, file_node_id: str, build_index: bool, module_name: str) -> None:
-        """Extract class declarations and their methods/fields."""
-        # Find all class_declaration nodes
-        for class_node in root.find_all(kind="class_declaration"):
-            # Get class name from field
-            name_field = class_node.field("name")
-            if not name_field:
+
+    def _link_calls(self, imports: List[Tuple[str, bool, bool]]) -> None:
+        """
+        Add the CALLS edges of the calls _collect_calls queued.
+
+        ``foo()`` calls the method of that name of the innermost enclosing
+        type declaring one, or else a method brought in by ``import static``.
+        A call on a receiver of a type of the file is linked when the type
+        declares the method; other receivers' types are queued with the
+        modules they may come from, and unknown ones as unresolved.
+        """
+        declared: Dict[Tuple[str, str], str] = {}
+        enclosing: Dict[str, str] = {}
+        for relation in self.relations:
+            target = self.nodes.get(relation.target_id)
+            if relation.relation_type != "DEFINES" or target is None:
                 continue
-            
-            class_name = name_field.text()
-            line_no = class_node.range().start.line + 1
-            
-            # Create class node
-            class_node_id = self._get_node_id("Class", class_name, self.current_file, line_no)
-            self.nodes[class_node_id] = CodeNode(
-                node_id=class_node_id,
-                node_type="Class",
-                name=class_name,
-                file_path=self.current_file,
-                line_no=line_no,
-            )
-            
-            # Add CONTAINS relation from file to class
-            self._add_relation(CodeRelation(file_node_id, class_node_id, "CONTAINS"))
-            
-            # Index the class for cross-file resolution
-            if build_index:
-                self.module_definitions[module_name][class_name] = class_node_id
-            
-            # Set current class context
-            self.current_class = class_node_id
-            
-            # Extract methods and fields from class body
-            body = class_node.field("body")
-            if body:
-                self._parse_methods(body, class_node_id)
-                self._parse_fields(body, class_node_id)
-            
-            # Reset context
-            self.current_class = None
-    
-    def _parse_methods(self, class_body: SgNode, class_node_id: str) -> None:
-        """Extract method declarations from class body."""
-        # Find all method_declaration nodes within class body
-        for method_node in class_body.find_all(kind="method_declaration"):
-            # Get method name
-            name_field = method_node.field("name")
-            if not name_field:
+            if target.node_type == "Method":
+                declared.setdefault((relation.source_id, target.name), target.node_id)
+            elif target.node_type in ("Class", "Interface", "Enum"):
+                enclosing[target.node_id] = relation.source_id
+        static_members = {path.rpartition(".")[2]: path.rpartition(".")[0]
+                          for path, static, wildcard in imports if static and not wildcard and "." in path}
+
+        for call in self._calls:
+            member, written = call["member"], call["type"]
+            if written is None:
+                type_id = call["owner_id"]
+                while type_id is not None and (type_id, member) not in declared:
+                    type_id = enclosing.get(type_id)
+                if type_id is not None:
+                    self._add_relation(CodeRelation(call["caller_id"], declared[(type_id, member)], "CALLS",
+                                                    properties={"class": self.nodes[type_id].name, **call["site"]}))
+                    continue
+                written = static_members.get(member, "")
+            if not written:
+                self.pending_imports.append({
+                    "type": "CALLS_UNRESOLVED",
+                    "source_id": call["caller_id"],
+                    "raw_name": call["raw_name"],
+                    **call["site"],
+                })
                 continue
-            
-            method_name = name_field.text()
-            line_no = method_node.range().start.line + 1
-            
-            # Create method node
-            method_node_id = self._get_node_id("Method", method_name, self.current_file, line_no)
-            self.nodes[method_node_id] = CodeNode(
-                node_id=method_node_id,
-                node_type="Method",
-                name=method_name,
-                file_path=self.current_file,
-                line_no=line_no,
-            )
-            
-            # Add DEFINES relation from class to method
-            self._add_relation(CodeRelation(class_node_id, method_node_id, "DEFINES"))
-    
-    def _parse_fields(self, class_body: SgNode, class_node_id: str) -> None:
-        """Extract field declarations (class variables) from class body."""
-        # Find all field_declaration nodes within class body
-        for field_node in class_body.find_all(kind="field_declaration"):
-            # Field declarations can have multiple declarators
-            # field_declaration has a "declarator" field (can be multiple)
-            for child in field_node.children():
-                if child.kind() == "variable_declarator":
-                    # Get variable name
-                    name_field = child.field("name")
-                    if name_field:
-                        var_name = name_field.text()
-                        line_no = child.range().start.line + 1
-                        
-                        # Create variable node (class field)
-                        var_node_id = self._get_node_id("Variable", var_name, self.current_file, line_no)
-                        self.nodes[var_node_id] = CodeNode(
-                            node_id=var_node_id,
-                            node_type="ClassVariable",
-                            name=var_name,
-                            file_path=self.current_file,
-                            line_no=line_no,
-                        )
-                        
-                        # Add DEFINES relation from class to field
-                        self._add_relation(CodeRelation(class_node_id, var_node_id, "DEFINES"))
+
+            name = written.split(".")[-1]
+            local_id = next((node_id for node_id, _ in self._local_types.get(name, [])
+                             if (node_id, member) in declared), None)
+            if local_id:
+                self._add_relation(CodeRelation(call["caller_id"], declared[(local_id, member)], "CALLS",
+                                                properties={"class": name, **call["site"]}))
+                continue
+            entry = {
+                "type": "CALLS_MEMBER",
+                "source_id": call["caller_id"],
+                "type_name": name,
+                "member_name": member,
+                "module_hints": self._module_hints(written, imports),
+                "raw_name": f"{written}.{member}",
+                **call["site"],
+            }
+            outer = written.split(".")[0]
+            if "." in written and outer[:1].isupper():
+                # A nested type (Dog.Builder) is indexed under its path in the outer type's module
+                entry["definition"] = written
+                entry["module_hints"] = self._module_hints(outer, imports)
+            self.pending_imports.append(entry)
+
+    def _module_hints(self, written: str, imports: List[Tuple[str, bool, bool]]) -> List[str]:
+        """
+        Modules a type name used in the file may be declared in, most likely first.
+
+        A qualified name names its module; otherwise a single-type import of
+        the name, the file's own package and the packages imported with
+        ``.*``, each assuming the type lives in a file of its name.
+        """
+        if "." in written:
+            return [written]
+        hints = [path for path, static, wildcard in imports
+                 if not static and not wildcard and path.split(".")[-1] == written]
+        hints.append(f"{self.current_package}.{written}" if self.current_package else written)
+        hints.extend(f"{path}.{written}" for path, static, wildcard in imports if wildcard and not static)
+        return hints
+
+    def _interface_method(self, interface_id: str, method_name: str) -> Optional[str]:
+        """The method of that name an interface of this file declares."""
+        for relation in self.relations:
+            if relation.source_id == interface_id and relation.relation_type == "DEFINES":
+                node = self.nodes.get(relation.target_id)
+                if node is not None and node.node_type == "Method" and node.name == method_name:
+                    return node.node_id
+        return None
+
+    def _type_names(self, clause: SgNode) -> List[str]:
+        """Supertypes listed by an extends or implements clause, as written without type arguments."""
+        types = []
+        for child in clause.children():
+            if child.kind() == "type_list":
+                types.extend(self._base_name(t.text()) for t in child.children() if t.is_named())
+            elif child.is_named():
+                types.append(self._base_name(child.text()))
+        return [t for t in types if t]
+
+    @staticmethod
+    def _base_name(type_text: str) -> str:
+        """A type as written without its type arguments: ``Comparable<Dog>`` -> ``Comparable``."""
+        return "".join(type_text.split("<", 1)[0].split())
+
+    @staticmethod
+    def _modifiers(declaration: SgNode) -> Optional[SgNode]:
+        return next((c for c in declaration.children() if c.kind() == "modifiers"), None)
+
+    def _annotations(self, declaration: SgNode) -> List[str]:
+        """Names of the annotations on a declaration, without ``@`` and arguments."""
+        modifiers = self._modifiers(declaration)
+        names = []
+        for child in modifiers.children() if modifiers else []:
+            if child.kind() not in ANNOTATION_KINDS:
+                continue
+            name_field = child.field("name")
+            name = name_field.text() if name_field else child.text().lstrip("@").split("(", 1)[0]
+            names.append("".join(name.split()))
+        return names
+
+    def _set_annotations(self, properties: Dict[str, Any], declaration: SgNode) -> None:
+        """Store the annotations of a declaration, and whether one deprecates it, in its properties."""
+        annotations = self._annotations(declaration)
+        if annotations:
+            properties["annotations"] = annotations
+        if any(name in DEPRECATED_ANNOTATIONS for name in annotations):
+            properties["deprecated"] = True
+
+    def _set_doc(self, node_id: str, declaration: SgNode) -> None:
+        """Store the Javadoc block in front of a declaration as its doc property."""
+        comment = declaration.prev()
+        text = comment.text() if comment is not None and comment.kind() == "block_comment" else ""
+        javadoc = text.startswith("/**") and not text.startswith("/**/")
+        set_doc(self.nodes[node_id], clean_doc_comment([text]) if javadoc else None)
+
+    def _modifier_keywords(self, declaration: SgNode) -> List[str]:
+        """Keyword modifiers of a declaration (``public``, ``static``, ...), annotations left out."""
+        modifiers = self._modifiers(declaration)
+        if modifiers is None:
+            return []
+        text = modifiers.text()
+        for child in modifiers.children():
+            if child.kind() in ANNOTATION_KINDS:
+                text = text.replace(child.text(), " ", 1)
+        return text.split()
+
+    def _visibility(self, declaration: SgNode, default: str = "Crate") -> str:
+        """
+        Visibility of a declaration from its access modifier.
+
+        Declarations without one are package-private, which maps to Crate:
+        visible throughout the package but not outside it. Interface members
+        are implicitly public, so their default is Public.
+        """
+        keywords = self._modifier_keywords(declaration)
+        for keyword, visibility in (("public", "Public"), ("protected", "Protected"), ("private", "Private")):
+            if keyword in keywords:
+                return visibility
+        return default
@@ -8,21 +8,32 @@ using the ast-grep-py library and produces output compatible with TypeScriptPars
 
 import os
 import logging
-from typing import Dict, List, Optional, Any
+from typing import Dict, List, Optional, Any, Tuple
 from ast_grep_py import SgRoot, SgNode
 
-from src.ast_parser.parser import CodeNode, CodeRelation
+from src.ast_parser.parser import CodeNode, CodeRelation, clean_doc_comment, es_module_name, set_doc
+from src.ast_parser.complexity import sg_complexity
 from .base_adapter import LanguageAdapter
 
 logger = logging.getLogger(__name__)
 
+# TypeScript-only declarations, mapped to the node type they produce
+TYPE_DECLARATIONS = {
+    "interface_declaration": "Interface",
+    "type_alias_declaration": "TypeAlias",
+    "enum_declaration": "Enum",
+}
+
+JSX_NODE_TYPES = ["jsx_element", "jsx_self_closing_element", "jsx_fragment"]
+
 
 class JavaScriptAstGrepAdapter(LanguageAdapter):
     """
     JavaScript/TypeScript adapter using ast-grep for parsing.
     
-    Extracts: Functions, Classes, Methods, Variables, Imports, Exports
-    Creates relations: CONTAINS, DEFINES, EXTENDS, IMPORTS
+    Extracts: Functions, Classes, Methods, Variables, Imports, Exports,
+    and for TypeScript also Interfaces, TypeAliases and Enums
+    Creates relations: CONTAINS, DEFINES, EXTENDS, IMPLEMENTS, IMPORTS, REEXPORTS
     
     Maintains parity with TypeScriptParser output format.
     """
@@ -41,28 +52,24 @@ class JavaScriptAstGrepAdapter(LanguageAdapter):
         self.current_file: str = ""
         self.current_class: Optional[str] = None
         self.imports: Dict[str, str] = {}  # {imported_name: module_path}
+        # (class node id, class name, interface as written) of this file's implements clauses
+        self.implements_clauses: List[Tuple[str, str, str]] = []
 
-    def parse_file(self, file_path: str, build_index: bool = False) -> tuple[Dict[str, CodeNode], List[CodeRelation]]:
+    def parse_source(self, source_code: str, file_path: str, build_index: bool = False,
+                     extension: Optional[str] = None) -> tuple[Dict[str, CodeNode], List[CodeRelation]]:
         """
-        Parse a JavaScript/TypeScript file using ast-grep.
-        
-        Args:
-            file_path: Path to the file to parse
-            build_index: Whether to build module definition index
-            
-        Returns:
-            Tuple of (nodes dictionary, relations list)
+        Parse JavaScript/TypeScript source with ast-grep.
+
+        ``extension`` (e.g. ".tsx") picks the grammar instead of the path's
+        extension.
         """
         self.current_file = file_path
         self.imports = {}
+        self.implements_clauses = []
         
         try:
-            # Read file content
-            with open(file_path, "r", encoding="utf-8") as f:
-                source_code = f.read()
-            
             # Determine language based on file extension
-            ext = os.path.splitext(file_path)[1].lower()
+            ext = (extension or os.path.splitext(file_path)[1]).lower()
             if ext == '.ts':
                 language = 'typescript'
             elif ext == '.tsx':
@@ -75,9 +82,10 @@ class JavaScriptAstGrepAdapter(LanguageAdapter):
             # Parse with ast-grep
             sg_root = SgRoot(source_code, language)
             root = sg_root.root()
+            self._record_syntax_errors(root)
             
             # Create file node
-            file_node_id = self._create_file_node(file_path)
+            file_node_id = self._create_file_node(file_path, source_code)
             
             # Generate module name for indexing
             module_name = os.path.splitext(os.path.basename(file_path))[0]
@@ -88,15 +96,20 @@ class JavaScriptAstGrepAdapter(LanguageAdapter):
             
             # Extract entities in order
             self._parse_imports(root, file_node_id)
+            self._parse_requires(root, file_node_id)
             self._parse_classes(root, file_node_id, build_index, module_name)
+            if language != 'javascript':
+                self._parse_type_declarations(root, file_node_id, build_index, module_name)
             self._parse_functions(root, file_node_id, build_index, module_name)
             self._parse_variables(root, file_node_id)
             self._parse_exports(root)
+            self._link_implements(file_node_id)
             
             return self.nodes, self.relations
             
         except Exception as e:
             logger.error(f"Error parsing file {file_path}: {e}")
+            self._record_failure(f"Parser failed: {e}")
             return {}, []
 
     def _parse_imports(self, root: SgNode, file_node_id: str) -> None:
@@ -107,62 +120,141 @@ class JavaScriptAstGrepAdapter(LanguageAdapter):
         - import { a, b } from './module'
         - import Default from './module'
         - import * as ns from './module'
+        - import ns = require('./module')
         """
         import_statements = root.find_all(kind="import_statement")
         
         for import_node in import_statements:
-            source_module = None
-            imported_names = []
-            
-            # Extract source module from string node
-            string_nodes = import_node.find_all(kind="string")
-            if string_nodes:
-                source_module = string_nodes[0].text().strip('"\'')
+            source_node = import_node.field("source")
+            if not source_node:
+                for require_clause in import_node.find_all(kind="import_require_clause"):
+                    self._parse_import_require(require_clause, file_node_id)
+                continue
+            source_module = source_node.text().strip('"\'')
             
-            # Extract imported names from import_clause
-            import_clauses = import_node.find_all(kind="import_clause")
+            # (local name, imported name) pairs; the imported name is None for
+            # a namespace import, which resolves to the module's file
+            imported_names = []
+            import_clauses = [c for c in import_node.children() if c.kind() == "import_clause"]
             if import_clauses:
                 import_clause = import_clauses[0]
                 
                 # Default import (identifier directly in clause)
                 for child in import_clause.children():
                     if child.kind() == "identifier":
-                        name = child.text()
-                        imported_names.append(name)
-                
-                # Named imports
-                named_imports = import_clause.find_all(kind="named_imports")
-                if named_imports:
-                    for named_import in named_imports:
-                        # Find import_specifier nodes
-                        specifiers = named_import.find_all(kind="import_specifier")
-                        for spec in specifiers:
-                            # Get identifier from specifier
-                            identifiers = [c for c in spec.children() if c.kind() == "identifier"]
-                            if identifiers:
-                                imported_names.append(identifiers[0].text())
+                        imported_names.append((child.text(), child.text()))
+                
+                # Named imports, possibly aliased (import { a as b })
+                for spec in import_clause.find_all(kind="import_specifier"):
+                    name_node = spec.field("name")
+                    if not name_node:
+                        continue
+                    alias_node = spec.field("alias")
+                    local = alias_node.text() if alias_node else name_node.text()
+                    imported_names.append((local, name_node.text()))
                 
                 # Namespace import (import * as name)
-                namespace_imports = import_clause.find_all(kind="namespace_import")
-                if namespace_imports:
-                    for ns_import in namespace_imports:
-                        identifiers = [c for c in ns_import.children() if c.kind() == "identifier"]
-                        if identifiers:
-                            imported_names.append(identifiers[0].text())
+                for ns_import in import_clause.find_all(kind="namespace_import"):
+                    identifiers = [c for c in ns_import.children() if c.kind() == "identifier"]
+                    if identifiers:
+                        imported_names.append((identifiers[0].text(), None))
             
             # Store imports for later resolution
-            if source_module:
-                root_module = source_module.split('/')[0] if '/' in source_module else source_module
-                
-                for name in imported_names:
-                    self.imports[name] = source_module
-                    self.pending_imports.append({
-                        "type": "IMPORTS",
-                        "source_id": file_node_id,
-                        "imported_module": root_module,
-                        "imported_name": name,
-                        "original_name": name,
-                    })
+            for local_name, _ in imported_names:
+                self.imports[local_name] = source_module
+            targets = [original for _, original in imported_names]
+            if not targets or None in targets:
+                targets = [None] + [name for name in targets if name is not None]
+            for name in targets:
+                self._queue_import(file_node_id, source_module, name)
+
+    def _parse_import_require(self, require_clause: SgNode, file_node_id: str) -> None:
+        """Queue a TypeScript `import ns = require('./module')` clause."""
+        source_node = require_clause.field("source")
+        if not source_node:
+            return
+        source_module = source_node.text().strip('"\'')
+        identifiers = [c for c in require_clause.children() if c.kind() == "identifier"]
+        if identifiers:
+            self.imports[identifiers[0].text()] = source_module
+        self._queue_import(file_node_id, source_module, None, require=True)
+
+    def _parse_requires(self, root: SgNode, file_node_id: str) -> None:
+        """
+        Extract CommonJS require calls.
+        
+        Handles:
+        - const lib = require('./lib')          (resolves to the file)
+        - const { a, b: c } = require('./lib')  (resolves to a and b)
+        - const a = require('./lib').a          (resolves to a)
+        - require('./lib')                      (resolves to the file)
+        """
+        for call_node in root.find_all(kind="call_expression"):
+            function_node = call_node.field("function")
+            if not function_node or function_node.kind() != "identifier" or function_node.text() != "require":
+                continue
+            arguments = call_node.field("arguments")
+            args = [c for c in arguments.children() if c.is_named()] if arguments else []
+            if len(args) != 1 or args[0].kind() != "string":
+                continue
+            source_module = args[0].text().strip('"\'')
+            
+            imported_names = self._require_bindings(call_node)
+            for local_name, _ in imported_names:
+                self.imports[local_name] = source_module
+            targets = [original for _, original in imported_names]
+            if not targets or None in targets:
+                targets = [None] + [name for name in targets if name is not None]
+            for name in targets:
+                self._queue_import(file_node_id, source_module, name, require=True)
+
+    def _require_bindings(self, call_node: SgNode) -> List[Tuple[str, Optional[str]]]:
+        """(local name, imported name) pairs bound by a require call; None binds the whole module."""
+        member = None
+        node = call_node
+        parent = node.parent()
+        if parent and parent.kind() == "member_expression":
+            # require('./lib').a
+            property_node = parent.field("property")
+            if property_node:
+                member = property_node.text()
+                node = parent
+        
+        declarator = node.parent()
+        if not declarator or declarator.kind() != "variable_declarator":
+            return [(member, member)] if member else []
+        name_node = declarator.field("name")
+        if not name_node:
+            return []
+        if name_node.kind() == "identifier":
+            return [(name_node.text(), member)]
+        
+        names = []
+        if name_node.kind() == "object_pattern" and member is None:
+            for child in name_node.children():
+                if child.kind() == "shorthand_property_identifier_pattern":
+                    names.append((child.text(), child.text()))
+                elif child.kind() == "pair_pattern":
+                    key_node = child.field("key")
+                    value_node = child.field("value")
+                    if key_node and value_node:
+                        names.append((value_node.text(), key_node.text()))
+        return names
+
+    def _queue_import(self, file_node_id: str, source_module: str, imported_name: Optional[str],
+                      import_type: str = "IMPORTS", require: bool = False) -> None:
+        """Queue an IMPORTS or REEXPORTS entry; a None imported_name targets the module's file."""
+        import_info = {
+            "type": import_type,
+            "source_id": file_node_id,
+            "imported_module": es_module_name(source_module),
+            "full_module_path": source_module,
+            "imported_name": imported_name,
+            "original_name": imported_name,
+        }
+        if require:
+            import_info["require"] = True
+        self.pending_imports.append(import_info)
 
     def _parse_classes(self, root: SgNode, file_node_id: str, build_index: bool, module_name: str) -> None:
         """
@@ -171,8 +263,11 @@ class JavaScriptAstGrepAdapter(LanguageAdapter):
         Handles:
         - class Animal { ... }
         - class Dog extends Animal { ... }
+        - abstract class Shape { ... } (TypeScript)
         """
         class_declarations = root.find_all(kind="class_declaration")
+        if self._get_language_from_file() in ("typescript", "tsx"):
+            class_declarations += root.find_all(kind="abstract_class_declaration")
         
         for class_node in class_declarations:
             # Get class name
@@ -199,7 +294,10 @@ class JavaScriptAstGrepAdapter(LanguageAdapter):
                 end_line_no=end_line_no,
                 properties={"language": self._get_language_from_file()},
             )
+            if class_node.kind() == "abstract_class_declaration":
+                self.nodes[node_id].properties["is_abstract"] = True
             self.nodes[node_id].code_snippet = class_node.text()
+            self._set_doc(node_id, class_node)
             
             # Create CONTAINS relation (file contains class)
             self._add_relation(CodeRelation(
@@ -218,6 +316,82 @@ class JavaScriptAstGrepAdapter(LanguageAdapter):
             if build_index and module_name:
                 self.module_definitions[module_name][class_name] = node_id
 
+    def _parse_type_declarations(self, root: SgNode, file_node_id: str, build_index: bool, module_name: str) -> None:
+        """
+        Extract TypeScript interfaces, type aliases and enums.
+        
+        Handles:
+        - interface Named extends Base { name: string }
+        - type Id = string | number
+        - const enum Role { Admin, Guest = "guest" }
+        """
+        for kind, node_type in TYPE_DECLARATIONS.items():
+            for decl in root.find_all(kind=kind):
+                name_node = decl.field("name")
+                if not name_node:
+                    continue
+                
+                type_name = name_node.text()
+                line_no = decl.range().start.line + 1
+                end_line_no = decl.range().end.line + 1
+                
+                properties: Dict[str, Any] = {"language": self._get_language_from_file()}
+                body = decl.field("body")
+                if kind == "interface_declaration":
+                    properties["members"] = self._member_names(body)
+                    properties["extends"] = [
+                        type_node.text()
+                        for clause in decl.children() if clause.kind() == "extends_type_clause"
+                        for type_node in clause.children() if type_node.is_named()
+                    ]
+                elif kind == "type_alias_declaration":
+                    value = decl.field("value")
+                    properties["value"] = value.text() if value else ""
+                else:
+                    properties["members"] = self._member_names(body)
+                    properties["is_const"] = any(c.kind() == "const" for c in decl.children())
+                
+                node_id = self._get_node_id(node_type, type_name, self.current_file, line_no)
+                self.nodes[node_id] = CodeNode(
+                    node_id=node_id,
+                    node_type=node_type,
+                    name=type_name,
+                    file_path=self.current_file,
+                    line_no=line_no,
+                    end_line_no=end_line_no,
+                    properties=properties,
+                )
+                self.nodes[node_id].code_snippet = decl.text()
+                self._set_doc(node_id, decl)
+                
+                self._add_relation(CodeRelation(
+                    source_id=file_node_id,
+                    target_id=node_id,
+                    relation_type="CONTAINS"
+                ))
+                
+                if build_index and module_name:
+                    self.module_definitions[module_name][type_name] = node_id
+
+    def _member_names(self, body: Optional[SgNode]) -> List[str]:
+        """Names of the members declared in an interface or enum body."""
+        names = []
+        if not body:
+            return names
+        for child in body.children():
+            if child.kind() == "property_identifier":
+                # Enum member without initializer
+                names.append(child.text())
+                continue
+            if child.kind() == "enum_assignment":
+                # Enum member with initializer; the name has no field of its own
+                names.append(next(c for c in child.children() if c.is_named()).text())
+                continue
+            name_node = child.field("name") if child.is_named() else None
+            if name_node:
+                names.append(name_node.text())
+        return names
+
     def _extract_class_inheritance(self, class_node: SgNode, class_node_id: str) -> None:
         """
         Extract class inheritance relationships (extends clause).
@@ -258,6 +432,51 @@ class JavaScriptAstGrepAdapter(LanguageAdapter):
                     target_id=parent_node_id,
                     relation_type="EXTENDS"
                 ))
+        
+        # TypeScript: class Foo extends Bar implements Baz, Qux<T>
+        for clause in heritage.children():
+            if clause.kind() != "implements_clause":
+                continue
+            for type_node in clause.children():
+                if type_node.is_named():
+                    self.implements_clauses.append(
+                        (class_node_id, self.nodes[class_node_id].name, type_node.text()))
+    
+    def _link_implements(self, file_node_id: str) -> None:
+        """
+        Create IMPLEMENTS edges for the implements clauses of this file's classes.
+        
+        An interface declared in the same file is linked directly. Otherwise
+        the edge is queued like a Rust trait impl, with the module it was
+        imported from as a hint, and resolved once every file is indexed.
+        """
+        for class_node_id, class_name, interface in self.implements_clauses:
+            # Qux<T> -> Qux, ns.Qux -> Qux
+            interface_name = interface.split("<", 1)[0].strip().split(".")[-1]
+            local = next((node.node_id for node in self.nodes.values()
+                          if node.node_type == "Interface" and node.name == interface_name
+                          and node.file_path == self.current_file), None)
+            if local:
+                self._add_relation(CodeRelation(
+                    source_id=class_node_id,
+                    target_id=local,
+                    relation_type="IMPLEMENTS",
+                    properties={"trait_path": interface},
+                ))
+                continue
+            
+            imported_from = self.imports.get(interface.split("<", 1)[0].strip().split(".")[0])
+            self.pending_imports.append({
+                "type": "IMPLEMENTS",
+                "source_id": file_node_id,
+                "type_name": class_name,
+                "type_node_id": class_node_id,
+                "trait_name": interface_name,
+                "trait_node_id": None,
+                "trait_path": interface,
+                "methods": {},
+                "module_hints": [es_module_name(imported_from)] if imported_from else [],
+            })
 
     def _extract_class_methods(self, class_node: SgNode, class_node_id: str, class_name: str) -> None:
         """
@@ -308,9 +527,11 @@ class JavaScriptAstGrepAdapter(LanguageAdapter):
                     "parameters": params,
                     "language": self._get_language_from_file(),
                     "is_async": is_async,
+                    "complexity": sg_complexity(method_node, "javascript"),
                 },
             )
             self.nodes[node_id].code_snippet = method_node.text()
+            self._set_doc(node_id, method_node)
             
             # Create DEFINES relation (class defines method)
             self._add_relation(CodeRelation(
@@ -365,9 +586,13 @@ class JavaScriptAstGrepAdapter(LanguageAdapter):
                     "language": self._get_language_from_file(),
                     "function_style": "standard",
                     "is_async": is_async,
+                    "complexity": sg_complexity(func_node, "javascript"),
                 },
             )
+            if self._is_react_component(func_name, func_node):
+                self.nodes[node_id].properties["react_component"] = True
             self.nodes[node_id].code_snippet = func_node.text()
+            self._set_doc(node_id, func_node)
             
             # Create CONTAINS relation (file contains function)
             self._add_relation(CodeRelation(
@@ -437,9 +662,13 @@ class JavaScriptAstGrepAdapter(LanguageAdapter):
                         "language": self._get_language_from_file(),
                         "function_style": "arrow",
                         "is_async": is_async,
+                        "complexity": sg_complexity(arrow_func, "javascript"),
                     },
                 )
+                if self._is_react_component(func_name, arrow_func):
+                    self.nodes[node_id].properties["react_component"] = True
                 self.nodes[node_id].code_snippet = arrow_func.text()
+                self._set_doc(node_id, lex_decl)
                 
                 # Create CONTAINS relation (file contains function)
                 self._add_relation(CodeRelation(
@@ -582,21 +811,38 @@ class JavaScriptAstGrepAdapter(LanguageAdapter):
         - export function name() { ... }
         - export class Name { ... }
         - export const name = ...
+        - export interface / type / enum Name ...
+        - export default Name
         - export { name1, name2 }
+        - export * from './module', export { name } from './module' (re-exports)
         """
         export_statements = root.find_all(kind="export_statement")
+        file_node_id = f"file:{self.current_file}"
         
         for export_node in export_statements:
+            source_node = export_node.field("source")
+            if source_node:
+                # Re-exports get their own edge type, apart from direct imports
+                source_module = source_node.text().strip('"\'')
+                names = [spec.field("name").text()
+                         for spec in export_node.find_all(kind="export_specifier") if spec.field("name")]
+                for name in names or [None]:
+                    self._queue_import(file_node_id, source_module, name, import_type="REEXPORTS")
+                continue
+            
+            is_default = any(child.kind() == "default" for child in export_node.children())
+            export_type = "default" if is_default else "named"
+            
             # Check for directly exported declarations
             for child in export_node.children():
                 entity_name = None
                 
-                if child.kind() == "function_declaration":
+                if child.kind() in ["function_declaration", *TYPE_DECLARATIONS]:
                     name_node = child.field("name")
                     if name_node:
                         entity_name = name_node.text()
                 
-                elif child.kind() == "class_declaration":
+                elif child.kind() in ["class_declaration", "abstract_class_declaration"]:
                     # Get class name
                     for class_child in child.children():
                         if class_child.kind() in ["identifier", "type_identifier"]:
@@ -611,26 +857,65 @@ class JavaScriptAstGrepAdapter(LanguageAdapter):
                         if name_node:
                             entity_name = name_node.text()
                 
+                elif child.kind() == "identifier" and is_default:
+                    # export default Name;
+                    entity_name = child.text()
+                
                 elif child.kind() == "export_clause":
                     # Named exports: export { name1, name2 }
                     export_specifiers = child.find_all(kind="export_specifier")
                     for spec in export_specifiers:
                         identifiers = [c for c in spec.children() if c.kind() == "identifier"]
                         if identifiers:
-                            entity_name = identifiers[0].text()
-                            # Mark as exported
-                            for node_id, code_node in self.nodes.items():
-                                if code_node.name == entity_name and code_node.file_path == self.current_file:
-                                    code_node.properties["exported"] = True
-                                    code_node.properties["export_type"] = "named"
+                            self._mark_exported(identifiers[0].text(), "named")
                     continue
                 
                 # Mark entity as exported
                 if entity_name:
-                    for node_id, code_node in self.nodes.items():
-                        if code_node.name == entity_name and code_node.file_path == self.current_file:
-                            code_node.properties["exported"] = True
-                            code_node.properties["export_type"] = "named"
+                    self._mark_exported(entity_name, export_type)
+
+    def _mark_exported(self, entity_name: str, export_type: str) -> None:
+        """Mark the current file's nodes with the given name as exported."""
+        for code_node in self.nodes.values():
+            if code_node.name == entity_name and code_node.file_path == self.current_file:
+                code_node.properties["exported"] = True
+                code_node.properties["export_type"] = export_type
+
+    def _set_doc(self, node_id: str, declaration: SgNode) -> None:
+        """
+        Store the JSDoc block right above a declaration as its doc property.
+        
+        Only ``/** */`` comments ending on the line before the declaration
+        (or before the ``export`` wrapping it) count; tags such as
+        ``@param`` are kept as written.
+        """
+        set_doc(self.nodes[node_id], self._jsdoc(declaration))
+    
+    @staticmethod
+    def _jsdoc(declaration: SgNode) -> Optional[str]:
+        """The cleaned JSDoc block of a declaration, or None."""
+        parent = declaration.parent()
+        if parent and parent.kind() == "export_statement":
+            declaration = parent
+        comment = declaration.prev()
+        if comment is None or comment.kind() != "comment":
+            return None
+        text = comment.text()
+        if not text.startswith("/**") or text.startswith("/**/"):
+            return None
+        if comment.range().end.line < declaration.range().start.line - 1:
+            return None
+        return clean_doc_comment([text])
+    
+    def _is_react_component(self, name: str, func_node: SgNode) -> bool:
+        """
+        Check if a function looks like a React function component.
+        
+        A component has a capitalized name and renders JSX in its body.
+        """
+        if self._get_language_from_file() not in ("jsx", "tsx") or not name[:1].isupper():
+            return False
+        return any(func_node.find(kind=kind) for kind in JSX_NODE_TYPES)
 
     def _extract_function_params(self, func_node: SgNode) -> List[str]:
         """
@@ -692,7 +977,7 @@ class JavaScriptAstGrepAdapter(LanguageAdapter):
         """
         current = node.parent()
         while current:
-            if current.kind() == "class_declaration":
+            if current.kind() in ["class_declaration", "abstract_class_declaration"]:
                 return True
             current = current.parent()
         return False
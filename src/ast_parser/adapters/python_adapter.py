@@ -1,13 +1,19 @@
 """Python language adapter using ast-grep for AST parsing."""
 
+import ast
 import os
 import json
-from typing import Dict, List, Optional, Tuple, Any, Union, Set
+from typing import Dict, List, Optional, Tuple, Any, Union
 
 from ast_grep_py import SgRoot, SgNode
 
 from .base_adapter import LanguageAdapter
-from ast_parser.parser import CodeNode, CodeRelation
+from ast_parser.complexity import sg_complexity
+from ast_parser.parser import (
+    REEXPORT_PREFIX, CodeNode, CodeRelation, clean_docstring, python_field_accesses, python_fields,
+    python_import_target, python_method_kind, python_module_name, python_receiver, python_relative_files,
+    python_visibility, set_doc,
+)
 
 
 class PythonAstGrepAdapter(LanguageAdapter):
@@ -16,43 +22,56 @@ class PythonAstGrepAdapter(LanguageAdapter):
     
     Extracts identical information to ASTParser for parity:
     - File, Class, Method, Function nodes
-    - ClassVariable, LocalVariable, GlobalVariable nodes
-    - CONTAINS, DEFINES, EXTENDS, CALLS relations
+    - ClassVariable, LocalVariable, GlobalVariable, Field nodes
+    - CONTAINS, DEFINES, EXTENDS, CALLS, REFERENCES relations
     - Import tracking for cross-file dependency resolution
     """
     
-    def __init__(self):
+    def __init__(self, init_reexports: bool = False):
+        """
+        Args:
+            init_reexports: Index an __init__.py under its package's name, with
+                the names it imports from its submodules as definitions of the package
+        """
         super().__init__("python")
+        self.init_reexports = init_reexports
         # Context tracking during parsing
         self.current_file: str = ""
+        # Module name of the current file in the definitions index; empty when not indexing
+        self.current_module: str = ""
         self.current_class: Optional[str] = None
         self.current_function: Optional[str] = None
+        # Fields of the current class by name, and the ast of its methods
+        # (with the line offset of the class) by line
+        self.current_fields: Dict[str, str] = {}
+        self._method_asts: Dict[int, Tuple[Union[ast.FunctionDef, ast.AsyncFunctionDef], int]] = {}
         # Import tracking: maps alias -> full module path
         self.imports: Dict[str, str] = {}
+        # Source lines of the current file, used to record call sites
+        self.source_lines: List[str] = []
     
-    def parse_file(self, file_path: str, build_index: bool = False) -> Tuple[Dict[str, CodeNode], List[CodeRelation]]:
+    def parse_source(self, source: str, file_path: str, build_index: bool = False) -> Tuple[Dict[str, CodeNode], List[CodeRelation]]:
         """
-        Parse a Python file using ast-grep.
-        
+        Parse Python source with ast-grep.
+
         Matches ASTParser behavior exactly for compatibility.
         """
         print(f"Parsing file: {file_path}")
         self.current_file = file_path
         self.imports = {}
+        self.source_lines = source.splitlines()
         
         try:
-            # Read source code
-            with open(file_path, "r", encoding="utf-8") as f:
-                source = f.read()
-            
             # Parse with ast-grep
             root = SgRoot(source, "python").root()
+            self._record_syntax_errors(root)
             
             # Create file node
-            file_node_id = self._create_file_node(file_path)
+            file_node_id = self._create_file_node(file_path, source)
             
             # Generate module name for indexing
-            module_name = os.path.splitext(os.path.basename(file_path))[0]
+            module_name = python_module_name(file_path, self.init_reexports)
+            self.current_module = module_name if build_index else ""
             if build_index:
                 if module_name not in self.module_definitions:
                     self.module_definitions[module_name] = {}
@@ -69,6 +88,7 @@ class PythonAstGrepAdapter(LanguageAdapter):
             
         except Exception as e:
             print(f"Error parsing file {file_path}: {e}")
+            self._record_failure(f"Parser failed: {e}")
             return {}, []
     
     def _parse_imports(self, root: SgNode) -> None:
@@ -115,25 +135,23 @@ class PythonAstGrepAdapter(LanguageAdapter):
         for import_from in root.find_all(kind="import_from_statement"):
             module_name_node = import_from.field("module_name")
             module_name = module_name_node.text() if module_name_node else None
+            # A relative module (..pkg) is written with its dots, and named without them
+            level = len(module_name) - len(module_name.lstrip(".")) if module_name else 0
+            bare_module = module_name[level:] if module_name else None
             
             # Find imported names (can be dotted_name, aliased_import, or wildcard_import)
             for child in import_from.children():
                 if child.kind() == "dotted_name":
                     # from module import symbol
                     symbol_name = child.text()
-                    if module_name:
-                        full_name = f"{module_name}.{symbol_name}"
+                    if bare_module:
+                        full_name = f"{bare_module}.{symbol_name}"
                         self.imports[symbol_name] = full_name
                     else:
                         self.imports[symbol_name] = symbol_name
                     
-                    self.pending_imports.append({
-                        "type": "IMPORTS_SYMBOL",
-                        "source_id": file_node_id,
-                        "imported_module": module_name,
-                        "imported_name": symbol_name,
-                        "alias": symbol_name
-                    })
+                    self._add_symbol_import(file_node_id, module_name, level, symbol_name, symbol_name)
+                    self._record_reexport(import_from, module_name, symbol_name, symbol_name)
                     
                 elif child.kind() == "aliased_import":
                     # from module import symbol as alias
@@ -142,19 +160,41 @@ class PythonAstGrepAdapter(LanguageAdapter):
                     if name_node and alias_node:
                         symbol_name = name_node.text()
                         alias_name = alias_node.text()
-                        if module_name:
-                            full_name = f"{module_name}.{symbol_name}"
+                        if bare_module:
+                            full_name = f"{bare_module}.{symbol_name}"
                             self.imports[alias_name] = full_name
                         else:
                             self.imports[alias_name] = symbol_name
                         
-                        self.pending_imports.append({
-                            "type": "IMPORTS_SYMBOL",
-                            "source_id": file_node_id,
-                            "imported_module": module_name,
-                            "imported_name": symbol_name,
-                            "alias": alias_name
-                        })
+                        self._add_symbol_import(file_node_id, module_name, level, symbol_name, alias_name)
+                        self._record_reexport(import_from, module_name, symbol_name, alias_name)
+    
+    def _add_symbol_import(self, file_node_id: str, module_name: Optional[str], level: int, symbol_name: str,
+                           alias_name: str) -> None:
+        """Queue a from-import of one name, with the files a relative import may lead to."""
+        import_info = {
+            "type": "IMPORTS_SYMBOL",
+            "source_id": file_node_id,
+            "imported_module": module_name,
+            "imported_name": symbol_name,
+            "alias": alias_name
+        }
+        if level:
+            import_info.update(python_relative_files(self.current_file, level, module_name[level:] or None,
+                                                     symbol_name))
+        self.pending_imports.append(import_info)
+    
+    def _record_reexport(self, import_from: SgNode, module_name: Optional[str], symbol_name: str,
+                         alias_name: str) -> None:
+        """Index a name a package's __init__.py imports at top level as the package's own."""
+        if not (self.init_reexports and self.current_module and module_name) \
+                or os.path.basename(self.current_file) != "__init__.py" \
+                or import_from.parent() is None or import_from.parent().kind() != "module":
+            return
+        source_module = module_name.rsplit(".", 1)[-1]
+        if source_module:
+            self.module_definitions[self.current_module].setdefault(
+                alias_name, f"{REEXPORT_PREFIX}{source_module}:{symbol_name}")
     
     def _parse_classes(self, root: SgNode, build_index: bool, module_name: str) -> None:
         """Extract all class definitions."""
@@ -189,7 +229,10 @@ class PythonAstGrepAdapter(LanguageAdapter):
             file_path=self.current_file,
             line_no=line_no,
             end_line_no=end_line_no,
+            properties={"visibility": python_visibility(class_name)},
         )
+        self._set_decorators(node_id, class_node)
+        self._set_doc(node_id, class_node)
         
         # Create file CONTAINS class relation
         file_node_id = f"file:{self.current_file}"
@@ -209,17 +252,30 @@ class PythonAstGrepAdapter(LanguageAdapter):
         superclasses = class_node.field("superclasses")
         if superclasses:
             for child in superclasses.children():
-                if child.kind() == "identifier":
+                object_node = child.field("object") if child.kind() == "attribute" else None
+                if object_node and object_node.kind() == "identifier" and object_node.text() in self.imports:
+                    # Base class of an imported module (models.Base)
+                    attribute = child.field("attribute").text()
+                    imported_module, imported_name = python_import_target(
+                        f"{self.imports[object_node.text()]}.{attribute}")
+                    self.pending_imports.append({
+                        "type": "EXTENDS",
+                        "source_id": node_id,
+                        "imported_module": imported_module,
+                        "imported_name": imported_name,
+                        "original_name": f"{object_node.text()}.{attribute}"
+                    })
+                elif child.kind() == "identifier":
                     base_name = child.text()
                     
                     if base_name in self.imports:
                         # Imported base class - add to pending for second pass
-                        imported_class = self.imports[base_name]
+                        imported_module, imported_name = python_import_target(self.imports[base_name])
                         self.pending_imports.append({
                             "type": "EXTENDS",
                             "source_id": node_id,
-                            "imported_module": imported_class.split(".")[0] if "." in imported_class else imported_class,
-                            "imported_name": imported_class.split(".")[-1] if "." in imported_class else imported_class,
+                            "imported_module": imported_module,
+                            "imported_name": imported_name,
                             "original_name": base_name
                         })
                     else:
@@ -235,6 +291,8 @@ class PythonAstGrepAdapter(LanguageAdapter):
         # Parse class body (methods and attributes)
         prev_class = self.current_class
         self.current_class = node_id
+        prev_fields = self.current_fields, self._method_asts
+        self.current_fields, self._method_asts = self._parse_fields(class_node, class_name, node_id)
         
         body = class_node.field("body")
         if body:
@@ -254,8 +312,46 @@ class PythonAstGrepAdapter(LanguageAdapter):
                             self._parse_class_attribute(expr_child)
         
         self.current_class = prev_class
+        self.current_fields, self._method_asts = prev_fields
         return node_id
     
+    def _parse_fields(self, class_node: SgNode, class_name: str, class_id: str):
+        """
+        Extract the instance attributes of a class as Field nodes, as ASTParser does.
+        
+        The class is parsed again with the ast module, so that both parsers
+        share python_fields and python_field_accesses; a class that does not
+        parse on its own, e.g. one with a syntax error, has no fields.
+        
+        Returns:
+            Field node IDs by name, and (method ast, line offset) by method line
+        """
+        try:
+            class_def = ast.parse(class_node.text()).body[0]
+        except SyntaxError:
+            return {}, {}
+        offset = class_node.range().start.line
+        
+        fields = {}
+        for name, declaration, field_type in python_fields(class_def):
+            line_no = declaration.lineno + offset
+            node_id = self._get_node_id("Field", name, self.current_file, line_no)
+            self.nodes[node_id] = CodeNode(
+                node_id=node_id,
+                node_type="Field",
+                name=name,
+                file_path=self.current_file,
+                line_no=line_no,
+                end_line_no=declaration.end_lineno + offset,
+                properties={"field_of": class_name, "type": field_type,
+                            "visibility": python_visibility(name, member=True)},
+            )
+            self.relations.append(CodeRelation(source_id=class_id, target_id=node_id, relation_type="DEFINES"))
+            fields[name] = node_id
+        methods = {item.lineno + offset: (item, offset) for item in class_def.body
+                   if isinstance(item, (ast.FunctionDef, ast.AsyncFunctionDef))}
+        return fields, methods
+    
     def _parse_method(self, method_node: SgNode) -> None:
         """Parse a method definition inside a class."""
         name_node = method_node.field("name")
@@ -276,8 +372,12 @@ class PythonAstGrepAdapter(LanguageAdapter):
             file_path=self.current_file,
             line_no=line_no,
             end_line_no=end_line_no,
-            properties={"is_method": True},
+            properties={"is_method": True, "visibility": python_visibility(method_name, member=True),
+                        "method_kind": python_method_kind(self._decorators(method_node)),
+                        "complexity": sg_complexity(method_node, "python")},
         )
+        self._set_decorators(node_id, method_node)
+        self._set_doc(node_id, method_node)
         
         # Create class DEFINES method relation
         if self.current_class:
@@ -300,6 +400,16 @@ class PythonAstGrepAdapter(LanguageAdapter):
         if body:
             self._find_function_calls(body)
         
+        # Field accesses through self
+        function, offset = self._method_asts.get(line_no, (None, 0))
+        receiver = python_receiver(function) if function else None
+        if receiver is not None:
+            for name, access_line, access in python_field_accesses(function, receiver):
+                if name in self.current_fields:
+                    self._add_relation(CodeRelation(
+                        node_id, self.current_fields[name], "REFERENCES",
+                        properties={"access": access, **self._call_site(access_line + offset)}))
+        
         self.current_function = prev_function
     
     def _parse_top_level_functions(self, root: SgNode, build_index: bool, module_name: str) -> None:
@@ -338,8 +448,11 @@ class PythonAstGrepAdapter(LanguageAdapter):
             file_path=self.current_file,
             line_no=line_no,
             end_line_no=end_line_no,
-            properties={"is_method": False},
+            properties={"is_method": False, "visibility": python_visibility(func_name),
+                        "complexity": sg_complexity(func_node, "python")},
         )
+        self._set_decorators(node_id, func_node)
+        self._set_doc(node_id, func_node)
         
         # Create file CONTAINS function relation
         file_node_id = f"file:{self.current_file}"
@@ -369,6 +482,100 @@ class PythonAstGrepAdapter(LanguageAdapter):
         self.current_function = prev_function
         return node_id
     
+    def _parse_nested_function(self, func_node: SgNode) -> None:
+        """
+        Parse a function defined in the body of another function or method.
+        
+        Matches ASTParser._parse_nested_function: a Private Function node the
+        enclosing function DEFINES, owning the calls in its body, while its
+        decorators, defaults and annotations count as calls of the enclosing one.
+        """
+        name_node = func_node.field("name")
+        if not name_node:
+            return
+        decorated = func_node.parent()
+        if decorated and decorated.kind() == "decorated_definition":
+            for decorator in decorated.children():
+                if decorator.kind() == "decorator":
+                    self._find_function_calls(decorator)
+        for field_name in ("parameters", "return_type"):
+            scope_node = func_node.field(field_name)
+            if scope_node:
+                self._find_function_calls(scope_node)
+        
+        func_name = name_node.text()
+        line_no = func_node.range().start.line + 1
+        node_id = self._get_node_id("Function", func_name, self.current_file, line_no)
+        self.nodes[node_id] = CodeNode(
+            node_id=node_id,
+            node_type="Function",
+            name=func_name,
+            file_path=self.current_file,
+            line_no=line_no,
+            end_line_no=func_node.range().end.line + 1,
+            properties={"is_method": False, "visibility": "Private",
+                        "complexity": sg_complexity(func_node, "python")},
+        )
+        self._set_decorators(node_id, func_node)
+        self._set_doc(node_id, func_node)
+        self.relations.append(
+            CodeRelation(
+                source_id=self.current_function,
+                target_id=node_id,
+                relation_type="DEFINES",
+            )
+        )
+        self._parse_function_args(func_node, node_id)
+        
+        prev_function = self.current_function
+        self.current_function = node_id
+        body = func_node.field("body")
+        if body:
+            self._find_function_calls(body)
+        self.current_function = prev_function
+    
+    @staticmethod
+    def _decorators(definition: SgNode) -> List[str]:
+        """Decorators of a class or function as written, without the ``@``."""
+        decorated = definition.parent()
+        if not decorated or decorated.kind() != "decorated_definition":
+            return []
+        return [decorator.text()[1:].strip() for decorator in decorated.children()
+                if decorator.kind() == "decorator"]
+    
+    def _set_decorators(self, node_id: str, definition: SgNode) -> None:
+        """Record the decorators of a class or function, matching ASTParser._set_decorators."""
+        decorators = self._decorators(definition)
+        if decorators:
+            self.nodes[node_id].properties["decorators"] = decorators
+    
+    def _set_doc(self, node_id: str, definition: SgNode) -> None:
+        """
+        Store the docstring of a class or function as its doc property.
+        
+        The docstring is a string literal as the first statement of the
+        body; raw, escaped and implicitly concatenated strings are evaluated
+        as Python would, and f-strings are not docstrings.
+        """
+        set_doc(self.nodes[node_id], self._docstring(definition))
+    
+    @staticmethod
+    def _docstring(definition: SgNode) -> Optional[str]:
+        """The cleaned docstring of a class or function, or None."""
+        body = definition.field("body")
+        statements = [child for child in body.children()
+                      if child.is_named() and child.kind() != "comment"] if body else []
+        if not statements or statements[0].kind() != "expression_statement":
+            return None
+        literal = statements[0].child(0)
+        if literal is None or literal.kind() not in ("string", "concatenated_string"):
+            return None
+        try:
+            value = ast.literal_eval(literal.text())
+        except (ValueError, SyntaxError):
+            return None
+        return clean_docstring(value) if isinstance(value, str) else None
+    
     def _parse_function_args(self, func_node: SgNode, node_id: str) -> None:
         """Extract function/method parameters."""
         params_node = func_node.field("parameters")
@@ -526,9 +733,20 @@ class PythonAstGrepAdapter(LanguageAdapter):
     
     def _find_function_calls(self, node: SgNode) -> None:
         """Recursively find all function/method calls in a node."""
-        # Find all call expressions in this subtree
-        for call_node in node.find_all(kind="call"):
-            self._process_call(call_node)
+        for child in node.children():
+            kind = child.kind()
+            if kind == "decorated_definition":
+                definition = child.field("definition")
+                if definition and definition.kind() == "function_definition" and self.current_function:
+                    # Parsed with its decorators
+                    self._parse_nested_function(definition)
+                    continue
+            elif kind == "function_definition" and self.current_function:
+                self._parse_nested_function(child)
+                continue
+            elif kind == "call":
+                self._process_call(child)
+            self._find_function_calls(child)
     
     def _process_call(self, call_node: SgNode) -> None:
         """Process a single call node."""
@@ -539,6 +757,8 @@ class PythonAstGrepAdapter(LanguageAdapter):
         if not func_node:
             return
         
+        call_site = self._call_site(call_node.range().start.line + 1)
+        
         if func_node.kind() == "identifier":
             # Direct function call: func()
             func_name = func_node.text()
@@ -551,7 +771,8 @@ class PythonAstGrepAdapter(LanguageAdapter):
                     "source_id": self.current_function,
                     "imported_module": imported_func.split(".")[0] if "." in imported_func else imported_func,
                     "imported_name": imported_func.split(".")[-1] if "." in imported_func else imported_func,
-                    "original_name": func_name
+                    "original_name": func_name,
+                    **call_site
                 })
             else:
                 # Call to local function
@@ -560,6 +781,7 @@ class PythonAstGrepAdapter(LanguageAdapter):
                         source_id=self.current_function,
                         target_id=f"Function:{self.current_file}:{func_name}:0",
                         relation_type="CALLS",
+                        properties=dict(call_site),
                     )
                 )
         
@@ -581,7 +803,8 @@ class PythonAstGrepAdapter(LanguageAdapter):
                         "imported_module": imported_obj.split(".")[0] if "." in imported_obj else imported_obj,
                         "imported_class": imported_obj.split(".")[-1] if "." in imported_obj else imported_obj,
                         "method_name": method_name,
-                        "original_obj_name": obj_name
+                        "original_obj_name": obj_name,
+                        **call_site
                     })
                 else:
                     # Method call on local object
@@ -590,6 +813,13 @@ class PythonAstGrepAdapter(LanguageAdapter):
                             source_id=self.current_function,
                             target_id=f"Method:{self.current_file}:{method_name}:0",
                             relation_type="CALLS",
-                            properties={"object": obj_name},
+                            properties={"object": obj_name, **call_site},
                         )
                     )
+    
+    def _call_site(self, line_no: int) -> Dict[str, Any]:
+        """Line number and source text of a call, matching ASTParser._call_site."""
+        site: Dict[str, Any] = {"line_no": line_no}
+        if 0 < line_no <= len(self.source_lines):
+            site["call_site"] = self.source_lines[line_no - 1].strip()
+        return site
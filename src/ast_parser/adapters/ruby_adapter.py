@@ -0,0 +1,425 @@
+"""Ruby language adapter using ast-grep for AST parsing."""
+
+import json
+import os
+import re
+from typing import Any, Dict, List, Optional, Tuple
+
+from ast_grep_py import SgRoot, SgNode
+
+from .base_adapter import LanguageAdapter
+from ast_parser.complexity import sg_complexity
+from ast_parser.parser import CodeNode, CodeRelation
+
+
+# Methods loading another file, and whether their path is relative to the requiring file
+REQUIRE_METHODS = {"require": False, "require_relative": True}
+
+# ActiveRecord association macros, called in the body of a model
+AR_ASSOCIATIONS = ("belongs_to", "has_one", "has_many", "has_and_belongs_to_many")
+
+# Associations named in the plural, whose model is the singular
+PLURAL_ASSOCIATIONS = ("has_many", "has_and_belongs_to_many")
+
+# Association options recorded on the edge
+AR_OPTIONS = ("class_name", "through", "polymorphic")
+
+# Keywords setting the visibility of the methods that follow them, or of the ones they name
+VISIBILITY_KEYWORDS = {"public": "Public", "private": "Private", "protected": "Protected"}
+
+# Plurals the singularizing rules get wrong
+IRREGULAR_PLURALS = {"people": "person", "children": "child", "men": "man", "women": "woman"}
+
+# Parameter node kinds and the kind recorded in ``args``
+PARAMETER_KINDS = {
+    "identifier": "required",
+    "optional_parameter": "optional",
+    "keyword_parameter": "keyword",
+    "splat_parameter": "splat",
+    "hash_splat_parameter": "double_splat",
+    "block_parameter": "block",
+}
+
+
+def singularize(name: str) -> str:
+    """Singular of a plural snake_case name, by the common English rules: ``categories`` -> ``category``."""
+    head, _, last = name.rpartition("_")
+    if last in IRREGULAR_PLURALS:
+        singular = IRREGULAR_PLURALS[last]
+    elif last.endswith("ies") and len(last) > 3:
+        singular = last[:-3] + "y"
+    elif last.endswith(("sses", "xes", "ches", "shes")):
+        singular = last[:-2]
+    elif last.endswith("s") and not last.endswith("ss"):
+        singular = last[:-1]
+    else:
+        singular = last
+    return f"{head}_{singular}" if head else singular
+
+
+def camelize(name: str) -> str:
+    """``line_item`` -> ``LineItem``."""
+    return "".join(part[:1].upper() + part[1:] for part in name.split("_"))
+
+
+def underscore(name: str) -> str:
+    """``ApplicationRecord`` -> ``application_record``, the file Rails expects the constant in."""
+    return re.sub(r"(?<=[a-z0-9])(?=[A-Z])|(?<=[A-Z])(?=[A-Z][a-z])", "_", name).lower()
+
+
+class RubyAdapter(LanguageAdapter):
+    """
+    Ruby adapter using ast-grep library.
+
+    Extracts:
+    - File, Class, Module, Function and Method nodes
+    - CONTAINS, DEFINES and EXTENDS relations
+    - ``require`` and ``require_relative`` as IMPORTS edges between files
+    - ActiveRecord associations (``has_many :posts``) as AR_ASSOCIATION
+      edges from a model to the model they name
+
+    Methods carry ``method_of`` and a ``method_kind`` of ``instance`` or
+    ``class``: ``def self.name``, and every ``def`` inside ``class << self``,
+    is a class method. ``private``, ``protected`` and ``public`` set the
+    ``visibility`` of the methods after them, of the method they wrap
+    (``private def name``) or of the methods they name (``private :name``);
+    ``initialize`` is always private. A ``def`` outside any class or module
+    is a Function.
+
+    Requires, superclasses declared in other files and associations are
+    resolved in the second pass. Constants are looked up by name, in the
+    file Rails expects them in first (``Comment`` in ``comment.rb``). The
+    model of an association is its ``class_name:``, or else its name,
+    singularized for ``has_many`` and ``has_and_belongs_to_many`` and
+    camelized (``:line_items`` -> ``LineItem``); polymorphic ``belongs_to``
+    associations have no model to link to.
+
+    Supports Ruby source files (.rb).
+    """
+
+    def __init__(self):
+        super().__init__("ruby")
+        self.current_file: str = ""
+
+    def parse_source(self, source: str, file_path: str, build_index: bool = False) -> Tuple[Dict[str, CodeNode], List[CodeRelation]]:
+        """
+        Parse Ruby source with ast-grep.
+
+        Extracts classes, modules, methods, requires and associations.
+        """
+        self.current_file = file_path
+
+        try:
+            root = SgRoot(source, "ruby").root()
+            self._record_syntax_errors(root)
+
+            file_node_id = self._create_file_node(file_path, source)
+
+            # Files are indexed by name, as Rails autoloads constants from the file named after them
+            module_name = os.path.splitext(os.path.basename(file_path))[0]
+            if build_index:
+                if module_name not in self.module_definitions:
+                    self.module_definitions[module_name] = {}
+                self.module_to_file[module_name] = file_node_id
+
+            self._parse_requires(root, file_node_id)
+            self._parse_body(root, file_node_id, None, file_node_id, build_index, module_name)
+
+            return self.nodes, self.relations
+
+        except Exception as e:
+            print(f"Error parsing Ruby file {file_path}: {e}")
+            self._record_failure(f"Parser failed: {e}")
+            return {}, []
+
+    def _parse_requires(self, root: SgNode, file_node_id: str) -> None:
+        """Queue every ``require "x"`` and ``require_relative "x"`` of the file, wherever it is called."""
+        for call in root.find_all(kind="call"):
+            method = call.field("method")
+            if call.field("receiver") is not None or method is None or method.text() not in REQUIRE_METHODS:
+                continue
+            arguments = self._arguments(call)
+            path = self._literal(arguments[0]) if arguments else None
+            if not path:
+                # A computed path (File.join(...), interpolation) cannot be followed
+                continue
+            self.pending_imports.append({
+                "type": "IMPORTS_RUBY",
+                "source_id": file_node_id,
+                "imported_module": os.path.splitext(os.path.basename(path))[0],
+                "path": path,
+                "relative": REQUIRE_METHODS[method.text()],
+                "line_no": call.range().start.line + 1,
+                "raw_name": path,
+            })
+
+    def _parse_body(self, container: SgNode, owner_id: str, owner_name: Optional[str], file_node_id: str,
+                    build_index: bool, module_name: str, class_methods: bool = False) -> None:
+        """
+        Extract the declarations directly inside the file, a class or module
+        body, or a ``class << self`` block, in order, so that visibility
+        keywords apply to the methods after them.
+        """
+        visibility = "Public"
+        for statement in self._statements(container):
+            kind = statement.kind()
+            if kind in ("class", "module"):
+                self._parse_type(statement, owner_id, file_node_id, build_index, module_name)
+            elif kind == "singleton_class":
+                # class << self: its methods are class methods of the enclosing type
+                if owner_name is not None:
+                    self._parse_body(statement, owner_id, owner_name, file_node_id, build_index, module_name,
+                                     class_methods=True)
+            elif kind in ("method", "singleton_method"):
+                self._create_method(statement, owner_id, owner_name, visibility,
+                                    class_methods or kind == "singleton_method",
+                                    file_node_id, build_index, module_name)
+            elif kind == "identifier" and statement.text() in VISIBILITY_KEYWORDS and owner_name is not None:
+                visibility = VISIBILITY_KEYWORDS[statement.text()]
+            elif kind == "call" and statement.field("receiver") is None and statement.field("method"):
+                self._parse_macro(statement, owner_id, owner_name, class_methods,
+                                  file_node_id, build_index, module_name)
+
+    def _parse_macro(self, call: SgNode, owner_id: str, owner_name: Optional[str], class_methods: bool,
+                     file_node_id: str, build_index: bool, module_name: str) -> None:
+        """A call in a class body: a visibility keyword with arguments, or an association."""
+        method = call.field("method").text()
+        arguments = self._arguments(call)
+        if method in VISIBILITY_KEYWORDS and owner_name is not None:
+            for argument in arguments:
+                if argument.kind() in ("method", "singleton_method"):
+                    # private def name ... end
+                    self._create_method(argument, owner_id, owner_name, VISIBILITY_KEYWORDS[method],
+                                        class_methods or argument.kind() == "singleton_method",
+                                        file_node_id, build_index, module_name)
+                elif self._literal(argument):
+                    # private :name, "other"
+                    self._set_visibility(owner_id, self._literal(argument), VISIBILITY_KEYWORDS[method])
+        elif method in AR_ASSOCIATIONS and owner_name is not None \
+                and self.nodes[owner_id].node_type == "Class":
+            self._queue_association(call, method, arguments, owner_id)
+
+    def _parse_type(self, declaration: SgNode, owner_id: str, file_node_id: str,
+                    build_index: bool, module_name: str) -> None:
+        """Extract a class or module, its superclass and everything in its body."""
+        name_node = declaration.field("name")
+        if name_node is None:
+            return
+        name = self._constant_name(name_node)
+        node_type = "Class" if declaration.kind() == "class" else "Module"
+        line_no = declaration.range().start.line + 1
+
+        properties: Dict[str, Any] = {"visibility": "Public"}
+        if name_node.kind() == "scope_resolution":
+            # class Admin::User names its namespace in place
+            properties["namespace"] = name_node.text().rsplit("::", 1)[0]
+        node_id = self._get_node_id(node_type, name, self.current_file, line_no)
+        self.nodes[node_id] = CodeNode(
+            node_id=node_id,
+            node_type=node_type,
+            name=name,
+            file_path=self.current_file,
+            line_no=line_no,
+            end_line_no=declaration.range().end.line + 1,
+            properties=properties,
+        )
+
+        # Top-level types belong to the file, nested ones to their class or module
+        if owner_id == file_node_id:
+            self._add_relation(CodeRelation(file_node_id, node_id, "CONTAINS"))
+        else:
+            self._add_relation(CodeRelation(owner_id, node_id, "DEFINES"))
+        if build_index:
+            self.module_definitions[module_name].setdefault(name, node_id)
+
+        superclass = declaration.field("superclass") if node_type == "Class" else None
+        if superclass is not None:
+            self._parse_superclass(superclass, node_id)
+
+        self._parse_body(declaration, node_id, name, file_node_id, build_index, module_name)
+
+    def _parse_superclass(self, superclass: SgNode, class_id: str) -> None:
+        """Link a class to its superclass, here or in the second pass."""
+        base = next((c for c in superclass.children() if c.kind() in ("constant", "scope_resolution")), None)
+        if base is None:
+            # class Foo < Struct.new(:a) and other computed superclasses
+            return
+        base_name = self._constant_name(base)
+        local_id = next((node_id for node_id, node in self.nodes.items()
+                         if node.node_type == "Class" and node.name == base_name and node_id != class_id
+                         and node.file_path == self.current_file), None)
+        if local_id:
+            self._add_relation(CodeRelation(class_id, local_id, "EXTENDS",
+                                            properties={"original_name": base.text()}))
+        else:
+            self.pending_imports.append({
+                "type": "EXTENDS",
+                "source_id": class_id,
+                "imported_module": None,
+                "imported_name": base_name,
+                "original_name": base.text(),
+                "module_hints": [underscore(base_name)],
+            })
+
+    def _create_method(self, declaration: SgNode, owner_id: str, owner_name: Optional[str], visibility: str,
+                       class_method: bool, file_node_id: str, build_index: bool, module_name: str) -> None:
+        """Create a Method of a class or module, or a Function for a top-level ``def``."""
+        name_node = declaration.field("name")
+        if name_node is None:
+            return
+        name = name_node.text()
+        line_no = declaration.range().start.line + 1
+        properties: Dict[str, Any] = {
+            "args": json.dumps(self._parameters(declaration.field("parameters"))),
+            "complexity": sg_complexity(declaration, "ruby"),
+        }
+
+        if owner_name is None:
+            node_type = "Function"
+            properties["visibility"] = "Public"
+        else:
+            node_type = "Method"
+            properties.update({
+                "method_of": owner_name,
+                "method_kind": "class" if class_method else "instance",
+                "visibility": "Private" if name == "initialize" and not class_method else visibility,
+            })
+
+        node_id = self._get_node_id(node_type, name, self.current_file, line_no)
+        self.nodes[node_id] = CodeNode(
+            node_id=node_id,
+            node_type=node_type,
+            name=name,
+            file_path=self.current_file,
+            line_no=line_no,
+            end_line_no=declaration.range().end.line + 1,
+            properties=properties,
+        )
+        if owner_name is None:
+            self._add_relation(CodeRelation(file_node_id, node_id, "CONTAINS"))
+            if build_index:
+                self.module_definitions[module_name].setdefault(name, node_id)
+        else:
+            self._add_relation(CodeRelation(owner_id, node_id, "DEFINES"))
+
+    def _set_visibility(self, owner_id: str, method_name: str, visibility: str) -> None:
+        """Apply ``private :name`` to the instance methods of that name the type defined so far."""
+        for relation in self.relations:
+            target = self.nodes.get(relation.target_id)
+            if relation.source_id == owner_id and relation.relation_type == "DEFINES" and target is not None \
+                    and target.node_type == "Method" and target.name == method_name \
+                    and target.properties.get("method_kind") == "instance":
+                target.properties["visibility"] = visibility
+
+    def _queue_association(self, call: SgNode, association: str, arguments: List[SgNode], model_id: str) -> None:
+        """Queue ``has_many :comments`` and the like, to be linked to the model once every file is parsed."""
+        name = self._literal(arguments[0]) if arguments and arguments[0].kind() == "simple_symbol" else None
+        if not name:
+            return
+        options = self._options(arguments[1:])
+        class_name = options.get("class_name")
+        if not isinstance(class_name, str) or not class_name:
+            class_name = camelize(singularize(name) if association in PLURAL_ASSOCIATIONS else name)
+        model_name = class_name.split("::")[-1]
+
+        entry: Dict[str, Any] = {
+            "type": "AR_ASSOCIATION",
+            "source_id": model_id,
+            "association": association,
+            "name": name,
+            "imported_name": model_name,
+            "original_name": class_name,
+            "module_hints": [underscore(model_name)],
+            "line_no": call.range().start.line + 1,
+            "raw_name": class_name,
+        }
+        for option in AR_OPTIONS:
+            if option in options:
+                entry[option] = options[option]
+        self.pending_imports.append(entry)
+
+    def _options(self, arguments: List[SgNode]) -> Dict[str, Any]:
+        """
+        Literal values of the ``key: value`` options of a call, bare or in
+        braces; ``true`` and ``false`` become booleans and other expressions
+        are left out.
+        """
+        pairs = []
+        for argument in arguments:
+            if argument.kind() == "pair":
+                pairs.append(argument)
+            elif argument.kind() == "hash":
+                pairs.extend(c for c in argument.children() if c.kind() == "pair")
+
+        options: Dict[str, Any] = {}
+        for pair in pairs:
+            key, value = pair.field("key"), pair.field("value")
+            key_text = self._literal(key) if key is not None else None
+            if not key_text or value is None:
+                continue
+            if value.kind() in ("true", "false"):
+                options[key_text] = value.kind() == "true"
+            elif self._literal(value) is not None:
+                options[key_text] = self._literal(value)
+        return options
+
+    @staticmethod
+    def _parameters(parameters: Optional[SgNode]) -> List[Dict[str, str]]:
+        """``{"name", "kind"}`` of each named parameter of a method, ``kind`` one of PARAMETER_KINDS' values."""
+        entries: List[Dict[str, str]] = []
+        if parameters is None:
+            return entries
+        for parameter in parameters.children():
+            kind = PARAMETER_KINDS.get(parameter.kind())
+            if kind is None:
+                continue
+            name_node = parameter if parameter.kind() == "identifier" else parameter.field("name")
+            if name_node is not None:
+                entries.append({"name": name_node.text(), "kind": kind})
+        return entries
+
+    def _statements(self, container: SgNode) -> List[SgNode]:
+        """Statements directly inside the program, or the body of a class, module or ``class << self``."""
+        if container.kind() == "program":
+            return [c for c in container.children() if c.is_named() and c.kind() != "comment"]
+        body = container.field("body")
+        if body is None:
+            body = next((c for c in container.children() if c.kind() == "body_statement"), None)
+        if body is not None:
+            return [c for c in body.children() if c.is_named() and c.kind() != "comment"]
+        # Grammars without a body node put the statements next to the name
+        header = [container.field(name) for name in ("name", "superclass", "value")]
+        return [c for c in container.children() if c.is_named() and c.kind() != "comment"
+                and not any(self._same_node(h, c) for h in header)]
+
+    @staticmethod
+    def _arguments(call: SgNode) -> List[SgNode]:
+        """Arguments of a call, with or without parentheses."""
+        arguments = call.field("arguments")
+        return [c for c in arguments.children() if c.is_named()] if arguments is not None else []
+
+    @staticmethod
+    def _literal(node: SgNode) -> Optional[str]:
+        """
+        Value of a plain string or symbol literal, or of a hash key;
+        None for interpolated strings and any other expression.
+        """
+        kind = node.kind()
+        if kind == "string":
+            if any(c.kind() == "interpolation" for c in node.children()):
+                return None
+            return "".join(c.text() for c in node.children() if c.kind() == "string_content")
+        if kind == "simple_symbol":
+            return node.text()[1:]
+        if kind == "hash_key_symbol":
+            return node.text()
+        return None
+
+    @staticmethod
+    def _constant_name(node: SgNode) -> str:
+        """Last segment of a constant path: ``Admin::User`` -> ``User``."""
+        if node.kind() == "scope_resolution":
+            name = node.field("name")
+            if name is not None:
+                return name.text()
+        return node.text().split("::")[-1]
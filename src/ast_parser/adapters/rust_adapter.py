@@ -1,12 +1,149 @@
 """Rust language adapter using ast-grep for AST parsing."""
 
+import ast
+import itertools
 import os
+import re
 from typing import Dict, List, Tuple, Optional
 
 from ast_grep_py import SgRoot, SgNode
 
 from .base_adapter import LanguageAdapter
-from ast_parser.parser import CodeNode, CodeRelation
+from ast_parser.complexity import sg_complexity
+from ast_parser.parser import CodeNode, CodeRelation, clean_doc_comment, set_doc
+from ast_parser.positions import LineIndex, SourceSpan
+from ast_parser.workspace import find_workspace_member
+
+
+# Optional visibility, the use keyword, then the use tree up to the semicolon
+USE_DECLARATION = re.compile(r"^(?:pub(?:\([^)]*\))?\s+)?use\s+(.*?);?$", re.DOTALL)
+
+# #[doc = "..."] with a plain or raw string
+DOC_ATTRIBUTE = re.compile(r'^#\[\s*doc\s*=\s*(r#*".*"#*|".*")\s*\]$', re.DOTALL)
+
+# type_kind of a struct or enum variant by the kind of its body; no body is a unit
+STRUCT_KINDS = {"field_declaration_list": "struct", "ordered_field_declaration_list": "tuple_struct"}
+VARIANT_KINDS = {"field_declaration_list": "struct", "ordered_field_declaration_list": "tuple"}
+
+# Node types a field's type may link to with USES_TYPE
+FIELD_TYPE_TARGETS = ("Class", "Enum", "Interface")
+
+# Node types an identifier in a macro's arguments may refer to
+MACRO_ARGUMENT_TARGETS = ("Function", "Class", "Enum", "Interface")
+
+# The trait list of a derive attribute, kept without #[ ] by _set_doc
+DERIVE_ATTRIBUTE = re.compile(r"^derive\s*\((.*)\)$", re.DOTALL)
+
+# The macro name of a proc-macro crate's derive function, #[proc_macro_derive(Name, attributes(..))]
+PROC_MACRO_DERIVE = re.compile(r"^proc_macro_derive\s*\(\s*([A-Za-z_][A-Za-z0-9_]*)")
+
+# Token kinds that may make up a path inside a macro's token tree
+PATH_TOKEN_KINDS = ("identifier", "self", "super", "crate")
+
+# Node types that are items, dropped when private unless index_private is set
+PRIVATE_ITEM_TYPES = ("Function", "Method", "Class", "Enum", "Interface", "Macro")
+
+
+def expand_use_tree(tree: str, prefix: Optional[List[str]] = None) -> List[Tuple[List[str], Optional[str], bool]]:
+    """
+    Flatten a use tree into one (path segments, alias, glob) entry per import.
+    
+    ``crate::a::{self, B as C, d::*}`` gives ``(["crate", "a"], None, False)``,
+    ``(["crate", "a", "B"], "C", False)`` and ``(["crate", "a", "d"], None, True)``.
+    """
+    prefix = prefix or []
+    tree = " ".join(tree.split())
+    if not tree:
+        return []
+    if tree.endswith("}") and "{" in tree:
+        brace = tree.index("{")
+        base = prefix + _path_segments(tree[:brace])
+        entries = []
+        for part in _split_top_level(tree[brace + 1:-1]):
+            entries.extend(expand_use_tree(part, base))
+        return entries
+    if tree.endswith("*"):
+        return [(prefix + _path_segments(tree[:-1]), None, True)]
+
+    path, _, alias = tree.partition(" as ")
+    segments = prefix + _path_segments(path)
+    if segments and segments[-1] == "self":
+        segments = segments[:-1]
+    return [(segments, alias.strip() or None, False)] if segments else []
+
+
+def _path_segments(path: str) -> List[str]:
+    return [segment.strip() for segment in path.split("::") if segment.strip()]
+
+
+def _split_top_level(text: str) -> List[str]:
+    """Split on commas that are not inside braces."""
+    parts, depth, start = [], 0, 0
+    for i, c in enumerate(text):
+        if c == "{":
+            depth += 1
+        elif c == "}":
+            depth -= 1
+        elif c == "," and depth == 0:
+            parts.append(text[start:i])
+            start = i + 1
+    parts.append(text[start:])
+    return [part for part in parts if part.strip()]
+
+
+def crate_root(file_path: str) -> str:
+    """
+    Directory the crate's module tree starts from.
+    
+    The nearest directory holding ``lib.rs`` or ``main.rs``, or the ``src``
+    directory next to a ``Cargo.toml``. The search climbs only while it stays
+    inside Rust sources; a file with no crate root around it is treated as a
+    module of its own directory, so sibling files are ``crate::<name>``.
+    """
+    start = os.path.dirname(os.path.abspath(file_path))
+    directory = start
+    while True:
+        if os.path.isfile(os.path.join(directory, "lib.rs")) or os.path.isfile(os.path.join(directory, "main.rs")):
+            return directory
+        if os.path.isfile(os.path.join(directory, "Cargo.toml")):
+            src = os.path.join(directory, "src")
+            return src if os.path.isdir(src) else directory
+        parent = os.path.dirname(directory)
+        if parent == directory:
+            return start
+        try:
+            has_sources = any(name.endswith(".rs") for name in os.listdir(directory))
+        except OSError:
+            has_sources = False
+        if not has_sources and not os.path.isfile(os.path.join(parent, os.path.basename(directory) + ".rs")):
+            return start
+        directory = parent
+
+
+def crate_module_path(file_path: str) -> str:
+    """
+    Module path of a file under the ``mod.rs``/``foo.rs`` conventions.
+    
+    ``src/lib.rs`` -> ``crate``, ``src/person.rs`` -> ``crate::person``,
+    ``src/geometry/mod.rs`` and ``src/geometry.rs`` -> ``crate::geometry``.
+    """
+    relative = os.path.relpath(os.path.abspath(file_path), crate_root(file_path))
+    parts = os.path.splitext(relative)[0].split(os.sep)
+    if parts[-1] == "mod" or (len(parts) == 1 and parts[0] in ("lib", "main")):
+        parts = parts[:-1]
+    return "::".join(["crate"] + parts)
+
+
+def workspace_module_path(file_path: str, module_path: str) -> str:
+    """
+    A crate module path with ``crate`` replaced by the crate's name when the
+    file belongs to a Cargo workspace member: ``crate::person`` of package
+    ``core-lib`` -> ``core_lib::person``. Unchanged outside any Cargo package.
+    """
+    member = find_workspace_member(file_path)
+    if member is None or member.manifest != "Cargo.toml":
+        return module_path
+    return "::".join([member.name.replace("-", "_")] + module_path.split("::")[1:])
 
 
 class RustAdapter(LanguageAdapter):
@@ -14,37 +151,112 @@ class RustAdapter(LanguageAdapter):
     Rust adapter using ast-grep library.
     
     Extracts minimal Rust structures for proof of concept:
-    - File, Struct, Function, Method nodes
-    - CONTAINS, DEFINES relations
-    - Use declaration tracking (imports)
+    - File, Struct, Enum, Variant, Field, Trait, Function, Method, Macro nodes
+    - CONTAINS, DEFINES, IMPLEMENTS, EXTENDS, REFERENCES, CALLS, USES_TYPE, DERIVE_USE relations
+    - IMPORTS relations for use declarations
+    
+    Structs are Class nodes with a ``type_kind`` of struct, tuple_struct or
+    unit_struct; enums are Enum nodes defining one Variant node per variant.
+    Named fields, tuple fields (named ``0``, ``1``, ...) and the fields of
+    enum variants become Field nodes.
+    A struct or enum has a USES_TYPE edge, listing the ``fields``, to each
+    indexed type its fields name, generic arguments included
+    (``Vec<Person>`` -> Person); std and foreign types get none.
+    
+    Every file is placed in the crate's module tree by the ``mod.rs`` /
+    ``foo.rs`` conventions (``crate::geometry::shapes``), inline ``mod``
+    blocks included. Each imported item of a use declaration, grouped,
+    renamed (``as``) or glob, is queued with the absolute module paths it
+    may refer to and resolved in the second pass to the module's File node
+    or the imported definition. Globs link to the module. Paths that leave
+    the indexed crate (``std::fmt``) are kept for CodeGraph, which points
+    them at an ``Unresolved`` placeholder named after the path.
+    
+    Methods declared in an impl block carry a ``method_of`` property naming
+    the implementing type. When the type (or trait) lives in another file the
+    link is queued in pending_imports and resolved in the second pass, so
+    every impl block for a type attaches to the same struct node. A blanket
+    impl (``impl<T: Display> Summary for T``) has no type node; its
+    IMPLEMENTS edge leaves the file instead and carries ``blanket``,
+    ``for_type`` and ``bounds`` properties.
+    
+    Structs, enums and traits with type parameters carry their bounds,
+    inline and from the where clause, as ``generic_bounds`` (parameter ->
+    traits). A trait lists its supertraits (``trait Shape: Named``) as
+    ``supertraits`` and has an EXTENDS edge to each one indexed, linked in
+    the second pass when it lives in another file.
+    
+    Items carry their end line and, as ``column`` / ``end_column``
+    properties, the character columns their span starts and ends at. Their
+    ``span`` converts those columns to UTF-8 or UTF-16 units on request,
+    from the parsed source.
+    
+    ``self.field`` accesses inside impl methods become REFERENCES edges from
+    the method to the Field node, tagged with an ``access`` of Read, Write,
+    ReadWrite (compound assignment) based on the syntactic context. A method
+    called on the field reads or writes it as the method's ``self``
+    parameter says when the method is defined in this file, and is Unknown
+    otherwise.
+    
+    Functions and methods carry ``is_async``. Calls are attributed to the
+    innermost enclosing function or method (closures count as part of their
+    function); a call awaited on the spot (``fetch(url).await``) is marked
+    ``await_call``. Plain calls, ``module::f()``
+    and ``Type::f()`` paths, and method calls are linked. A call to a name
+    imported by ``use`` (renamed or through a glob) or to a module path is
+    queued with the absolute paths it may refer to, from the file's table
+    of use symbols, and linked to the definition in the second pass. A method call's
+    receiver type comes from ``self``, a typed parameter or a let binding
+    initialized with ``Type::f(..)`` or ``Type { .. }``. Calls whose target
+    is not indexed (std, other crates, receivers of unknown type) carry
+    their textual path as ``raw_name`` so CodeGraph can point them at an
+    ``Unresolved`` node.
+    
+    ``#[derive(..)]`` gives the struct or enum an IMPLEMENTS edge, marked
+    ``derived``, to each trait listed, and a DERIVE_USE edge to each derive
+    macro: the ``#[proc_macro_derive]`` function when it is indexed, an
+    ``Unresolved`` placeholder otherwise. ``macro_rules!`` definitions are
+    Macro nodes and every invocation is a CALLS edge to its macro, or to a
+    placeholder for std and proc macros (``println!``). Paths in
+    a macro's arguments (``println!("{}", area(c))``) are matched to
+    functions and types by name and linked with low-confidence REFERENCES.
+    
+    With ``index_private=False`` items without ``pub`` are left out, along
+    with the methods, fields and variants of a private type, so the graph
+    holds only what other modules can use; calls to a dropped function
+    become unresolved.
     
     Supports Rust source files (.rs).
     """
     
-    def __init__(self):
+    def __init__(self, index_private: bool = True):
         super().__init__("rust")
+        self.index_private = index_private
         self.current_file: str = ""
         self.current_struct: Optional[str] = None
     
-    def parse_file(self, file_path: str, build_index: bool = False) -> Tuple[Dict[str, CodeNode], List[CodeRelation]]:
+    def parse_source(self, source: str, file_path: str, build_index: bool = False) -> Tuple[Dict[str, CodeNode], List[CodeRelation]]:
         """
-        Parse a Rust file using ast-grep.
-        
+        Parse Rust source with ast-grep.
+
         Extracts structs, functions, impl blocks, and use declarations.
         """
         self.current_file = file_path
         self.current_struct = None
+        # Types named by each struct's or enum's fields: owner ID -> (type name, path) -> field names
+        self._field_types: Dict[str, Dict[Tuple[str, str], List[str]]] = {}
+        # Fields of this file by owner: field_of -> field name -> Field node ID
+        self._fields: Dict[str, Dict[str, str]] = {}
+        # Whether each method of this file takes &mut self: (type name, method name) -> bool
+        self._mut_receivers: Dict[Tuple[str, str], bool] = {}
         
         try:
-            # Read source code
-            with open(file_path, "r", encoding="utf-8") as f:
-                source = f.read()
-            
             # Parse with ast-grep (Rust language)
             root = SgRoot(source, "rust").root()
+            self._record_syntax_errors(root)
             
             # Create file node
-            file_node_id = self._create_file_node(file_path)
+            file_node_id = self._create_file_node(file_path, source)
             
             # Generate module name for indexing
             module_name = os.path.splitext(os.path.basename(file_path))[0]
@@ -53,42 +265,147 @@ class RustAdapter(LanguageAdapter):
                     self.module_definitions[module_name] = {}
                 self.module_to_file[module_name] = file_node_id
             
+            # Position in the crate's module tree; modules are indexed under the
+            # crate's name so that two crates of a workspace do not collide
+            module_path = crate_module_path(file_path)
+            crate_module = workspace_module_path(file_path, module_path)
+            self.nodes[file_node_id].properties["module_path"] = module_path
+            if crate_module != module_path:
+                self.nodes[file_node_id].properties["qualified_module"] = crate_module
+            
+            first_new_node = len(self.nodes)
+            
             # Extract Rust structures
-            self._parse_use_declarations(root, file_node_id)
+            self._parse_use_declarations(root, file_node_id, crate_module)
             self._parse_structs(root, file_node_id, build_index, module_name)
-            self._parse_functions(root, file_node_id)
-            self._parse_impl_blocks(root, build_index, module_name)
+            self._parse_enums(root, file_node_id, build_index, module_name)
+            self._parse_traits(root, file_node_id, build_index, module_name)
+            self._parse_functions(root, file_node_id, build_index, module_name)
+            self._parse_macros(root, file_node_id, build_index, module_name)
+            self._mut_receivers = self._method_receivers(root)
+            self._parse_impl_blocks(root, file_node_id)
+            self._parse_derives(file_node_id, self._use_path_segments(root))
+            self._link_field_types(file_node_id, self._use_path_segments(root))
+            self._parse_calls(root, crate_module)
+            self._parse_macro_invocations(root, file_node_id, crate_module)
+            self._attach_spans(LineIndex(source), first_new_node)
+            
+            if build_index:
+                self._index_crate_modules(root, file_node_id, module_name, crate_module)
+            if not self.index_private:
+                self._drop_private_items(first_new_node)
             
             return self.nodes, self.relations
             
         except Exception as e:
             print(f"Error parsing Rust file {file_path}: {e}")
+            self._record_failure(f"Parser failed: {e}")
             return {}, []
     
-    def _parse_use_declarations(self, root: SgNode, file_node_id: str) -> None:
-        """Extract use declarations (imports)."""
-        # Find all use_declaration nodes
+    def _drop_private_items(self, first_new_node: int) -> None:
+        """Remove this file's private items, what they define, and every edge or pending entry naming them."""
+        dropped = {node_id for node_id in list(self.nodes)[first_new_node:]
+                   if self.nodes[node_id].node_type in PRIVATE_ITEM_TYPES
+                   and self.nodes[node_id].properties.get("visibility") == "Private"}
+        # Methods, fields and variants go with the type defining them, whatever their own visibility
+        defines = [r for r in self.relations if r.relation_type == "DEFINES"]
+        added = dropped
+        while added:
+            added = {r.target_id for r in defines if r.source_id in added and r.target_id not in dropped}
+            dropped |= added
+        if not dropped:
+            return
+        
+        for node_id in dropped:
+            del self.nodes[node_id]
+        self.relations = [r for r in self.relations if r.source_id not in dropped and r.target_id not in dropped]
+        self.pending_imports = [entry for entry in self.pending_imports
+                                if not any(key.endswith("_id") and value in dropped for key, value in entry.items())]
+        for definitions in self.module_definitions.values():
+            for name in [name for name, node_id in definitions.items() if node_id in dropped]:
+                del definitions[name]
+    
+    def _parse_use_declarations(self, root: SgNode, file_node_id: str, crate_module: str) -> None:
+        """Queue one import per item of each use declaration, at any nesting level."""
         for use_node in root.find_all(kind="use_declaration"):
-            # Get the use path
-            use_text = use_node.text().strip()
-            if use_text.startswith("use "):
-                use_text = use_text[4:].strip()
-                if use_text.endswith(";"):
-                    use_text = use_text[:-1].strip()
-                
-                # Extract root module
-                parts = use_text.split("::")
-                if len(parts) > 0:
-                    root_module = parts[0]
-                    self.pending_imports.append({
-                        "type": "IMPORTS_MODULE",
-                        "source_id": file_node_id,
-                        "imported_module": root_module,
-                        "full_module_path": use_text,
-                    })
+            match = USE_DECLARATION.match(use_node.text().strip())
+            if not match:
+                continue
+            line_no = use_node.range().start.line + 1
+            for segments, alias, glob in expand_use_tree(match.group(1)):
+                candidates = self._use_candidates(segments, crate_module)
+                modules = sorted({"::".join(c.split("::")[:end])
+                                  for c in candidates for end in range(1, len(c.split("::")) + 1)})
+                self.pending_imports.append({
+                    "type": "IMPORTS_RUST",
+                    "source_id": file_node_id,
+                    "full_module_path": "::".join(segments),
+                    "candidates": candidates,
+                    "modules": modules,
+                    "alias": alias,
+                    "glob": glob,
+                    "line_no": line_no,
+                })
+    
+    @staticmethod
+    def _use_candidates(segments: List[str], crate_module: str) -> List[str]:
+        """
+        Absolute module paths a use path may refer to, most likely first.
+        
+        ``crate::``, ``self::`` and ``super::`` are anchored; a bare path is
+        tried as a child of the current module, then from the crate root
+        (2015-style paths), and is an external crate when neither exists.
+        In a workspace, where the crate root is the crate's name rather than
+        ``crate``, a bare path may also start with another member's name.
+        """
+        current = crate_module.split("::")
+        root = current[0]
+        head = segments[0] if segments else ""
+        if head == "crate":
+            return ["::".join([root] + segments[1:])]
+        if head in ("self", "super"):
+            base = list(current)
+            rest = list(segments)
+            if rest[0] == "self":
+                rest = rest[1:]
+            while rest and rest[0] == "super":
+                base = base[:-1] if len(base) > 1 else base
+                rest = rest[1:]
+            return ["::".join(base + rest)]
+        candidates = ["::".join(current + segments)]
+        if len(current) > 1:
+            candidates.append("::".join([root] + segments))
+        if root != "crate" and len(segments) > 1:
+            candidates.append("::".join(segments))
+        return candidates
+    
+    def _index_crate_modules(self, root: SgNode, file_node_id: str, module_name: str, crate_module: str) -> None:
+        """
+        Index this file, and each inline ``mod`` block in it, by crate module path.
+        
+        The definitions are those of the file, so an item of an inline module
+        resolves by name within the file.
+        """
+        definitions = {name: node_id for name, node_id in self.module_definitions.get(module_name, {}).items()
+                       if node_id in self.nodes and self.nodes[node_id].file_path == self.current_file}
+        paths = [crate_module]
+        for mod_node in root.find_all(kind="mod_item"):
+            if not mod_node.field("body"):
+                # `mod foo;` lives in foo.rs or foo/mod.rs, indexed when that file is parsed
+                continue
+            names = []
+            ancestor = mod_node
+            while ancestor is not None:
+                if ancestor.kind() == "mod_item" and ancestor.field("name"):
+                    names.insert(0, ancestor.field("name").text())
+                ancestor = ancestor.parent()
+            paths.append("::".join([crate_module] + names))
+        for path in paths:
+            self.module_definitions[path] = dict(definitions)
+            self.module_to_file[path] = file_node_id
     
     def _parse_structs(self, root: SgNode, file_node_id: str, build_index: bool, module_name: str) -> None:
-        """Extract struct declarations."""
+        """Extract struct declarations: named-field, tuple and unit structs."""
         # Find all struct_item nodes
         for struct_node in root.find_all(kind="struct_item"):
             # Get struct name
@@ -97,26 +414,223 @@ class RustAdapter(LanguageAdapter):
                 continue
             
             struct_name = name_field.text()
-            line_no = struct_node.range().start.line + 1
-            
-            # Create struct node (using "Class" type for consistency)
-            struct_node_id = self._get_node_id("Class", struct_name, self.current_file, line_no)
-            self.nodes[struct_node_id] = CodeNode(
-                node_id=struct_node_id,
-                node_type="Class",
-                name=struct_name,
+            body = struct_node.field("body")
+            type_kind = STRUCT_KINDS.get(body.kind() if body else None, "unit_struct")
+            struct_node_id = self._create_type_node(struct_node, "Class", struct_name, file_node_id,
+                                                    build_index, module_name)
+            self.nodes[struct_node_id].properties["type_kind"] = type_kind
+            
+            fields = self._parse_fields(body, struct_name, struct_node_id)
+            # Fields are indexed by qualified name so impls in other files can find them
+            if build_index and type_kind == "struct":
+                for field_name, field_node_id, _ in fields:
+                    self.module_definitions[module_name][f"{struct_name}.{field_name}"] = field_node_id
+            self._record_field_types(struct_node_id, struct_node, fields)
+    
+    def _parse_enums(self, root: SgNode, file_node_id: str, build_index: bool, module_name: str) -> None:
+        """Extract enum declarations, their variants and the fields of tuple and struct variants."""
+        for enum_node in root.find_all(kind="enum_item"):
+            name_field = enum_node.field("name")
+            if not name_field:
+                continue
+            
+            enum_name = name_field.text()
+            enum_node_id = self._create_type_node(enum_node, "Enum", enum_name, file_node_id,
+                                                  build_index, module_name)
+            # Variants and their fields are as visible as the enum
+            visibility = self.nodes[enum_node_id].properties["visibility"]
+            
+            fields: List[Tuple[str, str, SgNode]] = []
+            body = enum_node.field("body")
+            for variant in body.children() if body else []:
+                if variant.kind() != "enum_variant" or not variant.field("name"):
+                    continue
+                variant_name = variant.field("name").text()
+                variant_body = variant.field("body")
+                line_no = variant.range().start.line + 1
+                variant_node_id = self._get_node_id("Variant", variant_name, self.current_file, line_no)
+                properties = {
+                    "variant_of": enum_name,
+                    "type_kind": VARIANT_KINDS.get(variant_body.kind() if variant_body else None, "unit"),
+                    "visibility": visibility,
+                    **self._columns(variant),
+                }
+                value = variant.field("value")
+                if value:
+                    properties["discriminant"] = value.text()
+                self.nodes[variant_node_id] = CodeNode(
+                    node_id=variant_node_id,
+                    node_type="Variant",
+                    name=variant_name,
+                    file_path=self.current_file,
+                    line_no=line_no,
+                    end_line_no=variant.range().end.line + 1,
+                    properties=properties,
+                )
+                self._set_doc(variant_node_id, variant)
+                self._add_relation(CodeRelation(enum_node_id, variant_node_id, "DEFINES"))
+                
+                variant_fields = self._parse_fields(variant_body, f"{enum_name}::{variant_name}", variant_node_id,
+                                                    visibility)
+                fields.extend((f"{variant_name}.{name}", node_id, type_node)
+                              for name, node_id, type_node in variant_fields)
+            self._record_field_types(enum_node_id, enum_node, fields)
+    
+    def _create_type_node(self, item: SgNode, node_type: str, name: str, file_node_id: str,
+                          build_index: bool, module_name: str) -> str:
+        """Create the Class node of a struct or the Enum node of an enum, contained by the file."""
+        line_no = item.range().start.line + 1
+        node_id = self._get_node_id(node_type, name, self.current_file, line_no)
+        self.nodes[node_id] = CodeNode(
+            node_id=node_id,
+            node_type=node_type,
+            name=name,
+            file_path=self.current_file,
+            line_no=line_no,
+            end_line_no=item.range().end.line + 1,
+            properties={"visibility": self._visibility(item), **self._columns(item)},
+        )
+        bounds = self._generic_bounds(item)
+        if bounds:
+            self.nodes[node_id].properties["generic_bounds"] = bounds
+        self._set_doc(node_id, item)
+        
+        # Add CONTAINS relation from file to the type
+        self._add_relation(CodeRelation(file_node_id, node_id, "CONTAINS"))
+        
+        # Index the type for cross-file resolution
+        if build_index:
+            self.module_definitions[module_name][name] = node_id
+        return node_id
+    
+    def _parse_fields(self, body: Optional[SgNode], owner_name: str, definer_id: str,
+                      visibility: Optional[str] = None) -> List[Tuple[str, str, Optional[SgNode]]]:
+        """
+        Extract the fields of a struct or enum variant body as Field nodes defined by definer_id.
+        
+        Named fields (``{ name: String }``) keep their name; tuple fields
+        (``(pub u32, Person)``) are named by position, ``0``, ``1``, ...
+        A unit body has no fields.
+        
+        Args:
+            body: field_declaration_list, ordered_field_declaration_list or None
+            owner_name: Recorded as field_of, e.g. ``Person`` or ``Shape::Circle``
+            definer_id: Node the DEFINES edges start from
+            visibility: Visibility of every field; None reads each field's own modifier
+        
+        Returns:
+            (name, node id, type node) per field, in declaration order
+        """
+        # (name, first node, type node, visibility modifier) per field
+        declarations: List[Tuple[str, SgNode, Optional[SgNode], Optional[SgNode]]] = []
+        if body is not None and body.kind() == "field_declaration_list":
+            for field_decl in body.children():
+                if field_decl.kind() == "field_declaration" and field_decl.field("name"):
+                    modifier = next((c for c in field_decl.children() if c.kind() == "visibility_modifier"), None)
+                    declarations.append((field_decl.field("name").text(), field_decl, field_decl.field("type"),
+                                         modifier))
+        elif body is not None and body.kind() == "ordered_field_declaration_list":
+            # Tuple fields have no node of their own: each type may follow a visibility modifier
+            modifier = None
+            for child in body.children():
+                if not child.is_named() or child.kind() in ("attribute_item", "line_comment", "block_comment"):
+                    continue
+                if child.kind() == "visibility_modifier":
+                    modifier = child
+                    continue
+                declarations.append((str(len(declarations)), modifier or child, child, modifier))
+                modifier = None
+        
+        fields = []
+        for field_name, first, type_node, modifier in declarations:
+            line_no = first.range().start.line + 1
+            # Fields of different variants may share a name and line; the owner keeps their IDs apart
+            id_name = field_name if owner_name.isidentifier() else f"{owner_name}.{field_name}"
+            field_node_id = self._get_node_id("Field", id_name, self.current_file, line_no)
+            last = type_node if first.kind() == "visibility_modifier" and type_node else first
+            self.nodes[field_node_id] = CodeNode(
+                node_id=field_node_id,
+                node_type="Field",
+                name=field_name,
                 file_path=self.current_file,
                 line_no=line_no,
+                end_line_no=last.range().end.line + 1,
+                properties={
+                    "field_of": owner_name,
+                    "type": type_node.text() if type_node else None,
+                    "visibility": visibility or self._modifier_visibility(modifier),
+                    "column": first.range().start.column + 1,
+                    "end_column": last.range().end.column + 1,
+                },
             )
-            
-            # Add CONTAINS relation from file to struct
-            self._add_relation(CodeRelation(file_node_id, struct_node_id, "CONTAINS"))
-            
-            # Index the struct for cross-file resolution
-            if build_index:
-                self.module_definitions[module_name][struct_name] = struct_node_id
+            self._set_doc(field_node_id, first)
+            self._fields.setdefault(owner_name, {}).setdefault(field_name, field_node_id)
+            self._add_relation(CodeRelation(definer_id, field_node_id, "DEFINES"))
+            fields.append((field_name, field_node_id, type_node))
+        return fields
+    
+    def _record_field_types(self, owner_id: str, item: SgNode, fields: List[Tuple[str, str, Optional[SgNode]]]) -> None:
+        """Remember the types named in a struct's or enum's fields, linked once the whole file is parsed."""
+        type_parameters = set(self._type_parameter_names(item))
+        uses = self._field_types.setdefault(owner_id, {})
+        for field_name, _, type_node in fields:
+            for type_name, path in self._named_types(type_node) if type_node else []:
+                if type_name in type_parameters or type_name == "Self":
+                    continue
+                use = uses.setdefault((type_name, path), [])
+                if field_name not in use:
+                    use.append(field_name)
+    
+    @staticmethod
+    def _named_types(type_node: SgNode) -> List[Tuple[str, str]]:
+        """
+        Every type a field type names, with the path it was written with.
+        
+        Generic arguments count as well as the base type, so ``Vec<Person>``
+        names ``Vec`` and ``Person``; ``geometry::Square`` names ``Square``
+        with the path ``geometry``. Primitive types are left out.
+        """
+        identifiers = [type_node] if type_node.kind() == "type_identifier" else []
+        identifiers += type_node.find_all(kind="type_identifier")
+        named: List[Tuple[str, str]] = []
+        for identifier in identifiers:
+            path = ""
+            parent = identifier.parent()
+            if parent is not None and parent.kind() == "scoped_type_identifier" and parent.field("path"):
+                path = parent.field("path").text()
+            if (identifier.text(), path) not in named:
+                named.append((identifier.text(), path))
+        return named
     
-    def _parse_functions(self, root: SgNode, file_node_id: str) -> None:
+    def _link_field_types(self, file_node_id: str, module_hints: List[str]) -> None:
+        """
+        Emit USES_TYPE edges from structs and enums to the types their fields name.
+        
+        Types declared in this file are linked directly; the rest are left
+        to the second pass, which links them only if the index defines
+        them, so ``String`` or ``Vec`` get no edge.
+        """
+        for owner_id, uses in self._field_types.items():
+            for (type_name, path), field_names in uses.items():
+                target_id = None if path else next(
+                    filter(None, (self._find_local_node(node_type, type_name) for node_type in FIELD_TYPE_TARGETS)),
+                    None)
+                if target_id:
+                    self._add_relation(CodeRelation(owner_id, target_id, "USES_TYPE",
+                                                    properties={"fields": field_names}))
+                    continue
+                path_hints = [segment for segment in _path_segments(path)
+                              if segment not in ("crate", "self", "super")]
+                self.pending_imports.append({
+                    "type": "USES_TYPE",
+                    "source_id": file_node_id,
+                    "owner_id": owner_id,
+                    "type_name": type_name,
+                    "fields": field_names,
+                    "module_hints": path_hints[-1:] + module_hints,
+                })
+    
+    def _parse_functions(self, root: SgNode, file_node_id: str, build_index: bool, module_name: str) -> None:
         """Extract top-level function declarations."""
         # Find all function_item nodes at top level (not in impl blocks)
         for func_node in root.children():
@@ -137,55 +651,1025 @@ class RustAdapter(LanguageAdapter):
                     name=func_name,
                     file_path=self.current_file,
                     line_no=line_no,
+                    end_line_no=func_node.range().end.line + 1,
+                    properties={
+                        "visibility": self._visibility(func_node),
+                        "complexity": sg_complexity(func_node, "rust"),
+                        "is_async": self._is_async(func_node),
+                        **self._columns(func_node),
+                    },
                 )
+                self._set_doc(func_node_id, func_node)
                 
                 # Add CONTAINS relation from file to function
                 self._add_relation(CodeRelation(file_node_id, func_node_id, "CONTAINS"))
+                
+                # Index the function so module::func() calls in other files resolve
+                if build_index:
+                    self.module_definitions[module_name][func_name] = func_node_id
     
-    def _parse_impl_blocks(self, root: SgNode, build_index: bool, module_name: str) -> None:
-        """Extract impl blocks and their methods."""
-        # Find all impl_item nodes
+    def _parse_macros(self, root: SgNode, file_node_id: str, build_index: bool, module_name: str) -> None:
+        """
+        Extract ``macro_rules!`` definitions as Macro nodes.
+        
+        A macro is Public when marked ``#[macro_export]`` and Private
+        otherwise. It is indexed as ``name!``: macros live in a namespace of
+        their own, so a function of the same name keeps its entry. The
+        functions of a proc-macro crate marked ``#[proc_macro_derive(Name)]``
+        are derive macros: they get a ``derive_macro`` property and are
+        indexed as ``Name!`` too, for the DERIVE_USE edges of other crates.
+        """
+        for macro_node in root.find_all(kind="macro_definition"):
+            name_field = macro_node.field("name")
+            if not name_field:
+                continue
+            
+            macro_name = name_field.text()
+            line_no = macro_node.range().start.line + 1
+            macro_node_id = self._get_node_id("Macro", macro_name, self.current_file, line_no)
+            self.nodes[macro_node_id] = CodeNode(
+                node_id=macro_node_id,
+                node_type="Macro",
+                name=macro_name,
+                file_path=self.current_file,
+                line_no=line_no,
+                end_line_no=macro_node.range().end.line + 1,
+                properties={
+                    "rules": sum(1 for child in macro_node.children() if child.kind() == "macro_rule"),
+                    **self._columns(macro_node),
+                },
+            )
+            self._set_doc(macro_node_id, macro_node)
+            properties = self.nodes[macro_node_id].properties
+            properties["visibility"] = "Public" if "macro_export" in properties.get("attributes", []) else "Private"
+            
+            self._add_relation(CodeRelation(file_node_id, macro_node_id, "CONTAINS"))
+            if build_index:
+                self.module_definitions[module_name][f"{macro_name}!"] = macro_node_id
+        
+        for func_node_id, node in list(self.nodes.items()):
+            if node.file_path != self.current_file or node.node_type != "Function":
+                continue
+            for attribute in node.properties.get("attributes", []):
+                match = PROC_MACRO_DERIVE.match(attribute)
+                if match:
+                    node.properties["derive_macro"] = match.group(1)
+                    if build_index:
+                        self.module_definitions[module_name][f"{match.group(1)}!"] = func_node_id
+    
+    def _parse_traits(self, root: SgNode, file_node_id: str, build_index: bool, module_name: str) -> None:
+        """Extract trait declarations, the methods they declare and their supertraits."""
+        supertraits: List[Tuple[str, str]] = []
+        for trait_node in root.find_all(kind="trait_item"):
+            name_field = trait_node.field("name")
+            if not name_field:
+                continue
+            
+            trait_name = name_field.text()
+            line_no = trait_node.range().start.line + 1
+            visibility = self._visibility(trait_node)
+            
+            # Traits are stored as Interface nodes
+            trait_node_id = self._get_node_id("Interface", trait_name, self.current_file, line_no)
+            self.nodes[trait_node_id] = CodeNode(
+                node_id=trait_node_id,
+                node_type="Interface",
+                name=trait_name,
+                file_path=self.current_file,
+                line_no=line_no,
+                end_line_no=trait_node.range().end.line + 1,
+                properties={"visibility": visibility, **self._columns(trait_node)},
+            )
+            bounds = self._generic_bounds(trait_node)
+            if bounds:
+                self.nodes[trait_node_id].properties["generic_bounds"] = bounds
+            # trait Shape: Named + fmt::Debug
+            paths = self._bound_texts(trait_node.field("bounds"))
+            if paths:
+                self.nodes[trait_node_id].properties["supertraits"] = paths
+                supertraits.extend((trait_node_id, path) for path in paths)
+            self._set_doc(trait_node_id, trait_node)
+            self._add_relation(CodeRelation(file_node_id, trait_node_id, "CONTAINS"))
+            
+            if build_index:
+                self.module_definitions[module_name][trait_name] = trait_node_id
+            
+            # Both required (signature only) and provided (default body) methods
+            body = trait_node.field("body")
+            if not body:
+                continue
+            for child in body.children():
+                if child.kind() not in ("function_item", "function_signature_item"):
+                    continue
+                name_field = child.field("name")
+                if not name_field:
+                    continue
+                
+                method_name = name_field.text()
+                method_line = child.range().start.line + 1
+                method_node_id = self._get_node_id("Method", method_name, self.current_file, method_line)
+                self.nodes[method_node_id] = CodeNode(
+                    node_id=method_node_id,
+                    node_type="Method",
+                    name=method_name,
+                    file_path=self.current_file,
+                    line_no=method_line,
+                    end_line_no=child.range().end.line + 1,
+                    properties={
+                        "method_of": trait_name,
+                        "has_default": child.kind() == "function_item",
+                        "is_async": self._is_async(child),
+                        # Trait methods take no modifier; they are as visible as the trait
+                        "visibility": visibility,
+                        **self._columns(child),
+                    },
+                )
+                if child.kind() == "function_item":
+                    self.nodes[method_node_id].properties["complexity"] = sg_complexity(child, "rust")
+                self._set_doc(method_node_id, child)
+                self._add_relation(CodeRelation(trait_node_id, method_node_id, "DEFINES"))
+        
+        # Linked once every trait of the file is known; the rest in the second pass
+        module_hints = self._use_path_segments(root) if supertraits else []
+        for trait_node_id, path in supertraits:
+            name = self._base_type_name(path)
+            supertrait_id = self._find_local_node("Interface", name)
+            if supertrait_id and "::" not in path:
+                self._add_relation(CodeRelation(trait_node_id, supertrait_id, "EXTENDS",
+                                                properties={"trait_path": path}))
+            else:
+                self.pending_imports.append({
+                    "type": "SUPERTRAIT",
+                    "source_id": file_node_id,
+                    "subtrait_id": trait_node_id,
+                    "trait_name": name,
+                    "trait_path": path,
+                    "module_hints": module_hints,
+                })
+    
+    def _parse_impl_blocks(self, root: SgNode, file_node_id: str) -> None:
+        """
+        Extract impl blocks and their methods.
+        
+        Handles both inherent impls (``impl Person``) and trait impls
+        (``impl Display for Person``). Methods are always emitted; the
+        DEFINES edge from the type and the IMPLEMENTS edge to the trait are
+        created directly when both ends are in this file and deferred to the
+        second pass otherwise. In a trait impl each method also gets an
+        IMPLEMENTS edge to the trait method it provides.
+        """
+        module_hints = self._use_path_segments(root)
+        source_lines = root.text().splitlines()
+        
         for impl_node in root.find_all(kind="impl_item"):
             # Get the type this impl is for
             type_field = impl_node.field("type")
             if not type_field:
                 continue
             
-            type_name = type_field.text()
+            type_name = self._base_type_name(type_field.text())
+            trait_field = impl_node.field("trait")
+            trait_path = trait_field.text() if trait_field else None
+            trait_name = self._base_type_name(trait_path) if trait_path else None
             
-            # Find the corresponding struct node
-            struct_node_id = None
-            for node_id, node in self.nodes.items():
-                if node.node_type == "Class" and node.name == type_name:
-                    struct_node_id = node_id
-                    break
+            # impl<T: Display> Summary for T covers every type meeting the
+            # bounds; the file stands in for the type it has no node for
+            blanket = bool(trait_field) and type_name in self._type_parameter_names(impl_node)
+            struct_node_id = file_node_id if blanket else self._find_local_node("Class", type_name)
             
-            # If struct not found, skip (might be external type)
-            if not struct_node_id:
-                continue
+            # Methods of a trait impl cannot carry ``pub``; they are reachable
+            # wherever the trait is, which for a foreign trait means anywhere
+            trait_node_id = self._find_local_node("Interface", trait_name) if trait_name else None
+            trait_visibility = None
+            if trait_node_id:
+                trait_visibility = self.nodes[trait_node_id].properties["visibility"]
+            elif trait_name:
+                trait_visibility = "Public"
             
             # Extract methods from impl block body
+            impl_methods: Dict[str, str] = {}
             body = impl_node.field("body")
-            if body:
-                for child in body.children():
-                    if child.kind() == "function_item":
-                        # Get method name
-                        name_field = child.field("name")
-                        if not name_field:
-                            continue
-                        
-                        method_name = name_field.text()
-                        line_no = child.range().start.line + 1
-                        
-                        # Create method node
-                        method_node_id = self._get_node_id("Method", method_name, self.current_file, line_no)
-                        self.nodes[method_node_id] = CodeNode(
-                            node_id=method_node_id,
-                            node_type="Method",
-                            name=method_name,
-                            file_path=self.current_file,
-                            line_no=line_no,
-                        )
-                        
-                        # Add DEFINES relation from struct to method
-                        self._add_relation(CodeRelation(struct_node_id, method_node_id, "DEFINES"))
+            for child in body.children() if body else []:
+                if child.kind() != "function_item":
+                    continue
+                # Get method name
+                name_field = child.field("name")
+                if not name_field:
+                    continue
+                
+                method_name = name_field.text()
+                line_no = child.range().start.line + 1
+                
+                properties = {
+                    "method_of": type_name,
+                    "visibility": trait_visibility or self._visibility(child),
+                    "complexity": sg_complexity(child, "rust"),
+                    "is_async": self._is_async(child),
+                    **self._columns(child),
+                }
+                if trait_name:
+                    properties["impl_trait"] = trait_name
+                
+                # Create method node
+                method_node_id = self._get_node_id("Method", method_name, self.current_file, line_no)
+                self.nodes[method_node_id] = CodeNode(
+                    node_id=method_node_id,
+                    node_type="Method",
+                    name=method_name,
+                    file_path=self.current_file,
+                    line_no=line_no,
+                    end_line_no=child.range().end.line + 1,
+                    properties=properties,
+                )
+                self._set_doc(method_node_id, child)
+                impl_methods[method_name] = method_node_id
+                
+                if blanket:
+                    self._add_relation(CodeRelation(file_node_id, method_node_id, "CONTAINS"))
+                elif struct_node_id:
+                    # Add DEFINES relation from struct to method
+                    self._add_relation(CodeRelation(struct_node_id, method_node_id, "DEFINES"))
+                else:
+                    # Type is defined in another file; attach in the second pass
+                    self.pending_imports.append({
+                        "type": "IMPL_METHOD",
+                        "source_id": file_node_id,
+                        "type_name": type_name,
+                        "method_id": method_node_id,
+                        "module_hints": module_hints,
+                    })
+                
+                self._parse_field_accesses(child, method_node_id, type_name, file_node_id,
+                                           module_hints, source_lines)
+            
+            if not trait_name:
+                continue
+            impl_properties = self._blanket_properties(impl_node, type_field) if blanket else {}
+            if struct_node_id and trait_node_id:
+                self._add_relation(CodeRelation(struct_node_id, trait_node_id, "IMPLEMENTS",
+                                                properties={"trait_path": trait_path, **impl_properties}))
+                for method_name, method_node_id in impl_methods.items():
+                    trait_method_id = self._trait_method(trait_node_id, method_name)
+                    if trait_method_id:
+                        self._add_relation(CodeRelation(method_node_id, trait_method_id, "IMPLEMENTS",
+                                                        properties={"type": type_name}))
+            else:
+                self.pending_imports.append({
+                    "type": "IMPLEMENTS",
+                    "source_id": file_node_id,
+                    "type_name": type_name,
+                    "type_node_id": struct_node_id,
+                    "trait_name": trait_name,
+                    "trait_node_id": trait_node_id,
+                    "trait_path": trait_path,
+                    "impl_properties": impl_properties,
+                    "methods": impl_methods,
+                    "module_hints": module_hints,
+                })
+    
+    @staticmethod
+    def _type_parameter_names(impl_node: SgNode) -> List[str]:
+        """Names of the type parameters an impl declares: ``impl<T: Display, U>`` -> T, U."""
+        parameters = impl_node.field("type_parameters")
+        names = []
+        for child in parameters.children() if parameters else []:
+            if child.kind() == "type_identifier":
+                names.append(child.text())
+                continue
+            name = child.field("left") or child.field("name")
+            if name is not None and name.kind() == "type_identifier":
+                names.append(name.text())
+        return names
+    
+    @classmethod
+    def _generic_bounds(cls, item: SgNode) -> Dict[str, List[str]]:
+        """
+        Bounds on the type parameters of a struct, enum or trait, inline and
+        from its where clause: ``<T: Shape + Clone, U> where U: Display`` ->
+        ``{"T": ["Shape", "Clone"], "U": ["Display"]}``. Lifetimes are left out.
+        """
+        bounds: Dict[str, List[str]] = {}
+        parameters = item.field("type_parameters")
+        for child in parameters.children() if parameters else []:
+            if child.kind() == "type_identifier":
+                bounds.setdefault(child.text(), [])
+                continue
+            name = child.field("left") or child.field("name")
+            if name is not None and name.kind() == "type_identifier":
+                bounds.setdefault(name.text(), []).extend(cls._bound_texts(child.field("bounds")))
+        for clause in (child for child in item.children() if child.kind() == "where_clause"):
+            for predicate in clause.children():
+                left = predicate.field("left") if predicate.kind() == "where_predicate" else None
+                if left is not None and left.kind() != "lifetime":
+                    bounds.setdefault(left.text(), []).extend(cls._bound_texts(predicate.field("bounds")))
+        return {name: list(dict.fromkeys(paths)) for name, paths in bounds.items()}
+    
+    @staticmethod
+    def _bound_texts(bounds: Optional[SgNode]) -> List[str]:
+        """The traits of a bound list as written (``fmt::Debug``, ``?Sized``), without lifetimes."""
+        return [child.text() for child in bounds.children()
+                if child.is_named() and child.kind() != "lifetime"] if bounds else []
+    
+    @staticmethod
+    def _blanket_properties(impl_node: SgNode, type_field: SgNode) -> Dict[str, object]:
+        """IMPLEMENTS properties of a blanket impl: the type it is written for and its bounds."""
+        parameters = impl_node.field("type_parameters")
+        bounds = [parameters.text()] if parameters else []
+        bounds += [child.text() for child in impl_node.children() if child.kind() == "where_clause"]
+        return {
+            "blanket": True,
+            "for_type": type_field.text(),
+            "bounds": " ".join(bounds),
+            "line_no": impl_node.range().start.line + 1,
+        }
+    
+    def _parse_derives(self, file_node_id: str, module_hints: List[str]) -> None:
+        """
+        Link each struct and enum to the traits its ``#[derive(..)]`` attributes name.
+        
+        A derive is an impl written by the compiler, so each trait gets an
+        IMPLEMENTS edge marked ``derived``. Traits defined in this file are
+        linked directly; the rest are queued like a foreign trait impl, which
+        CodeGraph points at an Unresolved placeholder (``Debug``,
+        ``serde::Serialize``) when the trait is not indexed.
+        
+        The impl is written by a derive macro of the same name, which lives
+        in a proc-macro crate, never in the file using it. Each derive is
+        queued as a DERIVE_USE edge from the type to that macro, linked in
+        the second pass to an indexed ``#[proc_macro_derive]`` function or,
+        by CodeGraph, to an Unresolved placeholder named after the path.
+        """
+        for type_node_id, node in list(self.nodes.items()):
+            if node.file_path != self.current_file or node.node_type not in ("Class", "Enum"):
+                continue
+            for attribute in node.properties.get("attributes", []):
+                match = DERIVE_ATTRIBUTE.match(attribute)
+                if not match:
+                    continue
+                for trait_path in _split_top_level(match.group(1)):
+                    trait_path = "".join(trait_path.split())
+                    trait_name = self._base_type_name(trait_path)
+                    self.pending_imports.append({
+                        "type": "DERIVE_USE",
+                        "source_id": type_node_id,
+                        "definition": f"{trait_name}!",
+                        "module_hints": module_hints,
+                        "raw_name": trait_path,
+                    })
+                    trait_node_id = self._find_local_node("Interface", trait_name)
+                    if trait_node_id:
+                        self._add_relation(CodeRelation(type_node_id, trait_node_id, "IMPLEMENTS",
+                                                        properties={"trait_path": trait_path, "derived": True}))
+                        continue
+                    self.pending_imports.append({
+                        "type": "IMPLEMENTS",
+                        "source_id": file_node_id,
+                        "type_name": node.name,
+                        "type_node_id": type_node_id,
+                        "trait_name": trait_name,
+                        "trait_node_id": None,
+                        "trait_path": trait_path,
+                        "impl_properties": {"derived": True},
+                        "methods": {},
+                        "module_hints": module_hints,
+                    })
+    
+    def _attach_spans(self, lines: LineIndex, first_new_node: int) -> None:
+        """Give the nodes parsed from one file, from the given index on, their source span."""
+        for node in itertools.islice(self.nodes.values(), first_new_node, None):
+            column, end_column = node.properties.get("column"), node.properties.get("end_column")
+            if column and end_column:
+                node.span = SourceSpan(node.line_no, column, node.end_line_no or node.line_no, end_column, lines)
+    
+    @staticmethod
+    def _columns(item: SgNode) -> Dict[str, int]:
+        """1-based start column and exclusive end column of an item, on its first and last line."""
+        span = item.range()
+        return {"column": span.start.column + 1, "end_column": span.end.column + 1}
+    
+    def _set_doc(self, node_id: str, item: SgNode) -> None:
+        """
+        Store the doc comment in front of an item as its doc property.
+        
+        Collects the ``///`` lines, ``/** */`` blocks and ``#[doc = "..."]``
+        attributes right before the item. Other attributes such as
+        ``#[derive(..)]`` or ``#[test]`` are kept, without ``#[ ]``, in an
+        ``attributes`` property. Plain ``//`` comments end the doc.
+        """
+        comments, attributes = [], []
+        sibling = item.prev()
+        while sibling is not None:
+            kind, text = sibling.kind(), sibling.text().strip()
+            if kind == "attribute_item":
+                match = DOC_ATTRIBUTE.match(text)
+                if match:
+                    comments.append(self._string_value(match.group(1)))
+                else:
+                    attributes.append(text[2:-1].strip())
+            elif kind == "line_comment" and text.startswith("///") and not text.startswith("////"):
+                comments.append(text)
+            elif kind == "block_comment" and text.startswith("/**") and not text.startswith(("/***", "/**/")):
+                comments.append(text)
+            else:
+                break
+            sibling = sibling.prev()
+        set_doc(self.nodes[node_id], clean_doc_comment(comments[::-1]) if comments else None)
+        if attributes:
+            self.nodes[node_id].properties["attributes"] = attributes[::-1]
+    
+    @staticmethod
+    def _string_value(literal: str) -> str:
+        """Value of a Rust string literal; raw strings are taken as written."""
+        if literal.startswith("r"):
+            return literal.lstrip("r").strip("#")[1:-1]
+        try:
+            return ast.literal_eval(literal)
+        except (ValueError, SyntaxError):
+            # Escapes Python does not know, such as \u{..}
+            return literal[1:-1]
+    
+    @staticmethod
+    def _is_async(func: SgNode) -> bool:
+        """Whether a function or method is declared ``async fn``."""
+        return any(child.kind() == "function_modifiers" and "async" in child.text().split()
+                   for child in func.children())
+    
+    @staticmethod
+    def _visibility(item: SgNode) -> str:
+        """
+        Visibility of an item from its ``pub`` modifier.
+        
+        ``pub`` is Public. ``pub(crate)``, ``pub(super)`` and ``pub(in path)``
+        stop at the crate boundary and map to Crate; ``pub(self)`` and no
+        modifier at all are Private.
+        """
+        modifier = next((child for child in item.children() if child.kind() == "visibility_modifier"), None)
+        return RustAdapter._modifier_visibility(modifier)
+    
+    @staticmethod
+    def _modifier_visibility(modifier: Optional[SgNode]) -> str:
+        """Visibility a ``pub`` modifier node grants; no modifier is Private."""
+        if modifier is None:
+            return "Private"
+        text = "".join(modifier.text().split())
+        if text == "pub":
+            return "Public"
+        return "Private" if text == "pub(self)" else "Crate"
+    
+    def _trait_method(self, trait_node_id: str, method_name: str) -> Optional[str]:
+        """The method of that name a trait in this file declares, if any."""
+        for relation in self.relations:
+            if relation.relation_type == "DEFINES" and relation.source_id == trait_node_id \
+                    and self.nodes.get(relation.target_id) \
+                    and self.nodes[relation.target_id].name == method_name:
+                return relation.target_id
+        return None
+    
+    def _parse_field_accesses(self, method: SgNode, method_node_id: str, type_name: str,
+                              file_node_id: str, module_hints: List[str], source_lines: List[str]) -> None:
+        """
+        Emit REFERENCES edges for ``self.field`` accesses in a method body.
+        
+        Only accesses through ``self`` are linked, since the receiver type is
+        then known to be the impl type. Method calls on ``self`` are not field
+        accesses and are skipped.
+        """
+        body = method.field("body")
+        if not body:
+            return
+        
+        for expr in body.find_all(kind="field_expression"):
+            value = expr.field("value")
+            field = expr.field("field")
+            if not value or not field or value.kind() != "self":
+                continue
+            if self._is_call_target(expr):
+                continue
+            
+            field_name = field.text()
+            line_no = expr.range().start.line + 1
+            field_node_id = self._find_local_field(type_name, field_name)
+            field_type = self.nodes[field_node_id].properties.get("type") if field_node_id else None
+            properties = {
+                "access": self._classify_access(expr, field_type),
+                "line_no": line_no,
+                "call_site": source_lines[line_no - 1].strip() if line_no <= len(source_lines) else "",
+            }
+            
+            if field_node_id:
+                self._add_relation(CodeRelation(method_node_id, field_node_id, "REFERENCES",
+                                                properties=properties))
+            else:
+                # Struct defined in another file; resolve in the second pass
+                self.pending_imports.append({
+                    "type": "FIELD_ACCESS",
+                    "source_id": file_node_id,
+                    "accessor_id": method_node_id,
+                    "type_name": type_name,
+                    "field_name": field_name,
+                    "module_hints": module_hints,
+                    **properties,
+                })
+    
+    def _find_local_field(self, type_name: str, field_name: str) -> Optional[str]:
+        """Find a field of a struct defined in this file."""
+        return self._fields.get(type_name, {}).get(field_name)
+    
+    def _is_call_target(self, expr: SgNode) -> bool:
+        """True for ``self.method`` in ``self.method(...)``."""
+        parent = expr.parent()
+        return parent is not None and parent.kind() == "call_expression" \
+            and self._same_node(parent.field("function"), expr)
+    
+    def _method_receivers(self, root: SgNode) -> Dict[Tuple[str, str], bool]:
+        """Whether each method of the file's impl blocks takes ``&mut self``, by (type name, method name)."""
+        receivers: Dict[Tuple[str, str], bool] = {}
+        for impl_node in root.find_all(kind="impl_item"):
+            type_field, body = impl_node.field("type"), impl_node.field("body")
+            if not type_field or not body:
+                continue
+            type_name = self._base_type_name(type_field.text())
+            for child in body.children():
+                name, parameters = child.field("name"), child.field("parameters")
+                if child.kind() != "function_item" or not name or not parameters:
+                    continue
+                receiver = next((p for p in parameters.children() if p.kind() == "self_parameter"), None)
+                if receiver is not None:
+                    receivers.setdefault((type_name, name.text()), receiver.text().startswith("&") and any(
+                        c.kind() == "mutable_specifier" for c in receiver.children()))
+        return receivers
+    
+    def _classify_access(self, expr: SgNode, field_type: Optional[str] = None) -> str:
+        """
+        Classify a field access as Read, Write, ReadWrite or Unknown.
+        
+        The access is widened to the whole place expression first, so
+        ``self.pos.x = 1`` and ``self.items[i] = v`` count as writes to the
+        field. Assignment targets and ``&mut`` borrows are writes, compound
+        assignment (``+=``) is ReadWrite, and everything else is a Read.
+        
+        A method called on the field (``self.pos.shift(1)``) is ReadWrite
+        when field_type has a method of that name in this file taking
+        ``&mut self`` and a Read when it takes ``&self`` or ``self``. Any
+        other receiver, such as ``self.items.len()`` on a std type or
+        ``self.items[0].reset()`` on a part of the field, is Unknown.
+        """
+        place = expr
+        parent = place.parent()
+        while parent is not None:
+            if parent.kind() == "field_expression" and self._same_node(parent.field("value"), place) \
+                    and not self._is_call_target(parent):
+                place = parent
+            elif parent.kind() == "index_expression" and parent.children() \
+                    and self._same_node(parent.children()[0], place):
+                place = parent
+            else:
+                break
+            parent = place.parent()
+        
+        if parent is None:
+            return "Read"
+        kind = parent.kind()
+        if kind == "field_expression" and self._same_node(parent.field("value"), place):
+            # Left of a method call, or the loop above would have widened to it
+            mutates = None
+            if field_type and self._same_node(place, expr):
+                mutates = self._mut_receivers.get((self._base_type_name(field_type), parent.field("field").text()))
+            return "Unknown" if mutates is None else "ReadWrite" if mutates else "Read"
+        if kind == "assignment_expression" and self._same_node(parent.field("left"), place):
+            return "Write"
+        if kind == "compound_assignment_expr" and self._same_node(parent.field("left"), place):
+            return "ReadWrite"
+        if kind == "reference_expression" \
+                and any(child.kind() == "mutable_specifier" for child in parent.children()):
+            return "Write"
+        return "Read"
+    
+    def _parse_calls(self, root: SgNode, crate_module: str) -> None:
+        """Emit CALLS edges from every function and method to the functions it calls."""
+        bindings = self._use_bindings(root)
+        symbols, globs = self._use_symbols(root, crate_module)
+        module_hints = self._use_path_segments(root)
+        source_lines = root.text().splitlines()
+        
+        for func in root.find_all(kind="function_item"):
+            caller_id = self._function_node_id(func)
+            body = func.field("body")
+            if not caller_id or not body:
+                continue
+            owner = self.nodes[caller_id].properties.get("method_of")
+            local_types = self._local_types(func, owner)
+            
+            for call in body.find_all(kind="call_expression"):
+                # Calls inside a nested fn item belong to that item
+                if not self._same_node(self._enclosing_function(call), func):
+                    continue
+                function = call.field("function")
+                if not function:
+                    continue
+                
+                line_no = call.range().start.line + 1
+                site = {
+                    "line_no": line_no,
+                    "call_site": source_lines[line_no - 1].strip() if line_no <= len(source_lines) else "",
+                }
+                # f(..).await: the call's future is awaited right there
+                if call.parent() is not None and call.parent().kind() == "await_expression":
+                    site["await_call"] = True
+                kind = function.kind()
+                
+                if kind == "identifier":
+                    # foo(...)
+                    name = function.text()
+                    target_id = self._find_local_node("Function", name)
+                    if target_id:
+                        self._add_relation(CodeRelation(caller_id, target_id, "CALLS", properties=site))
+                    elif name in symbols:
+                        written, candidates = symbols[name]
+                        self._add_path_call(caller_id, written, candidates, site)
+                    elif globs and not name[:1].isupper():
+                        # Tuple struct and variant constructors are not calls to follow
+                        self._add_path_call(caller_id, name, [f"{module}::{name}" for module in globs], site)
+                
+                elif kind == "scoped_identifier":
+                    # Type::assoc(...), Self::assoc(...) or module::func(...)
+                    path = function.field("path")
+                    name = function.field("name")
+                    if not path or not name:
+                        continue
+                    qualifier = self._base_type_name(path.text())
+                    if qualifier == "Self":
+                        qualifier = owner
+                    if not qualifier:
+                        continue
+                    segments = _path_segments(path.text()) + [name.text()]
+                    if qualifier not in ("self", "super", "crate") and self._is_type_name(qualifier):
+                        self._add_member_call(caller_id, qualifier, name.text(), site,
+                                              self._type_hints(qualifier, bindings, module_hints),
+                                              function.text())
+                    elif segments[0] == "self" and len(segments) == 2 \
+                            and self._find_local_node("Function", name.text()):
+                        self._add_relation(CodeRelation(caller_id, self._find_local_node("Function", name.text()),
+                                                        "CALLS", properties=site))
+                    elif segments[0] in symbols:
+                        # greeting::greet(..) after `use crate::greeting;`
+                        written, candidates = symbols[segments[0]]
+                        rest = "::".join(segments[1:])
+                        self._add_path_call(caller_id, f"{written}::{rest}",
+                                            [f"{c}::{rest}" for c in candidates], site)
+                    else:
+                        self._add_path_call(caller_id, "::".join(segments),
+                                            self._use_candidates(segments, crate_module), site)
+                
+                elif kind == "field_expression":
+                    # receiver.method(...): linked when the receiver's type is known
+                    value = function.field("value")
+                    field = function.field("field")
+                    if not value or not field:
+                        continue
+                    raw_name = self._method_call_path(value, field.text())
+                    type_name = self._expression_type(value, local_types)
+                    if type_name:
+                        self._add_member_call(caller_id, type_name, field.text(), site,
+                                              self._type_hints(type_name, bindings, module_hints),
+                                              raw_name)
+                    else:
+                        self._add_unresolved_call(caller_id, raw_name, site)
+    
+    def _local_types(self, func: SgNode, owner: Optional[str]) -> Dict[str, str]:
+        """
+        Map parameters and let bindings of a function to their type names.
+        
+        A binding's type comes from its annotation or, failing that, from an
+        initializer the type can be read off: ``Type { .. }`` or a
+        ``Type::f(..)`` call, which is assumed to return ``Type``. Shadowed
+        bindings keep the last type seen.
+        """
+        types: Dict[str, str] = {}
+        if owner:
+            types["self"] = owner
+        parameters = func.field("parameters")
+        if parameters:
+            for param in parameters.children():
+                if param.kind() != "parameter":
+                    continue
+                pattern, type_node = param.field("pattern"), param.field("type")
+                if pattern and type_node and pattern.kind() == "identifier":
+                    type_name = self._base_type_name(type_node.text())
+                    if type_name.isidentifier():
+                        types[pattern.text()] = type_name
+        
+        body = func.field("body")
+        for let_node in body.find_all(kind="let_declaration") if body else []:
+            if not self._same_node(self._enclosing_function(let_node), func):
+                continue
+            pattern = let_node.field("pattern")
+            if not pattern or pattern.kind() != "identifier":
+                continue
+            type_node, value = let_node.field("type"), let_node.field("value")
+            type_name = self._base_type_name(type_node.text()) if type_node else None
+            if not type_name and value:
+                type_name = self._expression_type(value, types)
+            if type_name and type_name.isidentifier():
+                types[pattern.text()] = type_name
+        return types
+    
+    def _expression_type(self, expr: SgNode, local_types: Dict[str, str]) -> Optional[str]:
+        """Type name of an expression, when it can be read off the syntax."""
+        kind = expr.kind()
+        if kind in ("identifier", "self"):
+            return local_types.get(expr.text())
+        if kind == "reference_expression":
+            inner = expr.field("value")
+            return self._expression_type(inner, local_types) if inner else None
+        if kind == "struct_expression":
+            name = expr.field("name")
+            return self._base_type_name(name.text()) if name else None
+        if kind == "call_expression":
+            function = expr.field("function")
+            path = function.field("path") if function and function.kind() == "scoped_identifier" else None
+            if path:
+                qualifier = self._base_type_name(path.text())
+                if qualifier == "Self":
+                    return local_types.get("self")
+                if qualifier.isidentifier() and self._is_type_name(qualifier):
+                    return qualifier
+        return None
+    
+    @staticmethod
+    def _method_call_path(receiver: SgNode, method_name: str) -> str:
+        """
+        Textual path of a method call, e.g. ``person.get_name``.
+        
+        Receivers other than a variable or field chain (``a.b().c()``) are
+        left out, giving ``.c``.
+        """
+        node = receiver
+        while node.kind() == "field_expression" and node.field("value"):
+            node = node.field("value")
+        if node.kind() in ("identifier", "self"):
+            return f"{receiver.text()}.{method_name}"
+        return f".{method_name}"
+    
+    def _add_path_call(self, caller_id: str, raw_name: str, candidates: List[str],
+                       site: Dict[str, object]) -> None:
+        """
+        Queue a call to a function named by a path, tried in candidate order.
+        
+        ``raw_name`` is the path as imported (``std::cmp::max``), kept on the
+        unresolved edge when no candidate is indexed.
+        """
+        self.pending_imports.append({
+            "type": "CALLS_RUST",
+            "source_id": caller_id,
+            "candidates": candidates,
+            "modules": sorted({c.rsplit("::", 1)[0] for c in candidates if "::" in c}),
+            "raw_name": raw_name,
+            **site,
+        })
+    
+    def _add_unresolved_call(self, caller_id: str, raw_name: str, site: Dict[str, object]) -> None:
+        """Queue a call whose target cannot be looked up, e.g. a method on a receiver of unknown type."""
+        self.pending_imports.append({
+            "type": "CALLS_UNRESOLVED",
+            "source_id": caller_id,
+            "raw_name": raw_name,
+            **site,
+        })
+    
+    def _parse_macro_invocations(self, root: SgNode, file_node_id: str, crate_module: str) -> None:
+        """
+        Link macro invocations to ``macro_rules!`` definitions and their arguments to symbols.
+        
+        An invocation, nested ones in the arguments included, is a CALLS edge
+        marked ``macro`` from the enclosing function (the file, at item
+        level) to the Macro node, found in this file or by name in the second
+        pass. Std macros such as ``println!`` and proc macros are not
+        indexed; CodeGraph points their calls at an Unresolved placeholder.
+        
+        Macro arguments are token trees rather than expressions, so a path in
+        them is matched to a function or type by name alone: in this file,
+        then through the use symbols or the module path. The REFERENCES edges
+        this gives name the ``via_macro`` and carry ``confidence: low``.
+        Fields and methods (``x.name``, ``Type::new``) are not followed.
+        """
+        symbols, _ = self._use_symbols(root, crate_module)
+        module_hints = self._use_path_segments(root)
+        source_lines = root.text().splitlines()
+        
+        for invocation in root.find_all(kind="macro_invocation"):
+            macro = invocation.field("macro")
+            if not macro:
+                continue
+            func = self._enclosing_function(invocation)
+            source_id = (self._function_node_id(func) if func else None) or file_node_id
+            line_no = invocation.range().start.line + 1
+            site = {
+                "line_no": line_no,
+                "call_site": source_lines[line_no - 1].strip() if line_no <= len(source_lines) else "",
+            }
+            macro_name = _path_segments(macro.text())[-1]
+            self._add_macro_call(source_id, macro_name, macro.text(), module_hints, site)
+            
+            tokens = self._macro_tokens(invocation)
+            i = 0
+            while i < len(tokens):
+                token = tokens[i]
+                previous = tokens[i - 1].text() if i else ""
+                if token.kind() not in PATH_TOKEN_KINDS or previous in (".", "::", "$"):
+                    i += 1
+                    continue
+                segments = [token.text()]
+                i += 1
+                while i + 1 < len(tokens) and tokens[i].text() == "::" \
+                        and tokens[i + 1].kind() in PATH_TOKEN_KINDS:
+                    segments.append(tokens[i + 1].text())
+                    i += 2
+                if i < len(tokens) and tokens[i].text() == "!":
+                    self._add_macro_call(source_id, segments[-1], "::".join(segments), module_hints, site)
+                else:
+                    self._add_macro_reference(source_id, segments, macro_name, symbols, crate_module, site)
+    
+    @staticmethod
+    def _macro_tokens(invocation: SgNode) -> List[SgNode]:
+        """The leaf tokens of a macro invocation's arguments, nested token trees flattened."""
+        tokens: List[SgNode] = []
+        stack = [child for child in invocation.children() if child.kind() == "token_tree"][::-1]
+        while stack:
+            node = stack.pop()
+            if node.kind() == "token_tree":
+                stack.extend(node.children()[::-1])
+            else:
+                tokens.append(node)
+        return tokens
+    
+    def _add_macro_call(self, caller_id: str, macro_name: str, raw_name: str,
+                        module_hints: List[str], site: Dict[str, object]) -> None:
+        """Link an invocation to a macro of this file, or queue it for the second pass."""
+        target_id = self._find_local_node("Macro", macro_name)
+        if target_id:
+            self._add_relation(CodeRelation(caller_id, target_id, "CALLS", properties={"macro": True, **site}))
+            return
+        self.pending_imports.append({
+            "type": "CALLS_MACRO",
+            "source_id": caller_id,
+            "definition": f"{macro_name}!",
+            "module_hints": module_hints,
+            "raw_name": f"{raw_name}!",
+            **site,
+        })
+    
+    def _add_macro_reference(self, source_id: str, segments: List[str], macro_name: str,
+                             symbols: Dict[str, Tuple[str, List[str]]], crate_module: str,
+                             site: Dict[str, object]) -> None:
+        """Link a path found in a macro's arguments to the function or type it names, if any."""
+        properties = {"via_macro": macro_name, "confidence": "low", **site}
+        # A path through a type names one of its members; the type is what can be found
+        if len(segments) > 1 and self._is_type_name(segments[0]):
+            segments = segments[:1]
+        
+        if len(segments) == 1:
+            name = segments[0]
+            for node_type in MACRO_ARGUMENT_TARGETS:
+                target_id = self._find_local_node(node_type, name)
+                if target_id:
+                    self._add_relation(CodeRelation(source_id, target_id, "REFERENCES", properties=properties))
+                    return
+            if name not in symbols:
+                return
+            raw_name, candidates = symbols[name]
+        elif segments[0] in symbols:
+            written, heads = symbols[segments[0]]
+            rest = "::".join(segments[1:])
+            raw_name, candidates = f"{written}::{rest}", [f"{head}::{rest}" for head in heads]
+        else:
+            raw_name, candidates = "::".join(segments), self._use_candidates(segments, crate_module)
+        
+        self.pending_imports.append({
+            "type": "REFERENCES_RUST",
+            "source_id": source_id,
+            "candidates": candidates,
+            "modules": sorted({c.rsplit("::", 1)[0] for c in candidates if "::" in c}),
+            "raw_name": raw_name,
+            **properties,
+        })
+    
+    def _function_node_id(self, func: SgNode) -> Optional[str]:
+        """Node ID of the Function or Method created for a function_item."""
+        name_field = func.field("name")
+        if not name_field:
+            return None
+        line_no = func.range().start.line + 1
+        for node_type in ("Method", "Function"):
+            node_id = self._get_node_id(node_type, name_field.text(), self.current_file, line_no)
+            if node_id in self.nodes:
+                return node_id
+        return None
+    
+    @staticmethod
+    def _enclosing_function(node: SgNode) -> Optional[SgNode]:
+        parent = node.parent()
+        while parent is not None and parent.kind() != "function_item":
+            parent = parent.parent()
+        return parent
+    
+    def _is_type_name(self, name: str) -> bool:
+        """Heuristic for the qualifier of a path call: local types and CamelCase names."""
+        return bool(self._find_local_node("Class", name) or self._find_local_node("Interface", name)) \
+            or name[:1].isupper()
+    
+    @staticmethod
+    def _type_hints(type_name: str, bindings: Dict[str, Tuple[str, str]], module_hints: List[str]) -> List[str]:
+        """Module hints for a type, with the module it was imported from first."""
+        if type_name in bindings:
+            return [bindings[type_name][0]] + [h for h in module_hints if h != bindings[type_name][0]]
+        return module_hints
+    
+    @staticmethod
+    def _use_bindings(root: SgNode) -> Dict[str, Tuple[str, str]]:
+        """
+        Map names brought into scope by use declarations to (module, name).
+        
+        ``use crate::shapes::{Circle, area as circle_area};`` binds ``Circle``
+        to ("shapes", "Circle") and ``circle_area`` to ("shapes", "area").
+        """
+        bindings: Dict[str, Tuple[str, str]] = {}
+        for use_node in root.find_all(kind="use_declaration"):
+            text = use_node.text().strip().rstrip(";").strip()
+            if text.startswith("pub "):
+                text = text.split(None, 1)[1]
+            if not text.startswith("use "):
+                continue
+            text = text[4:].strip()
+            
+            if "{" in text:
+                prefix, _, group = text.partition("{")
+                items = [prefix + item.strip() for item in group.rstrip("}").split(",") if item.strip()]
+            else:
+                items = [text]
+            
+            for item in items:
+                path, _, alias = item.partition(" as ")
+                segments = [seg for seg in path.strip().split("::") if seg]
+                if len(segments) < 2 or segments[-1] in ("self", "*"):
+                    continue
+                bindings[alias.strip() or segments[-1]] = (segments[-2], segments[-1])
+        return bindings
+    
+    def _use_symbols(self, root: SgNode, crate_module: str) -> Tuple[Dict[str, Tuple[str, List[str]]], List[str]]:
+        """
+        The file's symbol table: what each use declaration brings into scope.
+        
+        Maps every name bound by a use, under its alias if renamed, to the
+        path as written and the absolute paths it may refer to, and lists the
+        candidate modules of glob imports. ``use crate::shapes::{self, area as
+        circle_area};`` binds ``shapes`` to ``crate::shapes`` and
+        ``circle_area`` to ``crate::shapes::area``.
+        """
+        symbols: Dict[str, Tuple[str, List[str]]] = {}
+        globs: List[str] = []
+        for use_node in root.find_all(kind="use_declaration"):
+            match = USE_DECLARATION.match(use_node.text().strip())
+            if not match:
+                continue
+            for segments, alias, glob in expand_use_tree(match.group(1)):
+                if not segments:
+                    continue
+                candidates = self._use_candidates(segments, crate_module)
+                if glob:
+                    globs.extend(c for c in candidates if c not in globs)
+                elif segments[-1] not in ("crate", "super"):
+                    symbols[alias or segments[-1]] = ("::".join(segments), candidates)
+        return symbols, globs
+    
+    @staticmethod
+    def _base_type_name(type_text: str) -> str:
+        """
+        Reduce a type or trait path to its bare name.
+        
+        ``&'a mut Wrapper<T>`` -> ``Wrapper``, ``fmt::Display`` -> ``Display``.
+        """
+        text = type_text.split("<", 1)[0].strip()
+        words = text.lstrip("&").split()
+        # Drop reference lifetimes ('a) and qualifiers ahead of the path
+        while len(words) > 1 and (words[0].startswith("'") or words[0] in ("mut", "dyn", "impl")):
+            words = words[1:]
+        text = words[0] if words else ""
+        return text.split("::")[-1].strip()
+    
+    @staticmethod
+    def _use_path_segments(root: SgNode) -> List[str]:
+        """
+        Collect module names mentioned in this file's use declarations.
+        
+        Used as hints when resolving impl targets defined in other files.
+        """
+        hints: List[str] = []
+        for use_node in root.find_all(kind="use_declaration"):
+            text = use_node.text()
+            for token in text.replace("{", " ").replace("}", " ").replace(",", " ").replace(";", " ").split():
+                for segment in token.split("::"):
+                    if segment and segment not in ("use", "pub", "crate", "self", "super", "as", "*") \
+                            and segment not in hints:
+                        hints.append(segment)
+        return hints
@@ -0,0 +1,135 @@
+"""
+Cyclomatic complexity of functions and methods, counted while parsing.
+
+The complexity of a function is one plus its decision points: the syntax
+nodes that each add one path through its body. What counts, by language:
+
+- Python: ``if`` and each ``elif``, ``for``, ``while``, ``except``, the
+  conditional expression, each ``for`` and ``if`` of a comprehension, each
+  ``case`` of a ``match``, and each ``and``/``or`` operator
+- JavaScript and TypeScript: ``if``, ``for``, ``for..in``/``for..of``,
+  ``while``, ``do``, each ``case`` and the ``default`` of a ``switch``,
+  ``catch``, the ternary operator, ``&&`` and ``||``
+- Java: ``if``, ``for``, enhanced ``for``, ``while``, ``do``, each switch
+  label (``default`` included), ``catch``, the ternary operator, ``&&`` and ``||``
+- C and C++: ``if``, ``for``, range ``for``, ``while``, ``do``, each
+  ``case`` and ``default``, ``catch``, the conditional operator, ``&&`` and ``||``
+- Go: ``if``, ``for``, each case and ``default`` of a ``switch`` or
+  ``select``, ``&&`` and ``||``
+- Rust: ``if`` (so also each ``else if``), each ``match`` arm, ``while``,
+  ``for``, ``?``, ``&&`` and ``||``
+- Ruby: ``if``, ``elsif``, ``unless``, ``while``, ``until``, ``for``, each
+  ``when`` of a ``case``, ``rescue``, their modifier forms (``x if y``),
+  the ternary operator, ``&&``, ``||``, ``and`` and ``or``
+
+An ``else`` adds no path of its own. Closures and lambdas belong to the
+enclosing function; functions and classes defined inside it are counted
+on their own.
+"""
+
+import ast
+from typing import Any, Callable, Iterable, Optional
+
+_C_FAMILY = {"if_statement", "for_statement", "while_statement", "do_statement", "case_statement",
+             "conditional_expression"}
+_JS_FAMILY = {"if_statement", "for_statement", "for_in_statement", "while_statement", "do_statement",
+              "switch_case", "switch_default", "catch_clause", "ternary_expression"}
+
+# Syntax node kinds that each add one path through a function, by language
+DECISION_KINDS = {
+    "python": {"if_statement", "elif_clause", "for_statement", "while_statement", "except_clause",
+               "conditional_expression", "for_in_clause", "if_clause", "case_clause"},
+    "javascript": _JS_FAMILY,
+    "typescript": _JS_FAMILY,
+    "java": {"if_statement", "for_statement", "enhanced_for_statement", "while_statement", "do_statement",
+             "switch_label", "catch_clause", "ternary_expression"},
+    "c": _C_FAMILY,
+    "cpp": _C_FAMILY | {"for_range_loop", "catch_clause"},
+    "go": {"if_statement", "for_statement", "expression_case", "type_case", "communication_case",
+           "default_case"},
+    "rust": {"if_expression", "match_arm", "while_expression", "for_expression", "try_expression"},
+    "ruby": {"if", "elsif", "unless", "while", "until", "for", "when", "rescue", "conditional", "if_modifier",
+             "unless_modifier", "while_modifier", "until_modifier", "rescue_modifier"},
+}
+
+# Binary operators that add a path, and the node kinds holding them
+BOOLEAN_OPERATORS = {"&&", "||", "and", "or"}
+BOOLEAN_KINDS = {"binary_expression", "boolean_operator", "binary"}
+
+# Definitions inside a function body that are counted on their own
+NESTED_KINDS = {
+    "python": {"function_definition", "class_definition"},
+    "javascript": {"function_declaration", "generator_function_declaration", "method_definition",
+                   "class_declaration", "abstract_class_declaration", "class"},
+    "java": {"method_declaration", "constructor_declaration", "class_declaration", "interface_declaration"},
+    "c": {"function_definition"},
+    "cpp": {"function_definition", "class_specifier", "struct_specifier"},
+    "go": {"function_declaration", "method_declaration"},
+    "rust": {"function_item"},
+    "ruby": {"method", "singleton_method", "class", "module", "singleton_class"},
+}
+NESTED_KINDS["typescript"] = NESTED_KINDS["javascript"]
+
+
+def _count(body: Any, language: str, kind: Callable[[Any], str], children: Callable[[Any], Iterable[Any]],
+           operator: Callable[[Any], Optional[str]]) -> int:
+    decisions = DECISION_KINDS.get(language, set())
+    nested = NESTED_KINDS.get(language, set())
+    complexity = 1
+    stack = [body] if body is not None else []
+    while stack:
+        node = stack.pop()
+        node_kind = kind(node)
+        if node_kind in nested:
+            continue
+        if node_kind in decisions:
+            complexity += 1
+        elif node_kind in BOOLEAN_KINDS and operator(node) in BOOLEAN_OPERATORS:
+            complexity += 1
+        stack.extend(children(node))
+    return complexity
+
+
+def sg_complexity(func: Any, language: str) -> int:
+    """
+    Complexity of an ast-grep function node, from the decision points of its body.
+
+    Args:
+        func: Function or method node with a ``body`` field
+        language: Language of the file, a key of DECISION_KINDS
+    """
+    def operator(node):
+        op = node.field("operator")
+        return op.text() if op else None
+    return _count(func.field("body"), language, lambda node: node.kind(), lambda node: node.children(), operator)
+
+
+def tree_sitter_complexity(func: Any, language: str) -> int:
+    """Complexity of a tree-sitter function node; see sg_complexity."""
+    def operator(node):
+        op = node.child_by_field_name("operator")
+        return op.type if op is not None else None
+    return _count(func.child_by_field_name("body"), language, lambda node: node.type,
+                  lambda node: node.children, operator)
+
+
+def python_complexity(func: ast.AST) -> int:
+    """
+    Complexity of a Python function from its ``ast`` node, counted like
+    sg_complexity counts the tree-sitter one.
+    """
+    complexity = 1
+    stack = list(func.body)
+    while stack:
+        node = stack.pop()
+        if isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef, ast.ClassDef)):
+            continue
+        if isinstance(node, (ast.If, ast.For, ast.AsyncFor, ast.While, ast.ExceptHandler, ast.IfExp,
+                             ast.match_case)):
+            complexity += 1
+        elif isinstance(node, ast.comprehension):
+            complexity += 1 + len(node.ifs)
+        elif isinstance(node, ast.BoolOp):
+            complexity += len(node.values) - 1
+        stack.extend(ast.iter_child_nodes(node))
+    return complexity
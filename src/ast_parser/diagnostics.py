@@ -0,0 +1,323 @@
+"""
+Syntax errors met while parsing, so a broken file can be told from an empty one.
+
+Each parser keeps the errors of the file it parsed last in ``parse_errors``.
+Tree-sitter based parsers (ast-grep adapters and the TypeScript parser) find
+them as ERROR and MISSING nodes, and keep every declaration outside those
+nodes. The Python AST parser blanks out the top-level statement holding a
+syntax error and parses the rest again, so the other declarations survive.
+
+Lines are 1-based; columns are 1-based characters, as elsewhere in the
+parsers, with an exclusive end.
+
+An indexing run keeps a ParseDiagnostics as well: every error of every file,
+each with a kind telling why the file is missing from the graph in whole or
+in part, plus the files left out before parsing, too large or in a language
+no enabled parser handles. The File node of a file with errors carries the
+first of them as ``parse_error``, so the stored graph tells which files are
+incomplete; a file nothing was recovered from is kept as that node alone.
+"""
+
+import os
+import threading
+from dataclasses import dataclass, field
+from typing import Any, Dict, Iterable, List, Optional, Set
+
+# Kinds of error: the source could not be parsed (a parser failing on it counts too),
+# no enabled parser handles its language, it is over the size limit, it could not
+# be read, or it is not UTF-8 text
+SYNTAX_ERROR = "SyntaxError"
+UNSUPPORTED_LANGUAGE = "UnsupportedLanguage"
+FILE_TOO_LARGE = "FileTooLarge"
+IO_ERROR = "IoError"
+ENCODING_ERROR = "EncodingError"
+ERROR_KINDS = (SYNTAX_ERROR, UNSUPPORTED_LANGUAGE, FILE_TOO_LARGE, IO_ERROR, ENCODING_ERROR)
+
+# Kinds of the files left out before parsing, rather than failed
+SKIP_KINDS = (UNSUPPORTED_LANGUAGE, FILE_TOO_LARGE)
+
+# File node properties holding a file's first error
+PARSE_ERROR_PROPERTIES = ("parse_error", "parse_error_kind", "parse_error_line", "parse_error_column")
+
+
+@dataclass
+class ParseError:
+    """One syntax error, or a file that could not be parsed at all when the span is unknown."""
+    message: str
+    line_no: Optional[int] = None
+    column: Optional[int] = None
+    end_line_no: Optional[int] = None
+    end_column: Optional[int] = None
+    # One of ERROR_KINDS
+    kind: str = SYNTAX_ERROR
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"message": self.message, "line_no": self.line_no, "column": self.column,
+                "end_line_no": self.end_line_no, "end_column": self.end_column, "kind": self.kind}
+
+    @classmethod
+    def from_dict(cls, data: Dict[str, Any]) -> "ParseError":
+        return cls(data["message"], data.get("line_no"), data.get("column"),
+                   data.get("end_line_no"), data.get("end_column"), data.get("kind", SYNTAX_ERROR))
+
+
+def error_kind(error: BaseException) -> str:
+    """Kind of an exception met while reading or parsing a file."""
+    if isinstance(error, UnicodeError):
+        return ENCODING_ERROR
+    if isinstance(error, OSError):
+        return IO_ERROR
+    return SYNTAX_ERROR
+
+
+def read_error(error: BaseException) -> ParseError:
+    """A file that could not be read, as the only error of its parse."""
+    return ParseError(f"Could not read file: {error}", kind=error_kind(error))
+
+
+def flag_parse_error(file_path: str, nodes: Dict[str, Any], errors: List[ParseError]) -> None:
+    """
+    Put the first of a file's errors on its File node, adding the node when the parse gave none.
+
+    Args:
+        file_path: The file parsed
+        nodes: The file's nodes by ID, updated in place
+        errors: Its errors, in source order; nothing is done without any
+    """
+    if not errors:
+        return
+    from src.ast_parser.parser import CodeNode
+
+    node_id = f"file:{file_path}"
+    if node_id not in nodes:
+        nodes[node_id] = CodeNode(node_id, "File", os.path.basename(file_path), file_path, 0)
+    first = errors[0]
+    properties = nodes[node_id].properties
+    properties.update(parse_error=first.message, parse_error_kind=first.kind)
+    for key, value in (("parse_error_line", first.line_no), ("parse_error_column", first.column)):
+        if value is None:
+            properties.pop(key, None)
+        else:
+            properties[key] = value
+
+
+class ParseFailure(Exception):
+    """A file failed to parse while indexing in strict mode."""
+
+    def __init__(self, file_path: str, message: str):
+        super().__init__(f"{file_path}: {message}")
+        self.file_path = file_path
+        self.message = message
+
+
+@dataclass
+class FileDiagnostics:
+    """The syntax errors of one file."""
+    file_path: str
+    errors: List[ParseError]
+    # Whether declarations were still extracted around the errors
+    partial: bool
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"file_path": self.file_path, "partial": self.partial,
+                "errors": [error.to_dict() for error in self.errors]}
+
+
+@dataclass
+class ParseReport:
+    """Outcome of parsing a set of files."""
+    # Files with at least one error, by path
+    files: Dict[str, FileDiagnostics] = field(default_factory=dict)
+    # Every file parsed, with or without errors
+    parsed: Set[str] = field(default_factory=set)
+
+    def add(self, file_path: str, errors: List[ParseError], partial: bool) -> None:
+        """Record the parse of one file; a later parse of the same file replaces it."""
+        self.parsed.add(file_path)
+        if errors:
+            self.files[file_path] = FileDiagnostics(file_path, list(errors), partial)
+        else:
+            self.files.pop(file_path, None)
+
+    @property
+    def error_count(self) -> int:
+        return sum(len(diagnostics.errors) for diagnostics in self.files.values())
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "files_parsed": len(self.parsed),
+            "files_with_errors": len(self.files),
+            "errors": self.error_count,
+            "files": [self.files[path].to_dict() for path in sorted(self.files)],
+        }
+
+
+@dataclass
+class Diagnostic:
+    """One problem with one file, as the get_diagnostics MCP resource lists it."""
+    file: str
+    line: Optional[int]
+    column: Optional[int]
+    error_kind: str
+    message: str
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"file": self.file, "line": self.line, "column": self.column,
+                "error_kind": self.error_kind, "message": self.message}
+
+
+class ParseDiagnostics:
+    """
+    Problems met while indexing, by file, and how many files made it into the graph.
+
+    A file failed when it was left out before parsing or nothing was
+    recovered from it; a file parsed with declarations recovered around its
+    syntax errors counts as parsed, with its errors listed. Recording a file
+    again, as a watcher does after each change, replaces what was recorded.
+    Safe to read while another thread records.
+
+    In strict mode the first file recorded with an error, other than one
+    left out for its size or language, raises ParseFailure instead.
+    """
+
+    def __init__(self, strict: bool = False):
+        self.strict = strict
+        self._lock = threading.Lock()
+        self._diagnostics: Dict[str, List[Diagnostic]] = {}
+        self._failed: Set[str] = set()
+        self._parsed: Set[str] = set()
+
+    def record(self, file_path: str, errors: List[ParseError], partial: bool) -> None:
+        """Record the parse of one file; ``partial`` tells whether declarations were recovered around its errors."""
+        diagnostics = [Diagnostic(file_path, error.line_no, error.column, error.kind, error.message)
+                       for error in errors]
+        if self.strict and errors:
+            raise ParseFailure(file_path, errors[0].message)
+        self._set(file_path, diagnostics, failed=bool(errors) and not partial)
+
+    def skip(self, file_path: str, kind: str, message: str) -> None:
+        """Record a file left out of the graph, e.g. ``FILE_TOO_LARGE``."""
+        if self.strict and kind not in SKIP_KINDS:
+            raise ParseFailure(file_path, message)
+        self._set(file_path, [Diagnostic(file_path, None, None, kind, message)], failed=True)
+
+    def add_report(self, report: ParseReport) -> None:
+        """Record every file of a parse report."""
+        for file_path in sorted(report.parsed):
+            diagnostics = report.files.get(file_path)
+            if diagnostics is None:
+                self.record(file_path, [], partial=False)
+            else:
+                self.record(file_path, diagnostics.errors, diagnostics.partial)
+
+    def forget(self, file_path: str) -> None:
+        """Drop a file that no longer exists."""
+        with self._lock:
+            self._diagnostics.pop(file_path, None)
+            self._failed.discard(file_path)
+            self._parsed.discard(file_path)
+
+    def _set(self, file_path: str, diagnostics: List[Diagnostic], failed: bool) -> None:
+        with self._lock:
+            if diagnostics:
+                self._diagnostics[file_path] = diagnostics
+            else:
+                self._diagnostics.pop(file_path, None)
+            (self._failed if failed else self._parsed).add(file_path)
+            (self._parsed if failed else self._failed).discard(file_path)
+
+    def to_dict(self) -> Dict[str, Any]:
+        with self._lock:
+            diagnostics = [d.to_dict() for path in sorted(self._diagnostics) for d in self._diagnostics[path]]
+            parsed, failed = len(self._parsed), len(self._failed)
+        return {"files_parsed": parsed, "files_failed": failed, "complete": not diagnostics,
+                "diagnostics": diagnostics}
+
+    def summary(self) -> Dict[str, Any]:
+        """
+        Outcome of the run by file: how many made it into the graph, and the paths of those that did not.
+
+        ``failed_files`` could not be parsed, ``files_with_errors`` were
+        indexed with what was recovered around their errors, and the
+        ``skipped_*`` ones were left out before parsing.
+        """
+        with self._lock:
+            kinds = {path: {d.error_kind for d in diagnostics} for path, diagnostics in self._diagnostics.items()}
+            parsed, failed = set(self._parsed), set(self._failed)
+        skipped = {kind: sorted(path for path in failed if kind in kinds.get(path, ())) for kind in SKIP_KINDS}
+        failed_files = sorted(path for path in failed if not kinds.get(path, set()) & set(SKIP_KINDS))
+        return {
+            "files_indexed": len(parsed),
+            "files_failed": len(failed_files),
+            "failed_files": failed_files,
+            "files_with_errors": sorted(path for path in parsed if path in kinds),
+            "skipped_by_size": skipped[FILE_TOO_LARGE],
+            "skipped_unsupported": skipped[UNSUPPORTED_LANGUAGE],
+        }
+
+
+def has_declarations(nodes: Iterable[Any]) -> bool:
+    """Whether a parse extracted anything beyond the File node, i.e. produced partial results."""
+    return any(node.node_type != "File" for node in nodes)
+
+
+# Characters of the offending source quoted in an error message
+SNIPPET_CHARS = 40
+
+
+def _message(kind: str, text: str) -> str:
+    if kind == "missing":
+        return f"Missing {text}"
+    snippet = " ".join(text.split())
+    if len(snippet) > SNIPPET_CHARS:
+        snippet = snippet[:SNIPPET_CHARS] + "…"
+    return f"Syntax error at {snippet!r}" if snippet else "Syntax error"
+
+
+def ast_grep_errors(root: Any) -> List[ParseError]:
+    """
+    ERROR nodes under an ast-grep root, outermost only, in source order.
+
+    ast-grep reports character columns, 0-based.
+    """
+    errors: List[ParseError] = []
+    end = (-1, -1)
+    for node in sorted(root.find_all(kind="ERROR"), key=lambda n: (n.range().start.line, n.range().start.column)):
+        span = node.range()
+        if (span.start.line, span.start.column) < end:
+            # Inside the previous error
+            continue
+        end = (span.end.line, span.end.column)
+        errors.append(ParseError(_message("error", node.text()), span.start.line + 1, span.start.column + 1,
+                                 span.end.line + 1, span.end.column + 1))
+    return errors
+
+
+def tree_sitter_errors(root: Any, source: bytes) -> List[ParseError]:
+    """
+    ERROR and MISSING nodes under a tree-sitter root, outermost only, in source order.
+
+    Tree-sitter columns are UTF-8 byte offsets; they are turned into
+    character columns with the source the tree was parsed from.
+    """
+    lines = source.split(b"\n")
+
+    def column(point: Any) -> int:
+        row, offset = point[0], point[1]
+        line = lines[row] if row < len(lines) else b""
+        return len(line[:offset].decode("utf-8", errors="replace")) + 1
+
+    errors = []
+    stack = [root]
+    while stack:
+        node = stack.pop()
+        if node.is_missing:
+            errors.append(ParseError(_message("missing", node.type), node.start_point[0] + 1,
+                                     column(node.start_point), node.end_point[0] + 1, column(node.end_point)))
+        elif node.type == "ERROR":
+            text = source[node.start_byte:node.end_byte].decode("utf-8", errors="replace")
+            errors.append(ParseError(_message("error", text), node.start_point[0] + 1,
+                                     column(node.start_point), node.end_point[0] + 1, column(node.end_point)))
+        elif node.has_error:
+            stack.extend(reversed(node.children))
+    return errors
@@ -4,7 +4,11 @@ Maps file extensions to ast-grep language identifiers.
 """
 
 import os
-from typing import Optional
+import re
+from functools import lru_cache
+from typing import Mapping, Optional
+
+from src.ast_parser.path_filter import glob_to_regex
 
 
 # Extension to ast-grep language mapping
@@ -24,23 +28,100 @@ EXT_TO_LANG = {
     ".hpp": "cpp",
     ".rs": "rust",
     ".go": "go",
+    ".rb": "ruby",
+}
+
+
+# Language value asking for detection from the file extension
+AUTO = "auto"
+
+# Extension a language is parsed as when a path does not name one of its own
+LANG_TO_EXT = {
+    "python": ".py",
+    "javascript": ".js",
+    "typescript": ".ts",
+    "java": ".java",
+    "cpp": ".cpp",
+    "c": ".c",
+    "rust": ".rs",
+    "go": ".go",
+    "ruby": ".rb",
 }
 
 
-def detect_language(file_path: str) -> Optional[str]:
+# Extensions more than one language claims: headers are C unless only C++ is enabled
+SHARED_EXTENSIONS = {".h"}
+
+
+@lru_cache(maxsize=None)
+def _override_regex(pattern: str) -> "re.Pattern[str]":
+    return re.compile("(?:.*/)?" + glob_to_regex(pattern.strip("/")))
+
+
+def override_language(file_path: str, overrides: Optional[Mapping[str, str]]) -> Optional[str]:
+    """
+    Language a ``language_overrides`` glob assigns to a path.
+    
+    Globs use .gitignore syntax and match the end of the path, so ``*.h``
+    matches a header anywhere and ``include/*.h`` one directly under any
+    ``include`` directory. The first matching glob wins.
+    
+    Args:
+        file_path: Path to the source file
+        overrides: Language by glob, in the order written
+        
+    Returns:
+        The language of the first matching glob, None when none matches
+    """
+    if not overrides:
+        return None
+    path = file_path.replace(os.sep, "/")
+    for pattern, language in overrides.items():
+        if _override_regex(pattern).fullmatch(path):
+            return language
+    return None
+
+
+def detect_language(file_path: str, overrides: Optional[Mapping[str, str]] = None) -> Optional[str]:
     """
     Detect the programming language from a file extension.
     
     Args:
         file_path: Path to the source file
+        overrides: Language by glob, checked before the extension
         
     Returns:
         Language identifier (e.g., 'python', 'javascript') or None if unsupported
     """
+    language = override_language(file_path, overrides)
+    if language is not None:
+        return language
     ext = os.path.splitext(file_path)[1].lower()
     return EXT_TO_LANG.get(ext)
 
 
+def parsed_extension(file_path: str, overrides: Optional[Mapping[str, str]] = None) -> str:
+    """
+    Extension a file is parsed as, which selects its parser.
+    
+    A file an override assigns to another language, or to one of the
+    languages sharing its extension, is parsed as that language's own
+    extension; any other file keeps its extension.
+    
+    Args:
+        file_path: Path to the source file
+        overrides: Language by glob
+        
+    Returns:
+        The extension, with its dot and in the case written
+    """
+    ext = os.path.splitext(file_path)[1]
+    language = override_language(file_path, overrides)
+    if language is not None and (EXT_TO_LANG.get(ext.lower()) != language or ext.lower() in SHARED_EXTENSIONS):
+        return LANG_TO_EXT[language]
+    return ext
+
+
 def is_supported_extension(file_path: str) -> bool:
     """
     Check if a file extension is supported for parsing.
@@ -0,0 +1,110 @@
+"""
+Blank, comment and code line counts of a source file.
+
+Parsers count the lines once, while they have the source at hand, and keep
+the counts on the File node, so metrics never re-read a file.
+
+Each line is counted once: blank if it holds only whitespace, comment if it
+holds nothing but comments, code otherwise. A line with code and a trailing
+comment is code. Lines inside a multi-line string (a Python docstring, a
+JavaScript template literal) are code. Line endings may be LF, CRLF or CR;
+a CRLF pair ends one line, not two.
+"""
+
+from typing import Dict, Optional, Tuple
+
+# Property names on the File node, in the order they are reported
+LINE_METRICS = ("total_lines", "blank_lines", "comment_lines", "code_lines")
+
+# (line comment, block comment start and end, string quotes, quotes that may span lines) per language
+PYTHON_SYNTAX = ("#", None, ('"""', "'''", '"', "'"), ('"""', "'''"))
+C_SYNTAX = ("//", ("/*", "*/"), ('"', "'"), ())
+COMMENT_SYNTAX = {
+    "python": PYTHON_SYNTAX,
+    "javascript": ("//", ("/*", "*/"), ('"', "'", "`"), ("`",)),
+    "typescript": ("//", ("/*", "*/"), ('"', "'", "`"), ("`",)),
+    "tsx": ("//", ("/*", "*/"), ('"', "'", "`"), ("`",)),
+    # ' also opens lifetimes ('a), which never close
+    "rust": ("//", ("/*", "*/"), ('"',), ()),
+    "go": ("//", ("/*", "*/"), ('"', "'", "`"), ("`",)),
+    "ruby": ("#", ("=begin", "=end"), ('"', "'"), ('"', "'")),
+}
+
+
+def count_lines(source: str, language: Optional[str]) -> Dict[str, int]:
+    """
+    Count the lines of a source file.
+
+    Args:
+        source: File contents
+        language: Language identifier, which picks the comment syntax;
+            C-style comments are assumed for languages not listed
+
+    Returns:
+        Dict with total_lines, blank_lines, comment_lines and code_lines
+    """
+    line_comment, block, quotes, multiline_quotes = COMMENT_SYNTAX.get(language, C_SYNTAX)
+    lines = source.replace("\r\n", "\n").replace("\r", "\n").split("\n")
+    if lines[-1] == "":
+        # The newline ending the last line does not start another one
+        lines.pop()
+
+    counts = dict.fromkeys(LINE_METRICS, 0)
+    counts["total_lines"] = len(lines)
+    # Comment block or multi-line string still open at the start of a line
+    open_token: Optional[str] = None
+    for line in lines:
+        if not line.strip():
+            counts["blank_lines"] += 1
+            continue
+        has_code, has_comment, open_token = _scan(line, open_token, line_comment, block, quotes, multiline_quotes)
+        if has_code:
+            counts["code_lines"] += 1
+        elif has_comment:
+            counts["comment_lines"] += 1
+        else:
+            counts["code_lines"] += 1
+    return counts
+
+
+def _scan(line: str, open_token: Optional[str], line_comment: str, block: Optional[Tuple[str, str]],
+          quotes: Tuple[str, ...], multiline_quotes: Tuple[str, ...]) -> Tuple[bool, bool, Optional[str]]:
+    """Whether a line holds code and comments, and what is still open at its end."""
+    has_code = has_comment = False
+    i = 0
+    while i < len(line):
+        if block is not None and open_token == block[0]:
+            has_comment = True
+            end = line.find(block[1], i)
+            if end < 0:
+                return has_code, has_comment, open_token
+            i, open_token = end + len(block[1]), None
+            continue
+        if open_token is not None:
+            # Inside a string
+            has_code = True
+            if line[i] == "\\":
+                i += 2
+            elif line.startswith(open_token, i):
+                i, open_token = i + len(open_token), None
+            else:
+                i += 1
+            continue
+        if line.startswith(line_comment, i):
+            return has_code, True, None
+        if block is not None and line.startswith(block[0], i):
+            i, open_token = i + len(block[0]), block[0]
+            has_comment = True
+            continue
+        quote = next((q for q in quotes if line.startswith(q, i)), None)
+        if quote is not None:
+            i, open_token = i + len(quote), quote
+            has_code = True
+            continue
+        if not line[i].isspace():
+            has_code = True
+        i += 1
+    if open_token in quotes and open_token not in multiline_quotes:
+        # An unterminated one-line string ends with its line
+        open_token = None
+    return has_code, has_comment, open_token
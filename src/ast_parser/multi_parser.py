@@ -13,13 +13,23 @@ from src.ast_parser.typescript_parser import TypeScriptParser
 from src.ast_parser.adapters.python_adapter import PythonAstGrepAdapter
 from src.ast_parser.adapters.javascript_adapter import JavaScriptAstGrepAdapter
 from src.ast_parser.adapters.java_adapter import JavaAdapter
+from src.ast_parser.adapters.c_adapter import CAdapter
 from src.ast_parser.adapters.cpp_adapter import CppAdapter
 from src.ast_parser.adapters.rust_adapter import RustAdapter
 from src.ast_parser.adapters.go_adapter import GoAdapter
-from src.ast_parser.language_detector import detect_language
+from src.ast_parser.adapters.ruby_adapter import RubyAdapter
+from src.ast_parser.diagnostics import ParseError, ParseReport, flag_parse_error, has_declarations
+from src.ast_parser.language_detector import AUTO, LANG_TO_EXT, detect_language, parsed_extension
+from src.ast_parser.path_filter import PathFilter
+from src.ast_parser.positions import assign_spans, looks_binary, read_source
+from src.ast_parser.qualified_names import assign_qualified_names
+from src.ast_parser.testing import mark_tests, tests_relations
 
 logger = logging.getLogger(__name__)
 
+# Extensions whose parsers pick a grammar (JavaScript, TypeScript, TSX) from the extension
+JS_TS_EXTENSIONS = ['.js', '.jsx', '.ts', '.tsx']
+
 
 class MultiLanguageParser:
     """
@@ -37,7 +47,11 @@ class MultiLanguageParser:
     """
     
     def __init__(self, use_ast_grep: bool = False, ast_grep_languages: Optional[List[str]] = None,
-                 ast_grep_fallback: bool = True):
+                 ast_grep_fallback: bool = True, respect_gitignore: bool = True,
+                 exclude: Optional[List[str]] = None, include: Optional[List[str]] = None,
+                 max_file_size: Optional[int] = None,
+                 language_options: Optional[Dict[str, Dict[str, Any]]] = None,
+                 language_overrides: Optional[Dict[str, str]] = None):
         """
         Initialize the multi-language parser coordinator.
         
@@ -45,10 +59,21 @@ class MultiLanguageParser:
             use_ast_grep: If True, use ast-grep adapters when available
             ast_grep_languages: List of languages to enable for ast-grep (e.g., ['python', 'javascript'])
             ast_grep_fallback: If True, fall back to legacy parsers on error
+            respect_gitignore: If True, skip paths ignored by .gitignore files when walking directories
+            exclude: Extra glob patterns (.gitignore syntax) to skip when walking directories
+            include: Glob patterns (.gitignore syntax) a file must match to be collected; None keeps all
+            max_file_size: Size in bytes above which files are not collected
+            language_options: Parser options by language, e.g. {"rust": {"index_private": False}}
+            language_overrides: Language by glob, parsing matching files as that language
+                whatever their extension, e.g. {"*.h": "cpp"}
         """
         self.use_ast_grep = use_ast_grep
         self.ast_grep_languages = set(ast_grep_languages or ['python', 'javascript', 'typescript'])
         self.ast_grep_fallback = ast_grep_fallback
+        self.language_options = language_options or {}
+        self.language_overrides = language_overrides or {}
+        self.path_filter = PathFilter(respect_gitignore=respect_gitignore, exclude=exclude,
+                                      include=include, max_file_size=max_file_size)
         
         # Aggregated data structures for two-pass parsing
         self.nodes: Dict[str, CodeNode] = {}
@@ -58,6 +83,9 @@ class MultiLanguageParser:
         self.module_to_file: Dict[str, str] = {}
         self.established_relations: set = set()
         
+        # Syntax errors of every file parsed, by path
+        self.report = ParseReport()
+        
         logger.info(f"MultiLanguageParser initialized: use_ast_grep={use_ast_grep}, "
                    f"languages={self.ast_grep_languages}, fallback={ast_grep_fallback}")
     
@@ -72,8 +100,8 @@ class MultiLanguageParser:
         Returns:
             Tuple of (nodes dict, relations list)
         """
-        ext = os.path.splitext(file_path)[1].lower()
-        language = detect_language(file_path)
+        ext = parsed_extension(file_path, self.language_overrides).lower()
+        language = detect_language(file_path, self.language_overrides)
         
         # Select parser based on extension and configuration
         parser = self._get_parser_for_file(file_path, language, ext)
@@ -83,23 +111,18 @@ class MultiLanguageParser:
             return {}, []
         
         try:
-            # Parse the file
-            nodes, relations = parser.parse_file(file_path, build_index=build_index)
-            
-            # Aggregate indices for two-pass resolution
-            if hasattr(parser, 'module_definitions'):
-                self.module_definitions.update(parser.module_definitions)
-            if hasattr(parser, 'pending_imports'):
-                self.pending_imports.extend(parser.pending_imports)
-            if hasattr(parser, 'module_to_file'):
-                self.module_to_file.update(parser.module_to_file)
-            if hasattr(parser, 'established_relations'):
-                self.established_relations.update(parser.established_relations)
-            
-            # Aggregate nodes and relations
-            self.nodes.update(nodes)
-            self.relations.extend(relations)
-            
+            # Parse the file; the JS/TS grammars go by the extension, which an override may have changed
+            if ext in JS_TS_EXTENSIONS and ext != os.path.splitext(file_path)[1].lower():
+                source = read_source(file_path)
+                nodes, relations = parser.parse_source(source, file_path, build_index=build_index, extension=ext)
+            else:
+                nodes, relations = parser.parse_file(file_path, build_index=build_index)
+                source = read_source(file_path)
+            assign_spans(nodes.values(), source)
+            assign_qualified_names(nodes, relations, file_path, language)
+            mark_tests(file_path, nodes, relations, getattr(parser, 'pending_imports', []), language)
+            self._record_diagnostics(file_path, parser, nodes)
+            self._aggregate(parser, nodes, relations)
             return nodes, relations
             
         except Exception as e:
@@ -111,7 +134,117 @@ class MultiLanguageParser:
                 return self._parse_with_fallback(file_path, ext, build_index)
             
             # Otherwise, return empty results
+            return self._record_failure(file_path, e)
+    
+    def parse_source(self, language: Optional[str], source: str, virtual_path: str,
+                     build_index: bool = False) -> Tuple[Dict[str, CodeNode], List[CodeRelation]]:
+        """
+        Parse an in-memory buffer, such as an unsaved editor buffer.
+        
+        Produces the same nodes and relations as ``parse_file`` on a file with
+        that content. ``virtual_path`` is used for node IDs and locations only;
+        nothing is read from the filesystem. Parsing the same virtual path
+        again replaces what the previous parse contributed, so repeated parses
+        of one buffer are idempotent.
+        
+        Args:
+            language: Language identifier (e.g. 'rust'), or "auto"/None to
+                detect it from the extension of virtual_path
+            source: Source text to parse
+            virtual_path: Path the buffer is reported under
+            build_index: If True, build module definition index for cross-file resolution
+            
+        Returns:
+            Tuple of (nodes dict, relations list)
+            
+        Raises:
+            ValueError: If language is not a known language identifier
+        """
+        ext = os.path.splitext(virtual_path)[1].lower()
+        if language in (None, AUTO):
+            language = detect_language(virtual_path)
+        elif language not in LANG_TO_EXT:
+            raise ValueError(f"Unknown language: {language}")
+        elif detect_language(virtual_path) != language:
+            # The path's extension belongs to another language (or none); parse as the one asked for
+            ext = LANG_TO_EXT[language]
+        
+        parser = self._get_parser_for_file(virtual_path, language, ext)
+        if parser is None:
+            logger.warning(f"No parser available for source: {virtual_path}")
             return {}, []
+        
+        options = {"extension": ext} if ext in JS_TS_EXTENSIONS else {}
+        self._forget(virtual_path)
+        try:
+            nodes, relations = parser.parse_source(source, virtual_path, build_index=build_index, **options)
+            assign_spans(nodes.values(), source)
+            assign_qualified_names(nodes, relations, virtual_path, language)
+            mark_tests(virtual_path, nodes, relations, getattr(parser, 'pending_imports', []), language)
+            self._record_diagnostics(virtual_path, parser, nodes)
+            self._aggregate(parser, nodes, relations)
+            return nodes, relations
+            
+        except Exception as e:
+            logger.error(f"Error parsing source {virtual_path}: {e}")
+            
+            if self.ast_grep_fallback and self.use_ast_grep and language in self.ast_grep_languages:
+                logger.warning(f"Falling back to legacy parser for {virtual_path}")
+                return self._parse_with_fallback(virtual_path, ext, build_index, source=source)
+            
+            return self._record_failure(virtual_path, e)
+    
+    def _aggregate(self, parser: Any, nodes: Dict[str, CodeNode], relations: List[CodeRelation]) -> None:
+        """Merge one parser's output and indices into the aggregated structures."""
+        # Aggregate indices for two-pass resolution
+        if hasattr(parser, 'module_definitions'):
+            self.module_definitions.update(parser.module_definitions)
+        if hasattr(parser, 'pending_imports'):
+            self.pending_imports.extend(parser.pending_imports)
+        if hasattr(parser, 'module_to_file'):
+            self.module_to_file.update(parser.module_to_file)
+        if hasattr(parser, 'established_relations'):
+            self.established_relations.update(parser.established_relations)
+        
+        # Aggregate nodes and relations
+        self.nodes.update(nodes)
+        self.relations.extend(relations)
+    
+    def _record_diagnostics(self, file_path: str, parser: Any, nodes: Dict[str, CodeNode]) -> None:
+        """Add the syntax errors the parser met in file_path to the report, and flag its File node."""
+        errors = getattr(parser, 'parse_errors', [])
+        self.report.add(file_path, errors, partial=bool(errors) and has_declarations(nodes.values()))
+        flag_parse_error(file_path, nodes, errors)
+    
+    def _record_failure(self, file_path: str, error: Exception) -> Tuple[Dict[str, CodeNode], List[CodeRelation]]:
+        """Report a file no parser could handle; only its File node, flagged parse_error, is kept."""
+        errors = [ParseError(f"Parser failed: {error}")]
+        self.report.add(file_path, errors, partial=False)
+        nodes: Dict[str, CodeNode] = {}
+        flag_parse_error(file_path, nodes, errors)
+        self.nodes.update(nodes)
+        return nodes, []
+    
+    def _forget(self, file_path: str) -> None:
+        """Drop everything a previous parse of file_path contributed to the aggregates."""
+        node_ids = {node_id for node_id, node in self.nodes.items() if node.file_path == file_path}
+        if not node_ids:
+            return
+        for node_id in node_ids:
+            del self.nodes[node_id]
+        # Resolved edges from other files into this one go too; the next
+        # _process_pending_imports links them to the new nodes
+        self.relations = [r for r in self.relations
+                          if r.source_id not in node_ids and r.target_id not in node_ids]
+        self.pending_imports = [p for p in self.pending_imports if p.get("source_id") not in node_ids]
+        # Keys are "source|type|target[|...]"
+        self.established_relations = {key for key in self.established_relations
+                                      if not node_ids.intersection(key.split("|")[:3:2])}
+        for module_name in [m for m, file_id in self.module_to_file.items() if file_id in node_ids]:
+            del self.module_to_file[module_name]
+        for definitions in self.module_definitions.values():
+            for name in [n for n, node_id in definitions.items() if node_id in node_ids]:
+                del definitions[name]
     
     def _get_parser_for_file(self, file_path: str, language: Optional[str], ext: str):
         """
@@ -129,13 +262,13 @@ class MultiLanguageParser:
         if ext == '.py':
             if self.use_ast_grep and 'python' in self.ast_grep_languages:
                 # Use ast-grep Python adapter
-                return PythonAstGrepAdapter()
+                return PythonAstGrepAdapter(**self.language_options.get('python', {}))
             else:
                 # Use legacy ASTParser
-                return ASTParser()
+                return ASTParser(**self.language_options.get('python', {}))
         
         # JavaScript/TypeScript files
-        elif ext in ['.js', '.jsx', '.ts', '.tsx']:
+        elif ext in JS_TS_EXTENSIONS:
             if self.use_ast_grep and (language in self.ast_grep_languages):
                 # Use ast-grep JavaScript adapter
                 use_tsx = (ext in ['.tsx', '.jsx'])
@@ -152,6 +285,14 @@ class MultiLanguageParser:
                 logger.warning(f"Java parsing requires USE_AST_GREP=true and 'java' in AST_GREP_LANGUAGES")
                 return None
         
+        # C files; headers are C unless only C++ is enabled
+        elif ext == '.c' or (ext == '.h' and 'c' in self.ast_grep_languages):
+            if self.use_ast_grep and 'c' in self.ast_grep_languages:
+                return CAdapter()
+            else:
+                logger.warning(f"C parsing requires USE_AST_GREP=true and 'c' in AST_GREP_LANGUAGES")
+                return None
+        
         # C++ files
         elif ext in ['.cpp', '.cc', '.cxx', '.h', '.hpp']:
             if self.use_ast_grep and 'cpp' in self.ast_grep_languages:
@@ -163,7 +304,7 @@ class MultiLanguageParser:
         # Rust files
         elif ext == '.rs':
             if self.use_ast_grep and 'rust' in self.ast_grep_languages:
-                return RustAdapter()
+                return RustAdapter(**self.language_options.get('rust', {}))
             else:
                 logger.warning(f"Rust parsing requires USE_AST_GREP=true and 'rust' in AST_GREP_LANGUAGES")
                 return None
@@ -176,12 +317,21 @@ class MultiLanguageParser:
                 logger.warning(f"Go parsing requires USE_AST_GREP=true and 'go' in AST_GREP_LANGUAGES")
                 return None
         
+        # Ruby files
+        elif ext == '.rb':
+            if self.use_ast_grep and 'ruby' in self.ast_grep_languages:
+                return RubyAdapter()
+            else:
+                logger.warning(f"Ruby parsing requires USE_AST_GREP=true and 'ruby' in AST_GREP_LANGUAGES")
+                return None
+        
         # Unsupported extension
         else:
             logger.warning(f"Unsupported file extension: {ext} for file {file_path}")
             return None
     
-    def _parse_with_fallback(self, file_path: str, ext: str, build_index: bool) -> Tuple[Dict[str, CodeNode], List[CodeRelation]]:
+    def _parse_with_fallback(self, file_path: str, ext: str, build_index: bool,
+                             source: Optional[str] = None) -> Tuple[Dict[str, CodeNode], List[CodeRelation]]:
         """
         Fall back to legacy parser on error.
         
@@ -189,19 +339,29 @@ class MultiLanguageParser:
             file_path: Path to the source file
             ext: File extension (lowercase, with dot)
             build_index: Whether to build index
+            source: Source text to parse instead of reading file_path
             
         Returns:
             Tuple of (nodes dict, relations list)
         """
         try:
             if ext == '.py':
-                parser = ASTParser()
-            elif ext in ['.js', '.jsx', '.ts', '.tsx']:
+                parser = ASTParser(**self.language_options.get('python', {}))
+                options = {}
+            elif ext in JS_TS_EXTENSIONS:
                 parser = TypeScriptParser()
+                options = {"extension": ext}
             else:
                 return {}, []
             
-            nodes, relations = parser.parse_file(file_path, build_index=build_index)
+            if source is None:
+                nodes, relations = parser.parse_file(file_path, build_index=build_index)
+            else:
+                nodes, relations = parser.parse_source(source, file_path, build_index=build_index, **options)
+            assign_spans(nodes.values(), read_source(file_path) if source is None else source)
+            language = detect_language(file_path, self.language_overrides)
+            assign_qualified_names(nodes, relations, file_path, language)
+            mark_tests(file_path, nodes, relations, parser.pending_imports, language)
             
             # Aggregate indices
             if hasattr(parser, 'module_definitions'):
@@ -210,12 +370,13 @@ class MultiLanguageParser:
                 self.pending_imports.extend(parser.pending_imports)
             if hasattr(parser, 'module_to_file'):
                 self.module_to_file.update(parser.module_to_file)
+            self._record_diagnostics(file_path, parser, nodes)
             
             return nodes, relations
             
         except Exception as e:
             logger.error(f"Fallback parser also failed for {file_path}: {e}")
-            return {}, []
+            return self._record_failure(file_path, e)
     
     def parse_directory(self, directory_path: str, build_index: bool = True) -> Tuple[Dict[str, CodeNode], List[CodeRelation]]:
         """
@@ -231,7 +392,34 @@ class MultiLanguageParser:
         Returns:
             Tuple of (all nodes dict, all relations list)
         """
-        # Collect all source files
+        source_files = self.collect_source_files(directory_path)
+        
+        logger.info(f"Found {len(source_files)} source files to parse")
+        
+        # Parse all files (first pass)
+        for file_path in source_files:
+            self.parse_file(file_path, build_index=build_index)
+        
+        # Second pass: process pending imports
+        if build_index:
+            self._process_pending_imports()
+        
+        return self.nodes, self.relations
+    
+    def collect_source_files(self, directory_path: str) -> List[str]:
+        """
+        List the source files under a directory that this coordinator can parse.
+        
+        Args:
+            directory_path: Path to the directory containing source files
+            
+        Paths ignored by .gitignore or matching an exclude pattern are left
+        out, and ignored directories are not descended into. Binary files
+        with a source extension are skipped without a diagnostic.
+        
+        Returns:
+            List of file paths, in os.walk order
+        """
         source_files = []
         
         # Determine which extensions to collect based on enabled languages
@@ -244,12 +432,16 @@ class MultiLanguageParser:
                 supported_extensions.extend(['.js', '.ts', '.jsx', '.tsx'])
             if 'java' in self.ast_grep_languages:
                 supported_extensions.append('.java')
+            if 'c' in self.ast_grep_languages:
+                supported_extensions.extend(['.c', '.h'])
             if 'cpp' in self.ast_grep_languages:
                 supported_extensions.extend(['.cpp', '.cc', '.cxx', '.h', '.hpp'])
             if 'rust' in self.ast_grep_languages:
                 supported_extensions.append('.rs')
             if 'go' in self.ast_grep_languages:
                 supported_extensions.append('.go')
+            if 'ruby' in self.ast_grep_languages:
+                supported_extensions.append('.rb')
             supported_extensions = tuple(supported_extensions)
         else:
             # Legacy mode: respect ENABLE_JS_TS_PARSING flag
@@ -258,24 +450,16 @@ class MultiLanguageParser:
             js_ts_extensions = (".js", ".ts", ".jsx", ".tsx") if enable_js_ts else ()
             supported_extensions = python_extensions + js_ts_extensions
         
-        # Collect files
-        for root, _, files in os.walk(directory_path):
-            for file_name in files:
-                if file_name.endswith(supported_extensions):
-                    file_path = os.path.join(root, file_name)
-                    source_files.append(file_path)
+        # Collect files, by the extension a language override may substitute
+        for file_path in self.path_filter.walk(directory_path):
+            if parsed_extension(file_path, self.language_overrides).endswith(supported_extensions) \
+                    and not looks_binary(file_path):
+                source_files.append(file_path)
         
-        logger.info(f"Found {len(source_files)} source files to parse")
+        if self.path_filter.skipped:
+            logger.info(f"Skipped {self.path_filter.skipped} ignored or excluded paths under {directory_path}")
         
-        # Parse all files (first pass)
-        for file_path in source_files:
-            self.parse_file(file_path, build_index=build_index)
-        
-        # Second pass: process pending imports
-        if build_index:
-            self._process_pending_imports()
-        
-        return self.nodes, self.relations
+        return source_files
     
     def _process_pending_imports(self):
         """
@@ -297,6 +481,6 @@ class MultiLanguageParser:
         # Process imports (adds import relations to temp_parser.relations)
         temp_parser._process_pending_imports()
         
-        # Update our relations with the combined relations
-        self.relations = temp_parser.relations
+        # Update our relations with the combined relations, and the calls of tests with TESTS edges
+        self.relations = temp_parser.relations + tests_relations(temp_parser.relations, self.nodes)
         self.established_relations = temp_parser.established_relations
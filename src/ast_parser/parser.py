@@ -1,8 +1,26 @@
 import ast
+import inspect
 import os
-from typing import Dict, List, Optional, Tuple, Any, Union, Set
+import textwrap
+from typing import Dict, Iterable, List, Optional, Tuple, Any, Union, Set
 import json
 
+from src.ast_parser.complexity import python_complexity
+from src.ast_parser.diagnostics import ParseError, read_error
+from src.ast_parser.line_metrics import count_lines
+from src.ast_parser.positions import read_text
+from src.ast_parser.workspace import workspace_properties
+
+# 文件註解的大小上限（UTF-8 位元組）
+# Size cap for the doc property, in UTF-8 bytes; longer docs are cut off
+MAX_DOC_BYTES = 4096
+
+# Most syntax errors skipped in one Python file before giving up on it
+MAX_SYNTAX_RECOVERIES = 20
+
+# Keywords that continue a compound statement at the same indentation
+CONTINUATION_KEYWORDS = ("else", "elif", "except", "finally", "case")
+
 
 class CodeNode:
     """代表程式碼中的節點（類別、函數、變數等）"""
@@ -26,6 +44,8 @@ class CodeNode:
         self.end_line_no = end_line_no
         self.properties = properties or {}
         self.code_snippet = ""
+        # Source span with lazily converted columns (positions.SourceSpan); kept in memory only
+        self.span: Optional["SourceSpan"] = None
 
     def __str__(self):
         return f"{self.node_type}:{self.name} ({self.file_path}:{self.line_no})"
@@ -51,17 +71,356 @@ class CodeRelation:
         return f"{self.source_id} -{self.relation_type}-> {self.target_id}"
 
 
+def es_module_name(specifier: str) -> str:
+    """ES 模組路徑對應的模組名稱"""
+    # Module index key for an ES module specifier: "./models.js" -> "models",
+    # "lodash/fp" -> "lodash", "@scope/pkg/sub" -> "@scope/pkg"
+    if specifier.startswith(".") or specifier.startswith("/"):
+        base = os.path.basename(specifier.rstrip("/"))
+        return os.path.splitext(base)[0] if base not in ("", ".", "..") else base
+    parts = specifier.split("/")
+    if specifier.startswith("@") and len(parts) > 1:
+        return "/".join(parts[:2])
+    return parts[0]
+
+
+def python_visibility(name: str, member: bool = False) -> str:
+    """依命名慣例判斷 Python 符號的可見性"""
+    # Python has no access modifiers, so the naming convention decides:
+    # dunders and plain names are Public, a "__name" member is name-mangled
+    # (Private), and a leading underscore marks internal use - Protected on
+    # class members, Private at module level
+    if name.startswith("__") and not name.endswith("__"):
+        return "Private"
+    if name.startswith("_") and not name.startswith("__"):
+        return "Protected" if member else "Private"
+    return "Public"
+
+
+# Decorators that make a method static, a class method or a property
+PYTHON_METHOD_DECORATORS = {
+    "staticmethod": "static",
+    "classmethod": "class",
+    "property": "property",
+    "cached_property": "property",
+    "abstractproperty": "property",
+}
+
+
+def python_method_kind(decorators: List[str]) -> str:
+    """依裝飾器判斷 Python 方法的種類"""
+    # "static", "class" or "property" for a method decorated as one, also
+    # when qualified (functools.cached_property) and for the setter, getter
+    # or deleter of a property (@name.setter); any other method is "instance"
+    for decorator in decorators:
+        name = decorator.split("(", 1)[0].strip()
+        last = name.rsplit(".", 1)[-1]
+        if last in PYTHON_METHOD_DECORATORS:
+            return PYTHON_METHOD_DECORATORS[last]
+        if "." in name and last in ("setter", "getter", "deleter"):
+            return "property"
+    return "instance"
+
+
+def python_import_target(qualified: str) -> Tuple[str, str]:
+    """將導入的完整名稱拆成模組與符號"""
+    # "pkg.models.Base" is Base of the module the definitions index knows by
+    # its file name, "models"; a bare name is its own module
+    parts = qualified.split(".")
+    return (parts[-2] if len(parts) > 1 else parts[0]), parts[-1]
+
+
+# Prefix of a definitions index entry standing for a name an __init__.py
+# re-exports, "reexport:<module>:<name>", until resolve_reexports replaces it
+REEXPORT_PREFIX = "reexport:"
+
+
+def python_module_name(file_path: str, init_reexports: bool = False) -> str:
+    """Python 檔案在定義索引中的模組名稱"""
+    # The file name without extension; with init_reexports an __init__.py
+    # is the package, named after its directory
+    stem = os.path.splitext(os.path.basename(file_path))[0]
+    if init_reexports and stem == "__init__":
+        package = os.path.basename(os.path.dirname(os.path.abspath(file_path)))
+        return package or stem
+    return stem
+
+
+def python_relative_files(file_path: str, level: int, module: Optional[str], name: str) -> Dict[str, List[str]]:
+    """相對導入可能指向的檔案"""
+    # from ..pkg import name in a/b/c.py: the first dot is the file's own
+    # package and each further dot its parent, so the module is a/pkg.py or
+    # a/pkg/__init__.py, and name may also be the submodule a/pkg/name.py
+    directory = os.path.dirname(file_path)
+    for _ in range(level - 1):
+        directory = os.path.dirname(directory)
+    base = os.path.join(directory, *module.split(".")) if module else directory
+    return {
+        "module_files": ([base + ".py"] if module else []) + [os.path.join(base, "__init__.py")],
+        "submodule_files": [os.path.join(base, name + ".py"), os.path.join(base, name, "__init__.py")],
+    }
+
+
+def resolve_reexports(module_definitions: Dict[str, Dict[str, str]]) -> None:
+    """將 __init__.py 重新導出的名稱指向原始定義"""
+    # Replace each re-export entry with the node ID it leads to, following
+    # packages that re-export from packages; entries leading nowhere are dropped
+    for definitions in module_definitions.values():
+        for name, target in list(definitions.items()):
+            seen = set()
+            while target.startswith(REEXPORT_PREFIX) and target not in seen:
+                seen.add(target)
+                module_name, _, symbol = target[len(REEXPORT_PREFIX):].partition(":")
+                target = module_definitions.get(module_name, {}).get(symbol, "")
+            if target and not target.startswith(REEXPORT_PREFIX):
+                definitions[name] = target
+            else:
+                del definitions[name]
+
+
+def python_receiver(function: Union[ast.FunctionDef, ast.AsyncFunctionDef]) -> Optional[str]:
+    """方法中代表實例的參數名稱"""
+    # The first parameter of an instance method or property (usually
+    # "self"); static and class methods have no instance
+    if python_method_kind([ast.unparse(d) for d in function.decorator_list]) not in ("instance", "property"):
+        return None
+    parameters = function.args.posonlyargs + function.args.args
+    return parameters[0].arg if parameters else None
+
+
+def _receiver_attribute(node: ast.AST, receiver: str) -> bool:
+    """Whether a node is receiver.name"""
+    return isinstance(node, ast.Attribute) and isinstance(node.value, ast.Name) and node.value.id == receiver
+
+
+def _assigned_places(target: ast.AST) -> List[ast.AST]:
+    """The places an assignment target writes, unpacking tuples and lists"""
+    if isinstance(target, (ast.Tuple, ast.List)):
+        return [place for element in target.elts for place in _assigned_places(element)]
+    if isinstance(target, ast.Starred):
+        return _assigned_places(target.value)
+    return [target]
+
+
+def python_fields(class_def: ast.ClassDef) -> List[Tuple[str, ast.AST, Optional[str]]]:
+    """類別的實例欄位"""
+    # The attributes annotated in the class body (name: str) and those
+    # assigned through the receiver in its methods (self.name = name), each
+    # at its first declaration. A field assigned straight from an annotated
+    # parameter takes the parameter's annotation as its type. Names assigned
+    # in the class body without annotation are class variables, not fields
+    #
+    # Returns (name, declaring node, type annotation as written) in source order
+    class_variables = {target.id for item in class_def.body if isinstance(item, ast.Assign)
+                       for target in item.targets if isinstance(target, ast.Name)}
+    declarations: List[Tuple[str, ast.AST, Optional[ast.AST]]] = [
+        (item.target.id, item, item.annotation) for item in class_def.body
+        if isinstance(item, ast.AnnAssign) and isinstance(item.target, ast.Name)
+    ]
+    for item in class_def.body:
+        receiver = python_receiver(item) if isinstance(item, (ast.FunctionDef, ast.AsyncFunctionDef)) else None
+        if receiver is None:
+            continue
+        arguments = item.args.posonlyargs + item.args.args + item.args.kwonlyargs
+        annotations = {arg.arg: arg.annotation for arg in arguments if arg.annotation is not None}
+        for statement in ast.walk(item):
+            if isinstance(statement, ast.Assign):
+                unpacked = any(isinstance(target, (ast.Tuple, ast.List)) for target in statement.targets)
+                annotation = annotations.get(statement.value.id) \
+                    if isinstance(statement.value, ast.Name) and not unpacked else None
+                places = [(place, annotation) for target in statement.targets for place in _assigned_places(target)]
+            elif isinstance(statement, ast.AnnAssign):
+                places = [(statement.target, statement.annotation)]
+            else:
+                continue
+            declarations.extend((place.attr, place, annotation) for place, annotation in places
+                                if _receiver_attribute(place, receiver))
+
+    fields: Dict[str, Tuple[str, ast.AST, Optional[str]]] = {}
+    for name, declaration, annotation in sorted(declarations, key=lambda d: (d[1].lineno, d[1].col_offset)):
+        if name not in fields and name not in class_variables:
+            fields[name] = (name, declaration, ast.unparse(annotation) if annotation is not None else None)
+    return list(fields.values())
+
+
+def python_field_accesses(function: Union[ast.FunctionDef, ast.AsyncFunctionDef],
+                          receiver: str) -> List[Tuple[str, int, str]]:
+    """方法中經由實例存取的屬性"""
+    # Every receiver.name in a method, as Read, Write or ReadWrite. As for
+    # Rust, the access is that of the whole place: self.items[i] = v and
+    # self.pos.x = 1 write their field, self.count += 1 reads and writes it,
+    # and everything else, including calling self.callback(), reads it
+    #
+    # Returns (attribute name, line, access) in source order
+    writes: Dict[int, str] = {}
+    for statement in ast.walk(function):
+        if isinstance(statement, ast.Assign):
+            targets, access = statement.targets, "Write"
+        elif isinstance(statement, (ast.AnnAssign, ast.AugAssign)):
+            targets = [statement.target]
+            access = "ReadWrite" if isinstance(statement, ast.AugAssign) else "Write"
+        elif isinstance(statement, ast.Delete):
+            targets, access = statement.targets, "Write"
+        else:
+            continue
+        for place in (place for target in targets for place in _assigned_places(target)):
+            while isinstance(place, (ast.Attribute, ast.Subscript)) and not _receiver_attribute(place, receiver):
+                place = place.value
+            if _receiver_attribute(place, receiver):
+                writes[id(place)] = access
+
+    accesses = [(node.attr, node.lineno, writes.get(id(node), "Read"))
+                for node in ast.walk(function) if _receiver_attribute(node, receiver)]
+    return sorted(accesses, key=lambda access: access[1])
+
+
+def cap_doc(doc: Optional[str]) -> Optional[str]:
+    """將文件註解截斷至大小上限"""
+    # Cut a doc down to MAX_DOC_BYTES, ending it with an ellipsis when shortened
+    if not doc:
+        return None
+    data = doc.encode("utf-8")
+    if len(data) <= MAX_DOC_BYTES:
+        return doc
+    cut = data[:MAX_DOC_BYTES - len("…".encode("utf-8"))].decode("utf-8", errors="ignore")
+    return cut.rstrip() + "…"
+
+
+def clean_docstring(docstring: Optional[str]) -> Optional[str]:
+    """清理 Python 文件字串的縮排"""
+    # Dedent a docstring as inspect.cleandoc does, then cap it
+    if docstring is None:
+        return None
+    return cap_doc(inspect.cleandoc(docstring).strip())
+
+
+def clean_doc_comment(comments: List[str]) -> Optional[str]:
+    """去除文件註解的註解符號，保留段落分隔"""
+    # Join the raw doc comments in front of a declaration into its doc:
+    # "///" and "//!" lines, "/** */" and "/*! */" blocks (with their
+    # leading "*" on each line) and plain text such as #[doc = "..."]
+    # values. Markers and common indentation are removed, paragraphs stay
+    # separated by one blank line.
+    lines: List[str] = []
+    for comment in comments:
+        if comment.startswith(("///", "//!")):
+            line = comment[3:].rstrip("\r\n")
+            lines.append(line[1:] if line.startswith(" ") else line)
+        elif comment.startswith(("/**", "/*!")):
+            body = comment[3:]
+            body = body[:-2] if body.endswith("*/") else body
+            for line in body.splitlines():
+                stripped = line.lstrip()
+                if stripped.startswith("*"):
+                    stripped = stripped[1:]
+                    line = stripped[1:] if stripped.startswith(" ") else stripped
+                lines.append(line)
+        else:
+            lines.extend(comment.splitlines())
+
+    text = textwrap.dedent("\n".join(line.rstrip() for line in lines))
+    paragraphs, blank = [], False
+    for line in text.splitlines():
+        if not line.strip():
+            blank = bool(paragraphs)
+            continue
+        if blank:
+            paragraphs.append("")
+            blank = False
+        paragraphs.append(line)
+    return cap_doc("\n".join(paragraphs))
+
+
+# Node types whose public declarations are expected to have a doc
+DOCUMENTED_TYPES = ("Function", "Method", "Class", "Interface", "Enum")
+
+
+def set_doc(node: CodeNode, doc: Optional[str]) -> None:
+    """記錄節點的文件註解，以及公開符號是否有文件"""
+    # Public functions, methods and types also get a documented flag, so
+    # the undocumented ones can be listed; a trait impl method is documented
+    # by the trait, as rustdoc does
+    if doc:
+        node.properties["doc"] = doc
+    if node.node_type in DOCUMENTED_TYPES and node.properties.get("visibility") == "Public" \
+            and "impl_trait" not in node.properties:
+        node.properties["documented"] = bool(doc)
+
+
+def _top_level_block(lines: List[str], line_no: int) -> Tuple[int, int]:
+    """0-based start and end (exclusive) of the top-level statement holding a 1-based line."""
+    def starts_statement(line: str) -> bool:
+        return bool(line.strip()) and not line[0].isspace() and not line.startswith("#")
+
+    index = min(max(line_no, 1), len(lines)) - 1
+    start = index
+    while start > 0 and not starts_statement(lines[start]):
+        start -= 1
+    # Decorators belong to the definition below them
+    while start > 0 and lines[start - 1].startswith("@"):
+        start -= 1
+    end = index + 1
+    while end < len(lines) and not (starts_statement(lines[end])
+                                    and not lines[end].lstrip().startswith(CONTINUATION_KEYWORDS)):
+        end += 1
+    return start, end
+
+
+def parse_python(source: str) -> Tuple[Optional[ast.Module], List[ParseError]]:
+    """
+    Parse Python source, skipping the top-level statements that do not parse.
+
+    On a syntax error the top-level statement holding it (a whole function
+    or class, say) is replaced by blank lines, keeping every other line
+    where it was, and the source is parsed again.
+
+    Returns:
+        The tree of what parsed, or None when nothing could be recovered,
+        and the syntax errors in the order they were found
+    """
+    lines = source.splitlines(keepends=True)
+    errors: List[ParseError] = []
+    for _ in range(MAX_SYNTAX_RECOVERIES + 1):
+        try:
+            return ast.parse("".join(lines)), errors
+        except SyntaxError as e:
+            errors.append(ParseError(e.msg, e.lineno, e.offset,
+                                     getattr(e, "end_lineno", None), getattr(e, "end_offset", None)))
+            if not e.lineno or not lines:
+                return None, errors
+            start, end = _top_level_block(lines, e.lineno)
+            if not any(line.strip() for line in lines[start:end]):
+                return None, errors
+            lines[start:end] = ["\n" if line.endswith(("\n", "\r")) else "" for line in lines[start:end]]
+    return None, errors
+
+
 class ASTParser:
     """使用 Python AST 模組解析程式碼的解析器"""
     # Parser that uses the Python AST module to parse code
 
-    def __init__(self):
+    def __init__(self, init_reexports: bool = False):
+        """
+        Args:
+            init_reexports: Index an __init__.py under its package's name, with
+                the names it imports from its submodules as definitions of the package
+        """
+        self.init_reexports = init_reexports
         self.nodes: Dict[str, CodeNode] = {}
         self.relations: List[CodeRelation] = []
         self.current_file: str = ""
+        # 目前檔案在定義索引中的模組名稱，未建立索引時為空
+        # Module name of the current file in the definitions index; empty when not indexing
+        self.current_module: str = ""
         self.current_function: Optional[str] = None
         self.current_class: Optional[str] = None
+        # 目前類別的欄位（名稱 -> 節點 ID）
+        # Fields of the current class, name -> node ID
+        self.current_fields: Dict[str, str] = {}
         self.imports: Dict[str, str] = {}
+        # 目前檔案的原始碼行，用於記錄調用位置
+        # Source lines of the current file, used to record call sites
+        self.source_lines: List[str] = []
         # 用於追蹤模組中的定義
         # Used to track definitions within modules
         self.module_definitions: Dict[str, Dict[str, str]] = {}
@@ -74,6 +433,12 @@ class ASTParser:
         # 用於追蹤已建立的關係，避免重複
         # Used to track established relationships to avoid duplication
         self.established_relations: Set[str] = set()
+        # Syntax errors of the file parsed last
+        self.parse_errors: List[ParseError] = []
+        # Top-level definitions by file node ID, built when a relative import is resolved
+        self._file_definitions: Optional[Dict[str, Dict[str, str]]] = None
+        # File node IDs by base name, built when a C/C++ include or Ruby require is resolved
+        self._files_by_name: Optional[Dict[str, List[str]]] = None
 
     def parse_directory(self, directory_path: str) -> Tuple[Dict[str, CodeNode], List[CodeRelation]]:
         """解析目錄中的所有Python檔案"""
@@ -102,29 +467,44 @@ class ASTParser:
     def parse_file(self, file_path: str, build_index: bool = False) -> Tuple[Dict[str, CodeNode], List[CodeRelation]]:
         """解析單個Python檔案"""
         # Parses a single Python file
+        try:
+            file_content = read_text(file_path)
+        except (OSError, UnicodeDecodeError) as e:
+            print(f"Error reading file {file_path}: {e}")
+            self.parse_errors = [read_error(e)]
+            return {}, []
+
+        return self.parse_source(file_content, file_path, build_index=build_index)
+
+    def parse_source(self, file_content: str, file_path: str, build_index: bool = False) -> Tuple[Dict[str, CodeNode], List[CodeRelation]]:
+        """解析記憶體中的Python原始碼"""
+        # Parses Python source text; file_path is only used for node IDs and locations
         # print(f"解析檔案: {file_path}")
         # Disabled Chinese log above.
         print(f"Parsing file: {file_path}")
         self.current_file = file_path
         self.imports = {}
+        self.source_lines = file_content.splitlines()
 
         try:
-            with open(file_path, "r", encoding="utf-8") as file:
-                file_content = file.read()
-                tree = ast.parse(file_content)
-                file_node_id = self._create_file_node(file_path)
-                
-                # 生成模組名稱，用於索引
-                # Generate module name for indexing
-                module_name = os.path.splitext(os.path.basename(file_path))[0]
-                if build_index:
-                    if module_name not in self.module_definitions:
-                        self.module_definitions[module_name] = {}
-                    # 關聯模組名稱與檔案節點
-                    # Associate module name with file node
-                    self.module_to_file[module_name] = file_node_id
-                
-                self._parse_ast(tree, build_index, module_name)
+            tree, self.parse_errors = parse_python(file_content)
+            if tree is None:
+                print(f"Error parsing file {file_path}: {self.parse_errors[-1].message}")
+                return {}, []
+            file_node_id = self._create_file_node(file_path, file_content)
+            
+            # 生成模組名稱，用於索引
+            # Generate module name for indexing
+            module_name = python_module_name(file_path, self.init_reexports)
+            self.current_module = module_name if build_index else ""
+            if build_index:
+                if module_name not in self.module_definitions:
+                    self.module_definitions[module_name] = {}
+                # 關聯模組名稱與檔案節點
+                # Associate module name with file node
+                self.module_to_file[module_name] = file_node_id
+            
+            self._parse_ast(tree, build_index, module_name)
 
             return self.nodes, self.relations
         except Exception as e:
@@ -132,11 +512,12 @@ class ASTParser:
             # Disabled Chinese error log above.
             print(f"Error parsing file {file_path}: {e}")
             # Error parsing file {file_path}: {e}
+            self.parse_errors = [ParseError(f"Parser failed: {e}")]
             return {}, []
 
-    def _create_file_node(self, file_path: str) -> str:
+    def _create_file_node(self, file_path: str, source: str = "") -> str:
         """創建檔案節點"""
-        # Creates a file node
+        # Creates a file node, with the line counts of its source
         file_name = os.path.basename(file_path)
         node_id = f"file:{file_path}"
         self.nodes[node_id] = CodeNode(
@@ -145,6 +526,7 @@ class ASTParser:
             name=file_name,
             file_path=file_path,
             line_no=0,
+            properties={**count_lines(source, "python"), **workspace_properties(file_path)},
         )
         return node_id
 
@@ -153,6 +535,11 @@ class ASTParser:
         # Generates a unique identifier for the node
         return f"{node_type}:{file_path}:{name}:{line_no}"
 
+    def _set_decorators(self, node_id: str, node: ast.AST) -> None:
+        """Record the decorators of a class or function as written, without the ``@``."""
+        if node.decorator_list:
+            self.nodes[node_id].properties["decorators"] = [ast.unparse(d) for d in node.decorator_list]
+
     def _parse_ast(self, tree: ast.AST, build_index: bool = False, module_name: str = "") -> None:
         """遞迴解析AST樹狀結構"""
         # Recursively parses the AST tree structure
@@ -161,7 +548,7 @@ class ASTParser:
                 node_id = self._parse_class(node)
                 if build_index and module_name:
                     self.module_definitions[module_name][node.name] = node_id
-            elif isinstance(node, ast.FunctionDef):
+            elif isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef)):
                 node_id = self._parse_function(node)
                 if build_index and module_name:
                     self.module_definitions[module_name][node.name] = node_id
@@ -187,7 +574,10 @@ class ASTParser:
             file_path=self.current_file,
             line_no=node.lineno,
             end_line_no=getattr(node, "end_lineno", None),
+            properties={"visibility": python_visibility(node.name)},
         )
+        self._set_decorators(node_id, node)
+        set_doc(self.nodes[node_id], clean_docstring(ast.get_docstring(node)))
         
         # 創建檔案包含類別的關係
         # Create relationship that file contains class
@@ -203,17 +593,30 @@ class ASTParser:
         # 處理類別繼承
         # Process class inheritance
         for base in node.bases:
-            if isinstance(base, ast.Name):
+            if isinstance(base, ast.Attribute) and isinstance(base.value, ast.Name) \
+                    and base.value.id in self.imports:
+                # 基底類別來自導入的模組（例如 models.Base）
+                # Base class of an imported module (models.Base)
+                imported_module, imported_name = python_import_target(
+                    f"{self.imports[base.value.id]}.{base.attr}")
+                self.pending_imports.append({
+                    "type": "EXTENDS",
+                    "source_id": node_id,
+                    "imported_module": imported_module,
+                    "imported_name": imported_name,
+                    "original_name": f"{base.value.id}.{base.attr}"
+                })
+            elif isinstance(base, ast.Name):
                 base_name = base.id
                 if base_name in self.imports:
                     # 將此繼承關係添加到待處理隊列
                     # Add this inheritance relationship to the pending queue
+                    imported_module, imported_name = python_import_target(self.imports[base_name])
                     self.pending_imports.append({
                         "type": "EXTENDS",
                         "source_id": node_id,
-                        "imported_module": self.imports[base_name].split(".")[0],
-                        "imported_name": self.imports[base_name].split(".")[-1] 
-                            if "." in self.imports[base_name] else self.imports[base_name],
+                        "imported_module": imported_module,
+                        "imported_name": imported_name,
                         "original_name": base_name
                     })
                 else:
@@ -230,11 +633,13 @@ class ASTParser:
         # 設置當前類別上下文
         # Set current class context
         self.current_class = node_id
+        prev_fields = self.current_fields
+        self.current_fields = self._parse_fields(node, node_id)
         
         # 解析類別內部成員
         # Parse class members
         for item in node.body:
-            if isinstance(item, ast.FunctionDef):
+            if isinstance(item, (ast.FunctionDef, ast.AsyncFunctionDef)):
                 self._parse_method(item)
             elif isinstance(item, ast.Assign):
                 self._parse_class_attribute(item)
@@ -242,10 +647,11 @@ class ASTParser:
         # 恢復上下文
         # Restore context
         self.current_class = prev_class
+        self.current_fields = prev_fields
         
         return node_id
 
-    def _parse_method(self, node: ast.FunctionDef) -> None:
+    def _parse_method(self, node: Union[ast.FunctionDef, ast.AsyncFunctionDef]) -> None:
         """解析類別方法"""
         # Parses class methods
         node_id = self._get_node_id("Method", node.name, self.current_file, node.lineno)
@@ -259,8 +665,11 @@ class ASTParser:
             file_path=self.current_file,
             line_no=node.lineno,
             end_line_no=getattr(node, "end_lineno", None),
-            properties={"is_method": True},
+            properties={"is_method": True, "visibility": python_visibility(node.name, member=True),
+                        "method_kind": python_method_kind([ast.unparse(d) for d in node.decorator_list]),
+                        "complexity": python_complexity(node)},
         )
+        self._set_decorators(node_id, node)
         
         # 創建類別定義方法的關係
         # Create relationship that class defines method
@@ -285,19 +694,55 @@ class ASTParser:
         # Parse function body
         # 取得文檔字串（兼容不同 Python 版本）
         # Retrieve the docstring (compatible across Python versions)
-        doc = ast.get_docstring(node)
-        if doc:
-            self.nodes[node_id].properties["docstring"] = doc
+        set_doc(self.nodes[node_id], clean_docstring(ast.get_docstring(node)))
 
         # 尋找函數調用
         # Find function calls in the body
         for item in node.body:
             self._find_function_calls(item)
         
+        # 尋找經由 self 的欄位存取
+        # Find the field accesses through self
+        receiver = python_receiver(node)
+        if receiver is not None:
+            self._add_field_accesses(node, node_id, receiver)
+        
         # 恢復上下文
         self.current_function = prev_function
 
-    def _parse_function(self, node: ast.FunctionDef) -> str:
+    def _parse_fields(self, node: ast.ClassDef, class_id: str) -> Dict[str, str]:
+        """解析類別欄位"""
+        # Field nodes the class DEFINES, one per instance attribute (see
+        # python_fields); returns their IDs by name
+        fields = {}
+        for name, declaration, field_type in python_fields(node):
+            node_id = self._get_node_id("Field", name, self.current_file, declaration.lineno)
+            self.nodes[node_id] = CodeNode(
+                node_id=node_id,
+                node_type="Field",
+                name=name,
+                file_path=self.current_file,
+                line_no=declaration.lineno,
+                end_line_no=getattr(declaration, "end_lineno", None),
+                properties={"field_of": node.name, "type": field_type,
+                            "visibility": python_visibility(name, member=True)},
+            )
+            self.relations.append(CodeRelation(source_id=class_id, target_id=node_id, relation_type="DEFINES"))
+            fields[name] = node_id
+        return fields
+
+    def _add_field_accesses(self, node: Union[ast.FunctionDef, ast.AsyncFunctionDef], method_id: str,
+                            receiver: str) -> None:
+        """記錄方法對類別欄位的存取"""
+        # REFERENCES edges from a method to the fields of its class it
+        # reaches through self; attributes that are not fields of the class
+        # (methods, inherited attributes) stay unresolved
+        for name, line_no, access in python_field_accesses(node, receiver):
+            if name in self.current_fields:
+                self._add_relation(CodeRelation(method_id, self.current_fields[name], "REFERENCES",
+                                                properties={"access": access, **self._call_site(line_no)}))
+
+    def _parse_function(self, node: Union[ast.FunctionDef, ast.AsyncFunctionDef]) -> str:
         """解析函數定義"""
         # Parse a top-level function definition
         node_id = self._get_node_id("Function", node.name, self.current_file, node.lineno)
@@ -311,8 +756,10 @@ class ASTParser:
             file_path=self.current_file,
             line_no=node.lineno,
             end_line_no=getattr(node, "end_lineno", None),
-            properties={"is_method": False},
+            properties={"is_method": False, "visibility": python_visibility(node.name),
+                        "complexity": python_complexity(node)},
         )
+        self._set_decorators(node_id, node)
 
         # 創建檔案包含函數的關係
         # Create relationship that file contains function
@@ -338,9 +785,7 @@ class ASTParser:
         # Parse function body
         # 取得文檔字串（兼容不同 Python 版本）
         # Retrieve the docstring (compatible across Python versions)
-        doc = ast.get_docstring(node)
-        if doc:
-            self.nodes[node_id].properties["docstring"] = doc
+        set_doc(self.nodes[node_id], clean_docstring(ast.get_docstring(node)))
 
         # 尋找函數調用
         # Find function calls in the body
@@ -353,7 +798,43 @@ class ASTParser:
 
         return node_id
 
-    def _parse_function_args(self, node: ast.FunctionDef, node_id: str) -> None:
+    def _parse_nested_function(self, node: Union[ast.FunctionDef, ast.AsyncFunctionDef]) -> None:
+        """解析巢狀函數（閉包）"""
+        # A function defined in the body of another one is a Function node
+        # the enclosing function or method DEFINES. It is local to that body,
+        # so Private, and the calls in its own body are its calls; its
+        # decorators, defaults and annotations run in the enclosing scope
+        for item in [*node.decorator_list, node.args] + ([node.returns] if node.returns else []):
+            self._find_function_calls(item)
+
+        node_id = self._get_node_id("Function", node.name, self.current_file, node.lineno)
+        self.nodes[node_id] = CodeNode(
+            node_id=node_id,
+            node_type="Function",
+            name=node.name,
+            file_path=self.current_file,
+            line_no=node.lineno,
+            end_line_no=getattr(node, "end_lineno", None),
+            properties={"is_method": False, "visibility": "Private", "complexity": python_complexity(node)},
+        )
+        self._set_decorators(node_id, node)
+        set_doc(self.nodes[node_id], clean_docstring(ast.get_docstring(node)))
+        self.relations.append(
+            CodeRelation(
+                source_id=self.current_function,
+                target_id=node_id,
+                relation_type="DEFINES",
+            )
+        )
+        self._parse_function_args(node, node_id)
+
+        prev_function = self.current_function
+        self.current_function = node_id
+        for item in node.body:
+            self._find_function_calls(item)
+        self.current_function = prev_function
+
+    def _parse_function_args(self, node: Union[ast.FunctionDef, ast.AsyncFunctionDef], node_id: str) -> None:
         """解析函數參數"""
         args = []
         
@@ -409,6 +890,7 @@ class ASTParser:
         
         elif isinstance(node, ast.ImportFrom):
             module_name = node.module
+            written_module = "." * node.level + (module_name or "") if node.level else module_name
             for name in node.names:
                 import_name = name.name
                 asname = name.asname or import_name
@@ -423,13 +905,29 @@ class ASTParser:
                 
                 # 添加到待處理的導入依賴
                 # Add to pending import dependencies (symbol import)
-                self.pending_imports.append({
+                import_info = {
                     "type": "IMPORTS_SYMBOL",
                     "source_id": file_node_id,
-                    "imported_module": module_name,
+                    "imported_module": written_module,
                     "imported_name": import_name,
                     "alias": asname
-                })
+                }
+                if node.level and import_name != "*":
+                    # 相對導入依套件目錄解析
+                    # Relative imports resolve against the package directory
+                    import_info.update(python_relative_files(self.current_file, node.level, module_name, import_name))
+                self.pending_imports.append(import_info)
+                
+                if self._reexports() and module_name and import_name != "*":
+                    # The package defines the name as far as its importers are concerned
+                    self.module_definitions[self.current_module].setdefault(
+                        asname, f"{REEXPORT_PREFIX}{module_name.rsplit('.', 1)[-1]}:{import_name}")
+
+    def _reexports(self) -> bool:
+        """Whether imports seen now are re-exports of the package being indexed."""
+        return self.init_reexports and bool(self.current_module) \
+            and os.path.basename(self.current_file) == "__init__.py" \
+            and self.current_class is None and self.current_function is None
 
     def _parse_assignment(self, node: ast.Assign) -> None:
         """解析賦值語句"""
@@ -525,8 +1023,12 @@ class ASTParser:
     def _find_function_calls(self, node: ast.AST) -> None:
         """在AST節點中尋找函數調用"""
         # Search for function calls in AST nodes
+        if isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef)) and self.current_function:
+            self._parse_nested_function(node)
+            return
         if isinstance(node, ast.Call):
             func = node.func
+            call_site = self._call_site(node.lineno)
             
             if isinstance(func, ast.Name):
                 # 直接函數調用
@@ -547,7 +1049,8 @@ class ASTParser:
                                 if "." in imported_func else imported_func,
                             "imported_name": imported_func.split(".")[-1] 
                                 if "." in imported_func else imported_func,
-                            "original_name": func_name
+                            "original_name": func_name,
+                            **call_site
                         })
                 elif self.current_function:
                     # 處理本地函數調用
@@ -558,6 +1061,7 @@ class ASTParser:
                             target_id=f"Function:{self.current_file}:{func_name}:0",  # 假設的目標ID
                             # Assumed target ID
                             relation_type="CALLS",
+                            properties=dict(call_site),
                         )
                     )
             
@@ -583,7 +1087,8 @@ class ASTParser:
                                 "imported_class": imported_obj.split(".")[-1] 
                                     if "." in imported_obj else imported_obj,
                                 "method_name": method_name,
-                                "original_obj_name": obj_name
+                                "original_obj_name": obj_name,
+                                **call_site
                             })
                     elif self.current_function:
                         # 處理本地物件方法調用
@@ -593,7 +1098,7 @@ class ASTParser:
                                 source_id=self.current_function,
                                 target_id=f"Method:{self.current_file}:{method_name}:0",  # 假設的目標ID
                                 relation_type="CALLS",
-                                properties={"object": obj_name},
+                                properties={"object": obj_name, **call_site},
                             )
                         )
         
@@ -602,6 +1107,81 @@ class ASTParser:
         for child in ast.iter_child_nodes(node):
             self._find_function_calls(child)
             
+    def _call_site(self, line_no: int) -> Dict[str, Any]:
+        """記錄調用位置"""
+        # Line number and source text of a call, stored on CALLS relations
+        site: Dict[str, Any] = {"line_no": line_no}
+        if 0 < line_no <= len(self.source_lines):
+            site["call_site"] = self.source_lines[line_no - 1].strip()
+        return site
+
+    def _resolve_relative_import(self, import_info: Dict[str, Any], processed_modules: Set[str]) -> None:
+        """解析 Python 相對導入"""
+        # from .mod import name: the name defined at the top of mod.py (or
+        # mod/__init__.py) the import leads to, or else the submodule of that
+        # name; files are found by path, so modules sharing a name do not clash
+        source_id = import_info["source_id"]
+        symbol_name = import_info["imported_name"]
+        module_file_id = self._first_file(import_info["module_files"])
+        target_id = self._top_level_definitions(module_file_id).get(symbol_name) if module_file_id else None
+        if target_id is None:
+            submodule_file_id = self._first_file(import_info["submodule_files"])
+            if submodule_file_id and submodule_file_id != source_id:
+                self._add_relation(
+                    CodeRelation(
+                        source_id=source_id,
+                        target_id=submodule_file_id,
+                        relation_type="IMPORTS_FROM",
+                        properties={
+                            "module": import_info["imported_module"],
+                            "full_module_path": f"{import_info['imported_module'].rstrip('.')}.{symbol_name}",
+                            "alias": import_info.get("alias")
+                        }
+                    )
+                )
+                return
+        else:
+            self._add_relation(
+                CodeRelation(
+                    source_id=source_id,
+                    target_id=target_id,
+                    relation_type="IMPORTS_DEFINITION",
+                    properties={
+                        "module": import_info["imported_module"],
+                        "symbol": symbol_name,
+                        "alias": import_info.get("alias")
+                    }
+                )
+            )
+        if module_file_id and module_file_id != source_id and module_file_id not in processed_modules:
+            processed_modules.add(module_file_id)
+            self._add_relation(
+                CodeRelation(
+                    source_id=source_id,
+                    target_id=module_file_id,
+                    relation_type="IMPORTS_FROM",
+                    properties={
+                        "module": import_info["imported_module"],
+                        "imports_symbols": [symbol_name]
+                    }
+                )
+            )
+
+    def _first_file(self, paths: List[str]) -> Optional[str]:
+        """File node ID of the first path that was parsed."""
+        return next((f"file:{path}" for path in paths if f"file:{path}" in self.nodes), None)
+
+    def _top_level_definitions(self, file_node_id: str) -> Dict[str, str]:
+        """Names a file defines at top level, with their node IDs."""
+        if self._file_definitions is None:
+            self._file_definitions = {}
+            for relation in self.relations:
+                if relation.relation_type in ("CONTAINS", "DEFINES") and relation.source_id.startswith("file:") \
+                        and relation.target_id in self.nodes:
+                    self._file_definitions.setdefault(relation.source_id, {}).setdefault(
+                        self.nodes[relation.target_id].name, relation.target_id)
+        return self._file_definitions.get(file_node_id, {})
+
     def _add_relation(self, relation: CodeRelation) -> None:
         """添加關係，避免重複"""
         # 創建關係的唯一標識
@@ -618,6 +1198,12 @@ class ASTParser:
             # For symbol imports, include the symbol name in the key
             symbol = relation.properties.get("symbol", "")
             relation_key = f"{relation.source_id}|{relation.relation_type}|{relation.target_id}|{symbol}"
+        elif relation.relation_type == "REFERENCES":
+            # Every access site is a separate reference
+            relation_key += f"|{relation.properties.get('line_no', '')}|{relation.properties.get('access', '')}"
+        elif relation.relation_type == "AR_ASSOCIATION":
+            # has_one :author and has_many :reviews may both name the same model
+            relation_key += f"|{relation.properties.get('name', '')}"
         
         # 檢查是否已經存在相同的關係
         # Check whether the same relation already exists
@@ -631,6 +1217,14 @@ class ASTParser:
         # print(f"處理跨檔案依賴關係，共 {len(self.pending_imports)} 項")
         # Disabled Chinese log above.
         print(f"Processing cross-file dependencies, total {len(self.pending_imports)} items")
+        self._prepare_pending_imports()
+        self._resolve_pending_imports(self.pending_imports)
+
+    def _prepare_pending_imports(self) -> None:
+        """Finish the module index before any pending entry is resolved."""
+        resolve_reexports(self.module_definitions)
+        self._file_definitions = None
+        self._files_by_name = None
         
         # 先創建所有模組節點，將它們與檔案節點關聯
         # First create all module nodes and associate them with file nodes
@@ -641,11 +1235,13 @@ class ASTParser:
             if file_node_id in self.nodes:
                 file_node = self.nodes[file_node_id]
                 file_node.properties["module_name"] = module_name
-        
+
+    def _resolve_pending_imports(self, pending_imports: Iterable[Dict[str, Any]]) -> None:
+        """Resolve pending entries; a source's entries share the modules already linked."""
         # 按模組分組處理導入信息
         # Group pending import information by source module/file
         imports_by_source_module = {}
-        for import_info in self.pending_imports:
+        for import_info in pending_imports:
             source_id = import_info["source_id"]
             if source_id not in imports_by_source_module:
                 imports_by_source_module[source_id] = []
@@ -659,163 +1255,806 @@ class ASTParser:
             processed_modules = set()
             
             for import_info in imports:
-                import_type = import_info["type"]
+                self._resolve_pending_import(import_info, processed_modules)
+
+    def _resolve_pending_import(self, import_info: Dict[str, Any], processed_modules: Set[str]) -> None:
+        """解析單一待處理的導入依賴"""
+        # Resolve a single pending entry; processed_modules is shared by entries of the same source
+        source_id = import_info["source_id"]
+        import_type = import_info["type"]
+        
+        if import_type == "IMPORTS_MODULE":
+            # 檔案導入整個模組的情況
+            # Case: the file imports a module
+            module_name = import_info["imported_module"]
+            
+            # A C/C++ system include (#include <stdio.h>) is recorded but never resolved
+            if import_info.get("system"):
+                return
+            if "system" in import_info:
+                self._resolve_include(import_info, processed_modules)
+                return
+            
+            # 避免重複處理相同模組的導入
+            # Avoid processing the same module import multiple times
+            if module_name in processed_modules:
+                return
+            processed_modules.add(module_name)
+            
+            # 查找模組對應的檔案節點
+            # Find the file node corresponding to the module
+            if module_name in self.module_to_file:
+                target_file_id = self.module_to_file[module_name]
                 
-                if import_type == "IMPORTS_MODULE":
-                    # 檔案導入整個模組的情況
-                    # Case: the file imports a module
-                    module_name = import_info["imported_module"]
-                    
-                    # 避免重複處理相同模組的導入
-                    # Avoid processing the same module import multiple times
-                    if module_name in processed_modules:
-                        continue
+                # 創建檔案間依賴關係
+                # Create a file-to-file dependency relation
+                self._add_relation(
+                    CodeRelation(
+                        source_id=source_id,
+                        target_id=target_file_id,
+                        relation_type="IMPORTS_FROM",
+                        properties={
+                            "module": module_name,
+                            "full_module_path": import_info.get("full_module_path", module_name)
+                        }
+                    )
+                )
+        
+        elif import_type == "IMPORTS_SYMBOL" and "module_files" in import_info:
+            self._resolve_relative_import(import_info, processed_modules)
+
+        elif import_type == "IMPORTS_SYMBOL":
+            # 從模組導入特定符號的情況
+            # Case: importing a specific symbol from a module
+            module_name = import_info["imported_module"]
+            symbol_name = import_info["imported_name"]
+            
+            # 檢查模組定義索引
+            # Check module definitions index
+            if module_name in self.module_definitions and symbol_name in self.module_definitions[module_name]:
+                target_node_id = self.module_definitions[module_name][symbol_name]
+                
+                # 創建檔案到符號的依賴關係
+                # Create a dependency from the source file to the symbol node
+                self._add_relation(
+                    CodeRelation(
+                        source_id=source_id,
+                        target_id=target_node_id,
+                        relation_type="IMPORTS_DEFINITION",
+                        properties={
+                            "module": module_name,
+                            "symbol": symbol_name,
+                            "alias": import_info.get("alias")
+                        }
+                    )
+                )
+                
+                # 避免為已處理的模組重複創建IMPORTS_FROM關係
+                # Avoid creating duplicate IMPORTS_FROM relations for the same module
+                if module_name not in processed_modules and module_name in self.module_to_file:
                     processed_modules.add(module_name)
                     
-                    # 查找模組對應的檔案節點
-                    # Find the file node corresponding to the module
-                    if module_name in self.module_to_file:
-                        target_file_id = self.module_to_file[module_name]
-                        
-                        # 創建檔案間依賴關係
-                        # Create a file-to-file dependency relation
-                        self._add_relation(
-                            CodeRelation(
-                                source_id=source_id,
-                                target_id=target_file_id,
-                                relation_type="IMPORTS_FROM",
-                                properties={
-                                    "module": module_name,
-                                    "full_module_path": import_info.get("full_module_path", module_name)
-                                }
-                            )
+                    # 創建到檔案的導入關係
+                    # Create an IMPORTS_FROM relation to the module's file node
+                    self._add_relation(
+                        CodeRelation(
+                            source_id=source_id,
+                            target_id=self.module_to_file[module_name],
+                            relation_type="IMPORTS_FROM",
+                            properties={
+                                "module": module_name,
+                                "imports_symbols": [symbol_name]
+                            }
                         )
-                
-                elif import_type == "IMPORTS_SYMBOL":
-                    # 從模組導入特定符號的情況
-                    # Case: importing a specific symbol from a module
-                    module_name = import_info["imported_module"]
-                    symbol_name = import_info["imported_name"]
-                    
-                    # 檢查模組定義索引
-                    # Check module definitions index
-                    if module_name in self.module_definitions and symbol_name in self.module_definitions[module_name]:
-                        target_node_id = self.module_definitions[module_name][symbol_name]
-                        
-                        # 創建檔案到符號的依賴關係
-                        # Create a dependency from the source file to the symbol node
-                        self._add_relation(
-                            CodeRelation(
-                                source_id=source_id,
-                                target_id=target_node_id,
-                                relation_type="IMPORTS_DEFINITION",
-                                properties={
-                                    "module": module_name,
-                                    "symbol": symbol_name,
-                                    "alias": import_info.get("alias")
-                                }
-                            )
+                    )
+        
+        elif import_type in ("IMPORTS", "REEXPORTS"):
+            # JS/TS 的模組導入 (ES import 或 CommonJS require) 與重新導出
+            # Import (ES `import` or CommonJS `require()`) or re-export from a
+            # JS/TS file; an entry without imported_name (import "x",
+            # export * from "x", const x = require("x")) targets the file itself
+            module_name = import_info["imported_module"]
+            symbol_name = import_info.get("imported_name")
+            properties = {"module": module_name,
+                          "full_module_path": import_info.get("full_module_path", module_name)}
+            if import_info.get("require"):
+                properties["require"] = True
+
+            target_node_id = self.module_definitions.get(module_name, {}).get(symbol_name) if symbol_name else None
+            if target_node_id is None:
+                target_node_id = self.module_to_file.get(module_name)
+                if symbol_name:
+                    properties["imported_name"] = symbol_name
+            if target_node_id and target_node_id != source_id:
+                self._add_relation(
+                    CodeRelation(
+                        source_id=source_id,
+                        target_id=target_node_id,
+                        relation_type=import_type,
+                        properties=properties
+                    )
+                )
+
+        elif import_type == "IMPORTS_RUST":
+            # Rust use path: try each absolute candidate against the crate's module
+            # tree; the longest indexed module prefix is the module, and the
+            # segment after it, if any, the imported item
+            for candidate in import_info["candidates"]:
+                segments = candidate.split("::")
+                module_path = next((
+                    "::".join(segments[:end]) for end in range(len(segments), 0, -1)
+                    if "::".join(segments[:end]) in self.module_to_file
+                ), None)
+                if module_path is None:
+                    continue
+                rest = segments[len(module_path.split("::")):]
+                if rest:
+                    target_node_id = self.module_definitions.get(module_path, {}).get(rest[0])
+                else:
+                    target_node_id = self.module_to_file[module_path]
+                if target_node_id is None:
+                    continue
+                if target_node_id != source_id:
+                    self._add_relation(
+                        CodeRelation(
+                            source_id=source_id,
+                            target_id=target_node_id,
+                            relation_type="IMPORTS",
+                            properties={
+                                "path": import_info["full_module_path"],
+                                "module": module_path,
+                                "alias": import_info.get("alias"),
+                                "glob": import_info.get("glob", False),
+                                "line_no": import_info.get("line_no"),
+                            }
                         )
-                        
-                        # 避免為已處理的模組重複創建IMPORTS_FROM關係
-                        # Avoid creating duplicate IMPORTS_FROM relations for the same module
-                        if module_name not in processed_modules and module_name in self.module_to_file:
-                            processed_modules.add(module_name)
-                            
-                            # 創建到檔案的導入關係
-                            # Create an IMPORTS_FROM relation to the module's file node
+                    )
+                return
+
+        elif import_type == "IMPORTS_JAVA":
+            # Java import: a type (import a.b.Type, or a nested a.b.Type.Inner),
+            # a static member of one (import static a.b.Type.member, linked to
+            # the type), or for import a.b.* every file of package a.b
+            path = import_info["path"]
+            properties = {"path": path, "static": import_info.get("static", False),
+                          "wildcard": import_info.get("wildcard", False),
+                          "line_no": import_info.get("line_no")}
+            if properties["wildcard"] and not properties["static"]:
+                target_ids = sorted(node.node_id for node in self.nodes.values()
+                                    if node.node_type == "File" and node.properties.get("package") == path)
+            else:
+                segments = path.split(".")
+                if properties["static"] and not properties["wildcard"]:
+                    properties["member"] = segments.pop()
+                target_id = self._find_java_type(segments)
+                target_ids = [target_id] if target_id else []
+            for target_id in target_ids:
+                if target_id != source_id:
+                    self._add_relation(
+                        CodeRelation(
+                            source_id=source_id,
+                            target_id=target_id,
+                            relation_type="IMPORTS",
+                            properties=properties
+                        )
+                    )
+
+        elif import_type == "EXTENDS":
+            # 類別繼承關係
+            # Class inheritance relationship
+            module_name = import_info.get("imported_module")
+            class_name = import_info["imported_name"]
+            
+            # 檢查模組定義索引
+            # Check module definitions index; without a module (Java), look the
+            # name up through the hints as a type of the same kind as the subtype
+            if module_name is None:
+                source_node = self.nodes.get(source_id)
+                target_node_id = self._find_definition(
+                    class_name, import_info.get("module_hints", []),
+                    (source_node.node_type,) if source_node else ("Class",))
+            else:
+                target_node_id = self.module_definitions.get(module_name, {}).get(class_name)
+            if target_node_id:
+                # 創建繼承關係
+                # Create an EXTENDS (inheritance) relation
+                self._add_relation(
+                    CodeRelation(
+                        source_id=source_id,
+                        target_id=target_node_id,
+                        relation_type="EXTENDS",
+                        properties={"original_name": import_info.get("original_name")}
+                    )
+                )
+        
+        elif import_type == "CALLS":
+            # 函數調用關係
+            # Function call relationship
+            module_name = import_info["imported_module"]
+            func_name = import_info["imported_name"]
+            
+            # 檢查模組定義索引
+            # Check module definitions index
+            if module_name in self.module_definitions and func_name in self.module_definitions[module_name]:
+                target_node_id = self.module_definitions[module_name][func_name]
+                
+                # 創建調用關係
+                # Create a CALLS relation
+                self._add_relation(
+                    CodeRelation(
+                        source_id=source_id,
+                        target_id=target_node_id,
+                        relation_type="CALLS",
+                        properties={
+                            "original_name": import_info.get("original_name"),
+                            **self._pending_call_site(import_info)
+                        }
+                    )
+                )
+        
+        elif import_type == "CALLS_METHOD":
+            # 物件方法調用關係
+            # Method call on an imported object/class
+            module_name = import_info["imported_module"]
+            class_name = import_info["imported_class"]
+            method_name = import_info["method_name"]
+            
+            # 檢查模組定義索引中的類別
+            # Check that the class exists in the module definitions index
+            if module_name in self.module_definitions and class_name in self.module_definitions[module_name]:
+                class_node_id = self.module_definitions[module_name][class_name]
+                
+                # 尋找該類別定義的方法
+                # Find the method defined in that class node
+                for relation in self.relations:
+                    if relation.source_id == class_node_id and relation.relation_type == "DEFINES":
+                        # 確保 target_id 不是 None 再使用
+                        # Ensure target_id is not None before using it
+                        if relation.target_id is None:
+                            continue
+                        target_node = self.nodes.get(relation.target_id)
+                        if target_node and target_node.node_type == "Method" and target_node.name == method_name:
+                            # 創建調用關係
+                            # Create a CALLS relation to the method node
                             self._add_relation(
                                 CodeRelation(
                                     source_id=source_id,
-                                    target_id=self.module_to_file[module_name],
-                                    relation_type="IMPORTS_FROM",
+                                    target_id=relation.target_id,
+                                    relation_type="CALLS",
                                     properties={
-                                        "module": module_name,
-                                        "imports_symbols": [symbol_name]
+                                        "object": import_info.get("original_obj_name"),
+                                        "class": class_name,
+                                        **self._pending_call_site(import_info)
                                     }
                                 )
                             )
-                
-                elif import_type == "EXTENDS":
-                    # 類別繼承關係
-                    # Class inheritance relationship
-                    module_name = import_info["imported_module"]
-                    class_name = import_info["imported_name"]
-                    
-                    # 檢查模組定義索引
-                    # Check module definitions index
-                    if module_name in self.module_definitions and class_name in self.module_definitions[module_name]:
-                        target_node_id = self.module_definitions[module_name][class_name]
-                        
-                        # 創建繼承關係
-                        # Create an EXTENDS (inheritance) relation
-                        self._add_relation(
-                            CodeRelation(
-                                source_id=source_id,
-                                target_id=target_node_id,
-                                relation_type="EXTENDS",
-                                properties={"original_name": import_info.get("original_name")}
-                            )
+                            break
+
+        elif import_type == "CALLS_MEMBER":
+            # Type::member() or self.member() where the method lives in another file;
+            # ``definition`` is the type's indexed name when it differs (Outer.Inner)
+            type_name = import_info["type_name"]
+            member_name = import_info["member_name"]
+            type_node_id = self._find_definition(import_info.get("definition", type_name),
+                                                 import_info.get("module_hints", []), ("Class", "Interface"))
+            if type_node_id:
+                candidates = sorted(
+                    node.node_id for node in self.nodes.values()
+                    if node.node_type == "Method" and node.name == member_name
+                    and node.properties.get("method_of") == type_name
+                )
+                if len(candidates) > 1:
+                    # Same type name in several files: keep the one next to the type
+                    type_file = self.nodes[type_node_id].file_path
+                    candidates = [c for c in candidates if self.nodes[c].file_path == type_file]
+                if len(candidates) == 1:
+                    self._add_relation(
+                        CodeRelation(
+                            source_id=source_id,
+                            target_id=candidates[0],
+                            relation_type="CALLS",
+                            properties={"class": type_name, **self._pending_call_site(import_info)}
                         )
-                
-                elif import_type == "CALLS":
-                    # 函數調用關係
-                    # Function call relationship
-                    module_name = import_info["imported_module"]
-                    func_name = import_info["imported_name"]
-                    
-                    # 檢查模組定義索引
-                    # Check module definitions index
-                    if module_name in self.module_definitions and func_name in self.module_definitions[module_name]:
-                        target_node_id = self.module_definitions[module_name][func_name]
-                        
-                        # 創建調用關係
-                        # Create a CALLS relation
+                    )
+
+        elif import_type == "CALLS_RUST":
+            # Rust call through a use or module path: the first candidate whose
+            # module is indexed and defines a function of that name wins
+            for candidate in import_info["candidates"]:
+                module_path, _, name = candidate.rpartition("::")
+                target_node_id = self.module_definitions.get(module_path, {}).get(name)
+                target_node = self.nodes.get(target_node_id) if target_node_id else None
+                if target_node is None or target_node.node_type != "Function":
+                    continue
+                self._add_relation(
+                    CodeRelation(
+                        source_id=source_id,
+                        target_id=target_node_id,
+                        relation_type="CALLS",
+                        properties={"path": import_info["raw_name"], **self._pending_call_site(import_info)}
+                    )
+                )
+                return
+
+        elif import_type == "CALLS_MACRO":
+            # Rust macro invocation: macro_rules! definitions are indexed as name!
+            target_node_id = self._find_definition(import_info["definition"],
+                                                   import_info.get("module_hints", []), ("Macro",))
+            if target_node_id:
+                self._add_relation(
+                    CodeRelation(
+                        source_id=source_id,
+                        target_id=target_node_id,
+                        relation_type="CALLS",
+                        properties={"macro": True, "path": import_info["raw_name"],
+                                    **self._pending_call_site(import_info)}
+                    )
+                )
+
+        elif import_type == "DERIVE_USE":
+            # Rust #[derive(Name)]: the derive macro is a #[proc_macro_derive(Name)]
+            # function, indexed as Name! in the macro namespace
+            target_node_id = self._find_definition(import_info["definition"],
+                                                   import_info.get("module_hints", []), ("Function",))
+            if target_node_id:
+                self._add_relation(
+                    CodeRelation(
+                        source_id=source_id,
+                        target_id=target_node_id,
+                        relation_type="DERIVE_USE",
+                        properties={"derive_path": import_info["raw_name"]}
+                    )
+                )
+
+        elif import_type == "REFERENCES_RUST":
+            # Path inside a Rust macro's arguments, matched by name only: the
+            # first candidate naming an indexed function or type wins
+            for candidate in import_info["candidates"]:
+                module_path, _, name = candidate.rpartition("::")
+                target_node_id = self.module_definitions.get(module_path, {}).get(name)
+                target_node = self.nodes.get(target_node_id) if target_node_id else None
+                if target_node is None or target_node.node_type not in ("Function", "Class", "Enum", "Interface"):
+                    continue
+                self._add_relation(
+                    CodeRelation(
+                        source_id=source_id,
+                        target_id=target_node_id,
+                        relation_type="REFERENCES",
+                        properties={"path": import_info["raw_name"], "via_macro": import_info["via_macro"],
+                                    "confidence": import_info["confidence"],
+                                    **self._pending_call_site(import_info)}
+                    )
+                )
+                return
+
+        elif import_type == "CALLS_C":
+            # C/C++ call by name, looked up in the caller's file and then in the
+            # headers it includes
+            target_node_id = self._find_c_function(import_info["name"], self._c_files(import_info),
+                                                   import_info.get("arg_count"), import_info.get("method_of"))
+            if target_node_id and target_node_id != source_id:
+                self._add_relation(
+                    CodeRelation(
+                        source_id=source_id,
+                        target_id=target_node_id,
+                        relation_type="CALLS",
+                        properties={"path": import_info["raw_name"], **self._pending_call_site(import_info)}
+                    )
+                )
+
+        elif import_type == "DECLARED_BY":
+            # C/C++ definition and the prototypes declaring it, in its own file or
+            # an included header: same kind, name, class and signature
+            definition = self.nodes.get(source_id)
+            if definition is None:
+                return
+            files = self._c_files(import_info)
+            for node in sorted(self.nodes.values(), key=lambda n: n.node_id):
+                if node.node_id != source_id and node.properties.get("prototype") \
+                        and node.node_type == definition.node_type and node.name == definition.name \
+                        and node.file_path in files \
+                        and node.properties.get("method_of") == definition.properties.get("method_of") \
+                        and node.properties.get("signature") == definition.properties.get("signature"):
+                    self._add_relation(
+                        CodeRelation(
+                            source_id=source_id,
+                            target_id=node.node_id,
+                            relation_type="DECLARED_BY",
+                            properties={}
+                        )
+                    )
+                    # And the other way, from the declaration a reader of the header sees
+                    self._add_relation(
+                        CodeRelation(
+                            source_id=node.node_id,
+                            target_id=source_id,
+                            relation_type="DECLARES",
+                            properties={"signature": node.properties.get("signature")}
+                        )
+                    )
+
+        elif import_type == "CALLS_UNRESOLVED":
+            # Call whose target cannot be looked up (e.g. a method on a receiver
+            # of unknown type); nothing to resolve, CodeGraph keeps it as unresolved
+            return
+
+        elif import_type == "CALLS_PACKAGE":
+            # Go call to a function declared in another file of the same package
+            package_dir = import_info["package_dir"]
+            candidates = sorted(
+                node.node_id for node in self.nodes.values()
+                if node.node_type == "Function" and node.name == import_info["name"]
+                and os.path.dirname(node.file_path) == package_dir
+            )
+            if len(candidates) == 1:
+                self._add_relation(
+                    CodeRelation(
+                        source_id=source_id,
+                        target_id=candidates[0],
+                        relation_type="CALLS",
+                        properties=self._pending_call_site(import_info)
+                    )
+                )
+
+        elif import_type == "SATISFIES":
+            # Go interfaces are satisfied implicitly: link every struct of the
+            # package whose methods include all the interface's, by name
+            package_dir = import_info["package_dir"]
+            required = set(import_info["methods"])
+            method_sets: Dict[str, Set[str]] = {}
+            for node in self.nodes.values():
+                if node.node_type == "Method" and os.path.dirname(node.file_path) == package_dir:
+                    method_sets.setdefault(node.properties.get("method_of"), set()).add(node.name)
+            for node in sorted(self.nodes.values(), key=lambda n: n.node_id):
+                if node.node_type != "Class" or os.path.dirname(node.file_path) != package_dir:
+                    continue
+                file_node = self.nodes.get(f"file:{node.file_path}")
+                if file_node and file_node.properties.get("package") != import_info.get("package"):
+                    continue
+                if required <= method_sets.get(node.name, set()):
+                    self._add_relation(
+                        CodeRelation(
+                            source_id=node.node_id,
+                            target_id=import_info["interface_id"],
+                            relation_type="SATISFIES",
+                            properties={"methods": import_info["methods"]}
+                        )
+                    )
+
+        elif import_type == "IMPL_METHOD":
+            # Method declared in an impl block whose type lives in another file
+            type_node_id = self._find_definition(import_info["type_name"],
+                                                 import_info.get("module_hints", []),
+                                                 ("Class",))
+            if type_node_id:
+                self._add_relation(
+                    CodeRelation(
+                        source_id=type_node_id,
+                        target_id=import_info["method_id"],
+                        relation_type="DEFINES",
+                    )
+                )
+
+        elif import_type == "IMPLEMENTS":
+            # Type implements a trait/interface, at least one end in another file
+            hints = import_info.get("module_hints", [])
+            type_node_id = import_info.get("type_node_id") or self._find_definition(
+                import_info["type_name"], hints, ("Class",))
+            trait_node_id = import_info.get("trait_node_id") or self._find_definition(
+                import_info["trait_name"], hints, ("Interface",))
+            if type_node_id and trait_node_id:
+                self._add_relation(
+                    CodeRelation(
+                        source_id=type_node_id,
+                        target_id=trait_node_id,
+                        relation_type="IMPLEMENTS",
+                        properties={"trait_path": import_info.get("trait_path"),
+                                    **import_info.get("impl_properties", {})}
+                    )
+                )
+                # Each method of the impl block implements the trait method of the same name
+                trait_node = self.nodes[trait_node_id]
+                for method_name, method_id in sorted(import_info.get("methods", {}).items()):
+                    trait_method_id = next((
+                        node.node_id for node in self.nodes.values()
+                        if node.node_type == "Method" and node.name == method_name
+                        and node.file_path == trait_node.file_path
+                        and node.properties.get("method_of") == trait_node.name
+                    ), None)
+                    if trait_method_id:
                         self._add_relation(
                             CodeRelation(
-                                source_id=source_id,
-                                target_id=target_node_id,
-                                relation_type="CALLS",
-                                properties={"original_name": import_info.get("original_name")}
+                                source_id=method_id,
+                                target_id=trait_method_id,
+                                relation_type="IMPLEMENTS",
+                                properties={"type": import_info["type_name"]}
                             )
                         )
-                
-                elif import_type == "CALLS_METHOD":
-                    # 物件方法調用關係
-                    # Method call on an imported object/class
-                    module_name = import_info["imported_module"]
-                    class_name = import_info["imported_class"]
-                    method_name = import_info["method_name"]
-                    
-                    # 檢查模組定義索引中的類別
-                    # Check that the class exists in the module definitions index
-                    if module_name in self.module_definitions and class_name in self.module_definitions[module_name]:
-                        class_node_id = self.module_definitions[module_name][class_name]
-                        
-                        # 尋找該類別定義的方法
-                        # Find the method defined in that class node
-                        for relation in self.relations:
-                            if relation.source_id == class_node_id and relation.relation_type == "DEFINES":
-                                # 確保 target_id 不是 None 再使用
-                                # Ensure target_id is not None before using it
-                                if relation.target_id is None:
-                                    continue
-                                target_node = self.nodes.get(relation.target_id)
-                                if target_node and target_node.node_type == "Method" and target_node.name == method_name:
-                                    # 創建調用關係
-                                    # Create a CALLS relation to the method node
-                                    self._add_relation(
-                                        CodeRelation(
-                                            source_id=source_id,
-                                            target_id=relation.target_id,
-                                            relation_type="CALLS",
-                                            properties={
-                                                "object": import_info.get("original_obj_name"),
-                                                "class": class_name
-                                            }
-                                        )
-                                    )
-                                    break
+
+        elif import_type == "SUPERTRAIT":
+            # Rust trait whose supertrait is declared in another file
+            trait_node_id = self._find_definition(import_info["trait_name"],
+                                                  import_info.get("module_hints", []),
+                                                  ("Interface",))
+            if trait_node_id:
+                self._add_relation(
+                    CodeRelation(
+                        source_id=import_info["subtrait_id"],
+                        target_id=trait_node_id,
+                        relation_type="EXTENDS",
+                        properties={"trait_path": import_info.get("trait_path")}
+                    )
+                )
+
+        elif import_type == "FIELD_ACCESS":
+            # self.field access in an impl whose struct is defined in another file
+            hints = import_info.get("module_hints", [])
+            field_node_id = self._find_definition(
+                f"{import_info['type_name']}.{import_info['field_name']}", hints, ("Field",))
+            if field_node_id:
+                self._add_relation(
+                    CodeRelation(
+                        source_id=import_info["accessor_id"],
+                        target_id=field_node_id,
+                        relation_type="REFERENCES",
+                        properties={key: import_info[key]
+                                    for key in ("access", "line_no", "call_site") if key in import_info}
+                    )
+                )
+
+        elif import_type == "USES_TYPE":
+            # Struct or enum field whose type is defined in another file; std types stay unlinked
+            type_node_id = self._find_definition(import_info["type_name"],
+                                                 import_info.get("module_hints", []),
+                                                 ("Class", "Enum", "Interface"))
+            if type_node_id:
+                self._add_relation(
+                    CodeRelation(
+                        source_id=import_info["owner_id"],
+                        target_id=type_node_id,
+                        relation_type="USES_TYPE",
+                        properties={"fields": import_info.get("fields", [])}
+                    )
+                )
+
+        elif import_type == "IMPORTS_RUBY":
+            # require / require_relative of a file of the project; gems stay unlinked
+            path = import_info["path"]
+            if path in processed_modules:
+                return
+            processed_modules.add(path)
+            target_id = self._required_file(import_info)
+            if target_id and target_id != source_id:
+                self._add_relation(
+                    CodeRelation(
+                        source_id=source_id,
+                        target_id=target_id,
+                        relation_type="IMPORTS",
+                        properties={"path": path,
+                                    "require": "require_relative" if import_info.get("relative") else "require",
+                                    "line_no": import_info.get("line_no")}
+                    )
+                )
+
+        elif import_type == "AR_ASSOCIATION":
+            # ActiveRecord association (has_many :posts) to the model it names
+            model_id = self._association_model(import_info)
+            if model_id:
+                properties = {key: import_info[key]
+                              for key in ("association", "name", "line_no", "through", "polymorphic")
+                              if key in import_info}
+                properties["class_name"] = import_info["original_name"]
+                self._add_relation(
+                    CodeRelation(
+                        source_id=source_id,
+                        target_id=model_id,
+                        relation_type="AR_ASSOCIATION",
+                        properties=properties
+                    )
+                )
+
+    @staticmethod
+    def _pending_call_site(import_info: Dict[str, Any]) -> Dict[str, Any]:
+        """Call-site properties carried by a pending CALLS/CALLS_METHOD entry."""
+        return {key: import_info[key] for key in ("line_no", "call_site", "await_call") if key in import_info}
+
+    def _resolve_include(self, import_info: Dict[str, Any], processed_modules: Set[str]) -> None:
+        """
+        Link a C/C++ file to the file a quoted ``#include "..."`` names.
+
+        The path is looked up next to the including file first, then as the
+        end of the path of an indexed file (``codec/buffer.h`` matches
+        ``include/codec/buffer.h``), and last by base name alone, as calls
+        are resolved. Several files ending in the path leave it to the base
+        name.
+        """
+        include_path = import_info["full_module_path"]
+        if include_path in processed_modules:
+            return
+        processed_modules.add(include_path)
+
+        source = self.nodes.get(import_info["source_id"])
+        target_id = self._included_file(source.file_path if source is not None else "", include_path)
+        if target_id is None or target_id == import_info["source_id"]:
+            return
+        self._add_relation(
+            CodeRelation(
+                source_id=import_info["source_id"],
+                target_id=target_id,
+                relation_type="INCLUDES",
+                properties={"path": include_path, "system": False, "line_no": import_info.get("line_no")}
+            )
+        )
+
+    def _included_file(self, file_path: str, include_path: str) -> Optional[str]:
+        """File node ID a quoted include in a file resolves to, as described in _resolve_include."""
+        nearby = f"file:{os.path.normpath(os.path.join(os.path.dirname(file_path), include_path))}"
+        if file_path and nearby in self.nodes:
+            return nearby
+        module_name = include_path.split("/")[-1]
+        if "/" in include_path:
+            matches = self._files_ending_in(include_path)
+            if len(matches) == 1:
+                return matches[0]
+        return self.module_to_file.get(module_name)
+
+    def _files_ending_in(self, path: str) -> List[str]:
+        """File node IDs whose path ends in a relative path, whole segments only."""
+        if self._files_by_name is None:
+            self._files_by_name = {}
+            for node in self.nodes.values():
+                if node.node_type == "File":
+                    self._files_by_name.setdefault(os.path.basename(node.file_path), []).append(node.node_id)
+        suffix = os.sep + os.path.normpath(path)
+        return [file_id for file_id in self._files_by_name.get(os.path.basename(path), [])
+                if (os.sep + self.nodes[file_id].file_path).endswith(suffix)]
+
+    def _required_file(self, import_info: Dict[str, Any]) -> Optional[str]:
+        """
+        File node ID a Ruby require resolves to. ``require_relative`` paths
+        are relative to the requiring file; ``require`` paths are looked up
+        as the end of the path of a single indexed file (``models/user``
+        matches ``app/models/user.rb``), as the load path is not known.
+        """
+        path = import_info["path"]
+        if not path.endswith(".rb"):
+            path += ".rb"
+        if import_info.get("relative"):
+            source = self.nodes.get(import_info["source_id"])
+            if source is None:
+                return None
+            target_id = f"file:{os.path.normpath(os.path.join(os.path.dirname(source.file_path), path))}"
+            return target_id if target_id in self.nodes else None
+        matches = self._files_ending_in(path)
+        return matches[0] if len(matches) == 1 else None
+
+    def _association_model(self, import_info: Dict[str, Any]) -> Optional[str]:
+        """Class node ID of the model an ActiveRecord association names; None for polymorphic ones."""
+        if import_info.get("polymorphic"):
+            return None
+        return self._find_definition(import_info["imported_name"], import_info.get("module_hints", []), ("Class",))
+
+    def _c_files(self, import_info: Dict[str, Any]) -> List[str]:
+        """
+        Files a C/C++ name used in a file may come from, in order: the file
+        itself, then the files its quoted includes resolve to. Entries
+        without their ``includes`` fall back to the ``modules`` by base name.
+        """
+        source = self.nodes.get(import_info["source_id"])
+        if "includes" not in import_info or source is None:
+            return self._module_files(import_info["modules"])
+        files = [source.file_path]
+        for include_path in import_info["includes"]:
+            target = self.nodes.get(self._included_file(source.file_path, include_path) or "")
+            if target is not None and target.file_path not in files:
+                files.append(target.file_path)
+        return files
+
+    def _module_files(self, modules: List[str]) -> List[str]:
+        """File paths of the indexed modules among the given ones, in order."""
+        files = []
+        for module_name in modules:
+            file_node = self.nodes.get(self.module_to_file.get(module_name, ""))
+            if file_node is not None and file_node.file_path not in files:
+                files.append(file_node.file_path)
+        return files
+
+    def _find_c_function(self, name: str, files: List[str], arg_count: Optional[int],
+                         method_of: Optional[str]) -> Optional[str]:
+        """Find the C/C++ function a call by name resolves to.
+
+        Files are searched in order and the first with a function of that
+        name taking ``arg_count`` arguments decides; a call from a method also
+        matches the methods of its class. Overloads that all take that many
+        arguments make the call ambiguous, and it is not resolved. A
+        definition is preferred to the prototypes of the same signature.
+        """
+        for file_path in files:
+            candidates = [
+                node for node in self.nodes.values()
+                if node.name == name and node.file_path == file_path
+                and (node.node_type == "Function"
+                     or (method_of and node.node_type == "Method" and node.properties.get("method_of") == method_of))
+                and self._accepts_arguments(node.properties, arg_count)
+            ]
+            if not candidates:
+                continue
+            if len({node.properties.get("signature") for node in candidates}) > 1:
+                return None
+            candidates.sort(key=lambda n: (bool(n.properties.get("prototype")), n.line_no or 0))
+            return candidates[0].node_id
+        return None
+
+    @staticmethod
+    def _accepts_arguments(properties: Dict[str, Any], arg_count: Optional[int]) -> bool:
+        """Whether a C/C++ function with these properties can be called with arg_count arguments.
+
+        The parameters are read from its ``signature``, ``(int, const char *, ...)``,
+        split at the commas outside brackets; unknown either way counts as yes.
+        """
+        signature = properties.get("signature")
+        if signature is None or arg_count is None:
+            return True
+        parameters, depth, current = [], 0, ""
+        for char in signature[1:-1]:
+            if char in "([{<":
+                depth += 1
+            elif char in ")]}>":
+                depth -= 1
+            if char == "," and depth == 0:
+                parameters.append(current.strip())
+                current = ""
+            else:
+                current += char
+        if current.strip():
+            parameters.append(current.strip())
+        if parameters and parameters[-1] == "...":
+            return arg_count >= len(parameters) - 1
+        return len(parameters) - properties.get("default_args", 0) <= arg_count <= len(parameters)
+
+    def _find_java_type(self, segments: List[str]) -> Optional[str]:
+        """Find the type a qualified Java name such as ``com.example.Outer.Inner`` names.
+
+        Java modules are indexed per file as ``package.FileName``, with nested
+        types under ``Outer.Inner``. Every split of the name into a package
+        and a type path is tried, longest package first; within a package the
+        file named after the outermost type is searched before the others.
+        """
+        for split in range(len(segments) - 1, 0, -1):
+            package, type_path = ".".join(segments[:split]), ".".join(segments[split:])
+            modules = [f"{package}.{segments[split]}"] + sorted(
+                module for module in self.module_definitions if module.rpartition(".")[0] == package)
+            for module in modules:
+                node_id = self.module_definitions.get(module, {}).get(type_path)
+                if node_id in self.nodes:
+                    return node_id
+        return None
+
+    def _find_definition(self, name: str, module_hints: List[str], node_types: Tuple[str, ...]) -> Optional[str]:
+        """Look up a symbol by bare name in the module definitions index.
+
+        Modules named in ``module_hints`` are searched first. Otherwise the
+        symbol is only resolved when exactly one module defines it, so an
+        ambiguous name never links to an arbitrary definition.
+        """
+        def matches(node_id: str) -> bool:
+            node = self.nodes.get(node_id)
+            return node is not None and node.node_type in node_types
+
+        for module_name in module_hints:
+            node_id = self.module_definitions.get(module_name, {}).get(name)
+            if node_id and matches(node_id):
+                return node_id
+
+        # A set: the same node may be indexed under several module names
+        candidates = {
+            definitions[name]
+            for definitions in self.module_definitions.values()
+            if name in definitions and matches(definitions[name])
+        }
+        if len(candidates) == 1:
+            return candidates.pop()
+        return None
 
 
 # 使用範例
@@ -0,0 +1,247 @@
+"""
+Directory walking that honours .gitignore files and exclude globs.
+
+``.gitignore`` files are read as the walk reaches their directory and apply
+to everything below it, with the usual precedence: a later pattern wins over
+an earlier one, a nested file wins over its parents, and ``!pattern``
+re-includes a path. Exclude globs use the same syntax relative to the walk
+root, e.g. ``*.min.js`` or ``**/generated/**``. Ignored directories are not
+descended into, so ``node_modules/`` or ``target/`` cost nothing.
+
+Include globs, when given, narrow the walk to the files matching one of
+them; every directory is still entered, since a file below it may match.
+Files larger than a size limit are left out as well.
+"""
+
+import os
+import re
+import logging
+from dataclasses import dataclass
+from typing import Iterator, List, Optional, Tuple
+
+logger = logging.getLogger(__name__)
+
+GITIGNORE_FILE = ".gitignore"
+
+
+@dataclass
+class IgnoreRule:
+    """One compiled .gitignore or exclude pattern."""
+    pattern: str
+    regex: "re.Pattern[str]"
+    # Directory the pattern is relative to ("" for the walk root), with "/" separators
+    base: str
+    negate: bool = False
+    dir_only: bool = False
+
+    def matches(self, rel_path: str, is_dir: bool) -> bool:
+        """Whether the rule matches a path relative to the walk root."""
+        if self.dir_only and not is_dir:
+            return False
+        if self.base:
+            if not rel_path.startswith(self.base + "/"):
+                return False
+            rel_path = rel_path[len(self.base) + 1:]
+        return self.regex.fullmatch(rel_path) is not None
+
+
+def compile_pattern(pattern: str, base: str = "") -> Optional[IgnoreRule]:
+    """
+    Compile one line of .gitignore syntax.
+
+    Args:
+        pattern: The pattern as written
+        base: Directory the pattern is relative to, relative to the walk root
+
+    Returns:
+        The rule, or None for blank lines and comments
+    """
+    line = pattern.rstrip("\n").rstrip()
+    if not line or line.startswith("#"):
+        return None
+
+    negate = line.startswith("!")
+    if negate:
+        line = line[1:]
+    if line.startswith("\\"):
+        # "\#" and "\!" escape a leading special character
+        line = line[1:]
+    dir_only = line.endswith("/")
+    line = line.rstrip("/")
+    if not line:
+        return None
+
+    # A slash anywhere but the end anchors the pattern to its base directory;
+    # otherwise it matches a name at any depth
+    anchored = "/" in line
+    line = line.lstrip("/")
+    prefix = "" if anchored else "(?:.*/)?"
+    return IgnoreRule(pattern, re.compile(prefix + glob_to_regex(line)), base, negate, dir_only)
+
+
+def glob_to_regex(glob: str) -> str:
+    """Translate gitignore wildcards to a regex over "/"-separated paths."""
+    out = []
+    i = 0
+    while i < len(glob):
+        c = glob[i]
+        if glob.startswith("**/", i):
+            out.append("(?:.*/)?")
+            i += 3
+        elif glob.startswith("/**", i) and i + 3 == len(glob):
+            out.append("/.*")
+            i += 3
+        elif glob.startswith("**", i):
+            out.append(".*")
+            i += 2
+        elif c == "*":
+            out.append("[^/]*")
+            i += 1
+        elif c == "?":
+            out.append("[^/]")
+            i += 1
+        elif c == "[":
+            end = glob.find("]", i + 2)
+            if end == -1:
+                out.append(re.escape(c))
+                i += 1
+                continue
+            body = glob[i + 1:end]
+            if body.startswith("!"):
+                body = "^" + body[1:]
+            out.append("[" + body.replace("\\", "\\\\") + "]")
+            i = end + 1
+        else:
+            out.append(re.escape(c))
+            i += 1
+    return "".join(out)
+
+
+def pattern_error(pattern: str) -> Optional[str]:
+    """
+    Why a glob is not a valid pattern, None when it is.
+
+    .gitignore files are read as leniently as git reads them, but a pattern
+    from the config or the command line is checked before any walk: an
+    unclosed ``[`` or a character range running backwards is a mistake
+    there, not a literal.
+    """
+    start = pattern.find("[")
+    while start != -1:
+        end = pattern.find("]", start + 2)
+        if end == -1:
+            return f"unclosed '[' at position {start + 1}"
+        start = pattern.find("[", end + 1)
+    try:
+        compile_pattern(pattern)
+    except re.error as e:
+        # The position in e refers to the translated regex, not the glob
+        return e.msg
+    return None
+
+
+class PathFilter:
+    """
+    Walks a directory tree, skipping ignored and excluded paths.
+
+    ``skipped`` counts the paths the last walk left out: an ignored
+    directory counts once, however much it contains. ``too_large`` lists
+    the files among them left out for their size.
+    """
+
+    def __init__(self, respect_gitignore: bool = True, exclude: Optional[List[str]] = None,
+                 include: Optional[List[str]] = None, max_file_size: Optional[int] = None):
+        """
+        Args:
+            respect_gitignore: Read .gitignore files found during the walk
+            exclude: Extra patterns in .gitignore syntax, relative to the walk root
+            include: Patterns in .gitignore syntax a file must match one of; None or empty keeps all
+            max_file_size: Size in bytes above which a file is skipped; None for no limit
+        """
+        self.respect_gitignore = respect_gitignore
+        self.exclude = list(exclude or [])
+        self.include = list(include or [])
+        self.max_file_size = max_file_size
+        self.skipped = 0
+        self.too_large: List[str] = []
+
+    def walk(self, directory_path: str) -> Iterator[str]:
+        """
+        Yield every file under a directory that is neither ignored nor excluded.
+
+        Args:
+            directory_path: Root of the walk
+
+        Yields:
+            File paths joined onto directory_path, in os.walk order
+        """
+        self.skipped = 0
+        self.too_large = []
+        root_rules = [rule for rule in (compile_pattern(p) for p in self.exclude) if rule]
+        include_rules = [rule for rule in (compile_pattern(p) for p in self.include) if rule]
+        # Rules in effect per directory (relative path), inherited by subdirectories
+        rules_by_dir = {"": root_rules}
+
+        for root, dirs, files in os.walk(directory_path):
+            rel_root = os.path.relpath(root, directory_path).replace(os.sep, "/")
+            rel_root = "" if rel_root == "." else rel_root
+            rules = rules_by_dir.pop(rel_root, root_rules)
+            if self.respect_gitignore and GITIGNORE_FILE in files:
+                rules = rules + self._read_gitignore(os.path.join(root, GITIGNORE_FILE), rel_root)
+
+            kept_dirs = []
+            for name in sorted(dirs):
+                rel_path = f"{rel_root}/{name}" if rel_root else name
+                if (self.respect_gitignore and name == ".git") or self._ignored(rules, rel_path, True):
+                    self.skipped += 1
+                    continue
+                kept_dirs.append(name)
+                rules_by_dir[rel_path] = rules
+            dirs[:] = kept_dirs
+
+            for name in files:
+                rel_path = f"{rel_root}/{name}" if rel_root else name
+                if self._ignored(rules, rel_path, False) \
+                        or (include_rules and not self._ignored(include_rules, rel_path, False)):
+                    self.skipped += 1
+                    continue
+                path = os.path.join(root, name)
+                if self.max_file_size is not None and self._size(path) > self.max_file_size:
+                    logger.info(f"Skipping {path}: larger than {self.max_file_size} bytes")
+                    self.skipped += 1
+                    self.too_large.append(path)
+                    continue
+                yield path
+
+    @staticmethod
+    def _ignored(rules: List[IgnoreRule], rel_path: str, is_dir: bool) -> bool:
+        """The last matching rule decides; no match means not ignored."""
+        for rule in reversed(rules):
+            if rule.matches(rel_path, is_dir):
+                return not rule.negate
+        return False
+
+    @staticmethod
+    def _size(path: str) -> int:
+        try:
+            return os.path.getsize(path)
+        except OSError:
+            # Vanished or unreadable; let the parser report it
+            return 0
+
+    @staticmethod
+    def _read_gitignore(path: str, base: str) -> List[IgnoreRule]:
+        try:
+            with open(path, "r", encoding="utf-8", errors="replace") as f:
+                lines = f.readlines()
+        except OSError as e:
+            logger.warning(f"Cannot read {path}: {e}")
+            return []
+        return [rule for rule in (compile_pattern(line, base) for line in lines) if rule]
+
+
+def env_filter_options() -> Tuple[bool, List[str]]:
+    """RESPECT_GITIGNORE and EXCLUDE_PATTERNS (comma-separated) from the environment."""
+    respect_gitignore = os.getenv("RESPECT_GITIGNORE", "true").lower() == "true"
+    exclude = [p.strip() for p in os.getenv("EXCLUDE_PATTERNS", "").split(",") if p.strip()]
+    return respect_gitignore, exclude
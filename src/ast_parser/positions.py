@@ -0,0 +1,275 @@
+"""
+Source columns in the units different clients count them in.
+
+Parsers record columns as 1-based character (code point) offsets. LSP
+clients count columns in UTF-16 code units by default, and some in UTF-8
+bytes. The two only disagree with characters on lines holding non-ASCII
+text: ``é`` is one UTF-16 unit but two bytes, and an emoji outside the
+Basic Multilingual Plane is two UTF-16 units and four bytes.
+
+A LineIndex keeps the text a file was parsed from and converts a column
+only when a consumer asks for another encoding, so parsing pays nothing
+for it. The encodings are named after LSP's ``PositionEncodingKind``, where
+``utf-32`` means characters.
+
+``assign_spans`` completes the span of every node once its file is parsed:
+the columns the parser recorded, or whole lines when it recorded none, and
+the matching 0-based ``start_byte`` / exclusive ``end_byte`` offsets into the
+file's UTF-8 bytes, so a consumer can slice the exact source of a symbol.
+
+Files are read as UTF-8, except that a byte order mark, or the NUL bytes of
+UTF-16 text without one, select the encoding the file is transcoded from;
+the offsets of such a file are then into the UTF-8 encoding of its text. A
+file with NUL bytes that is not UTF-16 is binary and not parsed at all.
+"""
+
+import codecs
+import io
+from dataclasses import dataclass, field
+from typing import Any, Iterable, List, Optional, Tuple
+
+# Column units: UTF-8 bytes, UTF-16 code units, characters
+ENCODINGS = ("utf-8", "utf-16", "utf-32")
+
+# The unit parsers record columns in
+CHARACTER_ENCODING = "utf-32"
+
+# Byte order marks and the codec reading past each; UTF-32 first, as its
+# little-endian mark starts with the UTF-16 one
+BYTE_ORDER_MARKS = ((codecs.BOM_UTF32_LE, "utf-32"), (codecs.BOM_UTF32_BE, "utf-32"),
+                    (codecs.BOM_UTF8, "utf-8-sig"), (codecs.BOM_UTF16_LE, "utf-16"),
+                    (codecs.BOM_UTF16_BE, "utf-16"))
+
+# Leading bytes looked at to tell binary files and UTF-16 without a mark from UTF-8 text
+SNIFF_BYTES = 8000
+
+
+def _check_encoding(encoding: str) -> None:
+    if encoding not in ENCODINGS:
+        raise ValueError(f"Unknown position encoding {encoding!r}, expected one of {', '.join(ENCODINGS)}")
+
+
+def _utf8_length(text: str) -> int:
+    """Length in bytes of text decoded from UTF-8, undecodable bytes included."""
+    try:
+        # Bytes that were not UTF-8 come back as the lone surrogates surrogateescape made of them
+        return len(text.encode("utf-8", "surrogateescape"))
+    except UnicodeEncodeError:
+        return len(text.encode("utf-8", "surrogatepass"))
+
+
+def source_encoding(data: bytes) -> Optional[str]:
+    """Codec of a file's bytes when they are not plain UTF-8: named by a byte order mark, or UTF-16 without one."""
+    for mark, encoding in BYTE_ORDER_MARKS:
+        if data.startswith(mark):
+            return encoding
+    head = data[:SNIFF_BYTES - SNIFF_BYTES % 2]
+    if len(data) % 2 or b"\0" not in head:
+        return None
+    # Mostly ASCII text in UTF-16 has a NUL in every other byte, and no other NULs
+    for encoding, high, low in (("utf-16-le", head[1::2], head[0::2]), ("utf-16-be", head[0::2], head[1::2])):
+        if high.count(0) * 2 >= len(high) and low.count(0) == 0:
+            try:
+                data.decode(encoding)
+            except UnicodeDecodeError:
+                return None
+            return encoding
+    return None
+
+
+def is_binary(data: bytes) -> bool:
+    """Whether a file's bytes are binary: NUL bytes that are not UTF-16 text."""
+    return b"\0" in data[:SNIFF_BYTES] and source_encoding(data) is None
+
+
+def looks_binary(file_path: str) -> bool:
+    """is_binary for a file, read whole only when its start holds a NUL; an unreadable file is left for its parser to report."""
+    try:
+        with open(file_path, "rb") as f:
+            data = f.read(SNIFF_BYTES)
+            if b"\0" not in data:
+                return False
+            data += f.read()
+    except OSError:
+        return False
+    return is_binary(data)
+
+
+def _read_bytes(file_path: str) -> bytes:
+    with open(file_path, "rb") as f:
+        return f.read()
+
+
+def decode_text(data: bytes) -> str:
+    """
+    Text of a file's bytes, transcoded by source_encoding or else UTF-8.
+
+    Raises:
+        UnicodeDecodeError: If the bytes are not valid in that encoding
+    """
+    return data.decode(source_encoding(data) or "utf-8")
+
+
+def read_text(file_path: str) -> str:
+    """A file's text for parsing: decode_text, with line breaks read as text mode does."""
+    return io.StringIO(decode_text(_read_bytes(file_path)), newline=None).read()
+
+
+def decode_source(data: bytes) -> str:
+    """
+    Text of a file's bytes, for spans and snippets.
+
+    UTF-16 and files with a byte order mark are transcoded; anything else
+    gives text that encodes back to the same bytes, whatever they hold.
+    """
+    encoding = source_encoding(data)
+    if encoding is not None:
+        try:
+            return data.decode(encoding)
+        except UnicodeDecodeError:
+            pass
+    return data.decode("utf-8", "surrogateescape")
+
+
+def read_source(file_path: str) -> str:
+    """A file's text as decode_source gives it."""
+    return decode_source(_read_bytes(file_path))
+
+
+def _width(text: str, encoding: str) -> int:
+    """Length of text in the units of an encoding."""
+    if encoding == "utf-8":
+        return len(text.encode("utf-8", "surrogatepass"))
+    if encoding == "utf-16":
+        return len(text.encode("utf-16-le", "surrogatepass")) // 2
+    return len(text)
+
+
+class LineIndex:
+    """The lines of one source text, split on first use."""
+
+    def __init__(self, source: str):
+        self._source = source
+        self._lines: Optional[List[str]] = None
+        # Byte offset at which each line starts, computed on the first byte lookup
+        self._starts: Optional[List[int]] = None
+
+    @classmethod
+    def from_file(cls, file_path: str) -> "LineIndex":
+        with open(file_path, "r", encoding="utf-8", newline="") as f:
+            return cls(f.read())
+
+    def line(self, line_no: int) -> str:
+        """Text of a 1-based line without its line break; empty past the end."""
+        if self._lines is None:
+            self._lines = self._source.split("\n")
+        if 1 <= line_no <= len(self._lines):
+            return self._lines[line_no - 1].rstrip("\r")
+        return ""
+
+    def byte_offset(self, line_no: int, column: int) -> int:
+        """
+        0-based UTF-8 byte offset of a 1-based line and character column.
+
+        A column past the end of its line lands on the line break, and a
+        line past the end of the text on the end of the text.
+        """
+        if self._starts is None:
+            self._starts = [0]
+            for raw in self._source.split("\n"):
+                self._starts.append(self._starts[-1] + _utf8_length(raw) + 1)
+        if line_no > len(self._starts) - 1:
+            return self._starts[-1] - 1
+        text = self.line(max(1, line_no))
+        return self._starts[max(1, line_no) - 1] + _utf8_length(text[:max(0, column - 1)])
+
+    def encode_column(self, line_no: int, column: int, encoding: str) -> int:
+        """
+        Convert a 1-based character column to the units of an encoding.
+
+        Columns past the end of the line count one unit per character, so an
+        exclusive end column right after the last character stays exact.
+        """
+        _check_encoding(encoding)
+        if encoding == CHARACTER_ENCODING or column <= 1:
+            return column
+        text = self.line(line_no)
+        prefix = text[:column - 1]
+        return _width(prefix, encoding) + (column - 1 - len(prefix)) + 1
+
+    def decode_column(self, line_no: int, column: int, encoding: str) -> int:
+        """
+        Convert a 1-based column in the units of an encoding to characters.
+
+        A column that falls inside a character (the second half of a UTF-16
+        surrogate pair, a continuation byte) maps to that character.
+        """
+        _check_encoding(encoding)
+        if encoding == CHARACTER_ENCODING or column <= 1:
+            return column
+        text = self.line(line_no)
+        # Column of the first unit of the current character
+        units = 1
+        for index, char in enumerate(text):
+            width = _width(char, encoding)
+            if column < units + width:
+                return index + 1
+            units += width
+        return len(text) + 1 + (column - units)
+
+
+@dataclass(frozen=True)
+class SourceSpan:
+    """Start and exclusive end of a source item: 1-based lines and character columns."""
+    start_line: int
+    start_column: int
+    end_line: int
+    end_column: int
+    lines: LineIndex = field(compare=False, repr=False)
+
+    def columns(self, encoding: str = CHARACTER_ENCODING) -> Tuple[int, int]:
+        """Start and end column in the units of an encoding."""
+        return (self.lines.encode_column(self.start_line, self.start_column, encoding),
+                self.lines.encode_column(self.end_line, self.end_column, encoding))
+
+    def utf8(self) -> Tuple[int, int]:
+        return self.columns("utf-8")
+
+    def utf16(self) -> Tuple[int, int]:
+        return self.columns("utf-16")
+
+    def to_dict(self, encoding: str = CHARACTER_ENCODING) -> dict:
+        start, end = self.columns(encoding)
+        return {
+            "start": {"line": self.start_line, "column": start},
+            "end": {"line": self.end_line, "column": end},
+            "encoding": encoding,
+        }
+
+
+def assign_spans(nodes: Iterable[Any], source: str) -> None:
+    """
+    Complete the span of every node parsed from a source text, in place.
+
+    Nodes keep the ``column`` / ``end_column`` their parser recorded;
+    otherwise they span from the start of their first line to the end of
+    their last one (a node without an end line covers its start line).
+    Every node with a line then gets ``start_byte`` and ``end_byte``, and
+    a File node spans all of the text.
+    """
+    lines = LineIndex(source)
+    for node in nodes:
+        properties = node.properties
+        if node.node_type == "File":
+            properties["start_byte"], properties["end_byte"] = 0, _utf8_length(source)
+            continue
+        if not node.line_no:
+            continue
+        end_line = node.end_line_no or node.line_no
+        if not properties.get("column"):
+            properties["column"] = 1
+            properties["end_column"] = len(lines.line(end_line)) + 1
+        elif not properties.get("end_column"):
+            properties["end_column"] = len(lines.line(end_line)) + 1
+        properties["start_byte"] = lines.byte_offset(node.line_no, properties["column"])
+        properties["end_byte"] = max(properties["start_byte"], lines.byte_offset(end_line, properties["end_column"]))
@@ -0,0 +1,90 @@
+"""
+Fully qualified names of symbols: ``crate::person::Person::new`` rather
+than ``new``.
+
+A symbol's qualified name is the module path of its file followed by the
+names of the symbols enclosing it and its own name, joined by the
+language's separator: ``::`` for Rust, C++ and Ruby, ``.`` elsewhere. The
+enclosing symbols are those whose DEFINES edge leads to it (a class for
+its methods and fields, a function for its closures), or, for a method
+whose type is defined elsewhere (a Rust impl or Go method in another
+file), the type its ``method_of`` property names.
+
+The module path comes from what the parser recorded on the file node:
+
+- Rust: the file's place in the crate's module tree, under the package's
+  name in a workspace (``crate::person``, ``geometry::shapes``)
+- Java and Go: the package (``com.example.model``, ``model``)
+- Python, JavaScript and TypeScript: the module name, which is the file
+  name without its extension (the package's name for an ``__init__.py``)
+- C, C++ and Ruby: none, so names start at the outermost type or module
+
+Names are computed once per file, right after it is parsed, and stored as
+the ``qualified_name`` property, so they stay the same whichever order
+files are parsed in.
+"""
+
+import os
+from typing import Dict, Iterable, Optional
+
+from src.ast_parser.parser import CodeNode, CodeRelation, python_module_name
+
+# Separator between the parts of a qualified name, by language; "." otherwise
+QUALIFIED_SEPARATORS = {"rust": "::", "cpp": "::", "c": "::", "ruby": "::"}
+
+
+def qualified_separator(language: Optional[str]) -> str:
+    return QUALIFIED_SEPARATORS.get(language or "", ".")
+
+
+def module_path(file_node: Optional[CodeNode], file_path: str, language: Optional[str]) -> Optional[str]:
+    """Module path of a file, the first part of the qualified names of its symbols."""
+    properties = file_node.properties if file_node is not None else {}
+    if language == "rust":
+        return properties.get("qualified_module") or properties.get("module_path")
+    if language in ("java", "go"):
+        return properties.get("package")
+    if language in ("c", "cpp", "ruby"):
+        return None
+    if language == "python":
+        return python_module_name(file_path, init_reexports=True)
+    return os.path.splitext(os.path.basename(file_path))[0] or None
+
+
+def assign_qualified_names(nodes: Dict[str, CodeNode], relations: Iterable[CodeRelation],
+                           file_path: str, language: Optional[str]) -> None:
+    """
+    Set the ``qualified_name`` property of every symbol parsed from one file.
+
+    Args:
+        nodes: The file's nodes by ID, its File node included
+        relations: The file's relations; DEFINES edges between its symbols
+            give the enclosing symbols
+        file_path: Path of the file
+        language: Language the file was parsed as
+    """
+    separator = qualified_separator(language)
+    file_node = next((node for node in nodes.values() if node.node_type == "File"), None)
+    module = module_path(file_node, file_path, language)
+
+    parent: Dict[str, str] = {}
+    for relation in relations:
+        if relation.relation_type == "DEFINES" and relation.source_id in nodes and relation.target_id in nodes \
+                and nodes[relation.source_id].node_type != "File":
+            parent.setdefault(relation.target_id, relation.source_id)
+
+    for node_id, node in nodes.items():
+        if node.node_type == "File" or not node.name:
+            continue
+        parts, current, seen = [], node_id, set()
+        while current is not None and current not in seen:
+            seen.add(current)
+            parts.append(nodes[current].name)
+            owner = parent.get(current)
+            if owner is None and nodes[current].properties.get("method_of"):
+                # The type is defined in another file, or its DEFINES edge comes in the second pass
+                parts.append(nodes[current].properties["method_of"])
+            current = owner
+        if module:
+            parts.append(module)
+        node.properties["qualified_name"] = separator.join(reversed(parts))
@@ -0,0 +1,133 @@
+"""
+Test functions, and TESTS edges to the code they exercise.
+
+Test functions are recognised per language:
+
+- Python: functions and methods named ``test*`` in a file named
+  ``test_*.py`` or ``*_test.py``, and ``test*`` methods of a class
+  extending ``TestCase`` (``unittest.TestCase``, Django's and the like)
+- Rust: functions carrying ``#[test]``, ``#[tokio::test]`` or another test
+  attribute (``bench``, ``rstest``, ``test_case``)
+- Go: ``Test*``, ``Benchmark*``, ``Example*`` and ``Fuzz*`` functions of a
+  ``*_test.go`` file
+- JavaScript and TypeScript: a ``*.test.*`` or ``*.spec.*`` file, whose
+  cases are anonymous callbacks, is a test as a whole: the file and every
+  function in it
+
+Each gets ``is_test: True``, and so does the File node of a file holding one
+or named like a test file. ``tests_relations`` turns the calls a test function
+makes into TESTS edges, one per test and symbol called, leaving out symbols
+of test-flagged files so that the helpers of test modules do not pass for
+covered production code. Rust unit tests sit in a ``#[cfg(test)]`` module
+next to the code they test, so there the test's own file is kept, only its
+test functions are left out.
+"""
+
+import os
+import re
+from typing import Any, Dict, Iterable, List, Mapping, Optional, Set, Tuple
+
+from src.ast_parser.language_detector import detect_language
+from src.ast_parser.parser import CodeNode, CodeRelation
+
+TESTS_RELATION = "TESTS"
+
+TEST_ATTRIBUTES = re.compile(r"^(\w+::)*(test|bench|rstest|test_case)\b")
+TEST_FILE_NAME = re.compile(r"^test_.*\.py$|_test\.(py|go)$|\.(test|spec)\.[cm]?[jt]sx?$")
+GO_TEST_NAME = re.compile(r"^(Test|Benchmark|Example|Fuzz)([A-Z_]|$)")
+
+# Languages whose unit tests share a file with the code under test
+INLINE_TEST_LANGUAGES = ("rust",)
+
+
+def is_test_file_name(file_path: str) -> bool:
+    """Whether a file is named like a test file, e.g. ``test_app.py`` or ``cart.test.ts``."""
+    return TEST_FILE_NAME.search(os.path.basename(file_path)) is not None
+
+
+def mark_tests(file_path: str, nodes: Mapping[str, CodeNode], relations: Iterable[CodeRelation],
+               pending_imports: Iterable[Dict[str, Any]] = (), language: Optional[str] = None) -> bool:
+    """
+    Flag the test functions among one file's nodes, and its File node when
+    it holds any or is named like a test file.
+
+    Args:
+        file_path: The file parsed
+        nodes: Its nodes, flagged in place
+        relations: Its relations, for the methods of ``TestCase`` classes
+        pending_imports: Its unresolved references, for base classes imported from elsewhere
+        language: Its language, by default from the extension
+
+    Returns:
+        Whether the file was flagged
+    """
+    language = language or detect_language(file_path)
+    named = is_test_file_name(file_path)
+    test_case_methods = _test_case_methods(nodes, relations, pending_imports) if language == "python" else set()
+    found = False
+    for node in nodes.values():
+        if node.node_type in ("Function", "Method") and _is_test(node, language, named, test_case_methods):
+            node.properties["is_test"] = True
+            found = True
+    file_node = nodes.get(f"file:{file_path}")
+    if file_node is not None and (found or named):
+        file_node.properties["is_test"] = True
+    return found or named
+
+
+def _is_test(node: CodeNode, language: Optional[str], named: bool, test_case_methods: Set[str]) -> bool:
+    if language == "python":
+        return node.name.startswith("test") and (named or node.node_id in test_case_methods)
+    if language == "rust":
+        return any(TEST_ATTRIBUTES.match(a) for a in node.properties.get("attributes") or [])
+    if language == "go":
+        return named and node.node_type == "Function" and GO_TEST_NAME.match(node.name) is not None
+    if language in ("javascript", "typescript"):
+        return named
+    return False
+
+
+def _test_case_methods(nodes: Mapping[str, CodeNode], relations: Iterable[CodeRelation],
+                       pending_imports: Iterable[Dict[str, Any]]) -> Set[str]:
+    """IDs of the ``test*`` methods of classes extending a ``TestCase``."""
+    relations = list(relations)
+    classes = {r.source_id for r in relations
+               if r.relation_type == "EXTENDS" and r.target_id.rsplit(":", 2)[-2].endswith("TestCase")}
+    classes.update(entry["source_id"] for entry in pending_imports
+                   if entry.get("type") == "EXTENDS"
+                   and str(entry.get("original_name", "")).split(".")[-1].endswith("TestCase"))
+    return {r.target_id for r in relations
+            if r.relation_type == "DEFINES" and r.source_id in classes
+            and r.target_id in nodes and nodes[r.target_id].name.startswith("test")}
+
+
+def tests_relations(relations: Iterable[CodeRelation], nodes: Mapping[str, CodeNode]) -> List[CodeRelation]:
+    """
+    TESTS edges for the calls among ``relations`` that test functions make.
+
+    Args:
+        relations: Relations to draw calls from, of one file or many
+        nodes: Nodes of the whole graph, File nodes included, so that a
+            callee's file can be told to be a test file
+
+    Returns:
+        One edge per test and symbol called, carrying the line of the first call
+    """
+    edges: List[CodeRelation] = []
+    seen: Set[Tuple[str, str]] = set()
+    for relation in relations:
+        if relation.relation_type != "CALLS" or (relation.source_id, relation.target_id) in seen:
+            continue
+        source, target = nodes.get(relation.source_id), nodes.get(relation.target_id)
+        if source is None or target is None or not source.properties.get("is_test") \
+                or target.properties.get("is_test") or target.node_type in ("File", "Unresolved"):
+            continue
+        target_file = nodes.get(f"file:{target.file_path}")
+        if target_file is not None and target_file.properties.get("is_test") \
+                and not (target.file_path == source.file_path
+                         and detect_language(source.file_path) in INLINE_TEST_LANGUAGES):
+            continue
+        seen.add((relation.source_id, relation.target_id))
+        edges.append(CodeRelation(relation.source_id, relation.target_id, TESTS_RELATION,
+                                  {"line_no": relation.properties.get("line_no")}))
+    return edges
@@ -5,10 +5,36 @@ import tree_sitter_javascript
 import tree_sitter_typescript
 from tree_sitter import Language, Parser, Node, Query, QueryCursor
 
-from src.ast_parser.parser import CodeNode, CodeRelation
+from src.ast_parser.complexity import tree_sitter_complexity
+from src.ast_parser.diagnostics import ParseError, read_error, tree_sitter_errors
+from src.ast_parser.line_metrics import count_lines
+from src.ast_parser.positions import read_text
+from src.ast_parser.workspace import workspace_properties
+from src.ast_parser.parser import ASTParser, CodeNode, CodeRelation, clean_doc_comment, es_module_name, set_doc
 
 logger = logging.getLogger(__name__)
 
+# TypeScript-only declarations, mapped to the node type they produce
+TYPE_DECLARATIONS = {
+    "interface_declaration": "Interface",
+    "type_alias_declaration": "TypeAlias",
+    "enum_declaration": "Enum",
+}
+
+# Declarations that can follow `export` and carry the exported name
+EXPORTABLE_DECLARATIONS = [
+    "function_declaration", "class_declaration", "abstract_class_declaration",
+    "lexical_declaration", *TYPE_DECLARATIONS,
+]
+
+# Nameless values `export default` can take, by the node type they become
+ANONYMOUS_DEFAULT_EXPORTS = {
+    "function_expression": "Function", "function": "Function", "generator_function": "Function",
+    "arrow_function": "Function", "class": "Class",
+}
+
+JSX_NODE_TYPES = {"jsx_element", "jsx_self_closing_element", "jsx_fragment"}
+
 
 class TypeScriptParser:
     """Parser for JavaScript and TypeScript files using tree-sitter.
@@ -30,6 +56,10 @@ class TypeScriptParser:
         self.pending_imports: List[Dict[str, Any]] = []
         self.module_to_file: Dict[str, str] = {}
         self.established_relations: Set[str] = set()
+        # (class node id, class name, interface as written) of this file's implements clauses
+        self.implements_clauses: List[Tuple[str, str, str]] = []
+        # Syntax errors of the file parsed last
+        self.parse_errors: List[ParseError] = []
         
         # Initialize tree-sitter parsers for JavaScript and TypeScript
         try:
@@ -79,12 +109,36 @@ class TypeScriptParser:
             file_path: Path to the file to parse
             build_index: Whether to build module definition index
             
+        Returns:
+            Tuple of (nodes dictionary, relations list)
+        """
+        try:
+            file_content = read_text(file_path)
+        except (OSError, UnicodeDecodeError) as e:
+            logger.error(f"Error reading file {file_path}: {e}")
+            print(f"Error reading file {file_path}: {e}")
+            self.parse_errors = [read_error(e)]
+            return {}, []
+
+        return self.parse_source(file_content, file_path, build_index=build_index)
+
+    def parse_source(self, file_content: str, file_path: str, build_index: bool = False,
+                     extension: Optional[str] = None) -> Tuple[Dict[str, CodeNode], List[CodeRelation]]:
+        """Parse JavaScript/TypeScript source text that may not exist on disk.
+        
+        Args:
+            file_content: Source code to parse
+            file_path: Path used for node IDs, locations and grammar selection
+            build_index: Whether to build module definition index
+            extension: Extension selecting the grammar instead of file_path's, e.g. ".tsx"
+            
         Returns:
             Tuple of (nodes dictionary, relations list)
         """
         print(f"Parsing file: {file_path}")
         self.current_file = file_path
         self.imports = {}
+        self.implements_clauses = []
         
         # Reset nodes and relations if not building an index (standalone parse)
         if not build_index:
@@ -92,42 +146,44 @@ class TypeScriptParser:
             self.relations = []
 
         try:
-            with open(file_path, "r", encoding="utf-8") as file:
-                file_content = file.read()
-                
-                # Select appropriate parser based on file extension
-                parser = self._get_parser_for_file(file_path)
-                tree = parser.parse(bytes(file_content, "utf8"))
-                
-                file_node_id = self._create_file_node(file_path)
-                
-                # Generate module name for indexing
-                module_name = os.path.splitext(os.path.basename(file_path))[0]
-                if build_index:
-                    if module_name not in self.module_definitions:
-                        self.module_definitions[module_name] = {}
-                    # Associate module name with file node
-                    self.module_to_file[module_name] = file_node_id
-                
-                # Parse the syntax tree
-                self._parse_tree(tree.root_node, file_content, build_index, module_name)
+            # Select appropriate parser based on file extension
+            parser = self._get_parser_for_file(file_path, extension)
+            source = bytes(file_content, "utf8")
+            tree = parser.parse(source)
+            # Declarations outside the ERROR nodes are still extracted below
+            self.parse_errors = tree_sitter_errors(tree.root_node, source) if tree.root_node.has_error else []
+            
+            file_node_id = self._create_file_node(file_path, file_content)
+            
+            # Generate module name for indexing
+            module_name = os.path.splitext(os.path.basename(file_path))[0]
+            if build_index:
+                if module_name not in self.module_definitions:
+                    self.module_definitions[module_name] = {}
+                # Associate module name with file node
+                self.module_to_file[module_name] = file_node_id
+            
+            # Parse the syntax tree
+            self._parse_tree(tree.root_node, file_content, build_index, module_name)
 
             return self.nodes, self.relations
         except Exception as e:
             logger.error(f"Error parsing file {file_path}: {e}")
             print(f"Error parsing file {file_path}: {e}")
+            self.parse_errors = [ParseError(f"Parser failed: {e}")]
             return {}, []
 
-    def _get_parser_for_file(self, file_path: str) -> Parser:
+    def _get_parser_for_file(self, file_path: str, extension: Optional[str] = None) -> Parser:
         """Select the appropriate parser based on file extension.
         
         Args:
             file_path: Path to the file
+            extension: Extension to use instead of the path's
             
         Returns:
             Tree-sitter parser instance
         """
-        ext = os.path.splitext(file_path)[1].lower()
+        ext = (extension or os.path.splitext(file_path)[1]).lower()
         if ext == '.ts':
             return self.ts_parser
         elif ext in ['.tsx']:
@@ -135,11 +191,12 @@ class TypeScriptParser:
         else:  # .js, .jsx
             return self.js_parser
 
-    def _create_file_node(self, file_path: str) -> str:
+    def _create_file_node(self, file_path: str, source: str = "") -> str:
         """Create a file node.
         
         Args:
             file_path: Path to the file
+            source: File contents, whose line counts are kept on the node
             
         Returns:
             Node ID of the created file node
@@ -152,6 +209,7 @@ class TypeScriptParser:
             name=file_name,
             file_path=file_path,
             line_no=0,
+            properties={**count_lines(source, "javascript"), **workspace_properties(file_path)},
         )
         return node_id
 
@@ -187,14 +245,22 @@ class TypeScriptParser:
         # Extract classes
         self._extract_classes(root_node, source_code, build_index, module_name, language)
         
+        # Extract interfaces, type aliases and enums (not part of the JavaScript grammar)
+        if language is not self.js_language:
+            self._extract_type_declarations(root_node, source_code, build_index, module_name, language)
+        
         # Extract top-level variables
         self._extract_variables(root_node, source_code, language)
         
-        # Extract imports
+        # Extract imports (ES imports and CommonJS require calls)
         self._extract_imports(root_node, source_code, language)
+        self._extract_requires(root_node, source_code, language)
         
         # Extract exports
         self._extract_exports(root_node, source_code, language)
+        
+        # Link implements clauses, now that the file's interfaces and imports are known
+        self._link_implements()
 
     def _extract_functions(self, root_node: Node, source_code: str, build_index: bool = False, module_name: str = "", language: Language = None) -> None:
         """Extract function declarations from the syntax tree.
@@ -251,10 +317,14 @@ class TypeScriptParser:
                                 "language": self._get_language_from_file(),
                                 "function_style": "standard",
                                 "is_async": is_async,
+                                "complexity": tree_sitter_complexity(func_node, "typescript"),
                             },
                         )
+                        if self._is_react_component(func_name, func_node):
+                            self.nodes[node_id].properties["react_component"] = True
                         
                         self.nodes[node_id].code_snippet = self._get_node_text(func_node, source_code)
+                        self._set_doc(node_id, func_node, source_code)
                         
                         file_node_id = f"file:{self.current_file}"
                         self.relations.append(
@@ -312,10 +382,15 @@ class TypeScriptParser:
                                 "language": self._get_language_from_file(),
                                 "function_style": "arrow",
                                 "is_async": is_async,
+                                "complexity": tree_sitter_complexity(arrow_node, "typescript"),
                             },
                         )
+                        if self._is_react_component(func_name, arrow_node):
+                            self.nodes[node_id].properties["react_component"] = True
                         
                         self.nodes[node_id].code_snippet = self._get_node_text(arrow_node, source_code)
+                        # The JSDoc sits above the const declaring the arrow function
+                        self._set_doc(node_id, arrow_node.parent.parent, source_code)
                         
                         file_node_id = f"file:{self.current_file}"
                         self.relations.append(
@@ -346,6 +421,8 @@ class TypeScriptParser:
             
         # Use simpler query and extract name manually to support both JS and TS
         query_str = "(class_declaration) @class"
+        if language is not self.js_language:
+            query_str = "[(class_declaration) (abstract_class_declaration)] @class"
         
         try:
             query = Query(language, query_str)
@@ -381,9 +458,12 @@ class TypeScriptParser:
                                 "language": self._get_language_from_file(),
                             },
                         )
+                        if node.type == "abstract_class_declaration":
+                            self.nodes[node_id].properties["is_abstract"] = True
                         
                         # Add code snippet
                         self.nodes[node_id].code_snippet = self._get_node_text(node, source_code)
+                        self._set_doc(node_id, node, source_code)
                         
                         # Create relationship: file contains class
                         file_node_id = f"file:{self.current_file}"
@@ -445,9 +525,54 @@ class TypeScriptParser:
                                         relation_type="EXTENDS",
                                     )
                                 )
+
+                    # TypeScript: class Foo extends Bar implements Baz, Qux<T>
+                    for clause in child.children:
+                        if clause.type != "implements_clause":
+                            continue
+                        for type_node in clause.named_children:
+                            self.implements_clauses.append(
+                                (class_node_id, self.nodes[class_node_id].name,
+                                 self._get_node_text(type_node, source_code)))
         except Exception as e:
             logger.warning(f"Error extracting class inheritance: {e}")
 
+    def _link_implements(self) -> None:
+        """Create IMPLEMENTS edges for the implements clauses of this file's classes.
+        
+        An interface declared in the same file is linked directly. Otherwise
+        the edge is queued like a Rust trait impl, with the module it was
+        imported from as a hint, and resolved once every file is indexed;
+        interfaces that are never found point at an ``Unresolved`` node.
+        """
+        for class_node_id, class_name, interface in self.implements_clauses:
+            # Qux<T> -> Qux, ns.Qux -> Qux
+            interface_name = interface.split("<", 1)[0].strip().split(".")[-1]
+            local = next((node.node_id for node in self.nodes.values()
+                          if node.node_type == "Interface" and node.name == interface_name
+                          and node.file_path == self.current_file), None)
+            if local:
+                self.relations.append(CodeRelation(
+                    source_id=class_node_id,
+                    target_id=local,
+                    relation_type="IMPLEMENTS",
+                    properties={"trait_path": interface},
+                ))
+                continue
+            
+            imported_from = self.imports.get(interface.split("<", 1)[0].strip().split(".")[0])
+            self.pending_imports.append({
+                "type": "IMPLEMENTS",
+                "source_id": f"file:{self.current_file}",
+                "type_name": class_name,
+                "type_node_id": class_node_id,
+                "trait_name": interface_name,
+                "trait_node_id": None,
+                "trait_path": interface,
+                "methods": {},
+                "module_hints": [es_module_name(imported_from)] if imported_from else [],
+            })
+
     def _extract_class_methods(self, class_node: Node, class_node_id: str, class_name: str, source_code: str) -> None:
         """Extract methods from a class.
         
@@ -467,12 +592,16 @@ class TypeScriptParser:
                             # Get method name
                             method_name = None
                             is_async = False
+                            visibility = "Public"
                             
                             for method_child in method_node.children:
                                 if method_child.type == "property_identifier":
                                     method_name = self._get_node_text(method_child, source_code)
                                 elif method_child.type == "async":
                                     is_async = True
+                                elif method_child.type == "accessibility_modifier":
+                                    # TypeScript public / protected / private
+                                    visibility = self._get_node_text(method_child, source_code).capitalize()
                             
                             if method_name:
                                 line_no = method_node.start_point[0] + 1
@@ -497,11 +626,14 @@ class TypeScriptParser:
                                         "parameters": params,
                                         "language": self._get_language_from_file(),
                                         "is_async": is_async,
+                                        "visibility": visibility,
+                                        "complexity": tree_sitter_complexity(method_node, "typescript"),
                                     },
                                 )
                                 
                                 # Add code snippet
                                 self.nodes[node_id].code_snippet = self._get_node_text(method_node, source_code)
+                                self._set_doc(node_id, method_node, source_code)
                                 
                                 # Create relationship: class defines method
                                 self.relations.append(
@@ -514,6 +646,104 @@ class TypeScriptParser:
         except Exception as e:
             logger.warning(f"Error extracting class methods: {e}")
 
+    def _extract_type_declarations(self, root_node: Node, source_code: str, build_index: bool = False, module_name: str = "", language: Language = None) -> None:
+        """Extract TypeScript interfaces, type aliases and enums.
+        
+        Args:
+            root_node: Root node of the syntax tree
+            source_code: Source code of the file
+            build_index: Whether to build module definition index
+            module_name: Name of the module
+            language: Tree-sitter language object for queries (TypeScript or TSX)
+        """
+        query_str = "[" + " ".join(f"({kind})" for kind in TYPE_DECLARATIONS) + "] @declaration"
+        
+        try:
+            query = Query(language, query_str)
+            cursor = QueryCursor(query)
+            capture_dict = cursor.captures(root_node)
+            
+            file_node_id = f"file:{self.current_file}"
+            
+            for node in capture_dict.get("declaration", []):
+                name_node = node.child_by_field_name("name")
+                if name_node is None:
+                    continue
+                
+                type_name = self._get_node_text(name_node, source_code)
+                node_type = TYPE_DECLARATIONS[node.type]
+                line_no = node.start_point[0] + 1
+                end_line_no = node.end_point[0] + 1
+                node_id = self._get_node_id(node_type, type_name, self.current_file, line_no)
+                
+                properties: Dict[str, Any] = {"language": self._get_language_from_file()}
+                body = node.child_by_field_name("body")
+                if node.type == "interface_declaration":
+                    properties["members"] = self._member_names(body, source_code)
+                    properties["extends"] = [
+                        self._get_node_text(type_node, source_code)
+                        for child in node.children if child.type == "extends_type_clause"
+                        for type_node in child.named_children
+                    ]
+                elif node.type == "type_alias_declaration":
+                    value = node.child_by_field_name("value")
+                    properties["value"] = self._get_node_text(value, source_code) if value else ""
+                else:
+                    properties["members"] = self._member_names(body, source_code)
+                    properties["is_const"] = any(child.type == "const" for child in node.children)
+                
+                self.nodes[node_id] = CodeNode(
+                    node_id=node_id,
+                    node_type=node_type,
+                    name=type_name,
+                    file_path=self.current_file,
+                    line_no=line_no,
+                    end_line_no=end_line_no,
+                    properties=properties,
+                )
+                self.nodes[node_id].code_snippet = self._get_node_text(node, source_code)
+                self._set_doc(node_id, node, source_code)
+                
+                self.relations.append(
+                    CodeRelation(
+                        source_id=file_node_id,
+                        target_id=node_id,
+                        relation_type="CONTAINS",
+                    )
+                )
+                
+                if build_index and module_name:
+                    self.module_definitions[module_name][type_name] = node_id
+        except Exception as e:
+            logger.warning(f"Error extracting type declarations: {e}")
+
+    def _member_names(self, body: Optional[Node], source_code: str) -> List[str]:
+        """Names of the members declared in an interface or enum body.
+        
+        Args:
+            body: interface_body or enum_body node, if any
+            source_code: Source code of the file
+            
+        Returns:
+            Member names in declaration order
+        """
+        names = []
+        if body is None:
+            return names
+        for child in body.named_children:
+            if child.type == "property_identifier":
+                # Enum member without initializer
+                names.append(self._get_node_text(child, source_code))
+                continue
+            if child.type == "enum_assignment":
+                # Enum member with initializer; the name has no field of its own
+                names.append(self._get_node_text(child.named_children[0], source_code))
+                continue
+            name_node = child.child_by_field_name("name")
+            if name_node is not None:
+                names.append(self._get_node_text(name_node, source_code))
+        return names
+
     def _extract_variables(self, root_node: Node, source_code: str, language: Language = None) -> None:
         """Extract top-level variable declarations from the syntax tree.
         
@@ -615,6 +845,11 @@ class TypeScriptParser:
     def _extract_imports(self, root_node: Node, source_code: str, language: Language = None) -> None:
         """Extract import statements from the syntax tree.
         
+        Each imported name is queued as an IMPORTS entry against the module
+        named by the specifier. Namespace and side-effect imports are queued
+        without a name and resolve to the imported file, as is TypeScript's
+        `import x = require("...")`.
+        
         Args:
             root_node: Root node of the syntax tree
             source_code: Source code of the file
@@ -630,48 +865,37 @@ class TypeScriptParser:
             query = Query(language, query_str)
             cursor = QueryCursor(query)
             capture_dict = cursor.captures(root_node)
-            captures = []
-            for capture_name, nodes in capture_dict.items():
-                for node in nodes:
-                    captures.append((node, capture_name))
-            
-            file_node_id = f"file:{self.current_file}"
             
-            for node, capture_name in captures:
-                if capture_name == "import":
-                    # Extract source module
-                    source_module = None
-                    imported_names = []
-                    
+            for node in capture_dict.get("import", []):
+                source_node = node.child_by_field_name("source")
+                if source_node is None:
+                    # import x = require("...")
                     for child in node.children:
-                        if child.type == "string":
-                            # Remove quotes from string
-                            source_module = self._get_node_text(child, source_code).strip('"\'')
-                        elif child.type == "import_clause":
-                            # Extract imported names
-                            imported_names = self._extract_import_names(child, source_code)
-                    
-                    if source_module:
-                        # Add to imports mapping
-                        for name in imported_names:
-                            self.imports[name] = source_module
-                        
-                        # Add to pending imports for later resolution
-                        root_module = source_module.split('/')[0] if '/' in source_module else source_module
-                        
-                        for name in imported_names:
-                            self.pending_imports.append({
-                                "type": "IMPORTS",
-                                "source_id": file_node_id,
-                                "imported_module": root_module,
-                                "imported_name": name,
-                                "original_name": name,
-                            })
+                        if child.type == "import_require_clause":
+                            self._extract_import_require(child, source_code)
+                    continue
+                source_module = self._get_node_text(source_node, source_code).strip('"\'')
+                
+                imported_names = []
+                for child in node.children:
+                    if child.type == "import_clause":
+                        imported_names = self._extract_import_names(child, source_code)
+                
+                # Add to imports mapping
+                for local_name, _ in imported_names:
+                    self.imports[local_name] = source_module
+                
+                # Add to pending imports for later resolution
+                targets = [original for _, original in imported_names]
+                if not targets or None in targets:
+                    targets = [None] + [name for name in targets if name is not None]
+                for name in targets:
+                    self._queue_import(source_module, name)
                             
         except Exception as e:
             logger.warning(f"Error extracting imports: {e}")
 
-    def _extract_import_names(self, import_clause: Node, source_code: str) -> List[str]:
+    def _extract_import_names(self, import_clause: Node, source_code: str) -> List[Tuple[str, Optional[str]]]:
         """Extract names from an import clause.
         
         Args:
@@ -679,7 +903,8 @@ class TypeScriptParser:
             source_code: Source code of the file
             
         Returns:
-            List of imported names
+            List of (local name, imported name) pairs; the imported name is
+            None for a namespace import
         """
         names = []
         
@@ -687,27 +912,168 @@ class TypeScriptParser:
             for child in import_clause.children:
                 if child.type == "identifier":
                     # Default import
-                    names.append(self._get_node_text(child, source_code))
+                    name = self._get_node_text(child, source_code)
+                    names.append((name, name))
                 elif child.type == "named_imports":
-                    # Named imports
+                    # Named imports, possibly aliased (import { a as b })
                     for named_child in child.children:
                         if named_child.type == "import_specifier":
-                            for spec_child in named_child.children:
-                                if spec_child.type == "identifier":
-                                    names.append(self._get_node_text(spec_child, source_code))
+                            name_node = named_child.child_by_field_name("name")
+                            alias_node = named_child.child_by_field_name("alias")
+                            if name_node is None:
+                                continue
+                            name = self._get_node_text(name_node, source_code)
+                            local = self._get_node_text(alias_node, source_code) if alias_node else name
+                            names.append((local, name))
                 elif child.type == "namespace_import":
                     # Namespace import (import * as name)
                     for ns_child in child.children:
                         if ns_child.type == "identifier":
-                            names.append(self._get_node_text(ns_child, source_code))
+                            names.append((self._get_node_text(ns_child, source_code), None))
         except Exception as e:
             logger.warning(f"Error extracting import names: {e}")
         
         return names
 
+    def _extract_import_require(self, require_clause: Node, source_code: str) -> None:
+        """Queue a TypeScript `import x = require("...")` clause.
+        
+        Args:
+            require_clause: Tree-sitter import_require_clause node
+            source_code: Source code of the file
+        """
+        source_node = require_clause.child_by_field_name("source")
+        if source_node is None:
+            return
+        source_module = self._get_node_text(source_node, source_code).strip('"\'')
+        for child in require_clause.children:
+            if child.type == "identifier":
+                self.imports[self._get_node_text(child, source_code)] = source_module
+                break
+        self._queue_import(source_module, None, require=True)
+
+    def _extract_requires(self, root_node: Node, source_code: str, language: Language = None) -> None:
+        """Extract CommonJS `require()` calls from the syntax tree.
+        
+        Handles:
+        - const lib = require("./lib")          (resolves to the file)
+        - const { a, b: c } = require("./lib")  (resolves to a and b)
+        - const a = require("./lib").a          (resolves to a)
+        - require("./lib")                      (resolves to the file)
+        
+        Args:
+            root_node: Root node of the syntax tree
+            source_code: Source code of the file
+            language: Tree-sitter language object for queries
+        """
+        if language is None:
+            language = self.js_language
+        
+        query_str = """
+        (call_expression
+          function: (identifier) @function
+          arguments: (arguments . (string) .)) @call
+        """
+        
+        try:
+            query = Query(language, query_str)
+            cursor = QueryCursor(query)
+            capture_dict = cursor.captures(root_node)
+            
+            for call_node in capture_dict.get("call", []):
+                function_node = call_node.child_by_field_name("function")
+                if function_node is None or self._get_node_text(function_node, source_code) != "require":
+                    continue
+                arguments = call_node.child_by_field_name("arguments")
+                source_nodes = [c for c in arguments.children if c.type == "string"] if arguments else []
+                if not source_nodes:
+                    continue
+                source_module = self._get_node_text(source_nodes[0], source_code).strip('"\'')
+                
+                imported_names = self._require_bindings(call_node, source_code)
+                for local_name, _ in imported_names:
+                    self.imports[local_name] = source_module
+                targets = [original for _, original in imported_names]
+                if not targets or None in targets:
+                    targets = [None] + [name for name in targets if name is not None]
+                for name in targets:
+                    self._queue_import(source_module, name, require=True)
+                    
+        except Exception as e:
+            logger.warning(f"Error extracting require calls: {e}")
+
+    def _require_bindings(self, call_node: Node, source_code: str) -> List[Tuple[str, Optional[str]]]:
+        """(local name, imported name) pairs bound by a require call.
+        
+        Args:
+            call_node: Tree-sitter call_expression node of the require call
+            source_code: Source code of the file
+            
+        Returns:
+            Pairs for the declaration the call initializes; the imported name
+            is None when the whole module is bound. Empty for a bare call.
+        """
+        member = None
+        node = call_node
+        if node.parent is not None and node.parent.type == "member_expression":
+            # require("./lib").a
+            property_node = node.parent.child_by_field_name("property")
+            if property_node is not None:
+                member = self._get_node_text(property_node, source_code)
+                node = node.parent
+        
+        declarator = node.parent
+        if declarator is None or declarator.type != "variable_declarator":
+            return [(member, member)] if member else []
+        name_node = declarator.child_by_field_name("name")
+        if name_node is None:
+            return []
+        if name_node.type == "identifier":
+            return [(self._get_node_text(name_node, source_code), member)]
+        
+        names = []
+        if name_node.type == "object_pattern" and member is None:
+            for child in name_node.children:
+                if child.type == "shorthand_property_identifier_pattern":
+                    name = self._get_node_text(child, source_code)
+                    names.append((name, name))
+                elif child.type == "pair_pattern":
+                    key_node = child.child_by_field_name("key")
+                    value_node = child.child_by_field_name("value")
+                    if key_node is not None and value_node is not None:
+                        names.append((self._get_node_text(value_node, source_code),
+                                      self._get_node_text(key_node, source_code)))
+        return names
+
+    def _queue_import(self, source_module: str, imported_name: Optional[str],
+                      import_type: str = "IMPORTS", require: bool = False) -> None:
+        """Queue an IMPORTS or REEXPORTS entry for resolution once every file is indexed.
+        
+        Args:
+            source_module: Module specifier as written, e.g. "./models"
+            imported_name: Imported symbol, or None to target the module's file
+            import_type: "IMPORTS", or "REEXPORTS" for an `export ... from` statement
+            require: Whether the entry comes from a CommonJS `require()` call
+        """
+        import_info = {
+            "type": import_type,
+            "source_id": f"file:{self.current_file}",
+            "imported_module": es_module_name(source_module),
+            "full_module_path": source_module,
+            "imported_name": imported_name,
+            "original_name": imported_name,
+        }
+        if require:
+            import_info["require"] = True
+        self.pending_imports.append(import_info)
+
     def _extract_exports(self, root_node: Node, source_code: str, language: Language = None) -> None:
         """Extract export statements from the syntax tree.
         
+        Local exports mark the exported nodes. Re-exports (`export * from`,
+        `export { a } from`) are queued as REEXPORTS entries of the
+        re-exporting file, kept apart from the IMPORTS edges of direct use.
+        
         Args:
             root_node: Root node of the syntax tree
             source_code: Source code of the file
@@ -723,42 +1089,120 @@ class TypeScriptParser:
             query = Query(language, query_str)
             cursor = QueryCursor(query)
             capture_dict = cursor.captures(root_node)
-            captures = []
-            for capture_name, nodes in capture_dict.items():
-                for node in nodes:
-                    captures.append((node, capture_name))
-            
-            for node, capture_name in captures:
-                if capture_name == "export":
-                    # Mark exported entities
-                    for child in node.children:
-                        if child.type in ["function_declaration", "class_declaration", "lexical_declaration"]:
-                            # Find the name of the exported entity
-                            entity_name = self._extract_entity_name(child, source_code)
-                            if entity_name:
-                                # Find the corresponding node and mark as exported
-                                for node_id, code_node in self.nodes.items():
-                                    if code_node.name == entity_name and code_node.file_path == self.current_file:
-                                        code_node.properties["exported"] = True
-                                        code_node.properties["export_type"] = "named"
-                        elif child.type == "export_clause":
-                            # Named exports without declaration
-                            for export_child in child.children:
-                                if export_child.type == "export_specifier":
-                                    for spec_child in export_child.children:
-                                        if spec_child.type == "identifier":
-                                            entity_name = self._get_node_text(spec_child, source_code)
-                                            # Mark as exported
-                                            for node_id, code_node in self.nodes.items():
-                                                if code_node.name == entity_name and code_node.file_path == self.current_file:
-                                                    code_node.properties["exported"] = True
-                                                    code_node.properties["export_type"] = "named"
+            
+            for node in capture_dict.get("export", []):
+                source_node = node.child_by_field_name("source")
+                if source_node is not None:
+                    source_module = self._get_node_text(source_node, source_code).strip('"\'')
+                    names = [name for name, _ in self._export_specifiers(node, source_code)]
+                    for name in names or [None]:
+                        self._queue_import(source_module, name, import_type="REEXPORTS")
+                    continue
+                
+                export_type = "default" if any(child.type == "default" for child in node.children) else "named"
+                for child in node.children:
+                    if child.type in EXPORTABLE_DECLARATIONS:
+                        # Find the name of the exported entity
+                        entity_name = self._extract_entity_name(child, source_code)
+                        if entity_name:
+                            self._mark_exported(entity_name, export_type)
+                    elif child.type == "identifier" and export_type == "default":
+                        # export default Name;
+                        self._mark_exported(self._get_node_text(child, source_code), export_type)
+                    elif child.type in ANONYMOUS_DEFAULT_EXPORTS and export_type == "default":
+                        # export default function () {} / class {} / () => ...
+                        self._add_default_export(child, source_code)
+                
+                # Named exports without declaration; `export { a as default }` is the default export
+                for name, alias in self._export_specifiers(node, source_code):
+                    self._mark_exported(name, "default" if alias == "default" else "named", alias)
                                             
         except Exception as e:
             logger.warning(f"Error extracting exports: {e}")
 
+    def _export_specifiers(self, export_node: Node, source_code: str) -> List[Tuple[str, str]]:
+        """(local name, exported name) pairs listed in an export clause.
+        
+        Args:
+            export_node: Tree-sitter export_statement node
+            source_code: Source code of the file
+            
+        Returns:
+            Pairs in source order; empty when the statement has no clause
+        """
+        specifiers = []
+        for child in export_node.children:
+            if child.type != "export_clause":
+                continue
+            for spec in child.children:
+                if spec.type != "export_specifier":
+                    continue
+                name_node = spec.child_by_field_name("name")
+                alias_node = spec.child_by_field_name("alias")
+                if name_node is None:
+                    continue
+                name = self._get_node_text(name_node, source_code)
+                specifiers.append((name, self._get_node_text(alias_node, source_code) if alias_node else name))
+        return specifiers
+
+    def _mark_exported(self, entity_name: str, export_type: str, export_name: Optional[str] = None) -> None:
+        """Mark the current file's nodes with the given name as exported.
+        
+        Args:
+            entity_name: Name of the exported entity
+            export_type: "named" or "default"
+            export_name: Name importers use when it differs from the entity's
+                (`export { a as b }`); "default" for a default export
+        """
+        if export_type == "default":
+            export_name = "default"
+        for code_node in self.nodes.values():
+            # Methods are exported with their class, not by a same-named export
+            if code_node.name == entity_name and code_node.file_path == self.current_file \
+                    and code_node.node_type != "Method":
+                code_node.properties["exported"] = True
+                code_node.properties["export_type"] = export_type
+                code_node.properties["export_name"] = export_name or entity_name
+
+    def _add_default_export(self, value_node: Node, source_code: str) -> None:
+        """Add a node for a default export that has no declared name.
+        
+        A function or class expression keeps its own name when it has one;
+        otherwise the node is named "default", as importers see it.
+        
+        Args:
+            value_node: Tree-sitter function, arrow function or class expression
+            source_code: Source code of the file
+        """
+        node_type = ANONYMOUS_DEFAULT_EXPORTS[value_node.type]
+        name_node = value_node.child_by_field_name("name")
+        name = self._get_node_text(name_node, source_code) if name_node is not None else "default"
+        line_no = value_node.start_point[0] + 1
+        node_id = self._get_node_id(node_type, name, self.current_file, line_no)
+        properties = {"language": self._get_language_from_file()}
+        if node_type == "Function":
+            properties.update({
+                "is_method": False,
+                "parameters": self._extract_function_params(value_node, source_code),
+                "function_style": "arrow" if value_node.type == "arrow_function" else "standard",
+                "is_async": self._is_async_function(value_node),
+            })
+        self.nodes[node_id] = CodeNode(
+            node_id=node_id,
+            node_type=node_type,
+            name=name,
+            file_path=self.current_file,
+            line_no=line_no,
+            end_line_no=value_node.end_point[0] + 1,
+            properties=properties,
+        )
+        self.nodes[node_id].code_snippet = self._get_node_text(value_node, source_code)
+        self._set_doc(node_id, value_node, source_code)
+        self.relations.append(CodeRelation(f"file:{self.current_file}", node_id, "CONTAINS"))
+        self._mark_exported(name, "default")
+
     def _extract_entity_name(self, node: Node, source_code: str) -> Optional[str]:
-        """Extract the name of an entity (function, class, variable).
+        """Extract the name of an entity (function, class, variable, type).
         
         Args:
             node: Tree-sitter node representing the entity
@@ -769,7 +1213,7 @@ class TypeScriptParser:
         """
         try:
             for child in node.children:
-                if child.type == "identifier":
+                if child.type in ["identifier", "type_identifier"]:
                     return self._get_node_text(child, source_code)
                 elif child.type == "variable_declarator":
                     for decl_child in child.children:
@@ -837,7 +1281,7 @@ class TypeScriptParser:
         """
         current = node.parent
         while current:
-            if current.type == "class_declaration":
+            if current.type in ["class_declaration", "abstract_class_declaration"]:
                 return True
             current = current.parent
         return False
@@ -853,7 +1297,8 @@ class TypeScriptParser:
         """
         current = node.parent
         while current:
-            if current.type in ["function_declaration", "arrow_function", "class_declaration", "method_definition"]:
+            if current.type in ["function_declaration", "arrow_function", "class_declaration",
+                                "abstract_class_declaration", "method_definition"]:
                 return False
             current = current.parent
         return True
@@ -875,6 +1320,33 @@ class TypeScriptParser:
             current = current.parent
         return False
 
+    def _set_doc(self, node_id: str, declaration: Node, source_code: str) -> None:
+        """Store the JSDoc block right above a declaration as its doc property.
+        
+        Only ``/** */`` comments ending on the line before the declaration
+        (or before the ``export`` wrapping it) count.
+        
+        Args:
+            node_id: Node the doc belongs to
+            declaration: Tree-sitter node of the declaration
+            source_code: Source code of the file
+        """
+        set_doc(self.nodes[node_id], self._jsdoc(declaration, source_code))
+
+    def _jsdoc(self, declaration: Node, source_code: str) -> Optional[str]:
+        """The cleaned JSDoc block of a declaration, or None."""
+        if declaration.parent is not None and declaration.parent.type == "export_statement":
+            declaration = declaration.parent
+        comment = declaration.prev_sibling
+        if comment is None or comment.type != "comment":
+            return None
+        text = self._get_node_text(comment, source_code)
+        if not text.startswith("/**") or text.startswith("/**/"):
+            return None
+        if comment.end_point[0] < declaration.start_point[0] - 1:
+            return None
+        return clean_doc_comment([text])
+
     def _get_node_text(self, node: Node, source_code: str) -> str:
         """Get the text content of a tree-sitter node.
         
@@ -918,49 +1390,40 @@ class TypeScriptParser:
             # Use JS language for .js and .jsx
             return self.js_language
 
+    def _is_react_component(self, name: str, func_node: Node) -> bool:
+        """Check if a function looks like a React function component.
+        
+        A component has a capitalized name and renders JSX somewhere in its body.
+        
+        Args:
+            name: Function name
+            func_node: Tree-sitter function or arrow function node
+            
+        Returns:
+            True for a JSX/TSX function component, False otherwise
+        """
+        if self._get_language_from_file() not in ("jsx", "tsx") or not name[:1].isupper():
+            return False
+        stack = list(func_node.children)
+        while stack:
+            current = stack.pop()
+            if current.type in JSX_NODE_TYPES:
+                return True
+            stack.extend(current.children)
+        return False
+
     def _process_pending_imports(self) -> None:
         """Process pending import relationships.
         
         This method is called after all files have been parsed to resolve
-        import relationships between modules.
+        import relationships between modules. Resolution is shared with the
+        Python parser so both produce the same edges for the same entries.
         """
-        for import_info in self.pending_imports:
-            try:
-                import_type = import_info["type"]
-                source_id = import_info["source_id"]
-                imported_module = import_info["imported_module"]
-                imported_name = import_info["imported_name"]
-                
-                # Look up the target node in module definitions
-                if imported_module in self.module_definitions:
-                    if imported_name in self.module_definitions[imported_module]:
-                        target_id = self.module_definitions[imported_module][imported_name]
-                        
-                        # Create the relationship
-                        relation_key = f"{source_id}:{import_type}:{target_id}"
-                        if relation_key not in self.established_relations:
-                            self.relations.append(
-                                CodeRelation(
-                                    source_id=source_id,
-                                    target_id=target_id,
-                                    relation_type=import_type,
-                                )
-                            )
-                            self.established_relations.add(relation_key)
-                    else:
-                        # Try to link to the file node if specific entity not found
-                        if imported_module in self.module_to_file:
-                            target_id = self.module_to_file[imported_module]
-                            relation_key = f"{source_id}:{import_type}:{target_id}"
-                            if relation_key not in self.established_relations:
-                                self.relations.append(
-                                    CodeRelation(
-                                        source_id=source_id,
-                                        target_id=target_id,
-                                        relation_type=import_type,
-                                        properties={"imported_name": imported_name},
-                                    )
-                                )
-                                self.established_relations.add(relation_key)
-            except Exception as e:
-                logger.warning(f"Error processing pending import: {e}")
+        resolver = ASTParser()
+        resolver.nodes = self.nodes
+        resolver.relations = self.relations
+        resolver.module_definitions = self.module_definitions
+        resolver.module_to_file = self.module_to_file
+        resolver.pending_imports = self.pending_imports
+        resolver.established_relations = self.established_relations
+        resolver._process_pending_imports()
@@ -0,0 +1,113 @@
+"""
+Workspace members of a monorepo.
+
+A monorepo holds several packages, each rooted at a directory with a
+manifest (``Cargo.toml``, ``package.json``, ``go.mod`` or
+``pyproject.toml``), possibly nested in a workspace that has a manifest of
+its own. A file belongs to the member whose manifest is nearest above it,
+named after the package the manifest declares, or after its directory when
+it declares none (a virtual Cargo workspace, say).
+
+Two members may hold files at the same relative path, such as two
+``src/lib.rs``; ``<member>::<module path>`` tells them apart, so File nodes
+carry both the ``workspace_member`` and that ``qualified_module``.
+"""
+
+import json
+import os
+import re
+from dataclasses import dataclass
+from functools import lru_cache
+from typing import Any, Dict, Optional
+
+# Manifests marking the root of a member, in the order they are looked for
+MANIFESTS = ("Cargo.toml", "package.json", "go.mod", "pyproject.toml")
+
+# TOML tables whose `name` key is the package name
+_NAME_TABLES = {"Cargo.toml": ("package",), "pyproject.toml": ("project", "tool.poetry")}
+
+_TABLE = re.compile(r"^\s*\[([^\[\]]+)\]\s*(?:#.*)?$")
+_NAME = re.compile(r"""^\s*name\s*=\s*["']([^"']+)["']""")
+
+
+@dataclass(frozen=True)
+class WorkspaceMember:
+    """A package of the workspace and where it lives."""
+    name: str
+    # Directory holding the manifest
+    root: str
+    manifest: str
+
+
+def _toml_name(text: str, tables) -> Optional[str]:
+    """The `name` key of the first of the given tables, read line by line."""
+    table = None
+    for line in text.splitlines():
+        header = _TABLE.match(line)
+        if header:
+            table = header.group(1).strip()
+            continue
+        match = _NAME.match(line) if table in tables else None
+        if match:
+            return match.group(1)
+    return None
+
+
+def manifest_name(path: str) -> Optional[str]:
+    """The package name a manifest declares, or None when it declares none or cannot be read."""
+    try:
+        with open(path, "r", encoding="utf-8") as f:
+            text = f.read()
+    except (OSError, UnicodeDecodeError):
+        return None
+    manifest = os.path.basename(path)
+    if manifest == "package.json":
+        try:
+            name = json.loads(text).get("name")
+        except (ValueError, AttributeError):
+            return None
+        return name if isinstance(name, str) and name else None
+    if manifest == "go.mod":
+        match = re.search(r"^\s*module\s+(\S+)", text, re.MULTILINE)
+        return match.group(1).rstrip("/").rsplit("/", 1)[-1] if match else None
+    return _toml_name(text, _NAME_TABLES.get(manifest, ()))
+
+
+@lru_cache(maxsize=4096)
+def _member_at(directory: str) -> Optional[WorkspaceMember]:
+    """The member rooted at a directory, or found above it."""
+    for manifest in MANIFESTS:
+        path = os.path.join(directory, manifest)
+        if os.path.isfile(path):
+            return WorkspaceMember(manifest_name(path) or os.path.basename(directory) or directory,
+                                   directory, manifest)
+    parent = os.path.dirname(directory)
+    return None if parent == directory else _member_at(parent)
+
+
+def find_workspace_member(file_path: str) -> Optional[WorkspaceMember]:
+    """The member a file belongs to: the one with the nearest manifest above it, if any."""
+    return _member_at(os.path.dirname(os.path.abspath(file_path)))
+
+
+def module_path(file_path: str, member: WorkspaceMember) -> str:
+    """
+    Path of a file within its member, as ``::``-separated segments without
+    the extension: ``<root>/src/util/io.ts`` -> ``src::util::io``.
+    """
+    relative = os.path.relpath(os.path.abspath(file_path), member.root)
+    return "::".join(os.path.splitext(relative)[0].split(os.sep))
+
+
+def qualified_module(member: WorkspaceMember, path: str) -> str:
+    """``<workspace_member>::<module_path>``, or the member alone for its root module."""
+    return f"{member.name}::{path}" if path else member.name
+
+
+def workspace_properties(file_path: str) -> Dict[str, Any]:
+    """The workspace_member and qualified_module of a File node, or nothing outside any member."""
+    member = find_workspace_member(file_path)
+    if member is None:
+        return {}
+    return {"workspace_member": member.name,
+            "qualified_module": qualified_module(member, module_path(file_path, member))}
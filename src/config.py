@@ -0,0 +1,493 @@
+"""
+Project configuration read from ``codegraph.toml``, or ``.graph-mcp.toml``
+when there is no ``codegraph.toml``.
+
+The file sits at the root of the indexed codebase and is shared by the
+indexer and the MCP server. Settings are layered, each layer overriding the
+one below it: built-in defaults, environment variables, the file, then
+command-line flags. ``--exclude`` and ``--include`` add to the patterns of
+the layers below instead of replacing them. The Neo4j password is never
+read from the file; keep it in NEO4J_PASSWORD or pass --neo4j-password.
+
+    [paths]
+    include = ["src/**"]
+    exclude = ["**/generated/**", "target/", "node_modules/"]
+    respect_gitignore = true
+    max_file_size_kb = 1024
+
+    [languages]
+    use_ast_grep = true
+    enabled = ["python", "rust"]
+    disabled = []
+    fallback_to_legacy = true
+
+    [languages.rust]
+    index_private = false
+
+    [languages.python]
+    init_reexports = true
+
+    [storage]
+    backend = "sqlite"
+    db_path = "codegraph.db"
+
+    [language_overrides]
+    "*.h" = "c"
+    "*.inc" = "cpp"
+
+    [workspace]
+    roots = ["../shop", "../ledger"]
+
+    [workspace.packages]
+    ledger_api = "../ledger"
+
+    [queries]
+    max_traversal_depth = 10
+
+The keys of ``[paths]`` may also be written at the top level, before any
+table. ``max_file_size`` counts bytes and wins over ``max_file_size_kb``.
+``[language_overrides]`` parses the files matching a glob as a language
+whatever their extension; the first matching glob wins.
+
+``[queries]`` sets the default depth of the MCP tools that walk the graph:
+``max_traversal_depth`` is used when a call leaves ``depth`` or ``max_depth``
+out, and a call may ask for more or less.
+
+``[workspace]`` indexes several roots into one graph, each a project named
+after its directory. ``[workspace.packages]`` maps a package name to the
+root of the project it is imported from, so that imports of it resolve
+across projects. Relative roots are taken from the config file's directory.
+
+Unknown keys are reported with their line and otherwise ignored, so a typo
+never stops a run; a value of the wrong type, an unknown language or a
+glob that is not a valid pattern is an error, raised before any file is read.
+"""
+
+import json
+import logging
+import os
+import re
+from dataclasses import dataclass, field
+from typing import Any, Dict, List, Mapping, Optional, Tuple
+
+try:
+    import tomllib
+except ModuleNotFoundError:  # Python < 3.11
+    import tomli as tomllib
+
+from src.ast_parser.language_detector import EXT_TO_LANG
+from src.ast_parser.path_filter import pattern_error
+from src.neo4j_storage.store import DEFAULT_SQLITE_PATH, STORAGE_BACKENDS
+
+logger = logging.getLogger(__name__)
+
+CONFIG_FILE = "codegraph.toml"
+
+# Config file names looked for at the root of a codebase, first found wins
+CONFIG_FILES = (CONFIG_FILE, ".graph-mcp.toml")
+
+DEFAULT_LANGUAGES = ["python", "javascript", "typescript"]
+
+DEFAULT_MAX_TRAVERSAL_DEPTH = 10
+
+# Settings a config file may hold: table -> key -> (type, default)
+SCHEMA: Dict[str, Dict[str, Tuple[type, Any]]] = {
+    "paths": {
+        "include": (list, []),
+        "exclude": (list, []),
+        "respect_gitignore": (bool, True),
+        "max_file_size": (int, None),
+        "max_file_size_kb": (int, None),
+    },
+    "languages": {
+        "use_ast_grep": (bool, False),
+        "enabled": (list, DEFAULT_LANGUAGES),
+        "disabled": (list, []),
+        "fallback_to_legacy": (bool, True),
+    },
+    "languages.rust": {
+        # Index items without ``pub``; false keeps only what other modules can use
+        "index_private": (bool, True),
+    },
+    "languages.python": {
+        # Index an __init__.py under its package's name, with the names it
+        # imports from its submodules as the package's own definitions
+        "init_reexports": (bool, False),
+    },
+    "storage": {
+        "backend": (str, "neo4j"),
+        "db_path": (str, DEFAULT_SQLITE_PATH),
+        "neo4j_uri": (str, None),
+        "neo4j_user": (str, None),
+        "batch_size": (int, None),
+    },
+    "workspace": {
+        "roots": (list, []),
+    },
+    "queries": {
+        # Most edges a graph walk of an MCP tool follows from its starting nodes
+        "max_traversal_depth": (int, DEFAULT_MAX_TRAVERSAL_DEPTH),
+    },
+}
+
+# Environment variables standing in for settings, below the file in precedence
+ENV_VARS = {
+    ("paths", "exclude"): "EXCLUDE_PATTERNS",
+    ("paths", "respect_gitignore"): "RESPECT_GITIGNORE",
+    ("languages", "use_ast_grep"): "USE_AST_GREP",
+    ("languages", "enabled"): "AST_GREP_LANGUAGES",
+    ("languages", "fallback_to_legacy"): "AST_GREP_FALLBACK_TO_LEGACY",
+    ("storage", "backend"): "STORAGE_BACKEND",
+    ("storage", "db_path"): "SQLITE_DB_PATH",
+    ("storage", "neo4j_uri"): "NEO4J_URI",
+    ("storage", "neo4j_user"): "NEO4J_USER",
+    ("storage", "batch_size"): "NEO4J_BATCH_SIZE",
+    ("queries", "max_traversal_depth"): "MAX_TRAVERSAL_DEPTH",
+}
+
+# List settings that accumulate across layers rather than replace
+ADDITIVE_KEYS = {("paths", "include"), ("paths", "exclude")}
+
+# Language option tables, by the language they configure
+LANGUAGE_TABLE = re.compile(r"^languages\.(\w+)$")
+
+# Table of language by glob; its keys are the user's, not the schema's
+OVERRIDES_TABLE = "language_overrides"
+
+# Table of project root by package name, also keyed by the user
+PACKAGES_TABLE = "workspace.packages"
+
+# List settings holding globs, checked for syntax wherever they come from
+PATTERN_KEYS = {("paths", "include"), ("paths", "exclude")}
+
+
+class ConfigError(ValueError):
+    """
+    A config file that cannot be read or holds an invalid value.
+
+    ``location`` is the file and line, an environment variable or the
+    command line; ``key`` the dotted setting at fault, when there is one.
+    """
+
+    def __init__(self, message: str, location: Optional[str] = None, key: Optional[str] = None):
+        super().__init__(f"{location}: {message}" if location else message)
+        self.location = location
+        self.key = key
+
+
+@dataclass
+class Config:
+    """
+    Merged settings, by table and key as in the file.
+
+    ``source`` is the file the settings came from, None when there was none;
+    ``warnings`` lists the unknown keys it contained.
+    """
+    values: Dict[str, Dict[str, Any]] = field(default_factory=lambda: {
+        **{table: {key: list(default) if isinstance(default, list) else default
+                   for key, (_, default) in keys.items()}
+           for table, keys in SCHEMA.items()},
+        OVERRIDES_TABLE: {},
+        PACKAGES_TABLE: {},
+    })
+    source: Optional[str] = None
+    warnings: List[str] = field(default_factory=list)
+
+    def get(self, table: str, key: str) -> Any:
+        return self.values[table][key]
+
+    def set(self, table: str, key: str, value: Any) -> None:
+        """Set one value from a higher layer; None leaves the current value."""
+        if value is None:
+            return
+        if (table, key) in ADDITIVE_KEYS:
+            value = self.values[table][key] + [v for v in value if v not in self.values[table][key]]
+        self.values[table][key] = value
+
+    def override(self, overrides: Mapping[Tuple[str, str], Any]) -> "Config":
+        """
+        Apply command-line values, keyed by (table, key); None means not given.
+
+        Raises:
+            ConfigError: A glob that is not a valid pattern
+        """
+        for (table, key), value in overrides.items():
+            if value is not None and (table, key) in PATTERN_KEYS:
+                _check_patterns(value, table, key, "command line")
+            self.set(table, key, value)
+        return self
+
+    @property
+    def languages(self) -> List[str]:
+        """Enabled languages minus the disabled ones."""
+        disabled = set(self.get("languages", "disabled"))
+        return [language for language in self.get("languages", "enabled") if language not in disabled]
+
+    @property
+    def exclude(self) -> List[str]:
+        """
+        Exclude patterns, plus one per extension of every language that is
+        not enabled. ``.h`` stays while either C or C++ is enabled, and an
+        extension stays while a language override ending in it names an
+        enabled language.
+        """
+        languages = set(self.languages)
+        patterns = list(self.get("paths", "exclude"))
+        overridden = [glob for glob, language in self.language_overrides.items() if language in languages]
+        for ext, language in EXT_TO_LANG.items():
+            parsed = bool(languages & {"c", "cpp"}) if ext == ".h" else language in languages
+            parsed = parsed or any(glob.lower().endswith(ext) for glob in overridden)
+            if not parsed and f"*{ext}" not in patterns:
+                patterns.append(f"*{ext}")
+        return patterns
+
+    @property
+    def max_file_size(self) -> Optional[int]:
+        """Size limit in bytes: ``max_file_size``, else ``max_file_size_kb`` KiB, else None."""
+        size = self.get("paths", "max_file_size")
+        kb = self.get("paths", "max_file_size_kb")
+        return size if size is not None or kb is None else kb * 1024
+
+    @property
+    def max_traversal_depth(self) -> int:
+        """Edges a graph walk follows unless a call asks otherwise: ``queries.max_traversal_depth``."""
+        return self.get("queries", "max_traversal_depth")
+
+    @property
+    def language_overrides(self) -> Dict[str, str]:
+        """Language by glob, in the order written."""
+        return dict(self.values[OVERRIDES_TABLE])
+
+    @property
+    def workspace_roots(self) -> List[str]:
+        """Absolute workspace roots, in the order written."""
+        return [self._from_source_dir(root) for root in self.get("workspace", "roots")]
+
+    @property
+    def workspace_packages(self) -> Dict[str, str]:
+        """Absolute project root by package name."""
+        return {package: self._from_source_dir(root) for package, root in self.values[PACKAGES_TABLE].items()}
+
+    def _from_source_dir(self, path: str) -> str:
+        """A path relative to the config file's directory, or to the working directory without a file."""
+        base = os.path.dirname(os.path.abspath(self.source)) if self.source else os.getcwd()
+        return os.path.normpath(os.path.join(base, os.path.expanduser(path)))
+
+    @property
+    def language_options(self) -> Dict[str, Dict[str, Any]]:
+        """Per-language parser options, by language, as passed to the parsers."""
+        options = {}
+        for table, values in self.values.items():
+            match = LANGUAGE_TABLE.match(table)
+            if match:
+                options[match.group(1)] = dict(values)
+        return options
+
+    def to_toml(self) -> str:
+        """The merged settings in config file syntax; unset values are left out."""
+        lines = [f"# Merged from {self.source}" if self.source
+                 else f"# No {CONFIG_FILE} found; defaults and environment"]
+        for table, values in self.values.items():
+            lines += ["", f"[{table}]"]
+            lines += [f"{_toml_key(key)} = {_toml_value(value)}" for key, value in values.items()
+                      if value is not None]
+        return "\n".join(lines) + "\n"
+
+
+def _toml_key(key: str) -> str:
+    return key if re.fullmatch(r"[\w-]+", key) else json.dumps(key)
+
+
+def _toml_value(value: Any) -> str:
+    if isinstance(value, bool):
+        return "true" if value else "false"
+    if isinstance(value, list):
+        return "[" + ", ".join(_toml_value(v) for v in value) + "]"
+    if isinstance(value, str):
+        # A JSON string is a valid TOML basic string
+        return json.dumps(value)
+    return str(value)
+
+
+def find_config(codebase_path: str) -> Optional[str]:
+    """The codegraph.toml, or else .graph-mcp.toml, at the root of a codebase, if there is one."""
+    for name in CONFIG_FILES:
+        path = os.path.join(codebase_path, name)
+        if os.path.isfile(path):
+            return path
+    return None
+
+
+def load_config(path: Optional[str] = None, environ: Optional[Mapping[str, str]] = None) -> Config:
+    """
+    Build the settings from defaults, the environment and a config file.
+
+    Args:
+        path: Config file to read; None reads none
+        environ: Environment variables, os.environ if None
+
+    Returns:
+        The merged settings; apply command-line flags with Config.override
+
+    Raises:
+        ConfigError: The file cannot be read or parsed, or a value is invalid
+    """
+    config = Config()
+    _apply_environment(config, os.environ if environ is None else environ)
+    if path is None:
+        return config
+
+    try:
+        with open(path, "rb") as f:
+            data = f.read()
+        document = tomllib.loads(data.decode("utf-8"))
+    except (OSError, UnicodeDecodeError, tomllib.TOMLDecodeError) as e:
+        raise ConfigError(str(e), path) from e
+
+    config.source = path
+    lines = data.decode("utf-8").splitlines()
+    for table, key, value in _flatten(document):
+        line_no = _line_of(lines, table, key)
+        location = f"{path}:{line_no}" if line_no else path
+        if not table and key in SCHEMA["paths"]:
+            table = "paths"
+        if key is None:
+            config.warnings.append(f"{location}: unknown table '{table}'")
+        elif table == OVERRIDES_TABLE:
+            config.set(table, key, _checked_override(key, value, location))
+        elif table == PACKAGES_TABLE:
+            config.set(table, key, _checked_package(key, value, location))
+        elif table not in SCHEMA or key not in SCHEMA[table]:
+            config.warnings.append(f"{location}: unknown key '{_dotted(table, key)}'")
+        else:
+            config.set(table, key, _checked(value, table, key, location))
+    for warning in config.warnings:
+        logger.warning(warning)
+    return config
+
+
+def _apply_environment(config: Config, environ: Mapping[str, str]) -> None:
+    for (table, key), name in ENV_VARS.items():
+        raw = environ.get(name, "").strip()
+        if not raw:
+            continue
+        kind = SCHEMA[table][key][0]
+        if kind is bool:
+            value = raw.lower() == "true"
+        elif kind is list:
+            value = [item.strip() for item in raw.split(",") if item.strip()]
+            if (table, key) in PATTERN_KEYS:
+                _check_patterns(value, table, key, name)
+        elif kind is int:
+            try:
+                value = int(raw)
+            except ValueError:
+                logger.warning(f"Ignoring {name}={raw!r}: not an integer")
+                continue
+        else:
+            value = raw
+        config.set(table, key, value)
+
+
+def _flatten(document: Dict[str, Any], prefix: str = "") -> List[Tuple[str, Optional[str], Any]]:
+    """
+    (table, key, value) for every setting of a parsed file. A table the
+    schema does not know comes back once, with key None, rather than key by key.
+    """
+    entries = []
+    for name, value in document.items():
+        qualified = f"{prefix}.{name}" if prefix else name
+        if isinstance(value, dict):
+            if qualified in SCHEMA or qualified in (OVERRIDES_TABLE, PACKAGES_TABLE) \
+                    or any(table.startswith(qualified + ".") for table in SCHEMA):
+                entries.extend(_flatten(value, qualified))
+            else:
+                entries.append((qualified, None, value))
+        elif not prefix:
+            entries.append(("", name, value))
+        else:
+            entries.append((prefix, name, value))
+    return entries
+
+
+def _line_of(lines: List[str], table: str, key: Optional[str]) -> Optional[int]:
+    """
+    1-based line a setting is written on: ``key = ..`` under its table's
+    header, or a dotted key naming it from a parent table. Without a key,
+    the table's header. None when the scan cannot place it.
+    """
+    current = ""
+    target = _dotted(table, key) if key else table
+    for line_no, line in enumerate(lines, 1):
+        stripped = line.strip()
+        header = re.match(r"^\[\s*([^\[\]]+?)\s*\]", stripped)
+        if header:
+            current = re.sub(r"\s*\.\s*", ".", header.group(1))
+            if key is None and current == target:
+                return line_no
+            continue
+        assignment = re.match(r"^((?:\"[^\"]*\"|'[^']*'|[\w.\s-])+?)\s*=", stripped)
+        if assignment:
+            name = re.sub(r"\s*\.\s*", ".", assignment.group(1)).replace('"', "").replace("'", "")
+            qualified = _dotted(current, name)
+            if qualified == target or (key is None and qualified.startswith(target + ".")):
+                return line_no
+    return None
+
+
+def _dotted(table: str, key: str) -> str:
+    return f"{table}.{key}" if table else key
+
+
+def _checked(value: Any, table: str, key: str, location: str) -> Any:
+    kind = SCHEMA[table][key][0]
+    name = f"{table}.{key}"
+    if kind is list:
+        if not isinstance(value, list) or not all(isinstance(v, str) for v in value):
+            raise ConfigError(f"'{name}' must be a list of strings", location, name)
+        if table == "languages":
+            unknown = sorted(set(value) - set(EXT_TO_LANG.values()))
+            if unknown:
+                raise ConfigError(f"unknown language(s) {', '.join(unknown)} in '{name}' (known: {_known_languages()})",
+                                  location, name)
+        if (table, key) in PATTERN_KEYS:
+            _check_patterns(value, table, key, location)
+    elif kind is int:
+        # bool is an int subclass, but `max_file_size = true` is a mistake
+        if not isinstance(value, int) or isinstance(value, bool) or value < 1:
+            raise ConfigError(f"'{name}' must be a positive integer", location, name)
+    elif not isinstance(value, kind):
+        raise ConfigError(f"'{name}' must be a {'boolean' if kind is bool else 'string'}", location, name)
+    elif (table, key) == ("storage", "backend") and value not in STORAGE_BACKENDS:
+        raise ConfigError(f"'storage.backend' must be one of {', '.join(STORAGE_BACKENDS)}", location, name)
+    return value
+
+
+def _checked_override(glob: str, language: Any, location: str) -> str:
+    name = _dotted(OVERRIDES_TABLE, _toml_key(glob))
+    if not isinstance(language, str):
+        raise ConfigError(f"'{name}' must be a string", location, name)
+    if language not in EXT_TO_LANG.values():
+        raise ConfigError(f"unknown language {language} in '{name}' (known: {_known_languages()})", location, name)
+    error = "a negated pattern selects no files" if glob.startswith("!") else pattern_error(glob)
+    if error:
+        raise ConfigError(f"invalid pattern {glob!r} in '{OVERRIDES_TABLE}': {error}", location, name)
+    return language
+
+
+def _checked_package(package: str, root: Any, location: str) -> str:
+    name = _dotted(PACKAGES_TABLE, _toml_key(package))
+    if not isinstance(root, str) or not root:
+        raise ConfigError(f"'{name}' must be the path of a workspace root", location, name)
+    return root
+
+
+def _check_patterns(patterns: List[str], table: str, key: str, location: str) -> None:
+    for pattern in patterns:
+        error = pattern_error(pattern)
+        if error:
+            raise ConfigError(f"invalid pattern {pattern!r} in '{table}.{key}': {error}", location, f"{table}.{key}")
+
+
+def _known_languages() -> str:
+    return ", ".join(sorted(set(EXT_TO_LANG.values())))
@@ -0,0 +1,167 @@
+"""In-memory code graph package."""
+
+from src.graph.code_graph import (
+    CallSite,
+    CodeGraph,
+    FileState,
+    GraphDelta,
+    GraphDiff,
+    content_hash,
+    relation_identity,
+    relation_key,
+)
+from src.graph.watcher import (
+    FileChange,
+    PollingWatcher,
+    apply_changes,
+)
+from src.graph.cycles import (
+    Cycle,
+    find_cycles,
+)
+from src.graph.export import (
+    DotExporter,
+    DotOptions,
+    ExportTooLargeError,
+    GraphExporter,
+    GraphMlExporter,
+)
+from src.graph.module_graph import (
+    ModuleEdge,
+    ModuleGraph,
+)
+from src.graph.outline import (
+    OutlineEntry,
+    build_outline,
+)
+from src.graph.incremental import (
+    IncrementalResult,
+    IndexStats,
+    StoredFile,
+    reindex,
+)
+from src.graph.cache import GraphCache
+from src.graph.parse_cache import ParseCache
+from src.graph.spans import (
+    Span,
+    SpanIndex,
+)
+from src.graph.complexity import complexity_histogram
+from src.graph.file_metrics import (
+    file_metrics,
+    metrics_summary,
+)
+from src.graph.doc_coverage import undocumented_symbols
+from src.graph.symbol_search import (
+    PatternError,
+    SearchResults,
+    SymbolIndex,
+    SymbolMatch,
+    search_symbols,
+)
+from src.graph.node_query import (
+    QueryError,
+    parse_query,
+    query_nodes,
+)
+from src.graph.public_api import (
+    diff_api,
+    public_api,
+)
+from src.graph.dead_code import (
+    DeadSymbol,
+    EntrypointRules,
+    find_unreachable,
+    find_unreferenced,
+    find_unused,
+)
+from src.graph.git_diff import (
+    GitError,
+    diff_commits,
+    graph_at_revision,
+)
+from src.graph.async_hazards import (
+    AsyncHazard,
+    find_sync_in_async,
+)
+from src.graph.duplicates import (
+    DuplicateGroup,
+    FunctionBody,
+    find_duplicates,
+)
+from src.graph.impact import (
+    ImpactReport,
+    ImpactedSymbol,
+    impact_of,
+)
+from src.graph.metrics import (
+    FunctionMetrics,
+    function_metrics,
+    top_complex,
+)
+
+__all__ = [
+    'CallSite',
+    'CodeGraph',
+    'FileState',
+    'GraphDelta',
+    'GraphDiff',
+    'content_hash',
+    'relation_identity',
+    'relation_key',
+    'FileChange',
+    'PollingWatcher',
+    'apply_changes',
+    'Cycle',
+    'find_cycles',
+    'DotExporter',
+    'DotOptions',
+    'ExportTooLargeError',
+    'GraphExporter',
+    'GraphMlExporter',
+    'ModuleEdge',
+    'ModuleGraph',
+    'OutlineEntry',
+    'build_outline',
+    'IncrementalResult',
+    'IndexStats',
+    'StoredFile',
+    'reindex',
+    'GraphCache',
+    'ParseCache',
+    'Span',
+    'SpanIndex',
+    'complexity_histogram',
+    'file_metrics',
+    'metrics_summary',
+    'undocumented_symbols',
+    'PatternError',
+    'SearchResults',
+    'SymbolIndex',
+    'SymbolMatch',
+    'search_symbols',
+    'QueryError',
+    'parse_query',
+    'query_nodes',
+    'diff_api',
+    'public_api',
+    'DeadSymbol',
+    'EntrypointRules',
+    'find_unreachable',
+    'find_unreferenced',
+    'find_unused',
+    'GitError',
+    'diff_commits',
+    'graph_at_revision',
+    'AsyncHazard',
+    'find_sync_in_async',
+    'DuplicateGroup',
+    'FunctionBody',
+    'find_duplicates',
+    'ImpactReport',
+    'ImpactedSymbol',
+    'impact_of',
+    'FunctionMetrics',
+    'function_metrics',
+    'top_complex',
+]
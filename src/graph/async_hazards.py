@@ -0,0 +1,102 @@
+"""
+Blocking calls made from async functions.
+
+An async function that calls into synchronous code doing I/O holds up the
+executor thread until the call returns. The parsers mark functions and
+methods with ``is_async`` and calls awaited on the spot with
+``await_call``; a hazard is a call from an async function that is not
+awaited, to a callee not known to be async, that looks like I/O: its name
+contains ``read``, ``write``, ``recv`` or ``send``, or it lives in a module
+named ``io``, ``net`` or ``fs`` (the module of its file, or the path of an
+unresolved callee such as ``std::fs::read_to_string``). Names alone
+decide, so every hazard is a candidate to check rather than a certain bug.
+"""
+
+import re
+from dataclasses import asdict, dataclass
+from typing import Dict, List, Optional
+
+from src.graph.code_graph import UNRESOLVED_NODE_TYPE, CodeGraph
+
+# Parts of a callee's name that suggest it does I/O
+BLOCKING_NAME_PARTS = ("read", "write", "recv", "send")
+
+# Modules whose functions are taken to do I/O
+BLOCKING_MODULES = ("io", "net", "fs")
+
+# Node properties the search needs, for loading the graph
+ASYNC_PROPERTIES = ("is_async", "module_path")
+
+
+@dataclass
+class AsyncHazard:
+    """A call from an async function to synchronous code that may block."""
+    function_id: str
+    function: str
+    file_path: str
+    line_no: int
+    callee_id: str
+    callee: str
+    callee_type: str
+    call_site: Optional[str]
+    reasons: List[str]
+
+    def to_dict(self) -> Dict[str, object]:
+        return asdict(self)
+
+
+def _module_segments(graph: CodeGraph, node) -> List[str]:
+    """
+    Names of the modules a callee lives in: the prefix of an unresolved
+    path, the crate module path of a Rust file (``crate::net::client``) or
+    else the file's name and the directory holding it.
+    """
+    if node.node_type == UNRESOLVED_NODE_TYPE:
+        return re.split(r"::|\.", node.name)[:-1]
+    file_node = graph.nodes.get(f"file:{node.file_path}")
+    if file_node is not None and file_node.properties.get("module_path"):
+        return file_node.properties["module_path"].split("::")
+    directory, _, name = node.file_path.replace("\\", "/").rpartition("/")
+    return [directory.rpartition("/")[2], name.split(".")[0]]
+
+
+def blocking_reasons(graph: CodeGraph, node) -> List[str]:
+    """Why a callee looks like it does I/O; empty when it does not."""
+    name = re.split(r"::|\.", node.name)[-1].lower()
+    reasons = [f"name contains '{part}'" for part in BLOCKING_NAME_PARTS if part in name]
+    modules = _module_segments(graph, node)
+    reasons += [f"in module '{module}'" for module in BLOCKING_MODULES if module in modules]
+    return reasons
+
+
+def find_sync_in_async(graph: CodeGraph) -> List[AsyncHazard]:
+    """
+    List the calls from async functions to synchronous callees that look like I/O.
+
+    Returns:
+        One hazard per call, ordered by file and line of the call
+    """
+    hazards = []
+    for relation in graph.relations:
+        if relation.relation_type != "CALLS" or relation.properties.get("await_call"):
+            continue
+        caller, callee = graph.nodes.get(relation.source_id), graph.nodes.get(relation.target_id)
+        if caller is None or callee is None or not caller.properties.get("is_async") \
+                or callee.properties.get("is_async"):
+            continue
+        reasons = blocking_reasons(graph, callee)
+        if not reasons:
+            continue
+        hazards.append(AsyncHazard(
+            function_id=caller.node_id,
+            function=caller.name,
+            file_path=caller.file_path,
+            line_no=relation.properties.get("line_no") or caller.line_no,
+            callee_id=callee.node_id,
+            callee=callee.name,
+            callee_type=callee.node_type,
+            call_site=relation.properties.get("call_site"),
+            reasons=reasons,
+        ))
+    hazards.sort(key=lambda h: (h.file_path, h.line_no, h.callee_id))
+    return hazards
@@ -0,0 +1,232 @@
+"""
+On-disk SQLite cache of a parsed CodeGraph.
+
+After a full parse the graph is written file by file: one ``files`` row per
+source file, keyed by path, mtime and size, with the file's nodes and the
+edges it owns in normalized ``nodes`` and ``edges`` tables. Loading turns the
+rows back into StoredFile records, so ``reindex`` can restore unchanged files
+without parsing and re-parse only those whose key changed.
+
+The cache is disposable. A database that cannot be read, or that was written
+with a different schema version, is reported with a warning and treated as
+empty, which makes the caller fall back to a full parse; the next save
+rebuilds it from scratch.
+"""
+
+import os
+import json
+import sqlite3
+import logging
+from typing import Dict, List, Optional
+
+from src.ast_parser.diagnostics import ParseError
+from src.ast_parser.parser import CodeNode, CodeRelation
+from src.graph.code_graph import CodeGraph, FileState
+from src.graph.incremental import StoredFile
+
+logger = logging.getLogger(__name__)
+
+# Bump whenever the tables or the meaning of a column change
+SCHEMA_VERSION = 3
+
+SCHEMA = """
+CREATE TABLE meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+CREATE TABLE files (
+    file_path TEXT PRIMARY KEY,
+    mtime_ns INTEGER,
+    size INTEGER,
+    content_hash TEXT,
+    pending_imports TEXT NOT NULL,
+    module_definitions TEXT NOT NULL,
+    module_to_file TEXT NOT NULL,
+    parse_errors TEXT NOT NULL
+);
+CREATE TABLE nodes (
+    id TEXT NOT NULL,
+    file_path TEXT NOT NULL REFERENCES files(file_path) ON DELETE CASCADE,
+    type TEXT NOT NULL,
+    name TEXT NOT NULL,
+    line_no INTEGER,
+    end_line_no INTEGER,
+    properties TEXT NOT NULL,
+    code_snippet TEXT,
+    PRIMARY KEY (file_path, id)
+);
+CREATE TABLE edges (
+    file_path TEXT NOT NULL REFERENCES files(file_path) ON DELETE CASCADE,
+    seq INTEGER NOT NULL,
+    source_id TEXT NOT NULL,
+    target_id TEXT NOT NULL,
+    type TEXT NOT NULL,
+    properties TEXT NOT NULL,
+    -- 0 for edges found while parsing the file, 1 for second-pass edges
+    resolved INTEGER NOT NULL,
+    PRIMARY KEY (file_path, seq)
+);
+CREATE INDEX edges_type ON edges(type);
+"""
+
+
+class GraphCache:
+    """SQLite file holding the per-file states of a CodeGraph."""
+
+    def __init__(self, path: str):
+        """
+        Args:
+            path: Database file; created on first save
+        """
+        self.path = path
+
+    def load(self) -> Dict[str, StoredFile]:
+        """
+        Read the cached files.
+
+        Returns:
+            StoredFile records keyed by file path, ready for ``reindex``;
+            empty when there is no cache or it cannot be used
+        """
+        if not os.path.exists(self.path):
+            return {}
+        try:
+            conn = self._connect()
+            try:
+                version = self._schema_version(conn)
+                if version != SCHEMA_VERSION:
+                    logger.warning(f"Ignoring graph cache {self.path}: schema version {version}, "
+                                   f"expected {SCHEMA_VERSION}; re-parsing everything")
+                    return {}
+                return self._read_files(conn)
+            finally:
+                conn.close()
+        except (sqlite3.DatabaseError, ValueError, KeyError, TypeError) as e:
+            logger.warning(f"Ignoring unreadable graph cache {self.path}: {e}; re-parsing everything")
+            return {}
+
+    def save(self, graph: CodeGraph) -> None:
+        """
+        Replace the cache contents with the graph's files.
+
+        Files parsed from in-memory sources have no mtime and are left out,
+        since they cannot be checked against the disk next time. Failures
+        are logged, never raised: a missing cache only costs a full parse.
+        """
+        states = [state for state in graph.files.values() if state.mtime_ns is not None]
+        try:
+            self._write(states)
+        except sqlite3.DatabaseError as e:
+            logger.warning(f"Rebuilding unusable graph cache {self.path}: {e}")
+            try:
+                os.remove(self.path)
+                self._write(states)
+            except (OSError, sqlite3.DatabaseError) as retry_error:
+                logger.warning(f"Could not write graph cache {self.path}: {retry_error}")
+                return
+        except OSError as e:
+            logger.warning(f"Could not write graph cache {self.path}: {e}")
+            return
+        logger.info(f"Saved {len(states)} files to graph cache {self.path}")
+
+    def _connect(self) -> sqlite3.Connection:
+        conn = sqlite3.connect(self.path)
+        conn.execute("PRAGMA foreign_keys = ON")
+        return conn
+
+    @staticmethod
+    def _schema_version(conn: sqlite3.Connection) -> Optional[int]:
+        row = conn.execute("SELECT value FROM meta WHERE key = 'schema_version'").fetchone()
+        return int(row[0]) if row else None
+
+    @staticmethod
+    def _read_files(conn: sqlite3.Connection) -> Dict[str, StoredFile]:
+        states: Dict[str, FileState] = {}
+        for row in conn.execute("""
+            SELECT file_path, mtime_ns, size, content_hash,
+                   pending_imports, module_definitions, module_to_file, parse_errors
+            FROM files ORDER BY file_path
+        """):
+            path = row[0]
+            states[path] = FileState(
+                file_path=path,
+                nodes={},
+                local_relations=[],
+                pending_imports=json.loads(row[4]),
+                module_definitions=json.loads(row[5]),
+                module_to_file=json.loads(row[6]),
+                content_hash=row[3],
+                mtime_ns=row[1],
+                size=row[2],
+                parse_errors=[ParseError.from_dict(item) for item in json.loads(row[7])],
+            )
+
+        for row in conn.execute("""
+            SELECT file_path, id, type, name, line_no, end_line_no, properties, code_snippet
+            FROM nodes ORDER BY rowid
+        """):
+            node = CodeNode(row[1], row[2], row[3], row[0], row[4], row[5], json.loads(row[6]))
+            node.code_snippet = row[7]
+            states[row[0]].nodes[node.node_id] = node
+
+        for row in conn.execute("""
+            SELECT file_path, source_id, target_id, type, properties, resolved
+            FROM edges ORDER BY file_path, seq
+        """):
+            relation = CodeRelation(row[1], row[2], row[3], json.loads(row[4]))
+            state = states[row[0]]
+            (state.resolved_relations if row[5] else state.local_relations).append(relation)
+
+        return {
+            path: StoredFile(path, state.content_hash, state.mtime_ns, state, state.size)
+            for path, state in states.items()
+        }
+
+    def _write(self, states: List[FileState]) -> None:
+        conn = self._connect()
+        try:
+            with conn:
+                if self._needs_schema(conn):
+                    for table in ("edges", "nodes", "files", "meta"):
+                        conn.execute(f"DROP TABLE IF EXISTS {table}")
+                    conn.executescript(SCHEMA)
+                    conn.execute("INSERT INTO meta (key, value) VALUES ('schema_version', ?)",
+                                 (str(SCHEMA_VERSION),))
+                conn.execute("DELETE FROM files")
+                for state in states:
+                    self._write_file(conn, state)
+        finally:
+            conn.close()
+
+    def _needs_schema(self, conn: sqlite3.Connection) -> bool:
+        try:
+            return self._schema_version(conn) != SCHEMA_VERSION
+        except sqlite3.OperationalError:
+            # No meta table yet
+            return True
+
+    @staticmethod
+    def _write_file(conn: sqlite3.Connection, state: FileState) -> None:
+        conn.execute(
+            "INSERT INTO files VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            (state.file_path, state.mtime_ns, state.size, state.content_hash,
+             _dumps(state.pending_imports), _dumps(state.module_definitions), _dumps(state.module_to_file),
+             _dumps([error.to_dict() for error in state.parse_errors])),
+        )
+        conn.executemany(
+            "INSERT INTO nodes VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            [(node.node_id, state.file_path, node.node_type, node.name, node.line_no,
+              node.end_line_no, _dumps(node.properties), node.code_snippet)
+             for node in state.nodes.values()],
+        )
+        edges = [(r, 0) for r in state.local_relations] + [(r, 1) for r in state.resolved_relations]
+        conn.executemany(
+            "INSERT INTO edges VALUES (?, ?, ?, ?, ?, ?, ?)",
+            [(state.file_path, seq, r.source_id, r.target_id, r.relation_type,
+              _dumps(r.properties), resolved)
+             for seq, (r, resolved) in enumerate(edges)],
+        )
+
+
+def _dumps(value) -> str:
+    return json.dumps(value, ensure_ascii=False, default=str)
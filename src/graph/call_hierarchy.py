@@ -0,0 +1,192 @@
+"""
+Call hierarchy of a symbol, as the call hierarchy views of IDEs show it.
+
+The callers tree has the functions calling the root as its children, their
+callers as grandchildren and so on; the callees tree goes the other way.
+Every child stands for one function, with the first call linking it to its
+parent (location and source line) and the number of such calls. Trees are
+expanded breadth first, so a function reachable along several paths is
+expanded at its shallowest occurrence and every other occurrence is a
+``ref`` to it, listed without children. A function that calls back into one
+of its ancestors is a ``cycle`` and is not expanded either, so recursion
+terminates. Entries at the depth limit have no ``children`` key, while a
+function with nothing left to list has an empty one; those calling or
+called by functions the trees do not list make up the ``frontier``, to
+build further hierarchies from. The number of tree entries is capped; when
+the cap cuts the walk short the hierarchy is ``truncated``.
+"""
+
+from collections import deque
+from dataclasses import dataclass, field
+from typing import Any, Dict, List, Optional
+
+from src.ast_parser.parser import CodeRelation
+from src.graph.code_graph import UNRESOLVED_PREFIX, CodeGraph
+
+HIERARCHY_DIRECTIONS = ("callers", "callees", "both")
+
+
+@dataclass
+class HierarchyEntry:
+    """One function in a call hierarchy tree."""
+    id: str
+    name: str
+    qualified_name: str
+    kind: str
+    file_path: str
+    line_no: int
+    end_line_no: Optional[int] = None
+    # First call linking the entry to its parent, and how many there are; None on the root
+    call_site: Optional[Dict[str, Any]] = None
+    calls: int = 0
+    # Listed without children: expanded at another occurrence, or an ancestor of itself
+    ref: bool = False
+    cycle: bool = False
+    # None when the entry was not expanded
+    children: Optional[List["HierarchyEntry"]] = None
+
+    def to_dict(self) -> Dict[str, Any]:
+        data = {
+            "id": self.id,
+            "name": self.name,
+            "qualified_name": self.qualified_name,
+            "type": self.kind,
+            "file_path": self.file_path,
+            "line_no": self.line_no,
+            "end_line_no": self.end_line_no,
+        }
+        if self.call_site is not None:
+            data["call_site"] = self.call_site
+            data["calls"] = self.calls
+        if self.ref:
+            data["ref"] = True
+        if self.cycle:
+            data["cycle"] = True
+        if self.children is not None:
+            data["children"] = [child.to_dict() for child in self.children]
+        return data
+
+
+@dataclass
+class CallHierarchy:
+    """The callers and/or callees trees of one symbol."""
+    root: HierarchyEntry
+    callers: Optional[List[HierarchyEntry]] = None
+    callees: Optional[List[HierarchyEntry]] = None
+    # Entries listed, the root included
+    node_count: int = 1
+    truncated: bool = False
+    # Entries at max_depth with calls to or from functions not listed, in the order reached
+    frontier: List[str] = field(default_factory=list)
+
+    def to_dict(self) -> Dict[str, Any]:
+        data = self.root.to_dict()
+        for direction, tree in (("callers", self.callers), ("callees", self.callees)):
+            if tree is not None:
+                data[direction] = [entry.to_dict() for entry in tree]
+        return data
+
+
+def _qualified_name(graph: CodeGraph, definers: Dict[str, str], node_id: str) -> str:
+    """Name of a symbol prefixed with the names of the symbols defining it (``Person.greet``)."""
+    parts, current, seen = [], node_id, set()
+    while current in graph.nodes and current not in seen:
+        seen.add(current)
+        parts.append(graph.nodes[current].name)
+        current = definers.get(current)
+    return ".".join(reversed(parts))
+
+
+def call_hierarchy(graph: CodeGraph, root_id: str, direction: str = "both", max_depth: int = 3,
+                   max_nodes: int = 500) -> CallHierarchy:
+    """
+    Build the call hierarchy of one symbol.
+
+    Args:
+        graph: Resolved code graph; unresolved calls are left out
+        root_id: Node ID of the function or method at the root
+        direction: "callers", "callees" or "both"
+        max_depth: Levels below the root to expand
+        max_nodes: Most entries to list over both trees, the root included
+
+    Returns:
+        The hierarchy; each requested tree is a list of the root's children,
+        and the frontier where max_depth stopped it
+
+    Raises:
+        ValueError: Unknown direction or root
+    """
+    if direction not in HIERARCHY_DIRECTIONS:
+        raise ValueError(f"Unknown direction: {direction}")
+    if root_id not in graph.nodes:
+        raise ValueError(f"Unknown node: {root_id}")
+
+    definers: Dict[str, str] = {}
+    # Resolved CALLS edges by the node at each end, so every level is a lookup
+    calls: Dict[str, Dict[str, List[CodeRelation]]] = {"callers": {}, "callees": {}}
+    for relation in graph.relations:
+        if relation.relation_type == "DEFINES":
+            definers.setdefault(relation.target_id, relation.source_id)
+        elif relation.relation_type == "CALLS" and not relation.target_id.startswith(UNRESOLVED_PREFIX) \
+                and relation.source_id in graph.nodes and relation.target_id in graph.nodes:
+            calls["callers"].setdefault(relation.target_id, []).append(relation)
+            calls["callees"].setdefault(relation.source_id, []).append(relation)
+
+    def location(relation: CodeRelation):
+        """Order of call sites: file and position of the call, then the ends of the edge."""
+        return (graph.nodes[relation.source_id].file_path or "", relation.properties.get("line_no") or 0,
+                relation.properties.get("column") or 0, relation.source_id, relation.target_id)
+
+    def entry(node_id: str, relations: Optional[List[CodeRelation]] = None) -> HierarchyEntry:
+        node = graph.nodes[node_id]
+        created = HierarchyEntry(node_id, node.name, _qualified_name(graph, definers, node_id),
+                                 node.node_type, node.file_path, node.line_no, node.end_line_no)
+        if relations:
+            first = relations[0]
+            created.call_site = {"file_path": graph.nodes[first.source_id].file_path,
+                                 "line_no": first.properties.get("line_no"),
+                                 "column": first.properties.get("column"),
+                                 "snippet": first.properties.get("call_site")}
+            created.calls = len(relations)
+        return created
+
+    hierarchy = CallHierarchy(entry(root_id))
+    walks = ["callers", "callees"] if direction == "both" else [direction]
+    for walk in walks:
+        tree: List[HierarchyEntry] = []
+        setattr(hierarchy, walk, tree)
+        expanded, listed, stopped = {root_id}, {root_id}, []
+        # (children list to fill, node whose calls are listed, ancestors on the path, depth)
+        queue = deque([(tree, root_id, (root_id,), 1)])
+        while queue and not hierarchy.truncated:
+            children, node_id, path, depth = queue.popleft()
+            # One child per function at the other end, in order of its first call
+            by_node: Dict[str, List[CodeRelation]] = {}
+            for relation in sorted(calls[walk].get(node_id, ()), key=location):
+                other_id = relation.source_id if walk == "callers" else relation.target_id
+                by_node.setdefault(other_id, []).append(relation)
+            for other_id, relations in by_node.items():
+                if hierarchy.node_count >= max_nodes:
+                    hierarchy.truncated = True
+                    break
+                child = entry(other_id, relations)
+                hierarchy.node_count += 1
+                children.append(child)
+                listed.add(other_id)
+                if other_id in path:
+                    child.cycle = True
+                elif other_id in expanded:
+                    child.ref = True
+                elif depth < max_depth:
+                    expanded.add(other_id)
+                    child.children = []
+                    queue.append((child.children, other_id, path + (other_id,), depth + 1))
+                else:
+                    stopped.append(other_id)
+        # Checked once the tree is built, as a function may be listed at max_depth by another path
+        end = "source_id" if walk == "callers" else "target_id"
+        for node_id in stopped:
+            if node_id not in hierarchy.frontier \
+                    and any(getattr(r, end) not in listed for r in calls[walk].get(node_id, ())):
+                hierarchy.frontier.append(node_id)
+    return hierarchy
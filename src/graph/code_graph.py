@@ -0,0 +1,1560 @@
+"""
+In-memory code graph with incremental per-file updates.
+
+CodeGraph keeps the result of the two-pass parse split by file: each file
+owns the nodes and first-pass relations its parser emitted, plus the
+cross-file relations produced when its pending imports were resolved. That
+ownership makes it possible to re-parse one file and patch the graph without
+rebuilding everything else.
+"""
+
+import os
+import re
+import json
+import hashlib
+import logging
+from collections import deque
+from concurrent.futures import ThreadPoolExecutor, as_completed
+from dataclasses import dataclass, field
+from typing import Any, Dict, Iterable, List, Optional, Set, Tuple
+
+from src.ast_parser.parser import ASTParser, CodeNode, CodeRelation, resolve_reexports
+from src.ast_parser.diagnostics import ParseError, ParseReport, flag_parse_error, has_declarations, read_error
+from src.ast_parser.line_metrics import LINE_METRICS
+from src.ast_parser.positions import assign_spans, decode_source
+from src.ast_parser.qualified_names import assign_qualified_names
+from src.ast_parser.testing import mark_tests, tests_relations
+from src.ast_parser.multi_parser import JS_TS_EXTENSIONS, MultiLanguageParser
+from src.ast_parser.language_detector import detect_language, parsed_extension
+from src.graph.projects import ProjectMap
+from src.graph.progress import FileParsed, ProgressChannel, ResolutionFinished, ResolutionStarted
+from src.graph.spans import SpanIndex
+
+logger = logging.getLogger(__name__)
+
+# Node type and ID prefix for call targets that could not be resolved
+UNRESOLVED_NODE_TYPE = "Unresolved"
+UNRESOLVED_PREFIX = "unresolved:"
+
+# Default cap on the number of call hops a reachability search explores
+DEFAULT_MAX_DEPTH = 10
+
+# Version of the document written by CodeGraph.to_json; bump on any change to its shape
+JSON_SCHEMA_VERSION = 2
+# Older versions from_json still reads; version 1 nodes lack the top-level
+# visibility, which from_json takes from their properties anyway
+READABLE_JSON_SCHEMA_VERSIONS = (1, JSON_SCHEMA_VERSION)
+
+# Node properties that follow the position or bytes of the file rather than
+# describe the symbol; a diff does not report them as changes
+POSITIONAL_PROPERTIES = ("column", "end_column", "start_byte", "end_byte", "content_hash", "mtime_ns") + LINE_METRICS
+
+
+def relation_identity(relation: CodeRelation) -> Dict[str, Any]:
+    """Relation properties that, with the endpoints and type, identify a relation."""
+    if relation.relation_type == "IMPORTS_DEFINITION":
+        return {"symbol": relation.properties.get("symbol", "")}
+    if relation.relation_type == "REFERENCES":
+        return {key: relation.properties.get(key, "") for key in ("line_no", "access")}
+    return {}
+
+
+def relation_key(relation: CodeRelation) -> str:
+    """Identity of a relation, matching the dedup key used by the parsers."""
+    key = f"{relation.source_id}|{relation.relation_type}|{relation.target_id}"
+    for value in relation_identity(relation).values():
+        key += f"|{value}"
+    return key
+
+
+def node_record(node: CodeNode) -> Dict[str, Any]:
+    """A node as written in JSON snapshots and JSON Lines exports."""
+    return {
+        "id": node.node_id,
+        "kind": node.node_type,
+        "name": node.name,
+        "file": node.file_path,
+        "span": {"start_line": node.line_no, "end_line": node.end_line_no},
+        "visibility": node.properties.get("visibility"),
+        "properties": node.properties,
+        "code_snippet": node.code_snippet,
+    }
+
+
+def edge_record(relation: CodeRelation) -> Dict[str, Any]:
+    """A relation as written in JSON snapshots and JSON Lines exports."""
+    return {
+        "source": relation.source_id,
+        "target": relation.target_id,
+        "kind": relation.relation_type,
+        "properties": relation.properties,
+    }
+
+
+@dataclass
+class FileState:
+    """Everything one file contributed to the graph."""
+    file_path: str
+    nodes: Dict[str, CodeNode]
+    local_relations: List[CodeRelation]
+    pending_imports: List[Dict[str, Any]]
+    module_definitions: Dict[str, Dict[str, str]]
+    module_to_file: Dict[str, str]
+    # Second-pass relations produced from this file's pending imports
+    resolved_relations: List[CodeRelation] = field(default_factory=list)
+    # sha256 of the parsed contents, and the modification time and size when read from disk
+    content_hash: Optional[str] = None
+    mtime_ns: Optional[int] = None
+    size: Optional[int] = None
+    # Syntax errors met while parsing; the nodes are whatever was recovered around them
+    parse_errors: List[ParseError] = field(default_factory=list)
+
+    def owned_relations(self) -> List[CodeRelation]:
+        return self.local_relations + self.resolved_relations
+
+    def to_dict(self) -> Dict[str, Any]:
+        """JSON-compatible form, so a graph can be restored without re-parsing."""
+        return {
+            "file_path": self.file_path,
+            "nodes": [_node_to_dict(node) for node in self.nodes.values()],
+            "local_relations": [_relation_to_dict(r) for r in self.local_relations],
+            "resolved_relations": [_relation_to_dict(r) for r in self.resolved_relations],
+            "pending_imports": self.pending_imports,
+            "module_definitions": self.module_definitions,
+            "module_to_file": self.module_to_file,
+            "content_hash": self.content_hash,
+            "mtime_ns": self.mtime_ns,
+            "size": self.size,
+            "parse_errors": [error.to_dict() for error in self.parse_errors],
+        }
+
+    @classmethod
+    def from_dict(cls, data: Dict[str, Any]) -> "FileState":
+        nodes = [_node_from_dict(item) for item in data["nodes"]]
+        return cls(
+            file_path=data["file_path"],
+            nodes={node.node_id: node for node in nodes},
+            local_relations=[_relation_from_dict(item) for item in data["local_relations"]],
+            pending_imports=data["pending_imports"],
+            module_definitions=data["module_definitions"],
+            module_to_file=data["module_to_file"],
+            resolved_relations=[_relation_from_dict(item) for item in data["resolved_relations"]],
+            content_hash=data.get("content_hash"),
+            mtime_ns=data.get("mtime_ns"),
+            size=data.get("size"),
+            parse_errors=[ParseError.from_dict(item) for item in data.get("parse_errors", [])],
+        )
+
+
+def _node_to_dict(node: CodeNode) -> Dict[str, Any]:
+    return {
+        "id": node.node_id, "type": node.node_type, "name": node.name,
+        "file_path": node.file_path, "line_no": node.line_no, "end_line_no": node.end_line_no,
+        "properties": node.properties, "code_snippet": node.code_snippet,
+    }
+
+
+def _node_from_dict(data: Dict[str, Any]) -> CodeNode:
+    node = CodeNode(data["id"], data["type"], data["name"], data["file_path"],
+                    data["line_no"], data["end_line_no"], data["properties"])
+    node.code_snippet = data["code_snippet"]
+    return node
+
+
+def _relation_to_dict(relation: CodeRelation) -> Dict[str, Any]:
+    return {"source": relation.source_id, "target": relation.target_id,
+            "type": relation.relation_type, "properties": relation.properties}
+
+
+def _relation_from_dict(data: Dict[str, Any]) -> CodeRelation:
+    return CodeRelation(data["source"], data["target"], data["type"], data["properties"])
+
+
+def content_hash(data: bytes) -> str:
+    """Hash used to tell whether a file changed since it was indexed."""
+    return hashlib.sha256(data).hexdigest()
+
+
+@dataclass
+class GraphDelta:
+    """Node and relation changes caused by one graph update."""
+    added_nodes: List[str] = field(default_factory=list)
+    removed_nodes: List[str] = field(default_factory=list)
+    added_relations: List[CodeRelation] = field(default_factory=list)
+    removed_relations: List[CodeRelation] = field(default_factory=list)
+    # Nodes whose ID survived the update but whose content changed
+    updated_nodes: List[str] = field(default_factory=list)
+    # Files whose owned nodes or relations were recomputed
+    files: List[str] = field(default_factory=list)
+
+    def is_empty(self) -> bool:
+        return not (self.added_nodes or self.removed_nodes or self.updated_nodes
+                    or self.added_relations or self.removed_relations)
+
+
+@dataclass
+class CallSite:
+    """One CALLS edge seen from one end: the node at the other end and where the call is written."""
+    node_id: str
+    # Location of the call expression, in the caller's file
+    file_path: str
+    line_no: Optional[int]
+    column: Optional[int] = None
+    snippet: Optional[str] = None
+    # Name as written, for a call whose callee was not found
+    raw_name: Optional[str] = None
+
+    @property
+    def unresolved(self) -> bool:
+        return self.raw_name is not None
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"node_id": self.node_id, "file_path": self.file_path, "line_no": self.line_no,
+                "column": self.column, "snippet": self.snippet, "unresolved": self.unresolved,
+                "raw_name": self.raw_name}
+
+
+@dataclass
+class GraphDiff:
+    """
+    Symbol-level differences between two graphs of the same codebase.
+
+    Nodes are matched by kind, file, name and their order among same-named
+    siblings rather than by ID, so a function that only shifted down the
+    file, because lines were added above it, is not reported; its attribute
+    changes (body hash, complexity, visibility, ...) are, and so is a
+    ``span`` change when it moved past other symbols of its file. A removed
+    and an added node of the same kind whose bodies are the same but for
+    their own name are paired as one renamed or moved symbol, and edges
+    follow it. Removed nodes and edges use the IDs of the first graph, added
+    and changed ones those of the second.
+    """
+    added_nodes: List[Dict[str, Any]] = field(default_factory=list)
+    removed_nodes: List[Dict[str, Any]] = field(default_factory=list)
+    # Node summary plus ``changes``: {attribute: {"before": ..., "after": ...}}
+    changed_nodes: List[Dict[str, Any]] = field(default_factory=list)
+    # {"before": node summary, "after": node summary} of a renamed or moved symbol
+    renamed_nodes: List[Dict[str, Any]] = field(default_factory=list)
+    added_edges: List[Dict[str, Any]] = field(default_factory=list)
+    removed_edges: List[Dict[str, Any]] = field(default_factory=list)
+    # Node summary of a called symbol plus the ``gained`` and ``lost`` callers' summaries
+    caller_changes: List[Dict[str, Any]] = field(default_factory=list)
+    # Files left out of either graph, with the reason: {"file", "reason", ...}
+    skipped: List[Dict[str, Any]] = field(default_factory=list)
+
+    def is_empty(self) -> bool:
+        return not (self.added_nodes or self.removed_nodes or self.changed_nodes or self.renamed_nodes
+                    or self.added_edges or self.removed_edges)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "summary": {
+                "added_nodes": len(self.added_nodes),
+                "removed_nodes": len(self.removed_nodes),
+                "changed_nodes": len(self.changed_nodes),
+                "renamed_nodes": len(self.renamed_nodes),
+                "added_edges": len(self.added_edges),
+                "removed_edges": len(self.removed_edges),
+                "skipped_files": len(self.skipped),
+            },
+            "added_nodes": self.added_nodes,
+            "removed_nodes": self.removed_nodes,
+            "changed_nodes": self.changed_nodes,
+            "renamed_nodes": self.renamed_nodes,
+            "added_edges": self.added_edges,
+            "removed_edges": self.removed_edges,
+            "caller_changes": self.caller_changes,
+            "skipped": self.skipped,
+        }
+
+
+def _node_summary(node: CodeNode) -> Dict[str, Any]:
+    return {"id": node.node_id, "type": node.node_type, "name": node.name,
+            "file_path": node.file_path, "line_no": node.line_no, "end_line_no": node.end_line_no}
+
+
+def _symbol_keys(nodes: Iterable[CodeNode]) -> Dict[str, tuple]:
+    """Line-independent identity of each node: kind, file, name and occurrence among namesakes."""
+    keys: Dict[str, tuple] = {}
+    seen: Dict[tuple, int] = {}
+    for node in sorted(nodes, key=lambda n: (n.file_path or "", n.line_no or 0, n.node_id)):
+        base = (node.node_type, node.file_path, node.name)
+        keys[node.node_id] = base + (seen.get(base, 0),)
+        seen[base] = seen.get(base, 0) + 1
+    return keys
+
+
+def _body_signature(node: CodeNode, outgoing: List[tuple]) -> tuple:
+    """
+    What a node keeps when it is renamed or moved: its kind, its body (or,
+    when no source was kept, its length in lines), its other attributes but
+    its qualified name, and the edges it starts, with its own name blanked
+    out.
+    """
+    attributes = _attributes(node)
+    # The qualified name changes with the module a symbol moves to
+    attributes.pop("body_hash", None)
+    attributes.pop("qualified_name", None)
+    if node.code_snippet:
+        body: Any = node.code_snippet
+    elif node.line_no is not None and node.end_line_no is not None:
+        body = node.end_line_no - node.line_no
+    else:
+        body = None
+    shape = repr((body, sorted(attributes.items()), sorted(outgoing)))
+    if node.name:
+        shape = re.sub(rf"\b{re.escape(node.name)}\b", "\0", shape)
+    return node.node_type, content_hash(shape.encode("utf-8"))
+
+
+def _pair_by_body(removed: List[Tuple[CodeNode, tuple]],
+                  added: List[Tuple[CodeNode, tuple]]) -> List[Tuple[CodeNode, CodeNode]]:
+    """
+    One-to-one pairs of a removed and an added node with the same body
+    signature, pairs within one file first, otherwise in file and line order.
+    """
+    candidates: Dict[tuple, List[CodeNode]] = {}
+    for node, signature in added:
+        candidates.setdefault(signature, []).append(node)
+    pairs, paired = [], set()
+    for same_file in (True, False):
+        for old, signature in removed:
+            if old.node_id in paired:
+                continue
+            for new in candidates.get(signature, ()):
+                if new.node_id not in paired and (not same_file or new.file_path == old.file_path):
+                    pairs.append((old, new))
+                    paired.update((old.node_id, new.node_id))
+                    break
+    return sorted(pairs, key=lambda pair: (pair[1].file_path or "", pair[1].line_no or 0))
+
+
+def _reordered(pairs: List[Tuple[CodeNode, CodeNode]]) -> Set[str]:
+    """
+    IDs (after) of matched nodes that moved past other symbols of their file.
+
+    Per file, the longest run of nodes keeping their relative order is taken
+    as staying put; the rest moved. Lines added or removed above a node shift
+    it and everything after it alike, so they never count as a move.
+    """
+    by_file: Dict[str, List[Tuple[CodeNode, CodeNode]]] = {}
+    for old, new in pairs:
+        if old.line_no and new.line_no:
+            by_file.setdefault(new.file_path, []).append((old, new))
+    moved: Set[str] = set()
+    for file_pairs in by_file.values():
+        file_pairs.sort(key=lambda pair: (pair[0].line_no, pair[0].node_id))
+        positions = [(new.line_no, new.node_id) for _, new in file_pairs]
+        # Longest increasing subsequence of the new positions, by patience sorting
+        tails: List[int] = []
+        previous: List[Optional[int]] = [None] * len(positions)
+        for index, position in enumerate(positions):
+            low, high = 0, len(tails)
+            while low < high:
+                middle = (low + high) // 2
+                if positions[tails[middle]] < position:
+                    low = middle + 1
+                else:
+                    high = middle
+            previous[index] = tails[low - 1] if low else None
+            if low == len(tails):
+                tails.append(index)
+            else:
+                tails[low] = index
+        kept: Set[int] = set()
+        index = tails[-1] if tails else None
+        while index is not None:
+            kept.add(index)
+            index = previous[index]
+        moved.update(new.node_id for i, (_, new) in enumerate(file_pairs) if i not in kept)
+    return moved
+
+
+def _caller_changes(result: "GraphDiff", before: Dict[str, CodeNode], after: Dict[str, CodeNode],
+                    renamed: Dict[str, str]) -> List[Dict[str, Any]]:
+    """Callers each symbol gained or lost, from the added and removed CALLS edges."""
+    changes: Dict[str, Dict[str, Any]] = {}
+
+    def entry(callee: CodeNode) -> Dict[str, Any]:
+        return changes.setdefault(callee.node_id, dict(_node_summary(callee), gained=[], lost=[]))
+
+    for edge in result.added_edges:
+        if edge["type"] == "CALLS" and edge["target"] in after and edge["source"] in after:
+            entry(after[edge["target"]])["gained"].append(_node_summary(after[edge["source"]]))
+    for edge in result.removed_edges:
+        if edge["type"] == "CALLS" and edge["target"] in before and edge["source"] in before:
+            target = renamed.get(edge["target"], edge["target"])
+            callee = after.get(target) or before[edge["target"]]
+            entry(callee)["lost"].append(_node_summary(before[edge["source"]]))
+    return sorted(changes.values(), key=lambda c: (c["file_path"] or "", c["line_no"] or 0, c["id"]))
+
+
+def _attributes(node: CodeNode) -> Dict[str, Any]:
+    """What a diff compares for a matched node: its non-positional properties and a body hash."""
+    attributes = {key: value for key, value in node.properties.items() if key not in POSITIONAL_PROPERTIES}
+    if node.code_snippet:
+        attributes["body_hash"] = content_hash(node.code_snippet.encode("utf-8"))[:16]
+    return attributes
+
+
+class CodeGraph:
+    """
+    Code graph that can be patched one file at a time.
+
+    Cross-file relations are recomputed for the changed file and for every
+    file whose pending imports reference it, so edges pointing into a file
+    are refreshed when that file changes. Calls that do not resolve (for
+    example because the callee was deleted) are kept and pointed at an
+    ``Unresolved`` placeholder node instead of being dropped.
+    """
+
+    def __init__(self, use_ast_grep: bool = False, ast_grep_languages: Optional[List[str]] = None,
+                 ast_grep_fallback: bool = True, respect_gitignore: bool = True,
+                 exclude: Optional[List[str]] = None, include: Optional[List[str]] = None,
+                 max_file_size: Optional[int] = None,
+                 language_options: Optional[Dict[str, Dict[str, Any]]] = None,
+                 language_overrides: Optional[Dict[str, str]] = None,
+                 include_kinds: Optional[Iterable[str]] = None):
+        """
+        Initialize an empty graph.
+
+        Args:
+            use_ast_grep: If True, use ast-grep adapters when available
+            ast_grep_languages: Languages to enable for ast-grep
+            ast_grep_fallback: If True, fall back to legacy parsers on error
+            respect_gitignore: If True, directory walks skip paths ignored by .gitignore
+            exclude: Extra glob patterns (.gitignore syntax) that directory walks skip
+            include: Glob patterns (.gitignore syntax) a file must match for directory walks to keep it
+            max_file_size: Size in bytes above which directory walks skip a file
+            language_options: Parser options by language, e.g. {"rust": {"index_private": False}}
+            language_overrides: Language by glob, parsing matching files as that language
+                whatever their extension, e.g. {"*.h": "cpp"}
+            include_kinds: Node types to keep, e.g. ["Function", "Method", "Class"];
+                nodes of other types are left out and their relations moved to
+                the nearest kept ancestor. File nodes are always kept. None
+                keeps every node
+        """
+        self._parser_options = {
+            "use_ast_grep": use_ast_grep,
+            "ast_grep_languages": sorted(ast_grep_languages or []),
+            "ast_grep_fallback": ast_grep_fallback,
+        }
+        if language_options:
+            self._parser_options["language_options"] = language_options
+        if language_overrides:
+            self._parser_options["language_overrides"] = language_overrides
+        self.include_kinds: Optional[Set[str]] = None
+        if include_kinds is not None:
+            self.include_kinds = set(include_kinds) | {"File"}
+            self._parser_options["include_kinds"] = sorted(self.include_kinds)
+        self._coordinator = MultiLanguageParser(
+            use_ast_grep=use_ast_grep,
+            ast_grep_languages=ast_grep_languages,
+            ast_grep_fallback=ast_grep_fallback,
+            respect_gitignore=respect_gitignore,
+            exclude=exclude,
+            include=include,
+            max_file_size=max_file_size,
+            language_options=language_options,
+            language_overrides=language_overrides,
+        )
+        self.files: Dict[str, FileState] = {}
+        self.nodes: Dict[str, CodeNode] = {}
+        self._unresolved_refs: Dict[str, int] = {}
+        self._module_definitions: Dict[str, Dict[str, str]] = {}
+        self._module_to_file: Dict[str, str] = {}
+        # Workspace projects, see with_projects; the index above then only
+        # holds files outside every project
+        self.projects: Optional[ProjectMap] = None
+        self._project_indexes: Dict[str, Tuple[Dict[str, Dict[str, str]], Dict[str, str]]] = {}
+        # Built on the first position lookup in a file, dropped when the file changes
+        self._span_indexes: Dict[str, SpanIndex] = {}
+        # Per-file parse results reused across builds, see with_cache_dir
+        self.parse_cache = None
+
+    def with_cache_dir(self, path: str) -> "CodeGraph":
+        """
+        Reuse parse results stored under a directory for files read from disk.
+
+        Files whose contents hash to a stored entry are installed from it
+        without parsing, the rest are parsed and stored. Resolution still
+        runs over every file, so the result matches an uncached build.
+        """
+        from src.graph.parse_cache import ParseCache
+        self.parse_cache = ParseCache(path, self._parser_options)
+        self.parse_cache.prune()
+        return self
+
+    def with_projects(self, projects: ProjectMap) -> "CodeGraph":
+        """
+        Index the roots of a workspace as projects of one graph.
+
+        Every node gets the ``project`` of the root its file is under,
+        module names resolve within a project, and imports of a package
+        mapped to another project resolve into it, with the edge marked
+        ``cross_project``; see src.graph.projects.
+        """
+        self.projects = projects
+        return self
+
+    @classmethod
+    def from_directory(cls, directory_path: str, jobs: int = 1, **parser_options) -> "CodeGraph":
+        """Build a graph from every supported source file under a directory."""
+        graph = cls(**parser_options)
+        graph.add_directory(directory_path, jobs=jobs)
+        return graph
+
+    @classmethod
+    def from_git(cls, repo_path: str, revision: str = "HEAD", **parser_options) -> "CodeGraph":
+        """
+        Build a graph from the source files of a git commit, read from the
+        object store without checking anything out; paths are relative to
+        the repository root. Binary files and files over ``max_file_bytes``
+        (a parser option here) are skipped, see src.graph.git_diff.graph_at_revision.
+
+        Raises:
+            GitError: The revision is not a commit or git fails
+        """
+        from src.graph.git_diff import graph_at_revision
+        return graph_at_revision(repo_path, revision, **parser_options)[0]
+
+    @classmethod
+    def from_records(cls, nodes: Iterable[CodeNode], relations: Iterable[CodeRelation]) -> "CodeGraph":
+        """
+        Build a graph from nodes and relations loaded from storage.
+
+        Relations are owned by the file of their source node. No pending
+        imports are known, so the result is meant for reading and exporting;
+        updating a file will not re-resolve edges from other files.
+        """
+        graph = cls()
+        states: Dict[str, FileState] = {}
+        owner: Dict[str, str] = {}
+        for node in nodes:
+            if node.node_type == UNRESOLVED_NODE_TYPE:
+                # Recreated from the relations that target it
+                continue
+            state = states.setdefault(node.file_path, FileState(node.file_path, {}, [], [], {}, {}))
+            state.nodes[node.node_id] = node
+            owner[node.node_id] = node.file_path
+        for relation in relations:
+            path = owner.get(relation.source_id)
+            if path is not None:
+                states[path].local_relations.append(relation)
+        graph.restore(states.values())
+        return graph
+
+    def to_json(self, indent: Optional[int] = None) -> str:
+        """
+        Serialize the whole graph as a versioned JSON document.
+
+        The document is ``{schema_version, nodes, edges}``. Each node has its
+        id, kind, name, file, span (start and end line), visibility (null for
+        nodes without one) and properties; each edge has its source, target,
+        kind and properties. Node IDs are built from kind, file, name and
+        line, so the same sources always give the same IDs, and both lists are
+        sorted, which keeps two snapshots of a codebase diffable line by line.
+
+        Args:
+            indent: Passed to json.dumps; None writes a single line
+        """
+        nodes = [node_record(node) for node in sorted(self.nodes.values(), key=lambda n: n.node_id)]
+        edges = [edge_record(relation) for relation in sorted(self.relations, key=relation_key)]
+        document = {"schema_version": JSON_SCHEMA_VERSION, "nodes": nodes, "edges": edges}
+        return json.dumps(document, indent=indent, sort_keys=True, ensure_ascii=False, default=str)
+
+    @classmethod
+    def from_json(cls, document: str) -> "CodeGraph":
+        """
+        Rebuild a graph from the output of to_json.
+
+        Like from_records, the result holds no pending imports, so it is
+        meant for reading, exporting and diffing rather than for updates.
+        Documents of schema version 1 are read as well.
+
+        Raises:
+            ValueError: If the document was written with a schema version this one cannot read
+        """
+        data = json.loads(document)
+        version = data.get("schema_version")
+        if version not in READABLE_JSON_SCHEMA_VERSIONS:
+            raise ValueError(f"Unsupported graph schema version {version}, expected one of "
+                             f"{', '.join(map(str, READABLE_JSON_SCHEMA_VERSIONS))}")
+
+        nodes = []
+        for item in data["nodes"]:
+            node = CodeNode(item["id"], item["kind"], item["name"], item["file"],
+                            item["span"]["start_line"], item["span"]["end_line"], item["properties"])
+            node.code_snippet = item.get("code_snippet")
+            nodes.append(node)
+        relations = [CodeRelation(item["source"], item["target"], item["kind"], item["properties"])
+                     for item in data["edges"]]
+        return cls.from_records(nodes, relations)
+
+    @property
+    def relations(self) -> List[CodeRelation]:
+        """All relations, grouped by owning file."""
+        return [r for state in self.files.values() for r in state.owned_relations()]
+
+    def collect_source_files(self, directory_path: str) -> List[str]:
+        """Source files under a directory that this graph would parse."""
+        return self._coordinator.collect_source_files(directory_path)
+
+    # ------------------------------------------------------------------
+    # Building and updating
+    # ------------------------------------------------------------------
+
+    def add_directory(self, directory_path: str, jobs: int = 1) -> GraphDelta:
+        """
+        Parse all supported files under a directory and resolve them together.
+
+        Args:
+            directory_path: Directory to walk
+            jobs: Number of threads parsing files. Each file gets its own
+                parser and the results are installed in path order before
+                the single-threaded resolution pass, so any number of jobs
+                builds the same graph
+        """
+        return self.build_with_progress(sorted(self.collect_source_files(directory_path)), None, jobs)
+
+    def build_with_progress(self, paths: List[str], progress: Optional[ProgressChannel],
+                            jobs: int = 1) -> GraphDelta:
+        """
+        Parse files from disk and resolve them together, reporting progress.
+
+        Args:
+            paths: Files to parse, installed in this order
+            progress: Channel receiving a FileParsed event per file, then
+                ResolutionStarted and ResolutionFinished; None sends nothing
+            jobs: Number of threads parsing files, as for add_directory
+        """
+        before = self._snapshot(paths)
+        for state in self.parse_files(paths, jobs, progress):
+            self._install(state)
+        return self._finish_build(before, progress)
+
+    def add_sources(self, sources: Dict[str, str], progress: Optional[ProgressChannel] = None) -> GraphDelta:
+        """
+        Parse files from text held in memory (read out of git, say) and resolve them together.
+
+        Args:
+            sources: File contents by the path the nodes should carry
+            progress: Channel receiving the events of build_with_progress
+        """
+        paths = sorted(sources)
+        before = self._snapshot(paths)
+        for index, path in enumerate(paths, 1):
+            state = self._parse(path, sources[path])
+            self._install(state)
+            if progress is not None:
+                progress.send(FileParsed(path, len(state.nodes), index, len(paths)))
+        return self._finish_build(before, progress)
+
+    def _finish_build(self, before, progress: Optional[ProgressChannel]) -> GraphDelta:
+        """Resolve every file once a build has installed them, between the resolution markers."""
+        if progress is not None:
+            progress.send(ResolutionStarted(len(self.files)))
+        self._rebuild_index()
+        self._resolve(self.files.keys())
+        delta = self._diff(before, self._snapshot(self.files.keys()), self.files.keys())
+        if progress is not None:
+            progress.send(ResolutionFinished(len(self.files), len(self.relations)))
+        return delta
+
+    def restore(self, states: Iterable[FileState]) -> None:
+        """
+        Install previously saved file states as they are.
+
+        Nothing is parsed or re-resolved, so the graph matches the one the
+        states were saved from; later updates patch it as usual.
+        """
+        for state in states:
+            self._install(state)
+        self._rebuild_index()
+
+    def add_file(self, file_path: str, source: Optional[str] = None) -> GraphDelta:
+        """Add a file to the graph (same as update_file for a new path)."""
+        return self.update_file(file_path, source)
+
+    def update_file(self, file_path: str, new_source: Optional[str] = None,
+                    parsed: Optional[FileState] = None) -> GraphDelta:
+        """
+        Re-parse a single file and patch the graph in place.
+
+        Args:
+            file_path: Path of the changed file
+            new_source: New file contents; read from disk when omitted
+            parsed: The file's state from parse_files, used instead of parsing again
+
+        Returns:
+            GraphDelta describing the nodes and relations that changed
+        """
+        key = self._file_key(file_path)
+        old_state = self.files.get(key)
+        new_state = parsed if parsed is not None else self._parse(key, new_source)
+
+        affected = {key} | self._dependents(old_state, new_state)
+        before = self._snapshot(affected)
+
+        if old_state is not None:
+            self._uninstall(old_state)
+        self._install(new_state)
+        self._rebuild_index()
+        self._resolve(affected)
+
+        return self._diff(before, self._snapshot(affected), affected)
+
+    def remove_file(self, file_path: str) -> GraphDelta:
+        """Drop a file's nodes and relations; callers into it become unresolved."""
+        key = self._file_key(file_path)
+        old_state = self.files.get(key)
+        if old_state is None:
+            return GraphDelta()
+
+        affected = {key} | self._dependents(old_state, None)
+        before = self._snapshot(affected)
+
+        self._uninstall(old_state)
+        self._rebuild_index()
+        self._resolve(affected - {key})
+
+        return self._diff(before, self._snapshot(affected), affected)
+
+    # ------------------------------------------------------------------
+    # Queries
+    # ------------------------------------------------------------------
+
+    def reachable(self, from_id: str, to_id: str, max_depth: int = DEFAULT_MAX_DEPTH,
+                  stopped: Optional[List[str]] = None) -> Optional[List[str]]:
+        """
+        Shortest call path from one node to another.
+
+        Only CALLS edges are followed, breadth first, and every node is
+        visited once, so recursion and call cycles terminate.
+
+        Args:
+            from_id: Node ID of the caller to start from
+            to_id: Node ID of the callee to reach
+            max_depth: Maximum number of call hops to explore
+            stopped: Filled, when to_id is not reached, with the nodes at
+                max_depth that call nodes left unvisited
+
+        Returns:
+            Node IDs from from_id to to_id inclusive, or None when to_id is
+            not reachable within max_depth hops
+        """
+        if from_id not in self.nodes or to_id not in self.nodes:
+            return None
+        if from_id == to_id:
+            return [from_id]
+
+        callees: Dict[str, List[str]] = {}
+        for relation in self.relations:
+            if relation.relation_type == "CALLS":
+                callees.setdefault(relation.source_id, []).append(relation.target_id)
+
+        parents: Dict[str, str] = {from_id: from_id}
+        frontier = deque([(from_id, 0)])
+        at_limit = []
+        while frontier:
+            node_id, depth = frontier.popleft()
+            if depth >= max_depth:
+                at_limit.append(node_id)
+                continue
+            for callee_id in callees.get(node_id, ()):
+                if callee_id in parents:
+                    continue
+                parents[callee_id] = node_id
+                if callee_id == to_id:
+                    path = [callee_id]
+                    while path[-1] != from_id:
+                        path.append(parents[path[-1]])
+                    return path[::-1]
+                frontier.append((callee_id, depth + 1))
+        if stopped is not None:
+            stopped.extend(node_id for node_id in at_limit
+                           if any(callee_id not in parents for callee_id in callees.get(node_id, ())))
+        return None
+
+    def callers(self, node_id: str, include_unresolved: bool = False) -> List[CallSite]:
+        """
+        Every call into a node, one entry per call site, ordered by location.
+
+        A recursive function is among its own callers.
+
+        Args:
+            node_id: Node ID of the callee
+            include_unresolved: Also report calls that were not resolved but
+                whose name as written ends with the callee's name, such as
+                ``helper`` or ``utils.helper`` for ``helper``
+
+        Returns:
+            Call sites whose ``node_id`` is the caller
+        """
+        node = self.nodes.get(node_id)
+        if node is None:
+            return []
+        sites = []
+        for relation in self.relations:
+            if relation.relation_type != "CALLS":
+                continue
+            if relation.target_id == node_id:
+                sites.append(self._call_site(relation, relation.source_id))
+            elif include_unresolved and relation.target_id.startswith(UNRESOLVED_PREFIX):
+                site = self._call_site(relation, relation.source_id)
+                if self._names(site.raw_name, node.name):
+                    sites.append(site)
+        return sorted(sites, key=lambda s: (s.file_path or "", s.line_no or 0, s.node_id))
+
+    def callees(self, node_id: str, include_unresolved: bool = False) -> List[CallSite]:
+        """
+        Every call made by a node, one entry per call site, ordered by location.
+
+        A recursive function is among its own callees.
+
+        Args:
+            node_id: Node ID of the caller
+            include_unresolved: Also report calls whose callee was not found;
+                their ``node_id`` is the ``Unresolved`` placeholder and
+                ``raw_name`` the name as written
+
+        Returns:
+            Call sites whose ``node_id`` is the callee
+        """
+        if node_id not in self.nodes:
+            return []
+        sites = [self._call_site(relation, relation.target_id) for relation in self.relations
+                 if relation.relation_type == "CALLS" and relation.source_id == node_id
+                 and (include_unresolved or not relation.target_id.startswith(UNRESOLVED_PREFIX))]
+        return sorted(sites, key=lambda s: (s.file_path or "", s.line_no or 0, s.node_id))
+
+    def _call_site(self, relation: CodeRelation, other_id: str) -> CallSite:
+        caller = self.nodes.get(relation.source_id)
+        properties = relation.properties
+        raw_name = None
+        if relation.target_id.startswith(UNRESOLVED_PREFIX):
+            raw_name = properties.get("raw_name") or relation.target_id[len(UNRESOLVED_PREFIX):]
+        return CallSite(other_id, caller.file_path if caller else "", properties.get("line_no"),
+                        properties.get("column"), properties.get("call_site"), raw_name)
+
+    @staticmethod
+    def _names(raw_name: Optional[str], name: str) -> bool:
+        """Whether a name as written (``a.b``, ``a::b``) refers to something called ``name``."""
+        if not raw_name:
+            return False
+        return raw_name == name or raw_name.endswith("." + name) or raw_name.endswith("::" + name)
+
+    def nodes_at(self, file_path: str, line: int, column: int) -> List[str]:
+        """
+        Nodes of a file whose source span contains a position, innermost first.
+
+        Identical spans are ordered by node kind (a method before the class
+        of the same span) and then by ID. Lookups go through an interval tree
+        per file, built on first use.
+
+        Args:
+            file_path: Path of the file, as indexed or as an equivalent path
+            line: 1-based line
+            column: 1-based column
+
+        Returns:
+            Node IDs; empty when the file is not indexed or no symbol is there
+        """
+        key = self._file_key(file_path)
+        state = self.files.get(key)
+        if state is None:
+            return []
+        index = self._span_indexes.get(key)
+        if index is None:
+            index = self._span_indexes[key] = SpanIndex.from_nodes(state.nodes.values())
+        return [span.node_id for span in index.containing((line, column))]
+
+    def node_at(self, file_path: str, line: int, column: int) -> Optional[str]:
+        """Innermost node whose span contains a position, see nodes_at."""
+        found = self.nodes_at(file_path, line, column)
+        return found[0] if found else None
+
+    def find_by_qualified_name(self, name: str) -> List[CodeNode]:
+        """
+        Nodes whose ``qualified_name`` is exactly ``name``, such as
+        ``crate::person::Person::new`` or ``sample.Person.get_name``.
+
+        Returns:
+            Matching nodes by file and line; more than one only when a name is
+            really defined twice (a redefinition, overloads, a Python property
+            setter)
+        """
+        found = [node for node in self.nodes.values() if node.properties.get("qualified_name") == name]
+        return sorted(found, key=lambda node: (node.file_path or "", node.line_no or 0, node.node_id))
+
+    def metrics(self, node_id: str):
+        """
+        Fan-in, fan-out and cyclomatic complexity of a function or method.
+
+        Returns:
+            A FunctionMetrics, or None when the node is not a function or method
+        """
+        from src.graph.metrics import function_metrics
+        return function_metrics(self, node_id)
+
+    def module_cycles(self):
+        """
+        Import cycles between files: every strongly connected component of
+        the file import graph with more than one member, and every file
+        importing from itself, e.g. through a re-export.
+
+        Returns:
+            Cycles ordered by their first file, each with a representative
+            loop in ``path``, see src.graph.cycles.find_cycles
+        """
+        from src.graph.cycles import find_cycles
+        return find_cycles(self, "module", include_self_loops=True)
+
+    def parse_report(self) -> ParseReport:
+        """Syntax errors of every file in the graph, and whether declarations were recovered around them."""
+        report = ParseReport()
+        for path in sorted(self.files):
+            state = self.files[path]
+            report.add(path, state.parse_errors,
+                       partial=bool(state.parse_errors) and has_declarations(state.nodes.values()))
+        return report
+
+    def diff(self, other: "CodeGraph") -> GraphDiff:
+        """
+        Compare this graph (before) with another graph of the same codebase (after).
+
+        ``Unresolved`` placeholders are not reported as nodes, but edges to
+        them are, keyed by the placeholder's path.
+        """
+        def symbols(graph: "CodeGraph") -> Dict[str, CodeNode]:
+            return {node_id: node for node_id, node in graph.nodes.items()
+                    if node.node_type != UNRESOLVED_NODE_TYPE}
+
+        before_nodes, after_nodes = symbols(self), symbols(other)
+        before_keys, after_keys = _symbol_keys(before_nodes.values()), _symbol_keys(after_nodes.values())
+        before_by_key = {key: node_id for node_id, key in before_keys.items()}
+        after_by_key = {key: node_id for node_id, key in after_keys.items()}
+
+        def ordered(by_key: Dict[tuple, str], others: Dict[tuple, str], nodes: Dict[str, CodeNode]) -> List[CodeNode]:
+            return [nodes[node_id] for key, node_id in sorted(by_key.items(), key=lambda item: item[0][1:])
+                    if key not in others]
+
+        def outgoing(graph: "CodeGraph", keys: Dict[str, tuple]) -> Dict[str, List[tuple]]:
+            found: Dict[str, List[tuple]] = {}
+            for relation in graph.relations:
+                target = relation.target_id or ""
+                found.setdefault(relation.source_id, []).append((relation.relation_type, keys.get(target, target)))
+            return found
+
+        before_outgoing, after_outgoing = outgoing(self, before_keys), outgoing(other, after_keys)
+        pairs = _pair_by_body(
+            [(node, _body_signature(node, before_outgoing.get(node.node_id, [])))
+             for node in ordered(before_by_key, after_by_key, before_nodes)],
+            [(node, _body_signature(node, after_outgoing.get(node.node_id, [])))
+             for node in ordered(after_by_key, before_by_key, after_nodes)])
+        renamed = {old.node_id: new.node_id for old, new in pairs}
+        matched = [(before_nodes[before_by_key[key]], after_nodes[node_id])
+                   for key, node_id in sorted(after_by_key.items(), key=lambda item: item[0][1:])
+                   if key in before_by_key]
+        moved = _reordered(matched)
+
+        result = GraphDiff()
+        for old_node, node in matched:
+            before_attributes, after_attributes = _attributes(old_node), _attributes(node)
+            changes = {name: {"before": before_attributes.get(name), "after": after_attributes.get(name)}
+                       for name in sorted(set(before_attributes) | set(after_attributes))
+                       if before_attributes.get(name) != after_attributes.get(name)}
+            if node.node_id in moved:
+                changes["span"] = {"before": {"start_line": old_node.line_no, "end_line": old_node.end_line_no},
+                                   "after": {"start_line": node.line_no, "end_line": node.end_line_no}}
+            if changes:
+                result.changed_nodes.append(dict(_node_summary(node), before_id=old_node.node_id,
+                                                 before_line_no=old_node.line_no, changes=changes))
+        renamed_to = set(renamed.values())
+        result.added_nodes = [_node_summary(node) for node in ordered(after_by_key, before_by_key, after_nodes)
+                              if node.node_id not in renamed_to]
+        result.removed_nodes = [_node_summary(node) for node in ordered(before_by_key, after_by_key, before_nodes)
+                                if node.node_id not in renamed]
+        result.renamed_nodes = [{"before": _node_summary(old), "after": _node_summary(new)} for old, new in pairs]
+        # Edges of a renamed or moved symbol follow it
+        before_keys.update({old_id: after_keys[new_id] for old_id, new_id in renamed.items()})
+
+        def edges(graph: "CodeGraph", keys: Dict[str, tuple]) -> Dict[tuple, CodeRelation]:
+            found = {}
+            for relation in graph.relations:
+                source, target = relation.source_id or "", relation.target_id or ""
+                identity = tuple(sorted((k, str(v)) for k, v in relation_identity(relation).items()
+                                        if k != "line_no"))
+                found[(keys.get(source, source), relation.relation_type, keys.get(target, target), identity)] = relation
+            return found
+
+        before_edges, after_edges = edges(self, before_keys), edges(other, after_keys)
+        result.added_edges = [_relation_to_dict(after_edges[key])
+                              for key in sorted(after_edges, key=repr) if key not in before_edges]
+        result.removed_edges = [_relation_to_dict(before_edges[key])
+                                for key in sorted(before_edges, key=repr) if key not in after_edges]
+        result.caller_changes = _caller_changes(result, before_nodes, after_nodes, renamed)
+        return result
+
+    # ------------------------------------------------------------------
+    # Parsing
+    # ------------------------------------------------------------------
+
+    def _file_key(self, file_path: str) -> str:
+        """Map a path onto the key already used for that file, if any."""
+        if file_path in self.files:
+            return file_path
+        target = os.path.abspath(file_path)
+        for key in self.files:
+            if os.path.abspath(key) == target:
+                return key
+        return file_path
+
+    def parse_files(self, paths: List[str], jobs: int = 1,
+                    progress: Optional[ProgressChannel] = None) -> List[FileState]:
+        """
+        Parse files from disk, in the order given, on up to ``jobs`` threads.
+
+        Nothing is installed; pass a state to update_file to apply it. Node
+        IDs are derived from each node's kind, file, name and line, so the
+        states do not depend on which thread parsed them or when. A
+        FileParsed event is sent to ``progress`` as each file finishes.
+        """
+        if jobs <= 1 or len(paths) < 2:
+            states = []
+            for index, path in enumerate(paths, 1):
+                states.append(self._parse(path, None))
+                if progress is not None:
+                    progress.send(FileParsed(path, len(states[-1].nodes), index, len(paths)))
+            return states
+        with ThreadPoolExecutor(max_workers=min(jobs, len(paths))) as pool:
+            futures = {pool.submit(self._parse, path, None): path for path in paths}
+            if progress is not None:
+                for index, future in enumerate(as_completed(futures), 1):
+                    progress.send(FileParsed(futures[future], len(future.result().nodes), index, len(paths)))
+            # Returned in submission order whatever order the files finished in
+            return [future.result() for future in futures]
+
+    def _parse(self, file_path: str, source: Optional[str]) -> FileState:
+        """Parse one file with a fresh parser and capture its contribution."""
+        overrides = self._coordinator.language_overrides
+        ext = parsed_extension(file_path, overrides).lower()
+        language = detect_language(file_path, overrides)
+        parser = self._coordinator._get_parser_for_file(file_path, language, ext)
+        if parser is None:
+            return FileState(file_path, {}, [], [], {}, {})
+        # The JS/TS grammars go by the extension, which an override may have changed
+        own_ext = os.path.splitext(file_path)[1].lower()
+        options = {"extension": ext} if ext in JS_TS_EXTENSIONS and ext != own_ext else {}
+
+        mtime_ns = size = None
+        from_disk = source is None
+        if from_disk:
+            try:
+                with open(file_path, "rb") as f:
+                    data = f.read()
+                mtime_ns = os.stat(file_path).st_mtime_ns
+            except OSError as e:
+                # Gone or unreadable: flagged like a file that does not parse, and the build goes on
+                return self._failed_state(file_path, read_error(e))
+            digest, size = content_hash(data), len(data)
+            cached = self.parse_cache.get(file_path, digest) if self.parse_cache else None
+            if cached is not None:
+                cached.mtime_ns, cached.size = mtime_ns, size
+                self.stamp_file(cached)
+                return cached
+            # Parse the bytes that were hashed, so a cache entry always matches its contents
+            source = decode_source(data)
+        else:
+            digest = content_hash(source.encode("utf-8"))
+        try:
+            nodes, relations = parser.parse_source(source, file_path, build_index=True, **options)
+            assign_spans(nodes.values(), source)
+        except Exception as e:
+            logger.error(f"Error parsing file {file_path}: {e}")
+            return self._failed_state(file_path, ParseError(f"Parser failed: {e}"), digest, mtime_ns, size)
+        assign_qualified_names(nodes, relations, file_path, language)
+
+        state = FileState(
+            file_path=file_path,
+            nodes=dict(nodes),
+            local_relations=list(relations),
+            pending_imports=list(getattr(parser, "pending_imports", [])),
+            module_definitions=dict(getattr(parser, "module_definitions", {})),
+            module_to_file=dict(getattr(parser, "module_to_file", {})),
+            content_hash=digest,
+            mtime_ns=mtime_ns,
+            size=size,
+            parse_errors=list(getattr(parser, "parse_errors", [])),
+        )
+        flag_parse_error(file_path, state.nodes, state.parse_errors)
+        self.stamp_file(state)
+        for module_name, file_node_id in state.module_to_file.items():
+            if file_node_id in state.nodes:
+                state.nodes[file_node_id].properties["module_name"] = module_name
+        state.local_relations = [self._link_placeholder(state, r) for r in state.local_relations]
+        mark_tests(file_path, state.nodes, state.local_relations, state.pending_imports, language)
+        if self.include_kinds is not None:
+            self._prune(state)
+        if from_disk and self.parse_cache is not None:
+            self.parse_cache.put(state)
+        return state
+
+    def _failed_state(self, file_path: str, error: ParseError, digest: Optional[str] = None,
+                      mtime_ns: Optional[int] = None, size: Optional[int] = None) -> FileState:
+        """State of a file that could not be read or parsed: its File node alone, flagged parse_error."""
+        state = FileState(file_path, {}, [], [], {}, {}, content_hash=digest, mtime_ns=mtime_ns, size=size,
+                          parse_errors=[error])
+        flag_parse_error(file_path, state.nodes, state.parse_errors)
+        self.stamp_file(state)
+        return state
+
+    @staticmethod
+    def stamp_file(state: FileState) -> None:
+        """Copy the content hash and mtime onto the file's File node."""
+        for node in state.nodes.values():
+            if node.node_type != "File":
+                continue
+            node.properties["content_hash"] = state.content_hash
+            if state.mtime_ns is None:
+                node.properties.pop("mtime_ns", None)
+            else:
+                node.properties["mtime_ns"] = state.mtime_ns
+
+    def _link_placeholder(self, state: FileState, relation: CodeRelation) -> CodeRelation:
+        """
+        Point a same-file call or base class at the real node.
+
+        Parsers emit these against placeholder IDs ending in ``:0`` because
+        the target may be defined further down the file. Those are matched by
+        name here; targets with no unambiguous match become unresolved.
+        """
+        if relation.relation_type not in ("CALLS", "EXTENDS") or relation.target_id in state.nodes:
+            return relation
+        target_id = relation.target_id or ""
+        if not target_id.endswith(":0"):
+            return relation
+
+        node_type, _, rest = target_id.partition(":")
+        name = rest.rsplit(":", 2)[-2]
+        candidates = [n for n in state.nodes.values() if n.node_type == node_type and n.name == name]
+
+        if node_type == "Method" and len(candidates) > 1 \
+                and relation.properties.get("object") in ("self", "this"):
+            # Prefer a method on the caller's own class
+            owner = self._owner_of(state, relation.source_id)
+            siblings = {r.target_id for r in state.local_relations
+                        if r.relation_type == "DEFINES" and r.source_id == owner}
+            candidates = [n for n in candidates if n.node_id in siblings]
+
+        properties = dict(relation.properties)
+        if len(candidates) == 1:
+            return CodeRelation(relation.source_id, candidates[0].node_id, relation.relation_type, properties)
+
+        properties.update({"unresolved": True, "raw_name": name})
+        return CodeRelation(relation.source_id, UNRESOLVED_PREFIX + name, relation.relation_type, properties)
+
+    def _prune(self, state: FileState) -> None:
+        """
+        Leave out the nodes whose type is not in include_kinds.
+
+        Each one is replaced, in the file's relations, pending imports and
+        module definitions, by its nearest kept ancestor along DEFINES and
+        CONTAINS (an enclosing function or type, else the file), so a call
+        made from a left-out node still leaves that ancestor. A relation
+        that ends up from a node to itself, such as a function defining one
+        of its variables, is dropped.
+        """
+        parents: Dict[str, str] = {}
+        for relation in state.local_relations:
+            if relation.relation_type in ("DEFINES", "CONTAINS") and relation.source_id in state.nodes:
+                parents.setdefault(relation.target_id, relation.source_id)
+        file_id = f"file:{state.file_path}"
+        rerouted: Dict[str, Optional[str]] = {}
+        for node_id, node in state.nodes.items():
+            if node.node_type in self.include_kinds:
+                continue
+            seen, ancestor = {node_id}, parents.get(node_id)
+            while ancestor is not None and ancestor not in seen \
+                    and state.nodes[ancestor].node_type not in self.include_kinds:
+                seen.add(ancestor)
+                ancestor = parents.get(ancestor)
+            if ancestor is None or ancestor in seen:
+                ancestor = file_id if file_id in state.nodes else None
+            rerouted[node_id] = ancestor
+        if not rerouted:
+            return
+
+        for node_id in rerouted:
+            del state.nodes[node_id]
+        relations, keys = [], {relation_key(r) for r in state.local_relations}
+        for relation in state.local_relations:
+            source = rerouted.get(relation.source_id, relation.source_id)
+            target = rerouted.get(relation.target_id, relation.target_id)
+            if source is None or target is None or source == target:
+                continue
+            if (source, target) != (relation.source_id, relation.target_id):
+                relation = CodeRelation(source, target, relation.relation_type, dict(relation.properties))
+                # Two relations moved onto the same ancestor may now be one
+                if relation_key(relation) in keys:
+                    continue
+                keys.add(relation_key(relation))
+            relations.append(relation)
+        state.local_relations = relations
+
+        state.pending_imports = [
+            {key: rerouted.get(value, value) if isinstance(value, str) else value for key, value in entry.items()}
+            for entry in state.pending_imports if rerouted.get(entry.get("source_id"), "") is not None
+        ]
+        state.module_definitions = {
+            module_name: {name: rerouted.get(node_id, node_id) for name, node_id in definitions.items()
+                          if rerouted.get(node_id, node_id) is not None}
+            for module_name, definitions in state.module_definitions.items()
+        }
+
+    @staticmethod
+    def _owner_of(state: FileState, node_id: Optional[str]) -> Optional[str]:
+        for r in state.local_relations:
+            if r.relation_type == "DEFINES" and r.target_id == node_id:
+                return r.source_id
+        return None
+
+    # ------------------------------------------------------------------
+    # Cross-file resolution
+    # ------------------------------------------------------------------
+
+    def _rebuild_index(self) -> None:
+        """Merge per-file module indices in file order (later files win, as in a full parse)."""
+        if self.projects is None:
+            self._module_definitions, self._module_to_file = self._merge_index(self.files.values())
+            return
+        by_project: Dict[Optional[str], List[FileState]] = {}
+        for state in self.files.values():
+            project = self.projects.project_of(state.file_path)
+            by_project.setdefault(project.name if project else None, []).append(state)
+        # Files of a mapped package's project are importable from every project
+        packages = [(module_name, state) for state in self.files.values()
+                    for module_name in self.projects.package_modules(state.file_path)]
+        self._module_definitions, self._module_to_file = self._merge_index(by_project.pop(None, []), packages)
+        self._project_indexes = {name: self._merge_index(states, packages) for name, states in by_project.items()}
+
+    @staticmethod
+    def _merge_index(states: Iterable[FileState], packages: Iterable[Tuple[str, FileState]] = ()):
+        """Module definitions and files of some files, plus package module names none of them takes."""
+        module_definitions: Dict[str, Dict[str, str]] = {}
+        module_to_file: Dict[str, str] = {}
+        for state in states:
+            for module_name, definitions in state.module_definitions.items():
+                module_definitions.setdefault(module_name, {}).update(definitions)
+            module_to_file.update(state.module_to_file)
+        for module_name, state in packages:
+            if module_name in module_to_file or f"file:{state.file_path}" not in state.nodes:
+                continue
+            module_to_file[module_name] = f"file:{state.file_path}"
+            for definitions in state.module_definitions.values():
+                module_definitions.setdefault(module_name, {}).update(definitions)
+        resolve_reexports(module_definitions)
+        return module_definitions, module_to_file
+
+    def _index_of(self, file_path: str):
+        """The module index a file's imports resolve against: its project's, or the one of files outside any."""
+        project = self.projects.project_of(file_path) if self.projects is not None else None
+        if project is None:
+            return self._module_definitions, self._module_to_file
+        return self._project_indexes.get(project.name, ({}, {}))
+
+    def _dependents(self, old_state: Optional[FileState], new_state: Optional[FileState]) -> Set[str]:
+        """Files whose pending imports may resolve differently after a change."""
+        modules: Set[str] = set()
+        names: Set[str] = set()
+        for state in (old_state, new_state):
+            if state is None:
+                continue
+            modules.update(state.module_to_file)
+            if self.projects is not None:
+                modules.update(self.projects.package_modules(state.file_path))
+            for definitions in state.module_definitions.values():
+                names.update(definitions)
+
+        changed = (old_state or new_state).file_path
+        # Go package-level entries depend on every file of the package's directory
+        changed_dir = os.path.dirname(changed)
+        dependents = set()
+        for path, state in self.files.items():
+            if path == changed:
+                continue
+            for entry in state.pending_imports:
+                if entry.get("imported_module") in modules \
+                        or entry.get("type_name") in names or entry.get("trait_name") in names \
+                        or entry.get("definition") in names or entry.get("imported_name") in names \
+                        or not modules.isdisjoint(entry.get("modules", ())) \
+                        or entry.get("package_dir") == changed_dir:
+                    dependents.add(path)
+                    break
+        return dependents
+
+    def _resolve(self, paths: Iterable[str]) -> None:
+        """Recompute the second-pass relations owned by the given files."""
+        resolver = ASTParser()
+        resolver.nodes = self.nodes
+        # CALLS_METHOD resolution looks methods up through DEFINES relations
+        defines = [r for state in self.files.values() for r in state.owned_relations()
+                   if r.relation_type == "DEFINES"]
+
+        for path in list(paths):
+            state = self.files.get(path)
+            if state is None:
+                continue
+            self._release(state.resolved_relations)
+            resolver.module_definitions, resolver.module_to_file = self._index_of(path)
+            state.resolved_relations = self._resolve_file(resolver, state, defines)
+            state.resolved_relations += tests_relations(state.local_relations + state.resolved_relations, self.nodes)
+            if self.projects is not None:
+                self._mark_cross_project(state.resolved_relations)
+            self._retain(state.resolved_relations)
+
+    def _mark_cross_project(self, relations: List[CodeRelation]) -> None:
+        """Flag resolved relations that link nodes of two different projects."""
+        for relation in relations:
+            source, target = self.nodes.get(relation.source_id), self.nodes.get(relation.target_id)
+            if source is None or target is None:
+                continue
+            projects = source.properties.get("project"), target.properties.get("project")
+            if None not in projects and projects[0] != projects[1]:
+                relation.properties["cross_project"] = True
+
+    def _resolve_file(self, resolver: ASTParser, state: FileState,
+                      defines: List[CodeRelation]) -> List[CodeRelation]:
+        resolver.relations = list(defines)
+        resolver.established_relations = set()
+        processed_by_source: Dict[str, Set[str]] = {}
+        resolved: List[CodeRelation] = []
+
+        for entry in state.pending_imports:
+            start = len(resolver.relations)
+            processed = processed_by_source.setdefault(entry["source_id"], set())
+            resolver._resolve_pending_import(entry, processed)
+            produced = resolver.relations[start:]
+
+            if not produced and entry["type"] in ("CALLS", "CALLS_METHOD", "CALLS_MEMBER", "CALLS_RUST",
+                                                  "CALLS_PACKAGE", "CALLS_C", "CALLS_MACRO", "CALLS_UNRESOLVED"):
+                produced = [self._unresolved_call(entry)]
+            elif not produced and entry["type"] == "DERIVE_USE":
+                produced = [self._unresolved_derive(entry)]
+            elif not produced and entry["type"] == "IMPORTS_MODULE" and "system" in entry \
+                    and (entry["system"] or entry["imported_module"] not in resolver.module_to_file):
+                produced = [self._unresolved_include(entry)]
+            elif not produced and entry["type"] == "IMPLEMENTS":
+                produced = self._unresolved_implements(resolver, entry)
+            elif not produced and entry["type"] == "SUPERTRAIT":
+                produced = [self._unresolved_supertrait(entry)]
+            elif not produced and entry["type"] == "IMPORTS_RUST":
+                produced = [self._unresolved_import(entry)]
+            elif not produced and entry["type"] == "IMPORTS_RUBY" and resolver._required_file(entry) is None:
+                produced = [self._unresolved_require(entry)]
+            elif not produced and entry["type"] == "AR_ASSOCIATION" and resolver._association_model(entry) is None:
+                produced = [self._unresolved_association(entry)]
+            resolved.extend(produced)
+        return resolved
+
+    @staticmethod
+    def _unresolved_import(entry: Dict[str, Any]) -> CodeRelation:
+        """
+        IMPORTS relation to a placeholder for a Rust use path outside the
+        indexed crate (``std::fmt``), or naming an item that was not found.
+        """
+        raw_name = entry["full_module_path"]
+        head = raw_name.split("::")[0]
+        properties = {"path": raw_name, "alias": entry.get("alias"), "glob": entry.get("glob", False),
+                      "line_no": entry.get("line_no"), "unresolved": True, "raw_name": raw_name}
+        if head not in ("crate", "self", "super"):
+            properties["external_crate"] = head
+        return CodeRelation(entry["source_id"], UNRESOLVED_PREFIX + raw_name, "IMPORTS", properties)
+
+    @staticmethod
+    def _unresolved_require(entry: Dict[str, Any]) -> CodeRelation:
+        """
+        IMPORTS relation to a placeholder for a Ruby require of a gem or the
+        standard library (``require "json"``), or of a file that is not indexed.
+        """
+        raw_name = entry["path"]
+        return CodeRelation(entry["source_id"], UNRESOLVED_PREFIX + raw_name, "IMPORTS",
+                            {"path": raw_name, "require": "require_relative" if entry.get("relative") else "require",
+                             "line_no": entry.get("line_no"), "unresolved": True, "raw_name": raw_name})
+
+    @staticmethod
+    def _unresolved_association(entry: Dict[str, Any]) -> CodeRelation:
+        """
+        AR_ASSOCIATION relation to a placeholder for a model that was not
+        found, or the interface a polymorphic ``belongs_to`` names.
+        """
+        raw_name = entry["original_name"]
+        properties = {key: entry[key] for key in ("association", "name", "line_no", "through", "polymorphic")
+                      if key in entry}
+        properties.update({"class_name": raw_name, "unresolved": True, "raw_name": raw_name})
+        return CodeRelation(entry["source_id"], UNRESOLVED_PREFIX + raw_name, "AR_ASSOCIATION", properties)
+
+    @staticmethod
+    def _unresolved_include(entry: Dict[str, Any]) -> CodeRelation:
+        """
+        INCLUDES relation to a placeholder for a C/C++ include: a system header
+        (``#include <stdio.h>``) or a quoted one that is not indexed.
+        """
+        raw_name = entry["full_module_path"]
+        return CodeRelation(entry["source_id"], UNRESOLVED_PREFIX + raw_name, "INCLUDES",
+                            {"path": raw_name, "system": entry["system"], "line_no": entry.get("line_no"),
+                             "unresolved": True, "raw_name": raw_name})
+
+    @staticmethod
+    def _unresolved_implements(resolver: ASTParser, entry: Dict[str, Any]) -> List[CodeRelation]:
+        """
+        IMPLEMENTS relation to a placeholder for a trait that was not found
+        (``impl fmt::Display for Person``), as long as the type itself is known.
+        """
+        type_node_id = entry.get("type_node_id") or resolver._find_definition(
+            entry["type_name"], entry.get("module_hints", []), ("Class",))
+        if not type_node_id:
+            return []
+        raw_name = entry.get("trait_path") or entry["trait_name"]
+        return [CodeRelation(type_node_id, UNRESOLVED_PREFIX + raw_name, "IMPLEMENTS",
+                             {"trait_path": entry.get("trait_path"), **entry.get("impl_properties", {}),
+                              "unresolved": True, "raw_name": raw_name})]
+
+    @staticmethod
+    def _unresolved_supertrait(entry: Dict[str, Any]) -> CodeRelation:
+        """EXTENDS relation to a placeholder for a supertrait that was not found (``trait Shape: fmt::Debug``)."""
+        raw_name = entry["trait_path"]
+        return CodeRelation(entry["subtrait_id"], UNRESOLVED_PREFIX + raw_name, "EXTENDS",
+                            {"trait_path": raw_name, "unresolved": True, "raw_name": raw_name})
+
+    @staticmethod
+    def _unresolved_derive(entry: Dict[str, Any]) -> CodeRelation:
+        """
+        DERIVE_USE relation to a placeholder for a derive macro of a crate
+        that is not indexed (``Debug``, ``serde::Serialize``).
+        """
+        raw_name = entry["raw_name"]
+        return CodeRelation(entry["source_id"], UNRESOLVED_PREFIX + raw_name, "DERIVE_USE",
+                            {"derive_path": raw_name, "unresolved": True, "raw_name": raw_name})
+
+    @staticmethod
+    def _unresolved_call(entry: Dict[str, Any]) -> CodeRelation:
+        """
+        Call relation to a placeholder for a callee that was not found.
+
+        Parsers that know the callee's textual path (``String::from``) pass it
+        as ``raw_name``; otherwise it is built from the entry's parts.
+        """
+        if entry["type"] == "CALLS":
+            raw_name = f"{entry['imported_module']}.{entry['imported_name']}"
+            properties = {"original_name": entry.get("original_name")}
+        elif entry["type"] == "CALLS_MEMBER":
+            raw_name = f"{entry['type_name']}.{entry['member_name']}"
+            properties = {"class": entry["type_name"]}
+        elif entry["type"] == "CALLS_METHOD":
+            raw_name = f"{entry['imported_class']}.{entry['method_name']}"
+            properties = {"object": entry.get("original_obj_name")}
+        elif entry["type"] == "CALLS_MACRO":
+            raw_name = entry["raw_name"]
+            properties = {"macro": True}
+        else:
+            raw_name = entry["raw_name"]
+            properties = {}
+        raw_name = entry.get("raw_name") or raw_name
+        properties.update(ASTParser._pending_call_site(entry))
+        properties.update({"unresolved": True, "raw_name": raw_name})
+        return CodeRelation(entry["source_id"], UNRESOLVED_PREFIX + raw_name, "CALLS", properties)
+
+    # ------------------------------------------------------------------
+    # Bookkeeping
+    # ------------------------------------------------------------------
+
+    def _install(self, state: FileState) -> None:
+        project = self.projects.project_of(state.file_path) if self.projects is not None else None
+        if project is not None:
+            for node in state.nodes.values():
+                node.properties["project"] = project.name
+        self._span_indexes.pop(state.file_path, None)
+        self.files[state.file_path] = state
+        self.nodes.update(state.nodes)
+        self._retain(state.owned_relations())
+
+    def _uninstall(self, state: FileState) -> None:
+        self._release(state.owned_relations())
+        for node_id in state.nodes:
+            self.nodes.pop(node_id, None)
+        self.files.pop(state.file_path, None)
+        self._span_indexes.pop(state.file_path, None)
+
+    def _retain(self, relations: Iterable[CodeRelation]) -> None:
+        """Count references to unresolved placeholders, creating nodes on first use."""
+        for relation in relations:
+            target = relation.target_id or ""
+            if not target.startswith(UNRESOLVED_PREFIX):
+                continue
+            self._unresolved_refs[target] = self._unresolved_refs.get(target, 0) + 1
+            if target not in self.nodes:
+                name = target[len(UNRESOLVED_PREFIX):]
+                self.nodes[target] = CodeNode(
+                    node_id=target,
+                    node_type=UNRESOLVED_NODE_TYPE,
+                    name=name,
+                    file_path="",
+                    line_no=0,
+                    properties={"raw_name": name},
+                )
+
+    def _release(self, relations: Iterable[CodeRelation]) -> None:
+        for relation in relations:
+            target = relation.target_id or ""
+            if target not in self._unresolved_refs:
+                continue
+            self._unresolved_refs[target] -= 1
+            if self._unresolved_refs[target] <= 0:
+                del self._unresolved_refs[target]
+                self.nodes.pop(target, None)
+
+    def _snapshot(self, paths: Iterable[str]):
+        """
+        Nodes and relations owned by the given files.
+
+        Only the affected files are visited, so the cost of an update is
+        proportional to what those files own rather than to the whole graph.
+        Unresolved placeholders are included when an owned relation targets them.
+        """
+        nodes = {}
+        relations = {}
+        for path in paths:
+            state = self.files.get(path)
+            if state is None:
+                continue
+            for node_id, node in state.nodes.items():
+                nodes[node_id] = self._fingerprint(node)
+            for relation in state.owned_relations():
+                relations[relation_key(relation)] = relation
+                target = relation.target_id or ""
+                if target in self._unresolved_refs and target in self.nodes:
+                    nodes[target] = self._fingerprint(self.nodes[target])
+        return nodes, relations, set(self._unresolved_refs)
+
+    @staticmethod
+    def _fingerprint(node: CodeNode):
+        return (node.node_type, node.name, node.file_path, node.line_no, node.end_line_no,
+                node.code_snippet, repr(sorted(node.properties.items(), key=lambda item: item[0])))
+
+    def _diff(self, before, after, paths: Iterable[str]) -> GraphDelta:
+        before_nodes, before_relations, before_placeholders = before
+        after_nodes, after_relations, _ = after
+        # Placeholders are shared between files, so one can enter or leave the
+        # snapshot while another file keeps it alive
+        added = [n for n in after_nodes if n not in before_nodes and n not in before_placeholders]
+        removed = [n for n in before_nodes if n not in after_nodes and n not in self.nodes]
+        return GraphDelta(
+            added_nodes=sorted(added),
+            removed_nodes=sorted(removed),
+            updated_nodes=sorted(n for n in after_nodes
+                                 if n in before_nodes and after_nodes[n] != before_nodes[n]),
+            added_relations=[r for k, r in after_relations.items() if k not in before_relations],
+            removed_relations=[r for k, r in before_relations.items() if k not in after_relations],
+            files=sorted(paths),
+        )
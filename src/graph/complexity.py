@@ -0,0 +1,50 @@
+"""
+Cyclomatic complexity histogram over the functions of a codebase.
+
+Functions are bucketed with the usual McCabe risk bands: 1-5 simple,
+6-10 moderate, 11-20 complex, 21-50 very complex and above 50 untestable.
+Functions whose parser records no complexity are left out.
+"""
+
+from typing import Any, Dict, Iterable, List, Optional, Tuple
+
+# (label, lowest, highest) per bucket; None means unbounded
+BUCKETS: List[Tuple[str, int, Optional[int]]] = [
+    ("simple", 1, 5),
+    ("moderate", 6, 10),
+    ("complex", 11, 20),
+    ("very_complex", 21, 50),
+    ("untestable", 51, None),
+]
+
+
+def complexity_histogram(symbols: Iterable[Dict[str, Any]], top: int = 10) -> Dict[str, Any]:
+    """
+    Bucket functions by cyclomatic complexity.
+
+    Args:
+        symbols: Node summaries with name, file_path, line_no and complexity
+        top: How many of the most complex functions to list
+
+    Returns:
+        Dict with ``total``, ``mean``, ``max``, ``buckets`` (label, min, max,
+        count) and ``hotspots``, the most complex functions first
+    """
+    measured = [s for s in symbols if s.get("complexity") is not None]
+    counts = [0] * len(BUCKETS)
+    for symbol in measured:
+        for index, (_, low, high) in enumerate(BUCKETS):
+            if symbol["complexity"] >= low and (high is None or symbol["complexity"] <= high):
+                counts[index] += 1
+                break
+
+    values = [s["complexity"] for s in measured]
+    hotspots = sorted(measured, key=lambda s: (-s["complexity"], s.get("file_path") or "", s.get("line_no") or 0))
+    return {
+        "total": len(measured),
+        "mean": round(sum(values) / len(values), 2) if values else None,
+        "max": max(values) if values else None,
+        "buckets": [{"label": label, "min": low, "max": high, "count": count}
+                    for (label, low, high), count in zip(BUCKETS, counts)],
+        "hotspots": hotspots[:top],
+    }
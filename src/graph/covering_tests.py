@@ -0,0 +1,101 @@
+"""
+Tests that exercise a symbol.
+
+A test covers what it calls directly, recorded as a TESTS edge at indexing,
+and what the functions it calls call in turn. Calls are walked backwards
+from the symbols, breadth first, up to a depth limit: every test function
+reached (a node flagged ``is_test``) is reported once, at its shallowest
+depth, with the chain of callers linking it to one of the symbols. The walk
+stops at a test; tests calling each other do not make more tests. Functions
+at the depth limit with callers left unwalked make up the ``frontier``.
+"""
+
+from collections import deque
+from dataclasses import dataclass, field
+from typing import Any, Dict, List
+
+from src.ast_parser.testing import TESTS_RELATION
+from src.graph.code_graph import CodeGraph
+from src.graph.impact import reverse_dependencies
+
+DEFAULT_TESTS_DEPTH = 3
+
+
+@dataclass
+class CoveringTest:
+    """A test function reaching a symbol through calls."""
+    id: str
+    name: str
+    file_path: str
+    line_no: int
+    depth: int
+    # The symbol reached, then the callers between it and the test
+    via: List[str]
+
+    @property
+    def direct(self) -> bool:
+        return self.depth == 1
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"id": self.id, "name": self.name, "file_path": self.file_path, "line_no": self.line_no,
+                "depth": self.depth, "direct": self.direct, "via": self.via}
+
+
+@dataclass
+class CoveringTestsReport:
+    """Tests found for a set of symbols."""
+    targets: List[str]
+    max_depth: int
+    tests: List[CoveringTest] = field(default_factory=list)
+    # Non-test functions at max_depth with callers that were not walked
+    frontier: List[str] = field(default_factory=list)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"targets": self.targets, "max_depth": self.max_depth, "total": len(self.tests),
+                "direct": sum(1 for t in self.tests if t.direct), "tests": [t.to_dict() for t in self.tests],
+                "truncated": bool(self.frontier), "frontier": self.frontier}
+
+
+def find_tests_for(graph: CodeGraph, target_ids: List[str],
+                   max_depth: int = DEFAULT_TESTS_DEPTH) -> CoveringTestsReport:
+    """
+    Tests reaching any of the target symbols in at most ``max_depth`` calls.
+
+    Args:
+        graph: Graph with ``is_test`` loaded on its nodes
+        target_ids: IDs of the symbols to find tests for
+        max_depth: Calls between a test and a symbol, 1 for tests calling it directly
+
+    Returns:
+        The tests ordered by depth, file and line, and the frontier where
+        max_depth stopped the walk
+    """
+    callers = reverse_dependencies(graph, (TESTS_RELATION, "CALLS"))
+    report = CoveringTestsReport(list(target_ids), max_depth)
+    chains: Dict[str, List[str]] = {}
+    queue = deque()
+    stopped = []
+    for target_id in target_ids:
+        if target_id not in chains:
+            chains[target_id] = [target_id]
+            queue.append((target_id, 0))
+    while queue:
+        node_id, depth = queue.popleft()
+        if depth == max_depth:
+            stopped.append(node_id)
+            continue
+        for caller_id, _ in callers.get(node_id, []):
+            if caller_id in chains or caller_id not in graph.nodes:
+                continue
+            chains[caller_id] = chains[node_id] + [caller_id]
+            caller = graph.nodes[caller_id]
+            if caller.properties.get("is_test"):
+                report.tests.append(CoveringTest(caller_id, caller.name, caller.file_path, caller.line_no,
+                                                 depth + 1, chains[node_id]))
+            else:
+                queue.append((caller_id, depth + 1))
+    report.tests.sort(key=lambda t: (t.depth, t.file_path, t.line_no, t.id))
+    report.frontier = [node_id for node_id in stopped
+                       if any(caller_id not in chains and caller_id in graph.nodes
+                              for caller_id, _ in callers.get(node_id, []))]
+    return report
@@ -0,0 +1,310 @@
+"""
+Dependency cycle detection over a CodeGraph.
+
+Cycles are looked for in one of two dependency graphs derived from the code
+graph: ``module`` links each file to the files it imports from, and
+``function`` links functions and methods to the ones they call. Tarjan's
+algorithm finds the strongly connected components; every component with
+more than one member is a cycle, and so, when asked for, is a single
+function calling itself. Within a component a depth-first walk gives the
+cycle order and the back edges, i.e. the edges that close a loop and would
+have to go to break it.
+
+Everything runs over the edge list of the in-memory graph, and the results
+only depend on the node ids, so two runs over the same code list the same
+cycles in the same order. ``cycles_main`` compares them against a baseline
+file of known cycles, so that CI can fail on new ones only.
+"""
+
+import argparse
+import json
+import os
+import sys
+from dataclasses import dataclass, field
+from typing import Dict, Iterable, List, Optional, Set, Tuple
+
+from src.graph.code_graph import CodeGraph, UNRESOLVED_NODE_TYPE
+
+# Relations that make one file depend on another
+IMPORT_RELATIONS = ("IMPORTS_FROM", "IMPORTS_DEFINITION", "IMPORTS", "REEXPORTS", "INCLUDES")
+
+CYCLE_KINDS = ("module", "function")
+
+# (source id, target id, relation type)
+Edge = Tuple[str, str, str]
+
+
+@dataclass
+class Cycle:
+    """One strongly connected component of the dependency graph."""
+    # Members in depth-first order from the smallest id
+    nodes: List[str]
+    # Edges inside the component that point back to a node still on the walk
+    back_edges: List[Edge] = field(default_factory=list)
+    # Shortest loop through the first node, which it starts and ends with
+    path: List[str] = field(default_factory=list)
+
+
+def dependency_edges(graph: CodeGraph, kind: str) -> List[Edge]:
+    """
+    Edges of the module or function dependency graph.
+
+    Args:
+        graph: Graph to derive the dependencies from
+        kind: "module" for file imports, "function" for calls
+
+    Returns:
+        Distinct edges sorted by source and target
+    """
+    if kind not in CYCLE_KINDS:
+        raise ValueError(f"Unknown cycle kind: {kind}")
+
+    edges: Dict[Tuple[str, str], str] = {}
+    if kind == "module":
+        file_ids = {node.file_path: node_id for node_id, node in graph.nodes.items()
+                    if node.node_type == "File"}
+        for relation in graph.relations:
+            if relation.relation_type not in IMPORT_RELATIONS:
+                continue
+            source = graph.nodes.get(relation.source_id)
+            target = graph.nodes.get(relation.target_id)
+            if source is None or target is None:
+                continue
+            source_file = file_ids.get(source.file_path)
+            target_file = file_ids.get(target.file_path)
+            if source_file and target_file:
+                edges.setdefault((source_file, target_file), relation.relation_type)
+    else:
+        for relation in graph.relations:
+            if relation.relation_type != "CALLS":
+                continue
+            target = graph.nodes.get(relation.target_id)
+            if target is None or target.node_type == UNRESOLVED_NODE_TYPE:
+                continue
+            edges.setdefault((relation.source_id, relation.target_id), "CALLS")
+
+    return [(source, target, edges[(source, target)]) for source, target in sorted(edges)]
+
+
+def strongly_connected_components(edges: List[Edge]) -> List[List[str]]:
+    """
+    Tarjan's algorithm, iterative so deep call chains do not hit the recursion limit.
+
+    Returns:
+        Components in the order Tarjan completes them; members are unordered
+    """
+    adjacency: Dict[str, List[str]] = {}
+    for source, target, _ in edges:
+        adjacency.setdefault(source, []).append(target)
+        adjacency.setdefault(target, [])
+
+    index: Dict[str, int] = {}
+    lowlink: Dict[str, int] = {}
+    on_stack = set()
+    stack: List[str] = []
+    components: List[List[str]] = []
+
+    for root in sorted(adjacency):
+        if root in index:
+            continue
+        index[root] = lowlink[root] = len(index)
+        stack.append(root)
+        on_stack.add(root)
+        work = [(root, iter(adjacency[root]))]
+        while work:
+            node, successors = work[-1]
+            for successor in successors:
+                if successor not in index:
+                    index[successor] = lowlink[successor] = len(index)
+                    stack.append(successor)
+                    on_stack.add(successor)
+                    work.append((successor, iter(adjacency[successor])))
+                    break
+                if successor in on_stack:
+                    lowlink[node] = min(lowlink[node], index[successor])
+            else:
+                work.pop()
+                if work:
+                    parent = work[-1][0]
+                    lowlink[parent] = min(lowlink[parent], lowlink[node])
+                if lowlink[node] == index[node]:
+                    component = []
+                    while True:
+                        member = stack.pop()
+                        on_stack.discard(member)
+                        component.append(member)
+                        if member == node:
+                            break
+                    components.append(component)
+    return components
+
+
+def find_cycles(graph: CodeGraph, kind: str = "module", min_size: int = 1,
+                include_self_loops: bool = False) -> List[Cycle]:
+    """
+    Every dependency cycle of the given kind.
+
+    Args:
+        graph: Graph to search
+        kind: "module" for import cycles between files, "function" for call cycles
+        min_size: Drop cycles with fewer members, e.g. 3 for cycles through three or more modules
+        include_self_loops: Report a function calling itself, or a file
+            importing from itself, as a cycle of one
+
+    Returns:
+        One Cycle per strongly connected component with more than one
+        member, or with a self-loop, ordered by their first node
+    """
+    edges = dependency_edges(graph, kind)
+    self_loops = {source for source, target, _ in edges if source == target} if include_self_loops else set()
+    cycles = []
+    for component in strongly_connected_components(edges):
+        if len(component) < max(min_size, 1):
+            continue
+        if len(component) > 1 or component[0] in self_loops:
+            cycles.append(_order_cycle(set(component), edges))
+    return sorted(cycles, key=lambda cycle: cycle.nodes[0])
+
+
+def member_key(graph: CodeGraph, node_id: str, root: Optional[str] = None) -> str:
+    """
+    Name of a cycle member that survives re-indexing: the file path, relative
+    to root when given, followed for a function by its owner and name.
+    """
+    node = graph.nodes[node_id]
+    file_path = os.path.relpath(node.file_path, root) if root and node.file_path else node.file_path
+    if node.node_type == "File":
+        return file_path
+    owner = node.properties.get("method_of") or node.properties.get("class_name")
+    return f"{file_path}:{owner}.{node.name}" if owner else f"{file_path}:{node.name}"
+
+
+def cycle_key(graph: CodeGraph, cycle: Cycle, root: Optional[str] = None) -> Tuple[str, ...]:
+    """The sorted member keys of a cycle, identifying it in a baseline."""
+    return tuple(sorted(member_key(graph, node_id, root) for node_id in cycle.nodes))
+
+
+def new_cycles(graph: CodeGraph, found: Iterable[Cycle], baseline: Iterable[Iterable[str]],
+               root: Optional[str] = None) -> List[Cycle]:
+    """The cycles whose members are not those of a cycle in the baseline."""
+    known = {tuple(sorted(members)) for members in baseline}
+    return [cycle for cycle in found if cycle_key(graph, cycle, root) not in known]
+
+
+def cycles_main(argv: Optional[List[str]] = None) -> int:
+    """Command line entry point: parse a directory and list its dependency cycles."""
+    parser = argparse.ArgumentParser(prog="cycles", description="List import or call cycles of a codebase")
+    parser.add_argument("--codebase-path", required=True, help="Codebase path")
+    parser.add_argument("--kind", choices=CYCLE_KINDS, default="module",
+                        help="module for import cycles between files, function for call cycles (default: module)")
+    parser.add_argument("--min-size", type=int, default=1, metavar="N",
+                        help="Only report cycles with at least N members")
+    parser.add_argument("--include-self-loops", action="store_true",
+                        help="Report functions calling themselves and files importing from themselves "
+                             "as cycles of one")
+    parser.add_argument("--baseline", metavar="PATH",
+                        help="JSON file of known cycles; exit with status 1 only when a cycle is not in it")
+    parser.add_argument("--write-baseline", metavar="PATH", help="Write the cycles found to a baseline file")
+    parser.add_argument("--json", action="store_true", help="Print the cycles as JSON")
+    args = parser.parse_args(argv)
+    if args.min_size < 1:
+        parser.error("--min-size must be at least 1")
+
+    baseline = None
+    if args.baseline:
+        try:
+            with open(args.baseline, encoding="utf-8") as f:
+                baseline = json.load(f)["cycles"]
+        except (OSError, ValueError, KeyError, TypeError) as e:
+            print(f"error: cannot read baseline {args.baseline}: {e}", file=sys.stderr)
+            return 2
+
+    root = os.path.abspath(args.codebase_path)
+    # Same parser selection as indexing, see main.py
+    graph = CodeGraph.from_directory(
+        root,
+        use_ast_grep=os.getenv("USE_AST_GREP", "false").lower() == "true",
+        ast_grep_languages=os.getenv("AST_GREP_LANGUAGES", "python,javascript,typescript").split(","),
+        ast_grep_fallback=os.getenv("AST_GREP_FALLBACK_TO_LEGACY", "true").lower() == "true",
+    )
+    found = find_cycles(graph, args.kind, args.min_size, args.include_self_loops)
+    keys = [list(cycle_key(graph, cycle, root)) for cycle in found]
+
+    if args.write_baseline:
+        with open(args.write_baseline, "w", encoding="utf-8") as f:
+            json.dump({"kind": args.kind, "cycles": keys}, f, ensure_ascii=False, indent=2)
+            f.write("\n")
+    fresh = new_cycles(graph, found, baseline, root) if baseline is not None else found
+
+    if args.json:
+        print(json.dumps([{
+            "members": [member_key(graph, node_id, root) for node_id in cycle.nodes],
+            "path": [member_key(graph, node_id, root) for node_id in cycle.path],
+            "back_edges": [[member_key(graph, source, root), member_key(graph, target, root), relation_type]
+                           for source, target, relation_type in cycle.back_edges],
+            "new": cycle in fresh,
+        } for cycle in found], ensure_ascii=False, indent=2))
+    else:
+        for cycle in found:
+            members = [member_key(graph, node_id, root) for node_id in cycle.nodes]
+            marker = "new " if baseline is not None and cycle in fresh else ""
+            path = " -> ".join(member_key(graph, node_id, root) for node_id in cycle.path)
+            print(f"{marker}cycle of {len(members)}: {', '.join(members)} ({path})")
+        print(f"{len(found)} cycles" + (f", {len(fresh)} not in the baseline" if baseline is not None else ""),
+              file=sys.stderr)
+    return 1 if baseline is not None and fresh else 0
+
+
+def _order_cycle(members: Set[str], edges: List[Edge]) -> Cycle:
+    """Walk a component depth first from its smallest member, collecting back edges."""
+    adjacency: Dict[str, List[Tuple[str, str]]] = {}
+    for source, target, relation_type in edges:
+        if source in members and target in members:
+            adjacency.setdefault(source, []).append((target, relation_type))
+
+    start = min(members)
+    order = [start]
+    visited = {start}
+    on_path = {start}
+    back_edges: List[Edge] = []
+    work = [(start, iter(adjacency.get(start, ())))]
+    while work:
+        node, successors = work[-1]
+        for successor, relation_type in successors:
+            if successor in on_path:
+                back_edges.append((node, successor, relation_type))
+            elif successor not in visited:
+                order.append(successor)
+                visited.add(successor)
+                on_path.add(successor)
+                work.append((successor, iter(adjacency.get(successor, ()))))
+                break
+        else:
+            work.pop()
+            on_path.discard(node)
+    return Cycle(order, back_edges, _shortest_loop(start, adjacency))
+
+
+def _shortest_loop(start: str, adjacency: Dict[str, List[Tuple[str, str]]]) -> List[str]:
+    """Breadth-first search for the shortest walk from start back to itself."""
+    parents: Dict[str, str] = {start: start}
+    frontier = [start]
+    while frontier:
+        following = []
+        for node in frontier:
+            for successor, _ in adjacency.get(node, ()):
+                if successor == start:
+                    loop = []
+                    while node != start:
+                        loop.append(node)
+                        node = parents[node]
+                    return [start] + loop[::-1] + [start]
+                if successor not in parents:
+                    parents[successor] = node
+                    following.append(successor)
+        frontier = following
+    return []
+
+
+if __name__ == "__main__":
+    sys.exit(cycles_main())
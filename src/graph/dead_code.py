@@ -0,0 +1,492 @@
+"""
+Unreferenced symbol (dead code) detection over a CodeGraph.
+
+A function, method or type is a candidate when no other symbol calls,
+references, imports, extends or implements it. Symbols that are reached in
+ways the graph cannot see are exempt: entry points such as ``main``, tests,
+Python dunder methods, trait and interface methods (called through dynamic
+dispatch), functions nested in another function (usually returned rather
+than called by name), symbols carrying a decorator or a non-inert Rust attribute (a
+macro may register or call them), names matched by an allowlist and,
+optionally, everything public.
+
+Each candidate gets a confidence. Private symbols are ``high``; public
+symbols, methods and names that an unresolved call might have meant are
+``low``, since something outside the graph may still use them; the rest are
+``medium``. Results are sorted by confidence, then file and line.
+
+``find_unreachable`` walks the other way, for Rust: starting from entry
+points (``main``, optionally ``#[test]`` functions, ``pub`` items of
+``lib.rs``) it follows calls, references and trait dispatch, and reports
+what it never reaches, even when the unreached code calls itself in a
+closed group.
+
+``find_unused`` is the narrower question: which functions and methods have
+no incoming call or reference at all. It leaves out public symbols unless
+asked, and entry points are recognised by per-language rules that callers
+can replace. Both work on a resolved graph (a CodeGraph built from a
+directory or loaded from storage), so calls from other files count.
+"""
+
+import argparse
+import json
+import os
+import re
+import sys
+from dataclasses import asdict, dataclass, field
+from typing import Dict, Iterable, List, Optional, Pattern, Set, Tuple
+
+from src.ast_parser.language_detector import detect_language
+from src.ast_parser.testing import TEST_ATTRIBUTES
+from src.graph.code_graph import CodeGraph, UNRESOLVED_NODE_TYPE
+
+# Node kinds that can be dead
+DEAD_CODE_KINDS = ("Function", "Method", "Class", "Interface", "Enum", "TypeAlias")
+
+# Relations that count as a use of their target
+REFERENCE_RELATIONS = (
+    "CALLS", "REFERENCES", "EXTENDS", "IMPLEMENTS", "SATISFIES", "DERIVE_USE",
+    "IMPORTS", "IMPORTS_FROM", "IMPORTS_DEFINITION", "IMPORTS_SYMBOL", "IMPORTS_RUST", "REEXPORTS", "INCLUDES",
+    "AR_ASSOCIATION",
+)
+
+# Node properties the detector reads, for loaders that fetch only some
+DEAD_CODE_PROPERTIES = ("visibility", "attributes", "decorators", "annotations", "impl_trait", "method_of",
+                        "export_type", "is_test")
+
+# Relations find_unused counts as a use
+USE_RELATIONS = ("CALLS", "REFERENCES")
+
+CONFIDENCE_LEVELS = ("high", "medium", "low")
+
+ENTRY_POINTS = {"main", "__main__"}
+
+# Definers whose nested functions are local to them (Python closures)
+LOCAL_SCOPES = ("Function", "Method")
+
+# Rust attributes that neither call nor register the item they sit on
+INERT_ATTRIBUTES = {
+    "allow", "warn", "deny", "forbid", "expect", "cfg", "cfg_attr", "derive", "doc", "inline",
+    "cold", "must_use", "deprecated", "non_exhaustive", "repr", "track_caller",
+}
+
+
+@dataclass
+class EntrypointRules:
+    """
+    How one language marks functions that are called from outside the code.
+
+    ``names`` are regular expressions matched against the symbol name,
+    ``attributes`` against each Rust attribute, Python decorator or Java
+    annotation on it (without ``#[``/``@``); ``exported`` treats exported symbols (JavaScript
+    and TypeScript ``export``) as handlers.
+    """
+    names: List[str] = field(default_factory=list)
+    attributes: List[str] = field(default_factory=list)
+    exported: bool = False
+
+    @classmethod
+    def from_dict(cls, rules: Dict[str, object]) -> "EntrypointRules":
+        unknown = set(rules) - {"names", "attributes", "exported"}
+        if unknown:
+            raise ValueError(f"Unknown entrypoint rule: {', '.join(sorted(unknown))}")
+        return cls(list(rules.get("names") or []), list(rules.get("attributes") or []),
+                   bool(rules.get("exported", False)))
+
+
+DEFAULT_ENTRYPOINTS: Dict[str, EntrypointRules] = {
+    "python": EntrypointRules(
+        names=[r"^main$", r"^test_"],
+        attributes=[r"(^|\.)(fixture|route|get|post|put|patch|delete|command|task)\b"],
+    ),
+    "rust": EntrypointRules(names=[r"^main$"], attributes=[r"^(\w+::)*(test|bench|main)\b"]),
+    "go": EntrypointRules(names=[r"^main$", r"^init$", r"^(Test|Benchmark|Example|Fuzz)([A-Z_]|$)"]),
+    "javascript": EntrypointRules(names=[r"^main$"], exported=True),
+    "typescript": EntrypointRules(names=[r"^main$"], exported=True),
+    "java": EntrypointRules(names=[r"^main$"], attributes=[r"^(Test|Before\w*|After\w*)$"]),
+    "cpp": EntrypointRules(names=[r"^main$"]),
+    "c": EntrypointRules(names=[r"^main$"]),
+    "ruby": EntrypointRules(names=[r"^initialize$", r"^test_"]),
+}
+
+
+@dataclass
+class DeadSymbol:
+    """A symbol nothing in the graph uses."""
+    id: str
+    kind: str
+    name: str
+    file_path: str
+    line_no: int
+    confidence: str
+    reason: str
+
+    def to_dict(self) -> Dict[str, object]:
+        return asdict(self)
+
+
+def _attribute_name(attribute: str) -> str:
+    return re.split(r"[(\[=\s]", attribute, 1)[0]
+
+
+def _exemption(node, graph: CodeGraph, parents: Dict[str, str], implementing: Set[str],
+               allowlist: List[Pattern], exempt_public: bool) -> Optional[str]:
+    """Why a symbol is not reported, or None when it is a candidate."""
+    name, properties = node.name, node.properties
+    if name in ENTRY_POINTS:
+        return "entry point"
+    if name.startswith("__") and name.endswith("__"):
+        return "dunder method"
+    attributes = properties.get("attributes") or []
+    # Test functions, and the helpers of test files, as flagged by the parse
+    file_node = graph.nodes.get(f"file:{node.file_path}")
+    if properties.get("is_test") or (file_node is not None and file_node.properties.get("is_test")):
+        return "test"
+    if properties.get("impl_trait") or (node.node_type == "Method" and node.node_id in implementing):
+        return "trait implementation"
+    parent = graph.nodes.get(parents.get(node.node_id, ""))
+    if parent is not None and parent.node_type == "Interface":
+        return "interface method"
+    if parent is not None and parent.node_type in LOCAL_SCOPES:
+        return "local function"
+    if properties.get("decorators"):
+        return "decorated"
+    if any(_attribute_name(a).split("::")[-1] not in INERT_ATTRIBUTES for a in attributes):
+        return "macro attribute"
+    if any(pattern.search(name) for pattern in allowlist):
+        return "allowlist"
+    if exempt_public and properties.get("visibility") == "Public":
+        return "public"
+    return None
+
+
+def find_unreferenced(graph: CodeGraph, allowlist: Iterable[str] = (),
+                      exempt_public: bool = False) -> List[DeadSymbol]:
+    """
+    List the functions, methods and types nothing else in the graph uses.
+
+    Args:
+        graph: Code graph to inspect
+        allowlist: Regular expressions; symbols whose name matches one are exempt
+        exempt_public: Leave out ``Public`` symbols, e.g. for a library whose
+            callers are not indexed
+
+    Returns:
+        Candidates, highest confidence first, then by file and line
+
+    Raises:
+        re.error: An allowlist pattern is not a valid regular expression
+    """
+    patterns = [re.compile(p) for p in allowlist]
+    referenced, implementing, parents, unresolved_names = _scan(graph, REFERENCE_RELATIONS)
+
+    dead = []
+    for node in graph.nodes.values():
+        if node.node_type not in DEAD_CODE_KINDS or node.node_id in referenced:
+            continue
+        if _exemption(node, graph, parents, implementing, patterns, exempt_public):
+            continue
+        dead.append(_rate(node, unresolved_names))
+    return _sorted(dead)
+
+
+def find_unused(graph: CodeGraph, include_public: bool = False,
+                entrypoints: Optional[Dict[str, EntrypointRules]] = None) -> List[DeadSymbol]:
+    """
+    List the functions and methods with no incoming call or reference edge.
+
+    Trait and interface methods and their implementations are left out, as
+    they are reached through dispatch.
+
+    Args:
+        graph: Resolved code graph, so calls from other files are seen
+        include_public: Also list ``Public`` symbols, for finding library
+            code no indexed caller uses
+        entrypoints: Rules per language (``python``, ``rust``, ``go``, ...)
+            replacing those in DEFAULT_ENTRYPOINTS for that language
+
+    Returns:
+        Unused symbols, highest confidence first, then by file and line
+
+    Raises:
+        re.error: A rule is not a valid regular expression
+    """
+    rules = dict(DEFAULT_ENTRYPOINTS, **(entrypoints or {}))
+    compiled = {
+        language: ([re.compile(p) for p in r.names], [re.compile(p) for p in r.attributes], r.exported)
+        for language, r in rules.items()
+    }
+    used, implementing, parents, unresolved_names = _scan(graph, USE_RELATIONS)
+
+    unused = []
+    for node in graph.nodes.values():
+        if node.node_type not in ("Function", "Method") or node.node_id in used:
+            continue
+        if not include_public and node.properties.get("visibility") == "Public":
+            continue
+        # Trait and interface methods, and their implementations, are called through dispatch
+        parent = graph.nodes.get(parents.get(node.node_id, ""))
+        if node.properties.get("impl_trait") or node.node_id in implementing \
+                or (parent is not None and parent.node_type == "Interface"):
+            continue
+        # A closure is handed out by the function defining it rather than called by name
+        if parent is not None and parent.node_type in LOCAL_SCOPES:
+            continue
+        names, attributes, exported = compiled.get(detect_language(node.file_path), ([], [], False))
+        marks = [mark for key in ("attributes", "decorators", "annotations")
+                 for mark in node.properties.get(key) or []]
+        if any(p.search(node.name) for p in names) \
+                or any(p.search(mark) for p in attributes for mark in marks) \
+                or (exported and node.properties.get("export_type")):
+            continue
+        unused.append(_rate(node, unresolved_names))
+    return _sorted(unused)
+
+
+def find_unreachable(graph: CodeGraph, entry_names: Iterable[str] = ("main",), include_tests: bool = False,
+                     public_roots: Iterable[str] = ("lib.rs",), languages: Iterable[str] = ("rust",)) -> List[DeadSymbol]:
+    """
+    List the symbols no entry point reaches.
+
+    The walk follows CALLS, REFERENCES, EXTENDS and IMPLEMENTS edges, goes
+    from a trait or trait method to its implementations, from a method to
+    the type defining it and from a type to its trait methods (``fmt`` of a
+    reachable ``Display`` type). Items carrying a non-inert attribute are
+    entry points too, since a macro may call them.
+
+    Groups of unreached symbols that an unresolved call may have meant (the
+    graph is missing an edge, e.g. a call the parser could not follow) are
+    reported with ``low`` confidence, so a partly parsed codebase does not
+    look mostly dead.
+
+    Args:
+        graph: Resolved code graph
+        entry_names: Names of functions that are entry points
+        include_tests: Count test functions (``#[test]``, ``#[bench]``,
+            ``#[tokio::test]``) as entry points, so code only tests use is
+            alive; test functions themselves are never reported
+        public_roots: File names whose ``Public`` items are entry points,
+            as a library exports them
+        languages: Languages whose symbols are reported
+
+    Returns:
+        Unreachable symbols, highest confidence first, then by file and line
+
+    Raises:
+        ValueError: No entry point was found, so everything would be reported
+    """
+    names, roots, languages = set(entry_names), set(public_roots), set(languages)
+
+    def is_test(node) -> bool:
+        return bool(node.properties.get("is_test"))
+
+    entries = []
+    for node in graph.nodes.values():
+        if node.node_type == UNRESOLVED_NODE_TYPE:
+            continue
+        attributes = node.properties.get("attributes") or []
+        if (node.node_type in ("Function", "Method") and node.name in names) \
+                or (include_tests and is_test(node)) \
+                or (node.properties.get("visibility") == "Public" and os.path.basename(node.file_path) in roots) \
+                or any(_attribute_name(a).split("::")[-1] not in INERT_ATTRIBUTES and not TEST_ATTRIBUTES.match(a)
+                       for a in attributes):
+            entries.append(node.node_id)
+    if not entries:
+        raise ValueError("No entry points found; check entry_names and public_roots")
+
+    successors: Dict[str, Set[str]] = {}
+
+    def link(source: str, target: str) -> None:
+        successors.setdefault(source, set()).add(target)
+
+    for relation in graph.relations:
+        source, target, kind = relation.source_id, relation.target_id, relation.relation_type
+        if kind in ("CALLS", "REFERENCES", "EXTENDS", "IMPLEMENTS", "SATISFIES", "DERIVE_USE"):
+            link(source, target)
+        if kind == "IMPLEMENTS":
+            link(target, source)
+        elif kind == "DEFINES":
+            link(target, source)
+            child, parent = graph.nodes.get(target), graph.nodes.get(source)
+            if child is not None and (child.properties.get("impl_trait")
+                                      or (parent is not None and parent.node_type == "Interface")):
+                link(source, target)
+
+    reached = set(entries)
+    stack = list(entries)
+    while stack:
+        for successor in successors.get(stack.pop(), ()):
+            if successor not in reached:
+                reached.add(successor)
+                stack.append(successor)
+
+    candidates = {
+        node.node_id: node for node in graph.nodes.values()
+        if node.node_type in DEAD_CODE_KINDS and node.node_id not in reached
+        and detect_language(node.file_path) in languages and not is_test(node)
+    }
+
+    # Group the unreached symbols by the edges between them
+    group = {node_id: node_id for node_id in candidates}
+
+    def find(node_id: str) -> str:
+        while group[node_id] != node_id:
+            group[node_id] = group[group[node_id]]
+            node_id = group[node_id]
+        return node_id
+
+    for source, targets in successors.items():
+        for target in targets:
+            if source in candidates and target in candidates:
+                group[find(source)] = find(target)
+
+    _, _, _, unresolved_names = _scan(graph, ("CALLS",))
+    suspect = {find(node_id) for node_id, node in candidates.items() if node.name in unresolved_names}
+
+    dead = []
+    for node_id, node in candidates.items():
+        visibility = node.properties.get("visibility")
+        if find(node_id) in suspect:
+            confidence = "low"
+            reason = "unreachable from the entry points, but an unresolved call may reach its group"
+        elif visibility in ("Private", "Protected"):
+            confidence = "high"
+            reason = "unreachable from the entry points"
+        else:
+            confidence = "medium"
+            reason = "unreachable from the entry points" + ("; public outside the roots" if visibility == "Public" else "")
+        dead.append(DeadSymbol(node_id, node.node_type, node.name, node.file_path, node.line_no, confidence, reason))
+    return _sorted(dead)
+
+
+def _scan(graph: CodeGraph, relation_types: Iterable[str]):
+    """
+    Walk the relations once.
+
+    Returns:
+        Targets of ``relation_types`` edges (self edges aside), sources of
+        any IMPLEMENTS edge, the definer of each node, and the last name
+        segment of calls that resolved to nothing (they may have meant a
+        symbol)
+    """
+    referenced: Set[str] = set()
+    implementing: Set[str] = set()
+    parents: Dict[str, str] = {}
+    unresolved_names: Set[str] = set()
+    declarations: List[Tuple[str, str]] = []
+    for relation in graph.relations:
+        if relation.relation_type == "DEFINES":
+            parents.setdefault(relation.target_id, relation.source_id)
+        elif relation.relation_type == "DECLARED_BY":
+            declarations.append((relation.source_id, relation.target_id))
+        elif relation.relation_type == "IMPLEMENTS":
+            implementing.add(relation.source_id)
+        if relation.relation_type not in relation_types or relation.source_id == relation.target_id:
+            continue
+        referenced.add(relation.target_id)
+        target = graph.nodes.get(relation.target_id)
+        raw_name = relation.properties.get("raw_name")
+        if raw_name and (target is None or target.node_type == UNRESOLVED_NODE_TYPE):
+            unresolved_names.add(re.split(r"\.|::", raw_name)[-1])
+    # A C/C++ definition and the prototypes declaring it are one function: a
+    # call to either counts for all of them
+    for definition, declaration in declarations:
+        if declaration in referenced:
+            referenced.add(definition)
+    for definition, declaration in declarations:
+        if definition in referenced:
+            referenced.add(declaration)
+    return referenced, implementing, parents, unresolved_names
+
+
+def _rate(node, unresolved_names: Set[str]) -> DeadSymbol:
+    """Give a candidate its confidence and reason."""
+    visibility = node.properties.get("visibility")
+    reasons = []
+    if node.name in unresolved_names:
+        reasons.append("a call to this name could not be resolved")
+    if visibility == "Public":
+        reasons.append("public, so code outside the index may use it")
+    if node.node_type == "Method":
+        reasons.append("may be called through dynamic dispatch")
+    if reasons:
+        confidence = "low"
+    elif visibility in ("Private", "Protected"):
+        confidence = "high"
+        reasons.append(f"{visibility.lower()} and never referenced")
+    else:
+        confidence = "medium"
+        reasons.append("never referenced in the indexed code")
+    return DeadSymbol(node.node_id, node.node_type, node.name, node.file_path,
+                      node.line_no, confidence, "; ".join(reasons))
+
+
+def _sorted(dead: List[DeadSymbol]) -> List[DeadSymbol]:
+    return sorted(dead, key=lambda d: (CONFIDENCE_LEVELS.index(d.confidence), d.file_path, d.line_no, d.name))
+
+
+def at_least(dead: List[DeadSymbol], min_confidence: str) -> List[DeadSymbol]:
+    """Keep the candidates at or above a confidence level."""
+    if min_confidence not in CONFIDENCE_LEVELS:
+        raise ValueError(f"Unknown confidence: {min_confidence} (expected one of {', '.join(CONFIDENCE_LEVELS)})")
+    limit = CONFIDENCE_LEVELS.index(min_confidence)
+    return [d for d in dead if CONFIDENCE_LEVELS.index(d.confidence) <= limit]
+
+
+def dead_code_main(argv: Optional[List[str]] = None) -> int:
+    """Command line entry point: parse a directory and list its unreferenced symbols."""
+    parser = argparse.ArgumentParser(prog="dead-code", description="List functions, methods and types nothing uses")
+    parser.add_argument("--codebase-path", required=True, help="Codebase path")
+    parser.add_argument("--allow", action="append", default=[], metavar="REGEX",
+                        help="Exempt symbols whose name matches, e.g. '^handle_' (repeatable)")
+    parser.add_argument("--exempt-public", action="store_true",
+                        help="Do not report public symbols (for libraries whose callers are not indexed)")
+    parser.add_argument("--min-confidence", choices=CONFIDENCE_LEVELS, default="low",
+                        help="Only report candidates at or above this confidence (default: low)")
+    parser.add_argument("--from-entry-points", action="store_true",
+                        help="Report Rust symbols unreachable from the entry points instead of unreferenced ones")
+    parser.add_argument("--entry", action="append", metavar="NAME",
+                        help="Entry point function name with --from-entry-points (repeatable, default: main)")
+    parser.add_argument("--public-root", action="append", metavar="FILE",
+                        help="File name whose pub items are entry points (repeatable, default: lib.rs)")
+    parser.add_argument("--include-tests", action="store_true",
+                        help="Count #[test] functions as entry points with --from-entry-points")
+    parser.add_argument("--json", action="store_true", help="Print the candidates as JSON")
+    parser.add_argument("--fail-on-dead-code", action="store_true",
+                        help="Exit with status 1 when any candidate is reported")
+    args = parser.parse_args(argv)
+
+    try:
+        patterns = [re.compile(p) for p in args.allow]
+    except re.error as e:
+        parser.error(f"invalid --allow pattern: {e}")
+
+    # Same parser selection as indexing, see main.py
+    graph = CodeGraph.from_directory(
+        os.path.abspath(args.codebase_path),
+        use_ast_grep=os.getenv("USE_AST_GREP", "false").lower() == "true",
+        ast_grep_languages=os.getenv("AST_GREP_LANGUAGES", "python,javascript,typescript").split(","),
+        ast_grep_fallback=os.getenv("AST_GREP_FALLBACK_TO_LEGACY", "true").lower() == "true",
+    )
+    if args.from_entry_points:
+        try:
+            dead = find_unreachable(graph, args.entry or ["main"], args.include_tests, args.public_root or ["lib.rs"])
+        except ValueError as e:
+            print(f"error: {e}", file=sys.stderr)
+            return 2
+        dead = [d for d in dead if not any(p.search(d.name) for p in patterns)]
+    else:
+        dead = find_unreferenced(graph, [p.pattern for p in patterns], args.exempt_public)
+    dead = at_least(dead, args.min_confidence)
+
+    if args.json:
+        print(json.dumps([d.to_dict() for d in dead], ensure_ascii=False, indent=2))
+    else:
+        for d in dead:
+            print(f"{d.file_path}:{d.line_no}: {d.kind.lower()} {d.name} [{d.confidence}] {d.reason}")
+        print(f"{len(dead)} unreferenced symbols", file=sys.stderr)
+    return 1 if dead and args.fail_on_dead_code else 0
+
+
+if __name__ == "__main__":
+    sys.exit(dead_code_main())
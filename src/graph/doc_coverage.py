@@ -0,0 +1,54 @@
+"""
+Documentation coverage of the public API.
+
+The parsers flag each public function, method and type with ``documented``:
+whether it has a doc comment (``///``, ``/** */``) or a docstring. Symbols
+without the flag are left out of the coverage: private ones, trait impl
+methods (documented by the trait), and those of languages whose doc
+comments are not read (Go and C).
+"""
+
+from typing import Any, Collection, Dict, Iterable, List, Optional
+
+
+def is_counted(symbol: Dict[str, Any]) -> bool:
+    """Whether a symbol counts toward the coverage."""
+    return symbol.get("documented") is not None
+
+
+def coverage_ratio(documented: int, counted: int) -> Optional[float]:
+    """Share of the counted symbols that are documented, or None when none is counted."""
+    return round(documented / counted, 4) if counted else None
+
+
+def undocumented_symbols(symbols: Iterable[Dict[str, Any]],
+                         paths: Optional[Collection[str]] = None) -> Dict[str, Any]:
+    """
+    The public symbols without a doc, and the coverage over all public ones.
+
+    Args:
+        symbols: Symbol summaries with id, name, type, file_path, line_no
+            and documented
+        paths: Files to report; None reports every file
+
+    Returns:
+        Dict with ``symbols`` (id, name, type, file_path, line_no) sorted by
+        file and line, ``documented`` and ``total`` counts of the public
+        symbols and their ``doc_coverage_ratio``
+    """
+    wanted = None if paths is None else set(paths)
+    counted = [symbol for symbol in symbols
+               if is_counted(symbol) and (wanted is None or symbol.get("file_path") in wanted)]
+
+    missing: List[Dict[str, Any]] = [
+        {key: symbol.get(key) for key in ("id", "name", "type", "file_path", "line_no")}
+        for symbol in counted if not symbol["documented"]
+    ]
+    missing.sort(key=lambda s: (s["file_path"] or "", s["line_no"] or 0, s["name"]))
+    documented = len(counted) - len(missing)
+    return {
+        "symbols": missing,
+        "documented": documented,
+        "total": len(counted),
+        "doc_coverage_ratio": coverage_ratio(documented, len(counted)),
+    }
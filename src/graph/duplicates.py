@@ -0,0 +1,242 @@
+"""
+Near-identical function bodies, for finding copy-pasted code.
+
+Each function's source is reduced to a normalized token stream: comments
+and whitespace are dropped, every identifier that is not a keyword becomes
+``ID``, string literals ``STR`` and numbers ``NUM``, while keywords,
+operators and brackets are kept. Two functions that differ only in names,
+literals, comments or layout therefore have the same stream, and the hash
+of the stream stands in for a hash of the shape of their syntax trees.
+
+``exact`` mode groups functions whose streams hash the same. ``fuzzy``
+mode also links two functions whose streams are within a Levenshtein
+distance over tokens of each other, so that ``1 - distance / longer
+length`` is at least the similarity threshold; distances are only computed
+up to ``max_distance`` edits, and pairs whose lengths alone rule them out
+are never compared. Linked functions form one group.
+"""
+
+import hashlib
+import re
+from dataclasses import dataclass
+from typing import Any, Callable, Dict, Iterable, List, Optional, Tuple
+
+from src.ast_parser.language_detector import detect_language
+
+DUPLICATE_MODES = ("exact", "fuzzy")
+
+DEFAULT_SIMILARITY = 0.9
+
+# Bodies shorter than this many tokens (getters, one-line wrappers) are not compared
+DEFAULT_MIN_TOKENS = 30
+
+# Most token edits computed between two bodies in fuzzy mode
+DEFAULT_MAX_DISTANCE = 50
+
+# Keywords of the supported languages; kept in the stream as written
+KEYWORDS = frozenset("""
+    and as assert async await break case catch class const continue def default defer del do elif else
+    enum except extends final finally fn for func function go goto if impl implements import in instanceof
+    interface is lambda let loop match mod move mut new nonlocal not or pass raise return select self static
+    struct super switch this throw throws try type typeof unsafe var where while with yield
+    None True False null nil true false undefined
+""".split())
+
+_STRING = (r"[rbfuRBFU]{0,2}(?:'\'\'[\s\S]*?'\'\'|\"\"\"[\s\S]*?\"\"\""
+           r"|\"(?:\\.|[^\"\\\n])*\"|'(?:\\.|[^'\\\n])*'|`(?:\\.|[^`\\])*`)")
+_COMMENTS = {"python": r"#[^\n]*"}
+_C_COMMENTS = r"//[^\n]*|/\*[\s\S]*?\*/"
+
+
+def _token_pattern(comment: str) -> "re.Pattern[str]":
+    # Strings come first, so a comment marker inside one is part of the string
+    return re.compile(rf"(?P<string>{_STRING})|(?P<comment>{comment})|(?P<number>\d[\w.]*)"
+                      rf"|(?P<word>[A-Za-z_$][\w$]*)|(?P<operator>[-+*/%=<>!&|^~?:]+|[^\s\w])")
+
+
+_TOKEN_PATTERNS: Dict[str, "re.Pattern[str]"] = {}
+
+
+def normalized_tokens(source: str, language: Optional[str] = None) -> List[str]:
+    """Token stream of a function's source with names, literals, comments and layout normalized away."""
+    comment = _COMMENTS.get(language, _C_COMMENTS)
+    pattern = _TOKEN_PATTERNS.get(comment)
+    if pattern is None:
+        pattern = _TOKEN_PATTERNS[comment] = _token_pattern(comment)
+    tokens = []
+    for match in pattern.finditer(source):
+        kind = match.lastgroup
+        if kind == "comment":
+            continue
+        if kind == "string":
+            tokens.append("STR")
+        elif kind == "number":
+            tokens.append("NUM")
+        elif kind == "word":
+            tokens.append(match.group() if match.group() in KEYWORDS else "ID")
+        else:
+            tokens.append(match.group())
+    return tokens
+
+
+def token_hash(tokens: List[str]) -> str:
+    return hashlib.sha256(" ".join(tokens).encode("utf-8")).hexdigest()[:16]
+
+
+def bounded_distance(a: List[str], b: List[str], limit: int) -> Optional[int]:
+    """
+    Levenshtein distance between two token lists, or None when it exceeds
+    limit. Only the diagonal band of width ``2 * limit + 1`` is computed.
+    """
+    if abs(len(a) - len(b)) > limit:
+        return None
+    if len(a) > len(b):
+        a, b = b, a
+    infinity = limit + 1
+    previous = [j if j <= limit else infinity for j in range(len(b) + 1)]
+    for i in range(1, len(a) + 1):
+        low, high = max(1, i - limit), min(len(b), i + limit)
+        current = [infinity] * (len(b) + 1)
+        current[0] = i if i <= limit else infinity
+        best = current[0]
+        for j in range(low, high + 1):
+            cost = 0 if a[i - 1] == b[j - 1] else 1
+            value = min(previous[j] + 1, current[j - 1] + 1, previous[j - 1] + cost)
+            current[j] = value if value <= limit else infinity
+            best = min(best, current[j])
+        if best > limit:
+            return None
+        previous = current
+    return previous[len(b)] if previous[len(b)] <= limit else None
+
+
+@dataclass
+class FunctionBody:
+    """A function or method and the normalized tokens of its source."""
+    id: str
+    name: str
+    file_path: str
+    line_no: int
+    tokens: List[str]
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"id": self.id, "file": self.file_path, "function_name": self.name, "line": self.line_no,
+                "tokens": len(self.tokens)}
+
+
+@dataclass
+class DuplicateGroup:
+    """Functions with the same, or in fuzzy mode similar, normalized bodies."""
+    members: List[FunctionBody]
+    # Lowest similarity of the links holding the group together; 1.0 for identical bodies
+    similarity: float = 1.0
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"size": len(self.members), "similarity": round(self.similarity, 3),
+                "members": [m.to_dict() for m in self.members]}
+
+
+def function_bodies(nodes: Iterable[Any], read_text: Callable[[str], Optional[str]]) -> List[FunctionBody]:
+    """
+    Normalized bodies of functions and methods, read from their files.
+
+    Args:
+        nodes: Function and Method CodeNodes with a line span
+        read_text: Text of a file by path, None when it cannot be read;
+            each file is read once
+
+    Returns:
+        One body per node whose file could be read
+    """
+    by_file: Dict[str, List[Any]] = {}
+    for node in nodes:
+        if node.file_path and node.line_no and node.end_line_no:
+            by_file.setdefault(node.file_path, []).append(node)
+    bodies = []
+    for file_path in sorted(by_file):
+        text = read_text(file_path)
+        if text is None:
+            continue
+        lines = text.splitlines()
+        language = detect_language(file_path)
+        for node in by_file[file_path]:
+            source = "\n".join(lines[node.line_no - 1:node.end_line_no])
+            bodies.append(FunctionBody(node.node_id, node.name, file_path, node.line_no,
+                                       normalized_tokens(source, language)))
+    return bodies
+
+
+def find_duplicates(bodies: Iterable[FunctionBody], mode: str = "exact",
+                    threshold: float = DEFAULT_SIMILARITY, min_tokens: int = DEFAULT_MIN_TOKENS,
+                    max_distance: int = DEFAULT_MAX_DISTANCE) -> List[DuplicateGroup]:
+    """
+    Group functions with duplicate bodies.
+
+    Args:
+        bodies: Functions with their normalized tokens
+        mode: "exact" for equal hashes, "fuzzy" to also link similar bodies
+        threshold: Least similarity (0-1) of two linked bodies in fuzzy mode
+        min_tokens: Shorter bodies are left out
+        max_distance: Most token edits computed between two bodies in fuzzy mode
+
+    Returns:
+        Groups of two or more functions, largest first, members by file and line
+
+    Raises:
+        ValueError: Unknown mode or a threshold outside 0-1
+    """
+    if mode not in DUPLICATE_MODES:
+        raise ValueError(f"Unknown mode: {mode} (expected one of {', '.join(DUPLICATE_MODES)})")
+    if not 0.0 <= threshold <= 1.0:
+        raise ValueError(f"Similarity threshold must be between 0 and 1, not {threshold}")
+
+    by_hash: Dict[str, List[FunctionBody]] = {}
+    for body in bodies:
+        if len(body.tokens) >= min_tokens:
+            by_hash.setdefault(token_hash(body.tokens), []).append(body)
+
+    # Union-find over distinct bodies, keyed by hash
+    parent = {digest: digest for digest in by_hash}
+    similarity = {digest: 1.0 for digest in by_hash}
+
+    def root(digest: str) -> str:
+        while parent[digest] != digest:
+            parent[digest] = parent[parent[digest]]
+            digest = parent[digest]
+        return digest
+
+    if mode == "fuzzy":
+        for a, b, score in _similar_pairs(by_hash, threshold, max_distance):
+            ra, rb = root(a), root(b)
+            low = min(similarity[ra], similarity[rb], score)
+            if ra != rb:
+                parent[rb] = ra
+            similarity[ra] = low
+
+    grouped: Dict[str, List[str]] = {}
+    for digest in by_hash:
+        grouped.setdefault(root(digest), []).append(digest)
+    groups = []
+    for top, digests in grouped.items():
+        members = [body for digest in digests for body in by_hash[digest]]
+        if len(members) < 2:
+            continue
+        members.sort(key=lambda m: (m.file_path, m.line_no, m.id))
+        groups.append(DuplicateGroup(members, similarity[top]))
+    groups.sort(key=lambda g: (-len(g.members), -g.similarity, g.members[0].file_path, g.members[0].line_no))
+    return groups
+
+
+def _similar_pairs(by_hash: Dict[str, List[FunctionBody]], threshold: float,
+                   max_distance: int) -> Iterable[Tuple[str, str, float]]:
+    """(hash, hash, similarity) of every pair of distinct bodies similar enough, shortest bodies first."""
+    streams = sorted(((len(bodies[0].tokens), digest, bodies[0].tokens) for digest, bodies in by_hash.items()))
+    for i, (short_length, short_digest, short_tokens) in enumerate(streams):
+        for long_length, long_digest, long_tokens in streams[i + 1:]:
+            # The length difference alone is a lower bound on the distance
+            if short_length < threshold * long_length or long_length - short_length > max_distance:
+                break
+            limit = min(max_distance, int((1 - threshold) * long_length))
+            distance = bounded_distance(short_tokens, long_tokens, limit)
+            if distance is not None:
+                yield short_digest, long_digest, 1 - distance / long_length
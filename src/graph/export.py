@@ -0,0 +1,586 @@
+"""
+Exporters that render a CodeGraph in formats other tools understand.
+
+Each exporter implements GraphExporter. DotExporter writes Graphviz DOT, so
+a module-sized subgraph can be viewed with ``dot -Tsvg``. It also draws a
+ModuleGraph, one node per file with an edge weighted by the number of
+references between the two files. GraphMlExporter writes GraphML, the
+interchange format read by yEd, Gephi and NetworkX, element by element to
+its stream rather than building the document in memory. JsonLinesExporter
+writes one node or edge per line with all its properties, for pandas,
+NetworkX or jq, also as it goes. JsonExporter writes the snapshot document
+of CodeGraph.to_json, which CodeGraph.from_json reads back, so two exports
+can be compared with CodeGraph.diff.
+
+Run as ``python src/main.py export`` (or ``python -m src.graph.export``) to
+write the DOT for a directory without a database:
+
+    python src/main.py export --codebase-path . --kinds function,struct \
+        --path src/parser --edges CALLS,IMPORTS --max-nodes 500 -o parser.dot
+
+Add ``--modules`` to export the file-level graph instead, and
+``--format graphml``, ``--format jsonl`` or ``--format json`` to write
+GraphML, JSON Lines or a snapshot.
+"""
+
+import argparse
+import fnmatch
+import io
+import json
+import os
+import re
+import sys
+from abc import ABC, abstractmethod
+from dataclasses import dataclass
+from typing import Any, Dict, Iterable, List, Optional, TextIO, Tuple, Union
+from xml.sax.saxutils import XMLGenerator
+
+from src.ast_parser.parser import CodeNode, CodeRelation
+from src.graph.code_graph import CodeGraph, edge_record, node_record
+from src.graph.module_graph import ModuleEdge, ModuleGraph
+from src.graph.paths import path_under
+
+# Node shape per node type; anything else is drawn as an ellipse
+DOT_SHAPES = {
+    "File": "folder",
+    "Class": "box",
+    "Interface": "component",
+    "Function": "ellipse",
+    "Method": "ellipse",
+    "Field": "plaintext",
+    "Variable": "note",
+    "ClassVariable": "note",
+    "LocalVariable": "note",
+    "GlobalVariable": "note",
+    "Unresolved": "octagon",
+}
+
+# Edge attributes per relation type; anything else is drawn with the defaults
+DOT_EDGE_STYLES = {
+    "CONTAINS": 'color="gray60", arrowhead=none',
+    "DEFINES": 'color="gray40", arrowhead=diamond',
+    "CALLS": 'color="blue"',
+    "IMPORTS": 'color="darkgreen", style=dashed',
+    "IMPORTS_FROM": 'color="darkgreen", style=dashed',
+    "IMPORTS_DEFINITION": 'color="darkgreen", style=dashed',
+    "INCLUDES": 'color="darkgreen", style=dashed',
+    "REEXPORTS": 'color="darkgreen", style=dotted',
+    "EXTENDS": 'color="red", arrowhead=empty',
+    "IMPLEMENTS": 'color="red", style=dashed, arrowhead=empty',
+    "SATISFIES": 'color="red", style=dotted, arrowhead=empty',
+    "USES_TYPE": 'color="purple", style=dotted',
+    "REFERENCES": 'color="orange", style=dotted',
+    "AR_ASSOCIATION": 'color="brown", style=dashed, arrowhead=odiamond',
+}
+
+# Widest edge drawn for the heaviest file dependency of a ModuleGraph
+MAX_PENWIDTH = 5.0
+
+GRAPHML_NAMESPACE = "http://graphml.graphdrawing.org/xmlns"
+
+# GraphML attribute keys: (id, element, attr.name, attr.type)
+GRAPHML_KEYS = (
+    ("node_kind", "node", "kind", "string"),
+    ("node_name", "node", "name", "string"),
+    ("node_file", "node", "file", "string"),
+    ("node_line", "node", "line", "int"),
+    ("edge_kind", "edge", "kind", "string"),
+    ("edge_call_site", "edge", "call_site", "string"),
+    ("edge_weight", "edge", "weight", "int"),
+)
+
+# Property names that would shadow the attributes above; such properties are left out
+GRAPHML_RESERVED = {"node": {"kind", "name", "file", "line"}, "edge": {"kind", "weight"}}
+
+# GraphML attr.type of a property value by Python type, bool ahead of int
+# as it is a subclass; other values are written as strings, lists and dicts as JSON
+GRAPHML_TYPES = ((bool, "boolean"), (int, "long"), (float, "double"))
+
+# Characters XML 1.0 does not allow anywhere in a document, even escaped
+XML_INVALID_CHARS = re.compile("[\x00-\x08\x0b\x0c\x0e-\x1f\ud800-\udfff\ufffe\uffff]")
+
+# Lower-case kind names accepted by --kinds, mapped to node types; a node
+# type can also be given as is, e.g. "GlobalVariable"
+NODE_KINDS = {
+    "file": ["File"],
+    "module": ["Module"],
+    "package": ["Package"],
+    "class": ["Class"],
+    "struct": ["Class"],
+    "interface": ["Interface"],
+    "trait": ["Interface"],
+    "enum": ["Enum"],
+    "type": ["TypeAlias"],
+    "macro": ["Macro"],
+    "function": ["Function"],
+    "method": ["Method"],
+    "field": ["Field"],
+    "variable": ["Variable", "ClassVariable", "LocalVariable", "GlobalVariable"],
+}
+
+
+class ExportTooLargeError(ValueError):
+    """The selected subgraph has more nodes than the export allows."""
+
+
+@dataclass
+class DotOptions:
+    """What part of the graph to export and how to draw it."""
+    # fnmatch pattern matched against node file paths, e.g. "src/graph/*"
+    file_glob: Optional[str] = None
+    # Node types to keep, e.g. ["Class", "Function"]; None keeps every type
+    node_types: Optional[List[str]] = None
+    # Keep only nodes in this file or directory, e.g. "/repo/src/parser"
+    path_prefix: Optional[str] = None
+    # Relation types to keep, e.g. ["CALLS", "IMPORTS"]; None keeps every type
+    edge_types: Optional[List[str]] = None
+    # Refuse to export more nodes than this; None means no limit
+    max_nodes: Optional[int] = None
+    # Draw the nodes of each file inside a labelled cluster
+    cluster_by_file: bool = True
+    # Label each edge with its relation type
+    edge_labels: bool = True
+    graph_name: str = "codebase"
+
+
+class GraphExporter(ABC):
+    """Renders a CodeGraph or ModuleGraph as text."""
+
+    @abstractmethod
+    def write(self, graph: Union[CodeGraph, ModuleGraph], stream: TextIO, opts: Optional[DotOptions] = None) -> None:
+        """
+        Write the graph, or the subgraph selected by opts, to a text stream.
+
+        Raises:
+            ExportTooLargeError: More nodes are selected than opts.max_nodes;
+                raised before anything is written
+        """
+
+    def render(self, graph: Union[CodeGraph, ModuleGraph], opts: Optional[DotOptions] = None) -> str:
+        """The exported graph as one string."""
+        buffer = io.StringIO()
+        self.write(graph, buffer, opts)
+        return buffer.getvalue()
+
+
+def dot_quote(value: str) -> str:
+    """Quote a string as a DOT ID."""
+    escaped = value.replace("\\", "\\\\").replace('"', '\\"').replace("\n", "\\n").replace("\r", "")
+    return f'"{escaped}"'
+
+
+class DotExporter(GraphExporter):
+    """Graphviz DOT exporter."""
+
+    def write(self, graph: Union[CodeGraph, ModuleGraph], stream: TextIO, opts: Optional[DotOptions] = None) -> None:
+        stream.write(self.to_dot(graph, opts))
+
+    def to_dot(self, graph: Union[CodeGraph, ModuleGraph], opts: Optional[DotOptions] = None) -> str:
+        """
+        Render the graph as a directed DOT graph.
+
+        Only nodes passing the filters in opts are written, and only edges
+        of the selected types whose two endpoints were both written. For a
+        ModuleGraph the nodes are files, clustered by workspace member,
+        node_types and cluster_by_file do not apply, and each edge is
+        labelled with its weight.
+
+        Args:
+            graph: Graph to export
+            opts: Filters and drawing options; defaults export everything
+
+        Returns:
+            DOT source
+
+        Raises:
+            ExportTooLargeError: More nodes are selected than opts.max_nodes
+        """
+        opts = opts or DotOptions()
+        if isinstance(graph, ModuleGraph):
+            return self._module_dot(graph, opts)
+        nodes, relations = select_subgraph(graph, opts)
+
+        lines = [f"digraph {dot_quote(opts.graph_name)} {{",
+                 "  node [fontname=\"Helvetica\", fontsize=10];",
+                 "  edge [fontname=\"Helvetica\", fontsize=8];"]
+
+        if opts.cluster_by_file:
+            by_file: Dict[str, List[CodeNode]] = {}
+            for node in nodes.values():
+                by_file.setdefault(node.file_path, []).append(node)
+            for index, file_path in enumerate(sorted(by_file)):
+                if not file_path:
+                    # Unresolved placeholders belong to no file
+                    lines.extend(f"  {self._node_statement(n)}" for n in by_file[file_path])
+                    continue
+                lines.append(f"  subgraph {dot_quote(f'cluster_{index}')} {{")
+                lines.append(f"    label={dot_quote(file_path)};")
+                lines.extend(f"    {self._node_statement(n)}" for n in by_file[file_path])
+                lines.append("  }")
+        else:
+            lines.extend(f"  {self._node_statement(n)}" for n in nodes.values())
+
+        lines.extend(f"  {self._edge_statement(r, opts)}" for r in relations)
+        lines.append("}")
+        return "\n".join(lines) + "\n"
+
+    def _module_dot(self, graph: ModuleGraph, opts: DotOptions) -> str:
+        files, edges = select_modules(graph, opts)
+        heaviest = max((edge.weight for edge in edges), default=1)
+
+        lines = [f"digraph {dot_quote(opts.graph_name)} {{",
+                 "  node [fontname=\"Helvetica\", fontsize=10, shape=folder];",
+                 "  edge [fontname=\"Helvetica\", fontsize=8];"]
+        member_of = {file_path: member for member, paths in graph.members.items() for file_path in paths}
+        lines.extend(f"  {dot_quote(file_path)};" for file_path in files if file_path not in member_of)
+        for index, member in enumerate(graph.members):
+            # Workspace members are the top-level clusters
+            selected = [file_path for file_path in files if member_of.get(file_path) == member]
+            if not selected:
+                continue
+            lines.append(f"  subgraph {dot_quote(f'cluster_{index}')} {{")
+            lines.append(f"    label={dot_quote(member)};")
+            lines.extend(f"    {dot_quote(file_path)};" for file_path in selected)
+            lines.append("  }")
+        for edge in edges:
+            # Scale the width linearly so the most coupled pairs stand out
+            attributes = [f"weight={edge.weight}",
+                          f"penwidth={1 + (MAX_PENWIDTH - 1) * edge.weight / heaviest:.2f}"]
+            if opts.edge_labels:
+                attributes.insert(0, f"label={dot_quote(str(edge.weight))}")
+            lines.append(f"  {dot_quote(edge.source)} -> {dot_quote(edge.target)} [{', '.join(attributes)}];")
+        lines.append("}")
+        return "\n".join(lines) + "\n"
+
+    @staticmethod
+    def _node_statement(node: CodeNode) -> str:
+        shape = DOT_SHAPES.get(node.node_type, "ellipse")
+        label = node.name if node.node_type == "File" else f"{node.name}\n{node.node_type}"
+        return f"{dot_quote(node.node_id)} [label={dot_quote(label)}, shape={shape}];"
+
+    @staticmethod
+    def _edge_statement(relation: CodeRelation, opts: DotOptions) -> str:
+        statement = f"{dot_quote(relation.source_id)} -> {dot_quote(relation.target_id)}"
+        attributes = []
+        if opts.edge_labels:
+            attributes.append(f"label={dot_quote(relation.relation_type)}")
+        if relation.relation_type in DOT_EDGE_STYLES:
+            attributes.append(DOT_EDGE_STYLES[relation.relation_type])
+        if attributes:
+            statement += f" [{', '.join(attributes)}]"
+        return statement + ";"
+
+
+class GraphMlExporter(GraphExporter):
+    """
+    GraphML exporter.
+
+    Nodes carry their ``kind`` (node type), ``name``, ``file`` and ``line``,
+    edges their ``kind`` (relation type) and the ``call_site`` of a call.
+    Every other property of a selected node or edge gets a key of its own,
+    typed from its values: ``boolean``, ``long`` or ``double`` when all of
+    them are, ``string`` otherwise, with lists and dicts written as JSON.
+    Attributes a node or edge does not have are left out. A ModuleGraph is
+    written with File nodes and edges of kind ``DEPENDS_ON`` carrying their
+    ``weight``.
+    """
+
+    def write(self, graph: Union[CodeGraph, ModuleGraph], stream: TextIO, opts: Optional[DotOptions] = None) -> None:
+        opts = opts or DotOptions()
+        keys = GRAPHML_KEYS
+        if isinstance(graph, ModuleGraph):
+            files, module_edges = select_modules(graph, opts)
+            nodes = ((path, {"node_kind": "File", "node_name": os.path.basename(path), "node_file": path})
+                     for path in files)
+            edges = ((e.source, e.target, {"edge_kind": "DEPENDS_ON", "edge_weight": e.weight})
+                     for e in module_edges)
+        else:
+            selected, relations = select_subgraph(graph, opts)
+            keys += _property_keys("node", (node.properties for node in selected.values()), keys)
+            keys += _property_keys("edge", (r.properties for r in relations), keys)
+            nodes = ((node_id, {"node_kind": node.node_type, "node_name": node.name,
+                                "node_file": node.file_path, "node_line": node.line_no,
+                                **_property_data("node", node.properties)})
+                     for node_id, node in selected.items())
+            edges = ((r.source_id, r.target_id, {"edge_kind": r.relation_type,
+                                                 **_property_data("edge", r.properties)})
+                     for r in relations)
+
+        xml = XMLGenerator(stream, encoding="utf-8", short_empty_elements=True)
+        xml.startDocument()
+        xml.startElement("graphml", {"xmlns": GRAPHML_NAMESPACE})
+        for key_id, element, name, value_type in keys:
+            xml.ignorableWhitespace("\n  ")
+            xml.startElement("key", {"id": key_id, "for": element, "attr.name": name, "attr.type": value_type})
+            xml.endElement("key")
+        xml.ignorableWhitespace("\n  ")
+        xml.startElement("graph", {"id": _xml_text(opts.graph_name), "edgedefault": "directed"})
+        for node_id, data in nodes:
+            self._element(xml, "node", {"id": _xml_text(node_id)}, data)
+        for index, (source, target, data) in enumerate(edges):
+            self._element(xml, "edge", {"id": f"e{index}", "source": _xml_text(source),
+                                        "target": _xml_text(target)}, data)
+        xml.ignorableWhitespace("\n  ")
+        xml.endElement("graph")
+        xml.ignorableWhitespace("\n")
+        xml.endElement("graphml")
+        xml.ignorableWhitespace("\n")
+        xml.endDocument()
+
+    @staticmethod
+    def _element(xml: XMLGenerator, name: str, attributes: Dict[str, str], data: Dict[str, object]) -> None:
+        """One node or edge on its own line, with a <data> child per attribute it has."""
+        xml.ignorableWhitespace("\n    ")
+        xml.startElement(name, attributes)
+        for key_id, value in data.items():
+            if value is None or value == "":
+                continue
+            xml.startElement("data", {"key": key_id})
+            xml.characters(_xml_text(_graphml_value(value)))
+            xml.endElement("data")
+        xml.endElement(name)
+
+
+class JsonLinesExporter(GraphExporter):
+    """
+    JSON Lines exporter: one object per line, the selected nodes and then
+    the edges between them, written as they are serialized. Records have
+    the shape of those in a CodeGraph.to_json snapshot, all properties
+    included, plus a ``type`` of ``node`` or ``edge``. A ModuleGraph is
+    written as File nodes and ``DEPENDS_ON`` edges whose properties are the
+    ``weight`` and the count of each relation type.
+    """
+
+    def write(self, graph: Union[CodeGraph, ModuleGraph], stream: TextIO, opts: Optional[DotOptions] = None) -> None:
+        opts = opts or DotOptions()
+        if isinstance(graph, ModuleGraph):
+            files, module_edges = select_modules(graph, opts)
+            nodes = ({"id": path, "kind": "File", "name": os.path.basename(path), "file": path} for path in files)
+            edges = ({"source": e.source, "target": e.target, "kind": "DEPENDS_ON",
+                      "properties": {"weight": e.weight, "relations": e.relations}} for e in module_edges)
+        else:
+            selected, relations = select_subgraph(graph, opts)
+            nodes = (node_record(node) for node in selected.values())
+            edges = (edge_record(relation) for relation in relations)
+        for record in nodes:
+            self._line(stream, {"type": "node", **record})
+        for record in edges:
+            self._line(stream, {"type": "edge", **record})
+
+    @staticmethod
+    def _line(stream: TextIO, record: Dict[str, Any]) -> None:
+        stream.write(json.dumps(record, ensure_ascii=False, default=str))
+        stream.write("\n")
+
+
+class JsonExporter(GraphExporter):
+    """
+    Snapshot exporter: the selected subgraph as the versioned document of
+    CodeGraph.to_json, indented so that two snapshots diff line by line.
+    A ModuleGraph has no such document and is refused.
+    """
+
+    def write(self, graph: Union[CodeGraph, ModuleGraph], stream: TextIO, opts: Optional[DotOptions] = None) -> None:
+        if isinstance(graph, ModuleGraph):
+            raise ValueError("JSON snapshots hold the symbol graph; export the module graph as dot or graphml")
+        selected, relations = select_subgraph(graph, opts or DotOptions())
+        stream.write(CodeGraph.from_records(selected.values(), relations).to_json(indent=2))
+        stream.write("\n")
+
+
+# Exporters by the format name given to --format and the export tool
+EXPORTERS = {"dot": DotExporter, "graphml": GraphMlExporter, "json": JsonExporter, "jsonl": JsonLinesExporter}
+
+
+def _xml_text(value: str) -> str:
+    """Drop the characters XML cannot represent, such as NUL, from a string."""
+    return XML_INVALID_CHARS.sub("", value)
+
+
+def _graphml_type(value: Any) -> str:
+    return next((name for python_type, name in GRAPHML_TYPES if isinstance(value, python_type)), "string")
+
+
+def _graphml_value(value: Any) -> str:
+    """A data value as GraphML text: lower-case booleans, JSON for lists and dicts."""
+    if isinstance(value, bool):
+        return "true" if value else "false"
+    if isinstance(value, (list, tuple, dict)):
+        return json.dumps(value, ensure_ascii=False, default=str)
+    return str(value)
+
+
+def _property_keys(element: str, properties: Iterable[Dict[str, Any]],
+                   declared: Tuple[Tuple[str, str, str, str], ...]) -> Tuple[Tuple[str, str, str, str], ...]:
+    """
+    GraphML keys for the properties of the given nodes or edges, skipping
+    those already declared. A property whose values mix types is typed
+    ``double`` when they are all numbers and ``string`` otherwise.
+    """
+    types: Dict[str, str] = {}
+    for item in properties:
+        for name, value in item.items():
+            if value is None or name in GRAPHML_RESERVED[element]:
+                continue
+            value_type = _graphml_type(value)
+            previous = types.setdefault(name, value_type)
+            if previous != value_type:
+                types[name] = "double" if {previous, value_type} <= {"long", "double"} else "string"
+    declared_ids = {key[0] for key in declared}
+    return tuple((f"{element}_{name}", element, name, types[name]) for name in sorted(types)
+                 if f"{element}_{name}" not in declared_ids)
+
+
+def _property_data(element: str, properties: Dict[str, Any]) -> Dict[str, Any]:
+    """Data of a node or edge by key ID, for the keys _property_keys declares."""
+    return {f"{element}_{name}": value for name, value in properties.items()
+            if name not in GRAPHML_RESERVED[element]}
+
+
+def select_subgraph(graph: CodeGraph, opts: DotOptions) -> Tuple[Dict[str, CodeNode], List[CodeRelation]]:
+    """
+    Nodes passing the file glob, path prefix and node type filters, and the
+    relations of the selected types between them.
+
+    Raises:
+        ExportTooLargeError: More nodes are selected than opts.max_nodes
+    """
+    prefix = os.path.normpath(opts.path_prefix) if opts.path_prefix else None
+    selected = {}
+    for node_id, node in graph.nodes.items():
+        if opts.node_types is not None and node.node_type not in opts.node_types:
+            continue
+        if opts.file_glob is not None and not fnmatch.fnmatch(node.file_path, opts.file_glob):
+            continue
+        if prefix is not None and not path_under(node.file_path, prefix):
+            continue
+        selected[node_id] = node
+    if opts.max_nodes is not None and len(selected) > opts.max_nodes:
+        raise ExportTooLargeError(
+            f"Export would contain {len(selected)} nodes, more than the limit of {opts.max_nodes}; "
+            f"narrow it with node kinds, a path or a file glob, or raise max_nodes")
+    relations = [r for r in graph.relations
+                 if r.source_id in selected and r.target_id in selected
+                 and (opts.edge_types is None or r.relation_type in opts.edge_types)]
+    return selected, relations
+
+
+def select_modules(graph: ModuleGraph, opts: DotOptions) -> Tuple[List[str], List[ModuleEdge]]:
+    """
+    Files passing the file glob and path prefix filters, and the edges
+    between them. With opts.edge_types an edge only counts the relations of
+    those types, and edges left with no relations are dropped.
+
+    Raises:
+        ExportTooLargeError: More files are selected than opts.max_nodes
+    """
+    prefix = os.path.normpath(opts.path_prefix) if opts.path_prefix else None
+    files = [f for f in graph.files
+             if (opts.file_glob is None or fnmatch.fnmatch(f, opts.file_glob))
+             and (prefix is None or path_under(f, prefix))]
+    if opts.max_nodes is not None and len(files) > opts.max_nodes:
+        raise ExportTooLargeError(
+            f"Export would contain {len(files)} files, more than the limit of {opts.max_nodes}; "
+            f"narrow it with a path or a file glob, or raise max_nodes")
+    selected = set(files)
+    edges = []
+    for edge in graph.edges:
+        if edge.source not in selected or edge.target not in selected:
+            continue
+        if opts.edge_types is not None:
+            relations = {t: n for t, n in edge.relations.items() if t in opts.edge_types}
+            if not relations:
+                continue
+            edge = ModuleEdge(edge.source, edge.target, sum(relations.values()), relations)
+        edges.append(edge)
+    return files, sorted(edges, key=lambda e: (-e.weight, e.source, e.target))
+
+
+def parse_node_kinds(kinds: str) -> List[str]:
+    """
+    Node types for a comma-separated kind list such as "function,struct".
+
+    Raises:
+        ValueError: A kind is neither a known kind nor a node type
+    """
+    node_types: List[str] = []
+    for kind in filter(None, (k.strip() for k in kinds.split(","))):
+        if kind.lower() in NODE_KINDS:
+            node_types.extend(t for t in NODE_KINDS[kind.lower()] if t not in node_types)
+        elif kind[:1].isupper():
+            if kind not in node_types:
+                node_types.append(kind)
+        else:
+            raise ValueError(f"Unknown node kind: {kind} (expected one of {', '.join(NODE_KINDS)})")
+    return node_types
+
+
+def export_main(argv: Optional[List[str]] = None) -> int:
+    """Command line entry point: parse a directory and write its DOT, GraphML, JSON Lines or JSON export."""
+    parser = argparse.ArgumentParser(prog="export",
+                                     description="Export the code graph of a codebase as Graphviz DOT, GraphML, "
+                                                 "JSON Lines or a JSON snapshot")
+    parser.add_argument("--codebase-path", required=True, help="Codebase path")
+    parser.add_argument("--output", "-o", metavar="PATH", help="File to write the export to (default: stdout)")
+    parser.add_argument("--format", choices=sorted(EXPORTERS), default="dot", help="Output format (default: dot)")
+    parser.add_argument("--kinds", help="Comma-separated node kinds to keep, e.g. function,struct")
+    parser.add_argument("--path", metavar="PREFIX",
+                        help="Keep only nodes under this file or directory, relative to the codebase path")
+    parser.add_argument("--edges", help="Comma-separated relation types to keep, e.g. CALLS,IMPORTS")
+    parser.add_argument("--file-glob", metavar="GLOB", help="fnmatch pattern matched against absolute file paths")
+    parser.add_argument("--max-nodes", type=int, default=5000, metavar="N",
+                        help="Fail instead of exporting more than N nodes (default: 5000)")
+    parser.add_argument("--no-clusters", action="store_true", help="Do not group nodes by file")
+    parser.add_argument("--no-edge-labels", action="store_true", help="Do not label edges with their relation type")
+    parser.add_argument("--modules", action="store_true",
+                        help="Export one node per file, with edges weighted by cross-file references")
+    args = parser.parse_args(argv)
+    if args.max_nodes < 1:
+        parser.error("--max-nodes must be at least 1")
+
+    try:
+        node_types = parse_node_kinds(args.kinds) if args.kinds else None
+    except ValueError as e:
+        parser.error(str(e))
+    edge_types = [e.strip().upper() for e in args.edges.split(",") if e.strip()] if args.edges else None
+
+    codebase_path = os.path.abspath(args.codebase_path)
+    opts = DotOptions(
+        file_glob=args.file_glob,
+        node_types=node_types,
+        path_prefix=os.path.join(codebase_path, args.path) if args.path else None,
+        edge_types=edge_types,
+        max_nodes=args.max_nodes,
+        cluster_by_file=not args.no_clusters,
+        edge_labels=not args.no_edge_labels,
+    )
+
+    # Same parser selection as indexing, see main.py
+    graph = CodeGraph.from_directory(
+        codebase_path,
+        use_ast_grep=os.getenv("USE_AST_GREP", "false").lower() == "true",
+        ast_grep_languages=os.getenv("AST_GREP_LANGUAGES", "python,javascript,typescript").split(","),
+        ast_grep_fallback=os.getenv("AST_GREP_FALLBACK_TO_LEGACY", "true").lower() == "true",
+    )
+    target = ModuleGraph.from_code_graph(graph) if args.modules else graph
+    try:
+        # Select up front, so an export that is too large writes nothing
+        if args.modules:
+            select_modules(target, opts)
+        else:
+            select_subgraph(target, opts)
+    except ExportTooLargeError as e:
+        print(f"error: {e}", file=sys.stderr)
+        return 1
+
+    exporter = EXPORTERS[args.format]()
+    if args.output:
+        with open(args.output, "w", encoding="utf-8") as f:
+            exporter.write(target, f, opts)
+    else:
+        exporter.write(target, sys.stdout, opts)
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(export_main())
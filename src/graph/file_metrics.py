@@ -0,0 +1,136 @@
+"""
+Size metrics per file: line counts, function and struct counts, mean
+complexity and the share of public functions and structs with a doc.
+
+Line counts are taken from the File nodes, where the parsers store them
+while parsing; the other metrics are counted from the symbols of the file.
+Files indexed before line counts were recorded report them as None until
+they are parsed again.
+"""
+
+import fnmatch
+from typing import Any, Dict, Iterable, List, Optional
+
+from src.ast_parser.language_detector import detect_language
+from src.ast_parser.line_metrics import LINE_METRICS
+from src.graph.doc_coverage import coverage_ratio, is_counted
+
+# Symbol types counted as functions, and as structs (classes and structs share the Class type)
+FUNCTION_TYPES = ("Function", "Method")
+STRUCT_TYPES = ("Class",)
+
+# Characters that make a path argument a glob
+GLOB_CHARS = "*?["
+
+
+def is_glob(path: str) -> bool:
+    return any(char in path for char in GLOB_CHARS)
+
+
+def glob_matches(file_path: str, pattern: str) -> bool:
+    """
+    Whether a file path matches an fnmatch pattern.
+
+    Stored paths are usually absolute, so a relative pattern such as
+    ``src/*.py`` also matches at any directory boundary.
+    """
+    return fnmatch.fnmatch(file_path, pattern) or fnmatch.fnmatch(file_path, "*/" + pattern.lstrip("/"))
+
+
+def match_glob(file_paths: Iterable[str], pattern: str) -> List[str]:
+    """File paths matching an fnmatch pattern (see glob_matches), sorted."""
+    return sorted(path for path in file_paths if glob_matches(path, pattern))
+
+
+def file_metrics(files: Iterable[Dict[str, Any]], symbols: Iterable[Dict[str, Any]],
+                 paths: Optional[Iterable[str]] = None) -> Dict[str, Any]:
+    """
+    Metrics of each file, and their totals.
+
+    Args:
+        files: File node records with file_path and the line counts under
+            ``properties``, as returned by ``get_graph``
+        symbols: Symbol summaries with type, file_path, complexity and documented
+        paths: Files to report; None reports every file
+
+    Returns:
+        Dict with ``files`` (file_path, total_lines, blank_lines,
+        comment_lines, code_lines, function_count, struct_count,
+        avg_complexity, doc_coverage_ratio), sorted by path, and ``totals``
+        over them; doc_coverage_ratio is null for files without public
+        functions or structs whose docs are known
+    """
+    wanted = None if paths is None else set(paths)
+    rows: Dict[str, Dict[str, Any]] = {}
+    for record in files:
+        path = record.get("file_path")
+        if not path or (wanted is not None and path not in wanted):
+            continue
+        properties = record.get("properties") or {}
+        rows[path] = {"file_path": path, **{key: properties.get(key) for key in LINE_METRICS},
+                      "function_count": 0, "struct_count": 0, "avg_complexity": None,
+                      "doc_coverage_ratio": None}
+
+    complexities: Dict[str, List[int]] = {path: [] for path in rows}
+    # [documented, counted] public functions and structs per file
+    coverage: Dict[str, List[int]] = {path: [0, 0] for path in rows}
+    for symbol in symbols:
+        row = rows.get(symbol.get("file_path"))
+        if row is None:
+            continue
+        if symbol.get("type") in FUNCTION_TYPES:
+            row["function_count"] += 1
+            if symbol.get("complexity") is not None:
+                complexities[row["file_path"]].append(symbol["complexity"])
+        elif symbol.get("type") in STRUCT_TYPES:
+            row["struct_count"] += 1
+        else:
+            continue
+        if is_counted(symbol):
+            coverage[row["file_path"]][0] += bool(symbol["documented"])
+            coverage[row["file_path"]][1] += 1
+    for path, values in complexities.items():
+        rows[path]["avg_complexity"] = _mean(values)
+        rows[path]["doc_coverage_ratio"] = coverage_ratio(*coverage[path])
+
+    ordered = [rows[path] for path in sorted(rows)]
+    totals = _totals(ordered, [v for values in complexities.values() for v in values])
+    totals["doc_coverage_ratio"] = coverage_ratio(sum(d for d, _ in coverage.values()),
+                                                  sum(c for _, c in coverage.values()))
+    return {"files": ordered, "totals": totals}
+
+
+def metrics_summary(files: Iterable[Dict[str, Any]], symbols: Iterable[Dict[str, Any]]) -> Dict[str, Any]:
+    """
+    Project-wide totals, overall and per language.
+
+    Args:
+        files: File node records, as for ``file_metrics``
+        symbols: Symbol summaries, as for ``file_metrics``
+
+    Returns:
+        Dict with ``totals`` and ``by_language``, the same totals per
+        language detected from the file extension
+    """
+    files, symbols = list(files), list(symbols)
+    report = file_metrics(files, symbols)
+    by_language: Dict[str, List[str]] = {}
+    for row in report["files"]:
+        by_language.setdefault(detect_language(row["file_path"]) or "other", []).append(row["file_path"])
+    return {
+        "totals": report["totals"],
+        "by_language": {language: file_metrics(files, symbols, paths)["totals"]
+                        for language, paths in sorted(by_language.items())},
+    }
+
+
+def _mean(values: List[int]) -> Optional[float]:
+    return round(sum(values) / len(values), 2) if values else None
+
+
+def _totals(rows: List[Dict[str, Any]], complexities: List[int]) -> Dict[str, Any]:
+    totals: Dict[str, Any] = {"files": len(rows)}
+    for key in LINE_METRICS + ("function_count", "struct_count"):
+        totals[key] = sum(row[key] for row in rows if row[key] is not None)
+    totals["avg_complexity"] = _mean(complexities)
+    return totals
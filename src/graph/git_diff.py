@@ -0,0 +1,183 @@
+"""
+Code graphs of git commits, and a symbol-level diff between two of them.
+
+Nothing is checked out: the source files of a commit are listed with
+``git ls-tree`` and streamed through one ``git cat-file --batch`` process,
+then parsed into a CodeGraph under their repository-relative paths. Node
+IDs are derived from the path, name and line of each symbol, so a file
+unchanged between two commits gives the same IDs in both graphs; two
+graphs are compared with CodeGraph.diff, which matches symbols by kind,
+file and name. Files too large to parse, binary files and files that are
+not UTF-8 are left out and listed in ``skipped`` notes instead.
+"""
+
+import logging
+import subprocess
+from typing import Dict, List, Optional, Tuple
+
+from src.ast_parser.language_detector import detect_language
+from src.graph.code_graph import CodeGraph, GraphDiff
+from src.graph.progress import ProgressChannel
+
+logger = logging.getLogger(__name__)
+
+# Files larger than this many bytes are skipped rather than parsed
+DEFAULT_MAX_FILE_BYTES = 1_000_000
+
+# A NUL byte in this many leading bytes marks a file as binary, as git does
+BINARY_SNIFF_BYTES = 8000
+
+
+class GitError(RuntimeError):
+    """A git command failed or a ref does not name a commit."""
+
+
+def _git(repo_path: str, *args: str, stdin: Optional[bytes] = None) -> bytes:
+    try:
+        completed = subprocess.run(["git", "-C", repo_path, *args], input=stdin,
+                                   stdout=subprocess.PIPE, stderr=subprocess.PIPE)
+    except OSError as e:
+        raise GitError(f"Cannot run git: {e}") from e
+    if completed.returncode != 0:
+        message = completed.stderr.decode("utf-8", "replace").strip()
+        raise GitError(message or f"git {args[0]} exited with status {completed.returncode}")
+    return completed.stdout
+
+
+def resolve_ref(repo_path: str, ref: str) -> str:
+    """Commit SHA a ref (branch, tag, ``HEAD~1``, SHA) points at."""
+    try:
+        return _git(repo_path, "rev-parse", "--verify", "--quiet", f"{ref}^{{commit}}").decode().strip()
+    except GitError:
+        raise GitError(f"Not a commit in {repo_path}: {ref}") from None
+
+
+def changed_files(repo_path: str, base: str, head: Optional[str] = None) -> List[str]:
+    """
+    Repository-relative paths of the files changed between two commits, or
+    between a commit and the working tree when ``head`` is None; deleted
+    files are included.
+
+    Raises:
+        GitError: A ref is not a commit or git fails
+    """
+    refs = [resolve_ref(repo_path, base)] + ([resolve_ref(repo_path, head)] if head else [])
+    output = _git(repo_path, "diff", "--name-only", "-z", "--no-renames", *refs, "--")
+    return sorted(path.decode("utf-8", "surrogateescape") for path in output.split(b"\0") if path)
+
+
+def read_sources(repo_path: str, commit: str,
+                 max_file_bytes: int = DEFAULT_MAX_FILE_BYTES) -> Tuple[Dict[str, str], List[Dict[str, str]]]:
+    """
+    Read the supported source files of a commit.
+
+    Returns:
+        File text by repository-relative path, and a note for every
+        source file that was skipped ({"file", "reason"})
+    """
+    blobs: List[Tuple[str, str]] = []
+    skipped: List[Dict[str, str]] = []
+    for entry in _git(repo_path, "ls-tree", "-r", "-z", "--long", commit).split(b"\0"):
+        if not entry:
+            continue
+        meta, _, raw_path = entry.partition(b"\t")
+        _, object_type, sha, size = meta.split()
+        path = raw_path.decode("utf-8", "surrogateescape")
+        # Submodules are commits, not blobs
+        if object_type != b"blob" or detect_language(path) is None:
+            continue
+        if int(size) > max_file_bytes:
+            skipped.append({"file": path, "reason": f"larger than {max_file_bytes} bytes"})
+            continue
+        blobs.append((path, sha.decode()))
+
+    sources: Dict[str, str] = {}
+    if not blobs:
+        return sources, skipped
+    output = _git(repo_path, "cat-file", "--batch", stdin="".join(f"{sha}\n" for _, sha in blobs).encode())
+    offset = 0
+    for path, _ in blobs:
+        header_end = output.index(b"\n", offset)
+        size = int(output[offset:header_end].split()[2])
+        data = output[header_end + 1:header_end + 1 + size]
+        # Each object is followed by a newline
+        offset = header_end + 1 + size + 1
+        if b"\0" in data[:BINARY_SNIFF_BYTES]:
+            skipped.append({"file": path, "reason": "binary"})
+            continue
+        try:
+            sources[path] = data.decode("utf-8")
+        except UnicodeDecodeError:
+            skipped.append({"file": path, "reason": "not UTF-8 text"})
+    return sources, skipped
+
+
+def build_graph(sources: Dict[str, str], progress: Optional[ProgressChannel] = None,
+                **parser_options) -> CodeGraph:
+    """
+    Graph of in-memory sources. Symbols whose parser keeps no code snippet
+    get their source lines, so a diff can tell when a body changed.
+    ``progress`` receives the events of CodeGraph.build_with_progress.
+    """
+    graph = CodeGraph(**parser_options)
+    graph.add_sources(sources, progress)
+    for path, state in graph.files.items():
+        lines = sources[path].splitlines()
+        for node in state.nodes.values():
+            if not node.code_snippet and node.node_type != "File" and node.line_no and node.end_line_no:
+                node.code_snippet = "\n".join(lines[node.line_no - 1:node.end_line_no])
+    return graph
+
+
+def graph_at_revision(repo_path: str, ref: str = "HEAD", max_file_bytes: int = DEFAULT_MAX_FILE_BYTES,
+                      progress: Optional[ProgressChannel] = None,
+                      **parser_options) -> Tuple[CodeGraph, List[Dict[str, str]]]:
+    """
+    Build the code graph of a commit from the git object store.
+
+    Args:
+        repo_path: Any directory inside the repository
+        ref: Tree-ish naming the commit (``HEAD~3``, a branch, a tag, a SHA)
+        max_file_bytes: Files larger than this are skipped with a note
+        progress: Channel receiving the build's progress events
+        **parser_options: Passed to CodeGraph (use_ast_grep, ast_grep_languages, ...)
+
+    Returns:
+        The graph, and a note for every source file that was skipped ({"file", "reason"})
+
+    Raises:
+        GitError: The ref is not a commit or git fails
+    """
+    commit = resolve_ref(repo_path, ref)
+    sources, skipped = read_sources(repo_path, commit, max_file_bytes)
+    logger.info(f"Parsing {len(sources)} files at {ref} ({commit[:12]})")
+    return build_graph(sources, progress, **parser_options), skipped
+
+
+def diff_commits(repo_path: str, base: str, head: str = "HEAD", max_file_bytes: int = DEFAULT_MAX_FILE_BYTES,
+                 progress: Optional[ProgressChannel] = None, **parser_options) -> GraphDiff:
+    """
+    Compare the code graphs of two commits.
+
+    Args:
+        repo_path: Any directory inside the repository
+        base: Ref of the older commit (``HEAD~1``, a branch, a SHA)
+        head: Ref of the newer commit
+        max_file_bytes: Files larger than this are skipped with a note
+        progress: Channel receiving the progress events of both builds, base first
+        **parser_options: Passed to CodeGraph (use_ast_grep, ast_grep_languages, ...)
+
+    Raises:
+        GitError: A ref is not a commit or git fails
+    """
+    graphs = []
+    skipped: Dict[Tuple[str, str], Dict[str, str]] = {}
+    for ref in (base, head):
+        graph, notes = graph_at_revision(repo_path, ref, max_file_bytes, progress, **parser_options)
+        graphs.append(graph)
+        for note in notes:
+            skipped.setdefault((note["file"], note["reason"]), dict(note, refs=[]))["refs"].append(ref)
+
+    result = graphs[0].diff(graphs[1])
+    result.skipped = [skipped[key] for key in sorted(skipped)]
+    return result
@@ -0,0 +1,256 @@
+"""
+Blast radius of a change: what transitively depends on a set of symbols.
+
+Dependency edges are walked backwards from the changed symbols: whatever
+calls them, uses them as a type, imports them, or implements or extends
+them depends on them, and so on up to a depth limit. A changed class,
+struct or trait also stands for the members it defines, so callers of its
+methods are affected too. The walk is breadth first over a reverse
+adjacency list built once from the graph, so every affected symbol is
+reported once, at its shallowest depth, with the chain of edges linking
+it back to a changed symbol; in a diamond the first path found is kept.
+
+A symbol reached along many routes is more likely to break than one
+reached along a single chain, so every affected symbol also counts the
+dependency paths of at most ``max_depth`` edges leading to it from the
+changed set (a diamond gives two). A report can rank symbols, and the
+files holding them, by that count, telling apart files with a direct
+dependent of a changed symbol from files only affected transitively.
+"""
+
+from collections import deque
+from dataclasses import dataclass, field
+from typing import Any, Dict, Iterable, List, Optional, Tuple
+
+from src.graph.code_graph import UNRESOLVED_PREFIX, CodeGraph
+
+# Relation types along which a change spreads, from target to source;
+# every IMPORTS_* variant, and a C/C++ INCLUDES, counts as IMPORTS
+IMPACT_RELATIONS = ("CALLS", "USES_TYPE", "IMPORTS", "IMPLEMENTS", "EXTENDS")
+
+DEFAULT_IMPACT_DEPTH = 3
+
+
+def _relation_kind(relation_type: str) -> str:
+    return "IMPORTS" if relation_type.startswith("IMPORTS") or relation_type == "INCLUDES" else relation_type
+
+
+@dataclass
+class ImpactedSymbol:
+    """A symbol depending, directly or not, on a changed one."""
+    id: str
+    name: str
+    type: str
+    file_path: str
+    line_no: int
+    depth: int
+    # From the changed symbol to this one: each step's relation is the edge
+    # from that step's node to the previous one; None on the first step
+    path: List[Dict[str, Optional[str]]]
+    # Dependency paths of at most max_depth edges from the changed set
+    paths: int = 1
+
+    @property
+    def direct(self) -> bool:
+        return self.depth == 1
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"id": self.id, "name": self.name, "type": self.type, "line_no": self.line_no,
+                "depth": self.depth, "direct": self.direct, "paths": self.paths, "path": self.path}
+
+
+@dataclass
+class ImpactReport:
+    """Symbols affected by changing a set of seed symbols."""
+    seeds: List[str]
+    max_depth: int
+    symbols: List[ImpactedSymbol] = field(default_factory=list)
+    # Symbols at max_depth with dependents of their own that were not walked,
+    # to start a further walk from
+    frontier: List[str] = field(default_factory=list)
+
+    def to_dict(self, limit: Optional[int] = None) -> Dict[str, Any]:
+        """
+        Affected symbols grouped by file, files in path order and symbols by
+        depth then line. Counts cover every affected symbol even when
+        ``limit`` cuts the listed ones short. ``truncated`` is also set when
+        the walk stopped at max_depth with dependents left, the ``frontier``.
+        """
+        listed = self.symbols if limit is None else self.symbols[:max(0, limit)]
+        files: Dict[str, List[Dict[str, Any]]] = {}
+        for symbol in sorted(listed, key=lambda s: (s.file_path, s.depth, s.line_no, s.id)):
+            files.setdefault(symbol.file_path, []).append(symbol.to_dict())
+        direct = sum(1 for s in self.symbols if s.direct)
+        return {
+            "seeds": self.seeds,
+            "max_depth": self.max_depth,
+            "total": len(self.symbols),
+            "direct": direct,
+            "transitive": len(self.symbols) - direct,
+            "files": [{"file_path": path, "symbols": symbols} for path, symbols in files.items()],
+            "truncated": len(listed) < len(self.symbols) or bool(self.frontier),
+            "frontier": self.frontier,
+        }
+
+    def affected_files(self) -> List[Dict[str, Any]]:
+        """
+        Files holding affected symbols, most dependency paths first. A file
+        is ``direct`` when one of its symbols (or the file itself, for an
+        import) depends on a changed symbol without an intermediary.
+        """
+        files: Dict[str, Dict[str, Any]] = {}
+        for symbol in self.symbols:
+            entry = files.setdefault(symbol.file_path, {"file_path": symbol.file_path, "impact": "transitive",
+                                                        "symbols": 0, "paths": 0, "depth": symbol.depth})
+            entry["symbols"] += 1
+            entry["paths"] += symbol.paths
+            entry["depth"] = min(entry["depth"], symbol.depth)
+            if symbol.direct:
+                entry["impact"] = "direct"
+        return sorted(files.values(), key=lambda f: (-f["paths"], f["depth"], f["file_path"]))
+
+    def to_ranked_dict(self, limit: Optional[int] = None) -> Dict[str, Any]:
+        """
+        Affected files and symbols ranked by the number of dependency paths
+        from the changed set, then by depth. Counts cover every affected
+        symbol and file even when ``limit`` cuts the listed symbols short;
+        ``truncated`` and ``frontier`` are as in to_dict.
+        """
+        ranked = sorted(self.symbols, key=lambda s: (-s.paths, s.depth, s.file_path, s.line_no, s.id))
+        listed = ranked if limit is None else ranked[:max(0, limit)]
+        files = self.affected_files()
+        direct = [f["file_path"] for f in files if f["impact"] == "direct"]
+        return {
+            "seeds": len(self.seeds),
+            "max_depth": self.max_depth,
+            "total": len(self.symbols),
+            "direct_files": direct,
+            "transitive_files": [f["file_path"] for f in files if f["impact"] == "transitive"],
+            "files": files,
+            "symbols": [dict(s.to_dict(), file_path=s.file_path) for s in listed],
+            "truncated": len(listed) < len(self.symbols) or bool(self.frontier),
+            "frontier": self.frontier,
+        }
+
+
+def reverse_dependencies(graph: CodeGraph,
+                         relation_types: Iterable[str] = IMPACT_RELATIONS) -> Dict[str, List[Tuple[str, str]]]:
+    """Dependents of every node: target ID -> [(source ID, relation kind)], in one pass over the edges."""
+    kinds = set(relation_types)
+    dependents: Dict[str, List[Tuple[str, str]]] = {}
+    for relation in graph.relations:
+        kind = _relation_kind(relation.relation_type)
+        if kind in kinds and relation.source_id != relation.target_id:
+            dependents.setdefault(relation.target_id, []).append((relation.source_id, kind))
+    return dependents
+
+
+def _with_members(graph: CodeGraph, seeds: List[str]) -> List[str]:
+    """Seeds plus every member they define and the prototypes declaring them, recursively, in a stable order."""
+    defined: Dict[str, List[str]] = {}
+    for relation in graph.relations:
+        if relation.relation_type in ("DEFINES", "DECLARED_BY"):
+            defined.setdefault(relation.source_id, []).append(relation.target_id)
+    result, seen = [], set()
+    stack = list(reversed(seeds))
+    while stack:
+        node_id = stack.pop()
+        if node_id in seen:
+            continue
+        seen.add(node_id)
+        result.append(node_id)
+        stack.extend(reversed(defined.get(node_id, [])))
+    return result
+
+
+def symbols_in_files(graph: CodeGraph, file_paths: Iterable[str]) -> List[str]:
+    """IDs of every node of some files, their File nodes included, as seeds of a change."""
+    paths = set(file_paths)
+    return [node.node_id for node in graph.nodes.values()
+            if node.file_path in paths and node.node_type != "Unresolved"]
+
+
+def impact_of(graph: CodeGraph, seed_ids: Iterable[str], max_depth: int = DEFAULT_IMPACT_DEPTH,
+              relation_types: Iterable[str] = IMPACT_RELATIONS) -> ImpactReport:
+    """
+    Walk reverse dependency edges from the seeds.
+
+    Args:
+        graph: Graph holding the seeds and their dependents
+        seed_ids: IDs of the changed symbols; unknown IDs are ignored
+        max_depth: Most edges between a seed and a reported symbol
+        relation_types: Relation types to follow (IMPORTS covers every IMPORTS_* type)
+
+    Returns:
+        The affected symbols, seeds and their members excluded, ordered by
+        depth, with the number of dependency paths reaching each, and the
+        frontier where max_depth stopped the walk
+    """
+    seeds = [node_id for node_id in dict.fromkeys(seed_ids) if node_id in graph.nodes]
+    report = ImpactReport(seeds=seeds, max_depth=max_depth)
+    dependents = reverse_dependencies(graph, relation_types)
+    starts = _with_members(graph, seeds)
+
+    # node -> (previous node, relation from node to previous); None for starts
+    parents: Dict[str, Optional[Tuple[str, str]]] = {node_id: None for node_id in starts}
+    frontier = deque((node_id, 0) for node_id in starts)
+    stopped = []
+    while frontier:
+        node_id, depth = frontier.popleft()
+        if depth >= max_depth:
+            stopped.append(node_id)
+            continue
+        for source_id, kind in dependents.get(node_id, ()):
+            if source_id in parents or source_id.startswith(UNRESOLVED_PREFIX) or source_id not in graph.nodes:
+                continue
+            parents[source_id] = (node_id, kind)
+            node = graph.nodes[source_id]
+            report.symbols.append(ImpactedSymbol(source_id, node.name, node.node_type, node.file_path,
+                                                 node.line_no, depth + 1, _path(graph, parents, source_id)))
+            frontier.append((source_id, depth + 1))
+
+    # Checked once the walk is over, as a dependent may be reached at max_depth by another path
+    report.frontier = [node_id for node_id in stopped
+                       if any(source_id not in parents and not source_id.startswith(UNRESOLVED_PREFIX)
+                              and source_id in graph.nodes for source_id, _ in dependents.get(node_id, ()))]
+
+    paths = _count_paths(graph, dependents, starts, max_depth)
+    for symbol in report.symbols:
+        symbol.paths = paths.get(symbol.id, 1)
+    return report
+
+
+def _count_paths(graph: CodeGraph, dependents: Dict[str, List[Tuple[str, str]]],
+                 starts: List[str], max_depth: int) -> Dict[str, int]:
+    """
+    Number of dependency paths of 1 to max_depth edges from any start to
+    every node, counted layer by layer; two edges between the same pair of
+    symbols (a function calling another twice) are one step, and paths do
+    not pass through the starts again.
+    """
+    excluded = set(starts)
+    totals: Dict[str, int] = {}
+    layer = {node_id: 1 for node_id in starts}
+    for _ in range(max_depth):
+        following: Dict[str, int] = {}
+        for node_id, count in layer.items():
+            for source_id in dict.fromkeys(source_id for source_id, _ in dependents.get(node_id, ())):
+                if source_id in excluded or source_id.startswith(UNRESOLVED_PREFIX) or source_id not in graph.nodes:
+                    continue
+                following[source_id] = following.get(source_id, 0) + count
+        for node_id, count in following.items():
+            totals[node_id] = totals.get(node_id, 0) + count
+        layer = following
+    return totals
+
+
+def _path(graph: CodeGraph, parents: Dict[str, Optional[Tuple[str, str]]],
+          node_id: str) -> List[Dict[str, Optional[str]]]:
+    steps = []
+    current: Optional[str] = node_id
+    while current is not None:
+        parent = parents[current]
+        steps.append({"id": current, "name": graph.nodes[current].name,
+                      "relation": parent[1] if parent else None})
+        current = parent[0] if parent else None
+    return steps[::-1]
@@ -0,0 +1,147 @@
+"""
+Incremental re-indexing against a previously stored graph.
+
+Each indexed file keeps its content hash, modification time, size and
+saved FileState. On the next run the saved states are restored into a
+CodeGraph without parsing, and only files whose contents changed are
+re-parsed. The mtime and size are a cheap pre-check; a file whose mtime
+moved but whose hash did not is skipped as well.
+
+Because the restored graph knows which file owns every edge, re-parsing one
+file replaces exactly the edges it owns. Edges from unchanged files into it
+are re-resolved, and calls whose target disappeared point at an
+``Unresolved`` placeholder.
+
+A workspace is re-indexed from several roots at once. With ``keep_outside``
+only some of its roots are re-indexed: stored files under other roots are
+restored as they are, neither re-parsed nor deleted.
+"""
+
+import os
+import logging
+from dataclasses import dataclass, field
+from typing import Dict, List, Optional, Sequence, Union
+
+from src.graph.code_graph import CodeGraph, FileState, GraphDelta, content_hash
+
+logger = logging.getLogger(__name__)
+
+
+@dataclass
+class StoredFile:
+    """What the previous run recorded for one file."""
+    file_path: str
+    content_hash: Optional[str] = None
+    mtime_ns: Optional[int] = None
+    # None when the file was indexed without saving its graph state
+    state: Optional[FileState] = None
+    # None when the size was not recorded, which skips the size check
+    size: Optional[int] = None
+
+
+@dataclass
+class IndexStats:
+    """File counts for one incremental run."""
+    skipped: int = 0
+    reparsed: int = 0
+    added: int = 0
+    deleted: int = 0
+
+    def summary(self) -> str:
+        return (f"{self.skipped} files skipped, {self.reparsed} re-parsed, "
+                f"{self.added} added, {self.deleted} deleted")
+
+
+@dataclass
+class IncrementalResult:
+    """Graph changes to write, in order, plus the bookkeeping around them."""
+    deltas: List[GraphDelta] = field(default_factory=list)
+    stats: IndexStats = field(default_factory=IndexStats)
+    # Files stored without a graph state; their old nodes must be dropped
+    # before the deltas are written, since the graph cannot diff against them
+    stale_files: List[str] = field(default_factory=list)
+    # Unchanged files whose mtime moved, so their stored mtime needs refreshing
+    touched_files: List[str] = field(default_factory=list)
+
+
+def reindex(graph: CodeGraph, directory_path: Union[str, Sequence[str]], stored: Dict[str, StoredFile],
+            jobs: int = 1, keep_outside: bool = False) -> IncrementalResult:
+    """
+    Bring an empty graph up to date with a directory, parsing only what changed.
+
+    Changed and added files are parsed up front, on ``jobs`` threads, and
+    then applied one at a time in path order, so the deltas are the same
+    for any number of jobs.
+
+    Args:
+        graph: Empty CodeGraph; holds the full current graph afterwards
+        directory_path: Root directory of the codebase, or the roots of a workspace
+        stored: Previous run's records, keyed by file path
+        jobs: Number of threads parsing changed files
+        keep_outside: Keep stored files outside the given roots instead of
+            deleting them, to re-index some projects of a workspace
+
+    Returns:
+        IncrementalResult with one delta per re-parsed, added or deleted file
+    """
+    result = IncrementalResult()
+    graph.restore(record.state for record in stored.values() if record.state is not None)
+
+    roots = [directory_path] if isinstance(directory_path, str) else list(directory_path)
+    current = sorted(path for root in roots for path in graph.collect_source_files(root))
+    current_set = set(current)
+
+    gone = set(stored) - current_set
+    if keep_outside:
+        gone = {path for path in gone if any(_under(path, root) for root in roots)}
+    # Deletions first, so a file moved to a new path resolves against the new copy
+    for path in sorted(gone):
+        if stored[path].state is None:
+            result.stale_files.append(path)
+        else:
+            result.deltas.append(graph.remove_file(path))
+        result.stats.deleted += 1
+
+    changed = []
+    for path in current:
+        record = stored.get(path)
+        if record is not None and record.state is not None and _unchanged(record, result):
+            result.stats.skipped += 1
+            continue
+        if record is None:
+            result.stats.added += 1
+        else:
+            if record.state is None:
+                result.stale_files.append(path)
+            result.stats.reparsed += 1
+        changed.append(path)
+
+    for path, state in zip(changed, graph.parse_files(changed, jobs)):
+        result.deltas.append(graph.update_file(path, parsed=state))
+
+    logger.info(f"Incremental index of {', '.join(roots)}: {result.stats.summary()}")
+    return result
+
+
+def _under(path: str, root: str) -> bool:
+    path, root = os.path.abspath(path), os.path.abspath(root)
+    return path == root or path.startswith(root.rstrip(os.sep) + os.sep)
+
+
+def _unchanged(record: StoredFile, result: IncrementalResult) -> bool:
+    """Compare a file on disk with its record, refreshing the mtime when only that moved."""
+    try:
+        stat = os.stat(record.file_path)
+        if stat.st_mtime_ns == record.mtime_ns and record.size in (None, stat.st_size):
+            return True
+        with open(record.file_path, "rb") as f:
+            if content_hash(f.read()) != record.content_hash:
+                return False
+    except OSError:
+        return False
+
+    record.state.mtime_ns = stat.st_mtime_ns
+    record.state.size = stat.st_size
+    CodeGraph.stamp_file(record.state)
+    result.touched_files.append(record.file_path)
+    return True
@@ -0,0 +1,87 @@
+"""
+Per-function graph metrics: fan-in, fan-out and cyclomatic complexity.
+
+Fan-in is the number of calls into a function, one per call site, so a
+function called twice from the same caller counts twice. Fan-out is the
+number of distinct functions and methods it calls; calls the parser could
+not resolve are left out of both. Complexity is recorded by the parsers,
+see ``src.ast_parser.complexity``, and is None for a function without a
+body (an abstract method or a prototype).
+"""
+
+from dataclasses import asdict, dataclass
+from typing import Any, Dict, List, Optional
+
+from src.graph.code_graph import UNRESOLVED_PREFIX, CodeGraph
+from src.graph.file_metrics import FUNCTION_TYPES
+
+# Node properties the metrics need, for loading the graph
+METRIC_PROPERTIES = ("complexity",)
+
+
+@dataclass
+class FunctionMetrics:
+    """Fan-in, fan-out and complexity of one function or method."""
+    node_id: str
+    name: str
+    node_type: str
+    file_path: str
+    line_no: int
+    fan_in: int
+    fan_out: int
+    complexity: Optional[int]
+
+    def to_dict(self) -> Dict[str, Any]:
+        return asdict(self)
+
+
+def function_metrics(graph: CodeGraph, node_id: str) -> Optional[FunctionMetrics]:
+    """
+    Metrics of a function or method.
+
+    Returns:
+        None when the node is not in the graph or is not a function or method
+    """
+    node = graph.nodes.get(node_id)
+    if node is None or node.node_type not in FUNCTION_TYPES:
+        return None
+    fan_in = 0
+    callees = set()
+    for relation in graph.relations:
+        if relation.relation_type != "CALLS":
+            continue
+        if relation.target_id == node_id:
+            fan_in += 1
+        if relation.source_id == node_id and not relation.target_id.startswith(UNRESOLVED_PREFIX):
+            callees.add(relation.target_id)
+    return FunctionMetrics(node_id, node.name, node.node_type, node.file_path, node.line_no,
+                           fan_in, len(callees), node.properties.get("complexity"))
+
+
+def all_metrics(graph: CodeGraph) -> List[FunctionMetrics]:
+    """Metrics of every function and method, in one pass over the edges, ordered by file and line."""
+    fan_in: Dict[str, int] = {}
+    callees: Dict[str, set] = {}
+    for relation in graph.relations:
+        if relation.relation_type != "CALLS" or relation.target_id.startswith(UNRESOLVED_PREFIX):
+            continue
+        fan_in[relation.target_id] = fan_in.get(relation.target_id, 0) + 1
+        callees.setdefault(relation.source_id, set()).add(relation.target_id)
+    metrics = [FunctionMetrics(node.node_id, node.name, node.node_type, node.file_path, node.line_no,
+                               fan_in.get(node.node_id, 0), len(callees.get(node.node_id, ())),
+                               node.properties.get("complexity"))
+               for node in graph.nodes.values() if node.node_type in FUNCTION_TYPES]
+    metrics.sort(key=lambda m: (m.file_path, m.line_no, m.node_id))
+    return metrics
+
+
+def top_complex(graph: CodeGraph, n: int = 10) -> List[FunctionMetrics]:
+    """
+    The n functions and methods of highest complexity, most complex first.
+
+    Ties go to the higher fan-in, then to file and line. Functions without a
+    recorded complexity are left out.
+    """
+    measured = [m for m in all_metrics(graph) if m.complexity is not None]
+    measured.sort(key=lambda m: (-m.complexity, -m.fan_in, m.file_path, m.line_no, m.node_id))
+    return measured[:max(0, n)]
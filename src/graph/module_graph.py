@@ -0,0 +1,94 @@
+"""
+File-level view of a CodeGraph.
+
+A ModuleGraph has one node per file and one edge per ordered pair of files
+that reference each other: every relation whose source lives in one file and
+whose target lives in another (a call, an import, an implemented trait, a
+field access, ...) is collapsed into the edge between the two files. The
+edge weight is the number of such relations, so the most coupled file pairs
+come first. Relations inside one file and relations to unresolved
+placeholders are left out.
+
+In a monorepo the files are grouped under the workspace members their File
+nodes name (see src/ast_parser/workspace.py), the top level of the graph.
+"""
+
+from dataclasses import dataclass, field
+from typing import Any, Dict, List, Tuple
+
+from src.graph.code_graph import CodeGraph, UNRESOLVED_NODE_TYPE
+
+
+@dataclass
+class ModuleEdge:
+    """All references from one file into another."""
+    source: str
+    target: str
+    # Number of symbol-level relations collapsed into this edge
+    weight: int
+    # The same count per relation type, e.g. {"CALLS": 3, "IMPORTS_FROM": 1}
+    relations: Dict[str, int] = field(default_factory=dict)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"source": self.source, "target": self.target, "weight": self.weight,
+                "relations": dict(sorted(self.relations.items()))}
+
+
+@dataclass
+class ModuleGraph:
+    """Files and the weighted dependencies between them."""
+    # File paths, sorted
+    files: List[str]
+    # Heaviest first, then by source and target
+    edges: List[ModuleEdge]
+    # Workspace member -> its files, sorted; files outside any member are in none
+    members: Dict[str, List[str]] = field(default_factory=dict)
+
+    @classmethod
+    def from_code_graph(cls, graph: CodeGraph) -> "ModuleGraph":
+        """
+        Collapse the symbol-level relations of a graph onto its files.
+
+        Args:
+            graph: Graph to collapse
+
+        Returns:
+            The file-level graph
+        """
+        files = sorted({node.file_path for node in graph.nodes.values()
+                        if node.file_path and node.node_type != UNRESOLVED_NODE_TYPE})
+        members: Dict[str, List[str]] = {}
+        for node in graph.nodes.values():
+            member = node.properties.get("workspace_member") if node.node_type == "File" else None
+            if member and node.file_path:
+                members.setdefault(member, []).append(node.file_path)
+
+        edges: Dict[Tuple[str, str], ModuleEdge] = {}
+        for relation in graph.relations:
+            source = graph.nodes.get(relation.source_id)
+            target = graph.nodes.get(relation.target_id)
+            if source is None or target is None:
+                continue
+            if UNRESOLVED_NODE_TYPE in (source.node_type, target.node_type):
+                continue
+            if not source.file_path or not target.file_path or source.file_path == target.file_path:
+                continue
+            key = (source.file_path, target.file_path)
+            edge = edges.setdefault(key, ModuleEdge(*key, weight=0))
+            edge.weight += 1
+            edge.relations[relation.relation_type] = edge.relations.get(relation.relation_type, 0) + 1
+
+        return cls(files, sorted(edges.values(), key=lambda e: (-e.weight, e.source, e.target)),
+                   {member: sorted(set(paths)) for member, paths in sorted(members.items())})
+
+    def dependencies(self, file_path: str) -> List[ModuleEdge]:
+        """Edges out of one file, heaviest first."""
+        return [edge for edge in self.edges if edge.source == file_path]
+
+    def dependents(self, file_path: str) -> List[ModuleEdge]:
+        """Edges into one file, heaviest first."""
+        return [edge for edge in self.edges if edge.target == file_path]
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"files": list(self.files), "edges": [edge.to_dict() for edge in self.edges],
+                "members": [{"name": member, "files": list(paths)} for member, paths in self.members.items()]}
@@ -0,0 +1,323 @@
+"""
+Filter expressions over the indexed symbols.
+
+An expression combines ``field:value`` predicates with ``AND``, ``OR``,
+``NOT`` and parentheses, e.g.::
+
+    kind:function AND visibility:public AND file:"src/*.rs"
+    (kind:struct OR kind:enum) AND NOT name:_*
+
+``NOT`` binds tightest, then ``AND``, then ``OR``; keywords are not case
+sensitive. A value is a run of characters other than spaces and
+parentheses, or a double-quoted string in which ``\\"`` and ``\\\\`` escape
+a quote and a backslash. The fields are:
+
+- ``kind``: a kind as ``--kinds`` takes it (``function``, ``struct``, ...)
+  or a node type such as ``GlobalVariable``; ``function,method`` matches
+  either
+- ``name``: the symbol name, exactly, or as an fnmatch glob (``get_*``)
+- ``file``: the file path, matched as ``file_glob`` is elsewhere, so
+  ``src/*.rs`` also matches below any directory; a path without glob
+  characters matches that file
+- ``visibility``: ``public``, ``crate``, ``protected`` or ``private``
+- ``project``: the workspace project, exactly, or as an fnmatch glob
+- ``deprecated``: ``true`` for symbols marked deprecated (Java
+  ``@Deprecated``), ``false`` for the others
+
+A malformed expression, an unknown field or an invalid value raises a
+QueryError naming the position it was found at.
+"""
+
+import fnmatch
+from dataclasses import dataclass, field
+from typing import Any, Callable, Dict, Iterable, List, Optional, Tuple, Union
+
+from src.graph.export import parse_node_kinds
+from src.graph.file_metrics import glob_matches, is_glob
+
+VISIBILITIES = ("Public", "Crate", "Protected", "Private")
+
+KEYWORDS = ("AND", "OR", "NOT")
+
+
+class QueryError(ValueError):
+    """A filter expression that does not parse, with where it went wrong."""
+
+    def __init__(self, query: str, message: str, position: Optional[int] = None):
+        super().__init__(f"Invalid query {query!r}: {message}"
+                         + (f" at position {position}" if position is not None else ""))
+        self.query = query
+        self.message = message
+        self.position = position
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"code": "invalid_query", "message": self.message, "query": self.query, "position": self.position}
+
+
+def _kind_test(value: str) -> Callable[[Dict[str, Any]], bool]:
+    node_types = set(parse_node_kinds(value))
+    if not node_types:
+        raise ValueError("kind is empty")
+    return lambda symbol: symbol.get("type") in node_types
+
+
+def _name_test(value: str) -> Callable[[Dict[str, Any]], bool]:
+    if is_glob(value):
+        return lambda symbol: fnmatch.fnmatchcase(symbol.get("name") or "", value)
+    return lambda symbol: symbol.get("name") == value
+
+
+def _file_test(value: str) -> Callable[[Dict[str, Any]], bool]:
+    if is_glob(value):
+        return lambda symbol: glob_matches(symbol.get("file_path") or "", value)
+    suffix = "/" + value.lstrip("/")
+    return lambda symbol: (symbol.get("file_path") or "") == value \
+        or (symbol.get("file_path") or "").endswith(suffix)
+
+
+def _project_test(value: str) -> Callable[[Dict[str, Any]], bool]:
+    if is_glob(value):
+        return lambda symbol: fnmatch.fnmatchcase(symbol.get("project") or "", value)
+    return lambda symbol: symbol.get("project") == value
+
+
+def _visibility_test(value: str) -> Callable[[Dict[str, Any]], bool]:
+    visibility = value.capitalize()
+    if visibility not in VISIBILITIES:
+        raise ValueError(f"Unknown visibility: {value} (expected one of {', '.join(v.lower() for v in VISIBILITIES)})")
+    return lambda symbol: symbol.get("visibility") == visibility
+
+
+def _deprecated_test(value: str) -> Callable[[Dict[str, Any]], bool]:
+    if value.lower() not in ("true", "false"):
+        raise ValueError(f"Expected true or false for deprecated, found {value}")
+    wanted = value.lower() == "true"
+    return lambda symbol: bool(symbol.get("deprecated")) == wanted
+
+
+# Test of each field, built from the predicate's value; raises ValueError on an invalid value
+FIELDS: Dict[str, Callable[[str], Callable[[Dict[str, Any]], bool]]] = {
+    "kind": _kind_test,
+    "name": _name_test,
+    "file": _file_test,
+    "visibility": _visibility_test,
+    "project": _project_test,
+    "deprecated": _deprecated_test,
+}
+
+
+@dataclass
+class Predicate:
+    """``field:value``"""
+    field: str
+    value: str
+    _test: Callable[[Dict[str, Any]], bool] = field(init=False, repr=False, compare=False)
+
+    def __post_init__(self):
+        self._test = FIELDS[self.field](self.value)
+
+    def matches(self, symbol: Dict[str, Any]) -> bool:
+        return self._test(symbol)
+
+    def __str__(self) -> str:
+        plain = self.value and not any(c.isspace() or c in '()"\\' for c in self.value) \
+            and self.value.upper() not in KEYWORDS
+        value = self.value if plain else '"' + self.value.replace("\\", "\\\\").replace('"', '\\"') + '"'
+        return f"{self.field}:{value}"
+
+
+@dataclass
+class Not:
+    operand: "Filter"
+
+    def matches(self, symbol: Dict[str, Any]) -> bool:
+        return not self.operand.matches(symbol)
+
+    def __str__(self) -> str:
+        return f"NOT {_grouped(self.operand, (And, Or))}"
+
+
+@dataclass
+class And:
+    operands: Tuple["Filter", ...]
+
+    def matches(self, symbol: Dict[str, Any]) -> bool:
+        return all(operand.matches(symbol) for operand in self.operands)
+
+    def __str__(self) -> str:
+        return " AND ".join(_grouped(operand, (Or,)) for operand in self.operands)
+
+
+@dataclass
+class Or:
+    operands: Tuple["Filter", ...]
+
+    def matches(self, symbol: Dict[str, Any]) -> bool:
+        return any(operand.matches(symbol) for operand in self.operands)
+
+    def __str__(self) -> str:
+        return " OR ".join(str(operand) for operand in self.operands)
+
+
+Filter = Union[Predicate, Not, And, Or]
+
+
+def _grouped(operand: Filter, looser: Tuple[type, ...]) -> str:
+    """An operand as text, in parentheses when it binds more loosely than its operator."""
+    return f"({operand})" if isinstance(operand, looser) else str(operand)
+
+
+# Token kinds
+LPAREN, RPAREN, KEYWORD, PREDICATE, END = "(", ")", "keyword", "predicate", "end"
+
+
+@dataclass
+class _Token:
+    kind: str
+    position: int
+    # Keyword in upper case, or (field, value) of a predicate
+    value: Any = None
+
+
+def _tokenize(query: str) -> List[_Token]:
+    tokens: List[_Token] = []
+    i = 0
+    while i < len(query):
+        char = query[i]
+        if char.isspace():
+            i += 1
+        elif char in "()":
+            tokens.append(_Token(char, i))
+            i += 1
+        else:
+            start = i
+            while i < len(query) and not query[i].isspace() and query[i] not in '():"':
+                i += 1
+            word = query[start:i]
+            if i >= len(query) or query[i] != ":":
+                if word.upper() in KEYWORDS:
+                    tokens.append(_Token(KEYWORD, start, word.upper()))
+                    continue
+                if not word:
+                    raise QueryError(query, f"Unexpected {query[i]!r}", i)
+                raise QueryError(query, f"Expected field:value, found {word!r}", start)
+            name = word.lower()
+            if name not in FIELDS:
+                raise QueryError(query, f"Unknown field {word!r} (expected one of {', '.join(FIELDS)})", start)
+            i += 1
+            value_start = i
+            if i < len(query) and query[i] == '"':
+                chars = []
+                i += 1
+                while i < len(query) and query[i] != '"':
+                    if query[i] == "\\" and i + 1 < len(query):
+                        i += 1
+                    chars.append(query[i])
+                    i += 1
+                if i >= len(query):
+                    raise QueryError(query, "Unterminated string", value_start)
+                i += 1
+                value = "".join(chars)
+            else:
+                while i < len(query) and not query[i].isspace() and query[i] not in "()":
+                    i += 1
+                value = query[value_start:i]
+            if not value:
+                raise QueryError(query, f"Missing value for {name}", value_start)
+            tokens.append(_Token(PREDICATE, start, (name, value, value_start)))
+    tokens.append(_Token(END, len(query)))
+    return tokens
+
+
+class _Parser:
+    """Recursive descent over the tokens: or := and (OR and)*, and := not (AND not)*, not := NOT not | atom."""
+
+    def __init__(self, query: str):
+        self.query = query
+        self.tokens = _tokenize(query)
+        self.index = 0
+
+    def _peek(self) -> _Token:
+        return self.tokens[self.index]
+
+    def _keyword(self, keyword: str) -> bool:
+        token = self._peek()
+        if token.kind == KEYWORD and token.value == keyword:
+            self.index += 1
+            return True
+        return False
+
+    def parse(self) -> Filter:
+        if self._peek().kind == END:
+            raise QueryError(self.query, "Empty query", 0)
+        expression = self._or()
+        token = self._peek()
+        if token.kind != END:
+            found = "')'" if token.kind == RPAREN else "another term"
+            raise QueryError(self.query, f"Expected AND, OR or the end of the query, found {found}",
+                             token.position)
+        return expression
+
+    def _or(self) -> Filter:
+        operands = [self._and()]
+        while self._keyword("OR"):
+            operands.append(self._and())
+        return operands[0] if len(operands) == 1 else Or(tuple(operands))
+
+    def _and(self) -> Filter:
+        operands = [self._not()]
+        while self._keyword("AND"):
+            operands.append(self._not())
+        return operands[0] if len(operands) == 1 else And(tuple(operands))
+
+    def _not(self) -> Filter:
+        if self._keyword("NOT"):
+            return Not(self._not())
+        return self._atom()
+
+    def _atom(self) -> Filter:
+        token = self._peek()
+        if token.kind == LPAREN:
+            self.index += 1
+            expression = self._or()
+            closing = self._peek()
+            if closing.kind != RPAREN:
+                raise QueryError(self.query, "Expected ')'", closing.position)
+            self.index += 1
+            return expression
+        if token.kind == PREDICATE:
+            self.index += 1
+            name, value, value_position = token.value
+            try:
+                return Predicate(name, value)
+            except ValueError as e:
+                raise QueryError(self.query, str(e), value_position) from None
+        if token.kind == END:
+            raise QueryError(self.query, "Unexpected end of query", token.position)
+        found = token.value if token.kind == KEYWORD else f"'{token.kind}'"
+        raise QueryError(self.query, f"Expected field:value or '(', found {found}", token.position)
+
+
+def parse_query(query: str) -> Filter:
+    """
+    Parse a filter expression.
+
+    Raises:
+        QueryError: The expression is malformed, or names an unknown field
+            or an invalid kind or visibility
+    """
+    return _Parser(query).parse()
+
+
+def query_nodes(symbols: Iterable[Dict[str, Any]], query: Union[str, Filter]) -> List[Dict[str, Any]]:
+    """
+    The symbols matching a filter expression, sorted by file, line and name.
+
+    Args:
+        symbols: Symbol summaries with name, type, file_path, line_no and visibility
+        query: Expression, or one parse_query returned
+    """
+    expression = parse_query(query) if isinstance(query, str) else query
+    found = [symbol for symbol in symbols if expression.matches(symbol)]
+    found.sort(key=lambda s: (s.get("file_path") or "", s.get("line_no") or 0, s.get("name") or ""))
+    return found
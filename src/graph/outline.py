@@ -0,0 +1,134 @@
+"""
+Hierarchical symbol outline of one file.
+
+Symbols nest along DEFINES edges: a class over its methods and fields, a
+Rust struct over its fields and the methods of its impl blocks, an enum
+over its variants and a variant over its fields. A symbol with no definer
+in the same file sits at the top level, which covers free functions as well
+as methods whose type is declared in another file. Siblings are ordered by
+start line, so the outline follows the source rather than the order the
+graph was built in.
+"""
+
+from dataclasses import dataclass, field
+from typing import Any, Dict, List, Optional
+
+# Node types that never appear in an outline
+EXCLUDED_TYPES = ("File", "Unresolved")
+
+
+@dataclass
+class OutlineEntry:
+    """One symbol of the outline and the symbols nested under it."""
+    id: str
+    kind: str
+    name: str
+    start_line: int
+    end_line: Optional[int] = None
+    visibility: Optional[str] = None
+    doc: Optional[str] = None
+    # Cyclomatic complexity of functions and methods whose parser records it
+    complexity: Optional[int] = None
+    # "named" or "default" for symbols an ES module exports, and the name importers use
+    export_type: Optional[str] = None
+    export_name: Optional[str] = None
+    # Declared type of a struct or enum variant field, as written
+    field_type: Optional[str] = None
+    children: List["OutlineEntry"] = field(default_factory=list)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "id": self.id,
+            "kind": self.kind,
+            "name": self.name,
+            "visibility": self.visibility,
+            "start_line": self.start_line,
+            "end_line": self.end_line,
+            "doc": self.doc,
+            "complexity": self.complexity,
+            "export_type": self.export_type,
+            "export_name": self.export_name,
+            "field_type": self.field_type,
+            "children": [child.to_dict() for child in self.children],
+        }
+
+
+def build_outline(nodes: List[Dict[str, Any]], defines: List[Dict[str, Any]]) -> List[OutlineEntry]:
+    """
+    Nest a file's symbols along their DEFINES edges.
+
+    Args:
+        nodes: Symbols of the file (id, type, name, line_no, end_line_no and
+            optionally visibility, doc, complexity, export_type, export_name
+            and field_type)
+        defines: DEFINES edges (source, target) ending at those symbols
+
+    Returns:
+        Top-level entries in source order, each with its children in source order
+    """
+    entries = {
+        node["id"]: OutlineEntry(
+            id=node["id"],
+            kind=node["type"],
+            name=node["name"],
+            start_line=node.get("line_no") or 0,
+            end_line=node.get("end_line_no"),
+            visibility=node.get("visibility"),
+            doc=node.get("doc"),
+            complexity=node.get("complexity"),
+            export_type=node.get("export_type"),
+            export_name=node.get("export_name"),
+            field_type=node.get("field_type"),
+        )
+        for node in nodes if node["type"] not in EXCLUDED_TYPES
+    }
+
+    # A symbol defined by several in-file symbols goes under the first one in source order
+    parents: Dict[str, str] = {}
+    for edge in defines:
+        source, target = edge["source"], edge["target"]
+        if source not in entries or target not in entries or source == target:
+            continue
+        current = parents.get(target)
+        if current is None or _order(entries[source]) < _order(entries[current]):
+            parents[target] = source
+
+    children: Dict[Optional[str], List[OutlineEntry]] = {}
+    for entry_id, entry in entries.items():
+        children.setdefault(parents.get(entry_id), []).append(entry)
+
+    # Walk down from the roots; anything left unvisited sits on a DEFINES
+    # cycle and is promoted to the top level instead of being lost
+    roots = children.get(None, [])
+    visited = set()
+    stack = list(roots)
+    while stack:
+        entry = stack.pop()
+        visited.add(entry.id)
+        entry.children = sorted(children.get(entry.id, []), key=_order)
+        stack.extend(entry.children)
+    for entry_id, entry in entries.items():
+        if entry_id not in visited:
+            visited.add(entry_id)
+            roots.append(entry)
+            entry.children = []
+    return sorted(roots, key=_order)
+
+
+def prune_outline(entries: List[OutlineEntry], min_complexity: int) -> List[OutlineEntry]:
+    """
+    Keep the entries with a complexity of at least min_complexity.
+
+    Entries without one (a class, or a function from a parser that does not
+    record it) stay only as the parents of entries that are kept.
+    """
+    kept = []
+    for entry in entries:
+        entry.children = prune_outline(entry.children, min_complexity)
+        if entry.children or (entry.complexity is not None and entry.complexity >= min_complexity):
+            kept.append(entry)
+    return kept
+
+
+def _order(entry: OutlineEntry):
+    return entry.start_line, entry.name, entry.id
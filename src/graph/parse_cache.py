@@ -0,0 +1,135 @@
+"""
+On-disk cache of per-file parse results, keyed by content.
+
+Each entry is the FileState a parse produced, before cross-file resolution,
+stored as JSON under a hash of the file's path and contents. A build that
+finds an entry for a file installs it instead of parsing; resolution then
+runs over every file as usual from the pending imports, calls and module
+definitions the entry carries, so edges into changed files are still
+recomputed.
+
+Entries live in a directory named after the cache format, a fingerprint of
+the parser sources and a hash of the parser options. Upgrading the parsers
+or switching options lands in a new directory, which leaves stale entries
+unread; ``prune`` deletes the directories left by older formats and parsers
+for the same options, and keeps those of other option sets, which builds
+with those options still read.
+"""
+
+import os
+import re
+import json
+import shutil
+import hashlib
+import logging
+import threading
+from functools import lru_cache
+from typing import Any, Dict, Optional
+
+from src.graph.code_graph import FileState
+
+logger = logging.getLogger(__name__)
+
+# Bump whenever the layout of an entry changes
+CACHE_FORMAT_VERSION = 1
+
+# Directories whose sources decide what a parse produces
+_PARSER_SOURCES = (
+    os.path.join(os.path.dirname(os.path.dirname(os.path.abspath(__file__))), "ast_parser"),
+    os.path.join(os.path.dirname(os.path.abspath(__file__)), "code_graph.py"),
+)
+
+_VERSION_DIR = re.compile(r"^v\d+-[0-9a-f]{16}-(?P<options>[0-9a-f]{16})$")
+# Directories of the layout before the options got their own hash, which nothing reads any more
+_LEGACY_VERSION_DIR = re.compile(r"^v\d+-[0-9a-f]{16}$")
+
+
+@lru_cache(maxsize=1)
+def parser_fingerprint() -> str:
+    """Hash of the parser sources, so that a code change invalidates the cache."""
+    digest = hashlib.sha256()
+    for source in _PARSER_SOURCES:
+        if os.path.isfile(source):
+            paths = [source]
+        else:
+            paths = sorted(os.path.join(root, name)
+                           for root, _, names in os.walk(source)
+                           for name in names if name.endswith(".py"))
+        for path in paths:
+            digest.update(os.path.relpath(path, os.path.dirname(source)).encode("utf-8"))
+            with open(path, "rb") as f:
+                digest.update(f.read())
+    return digest.hexdigest()
+
+
+class ParseCache:
+    """Parse results of single files, stored as one JSON file per path and content hash."""
+
+    def __init__(self, directory: str, options: Optional[Dict[str, Any]] = None):
+        """
+        Args:
+            directory: Root of the cache; created on the first write
+            options: Parser options that change what a parse produces
+        """
+        self.directory = directory
+        self.options_hash = hashlib.sha256(json.dumps(options or {}, sort_keys=True).encode("utf-8")).hexdigest()[:16]
+        self.version = f"v{CACHE_FORMAT_VERSION}-{parser_fingerprint()[:16]}-{self.options_hash}"
+        self.path = os.path.join(directory, self.version)
+        self.hits = 0
+        self.misses = 0
+        self._lock = threading.Lock()
+
+    def _entry(self, file_path: str, digest: str) -> str:
+        # Node IDs embed the path, so the same contents at another path are another entry
+        key = hashlib.sha256(f"{file_path}\0{digest}".encode("utf-8")).hexdigest()
+        return os.path.join(self.path, key[:2], f"{key}.json")
+
+    def get(self, file_path: str, digest: str) -> Optional[FileState]:
+        """The state stored for a file with this content hash, or None."""
+        try:
+            with open(self._entry(file_path, digest), "r", encoding="utf-8") as f:
+                state = FileState.from_dict(json.load(f))
+        except FileNotFoundError:
+            state = None
+        except (OSError, ValueError, KeyError, TypeError) as e:
+            logger.warning(f"Ignoring unreadable parse cache entry for {file_path}: {e}")
+            state = None
+        with self._lock:
+            if state is None:
+                self.misses += 1
+            else:
+                self.hits += 1
+        return state
+
+    def put(self, state: FileState) -> None:
+        """Store a freshly parsed state; failing to write only costs a later parse."""
+        if state.content_hash is None:
+            return
+        path = self._entry(state.file_path, state.content_hash)
+        temp = f"{path}.{os.getpid()}.{threading.get_ident()}.tmp"
+        try:
+            os.makedirs(os.path.dirname(path), exist_ok=True)
+            with open(temp, "w", encoding="utf-8") as f:
+                json.dump(state.to_dict(), f)
+            # Readers on other threads or processes never see half an entry
+            os.replace(temp, path)
+        except OSError as e:
+            logger.warning(f"Could not write parse cache entry for {state.file_path}: {e}")
+            if os.path.exists(temp):
+                os.remove(temp)
+
+    def prune(self) -> int:
+        """Delete the entries of other cache versions for the same options; returns how many directories went."""
+        try:
+            names = os.listdir(self.directory)
+        except FileNotFoundError:
+            return 0
+        removed = 0
+        for name in names:
+            match = _VERSION_DIR.match(name)
+            if name == self.version:
+                continue
+            if (match and match.group("options") == self.options_hash) or _LEGACY_VERSION_DIR.match(name):
+                shutil.rmtree(os.path.join(self.directory, name), ignore_errors=True)
+                removed += 1
+        return removed
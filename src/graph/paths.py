@@ -0,0 +1,17 @@
+"""
+File path helpers shared by the graph queries.
+
+Node file paths are compared as written in the graph, normalized but not
+made absolute, so a prefix given by a caller matches the paths the parser
+recorded.
+"""
+
+import os
+
+
+def path_under(file_path: str, prefix: str) -> bool:
+    """Whether a file is the prefix path or inside it, comparing whole path components."""
+    if not file_path:
+        return False
+    path = os.path.normpath(file_path)
+    return path == prefix or path.startswith(prefix.rstrip(os.sep) + os.sep)
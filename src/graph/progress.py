@@ -0,0 +1,88 @@
+"""
+Progress events of a graph build, for callers that show a progress bar.
+
+CodeGraph.build_with_progress sends one FileParsed event per file, as each
+file's parse finishes, then ResolutionStarted and ResolutionFinished around
+the cross-file resolution pass. Events go through a ProgressChannel, a
+bounded queue the build never waits on: when the reader falls behind, file
+events are dropped and counted, and a resolution marker makes room for
+itself by dropping the oldest queued event. Only events are dropped; the
+parsed files are installed in the graph whatever happens to the channel.
+"""
+
+import queue
+import threading
+from dataclasses import asdict, dataclass
+from typing import Any, Dict, List, Union
+
+# Events a channel holds before it starts dropping them
+DEFAULT_PROGRESS_BUFFER = 1024
+
+
+@dataclass
+class FileParsed:
+    """One file was parsed; ``index`` counts finished files from 1, in the order they finished."""
+    path: str
+    nodes: int
+    index: int
+    total: int
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"event": "file_parsed", **asdict(self)}
+
+
+@dataclass
+class ResolutionStarted:
+    """Every file is parsed and cross-file references are being resolved."""
+    files: int
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"event": "resolution_started", **asdict(self)}
+
+
+@dataclass
+class ResolutionFinished:
+    """The build is done; ``relations`` counts the graph's relations."""
+    files: int
+    relations: int
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"event": "resolution_finished", **asdict(self)}
+
+
+ProgressEvent = Union[FileParsed, ResolutionStarted, ResolutionFinished]
+
+
+class ProgressChannel:
+    """Bounded, thread-safe queue of progress events that never blocks the sender."""
+
+    def __init__(self, maxsize: int = DEFAULT_PROGRESS_BUFFER):
+        self._queue: "queue.Queue[ProgressEvent]" = queue.Queue(max(1, maxsize))
+        self._lock = threading.Lock()
+        self.dropped = 0
+
+    def send(self, event: ProgressEvent) -> None:
+        """Queue an event, dropping it (or, for a resolution marker, the oldest one) when full."""
+        with self._lock:
+            try:
+                self._queue.put_nowait(event)
+                return
+            except queue.Full:
+                if isinstance(event, FileParsed):
+                    self.dropped += 1
+                    return
+            try:
+                self._queue.get_nowait()
+                self.dropped += 1
+            except queue.Empty:
+                pass
+            self._queue.put_nowait(event)
+
+    def drain(self) -> List[ProgressEvent]:
+        """Every queued event, oldest first, removed from the channel."""
+        events = []
+        while True:
+            try:
+                events.append(self._queue.get_nowait())
+            except queue.Empty:
+                return events
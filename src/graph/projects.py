@@ -0,0 +1,104 @@
+"""
+Projects of a workspace indexed into one graph.
+
+A workspace is a set of roots, each a project named after its directory.
+Every node of a file under a root carries the project's name in its
+``project`` property. Node IDs already embed the file's full path, so two
+projects holding an ``orders/models.py`` never collide.
+
+Module names resolve within a project: ``import models`` never links to
+another project's ``models.py``. Imports across projects go through the
+package map, which names the root a package is imported from, e.g.
+``{"ledger_api": "/work/ledger"}``. Each file of that project is then also
+known by the package name followed by its path below the package's
+directory (or below the root when there is no such directory), in dotted
+and slashed form: ``ledger_api.entries`` and ``ledger_api/entries`` for
+``ledger/ledger_api/entries.py``.
+"""
+
+import os
+import re
+from dataclasses import dataclass
+from typing import Dict, List, Optional, Sequence
+
+# File stems that stand for their directory
+PACKAGE_FILES = ("__init__", "index")
+
+
+@dataclass(frozen=True)
+class Project:
+    """One root of a workspace."""
+    name: str
+    root: str
+
+
+class ProjectMap:
+    """Projects by root, and the project each mapped package comes from."""
+
+    def __init__(self, roots: Sequence[str], packages: Optional[Dict[str, str]] = None):
+        """
+        Args:
+            roots: Project root directories
+            packages: Project root by package name; every root must be one of ``roots``
+
+        Raises:
+            ValueError: Two roots share a directory name, or a package maps
+                to a directory that is not a root
+        """
+        self.projects: List[Project] = []
+        by_name: Dict[str, Project] = {}
+        for root in roots:
+            root = os.path.abspath(root)
+            if any(project.root == root for project in self.projects):
+                continue
+            project = Project(os.path.basename(root) or root, root)
+            if project.name in by_name:
+                raise ValueError(f"Workspace roots {by_name[project.name].root} and {root} "
+                                 f"are both named {project.name!r}")
+            by_name[project.name] = project
+            self.projects.append(project)
+
+        self.packages: Dict[str, Project] = {}
+        for package, root in (packages or {}).items():
+            project = next((p for p in self.projects if p.root == os.path.abspath(root)), None)
+            if project is None:
+                raise ValueError(f"Package {package!r} maps to {root}, which is not a workspace root")
+            self.packages[package] = project
+
+    def get(self, name: str) -> Optional[Project]:
+        """The project of a name, if there is one."""
+        return next((project for project in self.projects if project.name == name), None)
+
+    def project_of(self, file_path: str) -> Optional[Project]:
+        """The project whose root holds a file; the deepest root wins when roots nest."""
+        path = os.path.abspath(file_path)
+        found = None
+        for project in self.projects:
+            if _under(path, project.root) and (found is None or len(project.root) > len(found.root)):
+                found = project
+        return found
+
+    def package_modules(self, file_path: str) -> List[str]:
+        """Module names a file is imported by from other projects, through the packages mapped to its project."""
+        project = self.project_of(file_path)
+        if project is None:
+            return []
+        segments = os.path.splitext(os.path.relpath(os.path.abspath(file_path), project.root))[0].split(os.sep)
+        if segments[-1] in PACKAGE_FILES:
+            segments.pop()
+        names: List[str] = []
+        for package, target in self.packages.items():
+            if target != project:
+                continue
+            # The directory of the package, when the root holds one, is where its paths start
+            tail = re.split(r"[./]", package)[-1]
+            rest = segments[segments.index(tail) + 1:] if tail in segments else segments
+            for separator in (".", "/"):
+                name = separator.join([package] + rest)
+                if name not in names:
+                    names.append(name)
+        return names
+
+
+def _under(path: str, root: str) -> bool:
+    return path == root or path.startswith(root.rstrip(os.sep) + os.sep)
@@ -0,0 +1,155 @@
+"""
+Public API surface of a codebase, and the difference between two of them.
+
+The surface is every symbol whose visibility is ``Public`` (optionally also
+``Crate``), with its doc and the edges between such symbols. Symbols are
+identified by kind, file and qualified name (``Person.get_name`` for a
+method defined by Person), not by line, so moving code around does not
+change the surface. A surface can be saved as a JSON snapshot and compared
+with a later one to decide on a semver bump: anything removed or narrowed
+is breaking, anything only added is a feature.
+"""
+
+import json
+import os
+from typing import Any, Dict, Iterable, List, Optional
+
+# Version of the snapshot document; bump on any change to its shape
+SNAPSHOT_VERSION = 1
+
+PUBLIC = "Public"
+CRATE = "Crate"
+
+
+def _qualified_names(symbols: Dict[str, Dict[str, Any]], relationships: Iterable[Dict[str, Any]]) -> Dict[str, str]:
+    """Name of each symbol prefixed with the names of the symbols that define it."""
+    parent = {}
+    for rel in relationships:
+        if rel["type"] == "DEFINES" and rel["source"] in symbols and rel["target"] in symbols:
+            parent.setdefault(rel["target"], rel["source"])
+
+    names = {}
+    for node_id in symbols:
+        parts, current, seen = [], node_id, set()
+        while current is not None and current not in seen:
+            seen.add(current)
+            parts.append(symbols[current]["name"])
+            current = parent.get(current)
+        names[node_id] = ".".join(reversed(parts))
+    return names
+
+
+def item_key(item: Dict[str, Any]) -> str:
+    """Identity of an API item across snapshots."""
+    return f"{item['kind']}:{item['file_path']}:{item['qualified_name']}"
+
+
+def public_api(symbols: Iterable[Dict[str, Any]], relationships: Iterable[Dict[str, Any]],
+               include_crate: bool = False, root: Optional[str] = None,
+               path_prefix: Optional[str] = None) -> Dict[str, Any]:
+    """
+    Collect the public symbols and the edges between them.
+
+    Args:
+        symbols: Node summaries (id, name, type, file_path, line_no, visibility, doc)
+        relationships: Edges (source, target, type); DEFINES edges qualify names
+        include_crate: Also keep ``Crate`` symbols (Rust ``pub(crate)``,
+            Java package-private)
+        root: Report file paths relative to this directory
+        path_prefix: Only keep symbols in files starting with this path
+            (as reported, so relative to ``root`` when one is given)
+
+    Returns:
+        Dict with ``items`` sorted by file and line, each with id, kind,
+        name, qualified_name, file_path, line_no, visibility and doc, and
+        ``edges`` (source, target, type) between items
+    """
+    relationships = list(relationships)
+    by_id = {s["id"]: s for s in symbols}
+    qualified = _qualified_names(by_id, relationships)
+    allowed = (PUBLIC, CRATE) if include_crate else (PUBLIC,)
+
+    items = []
+    for node_id, symbol in by_id.items():
+        if symbol.get("visibility") not in allowed:
+            continue
+        file_path = symbol.get("file_path") or ""
+        if root and os.path.isabs(file_path):
+            file_path = os.path.relpath(file_path, root)
+        if path_prefix and not file_path.startswith(path_prefix):
+            continue
+        items.append({
+            "id": node_id,
+            "kind": symbol["type"],
+            "name": symbol["name"],
+            "qualified_name": qualified[node_id],
+            "file_path": file_path,
+            "line_no": symbol.get("line_no"),
+            "visibility": symbol["visibility"],
+            "doc": symbol.get("doc"),
+        })
+    items.sort(key=lambda i: (i["file_path"], i["line_no"] or 0, i["qualified_name"]))
+
+    kept = {item["id"] for item in items}
+    edges = sorted(
+        {(r["source"], r["target"], r["type"]) for r in relationships
+         if r["source"] in kept and r["target"] in kept and r["source"] != r["target"]}
+    )
+    return {
+        "items": items,
+        "edges": [{"source": s, "target": t, "type": kind} for s, t, kind in edges],
+    }
+
+
+def save_snapshot(api: Dict[str, Any], path: str) -> None:
+    """Write a surface to a JSON snapshot file."""
+    with open(path, "w", encoding="utf-8") as f:
+        json.dump({"schema_version": SNAPSHOT_VERSION, "items": api["items"]}, f,
+                  ensure_ascii=False, indent=2)
+
+
+def load_snapshot(path: str) -> List[Dict[str, Any]]:
+    """
+    Read the items of a snapshot file.
+
+    Raises:
+        OSError: The file cannot be read
+        ValueError: The file is not a snapshot of this version
+    """
+    with open(path, "r", encoding="utf-8") as f:
+        try:
+            document = json.load(f)
+        except json.JSONDecodeError as e:
+            raise ValueError(f"Not a public API snapshot: {path}: {e}") from None
+    if not isinstance(document, dict) or document.get("schema_version") != SNAPSHOT_VERSION:
+        raise ValueError(f"Unsupported public API snapshot version in {path}; expected {SNAPSHOT_VERSION}")
+    return document["items"]
+
+
+def diff_api(before: List[Dict[str, Any]], after: List[Dict[str, Any]]) -> Dict[str, Any]:
+    """
+    Compare two surfaces.
+
+    Returns:
+        Dict with ``added`` and ``removed`` items, ``changed`` items whose
+        visibility moved (with before and after), and ``suggested_bump``:
+        "major" when anything was removed or narrowed, "minor" when items
+        were only added or widened, otherwise "patch"
+    """
+    old = {item_key(i): i for i in before}
+    new = {item_key(i): i for i in after}
+    added = [new[k] for k in sorted(new.keys() - old.keys())]
+    removed = [old[k] for k in sorted(old.keys() - new.keys())]
+    changed = [
+        dict(new[k], visibility_before=old[k]["visibility"])
+        for k in sorted(old.keys() & new.keys()) if old[k]["visibility"] != new[k]["visibility"]
+    ]
+
+    narrowed = any(c["visibility_before"] == PUBLIC for c in changed)
+    if removed or narrowed:
+        bump = "major"
+    elif added or changed:
+        bump = "minor"
+    else:
+        bump = "patch"
+    return {"added": added, "removed": removed, "changed": changed, "suggested_bump": bump}
@@ -0,0 +1,283 @@
+"""
+Preview of renaming a symbol: the edits the rename needs and what could go wrong.
+
+The edits start from what the graph knows: the line declaring the symbol and
+the line of every usage find_usages reports (calls, type references, field
+accesses, imports, inheritance). Each becomes a patch replacing the
+whole-word occurrences of the old name in code on that line, leaving string
+literals and comments alone. The graph keeps one edge per pair of symbols,
+so the other occurrences in code within the using symbol (a second call in
+the same function) are patched too, and usages the graph has no line for,
+such as imports, are looked up in their whole file; both are marked
+``inferred``.
+
+Occurrences of the name inside string literals or comments of the files
+involved are not changed but reported as warnings, since ``getattr(obj,
+"name")`` or a registry keyed by the name breaks silently; so are
+occurrences in code on lines the graph does not link to the symbol, which
+may be a namesake or a use the parser did not follow. A symbol already
+called the new name in the same scope (the same class, struct or enum, or
+the same file for top-level symbols) is a collision.
+"""
+
+import re
+from dataclasses import asdict, dataclass
+from typing import Any, Callable, Dict, Iterable, List, Optional, Tuple
+
+from src.ast_parser.language_detector import detect_language
+from src.ast_parser.line_metrics import COMMENT_SYNTAX, C_SYNTAX
+from src.graph.code_graph import CodeGraph, UNRESOLVED_NODE_TYPE
+
+CODE, STRING, COMMENT = "code", "string", "comment"
+
+# Warning kind of an occurrence the rename leaves alone, by where it is
+WARNING_KINDS = {STRING: "string_literal", COMMENT: "comment", CODE: "unlinked_code"}
+
+
+@dataclass
+class RenamePatch:
+    """One line to change."""
+    file: str
+    line: int
+    old_text: str
+    proposed_new_text: str
+    # "definition" or "reference"
+    kind: str
+    # find_usages kind of the reference, e.g. "call"; None on the definition
+    usage_kind: Optional[str] = None
+    # Found by searching the file, as the graph has no line for the usage
+    inferred: bool = False
+
+    def to_dict(self) -> Dict[str, Any]:
+        return asdict(self)
+
+
+@dataclass
+class RenameWarning:
+    """An occurrence of the old name the rename leaves alone."""
+    file: str
+    line: int
+    column: int
+    text: str
+    # "string_literal", "comment" or "unlinked_code" (in code, on a line the
+    # graph does not link to the symbol: a namesake, or a use the parser missed)
+    kind: str
+
+    def to_dict(self) -> Dict[str, Any]:
+        return asdict(self)
+
+
+@dataclass
+class RenameImpact:
+    """Everything renaming a symbol touches."""
+    patches: List[RenamePatch]
+    warnings: List[RenameWarning]
+    # Summaries (id, name, type, file_path, line_no) of symbols already named the new name
+    collisions: List[Dict[str, Any]]
+    # Declarations and usages (as find_usages reports them, usage_kind
+    # "definition" for declarations) whose file could not be read and
+    # whose stored source line does not show the name
+    unlocated: List[Dict[str, Any]]
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "patches": [p.to_dict() for p in self.patches],
+            "warnings": [w.to_dict() for w in self.warnings],
+            "collisions": self.collisions,
+            "unlocated": self.unlocated,
+        }
+
+
+def occurrences(lines: List[str], name: str, language: Optional[str]) -> Dict[int, List[Tuple[int, str]]]:
+    """
+    Whole-word occurrences of a name in a file, and whether each is in code,
+    a string literal or a comment.
+
+    Args:
+        lines: Lines of the file, without line endings
+        name: Identifier to look for
+        language: Language identifier picking the comment and string syntax;
+            C-style syntax is assumed for languages not listed
+
+    Returns:
+        {line number (1-based): [(column (1-based), "code" | "string" | "comment")]}
+    """
+    syntax = COMMENT_SYNTAX.get(language, C_SYNTAX)
+    pattern = re.compile(rf"(?<![\w$]){re.escape(name)}(?![\w$])")
+    found: Dict[int, List[Tuple[int, str]]] = {}
+    open_token: Optional[str] = None
+    for line_no, line in enumerate(lines, 1):
+        regions, open_token = _regions(line, open_token, syntax)
+        matches = [(m.start() + 1, regions[m.start()]) for m in pattern.finditer(line)]
+        if matches:
+            found[line_no] = matches
+    return found
+
+
+def _regions(line: str, open_token: Optional[str], syntax) -> Tuple[List[str], Optional[str]]:
+    """Whether each character of a line is code, string or comment, and what is still open at its end."""
+    line_comment, block, quotes, multiline_quotes = syntax
+    regions = [CODE] * len(line)
+    i = 0
+    while i < len(line):
+        if block is not None and open_token == block[0]:
+            end = line.find(block[1], i)
+            stop = len(line) if end < 0 else end + len(block[1])
+            regions[i:stop] = [COMMENT] * (stop - i)
+            if end < 0:
+                return regions, open_token
+            i, open_token = stop, None
+            continue
+        if open_token is not None:
+            regions[i] = STRING
+            if line[i] == "\\":
+                regions[i:i + 2] = [STRING] * len(line[i:i + 2])
+                i += 2
+            elif line.startswith(open_token, i):
+                regions[i:i + len(open_token)] = [STRING] * len(open_token)
+                i, open_token = i + len(open_token), None
+            else:
+                i += 1
+            continue
+        if line.startswith(line_comment, i):
+            regions[i:] = [COMMENT] * (len(line) - i)
+            return regions, None
+        if block is not None and line.startswith(block[0], i):
+            regions[i:i + len(block[0])] = [COMMENT] * len(block[0])
+            i, open_token = i + len(block[0]), block[0]
+            continue
+        quote = next((q for q in quotes if line.startswith(q, i)), None)
+        if quote is not None:
+            regions[i:i + len(quote)] = [STRING] * len(quote)
+            i, open_token = i + len(quote), quote
+            continue
+        i += 1
+    if open_token in quotes and open_token not in multiline_quotes:
+        # An unterminated one-line string ends with its line
+        open_token = None
+    return regions, open_token
+
+
+def _rename(line: str, name: str, new_name: str, columns: Iterable[int]) -> str:
+    """Replace the occurrences of name starting at the given 1-based columns."""
+    for column in sorted(columns, reverse=True):
+        start = column - 1
+        line = line[:start] + new_name + line[start + len(name):]
+    return line
+
+
+def find_collisions(graph: CodeGraph, target_id: str, new_name: str) -> List[Dict[str, Any]]:
+    """Symbols named new_name in the scope of a target: its definer, or its file when it has none."""
+    target = graph.nodes[target_id]
+    definers = {}
+    for relation in graph.relations:
+        if relation.relation_type == "DEFINES":
+            definers.setdefault(relation.target_id, relation.source_id)
+    scope = definers.get(target_id)
+
+    collisions = []
+    for node_id, node in graph.nodes.items():
+        if node_id == target_id or node.name != new_name or node.node_type in ("File", UNRESOLVED_NODE_TYPE):
+            continue
+        if definers.get(node_id) == scope and (scope is not None or node.file_path == target.file_path):
+            collisions.append({"id": node_id, "name": node.name, "type": node.node_type,
+                               "file_path": node.file_path, "line_no": node.line_no})
+    return sorted(collisions, key=lambda c: (c["file_path"], c["line_no"] or 0, c["id"]))
+
+
+def rename_impact(graph: CodeGraph, target_ids: List[str], usages: List[Dict[str, Any]], new_name: str,
+                  read_source: Callable[[str], Optional[str]]) -> RenameImpact:
+    """
+    Work out the patches, warnings and collisions of renaming symbols.
+
+    Args:
+        graph: Graph holding the targets
+        target_ids: Node IDs of the symbols being renamed; they share a name
+        usages: Usages of the targets as find_usages reports them (file,
+            line, usage_kind, context_snippet and the using node as source)
+        new_name: Name to rename to
+        read_source: Returns the text of a file, or None when it cannot be read
+
+    Returns:
+        Patches and warnings sorted by file and line, and the collisions
+    """
+    name = graph.nodes[target_ids[0]].name
+    # Node ID and span of each declaration, per file
+    definitions: Dict[str, List[Tuple[str, int, int]]] = {}
+    for target_id in target_ids:
+        node = graph.nodes[target_id]
+        definitions.setdefault(node.file_path, []).append(
+            (target_id, node.line_no, node.end_line_no or node.line_no))
+    by_file: Dict[str, List[Dict[str, Any]]] = {}
+    for usage in usages:
+        by_file.setdefault(usage["file"], []).append(usage)
+
+    files = sorted(set(definitions) | set(by_file))
+    patches: List[RenamePatch] = []
+    warnings: List[RenameWarning] = []
+    unlocated: List[Dict[str, Any]] = []
+    for file_path in files:
+        source = read_source(file_path)
+        if source is None:
+            # Without the file only the stored source line of a usage can be patched
+            for target_id, start, _ in definitions.get(file_path, []):
+                unlocated.append({"symbol_id": target_id, "file": file_path, "line": start,
+                                  "context_snippet": None, "usage_kind": "definition"})
+            for usage in by_file.get(file_path, []):
+                snippet = usage.get("context_snippet")
+                found = occurrences([snippet], name, detect_language(file_path)).get(1) if snippet else None
+                code = [column for column, where in found or [] if where == CODE]
+                if code and usage.get("line"):
+                    patches.append(RenamePatch(file_path, usage["line"], snippet,
+                                               _rename(snippet, name, new_name, code),
+                                               "reference", usage["usage_kind"]))
+                else:
+                    unlocated.append(usage)
+            continue
+
+        lines = source.replace("\r\n", "\n").replace("\r", "\n").split("\n")
+        found = occurrences(lines, name, detect_language(file_path))
+        code_lines = sorted(line for line, matches in found.items() if any(where == CODE for _, where in matches))
+        # Line -> (kind, usage_kind, inferred) of the edit wanted there
+        edits: Dict[int, Tuple[str, Optional[str], bool]] = {}
+        for _, start, end in definitions.get(file_path, []):
+            # The name is on the first line of the declaration, unless attributes or decorators come first
+            line = next((line for line in code_lines if start <= line <= end), None)
+            if line is not None:
+                edits[line] = ("definition", None, False)
+        # (first line, last line, usage kind) of the spans searched for further references
+        searched: List[Tuple[int, int, str]] = []
+        for usage in by_file.get(file_path, []):
+            line = usage.get("line")
+            if line in code_lines:
+                edits.setdefault(line, ("reference", usage["usage_kind"], False))
+            source = graph.nodes.get((usage.get("source") or {}).get("id"))
+            if not line:
+                searched.append((1, len(lines), usage["usage_kind"]))
+            elif source is not None and source.node_type != "File" and source.line_no:
+                searched.append((source.line_no, source.end_line_no or source.line_no, usage["usage_kind"]))
+        # The narrowest span containing a line gives its usage kind
+        for start, end, usage_kind in sorted(searched, key=lambda span: span[1] - span[0]):
+            for line in code_lines:
+                if start <= line <= end:
+                    edits.setdefault(line, ("reference", usage_kind, True))
+
+        for line, matches in sorted(found.items()):
+            code = [column for column, where in matches if where == CODE]
+            if line in edits and code:
+                kind, usage_kind, inferred = edits[line]
+                text = lines[line - 1]
+                patches.append(RenamePatch(file_path, line, text, _rename(text, name, new_name, code),
+                                           kind, usage_kind, inferred))
+            for column, where in matches:
+                if where != CODE or line not in edits:
+                    warnings.append(RenameWarning(file_path, line, column, lines[line - 1].strip(),
+                                                  WARNING_KINDS[where]))
+
+    collisions = {c["id"]: c for target_id in target_ids for c in find_collisions(graph, target_id, new_name)}
+    return RenameImpact(
+        sorted(patches, key=lambda p: (p.file, p.line)),
+        sorted(warnings, key=lambda w: (w.file, w.line, w.column)),
+        sorted(collisions.values(), key=lambda c: (c["file_path"], c["line_no"] or 0, c["id"])),
+        unlocated,
+    )
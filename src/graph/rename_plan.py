@@ -0,0 +1,307 @@
+"""
+Planned edits of renaming one symbol, for a client to apply.
+
+Where the rename_impact preview patches whole lines and leaves doubtful
+occurrences to the reader, a plan gives the exact span of every occurrence
+to replace, and is only made when nothing would be left behind:
+
+- the name in the symbol's declaration, at the column its parser recorded,
+  or else its first occurrence in code within the declaration's lines
+- the name at each reference the graph resolved to the symbol (the edges
+  find_usages reports), searched on the line of the edge; an edge without
+  a line is searched in the span of the using symbol, or in the whole file
+  for a file-level import. A call edge stands for every call from its
+  caller, so the caller's span is searched too. Only occurrences of the
+  reference's form count: ``self.name`` or ``this.name`` for a field
+  access, the name followed by its arguments for a call, and any
+  occurrence in code otherwise
+
+String literals and comments are never edited. A rename is refused, with
+what blocks it, when:
+
+- a reference holds no occurrence of its form where it was searched, or
+  its file cannot be read
+- a call or other usage the graph could not resolve ends in the name
+  (``obj.name()`` on a receiver of unknown type), in a file of the
+  symbol's language
+- for a field, ``x.name`` appears in code of a file of its language on a
+  receiver the graph did not type, unless that line is linked to another
+  field of the name
+
+Uses the parsers do not record at all, such as Rust struct literals
+(``Person { name, age }``), are not found.
+"""
+
+import re
+from dataclasses import asdict, dataclass
+from typing import Any, Callable, Dict, List, Optional, Set, Tuple
+
+from src.ast_parser.language_detector import detect_language
+from src.graph.code_graph import CodeGraph, UNRESOLVED_NODE_TYPE
+from src.graph.rename_impact import CODE, occurrences
+
+# Edges into a symbol that name it in code, and the usage kind find_usages gives them
+REFERENCE_RELATIONS = {
+    "CALLS": "call",
+    "USES_TYPE": "type_reference",
+    "REFERENCES": "field_access",
+    "IMPORTS": "import",
+    "IMPORTS_DEFINITION": "import",
+    "REEXPORTS": "import",
+    "EXTENDS": "inherit",
+    "IMPLEMENTS": "inherit",
+    "SATISFIES": "inherit",
+    "DERIVE_USE": "derive",
+}
+
+# Text in front of a field accessed through the receiver's own reference
+SELF_ACCESS = re.compile(r"(?<![\w$])(?:self|this)\s*(?:\.|->)\s*$")
+
+# Text in front of a member access on any receiver
+MEMBER_ACCESS = re.compile(r"(?:\.|->)\s*$")
+
+# Text after a called name: optional turbofish, macro bang, then the arguments
+CALL_ARGUMENTS = re.compile(r"\s*(?:::\s*<[^()]*>\s*)?!?\s*\(")
+
+# Separators of the segments of an unresolved path (obj.name, fmt::Display, p->name)
+PATH_SEPARATORS = re.compile(r"\.|::|->")
+
+
+@dataclass
+class RenameEdit:
+    """One occurrence of the old name to replace."""
+    file: str
+    line: int
+    # 1-based character column of the name
+    column: int
+    old_text: str
+    new_text: str
+    # "definition" or "reference"
+    kind: str
+    # find_usages kind of the reference, e.g. "call"; None on the definition
+    usage_kind: Optional[str] = None
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"file": self.file,
+                "span": {"start": {"line": self.line, "column": self.column},
+                         "end": {"line": self.line, "column": self.column + len(self.old_text)}},
+                "old_text": self.old_text, "new_text": self.new_text,
+                "kind": self.kind, "usage_kind": self.usage_kind}
+
+
+@dataclass
+class RenameBlocker:
+    """A reference the plan cannot account for."""
+    file: str
+    line: Optional[int]
+    # "unlocated_reference", "unresolved_reference" or "untyped_access"
+    reason: str
+    # The source line, or the unresolved path
+    text: Optional[str] = None
+    # Node the reference comes from, when the graph has it
+    source_id: Optional[str] = None
+
+    def to_dict(self) -> Dict[str, Any]:
+        return asdict(self)
+
+
+class RenameRefused(ValueError):
+    """A rename that would leave references to the old name behind."""
+
+    def __init__(self, symbol_id: str, blockers: List[RenameBlocker]):
+        super().__init__(f"Cannot rename {symbol_id}: {len(blockers)} reference(s) could not be resolved")
+        self.symbol_id = symbol_id
+        self.blockers = blockers
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"code": "unresolved_references", "symbol_id": self.symbol_id,
+                "blockers": [b.to_dict() for b in self.blockers]}
+
+
+@dataclass
+class RenamePlan:
+    """Every edit renaming a symbol needs."""
+    symbol_id: str
+    old_name: str
+    new_name: str
+    edits: List[RenameEdit]
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"symbol_id": self.symbol_id, "old_name": self.old_name, "new_name": self.new_name,
+                "files": sorted({e.file for e in self.edits}), "total": len(self.edits),
+                "edits": [e.to_dict() for e in self.edits]}
+
+
+class _Source:
+    """Lines of a file and the occurrences of the name in it, read once."""
+
+    def __init__(self, file_path: str, text: str, name: str):
+        self.lines = text.replace("\r\n", "\n").replace("\r", "\n").split("\n")
+        self.found = occurrences(self.lines, name, detect_language(file_path))
+
+    def code(self, line: int) -> List[int]:
+        return [column for column, where in self.found.get(line, []) if where == CODE]
+
+
+def plan_rename(graph: CodeGraph, symbol_id: str, new_name: str,
+                read_source: Callable[[str], Optional[str]]) -> RenamePlan:
+    """
+    Plan the edits renaming a symbol.
+
+    Args:
+        graph: Graph holding the symbol, the edges into it and the Unresolved
+            placeholders, with the ``column`` of nodes that have one
+        symbol_id: Node ID of the symbol
+        new_name: Name to rename to
+        read_source: Returns the text of a file, or None when it cannot be read
+
+    Returns:
+        The edits sorted by file, line and column
+
+    Raises:
+        KeyError: The symbol is not in the graph
+        RenameRefused: Some reference could not be located or resolved
+    """
+    target = graph.nodes[symbol_id]
+    name = target.name
+    sources: Dict[str, Optional[_Source]] = {}
+
+    def source_of(file_path: str) -> Optional[_Source]:
+        if file_path not in sources:
+            text = read_source(file_path)
+            sources[file_path] = _Source(file_path, text, name) if text is not None else None
+        return sources[file_path]
+
+    edits: Dict[Tuple[str, int, int], RenameEdit] = {}
+    blockers: List[RenameBlocker] = []
+
+    source = source_of(target.file_path)
+    column = _declaration_column(target, source) if source is not None else None
+    if column is None:
+        blockers.append(RenameBlocker(target.file_path, target.line_no, "unlocated_reference", source_id=symbol_id))
+    else:
+        edits[(target.file_path, column[0], column[1])] = RenameEdit(
+            target.file_path, column[0], column[1], name, new_name, "definition")
+
+    for relation in graph.relations:
+        if relation.target_id != symbol_id or relation.relation_type not in REFERENCE_RELATIONS:
+            continue
+        user = graph.nodes.get(relation.source_id)
+        if user is None:
+            continue
+        usage_kind = REFERENCE_RELATIONS[relation.relation_type]
+        line_no = relation.properties.get("line_no")
+        source = source_of(user.file_path)
+        found = _reference_columns(relation, user, source, name) if source is not None else []
+        if not found:
+            blockers.append(RenameBlocker(user.file_path, line_no or user.line_no or None, "unlocated_reference",
+                                          relation.properties.get("call_site"), user.node_id))
+        for line, column in found:
+            edits.setdefault((user.file_path, line, column), RenameEdit(
+                user.file_path, line, column, name, new_name, "reference", usage_kind))
+
+    language = detect_language(target.file_path)
+    blockers.extend(_unresolved_references(graph, target, language))
+    if target.node_type == "Field":
+        blockers.extend(_untyped_accesses(graph, target, language, edits, source_of))
+    if blockers:
+        raise RenameRefused(symbol_id, blockers)
+    return RenamePlan(symbol_id, name, new_name, [edits[key] for key in sorted(edits)])
+
+
+def _declaration_column(node, source: _Source) -> Optional[Tuple[int, int]]:
+    """Line and column of the name in a declaration."""
+    line, column = node.line_no, node.properties.get("column")
+    if column and column in source.code(line):
+        return line, column
+    for line in range(node.line_no, (node.end_line_no or node.line_no) + 1):
+        code = source.code(line)
+        if code:
+            return line, code[0]
+    return None
+
+
+def _reference_columns(relation, user, source: _Source, name: str) -> List[Tuple[int, int]]:
+    """(line, column) of the occurrences of the name a reference stands for."""
+    line_no = relation.properties.get("line_no")
+    if line_no:
+        lines = [line_no]
+        if relation.relation_type == "CALLS" and user.node_type != "File" and user.line_no:
+            lines += range(user.line_no, (user.end_line_no or user.line_no) + 1)
+    elif user.node_type == "File" or not user.line_no:
+        lines = range(1, len(source.lines) + 1)
+    else:
+        lines = range(user.line_no, (user.end_line_no or user.line_no) + 1)
+
+    found = []
+    for line in sorted(set(lines)):
+        if line > len(source.lines):
+            continue
+        text = source.lines[line - 1]
+        for column in source.code(line):
+            before, after = text[:column - 1], text[column - 1 + len(name):]
+            if relation.relation_type == "REFERENCES" and "access" in relation.properties:
+                matches = SELF_ACCESS.search(before) is not None
+            elif relation.relation_type == "CALLS":
+                matches = CALL_ARGUMENTS.match(after) is not None
+            else:
+                matches = True
+            if matches:
+                found.append((line, column))
+    return found
+
+
+def _unresolved_references(graph: CodeGraph, target, language: Optional[str]) -> List[RenameBlocker]:
+    """Edges to Unresolved placeholders whose path ends in the name, from files of the language."""
+    if target.node_type == "Field":
+        # Field accesses on untyped receivers are not recorded as edges; see _untyped_accesses
+        return []
+    blockers = []
+    for relation in graph.relations:
+        if relation.relation_type not in REFERENCE_RELATIONS:
+            continue
+        placeholder = graph.nodes.get(relation.target_id)
+        user = graph.nodes.get(relation.source_id)
+        if placeholder is None or placeholder.node_type != UNRESOLVED_NODE_TYPE or user is None:
+            continue
+        raw_name = relation.properties.get("raw_name") or placeholder.name
+        if PATH_SEPARATORS.split(raw_name)[-1] != target.name or detect_language(user.file_path) != language:
+            continue
+        blockers.append(RenameBlocker(user.file_path, relation.properties.get("line_no"), "unresolved_reference",
+                                      raw_name, user.node_id))
+    return sorted(blockers, key=lambda b: (b.file, b.line or 0))
+
+
+def _untyped_accesses(graph: CodeGraph, target, language: Optional[str],
+                      edits: Dict[Tuple[str, int, int], RenameEdit],
+                      source_of: Callable[[str], Optional[_Source]]) -> List[RenameBlocker]:
+    """``x.name`` accesses in files of the field's language that no edge accounts for."""
+    # Lines holding a declaration of, or a resolved access to, another field of the name
+    claimed: Set[Tuple[str, int]] = set()
+    for node in graph.nodes.values():
+        if node.node_id != target.node_id and node.name == target.name and node.node_type != "File":
+            claimed.add((node.file_path, node.line_no))
+    for relation in graph.relations:
+        other = graph.nodes.get(relation.target_id)
+        user = graph.nodes.get(relation.source_id)
+        if relation.relation_type in REFERENCE_RELATIONS and other is not None and user is not None \
+                and other.node_id != target.node_id and other.name == target.name:
+            claimed.add((user.file_path, relation.properties.get("line_no") or user.line_no))
+
+    blockers = []
+    files = sorted({n.file_path for n in graph.nodes.values() if n.node_type == "File"} | {target.file_path})
+    for file_path in files:
+        if detect_language(file_path) != language:
+            continue
+        source = source_of(file_path)
+        if source is None:
+            continue
+        for line in sorted(source.found):
+            text = source.lines[line - 1]
+            for column in source.code(line):
+                if (file_path, line, column) in edits or (file_path, line) in claimed:
+                    continue
+                before, after = text[:column - 1], text[column - 1 + len(target.name):]
+                if MEMBER_ACCESS.search(before) and not CALL_ARGUMENTS.match(after):
+                    blockers.append(RenameBlocker(file_path, line, "untyped_access", text.strip()))
+    return blockers
@@ -0,0 +1,62 @@
+"""
+A CodeGraph shared between threads.
+
+The MCP and LSP front ends answer queries from the same graph while a
+watcher or a reload may swap it. SharedGraph guards the graph with a
+readers-writer lock: any number of readers hold it at once, a writer holds
+it alone, and a waiting writer keeps new readers out so that a steady
+stream of queries cannot starve it.
+"""
+
+import threading
+from contextlib import contextmanager
+from typing import Iterator
+
+from src.graph.code_graph import CodeGraph
+
+
+class SharedGraph:
+    """CodeGraph behind a readers-writer lock."""
+
+    def __init__(self, graph: CodeGraph):
+        self._graph = graph
+        self._condition = threading.Condition()
+        self._readers = 0
+        self._writing = False
+        self._waiting_writers = 0
+
+    @contextmanager
+    def read(self) -> Iterator[CodeGraph]:
+        """Hold the graph for reading; other readers may hold it too."""
+        with self._condition:
+            while self._writing or self._waiting_writers:
+                self._condition.wait()
+            self._readers += 1
+        try:
+            yield self._graph
+        finally:
+            with self._condition:
+                self._readers -= 1
+                if not self._readers:
+                    self._condition.notify_all()
+
+    @contextmanager
+    def write(self) -> Iterator[CodeGraph]:
+        """Hold the graph alone, e.g. to update files in place."""
+        with self._condition:
+            self._waiting_writers += 1
+            while self._writing or self._readers:
+                self._condition.wait()
+            self._waiting_writers -= 1
+            self._writing = True
+        try:
+            yield self._graph
+        finally:
+            with self._condition:
+                self._writing = False
+                self._condition.notify_all()
+
+    def replace(self, graph: CodeGraph) -> None:
+        """Swap in another graph once the current readers are done."""
+        with self.write():
+            self._graph = graph
@@ -0,0 +1,70 @@
+"""
+Source snippets of indexed symbols.
+
+A node's span is recorded while its file is parsed (see
+``assign_spans``): lines, character columns and UTF-8 byte offsets. The
+snippet is cut out of the file's current bytes. While the file still hashes
+to the ``content_hash`` it was indexed with, the byte offsets give exactly
+the symbol's source. Once it has changed they may point anywhere, so the
+snippet is cut by line and column instead and marked ``stale``: a best
+effort that stays close to the symbol when the edit was elsewhere. The
+offsets of a transcoded file, UTF-16 or with a byte order mark, are into
+the UTF-8 encoding of its text, which the snippet is cut from.
+"""
+
+from typing import Any, Dict, List, Optional
+
+from src.ast_parser.positions import LineIndex, decode_source, source_encoding
+from src.graph.code_graph import content_hash
+
+
+def _lines(lines: LineIndex, first: int, last: int) -> List[str]:
+    return [lines.line(line_no) for line_no in range(max(1, first), last + 1)]
+
+
+def extract_snippet(data: bytes, span: Dict[str, Any], indexed_hash: Optional[str],
+                    context_lines: int = 0) -> Dict[str, Any]:
+    """
+    Cut a node's span out of its file.
+
+    Args:
+        data: Current bytes of the file
+        span: The node's line_no, end_line_no, column, end_column,
+            start_byte and end_byte, any of which may be missing
+        indexed_hash: content_hash of the file when it was indexed, if known
+        context_lines: Whole lines to add before and after the span
+
+    Returns:
+        Dict with the ``span`` used, the ``source`` in it, ``context_before``
+        and ``context_after`` lines and whether the file is ``stale``
+    """
+    text = decode_source(data)
+    utf8 = data if source_encoding(data) is None else text.encode("utf-8")
+    lines = LineIndex(text)
+    # A final line break ends the last line rather than starting an empty one
+    line_count = text.count("\n") + (0 if text.endswith("\n") else 1)
+    stale = indexed_hash is not None and content_hash(data) != indexed_hash
+
+    start_line = span.get("line_no") or 1
+    end_line = max(start_line, span.get("end_line_no") or start_line)
+    column = span.get("column") or 1
+    end_column = span.get("end_column") or len(lines.line(end_line)) + 1
+    start_byte, end_byte = span.get("start_byte"), span.get("end_byte")
+    if stale or start_byte is None or end_byte is None:
+        start_byte = lines.byte_offset(start_line, column)
+        end_byte = max(start_byte, lines.byte_offset(end_line, end_column))
+
+    snippet = {
+        "span": {"start_line": start_line, "start_column": column,
+                 "end_line": end_line, "end_column": end_column,
+                 "start_byte": start_byte, "end_byte": end_byte},
+        "source": utf8[start_byte:end_byte].decode("utf-8", errors="replace"),
+        "context_before": _lines(lines, start_line - context_lines, min(start_line - 1, line_count)),
+        "context_after": _lines(lines, end_line + 1, min(end_line + context_lines, line_count)),
+        "stale": stale,
+    }
+    if stale:
+        snippet["warning"] = "File changed since it was indexed; the snippet is cut by line and may not match the symbol"
+    if start_line > line_count:
+        snippet["warning"] = f"File now has {line_count} lines, fewer than the symbol's start line {start_line}"
+    return snippet
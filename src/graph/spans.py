@@ -0,0 +1,121 @@
+"""
+Position lookups over the source spans of one file's symbols.
+
+SpanIndex is a centered interval tree over the spans of a file's nodes, so
+the symbols enclosing a position are found in O(log n + k) rather than by
+scanning every node of the file. Positions are (line, column) pairs, both
+1-based. A node spans from its start line and ``column`` property to its
+end line and ``end_column`` property; parsers that record no columns give
+whole-line spans, and a node without an end line covers its start line.
+"""
+
+import sys
+from dataclasses import dataclass
+from typing import Iterable, List, Optional, Tuple
+
+from src.ast_parser.parser import CodeNode
+
+# (line, column), both 1-based
+Position = Tuple[int, int]
+
+# Node types that never answer a position lookup
+EXCLUDED_TYPES = ("File", "Unresolved")
+
+# Which of several nodes with the identical span wins: lower comes first
+KIND_PRIORITY = {
+    "Method": 0,
+    "Function": 1,
+    "Field": 2,
+    "ClassVariable": 3,
+    "LocalVariable": 4,
+    "Variable": 5,
+    "GlobalVariable": 6,
+    "Enum": 7,
+    "TypeAlias": 8,
+    "Class": 9,
+    "Interface": 10,
+    "Module": 11,
+}
+
+
+@dataclass(frozen=True)
+class Span:
+    """Inclusive range of positions covered by one node."""
+    start: Position
+    end: Position
+    node_id: str
+    kind: str
+
+    def contains(self, position: Position) -> bool:
+        return self.start <= position <= self.end
+
+    def innermost_key(self):
+        """Sort key putting nested spans before the spans around them."""
+        return (-self.start[0], -self.start[1], self.end,
+                KIND_PRIORITY.get(self.kind, len(KIND_PRIORITY)), self.node_id)
+
+
+def node_span(node: CodeNode) -> Optional[Span]:
+    """Span of a node, or None for files, placeholders and nodes without a line."""
+    if node.node_type in EXCLUDED_TYPES or not node.line_no:
+        return None
+    column = node.properties.get("column")
+    end_column = node.properties.get("end_column")
+    end_line = node.end_line_no or node.line_no
+    # end_column is exclusive; no columns means the whole first and last line
+    start = (node.line_no, column if column else 0)
+    end = (end_line, end_column - 1 if end_column else sys.maxsize)
+    if end < start:
+        return None
+    return Span(start, end, node.node_id, node.node_type)
+
+
+class _TreeNode:
+    """Spans overlapping a center point, and the subtrees entirely left and right of it."""
+
+    def __init__(self, spans: List[Span]):
+        endpoints = sorted(p for s in spans for p in (s.start, s.end))
+        self.center = endpoints[len(endpoints) // 2]
+        left = [s for s in spans if s.end < self.center]
+        right = [s for s in spans if s.start > self.center]
+        overlapping = [s for s in spans if s.start <= self.center <= s.end]
+        self.by_start = sorted(overlapping, key=lambda s: s.start)
+        self.by_end = sorted(overlapping, key=lambda s: s.end, reverse=True)
+        self.left = _TreeNode(left) if left else None
+        self.right = _TreeNode(right) if right else None
+
+
+class SpanIndex:
+    """Centered interval tree answering "which spans contain this position?"."""
+
+    def __init__(self, spans: Iterable[Span]):
+        spans = list(spans)
+        self._root = _TreeNode(spans) if spans else None
+        self.size = len(spans)
+
+    @classmethod
+    def from_nodes(cls, nodes: Iterable[CodeNode]) -> "SpanIndex":
+        return cls(span for span in map(node_span, nodes) if span is not None)
+
+    def containing(self, position: Position) -> List[Span]:
+        """Spans containing a position, innermost first."""
+        found: List[Span] = []
+        tree = self._root
+        while tree is not None:
+            if position < tree.center:
+                for span in tree.by_start:
+                    if span.start > position:
+                        break
+                    found.append(span)
+                tree = tree.left
+            elif position > tree.center:
+                for span in tree.by_end:
+                    if span.end < position:
+                        break
+                    found.append(span)
+                tree = tree.right
+            else:
+                found.extend(tree.by_start)
+                break
+        return sorted(found, key=Span.innermost_key)
+
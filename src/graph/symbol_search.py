@@ -0,0 +1,461 @@
+"""
+Ranked symbol search by name.
+
+A pattern is tried against each symbol name as plain text first and as a
+regular expression last, and every symbol is reported under the best kind
+of match it has: ``exact``, then ``prefix``, then ``boundary`` (the
+pattern is made of the starts of words of the name, split at camelCase
+humps and snake_case underscores, so ``pf`` and ``file`` both find
+``parse_file``), then ``substring``, then ``fuzzy`` (the pattern's
+characters appear in order, scored fzf-style, so ``prsn`` finds
+``Person``), then ``regex``. Plain matching ignores case unless asked not
+to. A pattern with a ``.`` or ``::`` is matched against ``Owner.member``
+instead, so ``Person.nam`` finds the ``name`` field of ``Person``. Patterns use
+ECMAScript syntax: ``/ab+c/i`` literals with their flags are accepted, and
+named groups ``(?<name>..)`` are translated for Python's ``re``.
+
+Equally good matches are ordered by kind (types before functions before
+locals), then by how deep their file is, then by shorter name. SymbolIndex
+keeps the names lower-cased and split into words ahead of the searches, and
+grouped by the set of characters they contain, so a search only looks at
+names that could match at all.
+"""
+
+import fnmatch
+import functools
+import heapq
+import itertools
+import os
+import re
+from dataclasses import dataclass
+from typing import Any, Dict, Iterable, List, Optional, Tuple
+
+from src.graph.paths import path_under
+
+# Base score per match kind; closer prefix, boundary, substring and fuzzy
+# matches add up to 0.1 (0.09 for boundary and fuzzy, so they stay below
+# prefix and substring)
+MATCH_SCORES = {
+    "exact": 1.0,
+    "prefix": 0.8,
+    "boundary": 0.7,
+    "substring": 0.6,
+    "fuzzy": 0.5,
+    "regex": 0.4,
+}
+
+# Score of an exact match in a different case, e.g. "getname" for "getName"
+EXACT_IGNORING_CASE = 0.95
+
+# Preferred node types between equally good matches, types first and locals
+# last; other types rank with the variables
+KIND_PREFERENCE = ("Class", "Interface", "Enum", "TypeAlias", "Module", "Function", "Method", "Field",
+                   "Variable", "GlobalVariable", "ClassVariable", "LocalVariable")
+KIND_RANKS = {kind: rank for rank, kind in enumerate(KIND_PREFERENCE)}
+
+# Separators between an owner and a member in a pattern; "::" is read as "."
+QUALIFIERS = ("::", ".")
+
+# A letter after one of these starts a word, as does an upper-case letter after a lower-case one
+WORD_SEPARATORS = frozenset("_-.:/$ ")
+
+# ECMAScript flags that have a Python equivalent; g and y only affect iteration
+JS_FLAGS = {"i": re.IGNORECASE, "m": re.MULTILINE, "s": re.DOTALL, "u": 0, "g": 0, "y": 0}
+
+# A regex literal: /body/flags
+REGEX_LITERAL = re.compile(r"^/(.+)/([a-z]*)$", re.DOTALL)
+
+# (?<name> opens a named group in ECMAScript; (?<= and (?<! are lookbehinds in both
+JS_NAMED_GROUP = re.compile(r"(?<!\\)\(\?<(?![=!])")
+
+# A pattern of word characters matches as a regex exactly where it matches as a substring
+PLAIN_PATTERN = re.compile(r"^\w*$")
+
+
+class PatternError(ValueError):
+    """A search pattern that does not compile, with where it went wrong."""
+
+    def __init__(self, pattern: str, message: str, position: Optional[int] = None):
+        super().__init__(f"Invalid pattern {pattern!r}: {message}")
+        self.pattern = pattern
+        self.message = message
+        self.position = position
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {"code": "invalid_pattern", "message": self.message,
+                "pattern": self.pattern, "position": self.position}
+
+
+@dataclass
+class SymbolMatch:
+    """A symbol and how well its name matched."""
+    symbol: Dict[str, Any]
+    match_kind: str
+    score: float
+
+    def to_dict(self) -> Dict[str, Any]:
+        return dict(self.symbol, match_kind=self.match_kind, score=self.score)
+
+
+def compile_pattern(pattern: str) -> "re.Pattern[str]":
+    """
+    Compile an ECMAScript-style regular expression.
+
+    Raises:
+        PatternError: The pattern or its flags are invalid
+    """
+    body, flags = pattern, 0
+    literal = REGEX_LITERAL.match(pattern)
+    if literal:
+        body = literal.group(1)
+        for flag in literal.group(2):
+            if flag not in JS_FLAGS:
+                raise PatternError(pattern, f"unknown flag {flag!r}")
+            flags |= JS_FLAGS[flag]
+    try:
+        return re.compile(JS_NAMED_GROUP.sub("(?P<", body), flags)
+    except re.error as e:
+        position = e.pos + (1 if literal else 0) if e.pos is not None else None
+        raise PatternError(pattern, e.msg, position) from e
+
+
+def fuzzy_score(name: str, needle: str, ignore_case: bool = True, lowered: Optional[str] = None) -> Optional[float]:
+    """
+    How well the characters of needle appear in order in name, from 0 to 1.
+
+    As in fzf, the shortest window holding the subsequence is scored: every
+    character counts, more so at the start of a word and right after the
+    previous one, and gaps inside the window lower the score.
+
+    Args:
+        name: Name to score
+        needle: Pattern, lower-cased when ignoring case
+        ignore_case: Compare with the lower-cased name
+        lowered: name.lower(), when already known
+
+    Returns:
+        The score, or None when needle is not a subsequence of name
+    """
+    if ignore_case:
+        text = lowered if lowered is not None else name.lower()
+    else:
+        text = name
+    if not needle or len(needle) > len(text):
+        return None
+    # Forward: where the first complete subsequence ends
+    found, end = 0, -1
+    for pos, ch in enumerate(text):
+        if ch == needle[found]:
+            found += 1
+            if found == len(needle):
+                end = pos
+                break
+    if end < 0:
+        return None
+    # Backward from there: the latest start, giving the shortest window
+    positions = []
+    remaining = len(needle) - 1
+    for pos in range(end, -1, -1):
+        if text[pos] == needle[remaining]:
+            positions.append(pos)
+            remaining -= 1
+            if remaining < 0:
+                break
+    positions.reverse()
+
+    points = 0
+    for index, pos in enumerate(positions):
+        points += 1
+        previous = name[pos - 1] if pos else ""
+        if not previous or previous in WORD_SEPARATORS or (previous.islower() and name[pos].isupper()):
+            points += 1
+        if index and positions[index - 1] == pos - 1:
+            points += 1
+    gaps = positions[-1] - positions[0] + 1 - len(positions)
+    return points / (3 * len(needle) - 1) * len(needle) / (len(needle) + gaps)
+
+
+def word_spans(name: str) -> List[Tuple[int, int]]:
+    """
+    (start, end) of the words of a name: separators split words, and so
+    does a capital after a lower-case letter or digit, or a capital followed
+    by a lower-case letter after other capitals ("HTTPServer" is HTTP, Server).
+    """
+    spans = []
+    start = None
+    for pos, ch in enumerate(name):
+        if ch in WORD_SEPARATORS:
+            if start is not None:
+                spans.append((start, pos))
+                start = None
+            continue
+        if start is not None and ch.isupper():
+            previous = name[pos - 1]
+            following = name[pos + 1] if pos + 1 < len(name) else ""
+            if previous.islower() or previous.isdigit() or (previous.isupper() and following.islower()):
+                spans.append((start, pos))
+                start = None
+        if start is None:
+            start = pos
+    if start is not None:
+        spans.append((start, len(name)))
+    return spans
+
+
+@functools.lru_cache(maxsize=64)
+def _without_separators(text: str) -> str:
+    return "".join(ch for ch in text if ch not in WORD_SEPARATORS)
+
+
+def boundary_match(text: str, needle: str, spans: List[Tuple[int, int]]) -> bool:
+    """
+    Whether needle, separators left out, is the starts of some words of
+    text in order, e.g. "pf" or "parsef" for "parse_file".
+
+    Args:
+        text: The name, lower-cased when ignoring case
+        needle: The pattern, in the same case as text
+        spans: word_spans of the name
+    """
+    needle = _without_separators(needle)
+    if not needle:
+        return False
+    # The first word still free once needle[:done] is covered, the earliest
+    # possible: a later one leaves fewer words for the rest
+    free: List[Optional[int]] = [None] * (len(needle) + 1)
+    free[0] = 0
+    for done in range(len(needle)):
+        if free[done] is None:
+            continue
+        if free[-1] is not None:
+            return True
+        first = needle[done]
+        for word in range(free[done], len(spans)):
+            start, end = spans[word]
+            if text[start] != first:
+                continue
+            taken = 0
+            while start + taken < end and done + taken < len(needle) and text[start + taken] == needle[done + taken]:
+                taken += 1
+                if free[done + taken] is None or free[done + taken] > word + 1:
+                    free[done + taken] = word + 1
+    return free[-1] is not None
+
+
+def match_name(name: str, pattern: str, ignore_case: bool = True, lowered: Optional[str] = None,
+               spans: Optional[List[Tuple[int, int]]] = None) -> Optional[Tuple[str, float]]:
+    """
+    Kind and score of the best plain match of a pattern against one name.
+
+    Args:
+        name: Name to match
+        pattern: Plain pattern
+        ignore_case: Compare lower-cased name and pattern
+        lowered: name.lower(), when already known
+        spans: word_spans(name), when already known
+    """
+    if ignore_case:
+        text, needle = (lowered if lowered is not None else name.lower()), pattern.lower()
+    else:
+        text, needle = name, pattern
+    if not needle:
+        return None
+    if needle in text:
+        if name == pattern:
+            return "exact", MATCH_SCORES["exact"]
+        if text == needle:
+            return "exact", EXACT_IGNORING_CASE
+        closeness = len(needle) / len(text)
+        if text.startswith(needle):
+            return "prefix", round(MATCH_SCORES["prefix"] + 0.1 * closeness, 4)
+    else:
+        # Word starts are a subsequence too, so names without one are out
+        fuzzy = fuzzy_score(name, needle, ignore_case, text)
+        if fuzzy is None:
+            return None
+    if boundary_match(text, needle, spans if spans is not None else word_spans(name)):
+        letters = sum(1 for ch in text if ch not in WORD_SEPARATORS)
+        return "boundary", round(MATCH_SCORES["boundary"] + 0.09 * len(_without_separators(needle)) / letters, 4)
+    if needle in text:
+        return "substring", round(MATCH_SCORES["substring"] + 0.1 * len(needle) / len(text), 4)
+    return "fuzzy", round(MATCH_SCORES["fuzzy"] + 0.09 * fuzzy, 4)
+
+
+def qualified_pattern(pattern: str) -> Optional[str]:
+    """The pattern as ``Owner.member`` when it names an owner, else None."""
+    if REGEX_LITERAL.match(pattern) or not any(q in pattern for q in QUALIFIERS):
+        return None
+    return pattern.replace("::", ".")
+
+
+def _char_mask(text: str) -> int:
+    """Bit set of the characters in text, folded onto 64 bits."""
+    mask = 0
+    for ch in set(text):
+        mask |= 1 << (ord(ch) & 63)
+    return mask
+
+
+# A name in a SymbolIndex: the name, lower-cased, its word_spans and the indexes of its symbols
+_IndexedName = Tuple[str, str, List[Tuple[int, int]], List[int]]
+
+
+@dataclass
+class SearchResults:
+    """The best matches of a search and how many there were in all."""
+    matches: List[SymbolMatch]
+    total: int
+
+
+class SymbolIndex:
+    """
+    Symbols indexed by name for repeated searches.
+
+    Every distinct name is compared once for all the symbols carrying it,
+    its lower-case form and words computed when the index is built. Names
+    are grouped by the characters they contain, so a plain pattern is only
+    compared with the groups holding all of its characters.
+    """
+
+    def __init__(self, symbols: Iterable[Dict[str, Any]]):
+        """
+        Args:
+            symbols: Node summaries with at least name, type and file_path,
+                and owner for members of a type
+        """
+        self.symbols = [symbol for symbol in symbols if symbol.get("name")]
+        # Tie-breaking sort key of every symbol
+        self._order = [self._order_key(symbol) for symbol in self.symbols]
+        self._by_name: Dict[str, List[int]] = {}
+        for index, symbol in enumerate(self.symbols):
+            self._by_name.setdefault(symbol["name"], []).append(index)
+        self._names = self._group(self._by_name.items())
+        qualified: Dict[str, List[int]] = {}
+        for index, symbol in enumerate(self.symbols):
+            if symbol.get("owner"):
+                qualified.setdefault(f"{symbol['owner']}.{symbol['name']}", []).append(index)
+        self._qualified = self._group(qualified.items())
+
+    def __len__(self) -> int:
+        return len(self.symbols)
+
+    @staticmethod
+    def _group(keys: Iterable[Tuple[str, List[int]]]) -> Dict[int, List[_IndexedName]]:
+        """Keys with their lower-case form, words and symbol indexes, by the character mask of the lower-case key."""
+        groups: Dict[int, List[_IndexedName]] = {}
+        for key, indexes in keys:
+            lowered = key.lower()
+            groups.setdefault(_char_mask(lowered), []).append((key, lowered, word_spans(key), indexes))
+        return groups
+
+    @staticmethod
+    def _order_key(symbol: Dict[str, Any]) -> Tuple:
+        file_path = (symbol.get("file_path") or "").replace("\\", "/")
+        name = symbol.get("name") or ""
+        return (KIND_RANKS.get(symbol.get("type"), KIND_RANKS["Variable"]), file_path.count("/"),
+                len(name), name, file_path, symbol.get("line_no") or 0)
+
+    def search(self, pattern: str, node_types: Optional[List[str]] = None, file_glob: Optional[str] = None,
+               path_prefix: Optional[str] = None, use_regex: bool = True, min_complexity: Optional[int] = None,
+               exact: bool = False, case_sensitive: bool = False, limit: Optional[int] = None,
+               project: Optional[str] = None) -> SearchResults:
+        """
+        Rank the symbols whose name matches a pattern.
+
+        Args:
+            pattern: Plain name, ``Owner.member`` or ECMAScript regular expression
+            node_types: Node types to keep; None keeps every type
+            file_glob: fnmatch pattern the symbol's file path must match
+            path_prefix: Keep only symbols in this file or directory
+            use_regex: Also try the pattern as a regular expression
+            min_complexity: Keep only symbols with at least this cyclomatic complexity
+            exact: Only report names equal to the pattern, case included
+            case_sensitive: Compare plain matches with case
+            limit: Most matches to return; None returns all of them
+            project: Keep only symbols of this workspace project
+
+        Returns:
+            Matches, best first; ties go to types before locals, then to
+            shallower files, then to shorter names, then by file and line
+
+        Raises:
+            PatternError: use_regex is set and the pattern does not compile
+        """
+        # (score, kind, symbol indexes) per matching name
+        found: List[Tuple[float, str, List[int]]] = []
+        if exact:
+            if pattern in self._by_name:
+                found.append((MATCH_SCORES["exact"], "exact", self._by_name[pattern]))
+        else:
+            regex = compile_pattern(pattern) if use_regex and not PLAIN_PATTERN.match(pattern) else None
+            qualified = qualified_pattern(pattern)
+            plain, groups = (qualified, self._qualified) if qualified is not None else (pattern, self._names)
+            wanted = _char_mask(plain.lower())
+            matched = set()
+            for mask, keys in groups.items():
+                if mask & wanted != wanted:
+                    continue
+                for key, lowered, spans, indexes in keys:
+                    match = match_name(key, plain, ignore_case=not case_sensitive, lowered=lowered, spans=spans)
+                    if match is not None:
+                        found.append((match[1], match[0], indexes))
+                        if regex is not None:
+                            matched.update(indexes)
+            if regex is not None:
+                # Regexes can match names with none of the pattern's characters
+                for name, indexes in self._by_name.items():
+                    if regex.search(name):
+                        rest = [index for index in indexes if index not in matched]
+                        if rest:
+                            found.append((MATCH_SCORES["regex"], "regex", rest))
+
+        prefix = os.path.normpath(path_prefix) if path_prefix else None
+
+        def keep(index: int) -> bool:
+            symbol = self.symbols[index]
+            if node_types is not None and symbol.get("type") not in node_types:
+                return False
+            if file_glob is not None and not fnmatch.fnmatch(symbol.get("file_path") or "", file_glob):
+                return False
+            if prefix is not None and not path_under(symbol.get("file_path") or "", prefix):
+                return False
+            if project is not None and symbol.get("project") != project:
+                return False
+            return min_complexity is None or (symbol.get("complexity") or 0) >= min_complexity
+
+        filtered = any(f is not None for f in (node_types, file_glob, prefix, min_complexity, project))
+        # Walk the scores from the best down, ordering the symbols within one score
+        found.sort(key=lambda entry: -entry[0])
+        matches: List[SymbolMatch] = []
+        total = 0
+        for score, entries in itertools.groupby(found, key=lambda entry: entry[0]):
+            entries = list(entries)
+            room = None if limit is None else limit - len(matches)
+            if room == 0 and not filtered:
+                # Past the limit, the other matches only need counting
+                total += sum(len(indexes) for _, _, indexes in entries)
+                continue
+            tier = [(index, kind) for _, kind, indexes in entries for index in indexes
+                    if not filtered or keep(index)]
+            total += len(tier)
+            if room is None:
+                tier.sort(key=lambda entry: self._order[entry[0]])
+            else:
+                tier = heapq.nsmallest(room, tier, key=lambda entry: self._order[entry[0]])
+            matches.extend(SymbolMatch(self.symbols[index], kind, score) for index, kind in tier)
+        return SearchResults(matches, total)
+
+
+def search_symbols(symbols: Iterable[Dict[str, Any]], pattern: str, node_types: Optional[List[str]] = None,
+                   file_glob: Optional[str] = None, use_regex: bool = True,
+                   min_complexity: Optional[int] = None, path_prefix: Optional[str] = None,
+                   exact: bool = False, case_sensitive: bool = False) -> List[SymbolMatch]:
+    """
+    Rank the symbols whose name matches a pattern, without keeping an index.
+
+    See SymbolIndex.search for the arguments and the order of the results.
+    """
+    return SymbolIndex(symbols).search(pattern, node_types=node_types, file_glob=file_glob,
+                                       path_prefix=path_prefix, use_regex=use_regex,
+                                       min_complexity=min_complexity, exact=exact,
+                                       case_sensitive=case_sensitive).matches
@@ -0,0 +1,139 @@
+"""
+Type hierarchy of a type: what it implements or extends, and what
+implements or extends it.
+
+Supertypes are followed along the IMPLEMENTS and EXTENDS edges leaving a
+type: the traits a Rust struct implements (derived ones included), the
+supertraits of a trait, the base classes and interfaces of a class.
+Subtypes are followed backwards along the same edges, so a trait's subtypes
+are the types implementing it and the traits extending it; a blanket impl
+(``impl<T: Display> Summary for T``) has the file holding it as subtype.
+Both directions are walked breadth first up to a depth, each type listed
+once at its shallowest depth; the types at that depth with supertypes or
+subtypes left unwalked make up the ``frontier``. Traits and types outside the index appear as
+their Unresolved placeholders, without going further.
+
+The result is a small subgraph: the types, each with the ``generic_bounds``
+of its type parameters when the parser recorded them, and the edges
+between them with their properties (``trait_path``, ``derived``,
+``blanket``, ``bounds``).
+"""
+
+from collections import deque
+from dataclasses import dataclass, field
+from typing import Any, Dict, List, Optional, Tuple
+
+from src.ast_parser.parser import CodeRelation
+from src.graph.code_graph import UNRESOLVED_NODE_TYPE, CodeGraph
+
+# Node types standing for a type; Unresolved covers traits of other crates
+TYPE_NODE_TYPES = ("Class", "Interface", "Enum", "Struct", UNRESOLVED_NODE_TYPE)
+
+HIERARCHY_RELATIONS = ("IMPLEMENTS", "EXTENDS")
+
+DEFAULT_TYPE_DEPTH = 2
+
+
+@dataclass
+class TypeEntry:
+    """A type of the hierarchy, and where it sits relative to the root."""
+    id: str
+    name: str
+    kind: str
+    file_path: str
+    line_no: int
+    # "root", "supertype" or "subtype"
+    role: str
+    depth: int
+    generic_bounds: Optional[Dict[str, List[str]]] = None
+
+    def to_dict(self) -> Dict[str, Any]:
+        data = {"id": self.id, "name": self.name, "type": self.kind, "file_path": self.file_path,
+                "line_no": self.line_no, "role": self.role, "depth": self.depth}
+        if self.generic_bounds:
+            data["generic_bounds"] = self.generic_bounds
+        return data
+
+
+@dataclass
+class TypeHierarchy:
+    """The supertypes and subtypes of one type, as nodes and edges."""
+    root: TypeEntry
+    max_depth: int
+    types: List[TypeEntry] = field(default_factory=list)
+    edges: List[CodeRelation] = field(default_factory=list)
+    # Types at max_depth with further types in the direction walked
+    frontier: List[str] = field(default_factory=list)
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "root": self.root.id,
+            "max_depth": self.max_depth,
+            "generic_bounds": self.root.generic_bounds or {},
+            "supertypes": [t.name for t in self.types if t.role == "supertype"],
+            "subtypes": [t.name for t in self.types if t.role == "subtype"],
+            "nodes": [t.to_dict() for t in [self.root] + self.types],
+            "edges": [{"source": r.source_id, "target": r.target_id, "type": r.relation_type,
+                       "properties": r.properties} for r in self.edges],
+        }
+
+
+def type_hierarchy(graph: CodeGraph, node_id: str, max_depth: int = DEFAULT_TYPE_DEPTH) -> TypeHierarchy:
+    """
+    Walk the supertypes and subtypes of a type.
+
+    Args:
+        graph: Graph with ``generic_bounds`` loaded on its nodes
+        node_id: ID of the type
+        max_depth: Levels walked each way, 1 for direct supertypes and subtypes
+
+    Returns:
+        The hierarchy, types in the order they were reached and edges
+        sorted, and the frontier where max_depth stopped the walk
+    """
+    up: Dict[str, List[CodeRelation]] = {}
+    down: Dict[str, List[CodeRelation]] = {}
+    for relation in graph.relations:
+        if relation.relation_type in HIERARCHY_RELATIONS and relation.source_id != relation.target_id:
+            up.setdefault(relation.source_id, []).append(relation)
+            down.setdefault(relation.target_id, []).append(relation)
+
+    hierarchy = TypeHierarchy(_entry(graph, node_id, "root", 0), max_depth)
+    edges: Dict[Tuple[str, str, str], CodeRelation] = {}
+    for role, links, end in (("supertype", up, "target_id"), ("subtype", down, "source_id")):
+        seen, stopped = {node_id}, []
+        queue = deque([(node_id, 0)])
+        while queue:
+            current, depth = queue.popleft()
+            if depth == max_depth:
+                stopped.append(current)
+                continue
+            for relation in sorted(links.get(current, []), key=lambda r: (getattr(r, end), r.relation_type)):
+                other = graph.nodes.get(getattr(relation, end))
+                if other is None or not _is_type(other, role):
+                    continue
+                edges.setdefault((relation.source_id, relation.target_id, relation.relation_type), relation)
+                if other.node_id in seen:
+                    continue
+                seen.add(other.node_id)
+                hierarchy.types.append(_entry(graph, other.node_id, role, depth + 1))
+                if other.node_type not in (UNRESOLVED_NODE_TYPE, "File"):
+                    queue.append((other.node_id, depth + 1))
+        for current in stopped:
+            if current not in hierarchy.frontier and any(
+                    getattr(r, end) not in seen and getattr(r, end) in graph.nodes
+                    and _is_type(graph.nodes[getattr(r, end)], role) for r in links.get(current, [])):
+                hierarchy.frontier.append(current)
+    hierarchy.edges = [edges[key] for key in sorted(edges)]
+    return hierarchy
+
+
+def _is_type(node, role: str) -> bool:
+    # A blanket impl's IMPLEMENTS edge leaves the file holding it
+    return node.node_type in TYPE_NODE_TYPES or (role == "subtype" and node.node_type == "File")
+
+
+def _entry(graph: CodeGraph, node_id: str, role: str, depth: int) -> TypeEntry:
+    node = graph.nodes[node_id]
+    return TypeEntry(node.node_id, node.name, node.node_type, node.file_path, node.line_no, role, depth,
+                     node.properties.get("generic_bounds"))
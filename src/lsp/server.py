@@ -0,0 +1,440 @@
+"""
+Language Server Protocol gateway over a CodeGraph, for editors without MCP.
+
+Only the subset needed to navigate code is spoken: ``initialize``,
+``initialized``, ``shutdown`` and ``exit``, plus three requests:
+
+- ``textDocument/definition``: where the symbol under the cursor is defined
+- ``textDocument/references``: every use of it the graph knows, i.e. the
+  calls, field accesses, type uses, imports and inheritance edges ending at
+  it, and with ``includeDeclaration`` its definition first
+- ``workspace/symbol``: symbols whose name contains the query, case
+  insensitively, names starting with it first
+
+Messages are JSON-RPC 2.0 framed by a ``Content-Length`` header, over any
+pair of byte streams (stdin and stdout with ``server.py --lsp``). LSP lines
+are 0-based where the graph's are 1-based, and columns are counted in the
+position encoding agreed on at ``initialize``, UTF-16 unless the client
+offers another one of src.ast_parser.positions.ENCODINGS.
+
+The graph records where a use is written by line, not always by column, so
+the symbol under the cursor is found from the text: the identifier at the
+position is matched against the names of the symbols that edges leaving
+that line point to, then against the symbols declared on that line. Paths
+in the graph that are not absolute are taken relative to the server's
+root, the indexed codebase.
+"""
+
+import json
+import logging
+import os
+import re
+from pathlib import Path
+from typing import Any, BinaryIO, Callable, Dict, Iterator, List, Optional, Set, Tuple
+from urllib.parse import urlparse
+from urllib.request import url2pathname
+
+from src.ast_parser.parser import CodeNode, CodeRelation
+from src.ast_parser.positions import CHARACTER_ENCODING, ENCODINGS, LineIndex
+from src.graph.code_graph import UNRESOLVED_NODE_TYPE, CodeGraph
+from src.graph.shared import SharedGraph
+
+logger = logging.getLogger(__name__)
+
+# JSON-RPC and LSP error codes
+PARSE_ERROR = -32700
+INVALID_REQUEST = -32600
+METHOD_NOT_FOUND = -32601
+INVALID_PARAMS = -32602
+INTERNAL_ERROR = -32603
+SERVER_NOT_INITIALIZED = -32002
+
+# Encoding of columns unless the client offers another at initialize
+DEFAULT_POSITION_ENCODING = "utf-16"
+
+# Relations from a use of a symbol to the symbol
+USAGE_RELATIONS = ("CALLS", "REFERENCES", "USES_TYPE", "IMPORTS_DEFINITION", "REEXPORTS",
+                   "EXTENDS", "IMPLEMENTS", "SATISFIES", "DERIVE_USE")
+
+# LSP SymbolKind of each node type listed by workspace/symbol
+SYMBOL_KINDS = {
+    "Package": 4,
+    "Class": 5,
+    "Method": 6,
+    "Field": 8,
+    "ClassVariable": 8,
+    "Enum": 10,
+    "Interface": 11,
+    "Function": 12,
+    "Macro": 12,
+    "GlobalVariable": 13,
+    "Variable": 13,
+    "TypeAlias": 5,
+    "Variant": 22,
+}
+
+# Most symbols a workspace/symbol response lists
+MAX_WORKSPACE_SYMBOLS = 200
+
+IDENTIFIER = re.compile(r"[\w$]+")
+
+
+class LspError(Exception):
+    """A request that gets an error response."""
+
+    def __init__(self, code: int, message: str):
+        super().__init__(message)
+        self.code = code
+        self.message = message
+
+
+def read_message(stream: BinaryIO) -> Optional[Any]:
+    """
+    Read one framed message.
+
+    Returns:
+        The decoded JSON body, or None at the end of the stream
+
+    Raises:
+        LspError: The header has no length or the body is not JSON
+    """
+    length = None
+    while True:
+        line = stream.readline()
+        if not line:
+            return None
+        line = line.strip()
+        if not line:
+            break
+        name, _, value = line.decode("ascii", "replace").partition(":")
+        if name.strip().lower() == "content-length":
+            try:
+                length = int(value.strip())
+            except ValueError:
+                length = None
+    if length is None:
+        raise LspError(PARSE_ERROR, "Missing Content-Length header")
+    body = stream.read(length)
+    if len(body) < length:
+        return None
+    try:
+        return json.loads(body.decode("utf-8"))
+    except ValueError as e:
+        raise LspError(PARSE_ERROR, f"Invalid JSON: {e}")
+
+
+def write_message(stream: BinaryIO, message: Dict[str, Any]) -> None:
+    """Write one message with its header and flush it."""
+    body = json.dumps(message, ensure_ascii=False, separators=(",", ":")).encode("utf-8")
+    stream.write(f"Content-Length: {len(body)}\r\n\r\n".encode("ascii") + body)
+    stream.flush()
+
+
+def path_to_uri(path: str) -> str:
+    return Path(os.path.abspath(path)).as_uri()
+
+
+def uri_to_path(uri: str) -> str:
+    parsed = urlparse(uri)
+    if parsed.scheme != "file":
+        raise LspError(INVALID_PARAMS, f"Not a file URI: {uri}")
+    return os.path.abspath(url2pathname(parsed.path))
+
+
+def _error(request_id: Any, code: int, message: str) -> Dict[str, Any]:
+    return {"jsonrpc": "2.0", "id": request_id, "error": {"code": code, "message": message}}
+
+
+def _written_names(relation: CodeRelation, target: CodeNode) -> Set[str]:
+    """Names a use of target may be written with: its own, an alias, the last part of a qualified name."""
+    names = {target.name}
+    for key in ("alias", "original_name", "raw_name"):
+        value = relation.properties.get(key)
+        if isinstance(value, str) and value:
+            names.add(re.split(r"[.:]+", value)[-1])
+    return names
+
+
+def _identifier_at(text: str, index: int) -> Optional[Tuple[int, int]]:
+    """Start and end index of the identifier covering a character index, or touching it from the left."""
+    for match in IDENTIFIER.finditer(text):
+        if match.start() <= index <= match.end():
+            return match.span()
+        if match.start() > index:
+            break
+    return None
+
+
+def _find_name(text: str, names: Set[str]) -> Optional[Tuple[int, int]]:
+    """Span of the first identifier of a line that is one of names."""
+    for match in IDENTIFIER.finditer(text):
+        if match.group() in names:
+            return match.span()
+    return None
+
+
+class _Documents:
+    """Source text of the files one request looks at, each read once."""
+
+    def __init__(self, root: str):
+        self.root = root
+        self._lines: Dict[str, Optional[LineIndex]] = {}
+        self._counts: Dict[str, int] = {}
+
+    def path(self, file_path: str) -> str:
+        return file_path if os.path.isabs(file_path) else os.path.join(self.root, file_path)
+
+    def lines(self, file_path: str) -> Optional[LineIndex]:
+        if file_path not in self._lines:
+            try:
+                with open(self.path(file_path), encoding="utf-8", newline="") as f:
+                    source = f.read()
+            except (OSError, UnicodeDecodeError):
+                self._lines[file_path], self._counts[file_path] = None, 0
+            else:
+                self._lines[file_path], self._counts[file_path] = LineIndex(source), source.count("\n") + 1
+        return self._lines[file_path]
+
+    def count(self, file_path: str) -> int:
+        self.lines(file_path)
+        return self._counts[file_path]
+
+    def line(self, file_path: str, line_no: int) -> str:
+        lines = self.lines(file_path)
+        return lines.line(line_no) if lines else ""
+
+
+class LspServer:
+    """Answers LSP requests from a SharedGraph, holding it for reading during each one."""
+
+    def __init__(self, graph: SharedGraph, root: str = "."):
+        """
+        Args:
+            graph: Graph to answer from, shared with whatever keeps it up to date
+            root: Directory the graph's relative paths start from
+        """
+        self.graph = graph
+        self.root = os.path.abspath(root)
+        self.encoding = DEFAULT_POSITION_ENCODING
+        self.initialized = False
+        self.shut_down = False
+        self.exited = False
+        self._handlers: Dict[str, Callable[[Dict[str, Any]], Any]] = {
+            "initialize": self._initialize,
+            "shutdown": self._shutdown,
+            "textDocument/definition": self._definition,
+            "textDocument/references": self._references,
+            "workspace/symbol": self._workspace_symbol,
+        }
+
+    def serve(self, reader: BinaryIO, writer: BinaryIO) -> int:
+        """
+        Answer messages until ``exit`` or the end of the input.
+
+        Returns:
+            The exit code LSP asks for: 0 when ``shutdown`` came first, 1 otherwise
+        """
+        while not self.exited:
+            try:
+                message = read_message(reader)
+            except LspError as e:
+                write_message(writer, _error(None, e.code, e.message))
+                continue
+            if message is None:
+                break
+            response = self.handle(message)
+            if response is not None:
+                write_message(writer, response)
+        return 0 if self.shut_down else 1
+
+    def handle(self, message: Any) -> Optional[Dict[str, Any]]:
+        """The response to one message, or None for a notification."""
+        if not isinstance(message, dict) or message.get("jsonrpc") != "2.0" \
+                or not isinstance(message.get("method"), str):
+            request_id = message.get("id") if isinstance(message, dict) else None
+            return _error(request_id, INVALID_REQUEST, "Not a JSON-RPC 2.0 request")
+        method = message["method"]
+        if method == "exit":
+            self.exited = True
+            return None
+        if "id" not in message:
+            # initialized and every other notification need no answer
+            return None
+
+        request_id = message["id"]
+        try:
+            if not self.initialized and method != "initialize":
+                raise LspError(SERVER_NOT_INITIALIZED, "The server is not initialized")
+            if self.shut_down:
+                raise LspError(INVALID_REQUEST, "The server is shut down")
+            handler = self._handlers.get(method)
+            if handler is None:
+                raise LspError(METHOD_NOT_FOUND, f"Unsupported method: {method}")
+            params = message.get("params") or {}
+            if not isinstance(params, dict):
+                raise LspError(INVALID_PARAMS, "params must be an object")
+            return {"jsonrpc": "2.0", "id": request_id, "result": handler(params)}
+        except LspError as e:
+            return _error(request_id, e.code, e.message)
+        except Exception as e:
+            logger.error(f"LSP request {method} failed: {e}")
+            return _error(request_id, INTERNAL_ERROR, str(e))
+
+    # ------------------------------------------------------------------
+    # Lifecycle
+    # ------------------------------------------------------------------
+
+    def _initialize(self, params: Dict[str, Any]) -> Dict[str, Any]:
+        if self.initialized:
+            raise LspError(INVALID_REQUEST, "The server is already initialized")
+        offered = ((params.get("capabilities") or {}).get("general") or {}).get("positionEncodings") or []
+        self.encoding = next((encoding for encoding in offered if encoding in ENCODINGS),
+                             DEFAULT_POSITION_ENCODING)
+        self.initialized = True
+        return {
+            "capabilities": {
+                "positionEncoding": self.encoding,
+                "definitionProvider": True,
+                "referencesProvider": True,
+                "workspaceSymbolProvider": True,
+            },
+            "serverInfo": {"name": "graph-codebase-mcp"},
+        }
+
+    def _shutdown(self, params: Dict[str, Any]) -> None:
+        self.shut_down = True
+        return None
+
+    # ------------------------------------------------------------------
+    # Requests
+    # ------------------------------------------------------------------
+
+    def _definition(self, params: Dict[str, Any]) -> Optional[Dict[str, Any]]:
+        documents = _Documents(self.root)
+        with self.graph.read() as graph:
+            node = self._symbol_at(graph, documents, params)
+            return None if node is None else self._declaration(documents, node)
+
+    def _references(self, params: Dict[str, Any]) -> List[Dict[str, Any]]:
+        documents = _Documents(self.root)
+        with self.graph.read() as graph:
+            node = self._symbol_at(graph, documents, params)
+            if node is None:
+                return []
+            locations = []
+            if (params.get("context") or {}).get("includeDeclaration"):
+                locations.append(self._declaration(documents, node))
+            uses = []
+            for relation, line_no in self._uses(graph, documents, graph.relations):
+                if relation.target_id != node.node_id:
+                    continue
+                source = graph.nodes[relation.source_id]
+                location = self._location(documents, source.file_path, line_no, _written_names(relation, node))
+                if location not in locations and location not in uses:
+                    uses.append(location)
+            uses.sort(key=lambda l: (l["uri"], l["range"]["start"]["line"], l["range"]["start"]["character"]))
+            return locations + uses
+
+    def _workspace_symbol(self, params: Dict[str, Any]) -> List[Dict[str, Any]]:
+        query = str(params.get("query") or "").lower()
+        documents = _Documents(self.root)
+        with self.graph.read() as graph:
+            found = [node for node in graph.nodes.values()
+                     if node.node_type in SYMBOL_KINDS and query in node.name.lower()]
+            found.sort(key=lambda n: (not n.name.lower().startswith(query), n.name.lower(),
+                                      n.file_path or "", n.line_no or 0, n.node_id))
+            symbols = []
+            for node in found[:MAX_WORKSPACE_SYMBOLS]:
+                symbol = {"name": node.name, "kind": SYMBOL_KINDS[node.node_type],
+                          "location": self._declaration(documents, node)}
+                qualified = node.properties.get("qualified_name") or ""
+                container = node.properties.get("method_of") or \
+                    (qualified[:-len(node.name)].rstrip(".:") if qualified.endswith(node.name) else "")
+                if container:
+                    symbol["containerName"] = container
+                symbols.append(symbol)
+            return symbols
+
+    # ------------------------------------------------------------------
+    # Positions
+    # ------------------------------------------------------------------
+
+    def _symbol_at(self, graph: CodeGraph, documents: _Documents,
+                   params: Dict[str, Any]) -> Optional[CodeNode]:
+        """The symbol the identifier at a text document position names."""
+        document, position = params.get("textDocument") or {}, params.get("position") or {}
+        if not isinstance(document.get("uri"), str) or not isinstance(position.get("line"), int) \
+                or not isinstance(position.get("character"), int):
+            raise LspError(INVALID_PARAMS, "Expected textDocument.uri and position.line/character")
+        file_path = self._indexed_path(graph, uri_to_path(document["uri"]))
+        if file_path is None:
+            return None
+        line_no = position["line"] + 1
+        lines = documents.lines(file_path)
+        if lines is None:
+            return None
+        column = lines.decode_column(line_no, position["character"] + 1, self.encoding)
+        span = _identifier_at(lines.line(line_no), column - 1)
+        if span is None:
+            return None
+        name = lines.line(line_no)[span[0]:span[1]]
+
+        state = graph.files[file_path]
+        for relation, use_line in self._uses(graph, documents, state.owned_relations()):
+            target = graph.nodes[relation.target_id]
+            if use_line == line_no and name in _written_names(relation, target):
+                return target
+        declared = [node for node in state.nodes.values()
+                    if node.line_no == line_no and node.name == name and node.node_type in SYMBOL_KINDS]
+        return min(declared, key=lambda n: n.node_id) if declared else None
+
+    def _uses(self, graph: CodeGraph, documents: _Documents,
+              relations: List[CodeRelation]) -> Iterator[Tuple[CodeRelation, int]]:
+        """
+        Relations from a use to a parsed symbol, with the line the use is on:
+        the one the relation records, else its source's, else, for a file
+        importing a name, the first line of the file the name is on.
+        """
+        for relation in relations:
+            if relation.relation_type not in USAGE_RELATIONS:
+                continue
+            source, target = graph.nodes.get(relation.source_id), graph.nodes.get(relation.target_id)
+            if source is None or target is None or target.node_type == UNRESOLVED_NODE_TYPE:
+                continue
+            line_no = relation.properties.get("line_no")
+            if not line_no and source.node_type != "File":
+                line_no = source.line_no
+            if not line_no:
+                line_no = self._first_line_naming(documents, source.file_path, _written_names(relation, target))
+            if line_no:
+                yield relation, line_no
+
+    @staticmethod
+    def _first_line_naming(documents: _Documents, file_path: str, names: Set[str]) -> Optional[int]:
+        for line_no in range(1, documents.count(file_path) + 1):
+            if _find_name(documents.line(file_path, line_no), names):
+                return line_no
+        return None
+
+    def _indexed_path(self, graph: CodeGraph, path: str) -> Optional[str]:
+        """The graph's key of a file, given its absolute path."""
+        for file_path in graph.files:
+            if os.path.abspath(os.path.join(self.root, file_path)) == path:
+                return file_path
+        return None
+
+    def _declaration(self, documents: _Documents, node: CodeNode) -> Dict[str, Any]:
+        return self._location(documents, node.file_path, node.line_no or 1, {node.name})
+
+    def _location(self, documents: _Documents, file_path: str, line_no: int, names: Set[str]) -> Dict[str, Any]:
+        """Location of the first of names on a line, or of the start of the line."""
+        start, end = _find_name(documents.line(file_path, line_no), names) or (0, 0)
+        lines = documents.lines(file_path)
+        if lines is not None and self.encoding != CHARACTER_ENCODING:
+            start = lines.encode_column(line_no, start + 1, self.encoding) - 1
+            end = lines.encode_column(line_no, end + 1, self.encoding) - 1
+        return {
+            "uri": path_to_uri(documents.path(file_path)),
+            "range": {"start": {"line": line_no - 1, "character": start},
+                      "end": {"line": line_no - 1, "character": end}},
+        }
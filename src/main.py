@@ -3,10 +3,13 @@ import sys
 import argparse
 import logging
 import time
-from typing import Dict, List, Any, Tuple, Optional
+import copy
+import threading
+import itertools
+from typing import Dict, Iterator, List, Any, Tuple, Optional, Union
 from dotenv import load_dotenv
 import json
-from concurrent.futures import as_completed
+from concurrent.futures import FIRST_COMPLETED, wait
 
 # Add project root to Python path
 project_root = os.path.abspath(os.path.join(os.path.dirname(__file__), '..'))
@@ -14,12 +17,30 @@ if project_root not in sys.path:
     sys.path.insert(0, project_root)
 
 from src.ast_parser.parser import ASTParser
+from src.ast_parser.diagnostics import (FILE_TOO_LARGE, UNSUPPORTED_LANGUAGE, ParseDiagnostics, ParseFailure,
+                                       has_declarations)
+from src.ast_parser.language_detector import detect_language, parsed_extension
 from src.ast_parser.multi_parser import MultiLanguageParser
+from src.ast_parser.path_filter import PathFilter, env_filter_options
+from src.ast_parser.positions import looks_binary
+from src.config import CONFIG_FILE, ConfigError, find_config, load_config
 from src.embeddings.factory import get_embedding_provider
 from src.embeddings.embedder import CodeEmbedder, OpenAIEmbeddings
 from src.neo4j_storage.graph_db import Neo4jDatabase
+from src.neo4j_storage.sqlite_db import SQLiteDatabase
+from src.neo4j_storage.store import DEFAULT_SQLITE_PATH, STORAGE_BACKENDS
+from src.graph.code_graph import CodeGraph, FileState, GraphDelta, relation_identity
+from src.graph.watcher import DEFAULT_DEBOUNCE, PollingWatcher, apply_changes
+from src.graph.incremental import IndexStats, StoredFile, reindex
+from src.graph.projects import ProjectMap
+from src.graph.cache import GraphCache
+from src.graph.export import export_main, parse_node_kinds
+from src.graph.cycles import cycles_main
+from src.graph.dead_code import dead_code_main
 from src.parallel.pool_manager import get_processing_pool
-from src.utils.runtime_detection import log_runtime_info
+from src.parallel.parse_worker import FileParseResult, create_parser, failed_result, merge_results, parse_file_task
+from src.parallel.streaming_merge import StreamingMerge, spill_threshold_for
+from src.utils.runtime_detection import get_optimal_worker_count, log_runtime_info, peak_memory_mb
 
 load_dotenv()
 
@@ -37,6 +58,22 @@ class CodebaseKnowledgeGraph:
         neo4j_user: Optional[str] = None,
         neo4j_password: Optional[str] = None,
         openai_api_key: Optional[str] = None,
+        respect_gitignore: Optional[bool] = None,
+        exclude: Optional[List[str]] = None,
+        cache_db: Optional[str] = None,
+        jobs: Optional[int] = None,
+        storage: Optional[str] = None,
+        db_path: Optional[str] = None,
+        batch_size: Optional[int] = None,
+        use_ast_grep: Optional[bool] = None,
+        ast_grep_languages: Optional[List[str]] = None,
+        ast_grep_fallback: Optional[bool] = None,
+        include: Optional[List[str]] = None,
+        max_file_size: Optional[int] = None,
+        language_options: Optional[Dict[str, Dict[str, Any]]] = None,
+        language_overrides: Optional[Dict[str, str]] = None,
+        max_memory_mb: Optional[int] = None,
+        strict: bool = False,
     ):
         """Initialize the Codebase Knowledge Graph
         
@@ -44,6 +81,33 @@ class CodebaseKnowledgeGraph:
             neo4j_uri: Neo4j database URI, if None, get from environment variables
             neo4j_user: Neo4j username, if None, get from environment variables
             neo4j_password: Neo4j password, if None, get from environment variables
+            respect_gitignore: Skip paths ignored by .gitignore, if None, get from RESPECT_GITIGNORE
+            exclude: Glob patterns of paths to skip, if None, get from EXCLUDE_PATTERNS
+            cache_db: SQLite file caching the parsed graph between runs, if None, no cache
+            jobs: Number of parse workers, if None, get from MAX_WORKERS or the CPU count;
+                  1 parses sequentially
+            storage: Graph storage backend, "neo4j" or "sqlite", if None, get from
+                     STORAGE_BACKEND or use neo4j
+            db_path: SQLite database file of the sqlite backend, if None, get from
+                     SQLITE_DB_PATH or use codegraph.db
+            batch_size: Rows per Neo4j write statement, if None, get from
+                        NEO4J_BATCH_SIZE or use 1000
+            use_ast_grep: Parse with the ast-grep adapters, if None, get from USE_AST_GREP
+            ast_grep_languages: Languages the ast-grep adapters parse, if None, get from
+                                AST_GREP_LANGUAGES
+            ast_grep_fallback: Fall back to the legacy parsers on adapter errors, if None,
+                               get from AST_GREP_FALLBACK_TO_LEGACY
+            include: Glob patterns a file must match to be indexed, if None, index every file
+            max_file_size: Size in bytes above which files are skipped, if None, no limit
+            language_options: Parser options by language, e.g. {"rust": {"index_private": False}}
+            language_overrides: Language by glob, parsing matching files as that language
+                                whatever their extension, e.g. {"*.h": "cpp"}
+            max_memory_mb: Memory budget hint; when set, a full index without an
+                           in-memory graph writes each file as soon as it is parsed
+                           and spills pending cross-file references and
+                           relationships to disk; the symbol table stays in memory
+            strict: Abort the run at the first file that fails to parse, instead of
+                    indexing the rest and reporting the failures at the end
         """
         self.neo4j_uri = neo4j_uri or os.environ.get("NEO4J_URI")
         self.neo4j_user = neo4j_user or os.environ.get("NEO4J_USER")
@@ -52,22 +116,45 @@ class CodebaseKnowledgeGraph:
         # Validate configuration
         self._validate_configuration()
         
-        # Initialize Neo4j database connection with connection pooling
-        max_pool_size = self._get_neo4j_pool_size()
-        self.db = Neo4jDatabase(
-            uri=self.neo4j_uri or "",
-            user=self.neo4j_user or "",
-            password=self.neo4j_password or "",
-            max_connection_pool_size=max_pool_size
-        )
+        self.storage = storage or os.environ.get("STORAGE_BACKEND", "neo4j")
+        if self.storage not in STORAGE_BACKENDS:
+            raise ValueError(f"Unknown storage backend: {self.storage}")
+        
+        if self.storage == "sqlite":
+            self.db = SQLiteDatabase(db_path or os.environ.get("SQLITE_DB_PATH", DEFAULT_SQLITE_PATH))
+        else:
+            # Initialize Neo4j database connection with connection pooling
+            max_pool_size = self._get_neo4j_pool_size()
+            self.db = Neo4jDatabase(
+                uri=self.neo4j_uri or "",
+                user=self.neo4j_user or "",
+                password=self.neo4j_password or "",
+                max_connection_pool_size=max_pool_size,
+                batch_size=batch_size
+            )
         
         # Initialize code parser
         self.parser = ASTParser()
         
         # Store ast-grep feature flags for use in parsing methods
-        self.use_ast_grep = os.getenv("USE_AST_GREP", "false").lower() == "true"
-        self.ast_grep_languages = os.getenv("AST_GREP_LANGUAGES", "python,javascript,typescript").split(',')
-        self.ast_grep_fallback = os.getenv("AST_GREP_FALLBACK_TO_LEGACY", "true").lower() == "true"
+        if use_ast_grep is None:
+            use_ast_grep = os.getenv("USE_AST_GREP", "false").lower() == "true"
+        if ast_grep_languages is None:
+            ast_grep_languages = os.getenv("AST_GREP_LANGUAGES", "python,javascript,typescript").split(',')
+        if ast_grep_fallback is None:
+            ast_grep_fallback = os.getenv("AST_GREP_FALLBACK_TO_LEGACY", "true").lower() == "true"
+        self.use_ast_grep = use_ast_grep
+        self.ast_grep_languages = ast_grep_languages
+        self.ast_grep_fallback = ast_grep_fallback
+        self.language_options = language_options or {}
+        self.language_overrides = language_overrides or {}
+        
+        # Directory walk filters
+        env_respect_gitignore, env_exclude = env_filter_options()
+        self.respect_gitignore = env_respect_gitignore if respect_gitignore is None else respect_gitignore
+        self.exclude = env_exclude if exclude is None else exclude
+        self.include = include or []
+        self.max_file_size = max_file_size
         
         # Initialize embedding handler
         # If an explicit API key is provided prefer the wrapper, otherwise use the factory
@@ -77,6 +164,23 @@ class CodebaseKnowledgeGraph:
             self.embedder = get_embedding_provider()
 
         self.code_embedder = CodeEmbedder(self.embedder)
+        
+        # File counts of the last incremental run
+        self.index_stats: Optional[IndexStats] = None
+        
+        # Files left out or parsed with errors, served by the get_diagnostics MCP resource
+        self.diagnostics = ParseDiagnostics(strict=strict)
+        
+        # On-disk graph cache, used whenever an in-memory CodeGraph is built
+        self.cache = GraphCache(cache_db) if cache_db else None
+        
+        if jobs is not None and jobs < 1:
+            raise ValueError(f"jobs must be at least 1, got {jobs}")
+        self.jobs = jobs
+        
+        if max_memory_mb is not None and max_memory_mb < 1:
+            raise ValueError(f"max_memory_mb must be at least 1, got {max_memory_mb}")
+        self.max_memory_mb = max_memory_mb
     
     def _validate_configuration(self) -> None:
         """Validate configuration parameters
@@ -142,18 +246,29 @@ class CodebaseKnowledgeGraph:
         logger.info(f"Using default Neo4j connection pool size: {default_size}")
         return default_size
     
-    def process_codebase(self, codebase_path: str, clear_db: bool = False) -> Tuple[int, int]:
+    def process_codebase(self, codebase_path: Union[str, List[str]], clear_db: bool = False,
+                         graph: Optional[CodeGraph] = None, incremental: bool = False,
+                         keep_outside: bool = False) -> Tuple[int, int]:
         """Process the entire codebase, parse and import into the knowledge graph
         
         Args:
-            codebase_path: Directory path of the codebase
+            codebase_path: Directory path of the codebase, or the roots of a
+                   workspace, which need graph
             clear_db: Whether to clear the database
+            graph: Optional empty CodeGraph to parse into. The database then
+                   mirrors the graph exactly, which watch mode relies on.
+            incremental: Only re-parse files whose contents changed since the
+                   previous run and patch the database. Requires graph.
+            keep_outside: With incremental, keep the stored files outside
+                   codebase_path, to re-index some projects of a workspace
             
         Returns:
             Number of nodes and relationships processed
         """
         start_time = time.time()
-        logger.info(f"Starting to process codebase: {codebase_path}")
+        roots = [codebase_path] if isinstance(codebase_path, str) else list(codebase_path)
+        codebase_path = roots[0] if len(roots) == 1 else roots
+        logger.info(f"Starting to process codebase: {', '.join(roots)}")
         
         # Log runtime information (GIL status, Python version, etc.)
         log_runtime_info()
@@ -172,7 +287,7 @@ class CodebaseKnowledgeGraph:
         self.db.create_schema_constraints()
         
         # Collect all source files (Python, JS, TS)
-        source_files = self._collect_source_files(codebase_path)
+        source_files = [path for root in roots for path in self._collect_source_files(root)]
         logger.info(f"Found {len(source_files)} source code files")
         
         # Get configuration for parallel processing
@@ -180,9 +295,65 @@ class CodebaseKnowledgeGraph:
         min_files_for_parallel = int(os.getenv("MIN_FILES_FOR_PARALLEL", "50"))
         
         # Determine if we should use parallel processing
-        use_parallel = parallel_enabled and len(source_files) >= min_files_for_parallel
+        use_parallel = (parallel_enabled and self.jobs != 1
+                        and len(source_files) >= min_files_for_parallel)
         
-        if use_parallel:
+        if incremental and graph is None:
+            raise ValueError("Incremental indexing needs a CodeGraph to restore into")
+        if len(roots) > 1 and graph is None:
+            raise ValueError("Indexing several roots needs a CodeGraph to build into")
+        
+        if incremental and not clear_db:
+            nodes, relations = self._index_incremental(codebase_path, graph, use_parallel, keep_outside)
+            num_nodes, num_relations = len(nodes), len(relations)
+        elif graph is None and self.max_memory_mb is not None:
+            num_nodes, num_relations = self._index_streaming(source_files, use_parallel)
+        else:
+            nodes, relations = self._index_full(codebase_path, source_files, graph, use_parallel)
+            num_nodes, num_relations = len(nodes), len(relations)
+        
+        self._create_search_indexes()
+        
+        elapsed_time = time.time() - start_time
+        peak = peak_memory_mb()
+        memory = f", peak memory: {peak:.0f} MB" if peak is not None else ""
+        logger.info(f"Codebase processing complete! Time taken: {elapsed_time:.2f} seconds{memory} "
+                    f"(Parallel mode: {use_parallel})")
+        self._log_summary()
+        return num_nodes, num_relations
+    
+    def _log_summary(self) -> None:
+        """Report the files indexed, and those that failed or were skipped for their size"""
+        summary = self.diagnostics.summary()
+        logger.info(f"Indexed {summary['files_indexed']} files, {summary['files_failed']} failed, "
+                    f"{len(summary['files_with_errors'])} with syntax errors, "
+                    f"{len(summary['skipped_by_size'])} skipped by size")
+        if summary["failed_files"]:
+            logger.warning(f"{summary['files_failed']} file(s) could not be parsed and are only flagged "
+                           f"parse_error in the graph: {', '.join(summary['failed_files'])}")
+    
+    def _index_full(self, codebase_path: Union[str, List[str]], source_files: List[str],
+                    graph: Optional[CodeGraph], use_parallel: bool) -> Tuple[Dict[str, Any], List[Any]]:
+        """Parse every source file and import the result"""
+        if graph is not None and self.cache is not None:
+            # Unchanged files come from the cache, the rest are parsed
+            logger.info(f"Building in-memory code graph for {len(source_files)} files from cache {self.cache.path}")
+            result = reindex(graph, codebase_path, self.cache.load(),
+                             jobs=get_optimal_worker_count(self.jobs) if use_parallel else 1)
+            self.index_stats = result.stats
+            nodes, relations = self._detached_nodes(graph.nodes), graph.relations
+        elif graph is not None:
+            logger.info(f"Building in-memory code graph for {len(source_files)} files")
+            # The in-memory graph parses on threads; resolution stays sequential
+            jobs = get_optimal_worker_count(self.jobs) if use_parallel else 1
+            if isinstance(codebase_path, str):
+                graph.add_directory(codebase_path, jobs=jobs)
+            else:
+                # The roots of a workspace are resolved together
+                graph.build_with_progress(sorted(path for root in codebase_path
+                                                 for path in graph.collect_source_files(root)), None, jobs)
+            nodes, relations = self._detached_nodes(graph.nodes), graph.relations
+        elif use_parallel:
             logger.info(f"Using parallel processing mode to process {len(source_files)} files")
             nodes, relations = self._process_files_parallel(source_files, codebase_path)
         else:
@@ -190,6 +361,9 @@ class CodebaseKnowledgeGraph:
             # For sequential mode, we still need to use the router
             nodes, relations = self._process_directory_with_routing(codebase_path)
         
+        if graph is not None:
+            self._record_graph_files(graph, graph.files)
+        
         logger.info(f"Total parsed {len(nodes)} nodes and {len(relations)} relationships")
         
         # Generate embedding vectors for nodes
@@ -206,6 +380,107 @@ class CodebaseKnowledgeGraph:
         neo4j_relations = self._convert_relations_to_neo4j_format(relations)
         self.db.batch_create_relationships(neo4j_relations)
         
+        if graph is not None:
+            # Saved so that a later incremental run can skip unchanged files
+            self._store_file_states(graph, graph.files)
+            if self.cache is not None:
+                self.cache.save(graph)
+        return nodes, relations
+    
+    def _index_streaming(self, source_files: List[str], use_parallel: bool) -> Tuple[int, int]:
+        """Parse every source file, writing each one's graph as soon as it is parsed
+        
+        Only a compact symbol table stays in memory until the second pass
+        resolves cross-file references, and pending references and
+        cross-file relationships beyond what max_memory_mb allows are
+        spilled to a temporary file; the stored graph is the one a full
+        in-memory run produces. The symbol table itself is not spilled, so
+        memory still grows with the number of symbols.
+        
+        Returns:
+            Number of nodes and relationships written
+        """
+        logger.info(f"Streaming {len(source_files)} files to the database within about {self.max_memory_mb} MB")
+        merge = StreamingMerge(self._write_nodes, self._write_relations,
+                               spill_threshold=spill_threshold_for(self.max_memory_mb))
+        if use_parallel:
+            results = self._parse_in_pool(source_files)
+        else:
+            results = (self._parse_logged(file_path) for file_path in source_files)
+        for result in results:
+            merge.add(result)
+        
+        logger.info("Second pass: Resolving cross-file references...")
+        module_names = merge.finish()
+        self.db.set_node_properties([{"id": node_id, "properties": {"module_name": name}}
+                                     for node_id, name in module_names.items()])
+        stats = merge.stats
+        logger.info(f"Streamed {stats.files} files ({stats.failed} failed): {stats.nodes} nodes, "
+                    f"{stats.relations} relationships, {stats.spilled}/{stats.pending} pending references and "
+                    f"{stats.deferred_spilled}/{stats.deferred} cross-file relationships spilled")
+        return stats.nodes, stats.relations
+    
+    def _write_nodes(self, nodes: Dict[str, Any]) -> None:
+        """Embed and store a batch of nodes"""
+        self._generate_embeddings(nodes)
+        self.db.batch_create_nodes(self._convert_nodes_to_neo4j_format(nodes))
+    
+    def _write_relations(self, relations: List[Any]) -> None:
+        self.db.batch_create_relationships(self._convert_relations_to_neo4j_format(relations))
+    
+    def _index_incremental(self, codebase_path: Union[str, List[str]], graph: CodeGraph,
+                           use_parallel: bool = False, keep_outside: bool = False) -> Tuple[Dict[str, Any], List[Any]]:
+        """Restore the stored graph, re-parse changed files and write the differences"""
+        stored = {}
+        for record in self.db.get_file_states():
+            state = None
+            if record.get("graph_state"):
+                state = FileState.from_dict(json.loads(record["graph_state"]))
+            stored[record["file_path"]] = StoredFile(
+                file_path=record["file_path"],
+                content_hash=record.get("content_hash"),
+                mtime_ns=record.get("mtime_ns"),
+                state=state,
+                size=state.size if state else None,
+            )
+        
+        result = reindex(graph, codebase_path, stored,
+                         jobs=get_optimal_worker_count(self.jobs) if use_parallel else 1, keep_outside=keep_outside)
+        self._record_graph_files(graph, graph.files)
+        if result.stale_files:
+            logger.info(f"Dropping {len(result.stale_files)} files indexed without a stored graph state")
+            self.db.delete_nodes_by_file(result.stale_files)
+        for delta in result.deltas:
+            self.apply_graph_delta(graph, delta)
+        self._store_file_states(graph, result.touched_files)
+        
+        if self.cache is not None:
+            self.cache.save(graph)
+        
+        self.index_stats = result.stats
+        logger.info(f"Incremental indexing summary: {result.stats.summary()}")
+        return graph.nodes, graph.relations
+    
+    def _store_file_states(self, graph: CodeGraph, paths) -> None:
+        """Save content hash, mtime and graph state on the File node of each path"""
+        states = []
+        for path in paths:
+            state = graph.files.get(path)
+            if state is None:
+                continue
+            file_node = next((n for n in state.nodes.values() if n.node_type == "File"), None)
+            if file_node is None:
+                continue
+            states.append({
+                "id": file_node.node_id,
+                "content_hash": state.content_hash,
+                "mtime_ns": state.mtime_ns,
+                "graph_state": json.dumps(state.to_dict(), ensure_ascii=False, default=str),
+            })
+        self.db.set_file_states(states)
+    
+    def _create_search_indexes(self) -> None:
+        """Create the vector and full-text indexes used for search"""
         # Create vector index (for similarity search)
         logger.info("Creating vector indexes...")
         try:
@@ -232,10 +507,6 @@ class CodebaseKnowledgeGraph:
             )
         except Exception as e:
             logger.error(f"Error creating full-text search index: {e}")
-        
-        elapsed_time = time.time() - start_time
-        logger.info(f"Codebase processing complete! Time taken: {elapsed_time:.2f} seconds (Parallel mode: {use_parallel})")
-        return len(nodes), len(relations)
     
     def _collect_source_files(self, directory_path: str) -> List[str]:
         """Collect all source code files in the directory (supports 7 languages)
@@ -257,12 +528,16 @@ class CodebaseKnowledgeGraph:
                 supported_extensions.extend(['.js', '.ts', '.jsx', '.tsx'])
             if 'java' in self.ast_grep_languages:
                 supported_extensions.append('.java')
+            if 'c' in self.ast_grep_languages:
+                supported_extensions.extend(['.c', '.h'])
             if 'cpp' in self.ast_grep_languages:
                 supported_extensions.extend(['.cpp', '.cc', '.cxx', '.h', '.hpp'])
             if 'rust' in self.ast_grep_languages:
                 supported_extensions.append('.rs')
             if 'go' in self.ast_grep_languages:
                 supported_extensions.append('.go')
+            if 'ruby' in self.ast_grep_languages:
+                supported_extensions.append('.rb')
             supported_extensions = tuple(supported_extensions)
             
             logger.info(f"ast-grep mode enabled languages: {', '.join(self.ast_grep_languages)}")
@@ -278,11 +553,25 @@ class CodebaseKnowledgeGraph:
             else:
                 logger.info("Only Python support enabled")
         
-        for root, _, files in os.walk(directory_path):
-            for file_name in files:
-                if file_name.endswith(supported_extensions):
-                    file_path = os.path.join(root, file_name)
-                    source_files.append(file_path)
+        path_filter = PathFilter(respect_gitignore=self.respect_gitignore, exclude=self.exclude,
+                                 include=self.include, max_file_size=self.max_file_size)
+        for file_path in path_filter.walk(directory_path):
+            if parsed_extension(file_path, self.language_overrides).endswith(supported_extensions):
+                if looks_binary(file_path):
+                    # A binary file with a source extension, e.g. a .ts video segment
+                    logger.debug(f"Skipping binary file {file_path}")
+                    continue
+                source_files.append(file_path)
+                continue
+            language = detect_language(file_path, self.language_overrides)
+            if language is not None:
+                # Source code, of a language left out of the enabled parsers
+                self.diagnostics.skip(file_path, UNSUPPORTED_LANGUAGE, f"No enabled parser for {language}")
+        for file_path in path_filter.too_large:
+            self.diagnostics.skip(file_path, FILE_TOO_LARGE, f"Larger than {self.max_file_size} bytes")
+        
+        if path_filter.skipped:
+            logger.info(f"Skipped {path_filter.skipped} paths ignored by .gitignore or exclude patterns")
         
         return source_files
     
@@ -295,26 +584,8 @@ class CodebaseKnowledgeGraph:
         Returns:
             Parser instance (ASTParser, TypeScriptParser, or MultiLanguageParser), None if unsupported
         """
-        # When USE_AST_GREP is enabled, use MultiLanguageParser coordinator
-        if self.use_ast_grep:
-            return MultiLanguageParser(
-                use_ast_grep=True,
-                ast_grep_languages=self.ast_grep_languages,
-                ast_grep_fallback=self.ast_grep_fallback
-            )
-        
-        # Otherwise, use legacy routing
-        ext = os.path.splitext(file_path)[1].lower()
-        
-        if ext == '.py':
-            from src.ast_parser.parser import ASTParser
-            return ASTParser()
-        elif ext in ['.js', '.ts', '.jsx', '.tsx']:
-            from src.ast_parser.typescript_parser import TypeScriptParser
-            return TypeScriptParser()
-        else:
-            logger.warning(f"Unsupported file extension: {ext} ({file_path})")
-            return None
+        return create_parser(file_path, self.use_ast_grep, self.ast_grep_languages, self.ast_grep_fallback,
+                             self.language_options, self.language_overrides)
     
     def _process_directory_with_routing(self, directory_path: str) -> Tuple[Dict[str, Any], List[Any]]:
         """Process directory with parser routing (sequential mode)
@@ -330,49 +601,67 @@ class CodebaseKnowledgeGraph:
             coordinator = MultiLanguageParser(
                 use_ast_grep=True,
                 ast_grep_languages=self.ast_grep_languages,
-                ast_grep_fallback=self.ast_grep_fallback
+                ast_grep_fallback=self.ast_grep_fallback,
+                respect_gitignore=self.respect_gitignore,
+                exclude=self.exclude,
+                include=self.include,
+                max_file_size=self.max_file_size,
+                language_options=self.language_options,
+                language_overrides=self.language_overrides
             )
-            return coordinator.parse_directory(directory_path, build_index=True)
+            nodes, relations = coordinator.parse_directory(directory_path, build_index=True)
+            for diagnostics in coordinator.report.files.values():
+                self._log_parse_errors(diagnostics.file_path, diagnostics.errors)
+            self.diagnostics.add_report(coordinator.report)
+            return nodes, relations
         
-        # Legacy routing (USE_AST_GREP=false)
-        all_nodes = {}
-        all_module_definitions = {}
-        all_pending_imports = []
-        all_module_to_file = {}
-        
-        # Collect all source files
-        source_files = self._collect_source_files(directory_path)
-        
-        # First pass: Parse all files and build index
-        for file_path in source_files:
-            try:
-                parser = self._get_parser_for_file(file_path)
-                if parser:
-                    nodes, _ = parser.parse_file(file_path, build_index=True)
-                    
-                    # Merge results
-                    all_nodes.update(nodes)
-                    all_module_definitions.update(parser.module_definitions)
-                    all_pending_imports.extend(parser.pending_imports)
-                    all_module_to_file.update(parser.module_to_file)
-            except Exception as e:
-                logger.error(f"Error parsing file {file_path}: {e}")
-        
-        # Second pass: Process pending imports
-        # Create a temporary parser to process imports
-        from src.ast_parser.parser import ASTParser
-        temp_parser = ASTParser()
-        temp_parser.nodes = all_nodes
-        temp_parser.module_definitions = all_module_definitions
-        temp_parser.pending_imports = all_pending_imports
-        temp_parser.module_to_file = all_module_to_file
-        temp_parser._process_pending_imports()
-        
-        return all_nodes, temp_parser.relations
+        # Legacy routing (USE_AST_GREP=false): the same per-file tasks and
+        # merge as parallel mode, run in this thread
+        return merge_results(self._parse_logged(file_path) for file_path in self._collect_source_files(directory_path))
+    
+    def _parse_logged(self, file_path: str) -> FileParseResult:
+        """Parse one file in this thread, logging a failure or syntax errors"""
+        result = parse_file_task(file_path, self.use_ast_grep, self.ast_grep_languages,
+                                 self.ast_grep_fallback, self.language_options, self.language_overrides)
+        self._record_result(result)
+        return result
+    
+    def _record_result(self, result: FileParseResult) -> None:
+        """Log a file's failure or syntax errors, and keep them for get_diagnostics"""
+        if result.error is not None:
+            logger.error(f"Error parsing file {result.file_path}: {result.error}")
+            self.diagnostics.skip(result.file_path, result.error_kind, result.error)
+            return
+        self._log_parse_errors(result.file_path, result.parse_errors)
+        self.diagnostics.record(result.file_path, result.parse_errors, has_declarations(result.nodes.values()))
+    
+    def _record_graph_files(self, graph: CodeGraph, paths) -> None:
+        """Keep the syntax errors of graph files for get_diagnostics; a path gone from the graph is forgotten"""
+        for path in list(paths):
+            state = graph.files.get(path)
+            if state is None:
+                self.diagnostics.forget(path)
+            else:
+                self.diagnostics.record(path, state.parse_errors, has_declarations(state.nodes.values()))
+    
+    @staticmethod
+    def _log_parse_errors(file_path: str, errors: List[Any]) -> None:
+        """Warn about a file indexed despite syntax errors; only what was recovered around them is in the graph."""
+        if not errors:
+            return
+        first = errors[0]
+        location = f" at line {first.line_no}" if first.line_no is not None else ""
+        logger.warning(f"{len(errors)} syntax error(s) in {file_path}, first{location}: {first.message}")
     
     def _process_files_parallel(self, source_files: List[str], codebase_path: str) -> Tuple[Dict[str, Any], List[Any]]:
         """Process source files using parallel processing mode (multi-language support)
         
+        Files are parsed on a worker pool, each task with its own parser.
+        Results are merged here, in file path order, and cross-file
+        references are resolved in a single sequential second pass. A file
+        whose worker fails, or dies, is logged and left out; the rest of the
+        run continues.
+        
         Args:
             source_files: List of source file paths
             codebase_path: Codebase directory path
@@ -380,107 +669,26 @@ class CodebaseKnowledgeGraph:
         Returns:
             Node dictionary and relationship list
         """
-        from src.ast_parser.parser import ASTParser
-        from src.ast_parser.typescript_parser import TypeScriptParser
-        
         try:
             # First pass: Parse all files in parallel to build module definition index
             logger.info("First pass: Parsing all files in parallel...")
             
-            # Create a shared parser for collecting results
-            # Each worker will create its own parser instance
-            all_nodes = {}
-            all_module_definitions = {}
-            all_pending_imports = []
-            all_module_to_file = {}
+            # Collect results as they complete; the merge below restores a fixed order
+            results: List[FileParseResult] = list(self._parse_in_pool(source_files))
+            failed = sum(1 for result in results if result.error is not None)
             
-            def parse_file_worker(file_path: str) -> Tuple[Dict, Dict, List, Dict]:
-                """Worker function to parse a single file
-                
-                Args:
-                    file_path: Path to the source file
-                    
-                Returns:
-                    Tuple of (nodes, module_definitions, pending_imports, module_to_file)
-                """
-                try:
-                    # When USE_AST_GREP is enabled, use MultiLanguageParser
-                    if self.use_ast_grep:
-                        parser = MultiLanguageParser(
-                            use_ast_grep=True,
-                            ast_grep_languages=self.ast_grep_languages,
-                            ast_grep_fallback=self.ast_grep_fallback
-                        )
-                    else:
-                        # Legacy routing: Select parser based on file extension
-                        ext = os.path.splitext(file_path)[1].lower()
-                        
-                        if ext == '.py':
-                            parser = ASTParser()
-                        elif ext in ['.js', '.ts', '.jsx', '.tsx']:
-                            parser = TypeScriptParser()
-                        else:
-                            logger.warning(f"Unsupported file extension: {ext} ({file_path})")
-                            return ({}, {}, [], {})
-                    
-                    parser.parse_file(file_path, build_index=True)
-                    
-                    return (
-                        dict(parser.nodes),
-                        dict(parser.module_definitions),
-                        list(parser.pending_imports),
-                        dict(parser.module_to_file)
-                    )
-                except Exception as e:
-                    logger.error(f"Error parsing file {file_path}: {e}")
-                    return ({}, {}, [], {})
-            
-            # Use the processing pool manager to process files in parallel
-            with get_processing_pool() as pool:
-                # Submit all file parsing tasks
-                futures = [pool.submit(parse_file_worker, file_path) for file_path in source_files]
-                
-                # Filter out None values (though submit() should always return a Future)
-                futures = [f for f in futures if f is not None]
-                
-                # Collect results as they complete
-                completed = 0
-                for future in as_completed(futures):
-                    try:
-                        nodes, module_defs, pending, module_files = future.result()
-                        
-                        # Merge results
-                        all_nodes.update(nodes)
-                        all_module_definitions.update(module_defs)
-                        all_pending_imports.extend(pending)
-                        all_module_to_file.update(module_files)
-                        
-                        completed += 1
-                        if completed % 10 == 0:
-                            logger.info(f"Completed {completed}/{len(source_files)} files")
-                    except Exception as e:
-                        logger.error(f"Error processing file result: {e}")
-            
-            logger.info(f"First pass complete: Parsed {len(all_nodes)} nodes")
+            logger.info(f"First pass complete: Parsed {len(results) - failed} files, {failed} failed")
             
             # Second pass: Process pending imports sequentially
             # This must be sequential because it requires the complete module definition index
             logger.info("Second pass: Processing pending imports...")
+            nodes, relations = merge_results(results)
+            logger.info(f"Second pass complete: {len(nodes)} nodes, {len(relations)} relationships")
             
-            # Create a parser with the aggregated data to process imports
-            final_parser = ASTParser()
-            final_parser.nodes = all_nodes
-            final_parser.module_definitions = all_module_definitions
-            final_parser.pending_imports = all_pending_imports
-            final_parser.module_to_file = all_module_to_file
-            
-            # Process all pending imports
-            final_parser._process_pending_imports()
-            
-            logger.info(f"Second pass complete: Processed {len(final_parser.relations)} relationships")
-            
-            return final_parser.nodes, final_parser.relations
+            return nodes, relations
             
+        except ParseFailure:
+            raise
         except Exception as e:
             # Graceful degradation: Fall back to sequential processing
             logger.error(f"Parallel processing failed: {e}")
@@ -493,6 +701,43 @@ class CodebaseKnowledgeGraph:
             # Use sequential processing with routing
             return self._process_directory_with_routing(codebase_path)
     
+    def _parse_in_pool(self, source_files: List[str]) -> Iterator[FileParseResult]:
+        """Parse files on a worker pool, yielding each result as it completes
+        
+        At most twice as many files as there are workers are submitted at a
+        time, and another is submitted as each result is taken, so results
+        the consumer has not taken yet never pile up beyond that window.
+        """
+        with get_processing_pool(max_workers=self.jobs) as pool:
+            window = max(1, pool.max_workers) * 2
+            remaining = iter(source_files)
+            futures = {}
+            
+            def submit(count: int) -> None:
+                for file_path in itertools.islice(remaining, count):
+                    futures[pool.submit(parse_file_task, file_path, self.use_ast_grep, self.ast_grep_languages,
+                                        self.ast_grep_fallback, self.language_options,
+                                        self.language_overrides)] = file_path
+            
+            submit(window)
+            completed = 0
+            while futures:
+                done, _ = wait(futures, return_when=FIRST_COMPLETED)
+                for future in done:
+                    file_path = futures.pop(future)
+                    try:
+                        result = future.result()
+                    except Exception as e:
+                        # The worker itself was lost, e.g. a crashed process
+                        result = failed_result(file_path, e)
+                    self._record_result(result)
+                    yield result
+                    submit(1)
+                    
+                    completed += 1
+                    if completed % 10 == 0:
+                        logger.info(f"Completed {completed}/{len(source_files)} files")
+    
     def _generate_embeddings(self, nodes: Dict[str, Any]) -> None:
         """Generate embedding vectors for nodes
         
@@ -652,6 +897,88 @@ class CodebaseKnowledgeGraph:
         
         return neo4j_relations
     
+    def watch_codebase(self, codebase_path: Union[str, List[str]], graph: CodeGraph, interval: float = 1.0,
+                       stop_event: Optional[threading.Event] = None,
+                       debounce: float = DEFAULT_DEBOUNCE) -> None:
+        """Keep the database in sync with the codebase as files change
+        
+        Only changed files are re-parsed, once per burst of saves; the
+        resulting graph deltas are written to the database one transaction
+        each. Blocks until stop_event is set.
+        
+        Args:
+            codebase_path: Directory path of the codebase, or the roots of a workspace
+            graph: CodeGraph that the database currently mirrors
+            interval: Seconds between filesystem polls
+            stop_event: Event that stops the watcher when set
+            debounce: Seconds without further changes before a batch is applied
+        """
+        roots = [codebase_path] if isinstance(codebase_path, str) else list(codebase_path)
+        
+        def collect_files(_directory_path):
+            # One watcher polls every root, so a batch spanning projects is applied at once
+            return [path for root in roots for path in graph.collect_source_files(root)]
+        
+        watcher = PollingWatcher(roots[0], collect_files, interval, debounce)
+        logger.info(f"Watching {', '.join(roots)} for changes (poll interval {interval}s, debounce {debounce}s)")
+        
+        def on_changes(changes):
+            for delta in apply_changes(graph, changes):
+                self.apply_graph_delta(graph, delta)
+            self._record_graph_files(graph, [change.path for change in changes])
+        
+        watcher.run(on_changes, stop_event)
+    
+    def apply_graph_delta(self, graph: CodeGraph, delta: GraphDelta) -> None:
+        """Write one CodeGraph delta to the database
+        
+        Removed relations are deleted by identity, removed nodes are deleted
+        with their remaining relationships, and added or changed nodes are
+        upserted before the added relations are created. All writes run in
+        one transaction, so concurrent queries never see a file half
+        replaced; embeddings are generated before it starts.
+        
+        Args:
+            graph: Graph the delta was produced from
+            delta: Changes to apply
+        """
+        if delta.is_empty():
+            return
+        
+        changed = self._detached_nodes(
+            # A batch of deltas may add a placeholder that a later update in the
+            # same batch already removed again; that later delta deletes it
+            {node_id: graph.nodes[node_id] for node_id in delta.added_nodes + delta.updated_nodes
+             if node_id in graph.nodes}
+        )
+        self._generate_embeddings(changed)
+        
+        with self.db.write_batch():
+            self.db.delete_relationships([
+                {
+                    "start_node_id": r.source_id,
+                    "end_node_id": r.target_id,
+                    "type": r.relation_type,
+                    "properties": relation_identity(r),
+                }
+                for r in delta.removed_relations
+            ])
+            self.db.delete_nodes(delta.removed_nodes)
+            self.db.upsert_nodes(self._convert_nodes_to_neo4j_format(changed))
+            self.db.batch_create_relationships(self._convert_relations_to_neo4j_format(delta.added_relations))
+            # Upserting replaced the stored state on changed File nodes
+            self._store_file_states(graph, delta.files)
+    
+    @staticmethod
+    def _detached_nodes(nodes: Dict[str, Any]) -> Dict[str, Any]:
+        """Copy nodes so that embeddings added for storage stay out of the in-memory graph"""
+        detached = {}
+        for node_id, node in nodes.items():
+            node_copy = copy.copy(node)
+            node_copy.properties = dict(node.properties)
+            detached[node_id] = node_copy
+        return detached
+    
     def close(self) -> None:
         """Close resources (database connections, etc.)"""
         self.db.close()
@@ -659,9 +986,26 @@ class CodebaseKnowledgeGraph:
 
 def main():
     """Main function"""
+    if sys.argv[1:2] == ["export"]:
+        # `main.py export ...` writes DOT for a directory without touching Neo4j
+        sys.exit(export_main(sys.argv[2:]))
+    if sys.argv[1:2] == ["dead-code"]:
+        # `main.py dead-code ...` lists unreferenced symbols, also without Neo4j
+        sys.exit(dead_code_main(sys.argv[2:]))
+    if sys.argv[1:2] == ["cycles"]:
+        # `main.py cycles ...` lists import or call cycles, checked against a baseline in CI
+        sys.exit(cycles_main(sys.argv[2:]))
+    
     parser = argparse.ArgumentParser(description="Codebase Knowledge Graph Creation Tool")
-    parser.add_argument("--codebase-path", required=True, help="Codebase path")
+    parser.add_argument("--codebase-path", action="append", required=True,
+                        help="Codebase path; repeat it to index several roots as the projects of one workspace")
+    parser.add_argument("--project", metavar="NAME",
+                        help="With --incremental, only re-index this project of a workspace; the others are kept")
     parser.add_argument("--clear-db", action="store_true", help="Clear database")
+    parser.add_argument("--storage", choices=STORAGE_BACKENDS,
+                        help="Graph storage backend (default: STORAGE_BACKEND or neo4j)")
+    parser.add_argument("--db-path", metavar="PATH",
+                        help="SQLite database file of the sqlite storage backend (default: codegraph.db)")
     parser.add_argument("--neo4j-uri", help="Neo4j database URI")
     parser.add_argument("--neo4j-user", help="Neo4j username")
     parser.add_argument("--neo4j-password", help="Neo4j password")
@@ -669,30 +1013,191 @@ def main():
     parser.add_argument("--start-mcp-server", action="store_true", help="Start MCP server after building knowledge graph")
     parser.add_argument("--mcp-transport", choices=["stdio", "sse"], default="stdio", help="MCP transport protocol")
     parser.add_argument("--mcp-port", type=int, default=8080, help="MCP server port number (only for SSE transport)")
+    parser.add_argument("--watch", action="store_true", help="Keep the knowledge graph updated as source files change")
+    parser.add_argument("--watch-interval", type=float, default=1.0, help="Seconds between filesystem polls in watch mode")
+    parser.add_argument("--watch-debounce", type=float, default=DEFAULT_DEBOUNCE,
+                        help="Seconds a burst of changes must settle before watch mode re-parses (default: 0.5)")
+    parser.add_argument("--incremental", action="store_true", help="Only re-parse files that changed since the previous run")
+    parser.add_argument("--exclude", action="append", default=[], metavar="GLOB",
+                        help="Skip paths matching a glob in .gitignore syntax, e.g. '**/generated/**' (repeatable)")
+    parser.add_argument("--no-gitignore", action="store_true", help="Index files even if .gitignore ignores them")
+    parser.add_argument("--cache-db", metavar="PATH",
+                        help="SQLite file caching the parsed graph; later runs only re-parse changed files")
+    parser.add_argument("--cache-dir", metavar="DIR",
+                        help="Directory caching each file's parse result by content hash; unchanged files are not re-parsed")
+    parser.add_argument("--include-kinds", metavar="KINDS",
+                        help="Only keep nodes of these comma-separated kinds, e.g. 'function,method,class'; "
+                             "edges of the others move to their nearest kept parent")
+    parser.add_argument("--jobs", "-j", type=int, metavar="N",
+                        help="Number of files to parse in parallel (default: MAX_WORKERS or the CPU count; 1 disables)")
+    parser.add_argument("--batch-size", type=int, metavar="N",
+                        help="Nodes or relationships per Neo4j write statement (default: NEO4J_BATCH_SIZE or 1000)")
+    parser.add_argument("--include", action="append", default=[], metavar="GLOB",
+                        help="Only index files matching a glob in .gitignore syntax, e.g. 'src/**' (repeatable)")
+    parser.add_argument("--max-file-size", type=int, metavar="BYTES", help="Skip files larger than this")
+    parser.add_argument("--max-memory-mb", type=int, metavar="MB",
+                        help="Index in bounded memory: write each file's graph as soon as it is parsed and spill "
+                             "pending cross-file references and relationships to disk beyond what this budget "
+                             "allows; a compact symbol table still stays in memory")
+    parser.add_argument("--max-traversal-depth", type=int, metavar="N",
+                        help="Edges the MCP server's graph walks follow when a call gives no depth "
+                             "(default: MAX_TRAVERSAL_DEPTH or 10)")
+    parser.add_argument("--strict", action="store_true",
+                        help="Stop at the first file that fails to parse and exit with an error; by default such "
+                             "files are flagged parse_error and the run succeeds if any file was indexed")
+    parser.add_argument("--config", metavar="PATH",
+                        help=f"Config file to read (default: {CONFIG_FILE} in the codebase path, if present)")
+    parser.add_argument("--print-config", action="store_true",
+                        help="Print the configuration merged from defaults, environment, config file and flags, then exit")
     
     args = parser.parse_args()
+    if args.jobs is not None and args.jobs < 1:
+        parser.error("--jobs must be at least 1")
+    if args.batch_size is not None and args.batch_size < 1:
+        parser.error("--batch-size must be at least 1")
+    if args.max_file_size is not None and args.max_file_size < 1:
+        parser.error("--max-file-size must be at least 1")
+    if args.max_memory_mb is not None and args.max_memory_mb < 1:
+        parser.error("--max-memory-mb must be at least 1")
+    if args.max_traversal_depth is not None and args.max_traversal_depth < 1:
+        parser.error("--max-traversal-depth must be at least 1")
+    
+    # --- Settings: defaults, environment, codegraph.toml, then command line ---
+    try:
+        config = load_config(args.config or find_config(args.codebase_path[0])).override({
+            ("paths", "include"): args.include,
+            ("paths", "exclude"): args.exclude,
+            ("paths", "respect_gitignore"): False if args.no_gitignore else None,
+            ("paths", "max_file_size"): args.max_file_size,
+            ("storage", "backend"): args.storage,
+            ("storage", "db_path"): args.db_path,
+            ("storage", "neo4j_uri"): args.neo4j_uri,
+            ("storage", "neo4j_user"): args.neo4j_user,
+            ("storage", "batch_size"): args.batch_size,
+            ("queries", "max_traversal_depth"): args.max_traversal_depth,
+        })
+    except ConfigError as e:
+        parser.error(str(e))
+    if args.print_config:
+        print(config.to_toml(), end="")
+        return
+    
+    # --- Workspace: several roots indexed as projects of one graph ---
+    roots = config.workspace_roots or args.codebase_path
+    projects = None
+    if len(roots) > 1 or config.workspace_roots:
+        try:
+            projects = ProjectMap(roots, config.workspace_packages)
+        except ValueError as e:
+            parser.error(str(e))
+    elif config.workspace_packages:
+        parser.error("[workspace.packages] needs several roots: repeat --codebase-path or set [workspace] roots")
+    if args.project:
+        project = projects.get(args.project) if projects is not None else None
+        if project is None:
+            parser.error(f"--project {args.project}: not a project of the workspace")
+        if not args.incremental or args.clear_db:
+            parser.error("--project needs --incremental, without --clear-db")
+    
+    # --- Node kinds kept in the graph ---
+    include_kinds = None
+    if args.include_kinds:
+        try:
+            include_kinds = parse_node_kinds(args.include_kinds)
+        except ValueError as e:
+            parser.error(f"--include-kinds: {e}")
+    
     # --- AST-grep integration feature flags ---
-    use_ast_grep = os.getenv("USE_AST_GREP", "false").lower() == "true"
-    ast_grep_languages = os.getenv("AST_GREP_LANGUAGES", "python,javascript,typescript").split(',')
-    ast_grep_fallback = os.getenv("AST_GREP_FALLBACK_TO_LEGACY", "true").lower() == "true"
+    use_ast_grep = config.get("languages", "use_ast_grep")
+    ast_grep_languages = config.languages
+    ast_grep_fallback = config.get("languages", "fallback_to_legacy")
     logger.info(f"USE_AST_GREP={use_ast_grep}, AST_GREP_LANGUAGES={ast_grep_languages}, AST_GREP_FALLBACK_TO_LEGACY={ast_grep_fallback}")
     
+    # --- Directory walk filters ---
+    respect_gitignore = config.get("paths", "respect_gitignore")
+    exclude = config.exclude
+    include = config.get("paths", "include")
+    max_file_size = config.max_file_size
+    
     # Create knowledge graph
     kg = CodebaseKnowledgeGraph(
-        neo4j_uri=args.neo4j_uri,
-        neo4j_user=args.neo4j_user,
+        neo4j_uri=config.get("storage", "neo4j_uri"),
+        neo4j_user=config.get("storage", "neo4j_user"),
         neo4j_password=args.neo4j_password,
-        openai_api_key=args.openai_api_key
+        openai_api_key=args.openai_api_key,
+        respect_gitignore=respect_gitignore,
+        exclude=exclude,
+        cache_db=args.cache_db,
+        jobs=args.jobs,
+        storage=config.get("storage", "backend"),
+        db_path=config.get("storage", "db_path"),
+        batch_size=config.get("storage", "batch_size"),
+        use_ast_grep=use_ast_grep,
+        ast_grep_languages=ast_grep_languages,
+        ast_grep_fallback=ast_grep_fallback,
+        include=include,
+        max_file_size=max_file_size,
+        language_options=config.language_options,
+        language_overrides=config.language_overrides,
+        max_memory_mb=args.max_memory_mb,
+        strict=args.strict
     )
     
+    # Watch and incremental mode keep the parsed graph in memory to patch it file by file,
+    # and the cache is filled from it; a workspace resolves its projects in it, and
+    # left-out node kinds are pruned from it
+    graph = CodeGraph(
+        use_ast_grep=use_ast_grep,
+        ast_grep_languages=ast_grep_languages,
+        ast_grep_fallback=ast_grep_fallback,
+        respect_gitignore=respect_gitignore,
+        exclude=exclude,
+        include=include,
+        max_file_size=max_file_size,
+        language_options=config.language_options,
+        language_overrides=config.language_overrides,
+        include_kinds=include_kinds
+    ) if args.watch or args.incremental or args.cache_db or args.cache_dir or projects or include_kinds else None
+    if projects is not None:
+        graph.with_projects(projects)
+    if graph is not None and args.cache_dir:
+        graph.with_cache_dir(args.cache_dir)
+    stop_event = threading.Event()
+    
     try:
         # Process codebase
-        num_nodes, num_relations = kg.process_codebase(
-            codebase_path=args.codebase_path,
-            clear_db=args.clear_db
-        )
+        try:
+            num_nodes, num_relations = kg.process_codebase(
+                codebase_path=[projects.get(args.project).root] if args.project else roots,
+                clear_db=args.clear_db,
+                graph=graph,
+                incremental=args.incremental,
+                keep_outside=bool(args.project)
+            )
+        except ParseFailure as e:
+            logger.error(f"Stopped at a file that failed to parse (--strict): {e}")
+            sys.exit(1)
         
+        summary = kg.diagnostics.summary()
+        if summary["files_failed"] and not summary["files_indexed"]:
+            logger.error(f"No file could be indexed; all {summary['files_failed']} failed to parse")
+            sys.exit(1)
         logger.info(f"Successfully processed codebase, imported {num_nodes} nodes and {num_relations} relationships")
+        # Strict mode is for the run above; later edits in watch mode are reported, not fatal
+        kg.diagnostics.strict = False
+        
+        if args.watch and args.start_mcp_server:
+            # The MCP server blocks the main thread, so watch in the background
+            threading.Thread(
+                target=kg.watch_codebase,
+                args=(roots, graph, args.watch_interval, stop_event, args.watch_debounce),
+                daemon=True
+            ).start()
+        elif args.watch:
+            try:
+                kg.watch_codebase(roots, graph, args.watch_interval, stop_event, args.watch_debounce)
+            except KeyboardInterrupt:
+                logger.info("Stopped watching")
         
         # Start MCP server (if needed)
         if args.start_mcp_server:
@@ -703,15 +1208,20 @@ def main():
             
             # Create and start MCP server
             server = CodebaseKnowledgeGraphMCP(
-                neo4j_uri=args.neo4j_uri,
-                neo4j_user=args.neo4j_user,
+                neo4j_uri=config.get("storage", "neo4j_uri"),
+                neo4j_user=config.get("storage", "neo4j_user"),
                 neo4j_password=args.neo4j_password,
-                openai_api_key=args.openai_api_key
+                openai_api_key=args.openai_api_key,
+                storage=config.get("storage", "backend"),
+                db_path=config.get("storage", "db_path"),
+                config=config,
+                diagnostics=kg.diagnostics
             )
             
             server.start(port=args.mcp_port, transport=args.mcp_transport)
     finally:
         # Close resources
+        stop_event.set()
         kg.close()
 
 
@@ -1,28 +1,168 @@
 import os
 import argparse
+import base64
+import hashlib
+import itertools
 import logging
 import asyncio
-from typing import Dict, List, Any, Optional, Tuple
+import time
+from typing import Callable, Dict, Iterable, Iterator, List, Any, Optional, Tuple
 from mcp.server.fastmcp import FastMCP, Context
 from mcp.server.models import InitializationOptions
 import sys
 import json
+import re
 
 sys.path.append(os.path.dirname(os.path.dirname(os.path.dirname(os.path.abspath(__file__)))))
 
 from src.neo4j_storage.graph_db import Neo4jDatabase
+from src.neo4j_storage.sqlite_db import SQLiteDatabase
+from src.neo4j_storage.store import DEFAULT_SQLITE_PATH, STORAGE_BACKENDS
+from src.config import CONFIG_FILE, ConfigError, find_config, load_config
 from src.embeddings.factory import get_embedding_provider
 from src.embeddings.embedder import CodeEmbedder
+from src.ast_parser.parser import CodeNode, CodeRelation
+from src.ast_parser.diagnostics import PARSE_ERROR_PROPERTIES
+from src.ast_parser.positions import CHARACTER_ENCODING, ENCODINGS, LineIndex, read_text
+from src.ast_parser.line_metrics import LINE_METRICS
+from src.graph.code_graph import CodeGraph
+from src.graph import cycles
+from src.graph.call_hierarchy import HIERARCHY_DIRECTIONS, call_hierarchy as build_call_hierarchy
+from src.graph.type_hierarchy import TYPE_NODE_TYPES, type_hierarchy as build_type_hierarchy
+from src.graph.dead_code import (CONFIDENCE_LEVELS, DEAD_CODE_PROPERTIES, EntrypointRules, at_least,
+                                 find_unreachable, find_unreferenced, find_unused as find_unused_symbols)
+from src.graph.export import EXPORTERS, DotOptions, parse_node_kinds, select_subgraph
+from src.graph.module_graph import ModuleGraph
+from src.graph.outline import build_outline, prune_outline
+from src.graph.complexity import complexity_histogram
+from src.graph.covering_tests import find_tests_for as find_covering_tests
+from src.graph.doc_coverage import is_counted, undocumented_symbols
+from src.graph.file_metrics import FUNCTION_TYPES, STRUCT_TYPES, file_metrics, is_glob, match_glob, metrics_summary
+from src.graph.rename_impact import rename_impact as build_rename_impact
+from src.graph.rename_plan import RenameRefused, plan_rename as build_rename_plan
+from src.graph.public_api import diff_api, load_snapshot, public_api, save_snapshot as save_snapshot_file
+from src.graph.symbol_search import PatternError, SymbolIndex
+from src.graph.node_query import QueryError, parse_query, query_nodes as filter_nodes
+from src.graph.snippets import extract_snippet
+from src.graph.progress import FileParsed, ProgressChannel, ResolutionStarted
+from src.graph.shared import SharedGraph
+from src.graph.git_diff import (DEFAULT_MAX_FILE_BYTES, GitError, changed_files, diff_commits as diff_graphs,
+                                graph_at_revision, resolve_ref)
+from src.graph.async_hazards import ASYNC_PROPERTIES, find_sync_in_async as find_async_hazards
+from src.graph.metrics import METRIC_PROPERTIES, top_complex as rank_complexity
+from src.graph.impact import impact_of as analyze_impact, symbols_in_files
+from src.graph.duplicates import (DEFAULT_MAX_DISTANCE, DEFAULT_MIN_TOKENS, DEFAULT_SIMILARITY,
+                                  find_duplicates as group_duplicates, function_bodies)
+from src.mcp.streaming import BufferSink, JsonLinesStream, context_sink, encode_record
 
 # 設定日誌
 logging.basicConfig(level=logging.INFO, format='%(asctime)s - %(name)s - %(levelname)s - %(message)s')
 logger = logging.getLogger(__name__)
 
+# Most usages find_usages returns before truncating
+MAX_USAGES = 500
+
+# Most entries call_hierarchy lists, over all its trees, before truncating
+MAX_HIERARCHY_NODES = 500
+
+# Most nodes the export tool renders before failing
+MAX_EXPORT_NODES = 5000
+
+# Most lines of context get_source_snippet adds on each side
+MAX_CONTEXT_LINES = 50
+
+# Most matches search_symbols returns before truncating
+MAX_SEARCH_RESULTS = 200
+
+# Most nodes query_nodes returns before truncating
+MAX_QUERY_RESULTS = 1000
+
+# Most functions top_complex returns
+MAX_TOP_COMPLEX = 200
+
+# Seconds the in-memory symbol index is reused before it is reloaded from the store
+SYMBOL_INDEX_TTL = 30.0
+
+# Graphs of git commits kept for queries at a revision, most recently used last
+REVISION_GRAPH_CACHE = 4
+
+# Seconds between forwarding the progress events of a graph build to the client
+PROGRESS_INTERVAL = 0.2
+
+# usage_kind reported by find_usages for each relation pointing at the symbol
+USAGE_KINDS = {
+    "CALLS": "call",
+    "USES_TYPE": "type_reference",
+    "REFERENCES": "field_access",
+    "IMPORTS": "import",
+    "IMPORTS_FROM": "import",
+    "IMPORTS_DEFINITION": "import",
+    "REEXPORTS": "import",
+    "INCLUDES": "import",
+    "EXTENDS": "inherit",
+    "IMPLEMENTS": "inherit",
+    "SATISFIES": "inherit",
+    "DERIVE_USE": "derive",
+    "AR_ASSOCIATION": "association",
+}
+
+
+def _cursor_scope(*query: Any) -> str:
+    """Short digest of a query, so a cursor cannot be replayed against another one."""
+    return hashlib.sha256(json.dumps(query, default=str).encode("utf-8")).hexdigest()[:16]
+
+
+def encode_cursor(scope: str, offset: int) -> str:
+    """Opaque cursor pointing at an offset in a query's result order."""
+    payload = json.dumps({"scope": scope, "offset": offset}).encode("utf-8")
+    return base64.urlsafe_b64encode(payload).decode("ascii")
+
+
+def decode_cursor(cursor: Optional[str], scope: str) -> int:
+    """Offset stored in a cursor; raises ValueError for a cursor of another query."""
+    if not cursor:
+        return 0
+    try:
+        payload = json.loads(base64.urlsafe_b64decode(cursor.encode("ascii")))
+        offset = int(payload["offset"])
+    except (ValueError, KeyError, TypeError):
+        raise ValueError(f"Invalid cursor: {cursor}")
+    if payload.get("scope") != scope or offset < 0:
+        raise ValueError("Cursor does not belong to this query")
+    return offset
+
+
+def paginate(items: Iterable[Any], limit: int, cursor: Optional[str],
+             scope: str) -> Tuple[List[Any], Optional[str]]:
+    """Take one page from a result stream
+
+    Results come in a fixed order for a given graph, so a cursor is just
+    an offset into that order. It holds no server-side state and stays
+    valid however queries are interleaved, as long as the graph itself
+    does not change. Only the requested page (plus one item to detect
+    the end) is pulled from the stream.
+
+    Returns:
+        (page, next_cursor), where next_cursor is None on the last page
+    """
+    offset = decode_cursor(cursor, scope)
+    limit = max(1, limit)
+    iterator = iter(items)
+    try:
+        page = list(itertools.islice(iterator, offset, offset + limit))
+        has_more = next(iterator, None) is not None
+    finally:
+        close = getattr(iterator, "close", None)
+        if close is not None:
+            close()
+    return page, encode_cursor(scope, offset + len(page)) if has_more else None
+
 
 class CodebaseKnowledgeGraphMCP:
     """Codebase知識圖譜的MCP服務器實現"""
     
-    def __init__(self, neo4j_uri=None, neo4j_user=None, neo4j_password=None, server_host=None, server_port=None):
+    def __init__(self, neo4j_uri=None, neo4j_user=None, neo4j_password=None, server_host=None, server_port=None,
+                 openai_api_key=None, storage=None, db_path=None, config=None, diagnostics=None):
         """初始化MCP服務器
         
         Args:
@@ -31,7 +171,21 @@ class CodebaseKnowledgeGraphMCP:
             neo4j_password: Neo4j密碼，若為None則從環境變數取得
             server_host: MCP服務器主機地址，用於HTTP/SSE傳輸
             server_port: MCP服務器端口，用於HTTP/SSE傳輸
+            openai_api_key: 未使用，嵌入提供商由環境變數決定
+                            / Unused, the embedding provider comes from the environment
+            storage: 圖儲存後端，"neo4j" 或 "sqlite"，若為None則從環境變數STORAGE_BACKEND取得
+                     / Graph storage backend, if None get from STORAGE_BACKEND (default neo4j)
+            db_path: sqlite後端的資料庫檔案，若為None則從環境變數SQLITE_DB_PATH取得
+                     / SQLite database file, if None get from SQLITE_DB_PATH (default codegraph.db)
+            config: 合併後的設定（codegraph.toml），決定工具即時解析檔案時的解析選項；若為None則只用環境變數
+                    / Merged settings (codegraph.toml) for the tools that parse files on the fly;
+                    if None only the environment is read
+            diagnostics: 建立圖譜時收集的解析問題（ParseDiagnostics），由 get_diagnostics 資源提供；若為None則該資源回報錯誤
+                         / Problems met by the indexing run that filled the database, for the
+                         get_diagnostics resource; None when the server was started on its own
         """
+        self.config = config or load_config()
+        self.diagnostics = diagnostics
         self.neo4j_uri = neo4j_uri or os.environ.get("NEO4J_URI")
         self.neo4j_user = neo4j_user or os.environ.get("NEO4J_USER")
         self.neo4j_password = neo4j_password or os.environ.get("NEO4J_PASSWORD")
@@ -48,12 +202,30 @@ class CodebaseKnowledgeGraphMCP:
             port=self.server_port
         )
         
-        # 初始化Neo4j資料庫
-        self.db = Neo4jDatabase(
-            uri=self.neo4j_uri,
-            user=self.neo4j_user,
-            password=self.neo4j_password
-        )
+        self.storage = storage or os.environ.get("STORAGE_BACKEND", "neo4j")
+        if self.storage not in STORAGE_BACKENDS:
+            raise ValueError(f"Unknown storage backend: {self.storage}")
+        
+        # 初始化圖資料庫
+        # Initialize the graph database
+        if self.storage == "sqlite":
+            self.db = SQLiteDatabase(db_path or os.environ.get("SQLITE_DB_PATH", DEFAULT_SQLITE_PATH))
+        else:
+            self.db = Neo4jDatabase(
+                uri=self.neo4j_uri,
+                user=self.neo4j_user,
+                password=self.neo4j_password
+            )
+        
+        # Symbol index for search_symbols and when it was built, see _symbol_index
+        self._symbols: Optional[SymbolIndex] = None
+        self._symbols_loaded_at = 0.0
+        
+        # Graphs built from git commits by get_callers/get_callees, by commit SHA
+        self._revision_graphs: Dict[str, CodeGraph] = {}
+        
+        # Stored graph shared with the LSP gateway, see shared_graph
+        self._shared_graph: Optional[SharedGraph] = None
         
         # 初始化嵌入處理器 (使用工廠模式支持多種提供商)
         embedding_provider = get_embedding_provider()
@@ -68,232 +240,2299 @@ class CodebaseKnowledgeGraphMCP:
         # 註冊MCP資源
         self._register_resources()
     
-    def _register_tools(self):
-        """註冊MCP工具"""
-        
+    def _register_tools(self):
+        """註冊MCP工具"""
+        
+        @self.mcp.tool()
+        async def search_code(query: str, limit: int = 10, search_type: str = "vector") -> str:
+            """搜索程式碼
+            
+            Args:
+                query: 搜索查詢
+                limit: 返回結果的最大數量
+                search_type: 搜索類型，可選 "vector" 或 "text"
+                
+            Returns:
+                搜索結果的JSON字符串
+            """
+            try:
+                results = []
+                
+                if search_type == "vector":
+                    # 生成查詢的嵌入向量
+                    vector = self.code_embedder.provider.embed_text(query)
+                    
+                    # 使用向量搜索
+                    for node_label in ["Function", "Method", "Class", "File"]:
+                        node_results = self.db.search_code_by_vector(vector, node_label, limit)
+                        results.extend(node_results)
+                    
+                    # 根據分數排序
+                    results = sorted(results, key=lambda x: x["score"], reverse=True)[:limit]
+                    
+                elif search_type == "text":
+                    # 使用全文檢索
+                    results = self.db.search_code_by_text(query, limit)
+                
+                return json.dumps(results, ensure_ascii=False)
+                
+            except Exception as e:
+                logger.error(f"搜索程式碼時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.tool()
+        async def execute_cypher_query(query: str, parameters: Dict = None) -> str:
+            """執行Cypher查詢
+            
+            Args:
+                query: Cypher查詢語句
+                parameters: 查詢參數
+                
+            Returns:
+                查詢結果的JSON字符串
+            """
+            try:
+                results = self.db.execute_cypher(query, parameters)
+                return json.dumps(results, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"執行Cypher查詢時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.tool()
+        async def get_code_by_name(name: str, node_type: str = None) -> str:
+            """根據名稱獲取程式碼
+            
+            Args:
+                name: 程式碼名稱(類別名、函數名等)
+                node_type: 節點類型，可選 "Function", "Method", "Class", "File"
+                
+            Returns:
+                程式碼的JSON字符串
+            """
+            try:
+                results = [{"n": node} for node in self.db.find_nodes_by_name(name, node_type, limit=10)]
+                return json.dumps(results, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"獲取程式碼時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.tool()
+        async def find_function_callers(function_name: str, limit: int = 10) -> str:
+            """查找調用特定函數的所有位置
+            
+            Args:
+                function_name: 函數名稱
+                limit: 返回結果的最大數量
+                
+            Returns:
+                調用者的JSON字符串
+            """
+            try:
+                callers = self.db.find_related("CALLS", "in", {"name": function_name}, limit=limit)
+                results = [{"caller": caller} for caller in callers]
+                
+                return json.dumps(results, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"查找函數調用者時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.tool()
+        async def find_function_callees(function_name: str, limit: int = 10) -> str:
+            """查找特定函數調用的所有函數
+            
+            Args:
+                function_name: 函數名稱
+                limit: 返回結果的最大數量
+                
+            Returns:
+                被調用函數的JSON字符串
+            """
+            try:
+                callees = self.db.find_related("CALLS", "out", {"name": function_name}, limit=limit)
+                results = [{"callee": callee} for callee in callees]
+                
+                return json.dumps(results, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"查找函數調用的函數時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.tool()
+        async def find_class_inheritance(class_name: str) -> str:
+            """查找類別的繼承關係
+            
+            Args:
+                class_name: 類別名稱
+                
+            Returns:
+                繼承關係的JSON字符串
+            """
+            try:
+                # 查找超類
+                superclasses = [
+                    {"super": node}
+                    for node in self.db.find_related("EXTENDS", "out", {"name": class_name}, "Class", "Class")
+                ]
+                
+                # 查找子類
+                subclasses = [
+                    {"sub": node}
+                    for node in self.db.find_related("EXTENDS", "in", {"name": class_name}, "Class", "Class")
+                ]
+                
+                return json.dumps({
+                    "superclasses": superclasses,
+                    "subclasses": subclasses
+                }, ensure_ascii=False)
+                
+            except Exception as e:
+                logger.error(f"查找類別繼承關係時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.tool()
+        async def find_file_dependencies(file_path: str) -> str:
+            """查找檔案的依賴關係
+            
+            Args:
+                file_path: 檔案路徑
+                
+            Returns:
+                依賴關係的JSON字符串
+            """
+            try:
+                # 查找該檔案導入的模組
+                imports = [
+                    {"m": node}
+                    for node in self.db.find_related("IMPORTS", "out", {"path": file_path}, "File", "Module")
+                ]
+                
+                # 查找導入該檔案的檔案
+                file_name = os.path.basename(file_path).split(".")[0]
+                imported_by = [
+                    {"f": node}
+                    for node in self.db.find_related("IMPORTS", "in", {"name": file_name}, "Module", "File")
+                ]
+                
+                return json.dumps({
+                    "imports": imports,
+                    "imported_by": imported_by
+                }, ensure_ascii=False)
+                
+            except Exception as e:
+                logger.error(f"查找檔案依賴關係時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+
+        @self.mcp.tool()
+        async def find_callers(symbol: str = None, depth: int = None, limit: int = 100,
+                               cursor: str = None, paginate_from: List[str] = None) -> str:
+            """查找調用某符號的函數，可沿調用鏈向上追溯
+            
+            Find the functions and methods that call a symbol. With depth > 1 the
+            reverse CALLS edges are walked breadth-first; each caller is reported
+            once, at the shortest distance from the target. Results are paged:
+            pass the returned ``next_cursor`` back to get the next page.
+            
+            ``depth`` defaults to the configured ``max_traversal_depth``. On
+            the last page, ``frontier`` lists the callers at that depth which
+            have callers of their own left unwalked, and ``truncated`` is
+            true when it is not empty. Pass it back as ``paginate_from`` to
+            continue the walk from those nodes instead of the symbol; callers
+            reachable along several paths may then be reported again.
+            
+            Args:
+                symbol: 符號名稱，可為節點ID、完整限定名稱、`Class.method` 或函數名稱
+                depth: 追溯層數，預設為設定的 max_traversal_depth
+                limit: 每頁返回調用者的最大數量
+                cursor: 上一頁返回的 next_cursor
+                paginate_from: 從這些節點ID繼續追溯（如上次返回的 frontier），取代 symbol
+                
+            Returns:
+                調用者鏈的JSON字符串
+            """
+            try:
+                depth = self._depth(depth)
+                targets, error = self._walk_starts(symbol, paginate_from)
+                if error:
+                    return json.dumps({"error": error}, ensure_ascii=False)
+                
+                stopped: List[str] = []
+                walk = self._iter_calls([t["id"] for t in targets], "callers", depth, stopped)
+                callers, next_cursor = paginate(
+                    (entry for entry, _ in walk if entry is not None),
+                    limit, cursor, _cursor_scope("find_callers", symbol, depth, paginate_from)
+                )
+                for caller in callers:
+                    caller["calls"] = caller.pop("callee_id")
+                    caller.pop("caller_id")
+                
+                return json.dumps({
+                    "symbol": symbol,
+                    "depth": depth,
+                    "targets": targets,
+                    "callers": callers,
+                    "next_cursor": next_cursor,
+                    "truncated": next_cursor is not None or bool(stopped),
+                    "frontier": stopped,
+                }, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"查找調用鏈時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.tool()
+        async def get_call_graph(symbol: str = None, direction: str = "both", depth: int = None,
+                                 limit: int = 200, stream: bool = False, paginate_from: List[str] = None,
+                                 ctx: Context = None) -> str:
+            """追蹤符號的調用圖（調用者與被調用者）
+            
+            Trace the call graph around a symbol. ``callers`` walks reverse
+            CALLS edges, ``callees`` walks forward ones and ``both`` does each.
+            Nodes are graph node ids; every edge carries its call site, with
+            ``await_call`` set when the call is awaited on the spot (Rust
+            ``f(..).await``). With ``stream`` the nodes and edges are sent in
+            batches as JSON Lines records (see src/mcp/streaming.py) instead
+            of one document.
+            
+            ``depth`` defaults to the configured ``max_traversal_depth``.
+            ``truncated`` is true when ``limit`` cut a walk short or when
+            ``frontier`` is not empty: the nodes at that depth with calls
+            left unwalked in the direction walked. Pass it back as
+            ``paginate_from`` to continue from those nodes instead of the
+            symbol; nodes and edges already returned may come back again.
+            
+            Args:
+                symbol: 符號名稱，可為節點ID、完整限定名稱、`Type.method` 或函數名稱
+                direction: 方向，"callers"、"callees" 或 "both"
+                depth: 追蹤層數，預設為設定的 max_traversal_depth
+                limit: 每個方向返回節點的最大數量
+                stream: 是否以 JSON Lines 記錄分批傳送結果
+                paginate_from: 從這些節點ID繼續追蹤（如上次返回的 frontier），取代 symbol
+                
+            Returns:
+                調用圖的JSON字符串；串流時為 JSON Lines
+            """
+            try:
+                if direction not in ("callers", "callees", "both"):
+                    return json.dumps({"error": f"Invalid direction: {direction}"}, ensure_ascii=False)
+                depth = self._depth(depth)
+                roots, error = self._walk_starts(symbol, paginate_from)
+                if error:
+                    return json.dumps({"error": error}, ensure_ascii=False)
+                
+                root_ids = [r["id"] for r in roots]
+                nodes = {r["id"]: {**r, "depth": 0} for r in roots}
+                edges = {}
+                truncated = False
+                frontier: Dict[str, None] = {}
+                walks = ["callers", "callees"] if direction == "both" else [direction]
+                for walk in walks:
+                    entries, walk_edges, walk_truncated, stopped = self._walk_calls(root_ids, walk, depth, limit)
+                    truncated = truncated or walk_truncated or bool(stopped)
+                    frontier.update(dict.fromkeys(stopped))
+                    for entry in entries:
+                        node = {k: v for k, v in entry.items()
+                                if k not in ("caller_id", "callee_id", "call_site")}
+                        node["depth"] = -entry["depth"] if walk == "callers" else entry["depth"]
+                        nodes.setdefault(entry["id"], node)
+                    for edge in walk_edges:
+                        key = (edge["source"], edge["target"], edge["call_site"]["line_no"])
+                        edges.setdefault(key, edge)
+                
+                if stream:
+                    return await self._stream_result(
+                        ctx, "get_call_graph",
+                        {"symbol": symbol, "direction": direction, "depth": depth, "roots": root_ids},
+                        [("nodes", nodes.values()), ("edges", edges.values())],
+                        {"truncated": truncated, "frontier": list(frontier)},
+                    )
+                return json.dumps({
+                    "symbol": symbol,
+                    "direction": direction,
+                    "depth": depth,
+                    "roots": root_ids,
+                    "nodes": list(nodes.values()),
+                    "edges": list(edges.values()),
+                    "truncated": truncated,
+                    "frontier": list(frontier),
+                }, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"查找調用圖時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.tool()
+        async def call_hierarchy(symbol: str = None, direction: str = "both", max_depth: int = None,
+                                 max_nodes: int = MAX_HIERARCHY_NODES, paginate_from: List[str] = None) -> str:
+            """獲取符號的調用層級樹（調用者樹與被調用者樹）
+            
+            Build the call hierarchy of a symbol, as IDE call hierarchy views
+            show it: a ``callers`` tree of who calls it, their callers and so
+            on, and a ``callees`` tree of what it calls. Each entry carries
+            the function's qualified name, location and the call site linking
+            it to its parent, with the number of such calls. A function
+            reachable along several paths is expanded once; its other
+            occurrences have ``ref: true`` and the same id, without children.
+            Recursion is marked ``cycle: true`` and not expanded, and entries
+            at ``max_depth`` have no ``children`` key. When the symbol matches
+            several nodes each gets a hierarchy. Past ``max_nodes`` entries
+            the trees are cut short and ``truncated`` is true.
+            
+            ``max_depth`` defaults to the configured ``max_traversal_depth``.
+            ``frontier`` lists the entries at that depth calling or called by
+            functions the trees leave out, and also sets ``truncated``; pass
+            it back as ``paginate_from`` to build hierarchies from them
+            instead of the symbol.
+            
+            Args:
+                symbol: 符號名稱，可為節點ID、完整限定名稱、`Type.method` 或函數名稱
+                direction: 方向，"callers"、"callees" 或 "both"
+                max_depth: 展開層數，預設為設定的 max_traversal_depth
+                max_nodes: 所有樹中條目的最大數量 (最多 500)
+                paginate_from: 從這些節點ID繼續展開（如上次返回的 frontier），取代 symbol
+                
+            Returns:
+                調用層級樹的JSON字符串
+            """
+            try:
+                if direction not in HIERARCHY_DIRECTIONS:
+                    return json.dumps({"error": f"Invalid direction: {direction}"}, ensure_ascii=False)
+                max_depth = self._depth(max_depth)
+                max_nodes = max(1, min(max_nodes, MAX_HIERARCHY_NODES))
+                roots, error = self._walk_starts(symbol, paginate_from)
+                if error:
+                    return json.dumps({"error": error}, ensure_ascii=False)
+                
+                graph = self._load_graph()
+                hierarchies = []
+                node_count = 0
+                truncated = False
+                frontier: Dict[str, None] = {}
+                for root in roots:
+                    if node_count >= max_nodes:
+                        truncated = True
+                        break
+                    hierarchy = build_call_hierarchy(graph, root["id"], direction, max_depth,
+                                                     max_nodes - node_count)
+                    hierarchies.append(hierarchy.to_dict())
+                    node_count += hierarchy.node_count
+                    truncated = truncated or hierarchy.truncated
+                    frontier.update(dict.fromkeys(hierarchy.frontier))
+                
+                return json.dumps({
+                    "symbol": symbol,
+                    "direction": direction,
+                    "max_depth": max_depth,
+                    "hierarchies": hierarchies,
+                    "node_count": node_count,
+                    "truncated": truncated or bool(frontier),
+                    "frontier": list(frontier),
+                }, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"獲取調用層級時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.tool()
+        async def get_callers(symbol: str, include_unresolved: bool = False, limit: int = 100,
+                              revision: str = None, repo_path: str = ".") -> str:
+            """列出直接調用某符號的函數及調用位置
+            
+            List the direct callers of a symbol, one entry per call site, with
+            the file, line and source text of the call so it can be opened
+            directly. Only resolved CALLS edges are followed unless
+            ``include_unresolved`` is set, which adds calls that could not be
+            resolved but whose name as written ends with the symbol's name. A
+            recursive function is listed among its own callers. With
+            ``revision`` (``HEAD~3``, a branch, a tag, a SHA) the callers are
+            those at that git commit, from a graph built straight from git
+            rather than the stored one; paths are then relative to the
+            repository root.
+            
+            Args:
+                symbol: 符號名稱，可為節點ID、完整限定名稱、`Type.method` 或函數名稱
+                include_unresolved: 是否包含名稱相符但未解析的調用
+                limit: 返回調用位置的最大數量
+                revision: 查詢此 git 版本的調用，預設為已索引的圖
+                repo_path: 儲存庫中任一目錄的路徑，預設為服務器的工作目錄
+                
+            Returns:
+                調用者與調用位置的JSON字符串
+            """
+            try:
+                return json.dumps(self._direct_calls(symbol, "callers", include_unresolved, limit,
+                                                     revision, repo_path), ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"列出調用者時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.tool()
+        async def get_callees(symbol: str, include_unresolved: bool = False, limit: int = 100,
+                              revision: str = None, repo_path: str = ".") -> str:
+            """列出某符號直接調用的函數及調用位置
+            
+            List what a symbol calls directly, one entry per call site, with
+            the file, line and source text of the call. Only resolved CALLS
+            edges are followed unless ``include_unresolved`` is set, which adds
+            calls whose callee was not found, reported by the name as written
+            (``raw_name``). A recursive function is listed among its own
+            callees. With ``revision`` the callees are those at that git
+            commit, as for get_callers.
+            
+            Args:
+                symbol: 符號名稱，可為節點ID、完整限定名稱、`Type.method` 或函數名稱
+                include_unresolved: 是否包含未解析的調用
+                limit: 返回調用位置的最大數量
+                revision: 查詢此 git 版本的調用，預設為已索引的圖
+                repo_path: 儲存庫中任一目錄的路徑，預設為服務器的工作目錄
+                
+            Returns:
+                被調用者與調用位置的JSON字符串
+            """
+            try:
+                return json.dumps(self._direct_calls(symbol, "callees", include_unresolved, limit,
+                                                     revision, repo_path), ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"列出被調用者時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.tool()
+        async def find_references(symbol_id: str, limit: int = 100, cursor: str = None,
+                                  public_only: bool = False) -> str:
+            """查找符號的所有引用位置，並區分讀取、寫入與調用
+            
+            Find every location that references a symbol. Field accesses are
+            tagged Read or Write; a compound assignment such as ``+=``, or a
+            Rust method taking ``&mut self`` called on the field, is reported
+            twice, once as Read and once as Write. A Rust method call on a
+            field whose method is not defined in the indexed file, such as
+            ``self.items.len()``, is tagged Unknown. Calls to functions
+            and methods are tagged Call. Results are paged: pass the returned
+            ``next_cursor`` back to get the next page. With ``public_only``
+            only symbols whose visibility is Public are looked up.
+            
+            Args:
+                symbol_id: 符號的節點ID，亦接受 `Type.member` 或名稱
+                limit: 每頁返回引用的最大數量
+                cursor: 上一頁返回的 next_cursor
+                public_only: 是否只查找可見性為 Public 的符號
+                
+            Returns:
+                引用位置列表的JSON字符串
+            """
+            try:
+                targets = self.db.find_nodes_by_symbol(symbol_id)
+                if public_only:
+                    targets = [t for t in targets if t.get("visibility") == "Public"]
+                if not targets:
+                    return json.dumps({"error": f"Symbol not found: {symbol_id}"}, ensure_ascii=False)
+                
+                references, next_cursor = paginate(
+                    self._iter_references([t["id"] for t in targets]),
+                    limit, cursor, _cursor_scope("find_references", symbol_id, public_only)
+                )
+                
+                return json.dumps({
+                    "symbol": symbol_id,
+                    "targets": targets,
+                    "references": references,
+                    "next_cursor": next_cursor,
+                }, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"查找符號引用時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.tool()
+        async def find_usages(symbol: str, fuzzy: bool = False, limit: int = MAX_USAGES) -> str:
+            """查找符號在程式碼庫中的所有使用位置
+            
+            Reverse lookup from a symbol to every place that uses it: calls,
+            type references, field accesses, imports, inheritance (extends
+            or implements) and ActiveRecord associations. Each usage has its file, line, column (null when
+            the parser did not record one), the source line as context and a
+            ``usage_kind``. With ``fuzzy`` every symbol whose name contains
+            the query, ignoring case, is included. At most ``limit`` usages
+            are returned; ``truncated`` tells whether more were found.
+            
+            Args:
+                symbol: 符號的節點ID、`Type.member` 或名稱
+                fuzzy: 是否以不分大小寫的部分比對查找符號
+                limit: 返回使用位置的最大數量（上限 500）
+                
+            Returns:
+                使用位置列表的JSON字符串
+            """
+            try:
+                targets = self.db.find_nodes_by_symbol(symbol, limit=100 if fuzzy else 20, fuzzy=fuzzy)
+                if not targets:
+                    return json.dumps({"error": f"Symbol not found: {symbol}"}, ensure_ascii=False)
+                
+                limit = max(1, min(limit, MAX_USAGES))
+                edges = self.db.get_usages([t["id"] for t in targets], list(USAGE_KINDS))
+                usages = [{
+                    "symbol_id": edge["target_id"],
+                    "file": edge["node"].get("file_path"),
+                    "line": edge.get("line_no"),
+                    "column": edge.get("column"),
+                    "context_snippet": edge.get("snippet"),
+                    "usage_kind": USAGE_KINDS[edge["relation"]],
+                    "source": edge["node"],
+                } for edge in itertools.islice(edges, limit + 1)]
+                close = getattr(edges, "close", None)
+                if close is not None:
+                    close()
+                
+                truncated = len(usages) > limit
+                return json.dumps({
+                    "symbol": symbol,
+                    "targets": targets,
+                    "usages": usages[:limit],
+                    "truncated": truncated,
+                }, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"查找符號使用位置時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.tool()
+        async def impact_of(symbol: str = None, file_paths: List[str] = None,
+                            max_depth: int = None, limit: int = 200,
+                            paginate_from: List[str] = None) -> str:
+            """分析修改某符號或檔案會傳遞影響到哪些符號
+            
+            Report the blast radius of changing a symbol, or every symbol of
+            some files: the symbols that call, use as a type, import,
+            implement or extend it, then their own dependents and so on up
+            to ``max_depth`` edges away. Changing a type also changes the
+            members it defines, so callers of its methods are included. Each
+            affected symbol is listed once, under its file, with its
+            ``depth``, whether it is a ``direct`` dependent, and the
+            ``path`` of edges leading back to the changed symbol. ``total``,
+            ``direct`` and ``transitive`` count every affected symbol even
+            when ``limit`` cuts the list short (``truncated``).
+            
+            ``max_depth`` defaults to the configured ``max_traversal_depth``.
+            Symbols at that depth with dependents left unwalked are listed
+            in ``frontier``, which also sets ``truncated``; pass it back as
+            ``paginate_from`` to continue the walk from them instead of the
+            symbol and files. Symbols already reported may come back again.
+            
+            Args:
+                symbol: 要修改的符號，可為節點ID、完整限定名稱、`Type.member` 或名稱
+                file_paths: 要修改的檔案路徑列表（與 symbol 二選一或同時使用）
+                max_depth: 反向依賴的最大層數，預設為設定的 max_traversal_depth
+                limit: 列出受影響符號的最大數量
+                paginate_from: 從這些節點ID繼續分析（如上次返回的 frontier），取代 symbol 與 file_paths
+                
+            Returns:
+                依檔案分組的受影響符號JSON字符串
+            """
+            try:
+                max_depth = self._depth(max_depth)
+                if paginate_from:
+                    resumed, missing = self._nodes_by_ids(paginate_from)
+                    if missing:
+                        return json.dumps({"error": f"Node not found: {', '.join(missing)}"}, ensure_ascii=False)
+                    report = analyze_impact(self._load_graph(), [n["id"] for n in resumed], max_depth)
+                    return json.dumps(report.to_dict(limit), ensure_ascii=False)
+                if not symbol and not file_paths:
+                    return json.dumps({"error": "Give a symbol, file_paths or paginate_from"}, ensure_ascii=False)
+                seeds = []
+                if symbol:
+                    targets = self.db.find_nodes_by_symbol(symbol)
+                    if not targets:
+                        return json.dumps({"error": f"Symbol not found: {symbol}"}, ensure_ascii=False)
+                    seeds += [t["id"] for t in targets]
+                paths = set()
+                for path in file_paths or []:
+                    matches = self.db.find_file_paths(path)
+                    if not matches:
+                        return json.dumps({"error": f"File not indexed: {path}"}, ensure_ascii=False)
+                    paths.update(matches)
+                
+                graph = self._load_graph()
+                seeds += symbols_in_files(graph, paths)
+                report = analyze_impact(graph, seeds, max_depth)
+                return json.dumps(report.to_dict(limit), ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"分析修改影響時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.tool()
+        async def change_impact(file_paths: List[str] = None, base_ref: str = None, repo_path: str = ".",
+                                depth: int = None, limit: int = 200,
+                                paginate_from: List[str] = None) -> str:
+            """分析一組已修改檔案可能影響的其他檔案與符號
+            
+            Estimate what else might break after changing some files: every
+            symbol defined in them is treated as changed, and calls, type
+            uses, imports, implementations and subclasses are walked
+            backwards from them up to ``depth`` edges. The files can be
+            listed, or taken from git as those changed since ``base_ref``
+            (committed or not); changed files the graph does not hold, such
+            as deleted or non-source files, are listed under ``unindexed``.
+            Affected files and symbols are ranked by the number of dependency
+            paths leading to them from the changed set. ``direct_files`` have
+            a symbol, or an import, depending on a changed symbol without an
+            intermediary; ``transitive_files`` are only affected through
+            other symbols. Counts cover everything even when ``limit`` cuts
+            the symbol list short (``truncated``).
+            
+            ``depth`` defaults to the configured ``max_traversal_depth``;
+            ``frontier`` and ``paginate_from`` work as in impact_of, the
+            frontier symbols replacing the changed files.
+            
+            Args:
+                file_paths: 已修改的檔案路徑列表
+                base_ref: git ref，取此提交以來（含未提交）修改的檔案，例如 `HEAD~1` 或 `main`
+                repo_path: 儲存庫中任一目錄的路徑，預設為服務器的工作目錄
+                depth: 反向依賴的最大層數，預設為設定的 max_traversal_depth
+                limit: 列出受影響符號的最大數量
+                paginate_from: 從這些節點ID繼續分析（如上次返回的 frontier），取代已修改的檔案
+                
+            Returns:
+                依依賴路徑數排序的受影響檔案與符號JSON字符串
+            """
+            try:
+                depth = self._depth(depth)
+                if paginate_from:
+                    resumed, missing = self._nodes_by_ids(paginate_from)
+                    if missing:
+                        return json.dumps({"error": f"Node not found: {', '.join(missing)}"}, ensure_ascii=False)
+                    report = analyze_impact(self._load_graph(), [n["id"] for n in resumed], depth)
+                    return json.dumps({"changed_files": [], "unindexed": [], **report.to_ranked_dict(limit)},
+                                      ensure_ascii=False)
+                if not file_paths and not base_ref:
+                    return json.dumps({"error": "Give file_paths, a base_ref or paginate_from"}, ensure_ascii=False)
+                paths, unindexed = set(), []
+                for path in file_paths or []:
+                    matches = self.db.find_file_paths(path)
+                    if not matches:
+                        return json.dumps({"error": f"File not indexed: {path}"}, ensure_ascii=False)
+                    paths.update(matches)
+                if base_ref:
+                    for path in changed_files(repo_path, base_ref):
+                        matches = self.db.find_file_paths(path)
+                        if matches:
+                            paths.update(matches)
+                        else:
+                            unindexed.append(path)
+                
+                graph = self._load_graph()
+                report = analyze_impact(graph, symbols_in_files(graph, paths), depth)
+                return json.dumps({"changed_files": sorted(paths), "unindexed": unindexed,
+                                   **report.to_ranked_dict(limit)}, ensure_ascii=False)
+            except GitError as e:
+                return json.dumps({"error": str(e)}, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"分析檔案修改影響時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.tool()
+        async def rename_impact(old_name: str, new_name: str, file: str = None) -> str:
+            """預覽重新命名符號的影響
+            
+            Preview what renaming a function, method, type or field would
+            touch, without changing anything. Every line declaring or using
+            the symbol (the usages find_usages reports) becomes a patch
+            ``{file, line, old_text, proposed_new_text}`` replacing the name
+            where it appears in code. Further occurrences within the using
+            symbol, and usages the graph has no line for such as imports,
+            are found by searching the source and marked ``inferred``.
+            Occurrences inside string literals or comments of those files
+            are listed under ``warnings`` instead, since they should be
+            checked by hand. A symbol already named ``new_name`` in
+            the same scope (class, struct or enum, or the file for top-level
+            symbols) is reported under ``collisions``.
+            
+            Args:
+                old_name: 要重新命名的符號，可為節點ID、完整限定名稱、`Type.member` 或名稱
+                new_name: 新名稱
+                file: 只處理定義在此檔案中的符號（可選）
+                
+            Returns:
+                修改建議、警告與名稱衝突的JSON字符串
+            """
+            try:
+                new_name = re.split(r"\.|::", new_name)[-1]
+                if not re.fullmatch(r"[A-Za-z_$][\w$]*", new_name):
+                    return json.dumps({"error": f"Invalid identifier: {new_name}"}, ensure_ascii=False)
+                targets = self.db.find_nodes_by_symbol(old_name)
+                if file:
+                    matches = self.db.find_file_paths(file)
+                    if len(matches) > 1:
+                        return json.dumps({"error": f"Ambiguous file path: {file}",
+                                           "candidates": matches}, ensure_ascii=False)
+                    targets = [t for t in targets if matches and t["file_path"] == matches[0]]
+                if not targets:
+                    return json.dumps({"error": f"Symbol not found: {old_name}"}, ensure_ascii=False)
+                
+                edges = self.db.get_usages([t["id"] for t in targets], list(USAGE_KINDS))
+                usages = [{
+                    "symbol_id": edge["target_id"],
+                    "file": edge["node"].get("file_path"),
+                    "line": edge.get("line_no"),
+                    "context_snippet": edge.get("snippet"),
+                    "usage_kind": USAGE_KINDS[edge["relation"]],
+                    "source": edge["node"],
+                } for edge in itertools.islice(edges, MAX_USAGES + 1)]
+                close = getattr(edges, "close", None)
+                if close is not None:
+                    close()
+                truncated = len(usages) > MAX_USAGES
+                
+                impact = build_rename_impact(self._load_graph(), [t["id"] for t in targets],
+                                             usages[:MAX_USAGES], new_name, self._read_text)
+                return json.dumps({
+                    "old_name": old_name,
+                    "new_name": new_name,
+                    "targets": targets,
+                    **impact.to_dict(),
+                    "truncated": truncated,
+                }, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"預覽重新命名影響時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.tool()
+        async def plan_rename(symbol_id: str, new_name: str) -> str:
+            """規劃重新命名符號所需的所有修改
+            
+            List every edit renaming one symbol needs, without applying
+            any: the name at its declaration and at each reference the graph
+            resolved to it, each as ``{file, span, old_text, new_text}``
+            with the 1-based line and character columns of the name. Only
+            true references are edited: ``self.name`` / ``this.name`` for a
+            field, the called name for a call, never string literals or
+            comments. When a reference cannot be located, or a call or
+            field access that may reach the symbol was not resolved (such
+            as ``obj.name`` on a receiver of unknown type), the rename is
+            refused with an ``unresolved_references`` error listing them,
+            since a partial rename would break the code.
+            
+            Args:
+                symbol_id: 要重新命名的符號節點ID（也接受完整限定名稱或唯一的名稱）
+                new_name: 新名稱
+                
+            Returns:
+                修改列表的JSON字符串；無法完整重新命名時為錯誤
+            """
+            try:
+                new_name = re.split(r"\.|::", new_name)[-1]
+                if not re.fullmatch(r"[A-Za-z_$][\w$]*", new_name):
+                    return json.dumps({"error": f"Invalid identifier: {new_name}"}, ensure_ascii=False)
+                targets = self.db.find_nodes_by_symbol(symbol_id)
+                if not targets:
+                    return json.dumps({"error": f"Symbol not found: {symbol_id}"}, ensure_ascii=False)
+                if len(targets) > 1:
+                    return json.dumps({"error": f"Ambiguous symbol: {symbol_id}", "candidates": targets},
+                                      ensure_ascii=False)
+                
+                graph = self._load_graph(properties=["column"])
+                plan = build_rename_plan(graph, targets[0]["id"], new_name, self._read_text)
+                return json.dumps(plan.to_dict(), ensure_ascii=False)
+            except RenameRefused as e:
+                return json.dumps({"error": str(e), **e.to_dict()}, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"規劃重新命名時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.tool()
+        async def export(format: str = "dot", file_glob: str = None, node_types: List[str] = None,
+                         path_prefix: str = None, edge_types: List[str] = None,
+                         max_nodes: int = MAX_EXPORT_NODES,
+                         cluster_by_file: bool = True, edge_labels: bool = True) -> str:
+            """將知識圖譜（或其子圖）匯出為Graphviz DOT、GraphML、JSON Lines或JSON快照格式
+            
+            Export the knowledge graph, or the subgraph selected by the
+            filters, as Graphviz DOT that can be rendered with ``dot -Tsvg``,
+            or as GraphML for yEd, Gephi and NetworkX. GraphML nodes carry
+            kind, name, file and line, edges kind and call_site, and both
+            every other property under a key typed boolean, long, double or
+            string. ``jsonl`` writes one node or edge object per line with all
+            its properties. ``json`` writes a snapshot that ``graph_diff``
+            compares with another.
+            
+            Args:
+                format: 匯出格式，"dot"、"graphml"、"jsonl" 或 "json"
+                file_glob: 檔案路徑的萬用字元模式，例如 "src/graph/*"
+                node_types: 要保留的節點類型，例如 ["Class", "Function"]
+                path_prefix: 只保留此檔案或目錄下的節點，例如 "/repo/src/parser"
+                edge_types: 要保留的關係類型，例如 ["CALLS", "IMPORTS"]
+                max_nodes: 節點數上限，超過時回傳錯誤而非匯出 (最多 5000)
+                cluster_by_file: 是否依檔案將節點分組
+                edge_labels: 是否在邊上顯示關係類型
+                
+            Returns:
+                包含匯出內容與節點、邊數量的JSON字符串
+            """
+            try:
+                if format not in EXPORTERS:
+                    return json.dumps({"error": f"Unsupported export format: {format}"}, ensure_ascii=False)
+                
+                graph = self._load_graph(node_types)
+                opts = DotOptions(file_glob=file_glob, node_types=node_types,
+                                  path_prefix=path_prefix, edge_types=edge_types,
+                                  max_nodes=max(1, min(max_nodes, MAX_EXPORT_NODES)),
+                                  cluster_by_file=cluster_by_file, edge_labels=edge_labels)
+                nodes, relations = select_subgraph(graph, opts)
+                
+                return json.dumps({
+                    "format": format,
+                    "nodes": len(nodes),
+                    "edges": len(relations),
+                    "content": EXPORTERS[format]().render(graph, opts),
+                }, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"匯出知識圖譜時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+    
+        @self.mcp.tool()
+        async def call_path(source: str = None, target: str = None, max_depth: int = None,
+                            paginate_from: List[str] = None) -> str:
+            """查找兩個符號之間的最短調用路徑
+            
+            Answer "can source eventually reach target through calls?". Only
+            CALLS edges are followed; the shortest path is returned, or null
+            when target is not reachable within ``max_depth`` hops. When a
+            name matches several symbols the shortest path over all pairs wins.
+            
+            ``max_depth`` defaults to the configured ``max_traversal_depth``.
+            When no path is found, ``frontier`` lists the functions at that
+            depth with calls left unwalked, and ``truncated`` is true when it
+            is not empty. Pass it back as ``paginate_from`` to search on from
+            those functions instead of source; the path found then starts at
+            one of them.
+            
+            Args:
+                source: 起點符號，可為節點ID、完整限定名稱、`Type.method` 或函數名稱
+                target: 終點符號，格式同 source
+                max_depth: 最大調用層數，預設為設定的 max_traversal_depth
+                paginate_from: 從這些節點ID繼續搜尋（如上次返回的 frontier），取代 source
+                
+            Returns:
+                調用路徑的JSON字符串
+            """
+            try:
+                max_depth = self._depth(max_depth)
+                if not source and not paginate_from:
+                    return json.dumps({"error": "Give a source or paginate_from"}, ensure_ascii=False)
+                sources, error = self._walk_starts(source, paginate_from)
+                if error:
+                    return json.dumps({"error": error}, ensure_ascii=False)
+                targets = self.db.find_nodes_by_symbol(target) if target else []
+                if not targets:
+                    return json.dumps({"error": f"Symbol not found: {target}"}, ensure_ascii=False)
+                
+                graph = self._load_graph()
+                path = None
+                stopped: Dict[str, None] = {}
+                for start in sources:
+                    for end in targets:
+                        at_limit: List[str] = []
+                        found = graph.reachable(start["id"], end["id"], max_depth, at_limit)
+                        stopped.update(dict.fromkeys(at_limit))
+                        if found is not None and (path is None or len(found) < len(path)):
+                            path = found
+                frontier = [] if path is not None else list(stopped)
+                
+                return json.dumps({
+                    "source": source,
+                    "target": target,
+                    "max_depth": max_depth,
+                    "path": None if path is None else [self._node_summary(graph, node_id) for node_id in path],
+                    "hops": None if path is None else len(path) - 1,
+                    "truncated": bool(frontier),
+                    "frontier": frontier,
+                }, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"查找調用路徑時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+    
+        @self.mcp.tool()
+        async def find_cycles(kind: str = "module", min_size: int = 1, include_self_loops: bool = False,
+                              project: str = None) -> str:
+            """查找循環依賴（模組導入或函數調用）
+            
+            Find circular dependencies. ``module`` looks for import cycles
+            between files (over IMPORTS edges), ``function`` for call cycles
+            between functions and methods (over CALLS edges). Every strongly
+            connected component with more than one member is returned with
+            its nodes in cycle order, the back edges that close the cycle
+            and ``path``, the shortest loop through its first node as node
+            IDs, e.g. [A, B, C, A]; ``include_self_loops`` adds functions
+            calling themselves and files importing from themselves as
+            cycles of one, and ``min_size`` drops smaller cycles.
+            ``project`` keeps the cycles passing through a file or function
+            of that workspace project. The cycles are found over the whole
+            graph loaded at once, and are listed in the same order on every
+            run.
+            
+            Args:
+                kind: 依賴類型，"module" 或 "function"
+                min_size: 只回傳成員數不少於此值的循環，例如 3
+                include_self_loops: 是否將呼叫自身的函數或導入自身的檔案視為循環
+                project: 只保留經過此工作區專案的循環，例如 "shop"
+                
+            Returns:
+                循環依賴列表的JSON字符串
+            """
+            try:
+                if kind not in cycles.CYCLE_KINDS:
+                    return json.dumps({"error": f"Invalid kind: {kind}"}, ensure_ascii=False)
+                
+                graph = self._load_graph(properties=["project"]) if project else self._load_graph()
+                found = [{
+                    "size": len(cycle.nodes),
+                    "nodes": [self._node_summary(graph, node_id) for node_id in cycle.nodes],
+                    "path": cycle.path,
+                    "back_edges": [{"source": source, "target": target, "type": relation_type}
+                                   for source, target, relation_type in cycle.back_edges],
+                } for cycle in cycles.find_cycles(graph, kind, min_size, include_self_loops)
+                    if not project or any(graph.nodes[node_id].properties.get("project") == project
+                                          for node_id in cycle.nodes)]
+                
+                return json.dumps({
+                    "kind": kind,
+                    "count": len(found),
+                    "cycles": found,
+                }, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"查找循環依賴時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+    
+        @self.mcp.tool()
+        async def find_unreferenced_symbols(allowlist: List[str] = None, exempt_public: bool = False,
+                                            min_confidence: str = "low", limit: int = 200) -> str:
+            """查找未被引用的符號（死碼）
+            
+            List functions, methods and types that nothing in the graph calls,
+            references, imports, extends or implements. Entry points (main),
+            tests, dunder methods, trait and interface methods, decorated
+            symbols and symbols with a non-inert Rust attribute (a macro may
+            use them) are exempt. Each result has its kind, path, line, a
+            confidence (high for private symbols, low for public ones,
+            methods and names an unresolved call may have meant) and the
+            reason; results are sorted by confidence, then file and line.
+            
+            Args:
+                allowlist: 正規表達式列表，名稱符合者不列出
+                exempt_public: 是否略過公開符號（例如函式庫）
+                min_confidence: 最低信心等級，"high"、"medium" 或 "low"
+                limit: 最多返回的符號數量
+                
+            Returns:
+                未被引用符號列表的JSON字符串
+            """
+            try:
+                if min_confidence not in CONFIDENCE_LEVELS:
+                    return json.dumps({"error": f"Invalid min_confidence: {min_confidence}"}, ensure_ascii=False)
+                
+                graph = self._load_graph(properties=list(DEAD_CODE_PROPERTIES))
+                try:
+                    dead = find_unreferenced(graph, allowlist or [], exempt_public)
+                except re.error as e:
+                    return json.dumps({"error": f"Invalid allowlist pattern: {e}"}, ensure_ascii=False)
+                dead = at_least(dead, min_confidence)
+                
+                return json.dumps({
+                    "count": len(dead),
+                    "truncated": len(dead) > limit,
+                    "symbols": [d.to_dict() for d in dead[:max(0, limit)]],
+                }, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"查找未被引用符號時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+    
+        @self.mcp.tool()
+        async def find_tests_for(symbol: str = None, max_depth: int = None, paginate_from: List[str] = None) -> str:
+            """查找覆蓋某符號的測試函數
+            
+            List the test functions exercising a symbol: those calling it
+            directly (a TESTS edge) and those reaching it through at most
+            ``max_depth`` calls via non-test functions. Test functions are
+            recognised at indexing per language (pytest and unittest, Rust
+            test attributes, Go Test/Benchmark functions, JS/TS test files).
+            Each test has its location, its depth (1 for a direct call) and
+            ``via``, the symbol reached followed by the callers in between.
+            
+            ``max_depth`` defaults to the configured ``max_traversal_depth``.
+            ``frontier`` lists the functions at that depth whose callers were
+            not walked, and ``truncated`` is true when it is not empty. Pass
+            it back as ``paginate_from`` to look for tests reaching them
+            instead of the symbol.
+            
+            Args:
+                symbol: 符號名稱，可為節點ID、完整限定名稱、`Type.method` 或函數名稱
+                max_depth: 測試與符號之間的最大調用層數，預設為設定的 max_traversal_depth
+                paginate_from: 從這些節點ID繼續查找（如上次返回的 frontier），取代 symbol
+                
+            Returns:
+                測試函數列表的JSON字符串
+            """
+            try:
+                max_depth = self._depth(max_depth)
+                targets, error = self._walk_starts(symbol, paginate_from)
+                if error:
+                    return json.dumps({"error": error}, ensure_ascii=False)
+                
+                graph = self._load_graph(properties=["is_test"])
+                report = find_covering_tests(graph, [t["id"] for t in targets], max_depth)
+                return json.dumps(dict(report.to_dict(), symbol=symbol), ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"查找覆蓋測試時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+    
+        @self.mcp.tool()
+        async def find_unused(include_public: bool = False, entrypoints: Dict[str, Dict[str, Any]] = None,
+                              limit: int = 200) -> str:
+            """查找沒有任何調用或引用的函數與方法
+            
+            List functions and methods with no incoming CALLS or REFERENCES
+            edge in the stored (cross-file resolved) graph. Public symbols
+            are left out unless ``include_public`` is set, for finding
+            library code no indexed caller uses. Entry points are recognised
+            per language: by default ``main``, tests (``test_*``, Go
+            ``TestXxx``, Rust ``#[test]``), Rust ``#[tokio::main]``-style
+            attributes, web framework decorators and exported JavaScript and
+            TypeScript handlers. ``entrypoints`` replaces the rules of the
+            languages it names, e.g. ``{"rust": {"names": ["^main$"],
+            "attributes": ["^test$"], "exported": false}}``.
+            
+            Args:
+                include_public: 是否也列出公開符號
+                entrypoints: 各語言的入口規則，鍵為語言名稱
+                limit: 最多返回的符號數量
+                
+            Returns:
+                未使用函數列表的JSON字符串
+            """
+            try:
+                try:
+                    rules = {language: EntrypointRules.from_dict(r) for language, r in (entrypoints or {}).items()}
+                except (AttributeError, TypeError, ValueError) as e:
+                    return json.dumps({"error": f"Invalid entrypoints: {e}"}, ensure_ascii=False)
+                
+                graph = self._load_graph(properties=list(DEAD_CODE_PROPERTIES))
+                try:
+                    unused = find_unused_symbols(graph, include_public, rules)
+                except re.error as e:
+                    return json.dumps({"error": f"Invalid entrypoint pattern: {e}"}, ensure_ascii=False)
+                
+                return json.dumps({
+                    "count": len(unused),
+                    "truncated": len(unused) > limit,
+                    "symbols": [u.to_dict() for u in unused[:max(0, limit)]],
+                }, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"查找未使用函數時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+    
+        @self.mcp.tool()
+        async def find_dead_code(entry_points: List[str] = None, include_tests: bool = False,
+                                 public_roots: List[str] = None, limit: int = 200) -> str:
+            """查找從入口點無法到達的 Rust 符號
+            
+            Walk the graph from entry points and report the Rust functions,
+            methods and types never reached, with file path and line for
+            highlighting. Entry points are functions named in
+            ``entry_points`` (default ``main``), ``pub`` items of the files
+            in ``public_roots`` (default ``lib.rs``), items with a macro
+            attribute and, with ``include_tests``, ``#[test]`` functions.
+            Calls, references and trait dispatch are followed. Unreached
+            groups an unresolved call may have meant, as happens when parsing
+            was incomplete, get ``low`` confidence.
+            
+            Args:
+                entry_points: 入口函數名稱列表
+                include_tests: 是否將測試函數視為入口點
+                public_roots: 其公開項目視為入口點的檔案名稱
+                limit: 最多返回的符號數量
+                
+            Returns:
+                無法到達符號列表的JSON字符串
+            """
+            try:
+                graph = self._load_graph(properties=list(DEAD_CODE_PROPERTIES))
+                try:
+                    dead = find_unreachable(graph, entry_points or ["main"], include_tests,
+                                            public_roots or ["lib.rs"])
+                except ValueError as e:
+                    return json.dumps({"error": str(e)}, ensure_ascii=False)
+                
+                return json.dumps({
+                    "count": len(dead),
+                    "truncated": len(dead) > limit,
+                    "symbols": [d.to_dict() for d in dead[:max(0, limit)]],
+                }, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"查找無法到達符號時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.tool()
+        async def find_sync_in_async(limit: int = 200) -> str:
+            """查找在 async 函數中調用可能阻塞的同步 I/O 函數
+            
+            List the calls from ``async`` functions to functions that are not
+            async and look like they do I/O, as potential blocking hazards:
+            the callee's name contains ``read``, ``write``, ``recv`` or
+            ``send``, or it lives in a module named ``io``, ``net`` or ``fs``.
+            Calls awaited on the spot (``f(..).await``) and calls to async
+            functions are left out. Each hazard names the async function,
+            the callee, the call site and the reasons it was flagged.
+            
+            Args:
+                limit: 最多返回的調用數量
+                
+            Returns:
+                潛在阻塞調用列表的JSON字符串
+            """
+            try:
+                hazards = find_async_hazards(self._load_graph(properties=list(ASYNC_PROPERTIES)))
+                return json.dumps({
+                    "count": len(hazards),
+                    "truncated": len(hazards) > limit,
+                    "hazards": [h.to_dict() for h in hazards[:max(0, limit)]],
+                }, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"查找 async 函數中的阻塞調用時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+    
+        @self.mcp.tool()
+        async def get_metrics(symbol: str) -> str:
+            """獲取函數或方法的扇入、扇出與圈複雜度
+            
+            Report the graph metrics of a function or method: ``fan_in``,
+            the number of calls into it (one per call site), ``fan_out``,
+            the number of distinct functions and methods it calls, and
+            ``complexity``, its cyclomatic complexity (one plus each branch,
+            loop, case and ``&&``/``||``; null when it has no body). Calls
+            that were not resolved count toward neither. When the symbol
+            matches several functions each gets an entry.
+            
+            Args:
+                symbol: 符號名稱，可為節點ID、完整限定名稱、`Type.method` 或函數名稱
+                
+            Returns:
+                度量列表的JSON字符串
+            """
+            try:
+                targets = [n for n in self.db.find_nodes_by_symbol(symbol) if n["type"] in FUNCTION_TYPES]
+                if not targets:
+                    return json.dumps({"error": f"Symbol not found: {symbol}"}, ensure_ascii=False)
+                graph = self._load_graph(list(FUNCTION_TYPES), properties=list(METRIC_PROPERTIES))
+                metrics = [m for m in (graph.metrics(t["id"]) for t in targets) if m is not None]
+                return json.dumps({"symbol": symbol, "metrics": [m.to_dict() for m in metrics]},
+                                  ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"獲取函數度量時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.tool()
+        async def find_duplicates(mode: str = "exact", threshold: float = DEFAULT_SIMILARITY,
+                                  max_distance: int = DEFAULT_MAX_DISTANCE, min_tokens: int = DEFAULT_MIN_TOKENS,
+                                  limit: int = 50) -> str:
+            """查找內容幾乎相同的函數，找出複製貼上的程式碼
+            
+            Group functions and methods whose bodies are copies of each
+            other. Each body is read from its file and reduced to a token
+            stream with identifiers, literals, comments and whitespace
+            normalized away, so renamed copies still match. ``exact`` mode
+            groups bodies whose streams hash the same; ``fuzzy`` mode also
+            links bodies whose token edit distance keeps their similarity
+            (``1 - distance / longer length``) at or above ``threshold``,
+            computing at most ``max_distance`` edits per pair. Bodies under
+            ``min_tokens`` tokens are ignored. Each group lists its members
+            as ``{file, function_name, line}`` with their size in tokens,
+            largest groups first.
+            
+            Args:
+                mode: 比對模式，"exact"（雜湊相同）或 "fuzzy"（編輯距離）
+                threshold: fuzzy 模式下的最低相似度 (0-1)
+                max_distance: fuzzy 模式下每對函數最多計算的編輯次數
+                min_tokens: 少於此詞元數量的函數不參與比對
+                limit: 最多返回的重複群組數量
+                
+            Returns:
+                重複函數群組的JSON字符串
+            """
+            try:
+                graph = self._load_graph(list(FUNCTION_TYPES))
+                bodies = function_bodies(graph.nodes.values(), self._read_text)
+                groups = group_duplicates(bodies, mode, threshold, max(1, min_tokens), max(0, max_distance))
+                return json.dumps({
+                    "mode": mode,
+                    "functions": len(bodies),
+                    "count": len(groups),
+                    "truncated": len(groups) > limit,
+                    "groups": [g.to_dict() for g in groups[:max(0, limit)]],
+                }, ensure_ascii=False)
+            except ValueError as e:
+                return json.dumps({"error": str(e)}, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"查找重複函數時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.tool()
+        async def top_complex(n: int = 10) -> str:
+            """列出圈複雜度最高的函數與方法
+            
+            List the ``n`` functions and methods of highest cyclomatic
+            complexity, most complex first, with their fan-in and fan-out so
+            the risky hot-spots (complex and called from many places) stand
+            out. Ties go to the higher fan-in. Functions without a recorded
+            complexity are left out.
+            
+            Args:
+                n: 返回的函數數量 (最多 200)
+                
+            Returns:
+                函數度量列表的JSON字符串
+            """
+            try:
+                n = max(1, min(n, MAX_TOP_COMPLEX))
+                graph = self._load_graph(list(FUNCTION_TYPES), properties=list(METRIC_PROPERTIES))
+                return json.dumps({"functions": [m.to_dict() for m in rank_complexity(graph, n)]},
+                                  ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"列出高複雜度函數時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+    
+        @self.mcp.tool()
+        async def get_file_outline(file_path: str, high_complexity: int = None,
+                                   exported_only: bool = False) -> str:
+            """取得單一檔案的階層式符號大綱
+            
+            Return what a file declares as a tree: classes, structs, traits
+            and enums with their methods, fields and variants nested under
+            them, free functions and variables at the top level. Fields
+            carry their declared ``field_type``. Each entry carries its
+            kind, name, visibility, start/end line and doc comment (null when
+            not captured), in source order, and functions and methods their
+            cyclomatic complexity when the parser records it. With
+            ``high_complexity`` only functions at or above that complexity
+            are kept, under their enclosing types. Symbols a JavaScript or
+            TypeScript module exports carry ``export_type`` ("named" or
+            "default") and ``export_name``, the name importers use;
+            ``exported_only`` keeps just those, answering what the module
+            exports. A path that is not indexed is an error; an indexed file
+            without symbols has an empty outline.
+            
+            Args:
+                file_path: 檔案路徑，可為絕對路徑或相對於索引根目錄的路徑
+                high_complexity: 只保留圈複雜度不低於此值的函數與方法
+                exported_only: 只保留模組匯出的頂層符號
+                
+            Returns:
+                符號大綱的JSON字符串
+            """
+            try:
+                matches = self.db.find_file_paths(file_path)
+                if not matches:
+                    return json.dumps({"error": f"File not indexed: {file_path}"}, ensure_ascii=False)
+                if len(matches) > 1:
+                    return json.dumps({"error": f"Ambiguous file path: {file_path}",
+                                       "candidates": matches}, ensure_ascii=False)
+                
+                symbols = self.db.get_file_symbols(matches[0])
+                outline = build_outline(symbols["nodes"], symbols["defines"])
+                if high_complexity is not None:
+                    outline = prune_outline(outline, high_complexity)
+                if exported_only:
+                    outline = [entry for entry in outline if entry.export_type is not None]
+                return json.dumps({
+                    "file_path": matches[0],
+                    "symbols": [entry.to_dict() for entry in outline],
+                }, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"取得檔案大綱時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+    
+        @self.mcp.tool()
+        async def symbol_at_position(file_path: str, line: int, column: int = 1,
+                                     position_encoding: str = "utf-32") -> str:
+            """查找檔案中某個位置所在的符號
+            
+            Return the symbol whose source span contains a position, for
+            editor hover: the innermost one first, then the symbols enclosing
+            it (a method, then its struct). Identical spans are ordered by
+            kind, a method before a class. Symbols parsed without columns
+            span whole lines. A path that is not indexed is an error; a
+            position outside every symbol gives a null symbol.
+            
+            Columns count characters (LSP's ``utf-32``) unless
+            ``position_encoding`` says the client counts UTF-16 code units
+            or UTF-8 bytes, as LSP editors do; the column is then converted
+            against the file's current text, which must be readable.
+            
+            Args:
+                file_path: 檔案路徑，可為絕對路徑或相對於索引根目錄的路徑
+                line: 行號（從 1 開始）
+                column: 欄位（從 1 開始）
+                position_encoding: 欄位單位，"utf-32"（字元）、"utf-16" 或 "utf-8"
+                
+            Returns:
+                符號及其外層符號的JSON字符串
+            """
+            try:
+                matches = self.db.find_file_paths(file_path)
+                if not matches:
+                    return json.dumps({"error": f"File not indexed: {file_path}"}, ensure_ascii=False)
+                if len(matches) > 1:
+                    return json.dumps({"error": f"Ambiguous file path: {file_path}",
+                                       "candidates": matches}, ensure_ascii=False)
+                
+                if position_encoding not in ENCODINGS:
+                    return json.dumps({"error": f"Unknown position encoding: {position_encoding}",
+                                       "encodings": list(ENCODINGS)}, ensure_ascii=False)
+                character = column
+                if position_encoding != CHARACTER_ENCODING:
+                    try:
+                        lines = LineIndex.from_file(matches[0])
+                    except (OSError, UnicodeDecodeError) as e:
+                        return json.dumps({"error": f"Cannot read {matches[0]} to convert "
+                                                    f"{position_encoding} columns: {e}"}, ensure_ascii=False)
+                    character = lines.decode_column(line, column, position_encoding)
+                
+                symbols = self.db.get_file_symbols(matches[0])
+                graph = CodeGraph.from_records([
+                    CodeNode(n["id"], n["type"], n["name"], matches[0], n["line_no"] or 0, n.get("end_line_no"),
+                             {key: n[key] for key in ("column", "end_column") if n.get(key) is not None})
+                    for n in symbols["nodes"]
+                ], [])
+                found = [self._node_summary(graph, node_id) for node_id in graph.nodes_at(matches[0], line, character)]
+                return json.dumps({
+                    "file_path": matches[0],
+                    "line": line,
+                    "column": column,
+                    "position_encoding": position_encoding,
+                    "symbol": found[0] if found else None,
+                    "enclosing": found[1:],
+                }, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"查找位置所在符號時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+    
+        @self.mcp.tool()
+        async def get_source_snippet(symbol: str, context_lines: int = 0) -> str:
+            """讀取符號的原始碼片段
+            
+            Return the exact source of a symbol, read from disk: the span
+            recorded when it was indexed, from its start line and column to
+            its end line and column, with its UTF-8 byte offsets.
+            ``context_lines`` adds up to that many whole lines before and
+            after it. A name matching several symbols gives a snippet for
+            each. If a file changed since it was indexed (its content hash
+            differs) its snippet is ``stale``, cut by line and column as a
+            best effort, with a warning; a file that cannot be read gives an
+            error for its snippets only.
+            
+            Args:
+                symbol: 符號名稱，可為節點ID、完整限定名稱、`Type.method` 或函數名稱
+                context_lines: 片段前後附帶的行數 (最多 50)
+                
+            Returns:
+                原始碼片段的JSON字符串
+            """
+            try:
+                context_lines = max(0, min(context_lines, MAX_CONTEXT_LINES))
+                targets = self.db.find_nodes_by_symbol(symbol)
+                if not targets:
+                    return json.dumps({"error": f"Symbol not found: {symbol}"}, ensure_ascii=False)
+                
+                hashes = {n["file_path"]: (n.get("properties") or {}).get("content_hash")
+                          for n in self.db.get_graph(["File"], ["content_hash"])["nodes"]}
+                spans: Dict[str, Dict[str, Any]] = {}
+                snippets = []
+                for target in targets:
+                    path = target["file_path"]
+                    if path not in spans:
+                        spans[path] = {n["id"]: n for n in self.db.get_file_symbols(path)["nodes"]}
+                    entry = {key: target.get(key) for key in ("id", "name", "type", "file_path")}
+                    try:
+                        with open(path, "rb") as f:
+                            data = f.read()
+                    except OSError as e:
+                        snippets.append({**entry, "error": f"Cannot read {path}: {e}"})
+                        continue
+                    span = spans[path].get(target["id"], target)
+                    snippets.append({**entry, **extract_snippet(data, span, hashes.get(path), context_lines)})
+                
+                return json.dumps({
+                    "symbol": symbol,
+                    "context_lines": context_lines,
+                    "snippets": snippets,
+                }, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"讀取原始碼片段時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+    
         @self.mcp.tool()
-        async def search_code(query: str, limit: int = 10, search_type: str = "vector") -> str:
-            """搜索程式碼
+        async def search_symbols(pattern: str, kinds: str = None, file_glob: str = None, path_prefix: str = None,
+                                 use_regex: bool = True, limit: int = 50, high_complexity: int = None,
+                                 exact: bool = False, case_sensitive: bool = False, kind: str = None,
+                                 project: str = None) -> str:
+            """依名稱搜尋符號，支援子字串、模糊比對與正規表示式
+            
+            Rank symbols by how their name matches the pattern: an exact
+            match first, then a prefix, then word starts (camelCase and
+            snake_case words, e.g. "getname" or "gn" for "get_name"), then a
+            substring, then a fuzzy match (the pattern's characters in
+            order, e.g. "prsn" for "Person"), then a regular expression
+            match. Plain matching
+            ignores case unless ``case_sensitive`` is set. A pattern with
+            ``.`` or ``::`` matches members by owner, so "Person.nam" finds
+            the ``name`` field of ``Person``. Equal scores go to types before
+            functions before locals, then to shallower files, then to
+            shorter names. ``exact=true``
+            only returns symbols named exactly the pattern.
+            
+            The pattern is an ECMAScript regular expression, optionally
+            written as a ``/body/flags`` literal; an invalid one gives a
+            structured error with the offending position. Each result
+            carries the symbol's doc comment (null when it has none), capped
+            at 4KB. Symbols are searched in an in-memory index that is
+            reloaded from the store every 30 seconds.
             
             Args:
-                query: 搜索查詢
-                limit: 返回結果的最大數量
-                search_type: 搜索類型，可選 "vector" 或 "text"
+                pattern: 符號名稱、`Owner.member` 或正規表示式，例如 "parse_" 或 "/^get[A-Z]/"
+                kinds: 以逗號分隔的符號種類，例如 "function,struct"
+                file_glob: 檔案路徑的萬用字元模式，例如 "src/graph/*"
+                path_prefix: 只保留此檔案或目錄下的符號，例如 "src/graph"
+                use_regex: 是否同時以正規表示式比對
+                limit: 最多回傳的結果數 (最多 200)
+                high_complexity: 只保留圈複雜度不低於此值的函數與方法
+                exact: 只回傳名稱與模式完全相同（區分大小寫）的符號
+                case_sensitive: 比對時是否區分大小寫
+                kind: 同 kinds，保留給舊的呼叫方式
+                project: 只保留此工作區專案的符號，例如 "shop"
                 
             Returns:
-                搜索結果的JSON字符串
+                依分數排序的符號列表JSON字符串
             """
             try:
-                results = []
-                
-                if search_type == "vector":
-                    # 生成查詢的嵌入向量
-                    vector = self.code_embedder.provider.embed_text(query)
-                    
-                    # 使用向量搜索
-                    for node_label in ["Function", "Method", "Class", "File"]:
-                        node_results = self.db.search_code_by_vector(vector, node_label, limit)
-                        results.extend(node_results)
-                    
-                    # 根據分數排序
-                    results = sorted(results, key=lambda x: x["score"], reverse=True)[:limit]
-                    
-                elif search_type == "text":
-                    # 使用全文檢索
-                    results = self.db.search_code_by_text(query, limit)
-                
-                return json.dumps(results, ensure_ascii=False)
-                
+                kinds = ",".join(k for k in (kinds, kind) if k)
+                node_types = parse_node_kinds(kinds) if kinds else None
+                limit = max(1, min(limit, MAX_SEARCH_RESULTS))
+                found = self._symbol_index().search(pattern, node_types=node_types, file_glob=file_glob,
+                                                    path_prefix=path_prefix, use_regex=use_regex,
+                                                    min_complexity=high_complexity, exact=exact,
+                                                    case_sensitive=case_sensitive, limit=limit, project=project)
+                return json.dumps({
+                    "pattern": pattern,
+                    "total": found.total,
+                    "truncated": found.total > limit,
+                    "results": [match.to_dict() for match in found.matches],
+                }, ensure_ascii=False)
+            except PatternError as e:
+                return json.dumps({"error": str(e), **e.to_dict()}, ensure_ascii=False)
             except Exception as e:
-                logger.error(f"搜索程式碼時發生錯誤: {e}")
+                logger.error(f"搜尋符號時發生錯誤: {e}")
                 return json.dumps({"error": str(e)})
-        
+    
         @self.mcp.tool()
-        async def execute_cypher_query(query: str, parameters: Dict = None) -> str:
-            """執行Cypher查詢
+        async def query_nodes(query: str, limit: int = 100, stream: bool = False,
+                              ctx: Context = None) -> str:
+            """以篩選運算式查詢符號
+            
+            Return every symbol matching a filter expression such as
+            ``kind:function AND visibility:public AND file:"src/*.rs"``.
+            Predicates are ``field:value`` over ``kind`` (as for
+            search_symbols' kinds, e.g. "function,method"), ``name``,
+            ``file`` and ``project`` (the workspace project; all three
+            taking globs), ``visibility`` (public, crate, protected or
+            private) and ``deprecated`` (true or false, for Java
+            ``@Deprecated`` symbols), combined with ``NOT``, ``AND`` and
+            ``OR`` in that order of precedence, and parentheses. A value
+            with spaces or parentheses is written in double quotes. A
+            malformed expression gives a structured error with the offending
+            position rather than no results. Results are sorted by file and
+            line. With ``stream`` they are sent in batches as JSON Lines
+            ``nodes`` records instead of one document.
             
             Args:
-                query: Cypher查詢語句
-                parameters: 查詢參數
+                query: 篩選運算式，例如 "(kind:struct OR kind:enum) AND NOT name:_*"
+                limit: 最多回傳的結果數 (最多 1000)
+                stream: 是否以 JSON Lines 記錄分批傳送結果
                 
             Returns:
-                查詢結果的JSON字符串
+                符合條件的符號列表JSON字符串；串流時為 JSON Lines
             """
             try:
-                results = self.db.execute_cypher(query, parameters)
-                return json.dumps(results, ensure_ascii=False)
+                expression = parse_query(query)
+                limit = max(1, min(limit, MAX_QUERY_RESULTS))
+                found = filter_nodes(self._symbol_index().symbols, expression)
+                if stream:
+                    return await self._stream_result(
+                        ctx, "query_nodes",
+                        {"query": query, "filter": str(expression), "total": len(found)},
+                        [("nodes", found[:limit])],
+                        {"truncated": len(found) > limit},
+                    )
+                return json.dumps({
+                    "query": query,
+                    "filter": str(expression),
+                    "total": len(found),
+                    "truncated": len(found) > limit,
+                    "results": found[:limit],
+                }, ensure_ascii=False)
+            except QueryError as e:
+                return json.dumps({"error": str(e), **e.to_dict()}, ensure_ascii=False)
             except Exception as e:
-                logger.error(f"執行Cypher查詢時發生錯誤: {e}")
+                logger.error(f"查詢符號時發生錯誤: {e}")
                 return json.dumps({"error": str(e)})
-        
+    
         @self.mcp.tool()
-        async def get_code_by_name(name: str, node_type: str = None) -> str:
-            """根據名稱獲取程式碼
+        async def find_implementations(trait_id: str, direction: str = "implementors") -> str:
+            """查找實作某個特徵（trait）或介面的所有型別
+            
+            List every type with an ``impl Trait for Type`` (or a TypeScript
+            ``class Type implements Interface``) for the given trait, each
+            with the methods it provides for the trait. Given a trait method
+            instead, list the concrete methods implementing it. Traits
+            defined in another file are linked across files. A blanket impl
+            (``impl<T: Display> Summary for T``) is listed at the file and
+            line declaring it, with ``blanket`` set and its bounds. A trait
+            that is not indexed (from another crate) is matched by the path
+            written in the impls, so ``std::fmt::Display`` still finds
+            ``impl fmt::Display for Person``.
+            
+            With ``direction="implemented"`` the lookup runs the other way:
+            given a type, list the traits and interfaces it implements, or
+            given a method, the trait methods it provides.
             
             Args:
-                name: 程式碼名稱(類別名、函數名等)
-                node_type: 節點類型，可選 "Function", "Method", "Class", "File"
+                trait_id: 特徵、型別或方法的節點ID，亦接受 `Trait.method` 或名稱
+                direction: 方向，"implementors"（實作此特徵者）或 "implemented"（此型別實作的特徵）
                 
             Returns:
-                程式碼的JSON字符串
+                實作列表的JSON字符串
             """
             try:
-                query = """
-                MATCH (n)
-                WHERE n.name = $name
-                """
+                if direction not in ("implementors", "implemented"):
+                    return json.dumps({"error": f"Invalid direction: {direction}"}, ensure_ascii=False)
                 
-                if node_type:
-                    query += f" AND n:{node_type}"
+                targets = self.db.find_nodes_by_symbol(trait_id)
+                if direction == "implemented":
+                    implementations = self.db.get_implemented([t["id"] for t in targets]) if targets else []
+                elif targets:
+                    implementations = self.db.get_implementations([t["id"] for t in targets])
+                else:
+                    # The trait was not indexed; match the path written in the impls
+                    records = self.db.find_implementors(trait_id)
+                    targets = list({record["trait"]["id"]: record["trait"] for record in records}.values())
+                    implementations = [{
+                        "target_id": record["trait"]["id"],
+                        "trait_path": record.get("trait_path"),
+                        "node": record["node"],
+                        "methods": [],
+                        "blanket": record.get("blanket", False),
+                    } for record in records]
+                if not targets:
+                    return json.dumps({"error": f"Symbol not found: {trait_id}"}, ensure_ascii=False)
                 
-                query += " RETURN n LIMIT 10"
-                
-                results = self.db.execute_cypher(query, {"name": name})
-                return json.dumps(results, ensure_ascii=False)
+                return json.dumps({
+                    "trait": trait_id,
+                    "direction": direction,
+                    "targets": targets,
+                    "count": len(implementations),
+                    "implementations": implementations,
+                }, ensure_ascii=False)
             except Exception as e:
-                logger.error(f"獲取程式碼時發生錯誤: {e}")
+                logger.error(f"查找特徵實作時發生錯誤: {e}")
                 return json.dumps({"error": str(e)})
         
         @self.mcp.tool()
-        async def find_function_callers(function_name: str, limit: int = 10) -> str:
-            """查找調用特定函數的所有位置
+        async def find_implementors(trait_name: str) -> str:
+            """查找實作指定特徵（trait）的所有型別及其位置
+            
+            Look a trait up by name rather than by node: ``Display``,
+            ``fmt::Display`` and ``std::fmt::Display`` all match
+            ``impl fmt::Display for Person``, including traits from crates
+            that were not indexed. Each implementor is reported once per
+            trait it matches, with its file and line span.
             
             Args:
-                function_name: 函數名稱
-                limit: 返回結果的最大數量
+                trait_name: 特徵名稱，可為 `Display` 或 `std::fmt::Display` 等路徑
                 
             Returns:
-                調用者的JSON字符串
+                實作型別列表的JSON字符串
             """
             try:
-                query = """
-                MATCH (caller)-[:CALLS]->(callee)
-                WHERE callee.name = $function_name
-                RETURN caller
-                LIMIT $limit
-                """
-                
-                results = self.db.execute_cypher(query, {
-                    "function_name": function_name,
-                    "limit": limit
-                })
-                
-                return json.dumps(results, ensure_ascii=False)
+                records = self.db.find_implementors(trait_name)
+                implementors = [{
+                    **record["node"],
+                    "trait": record["trait"],
+                    "trait_path": record.get("trait_path"),
+                    "blanket": record.get("blanket", False),
+                } for record in records]
+                return json.dumps({
+                    "trait": trait_name,
+                    "count": len(implementors),
+                    "implementors": implementors,
+                }, ensure_ascii=False)
             except Exception as e:
-                logger.error(f"查找函數調用者時發生錯誤: {e}")
+                logger.error(f"查找特徵實作型別時發生錯誤: {e}")
                 return json.dumps({"error": str(e)})
-        
+    
         @self.mcp.tool()
-        async def find_function_callees(function_name: str, limit: int = 10) -> str:
-            """查找特定函數調用的所有函數
+        async def get_type_hierarchy(type_name: str = None, max_depth: int = None,
+                                     paginate_from: List[str] = None) -> str:
+            """獲取型別的繼承層級（超型別、子型別與泛型約束）
+            
+            Build the type hierarchy of a struct, enum, class, trait or
+            interface as a small subgraph. ``supertypes`` are what it
+            implements or extends: the traits a Rust type implements,
+            derived ones included, a trait's supertraits
+            (``trait Shape: Named``), a class's bases. ``subtypes`` are what
+            implements or extends it: for a trait, its implementing types
+            and subtraits, or the file of a blanket impl. Both are followed
+            up to ``max_depth`` levels. ``generic_bounds`` maps each type
+            parameter of the type to its trait bounds, inline and from the
+            where clause (``struct Labeled<T: Shape> where T: Clone`` ->
+            ``{"T": ["Shape", "Clone"]}``); every node carries its own.
+            ``nodes`` and ``edges`` hold the subgraph, each node with its
+            ``role`` and ``depth``, each IMPLEMENTS or EXTENDS edge with its
+            properties. Traits from crates that were not indexed appear as
+            Unresolved nodes. When the name matches several types each gets
+            a hierarchy.
+            
+            ``max_depth`` defaults to the configured ``max_traversal_depth``.
+            ``frontier`` lists the types at that depth with supertypes or
+            subtypes left unwalked, and ``truncated`` is true when it is not
+            empty. Pass it back as ``paginate_from`` to build hierarchies
+            from those types instead of type_name.
             
             Args:
-                function_name: 函數名稱
-                limit: 返回結果的最大數量
+                type_name: 型別名稱、完整限定名稱或節點ID
+                max_depth: 每個方向展開的層數，預設為設定的 max_traversal_depth
+                paginate_from: 從這些節點ID繼續展開（如上次返回的 frontier），取代 type_name
                 
             Returns:
-                被調用函數的JSON字符串
+                型別層級子圖的JSON字符串
             """
             try:
-                query = """
-                MATCH (caller)-[:CALLS]->(callee)
-                WHERE caller.name = $function_name
-                RETURN callee
-                LIMIT $limit
-                """
-                
-                results = self.db.execute_cypher(query, {
-                    "function_name": function_name,
-                    "limit": limit
-                })
+                max_depth = self._depth(max_depth)
+                if paginate_from:
+                    targets, missing = self._nodes_by_ids(paginate_from)
+                    if missing:
+                        return json.dumps({"error": f"Node not found: {', '.join(missing)}"}, ensure_ascii=False)
+                elif type_name:
+                    targets = [t for t in self.db.find_nodes_by_symbol(type_name) if t["type"] in TYPE_NODE_TYPES]
+                    if not targets:
+                        return json.dumps({"error": f"Type not found: {type_name}"}, ensure_ascii=False)
+                else:
+                    return json.dumps({"error": "Give a type_name or paginate_from"}, ensure_ascii=False)
                 
-                return json.dumps(results, ensure_ascii=False)
+                graph = self._load_graph(properties=["generic_bounds"])
+                hierarchies = [build_type_hierarchy(graph, t["id"], max_depth)
+                               for t in targets if t["id"] in graph.nodes]
+                frontier = list(dict.fromkeys(node_id for h in hierarchies for node_id in h.frontier))
+                return json.dumps({
+                    "type_name": type_name,
+                    "hierarchies": [h.to_dict() for h in hierarchies],
+                    "truncated": bool(frontier),
+                    "frontier": frontier,
+                }, ensure_ascii=False)
             except Exception as e:
-                logger.error(f"查找函數調用的函數時發生錯誤: {e}")
+                logger.error(f"獲取型別層級時發生錯誤: {e}")
                 return json.dumps({"error": str(e)})
-        
+    
         @self.mcp.tool()
-        async def find_class_inheritance(class_name: str) -> str:
-            """查找類別的繼承關係
+        async def diff_commits(base: str, head: str = "HEAD", repo_path: str = ".",
+                               max_file_bytes: int = DEFAULT_MAX_FILE_BYTES, ctx: Context = None) -> str:
+            """比較兩個 git 提交之間新增、刪除或修改的符號
+            
+            Build the code graph of each commit straight from git (nothing is
+            checked out) and compare them: symbols added and removed, symbols
+            whose attributes changed (body hash, complexity, visibility, doc,
+            ...) with the before and after values, and edges added and
+            removed. Symbols are matched by kind, file and name, so code that
+            only shifted is not reported; a symbol reordered within its file
+            shows as a ``span`` change, and one renamed or moved to another
+            file is listed under ``renamed_nodes``, with ``caller_changes``
+            giving the callers each function gained and lost. Binary files, files that are not
+            UTF-8 and files over ``max_file_bytes`` are skipped and listed
+            under ``skipped``. The stored graph is not used or changed.
+            While the two graphs are built the client receives MCP progress
+            notifications, one per parsed file and one each when resolution
+            starts and finishes.
             
             Args:
-                class_name: 類別名稱
+                base: 較舊的 git ref，例如 `HEAD~1`、分支名稱或提交 SHA
+                head: 較新的 git ref，預設為 `HEAD`
+                repo_path: 儲存庫中任一目錄的路徑，預設為服務器的工作目錄
+                max_file_bytes: 超過此大小（位元組）的檔案會被略過
                 
             Returns:
-                繼承關係的JSON字符串
+                符號差異的JSON字符串
             """
             try:
-                # 查找超類
-                superclasses_query = """
-                MATCH (sub:Class {name: $class_name})-[:EXTENDS]->(super:Class)
-                RETURN super
-                """
-                
-                superclasses = self.db.execute_cypher(superclasses_query, {
-                    "class_name": class_name
-                })
-                
-                # 查找子類
-                subclasses_query = """
-                MATCH (sub:Class)-[:EXTENDS]->(super:Class {name: $class_name})
-                RETURN sub
-                """
-                
-                subclasses = self.db.execute_cypher(subclasses_query, {
-                    "class_name": class_name
-                })
-                
+                options = self._parser_options()
+                diff = await self._with_progress(
+                    ctx, lambda progress: diff_graphs(repo_path, base, head, max_file_bytes, progress, **options))
                 return json.dumps({
-                    "superclasses": superclasses,
-                    "subclasses": subclasses
+                    "base": {"ref": base, "commit": resolve_ref(repo_path, base)},
+                    "head": {"ref": head, "commit": resolve_ref(repo_path, head)},
+                    **diff.to_dict(),
                 }, ensure_ascii=False)
+            except GitError as e:
+                return json.dumps({"error": str(e)}, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"比較提交差異時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+    
+        @self.mcp.tool()
+        async def graph_diff(before: str, after: str) -> str:
+            """比較兩個圖譜快照之間新增、刪除、修改或重新命名的符號
+            
+            Compare two JSON snapshots of the graph, as written by the
+            ``export`` tool with ``format="json"`` or by
+            ``CodeGraph.to_json``. Symbols are matched by kind, file and
+            name, so lines added above a function do not report it; a
+            function moved past others in its file is reported with a
+            ``span`` change, and one renamed or moved to another file with
+            an unchanged body is paired in ``renamed_nodes``. Edges are
+            matched by the identity of their ends. ``caller_changes`` lists,
+            per function, the callers it gained and lost, for reviewing a
+            change.
+            
+            Args:
+                before: 較舊的快照，JSON文件內容或快照檔案路徑
+                after: 較新的快照，JSON文件內容或快照檔案路徑
                 
+            Returns:
+                符號與邊差異的JSON字符串
+            """
+            try:
+                graphs = [CodeGraph.from_json(self._snapshot_text(snapshot)) for snapshot in (before, after)]
+                return json.dumps(graphs[0].diff(graphs[1]).to_dict(), ensure_ascii=False)
+            except KeyError as e:
+                return json.dumps({"error": f"Invalid snapshot: missing {e}"}, ensure_ascii=False)
+            except (OSError, ValueError) as e:
+                return json.dumps({"error": f"Invalid snapshot: {e}"}, ensure_ascii=False)
             except Exception as e:
-                logger.error(f"查找類別繼承關係時發生錯誤: {e}")
+                logger.error(f"比較圖譜快照時發生錯誤: {e}")
                 return json.dumps({"error": str(e)})
-        
+    
         @self.mcp.tool()
-        async def find_file_dependencies(file_path: str) -> str:
-            """查找檔案的依賴關係
+        async def get_public_api(include_crate: bool = False, path_prefix: str = None, root: str = None,
+                                 diff_against: str = None, save_snapshot: str = None) -> str:
+            """取得公開 API 介面，並可與先前的快照比較
+            
+            Return the public contract of the codebase: every symbol whose
+            visibility is ``Public`` (Rust ``pub``, exported Go names, Java
+            and TypeScript ``public``, Python names without an underscore),
+            with its qualified name and doc, plus the edges between those
+            symbols. ``include_crate`` adds ``Crate`` symbols (Rust
+            ``pub(crate)``, Java package-private).
+            
+            ``diff_against`` compares the surface with a snapshot file saved
+            earlier with ``save_snapshot`` and reports the items added,
+            removed and whose visibility changed, with a suggested semver
+            bump. Items are matched by kind, file and qualified name, so use
+            the same ``root`` for both runs when the checkout moves.
             
             Args:
-                file_path: 檔案路徑
+                include_crate: 是否包含 crate 可見（pub(crate)）的符號
+                path_prefix: 只保留路徑以此開頭的檔案中的符號
+                root: 以此目錄為基準回報相對檔案路徑
+                diff_against: 要比較的快照檔案路徑
+                save_snapshot: 將目前的公開 API 寫入此快照檔案路徑
                 
             Returns:
-                依賴關係的JSON字符串
+                公開 API 或其差異的JSON字符串
             """
             try:
-                # 查找該檔案導入的模組
-                imports_query = """
-                MATCH (f:File {path: $file_path})-[:IMPORTS]->(m:Module)
-                RETURN m
-                """
+                api = public_api(self.db.get_symbols(), self.db.get_graph()["relationships"],
+                                 include_crate=include_crate, root=root, path_prefix=path_prefix)
+                if diff_against:
+                    result = {"snapshot": diff_against, **diff_api(load_snapshot(diff_against), api["items"])}
+                else:
+                    result = {"count": len(api["items"]), **api}
+                if save_snapshot:
+                    save_snapshot_file(api, save_snapshot)
+                    result["saved_snapshot"] = save_snapshot
+                return json.dumps(result, ensure_ascii=False)
+            except (OSError, ValueError) as e:
+                return json.dumps({"error": str(e)}, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"取得公開 API 時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+    
+        @self.mcp.tool()
+        async def get_file_metrics(path: str) -> str:
+            """取得檔案的行數、函數數量與平均複雜度統計
+            
+            A quick size dashboard for one file or a set of files: total,
+            blank, comment and code lines, the number of functions and
+            methods, the number of structs and classes, the mean
+            cyclomatic complexity of the functions that record one (null
+            otherwise) and ``doc_coverage_ratio``, the share of public
+            functions and structs with a doc (null when there are none, or
+            their docs are not read), per matched file and over all of them.
+            Line counts were taken while indexing and are null for files
+            indexed before they were recorded.
+            
+            Args:
+                path: 檔案路徑（絕對或相對於索引根目錄），或如 `src/*.rs` 的 glob 模式
                 
-                imports = self.db.execute_cypher(imports_query, {
-                    "file_path": file_path
-                })
+            Returns:
+                各檔案統計與總計的JSON字符串
+            """
+            try:
+                files = self.db.get_graph(["File"], list(LINE_METRICS))["nodes"]
+                if is_glob(path):
+                    matches = match_glob([f["file_path"] for f in files if f.get("file_path")], path)
+                else:
+                    matches = self.db.find_file_paths(path)
+                if not matches:
+                    return json.dumps({"error": f"No indexed file matches: {path}"}, ensure_ascii=False)
                 
-                # 查找導入該檔案的檔案
-                imported_by_query = """
-                MATCH (f:File)-[:IMPORTS]->(m:Module)
-                WHERE m.name = $file_name
-                RETURN f
-                """
+                symbols = self.db.get_symbols(list(FUNCTION_TYPES + STRUCT_TYPES))
+                return json.dumps({"path": path, **file_metrics(files, symbols, matches)}, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"取得檔案統計時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.tool()
+        async def get_undocumented_symbols(file_glob: str = None, kinds: str = None,
+                                           min_coverage: float = None) -> str:
+            """列出缺少文件註解的公開符號
+            
+            List the public functions, methods and types that have no doc
+            comment (``///``, ``/** */``) or docstring, with their file and
+            line, plus the share of public symbols that are documented.
+            Private symbols and trait impl methods (documented by their
+            trait) are not counted, nor are Go and C symbols, whose doc
+            comments are not read. With ``min_coverage`` the result says
+            whether the coverage reaches it, so a CI script can fail the
+            build when it drops.
+            
+            Args:
+                file_glob: 檔案路徑的萬用字元模式，例如 "src/*.rs"
+                kinds: 以逗號分隔的符號種類，例如 "function,struct"
+                min_coverage: 文件覆蓋率門檻（0 到 1）
                 
-                file_name = os.path.basename(file_path).split(".")[0]
-                imported_by = self.db.execute_cypher(imported_by_query, {
-                    "file_name": file_name
-                })
+            Returns:
+                未文件化的公開符號與文件覆蓋率的JSON字符串
+            """
+            try:
+                node_types = parse_node_kinds(kinds) if kinds else None
+                symbols = [s for s in self.db.get_symbols(node_types) if is_counted(s)]
+                paths = None
+                if file_glob:
+                    paths = match_glob({s["file_path"] for s in symbols if s.get("file_path")}, file_glob)
+                result = undocumented_symbols(symbols, paths)
+                if min_coverage is not None:
+                    ratio = result["doc_coverage_ratio"]
+                    result["meets_threshold"] = ratio is None or ratio >= min_coverage
+                return json.dumps(result, ensure_ascii=False)
+            except ValueError as e:
+                return json.dumps({"error": str(e)}, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"列出未文件化符號時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.tool()
+        async def parse_diagnostics(path: str, source: str = None) -> str:
+            """列出解析檔案時遇到的語法錯誤
+            
+            Parse a file, or every supported file under a directory, and
+            report the syntax errors met: for each broken file the error
+            spans (line and column, 1-based, end exclusive) and whether
+            declarations were still recovered around them (``partial``).
+            Files with errors are indexed with whatever was recovered, so a
+            file missing from search results can be checked here. Pass
+            ``source`` to check an unsaved buffer reported under ``path``.
+            The stored graph is not used or changed.
+            
+            Args:
+                path: 要檢查的檔案或目錄路徑；提供 source 時為緩衝區的虛擬路徑
+                source: 要檢查的原始碼內容，不讀取檔案
                 
+            Returns:
+                解析診斷結果的JSON字符串
+            """
+            try:
+                graph = CodeGraph(**self._parser_options())
+                if source is not None:
+                    graph.add_sources({path: source})
+                elif os.path.isdir(path):
+                    graph.add_directory(path)
+                elif os.path.isfile(path):
+                    graph.add_file(path)
+                else:
+                    return json.dumps({"error": f"No such file or directory: {path}"}, ensure_ascii=False)
+                return json.dumps({"path": path, **graph.parse_report().to_dict()}, ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"解析診斷時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.tool()
+        async def get_index_status() -> str:
+            """獲取索引狀態與無法解析的檔案
+            
+            Whether the stored graph is complete. Lists every indexed file
+            flagged ``parse_error``, with the message, kind and position of
+            its first error: such a file either could not be parsed at all,
+            and only its File node is stored, or was indexed with what was
+            recovered around syntax errors. When the indexer started this
+            server, ``run`` adds the summary of that run: the files indexed
+            and failed, and the paths of those with syntax errors or skipped
+            for their size or language.
+            
+            Returns:
+                索引狀態的JSON字符串
+            """
+            try:
+                graph = self._load_graph(["File"], list(PARSE_ERROR_PROPERTIES))
+                files = sorted((node for node in graph.nodes.values() if node.node_type == "File"),
+                               key=lambda node: node.file_path)
+                errors = [{"file_path": node.file_path, "message": node.properties["parse_error"],
+                           "kind": node.properties.get("parse_error_kind"),
+                           "line": node.properties.get("parse_error_line"),
+                           "column": node.properties.get("parse_error_column")}
+                          for node in files if node.properties.get("parse_error")]
                 return json.dumps({
-                    "imports": imports,
-                    "imported_by": imported_by
+                    "files_indexed": len(files),
+                    "files_with_parse_errors": len(errors),
+                    "complete": not errors,
+                    "parse_errors": errors,
+                    "run": self.diagnostics.summary() if self.diagnostics is not None else None,
                 }, ensure_ascii=False)
-                
             except Exception as e:
-                logger.error(f"查找檔案依賴關係時發生錯誤: {e}")
+                logger.error(f"獲取索引狀態時發生錯誤: {e}")
                 return json.dumps({"error": str(e)})
     
+    @staticmethod
+    def _snapshot_text(snapshot: str) -> str:
+        """A graph snapshot given inline, or read from the file it names."""
+        if snapshot.lstrip().startswith("{"):
+            return snapshot
+        with open(snapshot, "r", encoding="utf-8") as f:
+            return f.read()
+    
+    def _parser_options(self) -> Dict[str, Any]:
+        """CodeGraph parser options, from the same settings the indexer reads."""
+        return {
+            "use_ast_grep": self.config.get("languages", "use_ast_grep"),
+            "ast_grep_languages": self.config.languages,
+            "ast_grep_fallback": self.config.get("languages", "fallback_to_legacy"),
+            "respect_gitignore": self.config.get("paths", "respect_gitignore"),
+            "exclude": self.config.exclude,
+            "include": self.config.get("paths", "include"),
+            "max_file_size": self.config.max_file_size,
+            "language_options": self.config.language_options,
+            "language_overrides": self.config.language_overrides,
+        }
+    
+    async def _with_progress(self, ctx: Optional[Context], build: Callable[[Optional[ProgressChannel]], Any]) -> Any:
+        """在背景執行緒建構圖譜並回報進度
+        
+        Run a graph build that takes a progress channel. With a request
+        context the build runs on a worker thread while its events are
+        forwarded as MCP progress notifications every PROGRESS_INTERVAL
+        seconds; events the channel dropped are simply not reported.
+        Without a context it runs here with no channel.
+        """
+        if ctx is None:
+            return build(None)
+        channel = ProgressChannel()
+        task = asyncio.ensure_future(asyncio.to_thread(build, channel))
+        while True:
+            done, _ = await asyncio.wait({task}, timeout=PROGRESS_INTERVAL)
+            for event in channel.drain():
+                try:
+                    await self._report_progress(ctx, event)
+                except Exception as e:
+                    logger.error(f"回報建構進度時發生錯誤: {e}")
+            if done:
+                return task.result()
+
+    @staticmethod
+    async def _report_progress(ctx: Context, event: Any) -> None:
+        """One progress event as an MCP progress notification."""
+        if isinstance(event, FileParsed):
+            await ctx.report_progress(event.index, event.total, f"Parsed {event.path} ({event.nodes} nodes)")
+        elif isinstance(event, ResolutionStarted):
+            await ctx.report_progress(event.files, event.files, f"Resolving references across {event.files} files")
+        else:
+            await ctx.report_progress(event.files, event.files,
+                                      f"Resolved {event.files} files, {event.relations} relations")
+
+    @staticmethod
+    async def _stream_result(ctx: Optional[Context], tool: str, header: Dict[str, Any],
+                             sections: List[Tuple[str, Iterable[Any]]], summary: Dict[str, Any]) -> str:
+        """以 JSON Lines 記錄傳送工具結果
+        
+        With a request context every record goes out as it is written, as
+        an MCP log notification, and the tool result is the end record
+        alone. Without one the records are collected and returned as the
+        whole JSON Lines document.
+        """
+        buffer = BufferSink() if ctx is None else None
+        stream = JsonLinesStream(buffer if buffer is not None else context_sink(ctx))
+        await stream.begin(tool, **header)
+        try:
+            for kind, items in sections:
+                await stream.items(kind, items)
+        except Exception as e:
+            logger.error(f"串流傳送結果時發生錯誤: {e}")
+            record = await stream.error(str(e))
+        else:
+            record = await stream.end(**summary)
+        return buffer.text() if buffer is not None else encode_record(record)
+
+    @staticmethod
+    def _node_summary(graph: CodeGraph, node_id: str) -> Dict[str, Any]:
+        """The id/name/type/location fields the tools report for a node."""
+        node = graph.nodes[node_id]
+        return {"id": node_id, "name": node.name, "type": node.node_type,
+                "file_path": node.file_path, "line_no": node.line_no}
+    
+    def _symbol_index(self) -> SymbolIndex:
+        """Index of every symbol in the store, reloaded once it is SYMBOL_INDEX_TTL seconds old."""
+        now = time.monotonic()
+        if self._symbols is None or now - self._symbols_loaded_at > SYMBOL_INDEX_TTL:
+            self._symbols = SymbolIndex(self.db.get_symbols())
+            self._symbols_loaded_at = now
+        return self._symbols
+    
+    def shared_graph(self) -> SharedGraph:
+        """The whole stored graph, loaded on first use, for front ends that answer from one CodeGraph."""
+        if self._shared_graph is None:
+            self._shared_graph = SharedGraph(self._load_graph())
+        return self._shared_graph
+    
+    def _load_graph(self, node_types: Optional[List[str]] = None,
+                    properties: Optional[List[str]] = None) -> CodeGraph:
+        """Load the stored graph, optionally only some node types, into a CodeGraph.
+
+        ``properties`` names node properties to load as well; list and dict
+        values, which indexing stores as JSON strings, are decoded.
+        """
+        records = self.db.get_graph(node_types, properties) if properties else self.db.get_graph(node_types)
+        return CodeGraph.from_records(
+            [CodeNode(n["id"], n["type"], n["name"], n["file_path"] or "", n["line_no"] or 0,
+                      n.get("end_line_no"), self._decode_properties(n.get("properties") or {}))
+             for n in records["nodes"]],
+            [CodeRelation(r["source"], r["target"], r["type"], r.get("properties") or {})
+             for r in records["relationships"]],
+        )
+    
+    @staticmethod
+    def _read_text(file_path: str) -> Optional[str]:
+        """Text of an indexed file, or None when it cannot be read."""
+        try:
+            return read_text(file_path)
+        except (OSError, UnicodeDecodeError):
+            return None
+    
+    @staticmethod
+    def _decode_properties(properties: Dict[str, Any]) -> Dict[str, Any]:
+        decoded = {}
+        for key, value in properties.items():
+            if isinstance(value, str) and value[:1] in ("[", "{"):
+                try:
+                    value = json.loads(value)
+                except ValueError:
+                    pass
+            decoded[key] = value
+        return decoded
+    
+    def _iter_references(self, node_ids: List[str]) -> Iterator[Dict[str, Any]]:
+        """Stream the references to a set of nodes, tagged Read, Write or Call"""
+        for edge in self.db.get_references(node_ids):
+            if edge["relation"] == "CALLS":
+                kinds = ["Call"]
+            elif edge.get("access") == "ReadWrite":
+                kinds = ["Read", "Write"]
+            else:
+                kinds = [edge.get("access") or "Read"]
+            for kind in kinds:
+                yield {
+                    "kind": kind,
+                    "target_id": edge["target_id"],
+                    "source": edge["node"],
+                    "file_path": edge["node"].get("file_path"),
+                    "line_no": edge.get("line_no"),
+                    "snippet": edge.get("snippet"),
+                }
+
+    def _iter_calls(self, start_ids: List[str], direction: str, depth: int,
+                    stopped: Optional[List[str]] = None) -> Iterator[Tuple[Optional[Dict[str, Any]], Dict[str, Any]]]:
+        """Breadth-first walk over CALLS edges starting from a set of nodes
+
+        Each node is visited at most once, so recursive and mutually recursive
+        calls terminate. Edges back to already visited nodes are still
+        yielded so cycles stay visible. The walk is lazy: edges are fetched
+        level by level only as far as the consumer reads.
+
+        Args:
+            start_ids: Node ids to start from (depth 0, not reported)
+            direction: "callers" to follow edges backwards, "callees" forwards
+            depth: Maximum number of hops
+            stopped: Filled, once the walk is read to its end, with the nodes
+                at depth that have calls to unvisited nodes left unwalked
+
+        Yields:
+            (entry, edge) for every edge walked. entry describes the node on
+            the far side at the depth where it was first reached, and is
+            None when that node was already visited.
+        """
+        visited = set(start_ids)
+        frontier = list(start_ids)
+
+        for level in range(1, depth + 1):
+            if not frontier:
+                break
+            next_frontier = []
+            for edge in self.db.get_call_edges(frontier, direction):
+                node = edge["node"]
+                call_site = {
+                    "file_path": edge.get("call_file"),
+                    "line_no": edge.get("call_line"),
+                    "snippet": edge.get("call_site"),
+                }
+                if edge.get("await_call"):
+                    call_site["await_call"] = True
+                entry = None
+                if node["id"] not in visited:
+                    visited.add(node["id"])
+                    next_frontier.append(node["id"])
+                    entry = {
+                        **node,
+                        "depth": level,
+                        "caller_id": edge["caller_id"],
+                        "callee_id": edge["callee_id"],
+                        "call_site": call_site,
+                    }
+                yield entry, {
+                    "source": edge["caller_id"],
+                    "target": edge["callee_id"],
+                    "call_site": call_site,
+                }
+            frontier = next_frontier
+
+        if stopped is not None and frontier:
+            near = "callee_id" if direction == "callers" else "caller_id"
+            stopped.extend(dict.fromkeys(edge[near] for edge in self.db.get_call_edges(frontier, direction)
+                                         if edge["node"]["id"] not in visited))
+
+    def _direct_calls(self, symbol: str, direction: str, include_unresolved: bool, limit: int,
+                      revision: Optional[str] = None, repo_path: str = ".") -> Dict[str, Any]:
+        """Call sites into ("callers") or out of ("callees") the nodes matching a symbol
+
+        With a revision the graph of that git commit is used instead of the stored one.
+        """
+        commit = None
+        if revision:
+            try:
+                commit = resolve_ref(repo_path, revision)
+                graph = self._revision_graph(repo_path, commit)
+            except GitError as e:
+                return {"error": str(e)}
+            targets = self._graph_symbols(graph, symbol)
+        else:
+            targets = self.db.find_nodes_by_symbol(symbol)
+        if not targets:
+            return {"error": f"Symbol not found: {symbol}"}
+        
+        if not revision:
+            graph = self._load_graph()
+        key = "calls" if direction == "callers" else "called_from"
+        entries = []
+        for target in targets:
+            sites = graph.callers(target["id"], include_unresolved) if direction == "callers" \
+                else graph.callees(target["id"], include_unresolved)
+            for site in sites:
+                node = graph.nodes.get(site.node_id)
+                entries.append({
+                    "id": site.node_id,
+                    "name": node.name if node else None,
+                    "type": node.node_type if node else None,
+                    "file_path": node.file_path if node else None,
+                    "line_no": node.line_no if node else None,
+                    key: target["id"],
+                    "call_site": {"file_path": site.file_path, "line_no": site.line_no,
+                                  "column": site.column, "snippet": site.snippet},
+                    "unresolved": site.unresolved,
+                    "raw_name": site.raw_name,
+                })
+        result = {
+            "symbol": symbol,
+            "targets": targets,
+            direction: entries[:limit],
+            "truncated": len(entries) > limit,
+        }
+        if revision:
+            result["revision"] = {"ref": revision, "commit": commit}
+        return result
+    
+    def _revision_graph(self, repo_path: str, commit: str) -> CodeGraph:
+        """Graph of a git commit, built on first use and kept for the next queries."""
+        graph = self._revision_graphs.pop(commit, None)
+        if graph is None:
+            graph = graph_at_revision(repo_path, commit, **self._parser_options())[0]
+        self._revision_graphs[commit] = graph
+        while len(self._revision_graphs) > REVISION_GRAPH_CACHE:
+            self._revision_graphs.pop(next(iter(self._revision_graphs)))
+        return graph
+    
+    @staticmethod
+    def _graph_symbols(graph: CodeGraph, symbol: str) -> List[Dict[str, Any]]:
+        """Nodes of a graph matching a symbol as find_nodes_by_symbol matches it: ID, name or `Type.method`."""
+        owner, _, member = symbol.rpartition(".")
+        defined = {r.target_id for r in graph.relations if r.relation_type == "DEFINES"
+                   and r.source_id in graph.nodes and graph.nodes[r.source_id].name == owner} if owner else set()
+        matches = [node for node in graph.nodes.values()
+                   if node.node_id == symbol
+                   or (node.node_type not in ("File", "Unresolved")
+                       and (node.name == symbol or (owner and node.name == member and node.node_id in defined)))]
+        matches.sort(key=lambda n: (n.file_path, n.line_no, n.node_id))
+        return [{"id": n.node_id, "name": n.name, "type": n.node_type, "file_path": n.file_path,
+                 "line_no": n.line_no} for n in matches]
+
+    def _walk_calls(self, start_ids: List[str], direction: str, depth: int,
+                    limit: int) -> Tuple[List[Dict[str, Any]], List[Dict[str, Any]], bool, List[str]]:
+        """Collect a call graph walk, stopping after limit reported nodes
+
+        Returns:
+            (entries, edges, truncated, frontier) where truncated is True if
+            the limit cut the walk short, and frontier lists the nodes the
+            depth stopped it at (empty when the limit did)
+        """
+        entries: List[Dict[str, Any]] = []
+        edges: List[Dict[str, Any]] = []
+        stopped: List[str] = []
+        for entry, edge in self._iter_calls(start_ids, direction, depth, stopped):
+            if entry is not None:
+                if len(entries) >= limit:
+                    return entries, edges, True, []
+                entries.append(entry)
+            edges.append(edge)
+        return entries, edges, False, stopped
+
+    def _depth(self, requested: Optional[int]) -> int:
+        """A requested traversal depth, at least 1; the configured max_traversal_depth when None."""
+        return max(1, self.config.max_traversal_depth if requested is None else requested)
+    
+    def _walk_starts(self, symbol: Optional[str],
+                     paginate_from: Optional[List[str]]) -> Tuple[List[Dict[str, Any]], Optional[str]]:
+        """Nodes a graph walk starts from, those of paginate_from over those matching symbol, or an error."""
+        if paginate_from:
+            nodes, missing = self._nodes_by_ids(paginate_from)
+            return nodes, f"Node not found: {', '.join(missing)}" if missing else None
+        if not symbol:
+            return [], "Give a symbol or paginate_from"
+        nodes = self.db.find_nodes_by_symbol(symbol)
+        return nodes, None if nodes else f"Symbol not found: {symbol}"
+    
+    def _nodes_by_ids(self, node_ids: List[str]) -> Tuple[List[Dict[str, Any]], List[str]]:
+        """Stored nodes with the given IDs, such as a returned frontier, and the IDs not found."""
+        found, missing = [], []
+        for node_id in dict.fromkeys(node_ids):
+            matches = [n for n in self.db.find_nodes_by_symbol(node_id) if n["id"] == node_id]
+            found.extend(matches[:1])
+            if not matches:
+                missing.append(node_id)
+        return found, missing
+
     def _register_prompts(self):
         """註冊MCP提示詞"""
         
@@ -360,30 +2599,180 @@ class CodebaseKnowledgeGraphMCP:
             
             節點類型:
             - File: 代表程式碼檔案
-              - 屬性: id, path, name
-            - Class: 代表類別定義
-              - 屬性: id, name, file_path, line_no, end_line_no, code_snippet
+              - 屬性: id, path, name, total_lines, blank_lines, comment_lines, code_lines
+            - Class: 代表類別定義（Rust 的結構體亦是）
+              - 屬性: id, name, file_path, line_no, end_line_no, code_snippet, visibility, doc, type_kind
+              - type_kind (Rust): struct, tuple_struct, unit_struct; (C/C++): struct, union; (Java): class, record
+              - Java 的匿名類別以 javac 的方式命名（Outer$1），anonymous 為 true，base 為其實例化的型別
+            - Enum: 代表列舉定義
+              - 屬性: id, name, file_path, line_no, end_line_no, visibility, doc
             - Function: 代表全局函數定義
-              - 屬性: id, name, file_path, line_no, end_line_no, code_snippet
+              - 屬性: id, name, file_path, line_no, end_line_no, code_snippet, visibility, complexity, doc
+              - prototype: C/C++ 標頭檔中只有宣告、沒有函數本體的函數
+              - signature (C/C++): 參數型別，例如 (const char *, ...)；多載函數是各自的節點，以 signature 區分；default_args 為有預設值的參數數量
+              - Python 的巢狀函數（閉包）由外層函數以 DEFINES 連結，visibility 為 Private
             - Method: 代表類別方法
-              - 屬性: id, name, file_path, line_no, end_line_no, code_snippet
+              - 屬性: id, name, file_path, line_no, end_line_no, code_snippet, visibility, complexity, doc
+              - method_kind (Python): instance, static, class, property（依 @staticmethod、@classmethod、@property 等裝飾器）; (Java): instance, static, constructor; (Ruby): instance, class（def self.x 與 class << self 中的方法）
             - Variable: 代表變數定義
               - 屬性: id, name, file_path, line_no
-            - Module: 代表導入的模組
-              - 屬性: id, name
+            - Field: 代表結構體或列舉成員的欄位，元組欄位以位置 0, 1, ... 命名
+              - 屬性: id, name, file_path, line_no, field_of, type, visibility, doc
+            - Variant: 代表 Rust 列舉的成員或 Java 的列舉常數
+              - 屬性: id, name, file_path, line_no, variant_of, type_kind (unit, tuple, struct), visibility, discriminant, doc
+            - Module: 代表導入的模組，或 Ruby 的 module 定義
+              - 屬性: id, name; (Ruby) file_path, line_no, end_line_no, namespace
+            - Package: 代表 Java 檔案的 package 宣告（每個檔案一個，檔案節點另有 package 屬性）
+              - 屬性: id, name, file_path, line_no
+            - TypeAlias: 代表型別別名（TypeScript type、C typedef）
+              - 屬性: id, name, file_path, line_no, type (C 的被別名型別)
+            - Macro: 代表 C/C++ 的 #define 巨集或 Rust 的 macro_rules! 定義
+              - 屬性: id, name, file_path, line_no, parameters (函數式巨集), value; (Rust) rules (規則數量), visibility (#[macro_export] 為 Public)
+            
+            visibility 取值: Public, Crate, Protected, Private（依語言判斷，無法判斷時不設置）
+            complexity: 圈複雜度（1 + 分支點數量），由各語言解析器記錄
+            is_async: Rust 函數與方法是否宣告為 async fn
+            doc: 去除註解標記的文件註解或 docstring，保留段落，最多 4KB
+            documented: 公開的函數、方法與型別是否有文件註解（Python、JavaScript、TypeScript、Rust、Java；trait impl 方法不設置）
+            decorators: Python 類別、函數與方法的裝飾器（不含 @）
+            annotations: Java 型別、方法、建構子與欄位的註解名稱（不含 @ 與參數），例如 RestController, Autowired
+            exported, export_type, export_name: JavaScript/TypeScript 模組匯出的符號，export_type 為 named 或 default，export_name 為匯入時使用的名稱
             
             關係類型:
             - CONTAINS: 表示一個檔案包含某個程式碼元素
               - 例如: (File)-[:CONTAINS]->(Function)
-            - DEFINES: 表示一個類別定義了一個方法或屬性
-              - 例如: (Class)-[:DEFINES]->(Method)
+            - DEFINES: 表示一個類別定義了一個方法或屬性，或函數定義了巢狀函數
+              - 例如: (Class)-[:DEFINES]->(Method), (Enum)-[:DEFINES]->(Variant), (Variant)-[:DEFINES]->(Field), (Function)-[:DEFINES]->(Function)
+            - DECLARED_BY: 表示 C/C++ 函數定義由某個原型宣告（同檔案或 #include 的標頭檔）
+              - 例如: (Function)-[:DECLARED_BY]->(Function)，自定義指向宣告它的原型
+            - DECLARES: 表示 C/C++ 原型宣告了某個定義，方向與 DECLARED_BY 相反
+              - 例如: (Function)-[:DECLARES]->(Function)，自標頭檔的原型指向名稱、所屬類別與簽名相同的定義
+              - 屬性: signature
             - CALLS: 表示函數調用關係
               - 例如: (Function)-[:CALLS]->(Function)
+              - 屬性: line_no, call_site (調用所在行及其原始碼)
+              - Rust 巨集調用: (Function)-[:CALLS]->(Macro)，屬性 macro 為 true；std 巨集與程序巨集（println! 等）指向 Unresolved 佔位節點
+              - await_call: 為 true 時該調用就地 .await（Rust 的 f(..).await），區分非同步與同步調用
+            - REFERENCES: 表示方法存取了欄位
+              - 例如: (Method)-[:REFERENCES]->(Field)
+              - 屬性: access (Read, Write, ReadWrite, Unknown), line_no, call_site
+              - Rust 欄位作為方法接收者時，依同檔案中該方法的 self 參數為 ReadWrite（&mut self）或 Read；方法未解析（如 self.items.len()）時為 Unknown
+              - Rust 巨集參數中僅依名稱比對到的函數或型別: 屬性 via_macro (巨集名稱), confidence 為 low
+            - USES_TYPE: 表示結構體或列舉的欄位使用了已索引的型別（含泛型參數，Vec<Person> 指向 Person）
+              - 例如: (Class)-[:USES_TYPE]->(Class), (Enum)-[:USES_TYPE]->(Class)
+              - 屬性: fields (使用該型別的欄位名稱)
             - EXTENDS: 表示類別的繼承關係
-              - 例如: (Class)-[:EXTENDS]->(Class)
+              - 例如: (Class)-[:EXTENDS]->(Class), (Interface)-[:EXTENDS]->(Interface)
+            - IMPLEMENTS: 表示型別實作了特徵（trait）或介面，或方法實作了特徵方法
+              - 例如: (Class)-[:IMPLEMENTS]->(Interface), (Method)-[:IMPLEMENTS]->(Method)
+              - 屬性: trait_path (型別到特徵), type (方法到特徵方法)
+              - Rust #[derive(..)] 列出的每個特徵: 屬性 derived 為 true，未索引的特徵（Debug 等）指向 Unresolved 佔位節點
+              - 泛型全覆蓋實作（impl<T: Bound> Trait for T）自 File 出發，屬性另有 blanket, for_type, bounds, line_no
+            - DERIVE_USE: 表示 Rust 結構體或列舉透過 #[derive(..)] 使用了衍生巨集
+              - 例如: (Class)-[:DERIVE_USE]->(Function)，目標為已索引的 #[proc_macro_derive] 函數（屬性 derive_macro），否則為 Unresolved 佔位節點（Debug, serde::Serialize）
+              - 屬性: derive_path
+            - SATISFIES: 表示 Go 結構體隱式滿足了同一套件中的介面（擁有介面宣告的所有方法）
+              - 例如: (Class)-[:SATISFIES]->(Interface)
+              - 屬性: methods (介面要求的方法名稱)
             - IMPORTS: 表示檔案導入了某個模組
               - 例如: (File)-[:IMPORTS]->(Module)
+              - Java: (File)-[:IMPORTS]->(Class) 指向導入的型別，import pkg.* 指向該 package 的每個檔案；屬性: path, static, wildcard, member (static import 的成員), line_no
+            - INCLUDES: 表示 C/C++ 檔案以 #include 包含了另一個檔案
+              - 例如: (File)-[:INCLUDES]->(File)，#include "x.h" 先依包含者所在目錄、再依路徑結尾在儲存庫中解析
+              - #include <x.h> 為系統標頭，不解析，指向 Unresolved 佔位節點，屬性 system 為 true
+              - 屬性: path, system, line_no
+            - Ruby 的 require / require_relative: (File)-[:IMPORTS]->(File)，require_relative 依所在目錄解析，require 依路徑結尾解析；gem 與標準函式庫指向 Unresolved 佔位節點
+              - 屬性: path, require (require 或 require_relative), line_no
+            - AR_ASSOCIATION: 表示 ActiveRecord 模型以 has_many、belongs_to、has_one 或 has_and_belongs_to_many 關聯到另一個模型
+              - 例如: (Class)-[:AR_ASSOCIATION]->(Class)，模型為 class_name: 或依關聯名稱推得（:line_items -> LineItem）；polymorphic 與未索引的模型指向 Unresolved 佔位節點
+              - 屬性: association, name, class_name, line_no, through, polymorphic
+            """
+        
+        @self.mcp.resource("complexity://histogram")
+        def get_complexity_histogram() -> str:
+            """獲取所有函數的圈複雜度分佈
+            
+            Bucket every function and method by cyclomatic complexity
+            (1-5, 6-10, 11-20, 21-50, above 50) and list the most complex
+            ones, for a quick look at the health of the codebase.
+            
+            Returns:
+                複雜度分佈的JSON字符串
             """
+            try:
+                return json.dumps(complexity_histogram(self.db.get_symbols(["Function", "Method"])),
+                                  ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"計算圈複雜度分佈時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.resource("metrics://summary")
+        def get_metrics_summary() -> str:
+            """獲取整個專案的行數與函數數量統計
+            
+            Project-wide totals of the per-file metrics: files, total,
+            blank, comment and code lines, functions, structs, the mean
+            cyclomatic complexity and the doc coverage ratio of public
+            functions and structs, overall and per language.
+            
+            Returns:
+                專案統計的JSON字符串
+            """
+            try:
+                files = self.db.get_graph(["File"], list(LINE_METRICS))["nodes"]
+                symbols = self.db.get_symbols(list(FUNCTION_TYPES + STRUCT_TYPES))
+                return json.dumps(metrics_summary(files, symbols), ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"計算專案統計時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.resource("graph://modules")
+        def get_module_graph() -> str:
+            """獲取檔案層級的依賴圖
+            
+            Collapse the symbol graph onto files: one node per file and one
+            edge per pair of files whose symbols reference each other, with
+            the number of references as its weight and per relation type.
+            Edges are listed heaviest first, so the most coupled pairs lead.
+            In a monorepo, ``members`` lists each workspace member (the
+            package of the nearest Cargo.toml, package.json, go.mod or
+            pyproject.toml) with its files, as the top-level clusters.
+            
+            Returns:
+                檔案依賴圖的JSON字符串
+            """
+            try:
+                graph = self._load_graph(properties=["workspace_member"])
+                return json.dumps(ModuleGraph.from_code_graph(graph).to_dict(), ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"建立檔案依賴圖時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
+        
+        @self.mcp.resource("diagnostics://parse")
+        def get_diagnostics() -> str:
+            """獲取建立圖譜時遇到的解析問題
+            
+            Every problem the indexing run met, so a client can tell whether
+            the graph is complete and why not: one ``{file, line, column,
+            error_kind, message}`` object per problem, where ``error_kind``
+            is SyntaxError, UnsupportedLanguage, FileTooLarge, IoError or
+            EncodingError, and the counts of files parsed and failed. A file
+            failed when it was left out or nothing was recovered from it; a
+            file with syntax errors but recovered declarations is parsed.
+            Only served when the indexer started this server; the
+            parse_diagnostics tool checks files on demand instead.
+            
+            Returns:
+                解析問題的JSON字符串
+            """
+            if self.diagnostics is None:
+                return json.dumps({"error": "No indexing run in this process: start the server with "
+                                            "main.py --start-mcp-server, or use the parse_diagnostics tool"})
+            try:
+                return json.dumps(self.diagnostics.to_dict(), ensure_ascii=False)
+            except Exception as e:
+                logger.error(f"獲取解析問題時發生錯誤: {e}")
+                return json.dumps({"error": str(e)})
         
         @self.mcp.resource("cypher://examples")
         def get_cypher_examples() -> str:
@@ -429,6 +2818,13 @@ class CodebaseKnowledgeGraphMCP:
             WHERE module.name = "pandas"
             RETURN file
             ```
+            
+            6. 列出所有公開的函數與方法:
+            ```
+            MATCH (f:Base)
+            WHERE (f:Function OR f:Method) AND f.visibility = "Public"
+            RETURN f.name, f.file_path, f.line_no
+            ```
             """
     
     def start(self, port=None, transport="stdio"):
@@ -455,20 +2851,56 @@ def main():
     parser.add_argument("--codebase-path", help="程式碼庫路徑", default=".")
     parser.add_argument("--transport", choices=["stdio", "http", "sse"], default="stdio", help="MCP傳輸協議")
     parser.add_argument("--port", type=int, help="HTTP服務器端口號（僅用於HTTP/SSE傳輸）", default=8080)
+    parser.add_argument("--storage", choices=STORAGE_BACKENDS, help="圖儲存後端（預設為STORAGE_BACKEND或neo4j）")
+    parser.add_argument("--db-path", help="sqlite儲存後端的資料庫檔案（預設為codegraph.db）")
     parser.add_argument("--neo4j-uri", help="Neo4j資料庫URI")
     parser.add_argument("--neo4j-user", help="Neo4j使用者名稱")
     parser.add_argument("--neo4j-password", help="Neo4j密碼")
+    parser.add_argument("--max-traversal-depth", type=int,
+                        help="圖遍歷工具未指定depth時的預設層數（預設為MAX_TRAVERSAL_DEPTH或10）")
+    parser.add_argument("--config", help=f"設定檔路徑（預設為程式碼庫路徑下的{CONFIG_FILE}）")
+    parser.add_argument("--print-config", action="store_true", help="印出合併後的設定並結束")
+    parser.add_argument("--lsp", action="store_true",
+                        help="以Language Server Protocol（definition、references、workspace/symbol）經stdio提供服務，取代MCP")
     
     args = parser.parse_args()
+    if args.max_traversal_depth is not None and args.max_traversal_depth < 1:
+        parser.error("--max-traversal-depth must be at least 1")
+    
+    # 與索引器讀取同一份設定檔；命令列參數優先
+    # Read the same config file as the indexer; command-line flags win
+    try:
+        config = load_config(args.config or find_config(args.codebase_path)).override({
+            ("storage", "backend"): args.storage,
+            ("storage", "db_path"): args.db_path,
+            ("storage", "neo4j_uri"): args.neo4j_uri,
+            ("storage", "neo4j_user"): args.neo4j_user,
+            ("queries", "max_traversal_depth"): args.max_traversal_depth,
+        })
+    except ConfigError as e:
+        parser.error(str(e))
+    if args.print_config:
+        print(config.to_toml(), end="")
+        return
     
     # 創建MCP服務器
     server = CodebaseKnowledgeGraphMCP(
-        neo4j_uri=args.neo4j_uri,
-        neo4j_user=args.neo4j_user,
+        neo4j_uri=config.get("storage", "neo4j_uri"),
+        neo4j_user=config.get("storage", "neo4j_user"),
         neo4j_password=args.neo4j_password,
-        server_port=args.port
+        server_port=args.port,
+        storage=config.get("storage", "backend"),
+        db_path=config.get("storage", "db_path"),
+        config=config
     )
     
+    # 以LSP模式服務不支援MCP的編輯器
+    # Serve editors without MCP support over LSP, from the same stored graph
+    if args.lsp:
+        from src.lsp.server import LspServer
+        logger.info("LSP服務器以stdio模式啟動")
+        sys.exit(LspServer(server.shared_graph(), args.codebase_path).serve(sys.stdin.buffer, sys.stdout.buffer))
+    
     # 啟動服務器
     server.start(transport=args.transport)
 
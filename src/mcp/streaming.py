@@ -0,0 +1,113 @@
+"""
+JSON Lines streaming of large tool results.
+
+A tool called with ``stream: true`` does not build one JSON document.
+Instead it emits a sequence of records, one JSON object per line, and hands
+each line to a sink as soon as it is serialized. A batch of at most
+``batch_size`` nodes or edges goes into one record, so memory and latency
+stay bounded by the batch rather than the whole result.
+
+Records, in order (schema version 1):
+
+- ``{"type": "begin", "schema": 1, "tool": ..., <query fields>}``
+- ``{"type": "nodes", "items": [...]}`` and ``{"type": "edges", "items": [...]}``,
+  any number of each
+- ``{"type": "end", "nodes": N, "edges": M, "lines": L, <summary fields>}``,
+  where the counts cover every batch and ``lines`` includes the end record
+- or ``{"type": "error", "error": ...}`` in place of ``end`` if the tool
+  fails after it started streaming
+
+A stream is complete only once ``end`` has arrived. Every record is written
+with its trailing newline in one call and flushed, and ``json.dumps``
+escapes newlines inside strings, so a reader cut off at any point sees only
+whole records followed by at most one unterminated fragment.
+"""
+
+import json
+from typing import Any, Awaitable, Callable, Dict, Iterable, List, TextIO
+
+STREAM_SCHEMA_VERSION = 1
+
+# Most nodes or edges in one record
+STREAM_BATCH_SIZE = 200
+
+# Logger name of the MCP log notifications carrying the records
+STREAM_LOGGER = "graph-codebase-mcp.stream"
+
+Sink = Callable[[str], Awaitable[None]]
+
+
+def encode_record(record: Dict[str, Any]) -> str:
+    """One record as a line of JSON, newline included."""
+    return json.dumps(record, ensure_ascii=False, separators=(",", ":")) + "\n"
+
+
+def context_sink(ctx) -> Sink:
+    """Send each record as an MCP log notification tied to the current request."""
+    async def send(line: str) -> None:
+        await ctx.log("info", line.rstrip("\n"), logger_name=STREAM_LOGGER)
+    return send
+
+
+def file_sink(stream: TextIO) -> Sink:
+    """Write each record to a text stream and flush it."""
+    async def send(line: str) -> None:
+        stream.write(line)
+        stream.flush()
+    return send
+
+
+class BufferSink:
+    """Keep the records, for callers that cannot receive them as they come."""
+
+    def __init__(self):
+        self.lines: List[str] = []
+
+    async def __call__(self, line: str) -> None:
+        self.lines.append(line)
+
+    def text(self) -> str:
+        return "".join(self.lines)
+
+
+class JsonLinesStream:
+    """Writes the records of one streamed tool result to a sink."""
+
+    def __init__(self, sink: Sink, batch_size: int = STREAM_BATCH_SIZE):
+        self._sink = sink
+        self.batch_size = max(1, batch_size)
+        self.counts: Dict[str, int] = {"nodes": 0, "edges": 0}
+        self.lines = 0
+
+    async def _emit(self, record: Dict[str, Any]) -> None:
+        await self._sink(encode_record(record))
+        self.lines += 1
+
+    async def begin(self, tool: str, **fields: Any) -> None:
+        await self._emit({"type": "begin", "schema": STREAM_SCHEMA_VERSION, "tool": tool, **fields})
+
+    async def items(self, kind: str, items: Iterable[Any]) -> None:
+        """Emit items of one kind ("nodes" or "edges") in batches."""
+        batch = []
+        for item in items:
+            batch.append(item)
+            if len(batch) == self.batch_size:
+                await self._batch(kind, batch)
+                batch = []
+        if batch:
+            await self._batch(kind, batch)
+
+    async def _batch(self, kind: str, batch: List[Any]) -> None:
+        await self._emit({"type": kind, "items": batch})
+        self.counts[kind] = self.counts.get(kind, 0) + len(batch)
+
+    async def end(self, **fields: Any) -> Dict[str, Any]:
+        """Emit the closing record and return it."""
+        record = {"type": "end", **self.counts, "lines": self.lines + 1, **fields}
+        await self._emit(record)
+        return record
+
+    async def error(self, message: str) -> Dict[str, Any]:
+        record = {"type": "error", "error": message}
+        await self._emit(record)
+        return record
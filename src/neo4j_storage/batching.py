@@ -0,0 +1,108 @@
+"""
+Splitting graph writes into batches, with retries and a per-item fallback.
+
+A write of many nodes or relationships is sent as a few parameterized
+statements over batches of rows instead of one statement per row. Batches
+hold at most ``batch_size`` rows and about ``max_bytes`` of parameters, as
+measured by their JSON size, so that rows carrying embeddings do not exceed
+what the server accepts in one message.
+
+A batch that fails is retried with exponential backoff. When it keeps
+failing, its rows are written one by one, so that a single malformed row
+costs only itself; the rows that still fail are logged and returned.
+"""
+
+import json
+import logging
+import time
+from typing import Any, Callable, Iterable, Iterator, List, Sequence
+
+logger = logging.getLogger(__name__)
+
+DEFAULT_BATCH_SIZE = 1000
+# Neo4j accepts larger messages, but big ones stall the connection
+DEFAULT_MAX_BATCH_BYTES = 8 * 1024 * 1024
+DEFAULT_MAX_RETRIES = 3
+# Seconds before the first retry; doubled before each further one
+DEFAULT_RETRY_BACKOFF = 0.5
+
+
+def row_size(row: Any) -> int:
+    """Approximate size of a row once sent, in bytes."""
+    return len(json.dumps(row, default=str))
+
+
+def split_batches(rows: Iterable[Any], batch_size: int = DEFAULT_BATCH_SIZE,
+                  max_bytes: int = DEFAULT_MAX_BATCH_BYTES) -> Iterator[List[Any]]:
+    """
+    Group rows into batches of at most batch_size rows and about max_bytes.
+
+    A row larger than max_bytes on its own makes a batch of one.
+    """
+    batch: List[Any] = []
+    size = 0
+    for row in rows:
+        row_bytes = row_size(row)
+        if batch and (len(batch) >= batch_size or size + row_bytes > max_bytes):
+            yield batch
+            batch, size = [], 0
+        batch.append(row)
+        size += row_bytes
+    if batch:
+        yield batch
+
+
+def write_with_retry(write: Callable[[List[Any]], None], batch: List[Any],
+                     max_retries: int = DEFAULT_MAX_RETRIES, backoff: float = DEFAULT_RETRY_BACKOFF,
+                     sleep: Callable[[float], None] = time.sleep) -> None:
+    """Write a batch, retrying up to max_retries times; the last error is raised."""
+    for attempt in range(max_retries + 1):
+        try:
+            write(batch)
+            return
+        except Exception as e:
+            if attempt == max_retries:
+                raise
+            delay = backoff * 2 ** attempt
+            logger.warning(f"Batch of {len(batch)} rows failed ({e}), retrying in {delay:.1f}s")
+            sleep(delay)
+
+
+def write_batches(rows: Sequence[Any], write: Callable[[List[Any]], None],
+                  batch_size: int = DEFAULT_BATCH_SIZE, max_bytes: int = DEFAULT_MAX_BATCH_BYTES,
+                  max_retries: int = DEFAULT_MAX_RETRIES, backoff: float = DEFAULT_RETRY_BACKOFF,
+                  sleep: Callable[[float], None] = time.sleep) -> List[Any]:
+    """
+    Write rows in batches, falling back to one row at a time for a batch that keeps failing.
+
+    Args:
+        rows: Rows to write
+        write: Writes one batch of rows, raising when it fails
+        batch_size: Most rows per batch
+        max_bytes: Most bytes of rows per batch, as measured by row_size
+        max_retries: Retries of a failing batch before its rows are written one by one
+        backoff: Seconds before the first retry, doubled before each further one
+        sleep: Waits between retries
+
+    Returns:
+        The rows that could not be written
+    """
+    failed: List[Any] = []
+    for batch in split_batches(rows, batch_size, max_bytes):
+        try:
+            write_with_retry(write, batch, max_retries, backoff, sleep)
+            continue
+        except Exception as e:
+            if len(batch) == 1:
+                logger.error(f"Failed to write row: {e}")
+                failed.extend(batch)
+                continue
+            logger.warning(f"Batch of {len(batch)} rows failed after {max_retries} retries ({e}), "
+                           "writing its rows one by one")
+        for row in batch:
+            try:
+                write([row])
+            except Exception as e:
+                logger.error(f"Failed to write row: {e}")
+                failed.append(row)
+    return failed
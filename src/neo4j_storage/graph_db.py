@@ -1,14 +1,38 @@
 import os
-from typing import Dict, List, Any, Optional, Tuple, Set
+import threading
+from contextlib import contextmanager
+from typing import Dict, Iterator, List, Any, Optional, Tuple, Set
 from neo4j import GraphDatabase, Driver
 import logging
 
+from src.neo4j_storage.batching import (
+    DEFAULT_BATCH_SIZE,
+    DEFAULT_MAX_BATCH_BYTES,
+    DEFAULT_MAX_RETRIES,
+    DEFAULT_RETRY_BACKOFF,
+    split_batches,
+    write_batches,
+)
+from src.neo4j_storage.store import RELATED_DIRECTIONS, GraphStore, check_identifier
+
 # 設定日誌
 logging.basicConfig(level=logging.INFO, format='%(asctime)s - %(name)s - %(levelname)s - %(message)s')
 logger = logging.getLogger(__name__)
 
 
-class Neo4jDatabase:
+def _env_int(name: str, default: int) -> int:
+    """Integer environment variable, or the default when unset or invalid"""
+    value = os.environ.get(name)
+    if not value:
+        return default
+    try:
+        return int(value)
+    except ValueError:
+        logger.warning(f"Invalid {name}: {value}, using {default}")
+        return default
+
+
+class Neo4jDatabase(GraphStore):
     """Neo4j圖形資料庫操作類 / Neo4j graph database operations class"""
     
     def __init__(
@@ -18,6 +42,10 @@ class Neo4jDatabase:
         password: str = None,
         database: str = "neo4j",
         max_connection_pool_size: Optional[int] = None,
+        batch_size: Optional[int] = None,
+        max_batch_bytes: int = DEFAULT_MAX_BATCH_BYTES,
+        max_retries: Optional[int] = None,
+        retry_backoff: float = DEFAULT_RETRY_BACKOFF,
     ):
         """初始化Neo4j資料庫連接 / Initialize Neo4j database connection
         
@@ -32,6 +60,15 @@ class Neo4jDatabase:
                      / Database name, default is "neo4j"
             max_connection_pool_size: 最大連線池大小，若為None則從環境變數取得或使用預設值
                                      / Max connection pool size, if None get from env or use default
+            batch_size: 每批寫入的最大筆數，若為None則從環境變數NEO4J_BATCH_SIZE取得或使用1000
+                        / Most rows per write statement, if None get from NEO4J_BATCH_SIZE or use 1000
+            max_batch_bytes: 每批寫入的最大位元組數
+                             / Most bytes of parameters per write statement
+            max_retries: 失敗批次的重試次數，若為None則從環境變數NEO4J_WRITE_RETRIES取得或使用3
+                         / Retries of a failing batch before its rows are written one by one,
+                         if None get from NEO4J_WRITE_RETRIES or use 3
+            retry_backoff: 第一次重試前等待的秒數，之後每次加倍
+                           / Seconds before the first retry, doubled before each further one
         """
         self.uri = uri or os.environ.get("NEO4J_URI", "bolt://localhost:7687")
         self.user = user or os.environ.get("NEO4J_USER", "neo4j")
@@ -58,7 +95,14 @@ class Neo4jDatabase:
                 max_connection_pool_size = max(16, max_workers * 2)
         
         self.max_connection_pool_size = max_connection_pool_size
+        self.batch_size = max(1, batch_size or _env_int("NEO4J_BATCH_SIZE", DEFAULT_BATCH_SIZE))
+        self.max_batch_bytes = max_batch_bytes
+        self.max_retries = max(0, _env_int("NEO4J_WRITE_RETRIES", DEFAULT_MAX_RETRIES)
+                               if max_retries is None else max_retries)
+        self.retry_backoff = retry_backoff
         self.driver = None
+        # Open write_batch transaction of each thread
+        self._local = threading.local()
         
         try:
             # Create driver with connection pool configuration
@@ -128,6 +172,44 @@ class Neo4jDatabase:
         """
         return self.driver.session(database=self.database)
     
+    @contextmanager
+    def write_batch(self):
+        """Run the writes made in this thread inside the block as one transaction
+
+        Readers see either none or all of the batch, so a file whose nodes
+        are replaced is never seen without nodes. The batch is rolled back
+        if the block raises. Nested batches join the outer one.
+
+        Example:
+            >>> with db.write_batch():
+            ...     db.delete_nodes(old_ids)
+            ...     db.upsert_nodes(new_nodes)
+        """
+        if getattr(self._local, "tx", None) is not None:
+            yield
+            return
+        with self.driver.session(database=self.database) as session:
+            tx = session.begin_transaction()
+            self._local.tx = tx
+            try:
+                yield
+                tx.commit()
+            except Exception:
+                tx.rollback()
+                raise
+            finally:
+                self._local.tx = None
+
+    @contextmanager
+    def _writer(self):
+        """The open write_batch transaction of this thread, else a new session"""
+        tx = getattr(self._local, "tx", None)
+        if tx is not None:
+            yield tx
+        else:
+            with self.driver.session(database=self.database) as session:
+                yield session
+
     def clear_database(self):
         """清空資料庫中的所有節點和關係"""
         try:
@@ -178,7 +260,8 @@ class Neo4jDatabase:
                     {"name": "function_name_idx", "label": "Function", "property": "name"},
                     {"name": "method_name_idx", "label": "Method", "property": "name"},
                     {"name": "variable_name_idx", "label": "Variable", "property": "name"},
-                    {"name": "module_name_idx", "label": "Module", "property": "name"}
+                    {"name": "module_name_idx", "label": "Module", "property": "name"},
+                    {"name": "qualified_name_idx", "label": "Base", "property": "qualified_name"}
                 ]
                 
                 for config in index_configs:
@@ -256,43 +339,24 @@ class Neo4jDatabase:
             return
         
         try:
-            with self.driver.session(database=self.database) as session:
-                batch_size = 1000  # 設定適當的批次大小
-                
-                for i in range(0, len(nodes), batch_size):
-                    batch = nodes[i:i+batch_size]
-                    created = 0
-                    
-                    # 對每個節點單獨處理
-                    for node in batch:
-                        labels = node['labels']
-                        properties = node['properties']
-                        
-                        # 構建標籤字串，例如 `:Label1:Label2`
-                        labels_str = ''.join([f":{label}" for label in labels])
-                        
-                        # 構建屬性字串，例如 `{id: 'test1', name: 'Test 1'}`
-                        props_str = "{"
-                        props_str += ", ".join([f"{k}: ${k}" for k in properties.keys()])
-                        props_str += "}"
-                        
-                        # 創建節點查詢
-                        query = f"""
-                        CREATE (n{labels_str} {props_str})
-                        RETURN n
-                        """
-                        
-                        # 執行查詢
-                        session.run(query, properties)
-                        created += 1
-                    
-                    logger.info(f"已創建 {created} 個節點")
+            failed = []
+            # 每組標籤一個 UNWIND 查詢
+            # One UNWIND statement per label set
+            for labels, batch in self._group(nodes, lambda node: tuple(node['labels'])).items():
+                labels_str = ''.join(f":{check_identifier(label, 'label')}" for label in labels)
+                failed += self._write_rows(f"UNWIND $rows AS row CREATE (n{labels_str}) SET n = row.properties",
+                                           batch)
+            logger.info(f"已創建 {len(nodes) - len(failed)} 個節點")
+            self._log_failed(failed, "nodes")
         except Exception as e:
             logger.error(f"批量創建節點時發生錯誤: {e}")
             raise
     
     def batch_create_relationships(self, relationships: List[Dict[str, Any]]):
         """批量創建關係
+
+        Relationships between nodes that do not exist are skipped, so the
+        nodes of a batch must be written before its relationships.
         
         Args:
             relationships: 關係列表，每個關係為一個字典
@@ -303,43 +367,231 @@ class Neo4jDatabase:
             return
         
         try:
-            with self.driver.session(database=self.database) as session:
-                batch_size = 1000  # 設定適當的批次大小
-                
-                for i in range(0, len(relationships), batch_size):
-                    batch = relationships[i:i+batch_size]
-                    processed = 0
-                    
-                    # 對每個關係單獨處理，避免生成動態Cypher查詢
-                    for rel in batch:
-                        start_id = rel['start_node_id']
-                        end_id = rel['end_node_id']
-                        rel_type = rel['type']
-                        properties = rel['properties'] or {}
-                        
-                        # 使用參數化查詢
-                        query = f"""
-                        MATCH (start:Base {{id: $start_id}})
-                        MATCH (end:Base {{id: $end_id}})
-                        CREATE (start)-[r:{rel_type}]->(end)
-                        SET r = $props
-                        RETURN r
-                        """
-                        
-                        params = {
-                            "start_id": start_id,
-                            "end_id": end_id,
-                            "props": properties
-                        }
-                        
-                        session.run(query, params)
-                        processed += 1
-                    
-                    logger.info(f"已處理 {processed} 個關係")
+            failed = []
+            for rel_type, batch in self._group(relationships, lambda rel: rel['type']).items():
+                query = f"""
+                UNWIND $rows AS row
+                MATCH (start:Base {{id: row.start_node_id}})
+                MATCH (end:Base {{id: row.end_node_id}})
+                CREATE (start)-[r:{check_identifier(rel_type, 'relationship type')}]->(end)
+                SET r = coalesce(row.properties, {{}})
+                """
+                failed += self._write_rows(query, batch)
+            logger.info(f"已處理 {len(relationships) - len(failed)} 個關係")
+            self._log_failed(failed, "relationships")
         except Exception as e:
             logger.error(f"批量創建關係時發生錯誤: {e}")
             raise
     
+    def upsert_nodes(self, nodes: List[Dict[str, Any]]):
+        """Create or replace nodes by id
+
+        Args:
+            nodes: Nodes in the batch_create_nodes format; existing nodes with
+                   the same id have their properties replaced
+        """
+        if not nodes:
+            return
+
+        try:
+            failed = []
+            key = lambda node: tuple(label for label in node['labels'] if label != "Base")
+            for labels, batch in self._group(nodes, key).items():
+                labels_str = ''.join(f":{check_identifier(label, 'label')}" for label in labels)
+                query = f"""
+                UNWIND $rows AS row
+                MERGE (n:Base {{id: row.properties.id}})
+                SET n = row.properties
+                """ + (f"SET n{labels_str}" if labels_str else "")
+                failed += self._write_rows(query, batch)
+            logger.info(f"Upserted {len(nodes) - len(failed)} nodes")
+            self._log_failed(failed, "nodes")
+        except Exception as e:
+            logger.error(f"Error upserting nodes: {e}")
+            raise
+
+    def delete_nodes(self, node_ids: List[str]):
+        """Delete nodes by id, together with all their relationships
+
+        Args:
+            node_ids: Ids of the nodes to delete
+        """
+        if not node_ids:
+            return
+
+        try:
+            with self._writer() as session:
+                session.run(
+                    "MATCH (n:Base) WHERE n.id IN $ids DETACH DELETE n",
+                    {"ids": list(node_ids)}
+                )
+                logger.info(f"Deleted {len(node_ids)} nodes")
+        except Exception as e:
+            logger.error(f"Error deleting nodes: {e}")
+            raise
+
+    def delete_relationships(self, relationships: List[Dict[str, Any]]):
+        """Delete specific relationships
+
+        Args:
+            relationships: Relationships in the batch_create_relationships format.
+                           ``properties`` holds the identifying properties only;
+                           a relationship is deleted when all of them match.
+        """
+        if not relationships:
+            return
+
+        try:
+            failed = []
+            for rel_type, batch in self._group(relationships, lambda rel: rel['type']).items():
+                query = f"""
+                UNWIND $rows AS row
+                WITH row, coalesce(row.properties, {{}}) AS props
+                MATCH (start:Base {{id: row.start_node_id}})-[r:{check_identifier(rel_type, 'relationship type')}]->(end:Base {{id: row.end_node_id}})
+                WHERE all(k IN keys(props) WHERE r[k] = props[k])
+                DELETE r
+                """
+                failed += self._write_rows(query, batch)
+            logger.info(f"Deleted {len(relationships) - len(failed)} relationships")
+            self._log_failed(failed, "relationship deletions")
+        except Exception as e:
+            logger.error(f"Error deleting relationships: {e}")
+            raise
+
+    @staticmethod
+    def _group(items: List[Dict[str, Any]], key) -> Dict[Any, List[Dict[str, Any]]]:
+        """Items by key, keeping their order; labels and types cannot be query parameters"""
+        groups: Dict[Any, List[Dict[str, Any]]] = {}
+        for item in items:
+            groups.setdefault(key(item), []).append(item)
+        return groups
+
+    def _write_rows(self, query: str, rows: List[Dict[str, Any]]) -> List[Dict[str, Any]]:
+        """Run a query over ``$rows`` once per batch of rows
+
+        Inside write_batch the batches join the open transaction, and the
+        first failure rolls it back: a failed statement aborts the
+        transaction, so nothing can be retried. Otherwise each batch commits
+        on its own, is retried with backoff and, if it keeps failing, is
+        written row by row.
+
+        Returns:
+            The rows that could not be written
+        """
+        tx = getattr(self._local, "tx", None)
+        if tx is not None:
+            for batch in split_batches(rows, self.batch_size, self.max_batch_bytes):
+                tx.run(query, {"rows": batch}).consume()
+            return []
+
+        def write(batch):
+            with self.driver.session(database=self.database) as session:
+                session.run(query, {"rows": batch}).consume()
+
+        return write_batches(rows, write, self.batch_size, self.max_batch_bytes,
+                             self.max_retries, self.retry_backoff)
+
+    @staticmethod
+    def _log_failed(failed: List[Dict[str, Any]], what: str):
+        """Name the items that could not be written"""
+        if failed:
+            ids = [item.get('properties', {}).get('id') if 'labels' in item
+                   else f"{item.get('start_node_id')}-[{item.get('type')}]->{item.get('end_node_id')}"
+                   for item in failed[:10]]
+            logger.error(f"Could not write {len(failed)} {what}, e.g. {ids}")
+
+    def delete_nodes_by_file(self, file_paths: List[str]):
+        """Delete every node that belongs to the given files, with its relationships
+
+        Args:
+            file_paths: file_path values of the nodes to delete
+        """
+        if not file_paths:
+            return
+
+        try:
+            with self.driver.session(database=self.database) as session:
+                session.run(
+                    "MATCH (n:Base) WHERE n.file_path IN $paths DETACH DELETE n",
+                    {"paths": list(file_paths)}
+                )
+                logger.info(f"Deleted nodes of {len(file_paths)} files")
+        except Exception as e:
+            logger.error(f"Error deleting nodes by file: {e}")
+            raise
+
+    def get_file_states(self) -> List[Dict[str, Any]]:
+        """Return the indexing record stored on every File node
+
+        Returns:
+            One dict per file with file_path, content_hash, mtime_ns and
+            graph_state (JSON text, or None if it was never stored)
+        """
+        try:
+            with self.driver.session(database=self.database) as session:
+                result = session.run(
+                    """
+                    MATCH (f:File)
+                    RETURN f.file_path AS file_path, f.content_hash AS content_hash,
+                           f.mtime_ns AS mtime_ns, f.graph_state AS graph_state
+                    """
+                )
+                return [dict(record) for record in result]
+        except Exception as e:
+            logger.error(f"Error reading file states: {e}")
+            raise
+
+    def set_file_states(self, states: List[Dict[str, Any]]):
+        """Store the indexing record on existing File nodes
+
+        Args:
+            states: Dicts with the File node id, content_hash, mtime_ns and
+                    graph_state (JSON text)
+        """
+        if not states:
+            return
+
+        try:
+            with self._writer() as session:
+                session.run(
+                    """
+                    UNWIND $states AS state
+                    MATCH (f:Base {id: state.id})
+                    SET f.content_hash = state.content_hash,
+                        f.mtime_ns = state.mtime_ns,
+                        f.graph_state = state.graph_state
+                    """,
+                    {"states": states}
+                )
+                logger.info(f"Stored file states for {len(states)} files")
+        except Exception as e:
+            logger.error(f"Error storing file states: {e}")
+            raise
+
+    def set_node_properties(self, updates: List[Dict[str, Any]]):
+        """Set some properties of existing nodes, keeping the others
+
+        Args:
+            updates: Dicts with the node id and the properties to set
+        """
+        if not updates:
+            return
+
+        try:
+            with self._writer() as session:
+                session.run(
+                    """
+                    UNWIND $updates AS update
+                    MATCH (n:Base {id: update.id})
+                    SET n += update.properties
+                    """,
+                    {"updates": updates}
+                )
+                logger.info(f"Updated properties of {len(updates)} nodes")
+        except Exception as e:
+            logger.error(f"Error updating node properties: {e}")
+            raise
+
     def create_full_text_index(self, index_name: str, node_labels: List[str], properties: List[str]):
         """創建全文檢索索引
         
@@ -457,6 +709,506 @@ class Neo4jDatabase:
             logger.error(f"向量相似度搜索時發生錯誤: {e}")
             raise
     
+    def find_nodes_by_name(self, name: str, node_type: Optional[str] = None, limit: int = 10) -> List[Dict[str, Any]]:
+        """Fetch the nodes with a name
+
+        Args:
+            name: Exact node name
+            node_type: Node label to require, e.g. Function
+            limit: Maximum number of nodes
+
+        Returns:
+            Properties of each matching node
+        """
+        label = f":{check_identifier(node_type, 'node type')}" if node_type else ""
+        try:
+            with self.driver.session(database=self.database) as session:
+                result = session.run(
+                    f"MATCH (n{label}) WHERE n.name = $name RETURN n LIMIT $limit",
+                    {"name": name, "limit": limit}
+                )
+                return [dict(record["n"]) for record in result]
+        except Exception as e:
+            logger.error(f"Error finding nodes named {name}: {e}")
+            raise
+
+    def find_related(self, relation_type: str, direction: str, match: Dict[str, Any],
+                     label: Optional[str] = None, other_label: Optional[str] = None,
+                     limit: Optional[int] = None) -> List[Dict[str, Any]]:
+        """Fetch the nodes across one kind of edge
+
+        Args:
+            relation_type: Relationship type to follow, e.g. CALLS
+            direction: "out" from the matched nodes, "in" into them
+            match: Property values the starting nodes must have
+            label: Label the starting nodes must have
+            other_label: Label the returned nodes must have
+            limit: Maximum number of results
+
+        Returns:
+            Properties of each node reached, once per edge
+        """
+        if direction not in RELATED_DIRECTIONS:
+            raise ValueError(f"Unknown direction: {direction}")
+        relation_type = check_identifier(relation_type, "relation type")
+        start = f":{check_identifier(label, 'label')}" if label else ""
+        other = f":{check_identifier(other_label, 'label')}" if other_label else ""
+        conditions = " AND ".join(f"n.{check_identifier(k, 'property')} = $match.{k}" for k in match) or "true"
+        arrow = f"-[:{relation_type}]->" if direction == "out" else f"<-[:{relation_type}]-"
+        try:
+            with self.driver.session(database=self.database) as session:
+                result = session.run(
+                    f"""
+                    MATCH (n{start}){arrow}(m{other})
+                    WHERE {conditions}
+                    RETURN m
+                    """ + ("LIMIT $limit" if limit is not None else ""),
+                    {"match": match, "limit": limit}
+                )
+                return [dict(record["m"]) for record in result]
+        except Exception as e:
+            logger.error(f"Error fetching {relation_type} neighbours: {e}")
+            raise
+
+    def find_nodes_by_symbol(self, symbol: str, limit: int = 20, fuzzy: bool = False) -> List[Dict[str, Any]]:
+        """Find code nodes matching a symbol reference
+
+        Accepts a node id, a fully qualified name (e.g. ``crate::person::Person::new``),
+        a qualified ``Owner.member`` name (e.g. ``Person.get_name``) or a bare name.
+
+        Args:
+            symbol: Symbol to look up
+            limit: Maximum number of matches
+            fuzzy: Also match names containing the symbol, ignoring case
+
+        Returns:
+            List of node summaries (id, name, type, file_path, line_no, visibility)
+        """
+        owner, _, member = symbol.rpartition(".")
+        try:
+            with self.driver.session(database=self.database) as session:
+                result = session.run(
+                    """
+                    MATCH (n:Base)
+                    WHERE n.id = $symbol
+                       OR (n.name = $symbol AND NOT n:File)
+                       OR n.qualified_name = $symbol
+                       OR ($owner <> '' AND n.name = $member
+                           AND EXISTS { MATCH (o:Base {name: $owner})-[:DEFINES]->(n) })
+                       OR ($fuzzy AND NOT n:File AND toLower(n.name) CONTAINS toLower($symbol))
+                    RETURN n.id AS id, n.name AS name,
+                           [l IN labels(n) WHERE l <> 'Base'][0] AS type,
+                           n.file_path AS file_path, n.line_no AS line_no,
+                           n.visibility AS visibility
+                    ORDER BY n.file_path, n.line_no
+                    LIMIT $limit
+                    """,
+                    {"symbol": symbol, "owner": owner, "member": member, "fuzzy": fuzzy, "limit": limit}
+                )
+                return [record.data() for record in result]
+        except Exception as e:
+            logger.error(f"Error finding nodes for symbol {symbol}: {e}")
+            raise
+
+    def get_symbols(self, node_types: Optional[List[str]] = None) -> Iterator[Dict[str, Any]]:
+        """Stream the named code nodes, for symbol search
+
+        Args:
+            node_types: Node labels to include; None includes every symbol
+
+        Yields:
+            Node summaries (id, name, type, file_path, line_no, visibility,
+            complexity, doc, documented, owner, project for a node of a
+            workspace project and deprecated for a deprecated one) of every
+            node except files and unresolved placeholders; owner is the name
+            of the type defining a member
+        """
+        try:
+            with self.driver.session(database=self.database) as session:
+                result = session.run(
+                    """
+                    MATCH (n:Base)
+                    WHERE NOT n:File AND NOT n:Unresolved AND n.name IS NOT NULL
+                    WITH n, [l IN labels(n) WHERE l <> 'Base'][0] AS type
+                    WHERE $types IS NULL OR type IN $types
+                    OPTIONAL MATCH (owner:Base)-[:DEFINES]->(n)
+                    WHERE NOT owner:File
+                    WITH n, type, head(collect(owner.name)) AS owner
+                    RETURN n.id AS id, n.name AS name, type,
+                           n.file_path AS file_path, n.line_no AS line_no,
+                           n.visibility AS visibility, n.complexity AS complexity,
+                           coalesce(n.doc, n.docstring) AS doc, n.documented AS documented, owner,
+                           n.project AS project, n.deprecated AS deprecated
+                    """,
+                    {"types": node_types}
+                )
+                for record in result:
+                    symbol = record.data()
+                    for key in ("project", "deprecated"):
+                        if not symbol.get(key):
+                            del symbol[key]
+                    yield symbol
+        except Exception as e:
+            logger.error(f"Error fetching symbols: {e}")
+            raise
+
+    def get_call_edges(self, node_ids: List[str], direction: str = "callers") -> Iterator[Dict[str, Any]]:
+        """Stream CALLS edges touching a set of nodes
+
+        Records are yielded as the database returns them, in a fixed order,
+        so a caller can stop early without materializing every edge.
+
+        Args:
+            node_ids: Node ids to expand
+            direction: "callers" for edges into the nodes, "callees" for edges out of them
+
+        Yields:
+            One record per edge with the caller/callee ids, the node on the far
+            side (``node``) and the call site (the caller's file plus the
+            line, source text and ``await_call`` flag stored on the edge)
+        """
+        if direction == "callers":
+            match = "MATCH (other:Base)-[r:CALLS]->(n:Base) WHERE n.id IN $ids"
+            caller, callee = "other", "n"
+        elif direction == "callees":
+            match = "MATCH (n:Base)-[r:CALLS]->(other:Base) WHERE n.id IN $ids"
+            caller, callee = "n", "other"
+        else:
+            raise ValueError(f"Unknown call direction: {direction}")
+
+        try:
+            with self.driver.session(database=self.database) as session:
+                result = session.run(
+                    f"""
+                    {match}
+                    RETURN {caller}.id AS caller_id, {callee}.id AS callee_id,
+                           {{id: other.id, name: other.name,
+                             type: [l IN labels(other) WHERE l <> 'Base'][0],
+                             file_path: other.file_path, line_no: other.line_no}} AS node,
+                           {caller}.file_path AS call_file,
+                           r.line_no AS call_line, r.call_site AS call_site, r.await_call AS await_call
+                    ORDER BY caller_id, callee_id, call_line, call_site
+                    """,
+                    {"ids": list(node_ids)}
+                )
+                for record in result:
+                    yield record.data()
+        except Exception as e:
+            logger.error(f"Error fetching call edges: {e}")
+            raise
+
+    def get_references(self, node_ids: List[str]) -> Iterator[Dict[str, Any]]:
+        """Stream REFERENCES and CALLS edges pointing at a set of nodes
+
+        Records are yielded in a fixed order (file, line, referencing node,
+        target, access), so the same query always pages the same way.
+
+        Args:
+            node_ids: Ids of the referenced nodes
+
+        Yields:
+            One record per edge with the relation type, its ``access`` tag
+            (REFERENCES only), the referencing node (``node``) and the
+            location of the reference
+        """
+        try:
+            with self.driver.session(database=self.database) as session:
+                result = session.run(
+                    """
+                    MATCH (src:Base)-[r:REFERENCES|CALLS]->(n:Base)
+                    WHERE n.id IN $ids
+                    RETURN n.id AS target_id, type(r) AS relation, r.access AS access,
+                           {id: src.id, name: src.name,
+                            type: [l IN labels(src) WHERE l <> 'Base'][0],
+                            file_path: src.file_path, line_no: src.line_no} AS node,
+                           r.line_no AS line_no, r.call_site AS snippet
+                    ORDER BY node.file_path, line_no, node.id, target_id, relation, access
+                    """,
+                    {"ids": list(node_ids)}
+                )
+                for record in result:
+                    yield record.data()
+        except Exception as e:
+            logger.error(f"Error fetching references: {e}")
+            raise
+
+    def get_usages(self, node_ids: List[str], relation_types: List[str]) -> Iterator[Dict[str, Any]]:
+        """Stream the edges of the given types pointing at a set of nodes
+
+        Records are yielded in a fixed order (file, line, using node, target,
+        relation), so the caller can stop after as many as it needs.
+
+        Args:
+            node_ids: Ids of the used nodes
+            relation_types: Relationship types that count as a usage
+
+        Yields:
+            One record per edge with the relation type, the using node
+            (``node``), and the line, column and source text stored on the
+            edge, where the parser recorded them
+        """
+        try:
+            with self.driver.session(database=self.database) as session:
+                result = session.run(
+                    """
+                    MATCH (src:Base)-[r]->(n:Base)
+                    WHERE n.id IN $ids AND type(r) IN $types
+                    RETURN n.id AS target_id, type(r) AS relation,
+                           {id: src.id, name: src.name,
+                            type: [l IN labels(src) WHERE l <> 'Base'][0],
+                            file_path: src.file_path, line_no: src.line_no} AS node,
+                           coalesce(r.line_no, src.line_no) AS line_no, r.column AS column,
+                           r.call_site AS snippet
+                    ORDER BY node.file_path, line_no, node.id, target_id, relation
+                    """,
+                    {"ids": list(node_ids), "types": list(relation_types)}
+                )
+                for record in result:
+                    yield record.data()
+        except Exception as e:
+            logger.error(f"Error fetching usages: {e}")
+            raise
+
+    def get_implementations(self, node_ids: List[str]) -> List[Dict[str, Any]]:
+        """Fetch the IMPLEMENTS edges pointing at a set of traits/interfaces or their methods
+
+        Args:
+            node_ids: Ids of the implemented traits or trait methods
+
+        Returns:
+            One record per edge, ordered by location, with the implemented node
+            id (``target_id``), the implementing type or method (``node``), the
+            trait path as written in the impl (``trait_path``), and for a type
+            the methods it provides for the trait (``methods``). A blanket
+            impl's node is the file declaring it, located at the impl, with
+            ``blanket`` set and the impl's ``for_type`` and ``bounds``
+        """
+        try:
+            with self.driver.session(database=self.database) as session:
+                result = session.run(
+                    """
+                    MATCH (impl:Base)-[r:IMPLEMENTS]->(t:Base)
+                    WHERE t.id IN $ids
+                    OPTIONAL MATCH (impl)-[:DEFINES]->(m:Base)-[:IMPLEMENTS]->(:Base)<-[:DEFINES]-(t)
+                    WITH t, r, impl, m ORDER BY m.line_no
+                    WITH t, r, impl,
+                         [x IN collect(m) | {id: x.id, name: x.name,
+                                             file_path: x.file_path, line_no: x.line_no}] AS methods
+                    RETURN t.id AS target_id, r.trait_path AS trait_path,
+                           {id: impl.id, name: impl.name,
+                            type: [l IN labels(impl) WHERE l <> 'Base'][0],
+                            file_path: impl.file_path, line_no: coalesce(r.line_no, impl.line_no)} AS node,
+                           methods, coalesce(r.blanket, false) AS blanket,
+                           r.for_type AS for_type, r.bounds AS bounds
+                    ORDER BY node.file_path, node.line_no, node.id
+                    """,
+                    {"ids": list(node_ids)}
+                )
+                return [record.data() for record in result]
+        except Exception as e:
+            logger.error(f"Error fetching implementations: {e}")
+            raise
+
+    def get_implemented(self, node_ids: List[str]) -> List[Dict[str, Any]]:
+        """Fetch the IMPLEMENTS edges leaving a set of types or methods
+
+        The reverse of get_implementations: the traits and interfaces a type
+        implements, or the trait methods a method provides.
+
+        Args:
+            node_ids: Ids of the implementing types or methods
+
+        Returns:
+            One record per edge, ordered by trait location, with the
+            implementing node id (``source_id``), the implemented trait or
+            method (``node``, an ``Unresolved`` placeholder for traits that
+            were not indexed) and the trait path as written (``trait_path``)
+        """
+        try:
+            with self.driver.session(database=self.database) as session:
+                result = session.run(
+                    """
+                    MATCH (impl:Base)-[r:IMPLEMENTS]->(t:Base)
+                    WHERE impl.id IN $ids
+                    RETURN impl.id AS source_id, r.trait_path AS trait_path,
+                           {id: t.id, name: t.name,
+                            type: [l IN labels(t) WHERE l <> 'Base'][0],
+                            file_path: t.file_path, line_no: t.line_no} AS node
+                    ORDER BY node.file_path, node.line_no, node.id
+                    """,
+                    {"ids": list(node_ids)}
+                )
+                return [record.data() for record in result]
+        except Exception as e:
+            logger.error(f"Error fetching implemented traits: {e}")
+            raise
+
+    def find_implementors(self, trait_name: str) -> List[Dict[str, Any]]:
+        """Find the types implementing a trait or interface given by name or path
+
+        The name is matched against the implemented node's name and against
+        the trait path written in the impl, in either direction, so
+        ``Display``, ``fmt::Display`` and ``std::fmt::Display`` all find
+        ``impl fmt::Display for Person``. Traits that were not indexed are
+        matched through their ``Unresolved`` placeholder.
+
+        Args:
+            trait_name: Bare or ``::``-qualified trait name
+
+        Returns:
+            One record per IMPLEMENTS edge from a type, ordered by location,
+            with the implementing type (``node``), the implemented trait
+            (``trait``), the trait path as written (``trait_path``) and
+            whether the impl is a blanket impl declared by a file (``blanket``)
+        """
+        base = trait_name.rsplit("::", 1)[-1]
+        try:
+            with self.driver.session(database=self.database) as session:
+                result = session.run(
+                    """
+                    MATCH (impl:Base)-[r:IMPLEMENTS]->(t:Base)
+                    WHERE NOT impl:Method
+                      AND (t.name = $name OR r.trait_path = $name
+                           OR r.trait_path ENDS WITH ('::' + $name)
+                           OR $name ENDS WITH ('::' + r.trait_path)
+                           OR (t.name = $base AND NOT t:Unresolved))
+                    RETURN {id: impl.id, name: impl.name,
+                            type: [l IN labels(impl) WHERE l <> 'Base'][0],
+                            file_path: impl.file_path, line_no: coalesce(r.line_no, impl.line_no),
+                            end_line_no: impl.end_line_no} AS node,
+                           {id: t.id, name: t.name,
+                            type: [l IN labels(t) WHERE l <> 'Base'][0],
+                            file_path: t.file_path, line_no: t.line_no} AS trait,
+                           r.trait_path AS trait_path, coalesce(r.blanket, false) AS blanket
+                    ORDER BY node.file_path, node.line_no, node.id, trait.id
+                    """,
+                    {"name": trait_name, "base": base}
+                )
+                return [record.data() for record in result]
+        except Exception as e:
+            logger.error(f"Error finding implementors of {trait_name}: {e}")
+            raise
+
+    def get_graph(self, node_types: Optional[List[str]] = None,
+                  properties: Optional[List[str]] = None) -> Dict[str, List[Dict[str, Any]]]:
+        """Fetch code nodes and the relationships between them, for export
+
+        Args:
+            node_types: Node labels to include; None includes every node
+            properties: Node properties to return as well, e.g. visibility
+
+        Returns:
+            Dict with ``nodes`` (id, type, name, file_path, line_no,
+            end_line_no, and ``properties`` holding the requested ones that
+            are set) and ``relationships`` (source, target, type, properties)
+            between the returned nodes
+        """
+        try:
+            with self.driver.session(database=self.database) as session:
+                nodes = session.run(
+                    """
+                    MATCH (n:Base)
+                    WITH n, [l IN labels(n) WHERE l <> 'Base'][0] AS type
+                    WHERE $types IS NULL OR type IN $types
+                    RETURN n.id AS id, type, n.name AS name, n.file_path AS file_path,
+                           n.line_no AS line_no, n.end_line_no AS end_line_no,
+                           [k IN $keys WHERE n[k] IS NOT NULL | [k, n[k]]] AS properties
+                    ORDER BY n.file_path, n.line_no
+                    """,
+                    {"types": node_types, "keys": properties or []}
+                ).data()
+                for node in nodes:
+                    node["properties"] = dict(node["properties"])
+                relationships = session.run(
+                    """
+                    MATCH (a:Base)-[r]->(b:Base)
+                    WHERE a.id IN $ids AND b.id IN $ids
+                    RETURN a.id AS source, b.id AS target, type(r) AS type, properties(r) AS properties
+                    """,
+                    {"ids": [node["id"] for node in nodes]}
+                ).data()
+                return {"nodes": nodes, "relationships": relationships}
+        except Exception as e:
+            logger.error(f"Error fetching graph: {e}")
+            raise
+
+    def find_file_paths(self, path: str) -> List[str]:
+        """Stored paths of the File nodes a user-supplied path refers to
+
+        An exact match wins; otherwise every indexed file whose path ends
+        with ``/<path>`` is returned, so relative paths work.
+
+        Args:
+            path: File path as given, absolute or relative to the indexed root
+
+        Returns:
+            Matching stored paths, sorted; empty when the file is not indexed
+        """
+        relative = path.replace(os.sep, "/")
+        while relative.startswith("./"):
+            relative = relative[2:]
+        suffix = "/" + relative.lstrip("/")
+        try:
+            with self.driver.session(database=self.database) as session:
+                result = session.run(
+                    """
+                    MATCH (f:File)
+                    WHERE f.file_path = $path OR f.file_path ENDS WITH $suffix
+                    RETURN f.file_path AS file_path
+                    ORDER BY file_path
+                    """,
+                    {"path": path, "suffix": suffix}
+                )
+                paths = [record["file_path"] for record in result]
+                return [path] if path in paths else paths
+        except Exception as e:
+            logger.error(f"Error finding file {path}: {e}")
+            raise
+
+    def get_file_symbols(self, file_path: str) -> Dict[str, List[Dict[str, Any]]]:
+        """Fetch the symbols declared in one file and the DEFINES edges into them
+
+        Args:
+            file_path: Stored path of the file
+
+        Returns:
+            Dict with ``nodes`` (id, type, name, line_no, end_line_no,
+            column, end_column, start_byte, end_byte, visibility, doc,
+            complexity, export_type, export_name, field_type) for every node of the file
+            except the File node,
+            and ``defines`` (source, target) for each DEFINES edge ending at
+            one of them
+        """
+        try:
+            with self.driver.session(database=self.database) as session:
+                nodes = session.run(
+                    """
+                    MATCH (n:Base {file_path: $path})
+                    WHERE NOT n:File
+                    RETURN n.id AS id, [l IN labels(n) WHERE l <> 'Base'][0] AS type,
+                           n.name AS name, n.line_no AS line_no, n.end_line_no AS end_line_no,
+                           n.column AS column, n.end_column AS end_column,
+                           n.start_byte AS start_byte, n.end_byte AS end_byte,
+                           n.visibility AS visibility, coalesce(n.doc, n.docstring) AS doc,
+                           n.complexity AS complexity,
+                           n.export_type AS export_type, n.export_name AS export_name,
+                           CASE WHEN n:Field THEN n.type END AS field_type
+                    ORDER BY n.line_no
+                    """,
+                    {"path": file_path}
+                ).data()
+                defines = session.run(
+                    """
+                    MATCH (a:Base)-[:DEFINES]->(b:Base {file_path: $path})
+                    RETURN a.id AS source, b.id AS target
+                    """,
+                    {"path": file_path}
+                ).data()
+                return {"nodes": nodes, "defines": defines}
+        except Exception as e:
+            logger.error(f"Error fetching symbols of {file_path}: {e}")
+            raise
+
     def execute_cypher(self, query: str, parameters: Dict = None):
         """執行Cypher查詢
         
@@ -0,0 +1,569 @@
+"""
+Embedded SQLite storage for the code graph.
+
+Everything lives in one file: a ``nodes`` table (id, type, labels, name,
+file_path, line_no and the full property map as JSON) and an ``edges``
+table (source, target, type and properties as JSON), indexed for the
+lookups the MCP tools make: nodes by name, by file and by type, edges by
+source and by target. The queries mirror Neo4jDatabase record for record,
+including its ordering (nulls last), so tools give the same answers on
+either backend. Raw Cypher is not supported.
+"""
+
+import json
+import logging
+import math
+import os
+import sqlite3
+import threading
+from contextlib import contextmanager
+from typing import Any, Dict, Iterable, Iterator, List, Optional
+
+from src.neo4j_storage.store import RELATED_DIRECTIONS, GraphStore, check_identifier
+
+logger = logging.getLogger(__name__)
+
+SCHEMA = """
+CREATE TABLE IF NOT EXISTS nodes (
+    id TEXT PRIMARY KEY,
+    type TEXT,
+    labels TEXT NOT NULL,
+    name TEXT,
+    file_path TEXT,
+    line_no INTEGER,
+    properties TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS edges (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    source TEXT NOT NULL,
+    target TEXT NOT NULL,
+    type TEXT NOT NULL,
+    properties TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS nodes_name ON nodes(name);
+CREATE INDEX IF NOT EXISTS nodes_file_path ON nodes(file_path);
+CREATE INDEX IF NOT EXISTS nodes_type ON nodes(type);
+CREATE INDEX IF NOT EXISTS edges_source ON edges(source, type);
+CREATE INDEX IF NOT EXISTS edges_target ON edges(target, type);
+"""
+
+# Bound parameters per IN (...) list, below SQLite's limit on old builds
+CHUNK = 500
+
+# Rows fetched at a time by the queries that stream their results
+FETCH_SIZE = 500
+
+
+def _nulls_last(*values):
+    """Sort key ordering like Cypher's ORDER BY: None after every value."""
+    return tuple((value is None, 0 if value is None else value) for value in values)
+
+
+def _node_type(labels: List[str]) -> Optional[str]:
+    return next((label for label in labels if label != "Base"), None)
+
+
+class SQLiteDatabase(GraphStore):
+    """Code graph stored in a local SQLite file."""
+
+    def __init__(self, path: str):
+        """
+        Args:
+            path: Database file, created when missing; ":memory:" keeps the
+                  graph in memory
+        """
+        self.path = path
+        directory = os.path.dirname(os.path.abspath(path)) if path != ":memory:" else None
+        if directory:
+            os.makedirs(directory, exist_ok=True)
+        # One connection shared by every thread; the lock serializes its use
+        self.conn = sqlite3.connect(path, check_same_thread=False, isolation_level=None)
+        self.conn.row_factory = sqlite3.Row
+        self._lock = threading.RLock()
+        self._local = threading.local()
+        with self._lock:
+            self.conn.executescript(SCHEMA)
+        logger.info(f"Opened SQLite graph store: {path}")
+
+    def close(self):
+        with self._lock:
+            self.conn.close()
+        logger.info("Closed SQLite graph store")
+
+    def verify_connection(self) -> bool:
+        try:
+            with self._lock:
+                return self.conn.execute("SELECT 1").fetchone()[0] == 1
+        except sqlite3.Error as e:
+            logger.error(f"Error verifying SQLite graph store: {e}")
+            return False
+
+    @contextmanager
+    def write_batch(self):
+        """Run the writes made in this thread inside the block as one transaction
+
+        Other threads wait until the batch ends. The batch is rolled back if
+        the block raises. Nested batches join the outer one.
+        """
+        if getattr(self._local, "in_batch", False):
+            yield
+            return
+        with self._lock:
+            self.conn.execute("BEGIN")
+            self._local.in_batch = True
+            try:
+                yield
+                self.conn.execute("COMMIT")
+            except Exception:
+                self.conn.execute("ROLLBACK")
+                raise
+            finally:
+                self._local.in_batch = False
+
+    @contextmanager
+    def _writer(self):
+        """The open write_batch transaction of this thread, else a new one"""
+        with self.write_batch():
+            yield self.conn
+
+    def _query(self, sql: str, parameters: Iterable[Any] = ()) -> List[sqlite3.Row]:
+        with self._lock:
+            return self.conn.execute(sql, tuple(parameters)).fetchall()
+
+    def _stream(self, sql: str, parameters: Iterable[Any] = ()) -> Iterator[sqlite3.Row]:
+        """Rows of a query, fetched FETCH_SIZE at a time, so a caller can stop without reading the rest."""
+        with self._lock:
+            cursor = self.conn.execute(sql, tuple(parameters))
+        try:
+            while True:
+                with self._lock:
+                    rows = cursor.fetchmany(FETCH_SIZE)
+                if not rows:
+                    return
+                yield from rows
+        finally:
+            cursor.close()
+
+    def _in(self, sql: str, values: Iterable[Any], parameters: Iterable[Any] = ()) -> List[sqlite3.Row]:
+        """Run ``sql`` whose ``{in}`` placeholder takes ``values``, in chunks."""
+        values, rows = list(values), []
+        for i in range(0, len(values), CHUNK):
+            chunk = values[i:i + CHUNK]
+            rows += self._query(sql.format(**{"in": ",".join("?" * len(chunk))}), [*chunk, *parameters])
+        return rows
+
+    # --- writes ---
+
+    def clear_database(self):
+        with self._writer() as conn:
+            conn.execute("DELETE FROM edges")
+            conn.execute("DELETE FROM nodes")
+        logger.info("Cleared SQLite graph store")
+
+    def batch_create_nodes(self, nodes: List[Dict[str, Any]]):
+        self.upsert_nodes(nodes)
+
+    def batch_create_relationships(self, relationships: List[Dict[str, Any]]):
+        if not relationships:
+            return
+        with self._writer() as conn:
+            conn.executemany(
+                """
+                INSERT INTO edges (source, target, type, properties)
+                SELECT ?, ?, ?, ?
+                WHERE EXISTS (SELECT 1 FROM nodes WHERE id = ?) AND EXISTS (SELECT 1 FROM nodes WHERE id = ?)
+                """,
+                [(r['start_node_id'], r['end_node_id'], r['type'], json.dumps(r.get('properties') or {}),
+                  r['start_node_id'], r['end_node_id']) for r in relationships]
+            )
+        logger.info(f"Processed {len(relationships)} relationships")
+
+    def upsert_nodes(self, nodes: List[Dict[str, Any]]):
+        if not nodes:
+            return
+        rows = []
+        for node in nodes:
+            properties = node['properties']
+            labels = ["Base"] + [label for label in node['labels'] if label != "Base"]
+            rows.append((properties['id'], _node_type(labels), json.dumps(labels), properties.get('name'),
+                         properties.get('file_path'), properties.get('line_no'), json.dumps(properties)))
+        with self._writer() as conn:
+            conn.executemany(
+                "INSERT OR REPLACE INTO nodes (id, type, labels, name, file_path, line_no, properties) "
+                "VALUES (?, ?, ?, ?, ?, ?, ?)",
+                rows
+            )
+        logger.info(f"Upserted {len(nodes)} nodes")
+
+    def delete_nodes(self, node_ids: List[str]):
+        if not node_ids:
+            return
+        ids = list(node_ids)
+        with self._writer() as conn:
+            for i in range(0, len(ids), CHUNK):
+                chunk = ids[i:i + CHUNK]
+                marks = ",".join("?" * len(chunk))
+                conn.execute(f"DELETE FROM edges WHERE source IN ({marks}) OR target IN ({marks})", chunk + chunk)
+                conn.execute(f"DELETE FROM nodes WHERE id IN ({marks})", chunk)
+        logger.info(f"Deleted {len(ids)} nodes")
+
+    def delete_relationships(self, relationships: List[Dict[str, Any]]):
+        if not relationships:
+            return
+        with self._writer() as conn:
+            for rel in relationships:
+                wanted = rel.get('properties') or {}
+                for row in conn.execute(
+                    "SELECT id, properties FROM edges WHERE source = ? AND target = ? AND type = ?",
+                    (rel['start_node_id'], rel['end_node_id'], rel['type'])
+                ).fetchall():
+                    properties = json.loads(row["properties"])
+                    if all(properties.get(k) == v for k, v in wanted.items()):
+                        conn.execute("DELETE FROM edges WHERE id = ?", (row["id"],))
+        logger.info(f"Deleted {len(relationships)} relationships")
+
+    def delete_nodes_by_file(self, file_paths: List[str]):
+        if not file_paths:
+            return
+        ids = [row["id"] for row in self._in("SELECT id FROM nodes WHERE file_path IN ({in})", file_paths)]
+        self.delete_nodes(ids)
+        logger.info(f"Deleted nodes of {len(file_paths)} files")
+
+    def get_file_states(self) -> List[Dict[str, Any]]:
+        states = []
+        for node in self._nodes("type = 'File'"):
+            states.append({key: node.get(key) for key in ("file_path", "content_hash", "mtime_ns", "graph_state")})
+        return states
+
+    def set_file_states(self, states: List[Dict[str, Any]]):
+        if not states:
+            return
+        with self._writer() as conn:
+            for state in states:
+                row = conn.execute("SELECT properties FROM nodes WHERE id = ?", (state["id"],)).fetchone()
+                if row is None:
+                    continue
+                properties = json.loads(row["properties"])
+                properties.update({k: state.get(k) for k in ("content_hash", "mtime_ns", "graph_state")})
+                conn.execute("UPDATE nodes SET properties = ? WHERE id = ?", (json.dumps(properties), state["id"]))
+        logger.info(f"Stored file states for {len(states)} files")
+
+    def set_node_properties(self, updates: List[Dict[str, Any]]):
+        if not updates:
+            return
+        with self._writer() as conn:
+            for update in updates:
+                row = conn.execute("SELECT properties FROM nodes WHERE id = ?", (update["id"],)).fetchone()
+                if row is None:
+                    continue
+                properties = json.loads(row["properties"])
+                properties.update(update["properties"])
+                conn.execute("UPDATE nodes SET properties = ? WHERE id = ?", (json.dumps(properties), update["id"]))
+        logger.info(f"Updated properties of {len(updates)} nodes")
+
+    # --- node access ---
+
+    @staticmethod
+    def _props(row: sqlite3.Row) -> Dict[str, Any]:
+        """Stored properties of a node row, without nulls as Neo4j drops them."""
+        return {k: v for k, v in json.loads(row["properties"]).items() if v is not None}
+
+    def _nodes(self, where: str = "1", parameters: Iterable[Any] = ()) -> List[Dict[str, Any]]:
+        """Properties of the matching nodes, with ``_type`` and ``_labels`` added."""
+        return [dict(self._props(row), _type=row["type"], _labels=json.loads(row["labels"]))
+                for row in self._query(f"SELECT * FROM nodes WHERE {where}", parameters)]
+
+    def _nodes_by_id(self, ids: Iterable[str]) -> Dict[str, Dict[str, Any]]:
+        return {row["id"]: dict(self._props(row), _type=row["type"], _labels=json.loads(row["labels"]))
+                for row in self._in("SELECT * FROM nodes WHERE id IN ({in})", set(ids))}
+
+    @staticmethod
+    def _public(node: Dict[str, Any]) -> Dict[str, Any]:
+        return {k: v for k, v in node.items() if not k.startswith("_")}
+
+    @staticmethod
+    def _summary(node: Dict[str, Any], **extra) -> Dict[str, Any]:
+        summary = {"id": node.get("id"), "name": node.get("name"), "type": node["_type"],
+                   "file_path": node.get("file_path"), "line_no": node.get("line_no")}
+        summary.update(extra)
+        return summary
+
+    def _edges(self, column: str, ids: Iterable[str], types: Optional[Iterable[str]] = None) -> List[Dict[str, Any]]:
+        """Edges whose ``column`` (source or target) is one of ``ids``, optionally of some types."""
+        types = list(types) if types is not None else None
+        sql = f"SELECT * FROM edges WHERE {column} IN ({{in}})"
+        if types is not None:
+            if not types:
+                return []
+            sql += f" AND type IN ({','.join('?' * len(types))})"
+        return [{"id": row["id"], "source": row["source"], "target": row["target"], "type": row["type"],
+                 "properties": json.loads(row["properties"])}
+                for row in self._in(sql, set(ids), types or ())]
+
+    # --- search ---
+
+    def search_code_by_text(self, query: str, limit: int = 10):
+        """Match the query's words against names, docstrings and code, case-insensitively
+
+        A node's score is the number of words it contains, name matches
+        counting double.
+        """
+        words = [w.lower() for w in query.split() if w]
+        if not words:
+            return []
+        results = []
+        for node in self._nodes("type IN ('Function', 'Method', 'Class', 'File')"):
+            name = str(node.get("name") or "").lower()
+            text = " ".join(str(node.get(k) or "") for k in ("code_snippet", "docstring", "doc")).lower()
+            score = sum(2 * (w in name) + (w in text) for w in words)
+            if score:
+                results.append({"node": self._public(node), "score": float(score)})
+        results.sort(key=lambda r: -r["score"])
+        return results[:limit]
+
+    def search_code_by_vector(self, vector: List[float], node_label: str, limit: int = 10):
+        norm = math.sqrt(sum(x * x for x in vector))
+        results = []
+        for node in self._nodes("type = ?", [node_label]):
+            embedding = node.get("embedding")
+            if not embedding or not norm:
+                continue
+            other = math.sqrt(sum(x * x for x in embedding))
+            if other:
+                score = sum(a * b for a, b in zip(vector, embedding)) / (norm * other)
+                results.append({"node": self._public(node), "score": score})
+        results.sort(key=lambda r: -r["score"])
+        return results[:limit]
+
+    # --- queries ---
+
+    def find_nodes_by_name(self, name: str, node_type: Optional[str] = None, limit: int = 10):
+        where, parameters = "name = ?", [name]
+        if node_type:
+            where += " AND EXISTS (SELECT 1 FROM json_each(labels) WHERE value = ?)"
+            parameters.append(node_type)
+        return [self._public(node) for node in self._nodes(f"{where} LIMIT ?", parameters + [limit])]
+
+    def find_related(self, relation_type: str, direction: str, match: Dict[str, Any],
+                     label: Optional[str] = None, other_label: Optional[str] = None,
+                     limit: Optional[int] = None):
+        if direction not in RELATED_DIRECTIONS:
+            raise ValueError(f"Unknown direction: {direction}")
+        where = " AND ".join(f"json_extract(properties, '$.{check_identifier(k, 'property')}') = ?" for k in match)
+        starts = [node for node in self._nodes(where or "1", match.values())
+                  if label is None or label in node["_labels"]]
+        near, far = ("source", "target") if direction == "out" else ("target", "source")
+        edges = self._edges(near, [node["id"] for node in starts], [relation_type])
+        others = self._nodes_by_id(edge[far] for edge in edges)
+        related = []
+        for edge in sorted(edges, key=lambda e: e["id"]):
+            other = others.get(edge[far])
+            if other is not None and (other_label is None or other_label in other["_labels"]):
+                related.append(self._public(other))
+        return related if limit is None else related[:limit]
+
+    def find_nodes_by_symbol(self, symbol: str, limit: int = 20, fuzzy: bool = False):
+        owner, _, member = symbol.rpartition(".")
+        owners = {node["id"] for node in self._nodes("name = ?", [owner])} if owner else set()
+        defined = {edge["target"] for edge in self._edges("source", owners, ["DEFINES"])}
+        matches = []
+        for node in self._nodes():
+            is_file = "File" in node["_labels"]
+            name = node.get("name")
+            if node.get("id") == symbol \
+                    or (name == symbol and not is_file) \
+                    or node.get("qualified_name") == symbol \
+                    or (owner and name == member and node["id"] in defined) \
+                    or (fuzzy and not is_file and name is not None and symbol.lower() in str(name).lower()):
+                matches.append(self._summary(node, visibility=node.get("visibility")))
+        matches.sort(key=lambda n: _nulls_last(n["file_path"], n["line_no"]))
+        return matches[:limit]
+
+    def get_symbols(self, node_types: Optional[List[str]] = None):
+        # Newest first, so the first DEFINES edge into a node names its owner
+        owners = {row["target"]: row["name"] for row in self._query(
+            "SELECT e.target, n.name FROM edges e JOIN nodes n ON n.id = e.source "
+            "WHERE e.type = 'DEFINES' AND n.type != 'File' ORDER BY e.id DESC")}
+        for node in self._nodes("type NOT IN ('File', 'Unresolved') AND name IS NOT NULL"):
+            if node_types is not None and node["_type"] not in node_types:
+                continue
+            yield self._summary(node, visibility=node.get("visibility"), complexity=node.get("complexity"),
+                                doc=node.get("doc", node.get("docstring")), documented=node.get("documented"),
+                                owner=owners.get(node["id"]),
+                                **({"project": node["project"]} if node.get("project") else {}),
+                                **({"deprecated": True} if node.get("deprecated") else {}))
+
+    def get_call_edges(self, node_ids: List[str], direction: str = "callers"):
+        if direction == "callers":
+            near, far = "target", "source"
+        elif direction == "callees":
+            near, far = "source", "target"
+        else:
+            raise ValueError(f"Unknown call direction: {direction}")
+        # The far end of each edge; the ids go in as one JSON array, so a single ordered cursor covers them all
+        other = "caller" if far == "source" else "callee"
+        rows = self._stream(
+            f"SELECT e.source, e.target, e.properties, {other}.id AS id, {other}.name AS name, "
+            f"{other}.type AS type, {other}.file_path AS file_path, {other}.line_no AS line_no, "
+            "caller.file_path AS call_file "
+            "FROM edges e JOIN nodes caller ON caller.id = e.source JOIN nodes callee ON callee.id = e.target "
+            f"WHERE e.type = 'CALLS' AND e.{near} IN (SELECT value FROM json_each(?)) "
+            "ORDER BY e.source, e.target, "
+            "json_extract(e.properties, '$.line_no') IS NULL, json_extract(e.properties, '$.line_no'), "
+            "json_extract(e.properties, '$.call_site') IS NULL, json_extract(e.properties, '$.call_site')",
+            [json.dumps(list(node_ids))])
+        for row in rows:
+            properties = json.loads(row["properties"])
+            yield {
+                "caller_id": row["source"], "callee_id": row["target"],
+                "node": {"id": row["id"], "name": row["name"], "type": row["type"],
+                         "file_path": row["file_path"], "line_no": row["line_no"]},
+                "call_file": row["call_file"],
+                "call_line": properties.get("line_no"), "call_site": properties.get("call_site"),
+                "await_call": properties.get("await_call"),
+            }
+
+    def get_references(self, node_ids: List[str]):
+        edges = self._edges("target", node_ids, ["REFERENCES", "CALLS"])
+        nodes = self._nodes_by_id(e["source"] for e in edges)
+        records = [{
+            "target_id": edge["target"], "relation": edge["type"], "access": edge["properties"].get("access"),
+            "node": self._summary(nodes[edge["source"]]),
+            "line_no": edge["properties"].get("line_no"), "snippet": edge["properties"].get("call_site"),
+        } for edge in edges if edge["source"] in nodes]
+        records.sort(key=lambda r: _nulls_last(r["node"]["file_path"], r["line_no"], r["node"]["id"],
+                                               r["target_id"], r["relation"], r["access"]))
+        yield from records
+
+    def get_usages(self, node_ids: List[str], relation_types: List[str]):
+        edges = self._edges("target", node_ids, relation_types)
+        nodes = self._nodes_by_id(e["source"] for e in edges)
+        records = []
+        for edge in edges:
+            source = nodes.get(edge["source"])
+            if source is None:
+                continue
+            line_no = edge["properties"].get("line_no")
+            records.append({
+                "target_id": edge["target"], "relation": edge["type"], "node": self._summary(source),
+                "line_no": source.get("line_no") if line_no is None else line_no,
+                "column": edge["properties"].get("column"), "snippet": edge["properties"].get("call_site"),
+            })
+        records.sort(key=lambda r: _nulls_last(r["node"]["file_path"], r["line_no"], r["node"]["id"],
+                                               r["target_id"], r["relation"]))
+        yield from records
+
+    def get_implementations(self, node_ids: List[str]):
+        edges = self._edges("target", node_ids, ["IMPLEMENTS"])
+        impl_ids = {e["source"] for e in edges}
+        # Methods defined by each impl and by each trait, and which method implements which
+        impl_defines = self._edges("source", impl_ids, ["DEFINES"])
+        trait_defines = {(e["source"], e["target"]) for e in self._edges("source", set(node_ids), ["DEFINES"])}
+        method_ids = {e["target"] for e in impl_defines}
+        implements = {(e["source"], e["target"]) for e in self._edges("source", method_ids, ["IMPLEMENTS"])}
+        nodes = self._nodes_by_id(impl_ids | method_ids)
+
+        records = []
+        for edge in edges:
+            impl = nodes.get(edge["source"])
+            if impl is None:
+                continue
+            methods = [nodes[d["target"]] for d in impl_defines if d["source"] == edge["source"]
+                       for m, x in implements if m == d["target"] and (edge["target"], x) in trait_defines]
+            methods.sort(key=lambda m: _nulls_last(m.get("line_no")))
+            properties = edge["properties"]
+            line_no = properties.get("line_no")
+            records.append({
+                "target_id": edge["target"], "trait_path": properties.get("trait_path"),
+                "node": self._summary(impl, line_no=impl.get("line_no") if line_no is None else line_no),
+                "methods": [{"id": m.get("id"), "name": m.get("name"), "file_path": m.get("file_path"),
+                             "line_no": m.get("line_no")} for m in methods],
+                "blanket": bool(properties.get("blanket", False)),
+                "for_type": properties.get("for_type"), "bounds": properties.get("bounds"),
+            })
+        records.sort(key=lambda r: _nulls_last(r["node"]["file_path"], r["node"]["line_no"], r["node"]["id"]))
+        return records
+
+    def get_implemented(self, node_ids: List[str]):
+        edges = self._edges("source", node_ids, ["IMPLEMENTS"])
+        nodes = self._nodes_by_id(e["target"] for e in edges)
+        records = [{
+            "source_id": edge["source"], "trait_path": edge["properties"].get("trait_path"),
+            "node": self._summary(nodes[edge["target"]]),
+        } for edge in edges if edge["target"] in nodes]
+        records.sort(key=lambda r: _nulls_last(r["node"]["file_path"], r["node"]["line_no"], r["node"]["id"]))
+        return records
+
+    def find_implementors(self, trait_name: str):
+        base = trait_name.rsplit("::", 1)[-1]
+        edges = [
+            {"id": row["id"], "source": row["source"], "target": row["target"],
+             "properties": json.loads(row["properties"])}
+            for row in self._query("SELECT * FROM edges WHERE type = 'IMPLEMENTS'")
+        ]
+        nodes = self._nodes_by_id([e["source"] for e in edges] + [e["target"] for e in edges])
+        records = []
+        for edge in edges:
+            impl, trait = nodes.get(edge["source"]), nodes.get(edge["target"])
+            if impl is None or trait is None or "Method" in impl["_labels"]:
+                continue
+            path = edge["properties"].get("trait_path")
+            if not (trait.get("name") == trait_name or path == trait_name
+                    or (path is not None and (path.endswith("::" + trait_name) or trait_name.endswith("::" + path)))
+                    or (trait.get("name") == base and "Unresolved" not in trait["_labels"])):
+                continue
+            line_no = edge["properties"].get("line_no")
+            records.append({
+                "node": self._summary(impl, line_no=impl.get("line_no") if line_no is None else line_no,
+                                      end_line_no=impl.get("end_line_no")),
+                "trait": self._summary(trait),
+                "trait_path": path, "blanket": bool(edge["properties"].get("blanket", False)),
+            })
+        records.sort(key=lambda r: _nulls_last(r["node"]["file_path"], r["node"]["line_no"],
+                                               r["node"]["id"], r["trait"]["id"]))
+        return records
+
+    def get_graph(self, node_types: Optional[List[str]] = None, properties: Optional[List[str]] = None):
+        nodes = []
+        for node in self._nodes():
+            if node_types is not None and node["_type"] not in node_types:
+                continue
+            nodes.append({
+                "id": node.get("id"), "type": node["_type"], "name": node.get("name"),
+                "file_path": node.get("file_path"), "line_no": node.get("line_no"),
+                "end_line_no": node.get("end_line_no"),
+                "properties": {k: node[k] for k in properties or () if node.get(k) is not None},
+            })
+        nodes.sort(key=lambda n: _nulls_last(n["file_path"], n["line_no"]))
+        ids = {node["id"] for node in nodes}
+        relationships = [
+            {"source": e["source"], "target": e["target"], "type": e["type"], "properties": e["properties"]}
+            for e in sorted(self._edges("source", ids), key=lambda e: e["id"]) if e["target"] in ids
+        ]
+        return {"nodes": nodes, "relationships": relationships}
+
+    def find_file_paths(self, path: str) -> List[str]:
+        relative = path.replace(os.sep, "/")
+        while relative.startswith("./"):
+            relative = relative[2:]
+        suffix = "/" + relative.lstrip("/")
+        paths = sorted(node["file_path"] for node in self._nodes("type = 'File' AND file_path IS NOT NULL")
+                       if node["file_path"] == path or node["file_path"].endswith(suffix))
+        return [path] if path in paths else paths
+
+    def get_file_symbols(self, file_path: str):
+        keys = ("line_no", "end_line_no", "column", "end_column", "start_byte", "end_byte", "visibility")
+        nodes = []
+        for node in self._nodes("file_path = ?", [file_path]):
+            if "File" in node["_labels"]:
+                continue
+            record = {"id": node.get("id"), "type": node["_type"], "name": node.get("name")}
+            record.update({k: node.get(k) for k in keys})
+            record.update({"doc": node.get("doc", node.get("docstring")), "complexity": node.get("complexity"),
+                           "export_type": node.get("export_type"), "export_name": node.get("export_name"),
+                           "field_type": node.get("type") if node["_type"] == "Field" else None})
+            nodes.append(record)
+        nodes.sort(key=lambda n: _nulls_last(n["line_no"]))
+        defines = [{"source": e["source"], "target": e["target"]}
+                   for e in self._edges("target", [n["id"] for n in nodes], ["DEFINES"])]
+        return {"nodes": nodes, "defines": defines}
@@ -0,0 +1,181 @@
+"""
+Interface shared by the graph storage backends.
+
+Indexing (src/main.py) writes through ``upsert_*``/``batch_create_*`` and
+the ``delete_*`` methods, the MCP server reads through the ``get_*``/
+``find_*``/``search_*`` queries. Neo4jDatabase talks to a Neo4j server;
+SQLiteDatabase keeps the same graph in a single local file, so the server
+can run without one. Both return the same records, so every tool behaves
+the same on either backend. Only raw Cypher (``execute_cypher``) is
+Neo4j-specific.
+
+Nodes are written as ``{"labels": [...], "properties": {...}}`` with an
+``id`` property and labels ``Base`` plus the node type; relationships as
+``{"start_node_id", "end_node_id", "type", "properties"}``.
+"""
+
+import re
+from abc import ABC, abstractmethod
+from contextlib import contextmanager
+from typing import Any, Dict, Iterator, List, Optional
+
+# Values of --storage / STORAGE_BACKEND
+STORAGE_BACKENDS = ("neo4j", "sqlite")
+
+# Database file used by the sqlite backend when no path is given
+DEFAULT_SQLITE_PATH = "codegraph.db"
+
+RELATED_DIRECTIONS = ("out", "in")
+
+_IDENTIFIER = re.compile(r"^[A-Za-z_][A-Za-z0-9_]*$")
+
+
+def check_identifier(value: str, what: str) -> str:
+    """Reject labels, relation types and property keys that are not plain identifiers."""
+    if not _IDENTIFIER.match(value or ""):
+        raise ValueError(f"Invalid {what}: {value!r}")
+    return value
+
+
+class GraphStore(ABC):
+    """Storage for the code graph."""
+
+    @abstractmethod
+    def close(self) -> None:
+        """Release the connection."""
+
+    @abstractmethod
+    def verify_connection(self) -> bool:
+        """Whether the store can be used."""
+
+    @abstractmethod
+    @contextmanager
+    def write_batch(self):
+        """Run the writes made in this thread inside the block as one transaction."""
+        yield
+
+    @abstractmethod
+    def clear_database(self) -> None:
+        """Delete every node and relationship."""
+
+    def create_schema_constraints(self) -> None:
+        """Create constraints and lookup indexes, if the backend needs them."""
+
+    def create_vector_index(self, index_name: str, node_label: str, property_name: str, dimension: int = 1536):
+        """Create a vector index for similarity search, if the backend needs one."""
+
+    def create_full_text_index(self, index_name: str, node_labels: List[str], properties: List[str]):
+        """Create a full-text index for text search, if the backend needs one."""
+
+    @abstractmethod
+    def batch_create_nodes(self, nodes: List[Dict[str, Any]]):
+        """Create nodes."""
+
+    @abstractmethod
+    def batch_create_relationships(self, relationships: List[Dict[str, Any]]):
+        """Create relationships between existing nodes; others are skipped."""
+
+    @abstractmethod
+    def upsert_nodes(self, nodes: List[Dict[str, Any]]):
+        """Create or replace nodes by id."""
+
+    @abstractmethod
+    def delete_nodes(self, node_ids: List[str]):
+        """Delete nodes by id, together with all their relationships."""
+
+    @abstractmethod
+    def delete_relationships(self, relationships: List[Dict[str, Any]]):
+        """Delete the relationships whose given properties all match."""
+
+    @abstractmethod
+    def delete_nodes_by_file(self, file_paths: List[str]):
+        """Delete every node of the given files, with its relationships."""
+
+    @abstractmethod
+    def get_file_states(self) -> List[Dict[str, Any]]:
+        """Indexing record (file_path, content_hash, mtime_ns, graph_state) of every File node."""
+
+    @abstractmethod
+    def set_file_states(self, states: List[Dict[str, Any]]):
+        """Store the indexing record on existing File nodes, by id."""
+
+    @abstractmethod
+    def set_node_properties(self, updates: List[Dict[str, Any]]):
+        """Set some properties of existing nodes ({"id", "properties"}), keeping the others."""
+
+    @abstractmethod
+    def search_code_by_text(self, query: str, limit: int = 10) -> List[Dict[str, Any]]:
+        """Text search over code; records with ``node`` and ``score``."""
+
+    @abstractmethod
+    def search_code_by_vector(self, vector: List[float], node_label: str, limit: int = 10) -> List[Dict[str, Any]]:
+        """Cosine similarity search over embeddings; records with ``node`` and ``score``."""
+
+    @abstractmethod
+    def find_nodes_by_name(self, name: str, node_type: Optional[str] = None, limit: int = 10) -> List[Dict[str, Any]]:
+        """Properties of the nodes with a name, optionally of one type."""
+
+    @abstractmethod
+    def find_related(self, relation_type: str, direction: str, match: Dict[str, Any],
+                     label: Optional[str] = None, other_label: Optional[str] = None,
+                     limit: Optional[int] = None) -> List[Dict[str, Any]]:
+        """
+        Properties of the nodes across one kind of edge.
+
+        Args:
+            relation_type: Relationship type to follow, e.g. CALLS
+            direction: "out" from the matched nodes, "in" into them
+            match: Property values the starting nodes must have
+            label: Label the starting nodes must have
+            other_label: Label the returned nodes must have
+            limit: Maximum number of results
+        """
+
+    @abstractmethod
+    def find_nodes_by_symbol(self, symbol: str, limit: int = 20, fuzzy: bool = False) -> List[Dict[str, Any]]:
+        """Node summaries matching an id, ``Owner.member`` or bare name."""
+
+    @abstractmethod
+    def get_symbols(self, node_types: Optional[List[str]] = None) -> Iterator[Dict[str, Any]]:
+        """Summaries of the named code nodes with the name of their owner, if any, for symbol search."""
+
+    @abstractmethod
+    def get_call_edges(self, node_ids: List[str], direction: str = "callers") -> Iterator[Dict[str, Any]]:
+        """CALLS edges into ("callers") or out of ("callees") a set of nodes."""
+
+    @abstractmethod
+    def get_references(self, node_ids: List[str]) -> Iterator[Dict[str, Any]]:
+        """REFERENCES and CALLS edges pointing at a set of nodes."""
+
+    @abstractmethod
+    def get_usages(self, node_ids: List[str], relation_types: List[str]) -> Iterator[Dict[str, Any]]:
+        """Edges of the given types pointing at a set of nodes."""
+
+    @abstractmethod
+    def get_implementations(self, node_ids: List[str]) -> List[Dict[str, Any]]:
+        """IMPLEMENTS edges pointing at a set of traits or trait methods."""
+
+    @abstractmethod
+    def get_implemented(self, node_ids: List[str]) -> List[Dict[str, Any]]:
+        """IMPLEMENTS edges leaving a set of types or methods."""
+
+    @abstractmethod
+    def find_implementors(self, trait_name: str) -> List[Dict[str, Any]]:
+        """Types implementing a trait or interface given by name or path."""
+
+    @abstractmethod
+    def get_graph(self, node_types: Optional[List[str]] = None,
+                  properties: Optional[List[str]] = None) -> Dict[str, List[Dict[str, Any]]]:
+        """Code nodes and the relationships between them."""
+
+    @abstractmethod
+    def find_file_paths(self, path: str) -> List[str]:
+        """Stored paths of the File nodes a user-supplied path refers to."""
+
+    @abstractmethod
+    def get_file_symbols(self, file_path: str) -> Dict[str, List[Dict[str, Any]]]:
+        """Symbols declared in one file and the DEFINES edges into them."""
+
+    def execute_cypher(self, query: str, parameters: Dict = None):
+        """Run a raw Cypher query; only the neo4j backend supports it."""
+        raise NotImplementedError(f"Cypher queries need the neo4j storage backend, not {type(self).__name__}")
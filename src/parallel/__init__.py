@@ -4,8 +4,16 @@ from src.parallel.pool_manager import (
     ProcessingPoolManager,
     get_processing_pool,
 )
+from src.parallel.parse_worker import (
+    FileParseResult,
+    merge_results,
+    parse_file_task,
+)
 
 __all__ = [
     'ProcessingPoolManager',
     'get_processing_pool',
+    'FileParseResult',
+    'merge_results',
+    'parse_file_task',
 ]
@@ -0,0 +1,169 @@
+"""
+Per-file parse tasks for the parallel indexer.
+
+Each task builds its own parser inside the worker that runs it, so no parser
+is ever shared between threads: tree-sitter parsers are not thread-safe, and
+the two-pass parsers keep per-file indices that must not leak into another
+file's result. A task returns a FileParseResult, which is plain data and
+pickles across process boundaries.
+
+Results are merged in file path order rather than completion order. Node IDs
+only depend on a node's kind, file, name and line, and with a fixed merge
+order the second pass sees the same module index, so a parallel run produces
+exactly the graph a sequential run does.
+"""
+
+import os
+import logging
+from dataclasses import dataclass, field
+from typing import Any, Dict, Iterable, List, Optional, Tuple
+
+from src.ast_parser.diagnostics import SYNTAX_ERROR, ParseError, error_kind, flag_parse_error
+from src.ast_parser.language_detector import detect_language, parsed_extension
+from src.ast_parser.parser import ASTParser, CodeNode, CodeRelation
+from src.ast_parser.positions import assign_spans, read_source
+from src.ast_parser.qualified_names import assign_qualified_names
+from src.ast_parser.testing import mark_tests, tests_relations
+
+logger = logging.getLogger(__name__)
+
+
+@dataclass
+class FileParseResult:
+    """Everything one file contributes before cross-file resolution."""
+    file_path: str
+    nodes: Dict[str, CodeNode] = field(default_factory=dict)
+    relations: List[CodeRelation] = field(default_factory=list)
+    module_definitions: Dict[str, Dict[str, str]] = field(default_factory=dict)
+    pending_imports: List[Dict[str, Any]] = field(default_factory=list)
+    module_to_file: Dict[str, str] = field(default_factory=dict)
+    # Set when the worker failed on this file; nodes then only hold the File node flagged parse_error
+    error: Optional[str] = None
+    # Why it failed, one of the ERROR_KINDS of src.ast_parser.diagnostics
+    error_kind: str = SYNTAX_ERROR
+    # Syntax errors in the file; the nodes are what was recovered around them
+    parse_errors: List[ParseError] = field(default_factory=list)
+
+
+def create_parser(file_path: str, use_ast_grep: bool = False,
+                  ast_grep_languages: Optional[List[str]] = None, ast_grep_fallback: bool = True,
+                  language_options: Optional[Dict[str, Dict[str, Any]]] = None,
+                  language_overrides: Optional[Dict[str, str]] = None):
+    """
+    Fresh parser for one file.
+
+    ``language_options`` are passed to the parser of each language, e.g.
+    ``{"rust": {"index_private": False}}``; ``language_overrides`` parse the
+    files matching a glob as a language, e.g. ``{"*.h": "cpp"}``.
+
+    Returns:
+        MultiLanguageParser in ast-grep mode, otherwise ASTParser or
+        TypeScriptParser by extension; None for unsupported files
+    """
+    if use_ast_grep:
+        from src.ast_parser.multi_parser import MultiLanguageParser
+        return MultiLanguageParser(
+            use_ast_grep=True,
+            ast_grep_languages=ast_grep_languages,
+            ast_grep_fallback=ast_grep_fallback,
+            language_options=language_options,
+            language_overrides=language_overrides
+        )
+
+    ext = parsed_extension(file_path, language_overrides).lower()
+    if ext == '.py':
+        return ASTParser(**(language_options or {}).get('python', {}))
+    if ext in ['.js', '.ts', '.jsx', '.tsx']:
+        from src.ast_parser.typescript_parser import TypeScriptParser
+        return TypeScriptParser()
+    logger.warning(f"Unsupported file extension: {ext} ({file_path})")
+    return None
+
+
+def parse_file_task(file_path: str, use_ast_grep: bool = False,
+                    ast_grep_languages: Optional[List[str]] = None,
+                    ast_grep_fallback: bool = True,
+                    language_options: Optional[Dict[str, Dict[str, Any]]] = None,
+                    language_overrides: Optional[Dict[str, str]] = None) -> FileParseResult:
+    """
+    Parse one file; runs in a pool worker.
+
+    Never raises: an exception while parsing is reported in the result's
+    ``error`` so that one bad file cannot abort the whole run.
+    """
+    try:
+        parser = create_parser(file_path, use_ast_grep, ast_grep_languages, ast_grep_fallback,
+                               language_options, language_overrides)
+        if parser is None:
+            return FileParseResult(file_path)
+        ext = parsed_extension(file_path, language_overrides).lower()
+        if not use_ast_grep and ext != os.path.splitext(file_path)[1].lower():
+            # The legacy TypeScript parser picks its grammar by the extension an override gave
+            source = read_source(file_path)
+            options = {} if ext == '.py' else {"extension": ext}
+            nodes, relations = parser.parse_source(source, file_path, build_index=True, **options)
+        else:
+            nodes, relations = parser.parse_file(file_path, build_index=True)
+            source = read_source(file_path)
+        assign_spans(nodes.values(), source)
+        language = detect_language(file_path, language_overrides)
+        assign_qualified_names(nodes, relations, file_path, language)
+        mark_tests(file_path, nodes, relations, parser.pending_imports, language)
+        parse_errors = _parse_errors(parser, file_path)
+        nodes = dict(nodes)
+        flag_parse_error(file_path, nodes, parse_errors)
+        return FileParseResult(
+            file_path=file_path,
+            nodes=nodes,
+            relations=list(relations),
+            module_definitions=dict(parser.module_definitions),
+            pending_imports=list(parser.pending_imports),
+            module_to_file=dict(parser.module_to_file),
+            parse_errors=parse_errors,
+        )
+    except Exception as e:
+        return failed_result(file_path, e)
+
+
+def failed_result(file_path: str, error: BaseException) -> FileParseResult:
+    """Result of a file the worker failed on, keeping only its File node flagged with the error."""
+    message, kind = f"{type(error).__name__}: {error}", error_kind(error)
+    nodes: Dict[str, CodeNode] = {}
+    flag_parse_error(file_path, nodes, [ParseError(message, kind=kind)])
+    return FileParseResult(file_path, nodes=nodes, error=message, error_kind=kind)
+
+
+def _parse_errors(parser: Any, file_path: str) -> List[ParseError]:
+    """Syntax errors the parser met in file_path; the coordinator keeps them in its report."""
+    report = getattr(parser, "report", None)
+    if report is not None:
+        diagnostics = report.files.get(file_path)
+        return list(diagnostics.errors) if diagnostics else []
+    return list(getattr(parser, "parse_errors", []))
+
+
+def merge_results(results: Iterable[FileParseResult]) -> Tuple[Dict[str, CodeNode], List[CodeRelation]]:
+    """
+    Combine per-file results and resolve cross-file references.
+
+    Failed results only add their flagged File node. The order the results
+    arrive in does not matter; they are merged by file path.
+
+    Returns:
+        All nodes, and the per-file relations followed by the second-pass
+        ones and the TESTS edges drawn from both
+    """
+    resolver = ASTParser()
+    local_relations: List[CodeRelation] = []
+    for result in sorted(results, key=lambda r: r.file_path):
+        resolver.nodes.update(result.nodes)
+        if result.error is not None:
+            continue
+        local_relations.extend(result.relations)
+        resolver.module_definitions.update(result.module_definitions)
+        resolver.pending_imports.extend(result.pending_imports)
+        resolver.module_to_file.update(result.module_to_file)
+
+    resolver._process_pending_imports()
+    relations = local_relations + resolver.relations
+    return resolver.nodes, relations + tests_relations(relations, resolver.nodes)
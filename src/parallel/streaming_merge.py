@@ -0,0 +1,240 @@
+"""
+Memory-bounded merging of per-file parse results, for very large repositories.
+
+merge_results holds every node and relation of a run until cross-file
+references are resolved at the end. StreamingMerge instead hands each
+file's nodes and relations to the caller, to be written to storage, as soon
+as the file is added, and keeps only what the second pass reads:
+
+- a stub of every node: its kind, name, file, lines and the few properties
+  resolution looks at, without code snippets, docs or other properties
+- each file's module definitions and module-to-file entries
+- each file's pending imports, and the relations leaving the file they were
+  parsed from (written once every node is), both spilled to a temporary
+  file once more than ``spill_threshold`` entries are held
+
+finish() then resolves the pending imports file by file in path order,
+reading spilled ones back from disk, with the same index merge_results
+builds, so both produce the same node and edge sets. Resolution also names
+the module of each File node; as the nodes have been written already, those
+names are returned as property updates.
+"""
+
+import logging
+import os
+import pickle
+import tempfile
+from dataclasses import dataclass
+from typing import Any, Callable, Dict, Iterator, List, Optional
+
+from src.ast_parser.parser import ASTParser, CodeNode, CodeRelation
+from src.ast_parser.testing import tests_relations
+from src.parallel.parse_worker import FileParseResult
+
+logger = logging.getLogger(__name__)
+
+# Node properties the second pass reads, TESTS edges included; stubs keep only these
+RESOLUTION_PROPERTIES = ("method_of", "package", "prototype", "signature", "default_args", "is_test")
+
+# Relations the second pass reads back while resolving; others are written and dropped
+RESOLVER_RELATIONS = ("CONTAINS", "DEFINES")
+
+DEFAULT_SPILL_THRESHOLD = 200_000
+
+# Nodes or relations buffered before they are handed to the writer
+DEFAULT_FLUSH_SIZE = 5_000
+
+# Rough size in memory of one pending import entry, to size the spill threshold from a budget
+PENDING_ENTRY_BYTES = 1024
+
+
+def spill_threshold_for(max_memory_mb: int) -> int:
+    """Pending entries held in memory before spilling, using a quarter of the memory budget."""
+    return max(1_000, max_memory_mb * 1024 * 1024 // 4 // PENDING_ENTRY_BYTES)
+
+
+def symbol_stub(node: CodeNode) -> CodeNode:
+    """Copy of a node with only what cross-file resolution needs."""
+    return CodeNode(node.node_id, node.node_type, node.name, node.file_path, node.line_no, node.end_line_no,
+                    properties={key: node.properties[key] for key in RESOLUTION_PROPERTIES
+                                if key in node.properties})
+
+
+@dataclass
+class MergeStats:
+    """What a streaming merge wrote and held."""
+    files: int = 0
+    failed: int = 0
+    nodes: int = 0
+    relations: int = 0
+    pending: int = 0
+    spilled: int = 0
+    deferred: int = 0
+    deferred_spilled: int = 0
+
+
+class StreamingMerge:
+    """Write per-file results as they arrive, then resolve cross-file references."""
+
+    def __init__(self, write_nodes: Callable[[Dict[str, CodeNode]], Any],
+                 write_relations: Callable[[List[CodeRelation]], Any],
+                 spill_threshold: int = DEFAULT_SPILL_THRESHOLD, flush_size: int = DEFAULT_FLUSH_SIZE,
+                 spill_dir: Optional[str] = None):
+        """
+        Args:
+            write_nodes: Stores a batch of nodes by ID
+            write_relations: Stores a batch of relations; called only once
+                both ends of each have been passed to write_nodes
+            spill_threshold: Pending imports and deferred relations held in
+                memory before they are written to a temporary file
+            flush_size: Nodes or relations buffered before a write
+            spill_dir: Directory of the temporary file, the system's by default
+        """
+        self.write_nodes = write_nodes
+        self.write_relations = write_relations
+        self.spill_threshold = spill_threshold
+        self.flush_size = flush_size
+        self.spill_dir = spill_dir
+        self.stats = MergeStats()
+
+        self._stubs: Dict[str, Dict[str, CodeNode]] = {}
+        self._definitions: Dict[str, Dict[str, Dict[str, str]]] = {}
+        self._module_files: Dict[str, Dict[str, str]] = {}
+        # Relations leaving the file they were parsed from, written once every node is;
+        # whole files of them at a time, so TESTS edges drawn per batch are not repeated
+        self._deferred: List[CodeRelation] = []
+        self._deferred_spilled: List[int] = []
+        self._nodes: Dict[str, CodeNode] = {}
+        self._relations: List[CodeRelation] = []
+
+        self._pending: Dict[str, List[Dict[str, Any]]] = {}
+        self._held = 0
+        self._spill = None
+        self._spilled: Dict[str, int] = {}
+
+    def add(self, result: FileParseResult) -> None:
+        """Write one file's nodes and relations and keep what resolving needs; a failed result only writes its File node."""
+        if result.error is not None:
+            self.stats.failed += 1
+            self._nodes.update(result.nodes)
+            return
+        path = result.file_path
+        self.stats.files += 1
+        self._stubs[path] = {node_id: symbol_stub(node) for node_id, node in result.nodes.items()}
+        self._definitions[path] = result.module_definitions
+        self._module_files[path] = result.module_to_file
+
+        self._nodes.update(result.nodes)
+        for relation in result.relations:
+            if relation.source_id in result.nodes and relation.target_id in result.nodes:
+                self._relations.append(relation)
+            else:
+                self._deferred.append(relation)
+                self._held += 1
+                self.stats.deferred += 1
+        self._relations.extend(tests_relations(result.relations, result.nodes))
+        if len(self._nodes) >= self.flush_size or len(self._relations) >= self.flush_size:
+            self._flush()
+
+        if result.pending_imports:
+            self._pending[path] = result.pending_imports
+            self._held += len(result.pending_imports)
+            self.stats.pending += len(result.pending_imports)
+        if self._held > self.spill_threshold:
+            self._spill_held()
+
+    def finish(self) -> Dict[str, str]:
+        """
+        Resolve cross-file references and write the relations found.
+
+        Returns:
+            Module name by File node ID, to set on the written File nodes
+        """
+        self._flush()
+        paths = sorted(self._stubs)
+        resolver = ASTParser()
+        for path in paths:
+            resolver.nodes.update(self._stubs.pop(path))
+            resolver.module_definitions.update(self._definitions.pop(path))
+            resolver.module_to_file.update(self._module_files.pop(path))
+
+        logger.info(f"Resolving {self.stats.pending} cross-file references "
+                    f"({self.stats.spilled} spilled to disk) across {len(paths)} files")
+        resolver._prepare_pending_imports()
+        written = 0
+        try:
+            for deferred in self._deferred_batches():
+                self._relations.extend(deferred + tests_relations(deferred, resolver.nodes))
+                if len(self._relations) >= self.flush_size:
+                    self._flush()
+            for path in paths:
+                resolver._resolve_pending_imports(self._pending_of(path))
+                if len(resolver.relations) - written >= self.flush_size:
+                    written = self._take_resolved(resolver, written)
+            self._take_resolved(resolver, written)
+        finally:
+            self._close_spill()
+        self._flush()
+        return {node_id: node.properties["module_name"] for node_id, node in resolver.nodes.items()
+                if node.node_type == "File" and "module_name" in node.properties}
+
+    def _take_resolved(self, resolver: ASTParser, written: int) -> int:
+        """Write the relations resolved since the last call; keep those resolving reads back."""
+        fresh = resolver.relations[written:]
+        self._relations.extend(fresh + tests_relations(fresh, resolver.nodes))
+        self._flush()
+        resolver.relations[written:] = [r for r in fresh if r.relation_type in RESOLVER_RELATIONS]
+        return len(resolver.relations)
+
+    def _flush(self) -> None:
+        # Nodes first, so that both ends of every relation exist when it is written
+        if self._nodes:
+            self.write_nodes(self._nodes)
+            self.stats.nodes += len(self._nodes)
+            self._nodes = {}
+        if self._relations:
+            self.write_relations(self._relations)
+            self.stats.relations += len(self._relations)
+            self._relations = []
+
+    def _spill_held(self) -> None:
+        if self._spill is None:
+            self._spill = tempfile.TemporaryFile(prefix="graph-mcp-pending-", dir=self.spill_dir)
+        self._spill.seek(0, os.SEEK_END)
+        pending = 0
+        for path, entries in self._pending.items():
+            self._spilled[path] = self._spill.tell()
+            pickle.dump(entries, self._spill, protocol=pickle.HIGHEST_PROTOCOL)
+            pending += len(entries)
+        if self._deferred:
+            self._deferred_spilled.append(self._spill.tell())
+            pickle.dump(self._deferred, self._spill, protocol=pickle.HIGHEST_PROTOCOL)
+        logger.debug(f"Spilled {pending} pending cross-file references and {len(self._deferred)} "
+                     f"deferred relations to disk")
+        self.stats.spilled += pending
+        self.stats.deferred_spilled += len(self._deferred)
+        self._pending, self._deferred, self._held = {}, [], 0
+
+    def _deferred_batches(self) -> Iterator[List[CodeRelation]]:
+        """The deferred relations, spilled batches first, each batch holding whole files."""
+        for offset in self._deferred_spilled:
+            self._spill.seek(offset)
+            yield pickle.load(self._spill)
+        if self._deferred:
+            yield self._deferred
+        self._deferred, self._deferred_spilled = [], []
+
+    def _pending_of(self, path: str) -> List[Dict[str, Any]]:
+        if path in self._pending:
+            return self._pending.pop(path)
+        if path in self._spilled:
+            self._spill.seek(self._spilled.pop(path))
+            return pickle.load(self._spill)
+        return []
+
+    def _close_spill(self) -> None:
+        if self._spill is not None:
+            self._spill.close()
+            self._spill = None
+        self._pending, self._spilled, self._held = {}, {}, 0
+        self._deferred, self._deferred_spilled = [], []
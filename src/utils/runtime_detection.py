@@ -98,6 +98,23 @@ def get_optimal_worker_count(max_workers: Optional[int] = None) -> int:
     return min(cpu_count, 8)
 
 
+def peak_memory_mb() -> Optional[float]:
+    """
+    Peak resident memory of this process so far, in megabytes.
+    
+    Returns:
+        Optional[float]: The peak RSS, or None where the platform cannot
+        report it (Windows has no ``resource`` module)
+    """
+    try:
+        import resource
+    except ImportError:
+        return None
+    peak = resource.getrusage(resource.RUSAGE_SELF).ru_maxrss
+    # Linux reports kilobytes, macOS bytes
+    return peak / (1024 * 1024) if sys.platform == "darwin" else peak / 1024
+
+
 def get_runtime_info() -> dict:
     """
     Get comprehensive runtime information for logging and debugging.
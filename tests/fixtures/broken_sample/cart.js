@@ -0,0 +1,16 @@
+// checkout() has a deliberately broken body.
+
+export function total(items) {
+  return items.reduce((sum, item) => sum + item.price, 0);
+}
+
+export function checkout(items) {
+  const paid = total(items) +;
+  return paid;
+}
+
+export class Cart {
+  constructor() {
+    this.items = [];
+  }
+}
@@ -0,0 +1,23 @@
+"""Inventory helpers; restock() has a deliberately broken body."""
+
+LOW_STOCK = 5
+
+
+class Item:
+    def __init__(self, name, count):
+        self.name = name
+        self.count = count
+
+
+def is_low(item):
+    return item.count < LOW_STOCK
+
+
+def restock(item, amount):
+    if amount >:
+        item.count += amount
+    return item
+
+
+def report(items):
+    return [item.name for item in items if is_low(item)]
@@ -0,0 +1,18 @@
+#include <stdio.h>
+#include "shapes.h"
+
+static double width(const struct rect *r)
+{
+    return r->sides[1] - r->sides[0];
+}
+
+double area(const struct rect *r)
+{
+    return width(r) * SQUARE(1.0);
+}
+
+int main(void)
+{
+    printf("%f\n", area(0));
+    return 0;
+}
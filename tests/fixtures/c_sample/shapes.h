@@ -0,0 +1,27 @@
+#ifndef SHAPES_H
+#define SHAPES_H
+
+#define SQUARE(x) ((x) * (x))
+
+typedef struct {
+    double x, y;
+} Point;
+
+struct rect {
+    Point origin;
+    char *label;
+    int sides[4];
+};
+
+union number {
+    int i;
+    double d;
+};
+
+enum color { RED, GREEN = 2, BLUE };
+
+typedef int (*compare_fn)(const void *, const void *);
+
+double area(const struct rect *r);
+
+#endif
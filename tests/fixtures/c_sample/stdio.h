@@ -0,0 +1,23 @@
+/* Minimal mock of the C standard library's stdio.h */
+#ifndef _STDIO_H
+#define _STDIO_H
+
+#include <stddef.h>
+
+#define EOF (-1)
+#define BUFSIZ 8192
+
+typedef struct _IO_FILE FILE;
+typedef long fpos_t;
+
+extern FILE *stdin;
+extern FILE *stdout;
+
+int printf(const char *format, ...);
+int fprintf(FILE *stream, const char *format, ...);
+int fclose(FILE *stream);
+FILE *fopen(const char *path, const char *mode);
+char *fgets(char *s, int size, FILE *stream);
+int (*handler)(int);
+
+#endif /* _STDIO_H */
@@ -0,0 +1,34 @@
+#include "geometry.h"
+
+#include <cmath>
+
+namespace geo {
+
+Circle::Circle(double radius) : radius_(radius) {}
+
+double Circle::area() const
+{
+    return M_PI * std::pow(radius_, 2);
+}
+
+std::string Circle::describe(const std::string &prefix) const
+{
+    return prefix + " of area " + std::to_string(area());
+}
+
+int add(int a, int b)
+{
+    return a + b;
+}
+
+int add(int a, int b, int c)
+{
+    return add(a, b) + c;
+}
+
+double add(double a, double b)
+{
+    return a + b;
+}
+
+}  // namespace geo
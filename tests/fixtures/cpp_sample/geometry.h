@@ -0,0 +1,24 @@
+#ifndef GEOMETRY_H
+#define GEOMETRY_H
+
+#include <string>
+
+namespace geo {
+
+class Circle {
+public:
+    explicit Circle(double radius);
+    double area() const;
+    std::string describe(const std::string &prefix = "circle") const;
+
+private:
+    double radius_;
+};
+
+int add(int a, int b);
+int add(int a, int b, int c);
+double add(double a, double b);
+
+}  // namespace geo
+
+#endif
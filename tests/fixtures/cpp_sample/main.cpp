@@ -0,0 +1,9 @@
+#include <iostream>
+#include "geometry.h"
+
+int main()
+{
+    geo::Circle circle(2.0);
+    std::cout << circle.describe() << std::endl;
+    return geo::add(1, 2, 3) == 6 ? 0 : 1;
+}
@@ -0,0 +1,6 @@
+"""Billing records each charge in the ledger."""
+from ledger import record
+
+
+def charge(order_id):
+    return record(order_id)
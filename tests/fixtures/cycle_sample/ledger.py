@@ -0,0 +1,6 @@
+"""The ledger looks totals up on the orders, closing the cycle."""
+from orders import total
+
+
+def record(order_id):
+    return total(order_id)
@@ -0,0 +1,10 @@
+"""Orders are billed when they are placed."""
+from billing import charge
+
+
+def place(order_id):
+    return charge(order_id)
+
+
+def total(order_id):
+    return order_id * 10
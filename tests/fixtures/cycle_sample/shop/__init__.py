@@ -0,0 +1,2 @@
+"""The shop package re-exports its cart."""
+from .cart import checkout
@@ -0,0 +1,10 @@
+"""The cart imports the package, which re-exports this module's own checkout."""
+from . import checkout
+
+
+def checkout(items):
+    return len(items)
+
+
+def retry(items):
+    return checkout(items)
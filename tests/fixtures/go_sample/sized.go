@@ -0,0 +1,16 @@
+// Interfaces satisfied (or not) by Stack, whose methods live in stack_ops.go
+package stack
+
+type Sized interface {
+	Len() int
+}
+
+// Drainer needs a method Stack does not have
+type Drainer interface {
+	Len() int
+	Drain() int
+}
+
+func Size(s Sized) int {
+	return clamp(s.Len())
+}
@@ -0,0 +1,11 @@
+// Generic type whose methods live in another file of the package
+package stack
+
+type Stack[T any] struct {
+	items []T
+}
+
+var (
+	ErrEmpty    = "stack is empty"
+	minCap, maxCap int = 4, 1024
+)
@@ -0,0 +1,26 @@
+// Methods on a receiver type declared in stack.go
+package stack
+
+func (s *Stack[T]) Push(item T) {
+	s.items = append(s.items, item)
+}
+
+func (s Stack[T]) Len() int {
+	return len(s.items)
+}
+
+func (s *Stack[T]) Pop() (item T, ok bool) {
+	if s.Len() == 0 {
+		return item, false
+	}
+	item = s.items[len(s.items)-1]
+	s.items = s.items[:len(s.items)-1]
+	return item, true
+}
+
+func clamp(n int) int {
+	if n > maxCap {
+		return maxCap
+	}
+	return n
+}
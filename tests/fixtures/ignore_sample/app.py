@@ -0,0 +1,9 @@
+from generated.schema import build
+from util import helper
+from scratch import notes
+
+
+def main():
+    helper()
+    notes()
+    return build()
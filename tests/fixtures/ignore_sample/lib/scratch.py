@@ -0,0 +1,2 @@
+def draft():
+    return None
@@ -0,0 +1,11 @@
+package com.example.model;
+
+public abstract class Animal {
+    protected String name;
+
+    protected Animal(String name) {
+        this.name = name;
+    }
+
+    public abstract String sound();
+}
@@ -0,0 +1,52 @@
+package com.example.model;
+
+import java.util.Comparator;
+
+public class Dog extends Animal implements Pet, Comparable<Dog> {
+    public static final Comparator<Dog> BY_NAME = new Comparator<Dog>() {
+        @Override
+        public int compare(Dog a, Dog b) {
+            return a.getName().compareTo(b.getName());
+        }
+    };
+
+    private final String owner;
+
+    public Dog(String name, String owner) {
+        super(name);
+        this.owner = owner;
+    }
+
+    @Override
+    public String sound() {
+        return "Woof";
+    }
+
+    @Override
+    public String getName() {
+        return name;
+    }
+
+    @Override
+    public String owner() {
+        return owner;
+    }
+
+    @Override
+    public int compareTo(Dog other) {
+        return BY_NAME.compare(this, other);
+    }
+
+    public static class Builder {
+        private String name;
+
+        public Builder name(String name) {
+            this.name = name;
+            return this;
+        }
+
+        public Dog build() {
+            return new Dog(name, "nobody");
+        }
+    }
+}
@@ -0,0 +1,8 @@
+package com.example.model;
+
+/**
+ * Anything with a name.
+ */
+public interface Named {
+    String getName();
+}
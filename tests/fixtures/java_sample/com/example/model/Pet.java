@@ -0,0 +1,5 @@
+package com.example.model;
+
+public interface Pet extends Named {
+    String owner();
+}
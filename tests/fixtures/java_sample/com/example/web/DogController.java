@@ -0,0 +1,38 @@
+package com.example.web;
+
+import com.example.model.Dog;
+import com.example.model.*;
+import static com.example.model.Dog.BY_NAME;
+import java.util.List;
+
+@RestController
+@RequestMapping("/dogs")
+public class DogController {
+    @Autowired
+    private DogService service;
+
+    @GetMapping("/{name}")
+    public Dog find(@PathVariable String name) {
+        return service.find(name);
+    }
+
+    List<Dog> sorted(List<Dog> dogs) {
+        dogs.sort(BY_NAME);
+        Runnable log = new Runnable() {
+            public void run() {
+                System.out.println(dogs.size());
+            }
+        };
+        log.run();
+        return dogs;
+    }
+
+    enum Status implements Named {
+        ACTIVE, RETIRED;
+
+        @Override
+        public String getName() {
+            return name().toLowerCase();
+        }
+    }
+}
@@ -0,0 +1,15 @@
+package com.example.web;
+
+import com.example.model.Dog;
+import com.example.model.Named;
+
+public interface DogService {
+    Dog find(String name);
+}
+
+record DogView(String name, String owner) implements Named {
+    @Override
+    public String getName() {
+        return name;
+    }
+}
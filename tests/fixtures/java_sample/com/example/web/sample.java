@@ -0,0 +1,45 @@
+package com.example.web;
+
+import com.example.model.Dog;
+import java.util.ArrayList;
+import java.util.List;
+
+class Shelter {
+    private final List<Dog> dogs = new ArrayList<>();
+    private final DogService service;
+
+    Shelter(DogService service) {
+        this.service = service;
+    }
+
+    /**
+     * Finds a dog by name; use adopt instead.
+     */
+    @Deprecated
+    Dog lookup(String name) {
+        return this.service.find(name);
+    }
+
+    Dog adopt(String name) {
+        Dog dog = lookup(name);
+        dogs.remove(dog);
+        return dog;
+    }
+
+    Runnable reminder(String name) {
+        return new Runnable() {
+            @Override
+            public void run() {
+                Intake.record(adopt(name));
+            }
+        };
+    }
+
+    @Deprecated
+    static class Intake {
+        static String record(Dog dog) {
+            var builder = new Dog.Builder();
+            return builder.name(dog.getName()).build().sound();
+        }
+    }
+}
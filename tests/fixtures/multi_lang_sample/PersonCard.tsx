@@ -0,0 +1,24 @@
+// Simple TSX test file
+import React from "react";
+import { Person, greet as sayHello } from "./people";
+
+export * from "./people";
+export { Role } from "./people";
+
+interface PersonCardProps {
+    person: Person;
+}
+
+export function PersonCard({ person }: PersonCardProps) {
+    return (
+        <div className="person-card" onClick={() => sayHello(person.getName())}>
+            {person.getName()}
+        </div>
+    );
+}
+
+export const PersonBadge = ({ person }: PersonCardProps) => <span>{person.getAge()}</span>;
+
+export const formatAge = (age: number): string => `${age} years`;
+
+export default PersonCard;
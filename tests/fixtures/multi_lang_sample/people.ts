@@ -0,0 +1,48 @@
+// Simple TypeScript test file
+import { EventEmitter } from "events";
+
+export interface Named {
+    name: string;
+    describe(): string;
+}
+
+export type PersonId = string | number;
+
+export const enum Role {
+    Admin,
+    Guest = "guest",
+}
+
+export abstract class Entity {
+    abstract describe(): string;
+}
+
+export class Person extends Entity implements Named {
+    constructor(public name: string, public age: number, public role: Role = Role.Guest) {
+        super();
+    }
+
+    getName(): string {
+        return this.name;
+    }
+
+    setName(name: string): void {
+        this.name = name;
+    }
+
+    getAge(): number {
+        return this.age;
+    }
+
+    describe(): string {
+        return `${this.name} (${this.age})`;
+    }
+}
+
+export function greet(name: string): void {
+    console.log(`Hello, ${name}!`);
+}
+
+export const add = (a: number, b: number): number => a + b;
+
+export const events = new EventEmitter();
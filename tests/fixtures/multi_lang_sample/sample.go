@@ -5,11 +5,21 @@ import (
 	"fmt"
 )
 
+// DefaultName is used when a person has no name
+var DefaultName = "stranger"
+
+const MaxAge = 150
+
 type Person struct {
 	Name string
 	Age  int
 }
 
+// Greeter is implemented by anything that can greet
+type Greeter interface {
+	Greet() string
+}
+
 func NewPerson(name string, age int) *Person {
 	return &Person{
 		Name: name,
@@ -29,6 +39,11 @@ func (p *Person) GetAge() int {
 	return p.Age
 }
 
+// Greet makes Person satisfy Greeter without naming it
+func (p Person) Greet() string {
+	return "Hello, " + p.GetName()
+}
+
 func Greet(name string) {
 	fmt.Printf("Hello, %s!\n", name)
 }
@@ -36,3 +51,15 @@ func Greet(name string) {
 func Add(a, b int) int {
 	return a + b
 }
+
+// Divide returns several unnamed results
+func Divide(a, b int) (int, int) {
+	return a / b, a % b
+}
+
+// Split returns named results
+func Split(sum int) (x, y int, err error) {
+	x = sum * 4 / 9
+	y = Add(sum, -x)
+	return
+}
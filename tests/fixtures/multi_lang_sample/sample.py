@@ -0,0 +1,31 @@
+# Simple Python test file
+import os
+from typing import Optional
+
+
+class Person:
+    def __init__(self, name: str, age: int):
+        self.name = name
+        self.age = age
+
+    def get_name(self) -> str:
+        return self.name
+
+    def set_name(self, name: str) -> None:
+        self.name = name
+
+    def get_age(self) -> int:
+        return self.age
+
+
+def greet(name: str) -> None:
+    print(f"Hello, {name}!")
+
+
+def add(a: int, b: int) -> int:
+    return a + b
+
+
+def find_home(person: Optional[Person] = None) -> str:
+    greet(person.get_name() if person else "stranger")
+    return os.path.expanduser("~")
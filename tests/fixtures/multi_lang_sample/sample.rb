@@ -0,0 +1,28 @@
+# Simple Ruby test file
+require "json"
+
+class Person
+  attr_reader :name, :age
+
+  def initialize(name, age = 0)
+    @name = name
+    @age = age
+  end
+
+  def self.from_json(text)
+    data = JSON.parse(text)
+    new(data["name"], data["age"])
+  end
+
+  def greet
+    "Hello, #{name}"
+  end
+
+  def adult?
+    age >= 18 && !name.empty?
+  end
+end
+
+def add(a, b)
+  a + b
+end
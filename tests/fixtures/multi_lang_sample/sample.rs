@@ -1,6 +1,6 @@
 // Simple Rust test file
 use std::fmt;
-
+#[derive(Debug, Clone)]
 pub struct Person {
     name: String,
     age: u32,
@@ -0,0 +1,41 @@
+// TypeScript module mixing ES imports, CommonJS require and re-exports of people.ts
+import { Person, Role } from "./people";
+import people = require("./people");
+import * as path from "path";
+
+const { greet } = require("./people");
+const adder = require("./people").add;
+
+export { Named, PersonId } from "./people";
+
+export interface Team {
+    name: string;
+    members: Person[];
+}
+
+export type Roster = Map<string, Team>;
+
+export class TeamBuilder {
+    private members: Person[] = [];
+
+    add(person: Person): TeamBuilder {
+        this.members.push(person);
+        return this;
+    }
+
+    build(name: string): Team {
+        return { name, members: this.members };
+    }
+}
+
+export function welcome(team: Team): void {
+    team.members.forEach((member) => greet(member.getName()));
+}
+
+export const headcount = (team: Team): number => adder(team.members.length, 0);
+
+export const admins = (team: Team): Person[] => team.members.filter((m) => m.role === Role.Admin);
+
+export const fixtureName = path.basename(__filename);
+
+export const everyone = people;
@@ -0,0 +1,19 @@
+#ifndef CODEC_BUFFER_H
+#define CODEC_BUFFER_H
+
+#include <stddef.h>
+
+#define BUFFER_MAX 4096
+
+typedef struct buffer {
+    char *data;
+    size_t len;
+} buffer_t;
+
+enum buffer_mode { BUFFER_COPY, BUFFER_BORROW };
+
+int buffer_write(buffer_t *buf, const char *data, size_t len);
+void buffer_reset(buffer_t *buf);
+size_t buffer_read(buffer_t *buf, char *out);
+
+#endif
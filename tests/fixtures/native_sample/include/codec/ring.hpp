@@ -0,0 +1,19 @@
+#pragma once
+
+#include "codec/buffer.h"
+
+namespace codec {
+
+template <typename T, int N>
+class Ring {
+public:
+    bool push(const T &value);
+    int size() const { return count; }
+private:
+    T items[N];
+    int count = 0;
+};
+
+int drain(Ring<char, 8> &ring, buffer_t *out);
+
+}  // namespace codec
@@ -0,0 +1,26 @@
+#include <string.h>
+#include "codec/buffer.h"
+#include "buffer.h"
+
+int buffer_write(buffer_t *buf, const char *data, size_t len)
+{
+    if (buf->len + len > BUFFER_MAX)
+        return -1;
+    memcpy(buf->data + buf->len, data, len);
+    buf->len += len;
+    return 0;
+}
+
+void buffer_reset(buffer_t *buf)
+{
+    buf->len = 0;
+}
+
+/* Takes one more parameter than the header declares: not the same function */
+size_t buffer_read(buffer_t *buf, char *out, size_t n)
+{
+    size_t count = buf->len < n ? buf->len : n;
+    memcpy(out, buf->data, count);
+    buffer_reset(buf);
+    return count;
+}
@@ -0,0 +1,12 @@
+#ifndef BUFFER_INTERNAL_H
+#define BUFFER_INTERNAL_H
+
+#include "codec/buffer.h"
+
+#if defined(_WIN32)
+#define BUFFER_ALIGN 8
+#else
+#define BUFFER_ALIGN 16
+#endif
+
+#endif
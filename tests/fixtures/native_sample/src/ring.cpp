@@ -0,0 +1,11 @@
+#include "codec/ring.hpp"
+
+namespace codec {
+
+int drain(Ring<char, 8> &ring, buffer_t *out)
+{
+    buffer_reset(out);
+    return ring.size();
+}
+
+}  // namespace codec
@@ -0,0 +1,10 @@
+"""Shapes the drawing modules build on."""
+
+
+class Shape:
+    def area(self):
+        return 0.0
+
+
+class Named:
+    name = "shape"
@@ -0,0 +1 @@
+"""Polygons built on the shapes of base."""
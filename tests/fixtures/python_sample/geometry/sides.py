@@ -0,0 +1,2 @@
+def total(count, length):
+    return count * length
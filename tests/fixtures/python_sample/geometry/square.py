@@ -0,0 +1,15 @@
+from .polygon import Polygon
+from ..base import Named as Label
+
+
+class Square(Polygon, Label):
+    def __init__(self):
+        super().__init__(4)
+
+    @staticmethod
+    def corners():
+        return 4
+
+    @classmethod
+    def unit(cls):
+        return cls()
@@ -0,0 +1,53 @@
+import math
+import base
+from base import Named
+from functools import cached_property, wraps
+
+
+def traced(func):
+    """Print the name of func on every call."""
+    @wraps(func)
+    def wrapper(*args, **kwargs):
+        print(func.__name__)
+        return func(*args, **kwargs)
+    return wrapper
+
+
+class Circle(base.Shape, Named):
+    def __init__(self, radius):
+        self._radius = radius
+
+    @property
+    def radius(self):
+        return self._radius
+
+    @radius.setter
+    def radius(self, value):
+        self._radius = value
+
+    @cached_property
+    def diameter(self):
+        return 2 * self._radius
+
+    @staticmethod
+    def unit():
+        return Circle(1)
+
+    @classmethod
+    def from_diameter(cls, diameter):
+        return cls(diameter / 2)
+
+    @traced
+    def area(self):
+        return math.pi * self._radius ** 2
+
+    async def render(self):
+        def outline():
+            return describe(self)
+        return outline()
+
+
+def describe(shape):
+    def label():
+        return type(shape).__name__
+    return label()
@@ -0,0 +1,3 @@
+class Category < ApplicationRecord
+  has_and_belongs_to_many :posts
+end
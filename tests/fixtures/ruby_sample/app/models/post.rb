@@ -0,0 +1,21 @@
+require_relative "application_record"
+require "slugs/formatter"
+
+class Post < ApplicationRecord
+  belongs_to :user
+  belongs_to :author, class_name: "User"
+  has_many :comments, as: :commentable
+  has_and_belongs_to_many :categories
+
+  def self.published
+    where(published: true)
+  end
+
+  def slug
+    Slugs::Formatter.format(title)
+  end
+
+  private def draft?
+    !published
+  end
+end
@@ -0,0 +1,24 @@
+require "digest"
+
+class User < ApplicationRecord
+  has_many :posts, dependent: :destroy
+  has_many :comments
+  has_one :profile
+  has_many :categories, through: :posts
+
+  class << self
+    def by_email(email)
+      find_by(email: email)
+    end
+  end
+
+  def gravatar
+    Digest::MD5.hexdigest(email)
+  end
+
+  private
+
+  def normalize_email
+    self.email = email.downcase if email
+  end
+end
@@ -0,0 +1,7 @@
+module Slugs
+  class Formatter
+    def self.format(title)
+      title.to_s.downcase.gsub(/[^a-z0-9]+/, "-")
+    end
+  end
+end
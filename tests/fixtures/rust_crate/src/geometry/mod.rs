@@ -0,0 +1,8 @@
+// crate::geometry, declared with `mod geometry;` in lib.rs
+pub mod shapes;
+
+use super::person::{self, Person, adult as is_adult};
+
+pub fn can_vote(age: u32) -> bool {
+    is_adult(age) && person::adult(age)
+}
@@ -0,0 +1,18 @@
+// crate::geometry::shapes, with an inline module and a missing item
+use self::corners::{Corner, Missing};
+
+pub mod corners {
+    pub struct Corner;
+}
+
+pub struct Square {
+    pub side: f64,
+}
+
+pub fn perimeter(square: &Square) -> f64 {
+    square.side * 4.0
+}
+
+pub fn voting_age(age: u32) -> bool {
+    crate::geometry::can_vote(age)
+}
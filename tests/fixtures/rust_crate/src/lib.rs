@@ -0,0 +1,19 @@
+// Crate root: module declarations, a glob re-export, a rename and a std import
+mod geometry;
+mod person;
+
+pub use geometry::shapes::*;
+use crate::person::Person as Human;
+use std::fmt;
+
+pub fn describe(human: &Human) -> String {
+    human.name.clone()
+}
+
+pub fn fence(square: &Square) -> f64 {
+    perimeter(square)
+}
+
+pub fn render(human: &Human) -> String {
+    fmt::format(format_args!("{}", human.name))
+}
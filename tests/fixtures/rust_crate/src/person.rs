@@ -0,0 +1,8 @@
+// Leaf module: crate::person
+pub struct Person {
+    pub name: String,
+}
+
+pub fn adult(age: u32) -> bool {
+    age >= 18
+}
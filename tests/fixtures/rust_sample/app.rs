@@ -0,0 +1,16 @@
+// Calls to functions and associated functions, local and across files
+use crate::shapes::{Circle, Shape};
+
+fn total_area(shapes: &[Circle]) -> f64 {
+    shapes.iter().map(|c| c.area()).sum()
+}
+
+pub fn run() -> f64 {
+    let circle = Circle::new(2.0);
+    let scale = |x: f64| helper(x);
+    scale(circle.diameter()) + total_area(&[circle])
+}
+
+fn helper(x: f64) -> f64 {
+    x * 2.0
+}
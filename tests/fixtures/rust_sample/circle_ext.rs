@@ -0,0 +1,20 @@
+// Inherent impl for a struct declared in another file
+use crate::shapes::Circle;
+
+impl Circle {
+    pub fn new(radius: f64) -> Self {
+        Circle { radius }
+    }
+
+    pub fn unit() -> Self {
+        Self::new(1.0)
+    }
+
+    pub fn diameter(&self) -> f64 {
+        self.radius * 2.0
+    }
+
+    pub fn circumference(&self) -> f64 {
+        self.diameter() * 3.14159
+    }
+}
@@ -0,0 +1,18 @@
+// Free function and a struct whose methods are called from welcome.rs
+pub struct Greeter {
+    pub punctuation: char,
+}
+
+impl Greeter {
+    pub fn new() -> Self {
+        Greeter { punctuation: '!' }
+    }
+
+    pub fn salute(&self, name: &str) -> String {
+        format!("Hi, {}{}", name, self.punctuation)
+    }
+}
+
+pub fn greet(name: &str) -> String {
+    format!("Hello, {}!", name)
+}
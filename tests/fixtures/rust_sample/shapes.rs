@@ -0,0 +1,26 @@
+// Trait declaration with an in-file implementation
+use std::fmt;
+
+pub trait Shape {
+    fn area(&self) -> f64;
+
+    fn describe(&self) -> String {
+        format!("shape with area {}", self.area())
+    }
+}
+
+pub struct Circle {
+    pub radius: f64,
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        3.14159 * self.radius * self.radius
+    }
+}
+
+impl fmt::Display for Circle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Circle({})", self.radius)
+    }
+}
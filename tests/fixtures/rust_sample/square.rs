@@ -0,0 +1,18 @@
+// Local struct implementing a trait declared in another file
+use crate::shapes::Shape;
+
+pub struct Square {
+    side: f64,
+}
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.side * self.side
+    }
+}
+
+impl Square {
+    pub fn grow(&mut self, by: f64) {
+        self.side += by;
+    }
+}
@@ -0,0 +1,14 @@
+// Calls through a module path, on local variables and into std
+use crate::greeting::{self, Greeter};
+
+pub fn welcome(name: &str) -> String {
+    let greeter = Greeter::new();
+    let owned = String::from(name);
+    let first = greeting::greet(&owned);
+    let second = greeter.salute(name);
+    first + &second + &owned.to_uppercase()
+}
+
+pub fn welcome_back(greeter: &Greeter, name: &str) -> String {
+    greeter.salute(name)
+}
@@ -0,0 +1,19 @@
+// A struct implementing both traits of traits.rs and deriving two std ones
+use crate::traits::{Named, Shape};
+
+#[derive(Debug, Clone)]
+pub struct Circle {
+    pub radius: f64,
+}
+
+impl Named for Circle {
+    fn name(&self) -> String {
+        String::from("circle")
+    }
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        3.14159 * self.radius * self.radius
+    }
+}
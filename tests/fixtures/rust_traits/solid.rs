@@ -0,0 +1,6 @@
+// A trait extending a supertrait declared in another file
+use crate::traits::Shape;
+
+pub trait Solid: Shape {
+    fn volume(&self) -> f64;
+}
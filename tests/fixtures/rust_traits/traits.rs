@@ -0,0 +1,18 @@
+// Traits with supertraits, one of them from std, and a struct bounded by them
+use std::fmt;
+
+pub trait Named {
+    fn name(&self) -> String;
+}
+
+pub trait Shape: Named + fmt::Debug {
+    fn area(&self) -> f64;
+}
+
+pub struct Labeled<'a, T: Shape + Clone, U>
+where
+    U: fmt::Display + 'a,
+{
+    pub shape: T,
+    pub label: &'a U,
+}
@@ -0,0 +1,21 @@
+// Named, tuple and unit structs and an enum whose fields use types from person.rs
+use crate::person::Person;
+
+pub struct Team<T> {
+    pub name: String,
+    pub owner: Person,
+    members: Vec<Person>,
+    extra: Option<T>,
+    pub home: crate::person::Address,
+}
+
+pub struct Pair(pub Person, u32);
+
+pub struct Marker;
+
+/// Where a team plays
+pub enum Venue {
+    Unknown,
+    Hosted(Team<u8>),
+    Remote { host: Person, url: String },
+}
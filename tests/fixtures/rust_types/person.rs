@@ -0,0 +1,9 @@
+// Types used by the fields of model.rs
+pub struct Person {
+    pub name: String,
+    age: u32,
+}
+
+pub struct Address {
+    pub street: String,
+}
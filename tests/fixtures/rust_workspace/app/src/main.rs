@@ -0,0 +1,6 @@
+use core_lib::person::Person;
+
+fn main() {
+    let person = Person::new("Ada");
+    println!("{} {}", person.name, core_lib::version());
+}
@@ -0,0 +1,5 @@
+pub mod person;
+
+pub fn version() -> &'static str {
+    "1.0"
+}
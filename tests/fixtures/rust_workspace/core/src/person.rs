@@ -0,0 +1,9 @@
+pub struct Person {
+    pub name: String,
+}
+
+impl Person {
+    pub fn new(name: &str) -> Self {
+        Person { name: name.to_string() }
+    }
+}
@@ -0,0 +1,11 @@
+pub mod person;
+
+use crate::person::Person;
+
+pub fn version() -> &'static str {
+    "0.1"
+}
+
+pub fn greet(person: &Person) -> String {
+    format!("hello {}", person.nickname)
+}
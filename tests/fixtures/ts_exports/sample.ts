@@ -0,0 +1,34 @@
+// Sample TypeScript module with named and default exports
+
+import { Logger } from './logger';
+
+/** Greets a user by name. */
+export function greet(name: string): string {
+    return `Hello, ${name}`;
+}
+
+// Arrow function bound to a const
+export const shout = (text: string): string => text.toUpperCase();
+
+const whisper = (text: string): string => text.toLowerCase();
+
+export class Greeter {
+    constructor(private logger: Logger) {}
+
+    greet(name: string): string {
+        const message = greet(name);
+        this.logger.log(message);
+        return message;
+    }
+}
+
+function internalHelper(): number {
+    return 42;
+}
+
+export { whisper as quiet, internalHelper };
+
+/** Entry point of the module. */
+export default function (name: string): string {
+    return shout(greet(name));
+}
@@ -0,0 +1,16 @@
+// Sample TSX component; JSX is skipped, the declarations around it are kept
+
+import React from 'react';
+
+type Props = { label: string };
+
+export const Button = ({ label }: Props) => <button className="btn">{label}</button>;
+
+export default function App() {
+    return (
+        <div>
+            <Button label="Save" />
+            <>{[1, 2].map(n => <span key={n}>{n}</span>)}</>
+        </div>
+    );
+}
@@ -0,0 +1,5 @@
+from models import Order
+
+
+def post(entry):
+    return Order()
@@ -0,0 +1,2 @@
+class Order:
+    """An order as the ledger books it."""
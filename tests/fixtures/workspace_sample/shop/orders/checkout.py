@@ -0,0 +1,6 @@
+from ledger_api.entries import post
+from models import Order
+
+
+def checkout():
+    return post(Order())
@@ -0,0 +1,5 @@
+class Order:
+    """An order placed in the shop."""
+
+    def total(self):
+        return 0
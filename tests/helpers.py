@@ -0,0 +1,45 @@
+"""
+Helpers shared by the test modules, for picking nodes and edges out of a
+parse and comparing parses and graphs.
+"""
+
+import os
+
+from src.graph.code_graph import relation_key
+
+
+def find_node(nodes, node_type, name, file_name=None):
+    """The first node of a type with a name, optionally in a file of one basename; fails the test when there is none."""
+    for node in nodes.values():
+        if node.node_type == node_type and node.name == name \
+                and (file_name is None or os.path.basename(node.file_path) == file_name):
+            return node
+    raise AssertionError(f"{node_type} {name} not found")
+
+
+def node_names(nodes, node_type, file_path=None):
+    """Names of the nodes of a type, optionally only those of one file."""
+    return {n.name for n in nodes.values()
+            if n.node_type == node_type and (file_path is None or n.file_path == file_path)}
+
+
+def edge_names(nodes, relations, relation_type):
+    """(source name, target name) of every edge of one type between known nodes."""
+    return {(nodes[r.source_id].name, nodes[r.target_id].name) for r in relations
+            if r.relation_type == relation_type and r.source_id in nodes and r.target_id in nodes}
+
+
+def parse_shape(nodes, relations):
+    """Comparable view of a parse result."""
+    return (
+        sorted((n.node_id, n.node_type, n.name, n.line_no) for n in nodes.values()),
+        sorted((r.source_id, r.target_id, r.relation_type) for r in relations),
+    )
+
+
+def graph_shape(graph):
+    """Comparable view of a CodeGraph: its nodes with their locations, and its edges."""
+    return (
+        {(n.node_id, n.node_type, n.name, n.line_no, n.end_line_no) for n in graph.nodes.values()},
+        {relation_key(r) for r in graph.relations},
+    )
@@ -0,0 +1,70 @@
+"""
+Tests for finding blocking calls made from async functions.
+"""
+
+import os
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.ast_parser.parser import CodeNode, CodeRelation
+from src.graph.async_hazards import find_sync_in_async
+from src.graph.code_graph import UNRESOLVED_PREFIX, CodeGraph
+
+
+def _function(name, file_path, line_no, is_async):
+    return CodeNode(f"Function:{file_path}:{name}:{line_no}", "Function", name, file_path, line_no,
+                    properties={"is_async": is_async})
+
+
+def _call(caller, target_id, line_no, call_site, **properties):
+    return CodeRelation(caller.node_id, target_id, "CALLS",
+                        {"line_no": line_no, "call_site": call_site, **properties})
+
+
+@pytest.fixture
+def graph():
+    serve = _function("serve", "src/lib.rs", 1, True)
+    dial = _function("dial", "src/transport.rs", 1, False)
+    ping = _function("ping", "src/transport.rs", 5, True)
+    setup = _function("setup", "src/lib.rs", 10, False)
+    transport = CodeNode("file:src/transport.rs", "File", "transport.rs", "src/transport.rs", 0,
+                         properties={"module_path": "crate::net::transport"})
+    relations = [
+        _call(serve, UNRESOLVED_PREFIX + "std::fs::read_to_string", 2,
+              'let text = std::fs::read_to_string("a.toml");', raw_name="std::fs::read_to_string",
+              unresolved=True),
+        _call(serve, UNRESOLVED_PREFIX + "tokio::fs::read", 3, 'let bytes = tokio::fs::read("b").await;',
+              raw_name="tokio::fs::read", unresolved=True, await_call=True),
+        _call(serve, dial.node_id, 4, "let socket = dial(addr);"),
+        _call(serve, ping.node_id, 5, "let pong = ping(socket);"),
+        _call(setup, dial.node_id, 11, "dial(addr);"),
+    ]
+    return CodeGraph.from_records([serve, dial, ping, setup, transport], relations)
+
+
+class TestFindSyncInAsync:
+
+    def test_unawaited_io_calls_from_async_functions(self, graph):
+        hazards = find_sync_in_async(graph)
+        assert [(h.function, h.callee, h.line_no) for h in hazards] == [
+            ("serve", "std::fs::read_to_string", 2), ("serve", "dial", 4),
+        ]
+        assert hazards[0].callee_type == "Unresolved"
+        assert hazards[0].reasons == ["name contains 'read'", "in module 'fs'"]
+
+    def test_module_comes_from_the_crate_module_path(self, graph):
+        dial = next(h for h in find_sync_in_async(graph) if h.callee == "dial")
+        assert (dial.reasons, dial.call_site) == (["in module 'net'"], "let socket = dial(addr);")
+
+    def test_awaited_and_async_callees_are_not_hazards(self, graph):
+        callees = {h.callee for h in find_sync_in_async(graph)}
+        # tokio::fs::read is awaited; ping is async itself
+        assert not callees & {"tokio::fs::read", "ping"}
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -0,0 +1,324 @@
+"""
+Tests for CAdapter.
+
+Covers a mock of the standard library's stdio.h (prototypes, macros and
+typedefs inside an include guard), a header and source pair with structs,
+unions, enums, typedefs and function-like macros, includes resolved to the
+included file, and the C declarations CppAdapter now shares. The C++ sample
+covers calls resolved into included headers, overloads, and definitions
+linked to the header prototypes declaring them. The native sample mixes C
+and C++ with two headers of the same name, includes resolved by path, and
+templates and conditionals marked as partially parsed.
+"""
+
+import os
+import sys
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+pytest.importorskip("ast_grep_py")
+
+from src.ast_parser.multi_parser import MultiLanguageParser
+from src.ast_parser.adapters.c_adapter import CAdapter
+from src.ast_parser.adapters.cpp_adapter import CppAdapter
+from src.graph.code_graph import CodeGraph, UNRESOLVED_PREFIX
+from tests.helpers import find_node, node_names
+
+
+FIXTURES = os.path.abspath(os.path.join(os.path.dirname(__file__), "fixtures"))
+C_SAMPLE_DIR = os.path.join(FIXTURES, "c_sample")
+STDIO_H = os.path.join(C_SAMPLE_DIR, "stdio.h")
+SHAPES_H = os.path.join(C_SAMPLE_DIR, "shapes.h")
+CPP_SAMPLE_DIR = os.path.join(FIXTURES, "cpp_sample")
+NATIVE_SAMPLE_DIR = os.path.join(FIXTURES, "native_sample")
+
+
+class TestStdioHeader:
+    """The stdio.h mock: everything sits inside its include guard."""
+
+    @pytest.fixture
+    def parser(self):
+        return CAdapter()
+
+    def test_parses_without_errors(self, parser):
+        nodes, _ = parser.parse_file(STDIO_H)
+        assert f"file:{STDIO_H}" in nodes
+        assert parser.parse_errors == []
+
+    def test_prototypes(self, parser):
+        nodes, _ = parser.parse_file(STDIO_H)
+        # stdin, stdout and the function pointer handler are variables
+        assert node_names(nodes, "Function") == {"printf", "fprintf", "fclose", "fopen", "fgets"}
+        assert find_node(nodes, "Function", "fopen").properties == \
+            {"visibility": "Public", "prototype": True, "signature": "(const char *, const char *)"}
+        assert find_node(nodes, "Function", "printf").properties["signature"] == "(const char *, ...)"
+
+    def test_macros_and_typedefs(self, parser):
+        nodes, _ = parser.parse_file(STDIO_H)
+        assert node_names(nodes, "Macro") == {"_STDIO_H", "EOF", "BUFSIZ"}
+        assert find_node(nodes, "Macro", "EOF").properties == {"value": "(-1)"}
+        assert {n.name: n.properties["type"] for n in nodes.values() if n.node_type == "TypeAlias"} == \
+            {"FILE": "struct _IO_FILE", "fpos_t": "long"}
+        # struct _IO_FILE is only declared
+        assert node_names(nodes, "Class") == set()
+
+    def test_include(self, parser):
+        parser.parse_file(STDIO_H)
+        assert [(p["imported_module"], p["full_module_path"], p["system"]) for p in parser.pending_imports] == \
+            [("stddef.h", "stddef.h", True)]
+
+    def test_extern_c_split_over_conditionals_does_not_fail(self, parser):
+        source = ('#ifdef __cplusplus\nextern "C" {\n#endif\n\nint puts(const char *s);\n\n'
+                  '#ifdef __cplusplus\n}\n#endif\n')
+        nodes, _ = parser.parse_source(source, "split.h")
+        assert "file:split.h" in nodes
+        assert not any(e.message.startswith("Parser failed") for e in parser.parse_errors)
+
+
+class TestShapes:
+    """shapes.h and shapes.c, parsed as a directory."""
+
+    @pytest.fixture
+    def parsed(self):
+        parser = MultiLanguageParser(use_ast_grep=True, ast_grep_languages=['c'], ast_grep_fallback=False)
+        return parser.parse_directory(C_SAMPLE_DIR, build_index=True)
+
+    def test_records(self, parsed):
+        nodes, _ = parsed
+        kinds = {n.name: n.properties["type_kind"] for n in nodes.values() if n.node_type == "Class"}
+        # The anonymous struct takes its typedef's name
+        assert kinds == {"Point": "struct", "rect": "struct", "number": "union"}
+        assert node_names(nodes, "Enum") == {"color"}
+
+    def test_fields(self, parsed):
+        nodes, relations = parsed
+        rect = find_node(nodes, "Class", "rect")
+        fields = {nodes[r.target_id].name: nodes[r.target_id].properties["type"] for r in relations
+                  if r.relation_type == "DEFINES" and r.source_id == rect.node_id}
+        assert fields == {"origin": "Point", "label": "char *", "sides": "int [4]"}
+        point = find_node(nodes, "Class", "Point")
+        assert {nodes[r.target_id].name for r in relations
+                if r.relation_type == "DEFINES" and r.source_id == point.node_id} == {"x", "y"}
+
+    def test_typedefs(self, parsed):
+        nodes, _ = parsed
+        aliases = {n.name: n.properties["type"] for n in nodes.values()
+                   if n.node_type == "TypeAlias" and n.file_path == SHAPES_H}
+        assert aliases == {"Point": "struct", "compare_fn": "int (*)(const void *, const void *)"}
+
+    def test_function_like_macro(self, parsed):
+        nodes, _ = parsed
+        assert find_node(nodes, "Macro", "SQUARE").properties == {"parameters": ["x"], "value": "((x) * (x))"}
+
+    def test_definitions_prototypes_and_static(self, parsed):
+        nodes, _ = parsed
+        functions = {(n.name, os.path.basename(n.file_path)): n.properties for n in nodes.values()
+                     if n.node_type == "Function" and not n.file_path.endswith("stdio.h")}
+        rect = "(const struct rect *)"
+        assert functions == {
+            ("area", "shapes.h"): {"visibility": "Public", "prototype": True, "signature": rect},
+            ("width", "shapes.c"): {"visibility": "Private", "signature": rect},
+            ("area", "shapes.c"): {"visibility": "Public", "signature": rect},
+            ("main", "shapes.c"): {"visibility": "Public", "signature": "()"},
+        }
+
+    def test_includes_link_to_the_header(self, parsed):
+        nodes, relations = parsed
+        included = {os.path.basename(nodes[r.target_id].file_path) for r in relations
+                    if r.relation_type == "INCLUDES" and nodes[r.source_id].name == "shapes.c"}
+        # The header, not shapes.c, although both are "shapes"; <stdio.h> is a
+        # system include and stays unresolved although a stdio.h is indexed
+        assert included == {"shapes.h"}
+
+    def test_definition_is_declared_by_the_header_prototype(self, parsed):
+        nodes, relations = parsed
+        links = {(os.path.basename(nodes[r.source_id].file_path), os.path.basename(nodes[r.target_id].file_path))
+                 for r in relations if r.relation_type == "DECLARED_BY"}
+        assert links == {("shapes.c", "shapes.h")}
+        # And back from the prototype to its definition
+        declares = [(os.path.basename(nodes[r.source_id].file_path), nodes[r.target_id].name, r.properties)
+                    for r in relations if r.relation_type == "DECLARES"]
+        assert declares == [("shapes.h", "area", {"signature": "(const struct rect *)"})]
+
+    def test_calls_prefer_the_definition_in_the_same_file(self, parsed):
+        nodes, relations = parsed
+        calls = {(nodes[r.source_id].name, nodes[r.target_id].name, os.path.basename(nodes[r.target_id].file_path))
+                 for r in relations if r.relation_type == "CALLS"}
+        # printf is declared in <stdio.h>, which is not followed
+        assert calls == {("area", "width", "shapes.c"), ("main", "area", "shapes.c")}
+
+
+class TestCppSharesCDeclarations:
+    def test_structs_enums_and_namespaces(self):
+        source = """
+#define VERSION 2
+namespace geo {
+struct Point {
+    int x;
+    int norm() { return x; }
+};
+enum class Color { Red, Green };
+double distance(Point a, Point b);
+}
+"""
+        nodes, relations = CppAdapter().parse_source(source, "geo.hpp")
+        point = find_node(nodes, "Class", "Point")
+        members = {(nodes[r.target_id].node_type, nodes[r.target_id].name) for r in relations
+                   if r.relation_type == "DEFINES" and r.source_id == point.node_id}
+        assert members == {("Field", "x"), ("Method", "norm")}
+        assert node_names(nodes, "Enum") == {"Color"}
+        assert node_names(nodes, "Macro") == {"VERSION"}
+        assert find_node(nodes, "Function", "distance").properties["prototype"] is True
+
+
+class TestCppSample:
+    """geometry.h, geometry.cpp and main.cpp, resolved in a CodeGraph."""
+
+    @pytest.fixture(scope="class")
+    def graph(self):
+        return CodeGraph.from_directory(CPP_SAMPLE_DIR, use_ast_grep=True, ast_grep_languages=['cpp'],
+                                        ast_grep_fallback=False)
+
+    @staticmethod
+    def _at(node):
+        return os.path.basename(node.file_path), node.line_no
+
+    def test_overloads_are_distinct_by_signature(self, graph):
+        overloads = {(self._at(n), n.properties["signature"]) for n in graph.nodes.values()
+                     if n.node_type == "Function" and n.name == "add" and n.file_path.endswith(".h")}
+        assert overloads == {(("geometry.h", 18), "(int, int)"), (("geometry.h", 19), "(int, int, int)"),
+                             (("geometry.h", 20), "(double, double)")}
+
+    def test_methods_declared_in_the_class(self, graph):
+        circle = find_node(graph.nodes, "Class", "Circle")
+        methods = {graph.nodes[r.target_id].name: graph.nodes[r.target_id].properties for r in graph.relations
+                   if r.relation_type == "DEFINES" and r.source_id == circle.node_id
+                   and graph.nodes[r.target_id].file_path.endswith(".h")}
+        assert methods["describe"] == {"method_of": "Circle", "prototype": True,
+                                       "signature": "(const std::string &)", "default_args": 1}
+        assert set(methods) == {"Circle", "area", "describe"}
+
+    def test_definitions_are_declared_by_their_prototypes(self, graph):
+        links = sorted((graph.nodes[r.source_id].name, self._at(graph.nodes[r.source_id]),
+                        self._at(graph.nodes[r.target_id]))
+                       for r in graph.relations if r.relation_type == "DECLARED_BY")
+        assert links == [
+            ("Circle", ("geometry.cpp", 7), ("geometry.h", 10)),
+            ("add", ("geometry.cpp", 19), ("geometry.h", 18)),
+            ("add", ("geometry.cpp", 24), ("geometry.h", 19)),
+            ("add", ("geometry.cpp", 29), ("geometry.h", 20)),
+            ("area", ("geometry.cpp", 9), ("geometry.h", 11)),
+            ("describe", ("geometry.cpp", 14), ("geometry.h", 12)),
+        ]
+
+    def test_out_of_line_methods_belong_to_the_class(self, graph):
+        circle = find_node(graph.nodes, "Class", "Circle")
+        defined = {self._at(graph.nodes[r.target_id]) for r in graph.relations
+                   if r.relation_type == "DEFINES" and r.source_id == circle.node_id}
+        assert {("geometry.cpp", 7), ("geometry.cpp", 9), ("geometry.cpp", 14)} <= defined
+
+    def test_call_resolves_into_the_included_header(self, graph):
+        main = find_node(graph.nodes, "Function", "main")
+        callees = {graph.nodes[r.target_id].node_id if r.target_id in graph.nodes else r.target_id: r.properties
+                   for r in graph.relations if r.relation_type == "CALLS" and r.source_id == main.node_id}
+        add = next(node_id for node_id in callees if node_id.startswith("Function:"))
+        # Three arguments pick the one overload taking three
+        assert self._at(graph.nodes[add]) == ("geometry.h", 19)
+        assert callees[add]["path"] == "geo::add"
+        assert callees[UNRESOLVED_PREFIX + "circle.describe"]["unresolved"] is True
+
+    def test_ambiguous_overload_call_stays_unresolved(self, graph):
+        caller = next(n for n in graph.nodes.values() if n.name == "add" and self._at(n) == ("geometry.cpp", 24))
+        targets = {r.target_id for r in graph.relations if r.relation_type == "CALLS" and r.source_id == caller.node_id}
+        # add(int, int) and add(double, double) both take two arguments
+        assert targets == {UNRESOLVED_PREFIX + "add"}
+
+    def test_call_from_a_method_finds_a_sibling_method(self, graph):
+        describe = next(n for n in graph.nodes.values() if n.name == "describe" and self._at(n) == ("geometry.cpp", 14))
+        targets = {r.target_id for r in graph.relations if r.relation_type == "CALLS" and r.source_id == describe.node_id}
+        area = next(n.node_id for n in graph.nodes.values() if n.name == "area" and self._at(n) == ("geometry.cpp", 9))
+        assert targets == {area, UNRESOLVED_PREFIX + "std::to_string"}
+
+    def test_includes(self, graph):
+        imports = {(os.path.basename(r.source_id), r.target_id, r.properties.get("system"))
+                   for r in graph.relations if r.relation_type == "INCLUDES"}
+        assert imports == {
+            ("main.cpp", f"file:{os.path.join(CPP_SAMPLE_DIR, 'geometry.h')}", False),
+            ("geometry.cpp", f"file:{os.path.join(CPP_SAMPLE_DIR, 'geometry.h')}", False),
+            ("main.cpp", UNRESOLVED_PREFIX + "iostream", True),
+            ("geometry.cpp", UNRESOLVED_PREFIX + "cmath", True),
+            ("geometry.h", UNRESOLVED_PREFIX + "string", True),
+        }
+
+
+class TestNativeSample:
+    """include/codec/{buffer.h,ring.hpp} and src/{buffer.h,buffer.c,ring.cpp}, resolved in a CodeGraph."""
+
+    @pytest.fixture(scope="class")
+    def graph(self):
+        return CodeGraph.from_directory(NATIVE_SAMPLE_DIR, use_ast_grep=True, ast_grep_languages=['c', 'cpp'],
+                                        ast_grep_fallback=False)
+
+    @staticmethod
+    def _at(node):
+        return os.path.relpath(node.file_path, NATIVE_SAMPLE_DIR), node.line_no
+
+    def _file(self, graph, path):
+        return graph.nodes[f"file:{os.path.join(NATIVE_SAMPLE_DIR, path)}"]
+
+    def test_includes_resolve_by_path(self, graph):
+        includes = {(self._at(graph.nodes[r.source_id])[0],
+                     self._at(graph.nodes[r.target_id])[0] if r.target_id.startswith("file:") else r.target_id,
+                     r.properties["system"])
+                    for r in graph.relations if r.relation_type == "INCLUDES"}
+        # "buffer.h" is the header next to buffer.c, and "codec/buffer.h" the
+        # only file ending in that path, although both are named buffer.h
+        assert includes == {
+            ("src/buffer.c", "include/codec/buffer.h", False),
+            ("src/buffer.c", "src/buffer.h", False),
+            ("src/buffer.h", "include/codec/buffer.h", False),
+            ("include/codec/ring.hpp", "include/codec/buffer.h", False),
+            ("src/ring.cpp", "include/codec/ring.hpp", False),
+            ("src/buffer.c", UNRESOLVED_PREFIX + "string.h", True),
+            ("include/codec/buffer.h", UNRESOLVED_PREFIX + "stddef.h", True),
+        }
+
+    def test_declarations_link_to_their_definitions(self, graph):
+        declares = sorted((graph.nodes[r.source_id].name, self._at(graph.nodes[r.source_id]),
+                           self._at(graph.nodes[r.target_id]))
+                          for r in graph.relations if r.relation_type == "DECLARES")
+        # buffer_read is defined with one more parameter than declared, so it is another function
+        assert declares == [
+            ("buffer_reset", ("include/codec/buffer.h", 16), ("src/buffer.c", 14)),
+            ("buffer_write", ("include/codec/buffer.h", 15), ("src/buffer.c", 5)),
+            ("drain", ("include/codec/ring.hpp", 17), ("src/ring.cpp", 5)),
+        ]
+        defines = sorted((self._at(graph.nodes[r.target_id]), self._at(graph.nodes[r.source_id]))
+                         for r in graph.relations if r.relation_type == "DECLARED_BY")
+        assert defines == [(declaration, definition) for _, declaration, definition in declares]
+
+    def test_templates_and_conditionals_are_partial_regions(self, graph):
+        ring = self._file(graph, "include/codec/ring.hpp").properties["partial_regions"]
+        assert [(r["start_line"], r["reason"]) for r in ring] == [(7, "template")]
+        assert find_node(graph.nodes, "Class", "Ring").properties["partially_parsed"] is True
+        drain = next(n for n in graph.nodes.values() if n.name == "drain" and n.properties.get("prototype"))
+        assert "partially_parsed" not in drain.properties
+
+        internal = self._file(graph, "src/buffer.h").properties["partial_regions"]
+        assert [(r["start_line"], r["reason"]) for r in internal] == [(6, "conditional")]
+        # Both definitions of the macro are indexed
+        assert sorted(n.line_no for n in graph.nodes.values()
+                      if n.name == "BUFFER_ALIGN" and n.properties.get("partially_parsed")) == [7, 9]
+        assert "partial_regions" not in self._file(graph, "src/buffer.c").properties
+
+    def test_syntax_errors_are_partial_regions(self):
+        parser = CAdapter()
+        nodes, _ = parser.parse_source("int ok(void) { return 1; }\n\nint broken( {\n", "broken.c")
+        assert node_names(nodes, "Function") >= {"ok"}
+        assert "syntax_error" in {r["reason"] for r in nodes["file:broken.c"].properties["partial_regions"]}
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -0,0 +1,108 @@
+"""
+Tests for call hierarchy trees.
+"""
+
+import os
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.ast_parser.parser import CodeNode, CodeRelation
+from src.graph.call_hierarchy import call_hierarchy
+from src.graph.code_graph import CodeGraph
+
+
+APP = '''
+class Person:
+    def greet(self):
+        return format_name(self) + format_name(self)
+
+
+def format_name(person):
+    return clean(person.name)
+
+
+def clean(text):
+    return text.strip()
+
+
+def main():
+    person = Person()
+    person.greet()
+    return clean("  hi  ")
+'''
+
+
+@pytest.fixture
+def graph(tmp_path):
+    (tmp_path / "app.py").write_text(APP, encoding="utf-8")
+    return CodeGraph.from_directory(str(tmp_path))
+
+
+def _id(graph, name):
+    return next(node_id for node_id, node in graph.nodes.items()
+                if node.name == name and node.node_type in ("Function", "Method"))
+
+
+class TestCallHierarchy:
+    def test_callees_with_qualified_names_and_call_counts(self, graph):
+        hierarchy = call_hierarchy(graph, _id(graph, "greet"), "callees").to_dict()
+
+        assert hierarchy["qualified_name"] == "Person.greet"
+        assert "callers" not in hierarchy
+        (format_name,) = hierarchy["callees"]
+        assert (format_name["name"], format_name["calls"], format_name["call_site"]["line_no"]) == \
+            ("format_name", 2, 4)
+        assert format_name["call_site"]["snippet"] == "return format_name(self) + format_name(self)"
+        assert [(c["name"], c["children"]) for c in format_name["children"]] == [("clean", [])]
+
+    def test_shared_subtrees_are_expanded_once(self, graph):
+        tree = call_hierarchy(graph, _id(graph, "clean"), "callers", max_depth=5)
+        hierarchy = tree.to_dict()
+
+        # clean is called by format_name and main; format_name's chain reaches main again
+        assert [(c["name"], c.get("ref", False)) for c in hierarchy["callers"]] == \
+            [("format_name", False), ("main", False)]
+        (greet,) = hierarchy["callers"][0]["children"]
+        assert greet["qualified_name"] == "Person.greet"
+        assert [(c["name"], c.get("ref", False)) for c in greet["children"]] == [("main", True)]
+        assert tree.frontier == []
+
+    def test_frontier_lists_nodes_past_the_depth_limit(self, graph):
+        hierarchy = call_hierarchy(graph, _id(graph, "clean"), "callers", max_depth=1)
+        assert hierarchy.frontier == [_id(graph, "format_name")]
+
+    def test_cycles_terminate(self):
+        path = "/repo/loop.py"
+        ping = CodeNode(f"Function:{path}:ping:1", "Function", "ping", path, 1)
+        pong = CodeNode(f"Function:{path}:pong:5", "Function", "pong", path, 5)
+        graph = CodeGraph.from_records([ping, pong], [
+            CodeRelation(ping.node_id, pong.node_id, "CALLS", {"line_no": 2}),
+            CodeRelation(pong.node_id, ping.node_id, "CALLS", {"line_no": 6}),
+        ])
+
+        hierarchy = call_hierarchy(graph, ping.node_id, "both", max_depth=10)
+        assert hierarchy.node_count == 5
+        for tree in (hierarchy.callers, hierarchy.callees):
+            (other,) = tree
+            (back,) = other.children
+            assert (back.id, back.cycle, back.children) == (ping.node_id, True, None)
+
+    def test_node_cap(self, graph):
+        hierarchy = call_hierarchy(graph, _id(graph, "clean"), "both", max_nodes=2)
+        assert (hierarchy.node_count, hierarchy.truncated) == (2, True)
+        assert [e.name for e in hierarchy.callers] == ["format_name"]
+        assert hierarchy.callees == []
+
+    def test_invalid_arguments(self, graph):
+        with pytest.raises(ValueError, match="Unknown direction"):
+            call_hierarchy(graph, _id(graph, "clean"), "sideways")
+        with pytest.raises(ValueError, match="Unknown node"):
+            call_hierarchy(graph, "Function:missing.py:f:1")
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -0,0 +1,487 @@
+"""
+Tests for CodeGraph incremental updates.
+
+Uses the legacy Python parser so the graph logic can be exercised without
+any optional parsing backends.
+"""
+
+import json
+import os
+import sys
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.ast_parser.parser import CodeNode
+from src.graph.code_graph import CodeGraph, JSON_SCHEMA_VERSION, relation_key
+from tests.helpers import graph_shape
+
+
+MODELS = '''
+def helper():
+    return 1
+
+
+class Greeter:
+    def greet(self):
+        return self.wave()
+
+    def wave(self):
+        return "hi"
+'''
+
+APP = '''
+from models import helper
+
+
+def run():
+    return helper()
+
+
+def main():
+    run()
+    print("done")
+'''
+
+LOOP = '''
+def ping(n):
+    return pong(n - 1)
+
+
+def pong(n):
+    return ping(n - 1) if n else recurse(n)
+
+
+def recurse(n):
+    return recurse(n - 1) if n else 0
+'''
+
+FIXTURE_PY = os.path.join(os.path.dirname(os.path.abspath(__file__)),
+                          "fixtures", "multi_lang_sample", "sample.py")
+
+
+def _write(directory, name, content):
+    path = os.path.join(str(directory), name)
+    with open(path, "w", encoding="utf-8") as f:
+        f.write(content)
+    return path
+
+
+def _calls(graph):
+    """CALLS edges as (source name, target name, unresolved) triples."""
+    result = set()
+    for r in graph.relations:
+        if r.relation_type == "CALLS":
+            result.add((graph.nodes[r.source_id].name, graph.nodes[r.target_id].name,
+                        bool(r.properties.get("unresolved"))))
+    return result
+
+
+def _id(graph, name, node_type="Function"):
+    """Node ID of the only node of a type with the given name."""
+    matches = [n.node_id for n in graph.nodes.values()
+               if n.name == name and n.node_type == node_type]
+    assert len(matches) == 1, matches
+    return matches[0]
+
+
+class TestCodeGraph:
+
+    @pytest.fixture
+    def codebase(self, tmp_path):
+        models = _write(tmp_path, "models.py", MODELS)
+        app = _write(tmp_path, "app.py", APP)
+        return tmp_path, models, app
+
+    def test_build_links_local_and_cross_file_calls(self, codebase):
+        root, _, _ = codebase
+        graph = CodeGraph.from_directory(str(root))
+        calls = _calls(graph)
+        assert ("run", "helper", False) in calls
+        assert ("main", "run", False) in calls
+        assert ("greet", "wave", False) in calls
+        # Builtins have no definition in the codebase
+        assert ("main", "print", True) in calls
+
+    def test_every_relation_endpoint_exists(self, codebase):
+        root, _, _ = codebase
+        graph = CodeGraph.from_directory(str(root))
+        for r in graph.relations:
+            assert r.source_id in graph.nodes
+            assert r.target_id in graph.nodes
+
+    def test_deleted_callee_marks_callers_unresolved(self, codebase):
+        root, models, _ = codebase
+        graph = CodeGraph.from_directory(str(root))
+
+        delta = graph.update_file(models, MODELS.replace("def helper():\n    return 1\n", ""))
+
+        assert ("run", "models.helper", True) in _calls(graph)
+        assert any(graph.nodes[n].node_type == "Unresolved" for n in delta.added_nodes)
+        assert any(n.startswith("Function:") and n.endswith(":helper:2") for n in delta.removed_nodes)
+        assert any(r.relation_type == "CALLS" and r.properties.get("unresolved") for r in delta.added_relations)
+
+    def test_restored_callee_resolves_again(self, codebase):
+        root, models, _ = codebase
+        graph = CodeGraph.from_directory(str(root))
+        graph.update_file(models, MODELS.replace("def helper():\n    return 1\n", ""))
+        graph.update_file(models, MODELS)
+
+        assert ("run", "helper", False) in _calls(graph)
+        assert "unresolved:models.helper" not in graph.nodes
+
+    def test_update_matches_full_rebuild(self, codebase):
+        root, models, _ = codebase
+        graph = CodeGraph.from_directory(str(root))
+
+        new_models = "\n\n" + MODELS + "\ndef extra():\n    return helper()\n"
+        _write(root, "models.py", new_models)
+        graph.update_file(models)
+
+        assert graph_shape(graph) == graph_shape(CodeGraph.from_directory(str(root)))
+
+    def test_unchanged_source_yields_empty_delta(self, codebase):
+        root, models, _ = codebase
+        graph = CodeGraph.from_directory(str(root))
+        assert graph.update_file(models).is_empty()
+
+    def test_update_from_memory_does_not_touch_disk(self, codebase):
+        root, _, app = codebase
+        graph = CodeGraph.from_directory(str(root))
+        graph.update_file(app, APP + "\n\ndef unsaved():\n    pass\n")
+
+        assert any(n.name == "unsaved" for n in graph.nodes.values())
+        with open(app, encoding="utf-8") as f:
+            assert "unsaved" not in f.read()
+
+    def test_remove_file(self, codebase):
+        root, models, _ = codebase
+        graph = CodeGraph.from_directory(str(root))
+        graph.remove_file(models)
+
+        assert all(n.file_path != models for n in graph.nodes.values())
+        assert ("run", "models.helper", True) in _calls(graph)
+        assert not any(r.relation_type == "IMPORTS_FROM" for r in graph.relations)
+
+
+class TestReachable:
+
+    @pytest.fixture
+    def graph(self, tmp_path):
+        with open(FIXTURE_PY, encoding="utf-8") as f:
+            _write(tmp_path, "sample.py", f.read())
+        _write(tmp_path, "models.py", MODELS)
+        _write(tmp_path, "app.py", APP)
+        _write(tmp_path, "loop.py", LOOP)
+        return CodeGraph.from_directory(str(tmp_path))
+
+    def test_sample_paths(self, graph):
+        assert graph.reachable(_id(graph, "greet"), _id(graph, "add")) is None
+        assert graph.reachable(_id(graph, "find_home"), _id(graph, "greet")) == \
+            [_id(graph, "find_home"), _id(graph, "greet")]
+
+    def test_shortest_path_across_files(self, graph):
+        path = graph.reachable(_id(graph, "main"), _id(graph, "helper"))
+        assert [graph.nodes[n].name for n in path] == ["main", "run", "helper"]
+        assert graph.reachable(_id(graph, "main"), _id(graph, "main")) == [_id(graph, "main")]
+
+    def test_only_call_edges_are_followed(self, graph):
+        greet = _id(graph, "greet")
+        sample_file = _id(graph, "sample.py", "File")
+        # The file CONTAINS greet, but containment is not a call
+        assert graph.reachable(sample_file, greet) is None
+        # Calls go from caller to callee only
+        assert graph.reachable(greet, _id(graph, "find_home")) is None
+
+    def test_cycles_terminate(self, graph):
+        assert graph.reachable(_id(graph, "ping"), _id(graph, "helper")) is None
+        path = graph.reachable(_id(graph, "ping"), _id(graph, "recurse"))
+        assert [graph.nodes[n].name for n in path] == ["ping", "pong", "recurse"]
+
+    def test_max_depth(self, graph):
+        main, helper = _id(graph, "main"), _id(graph, "helper")
+        stopped = []
+        assert graph.reachable(main, helper, max_depth=1, stopped=stopped) is None
+        assert stopped
+        assert graph.reachable(main, helper, max_depth=2) is not None
+
+
+class TestCallersCallees:
+
+    @pytest.fixture
+    def graph(self, tmp_path):
+        _write(tmp_path, "models.py", MODELS)
+        _write(tmp_path, "app.py", APP)
+        _write(tmp_path, "loop.py", LOOP)
+        _write(tmp_path, "legacy.py", "import utils\n\n\ndef old():\n    return utils.helper()\n")
+        return CodeGraph.from_directory(str(tmp_path))
+
+    def test_callers_with_call_sites(self, graph):
+        [site] = graph.callers(_id(graph, "helper"))
+        assert site.node_id == _id(graph, "run")
+        assert site.file_path.endswith("app.py")
+        assert (site.line_no, site.snippet, site.unresolved) == (6, "return helper()", False)
+
+    def test_recursion_is_its_own_caller_and_callee(self, graph):
+        recurse = _id(graph, "recurse")
+        assert [s.node_id for s in graph.callers(recurse)] == [_id(graph, "pong"), recurse]
+        assert [s.node_id for s in graph.callees(recurse)] == [recurse]
+
+    def test_unresolved_calls_are_opt_in(self, graph):
+        main, helper = _id(graph, "main"), _id(graph, "helper")
+        assert [s.node_id for s in graph.callees(main)] == [_id(graph, "run")]
+        unresolved = graph.callees(main, include_unresolved=True)[-1]
+        assert (unresolved.node_id, unresolved.raw_name, unresolved.line_no) == ("unresolved:print", "print", 11)
+
+        # utils.helper() was not resolved, but its name matches helper
+        callers = graph.callers(helper, include_unresolved=True)
+        assert [(graph.nodes[s.node_id].name, s.raw_name) for s in callers] == [
+            ("run", None), ("old", "utils.helper"),
+        ]
+
+    def test_unknown_node(self, graph):
+        assert graph.callers("Function:missing") == [] and graph.callees("Function:missing") == []
+
+
+class TestNodeAt:
+
+    @pytest.fixture
+    def graph(self, tmp_path):
+        with open(FIXTURE_PY, encoding="utf-8") as f:
+            _write(tmp_path, "sample.py", f.read())
+        return CodeGraph.from_directory(str(tmp_path))
+
+    def test_innermost_first(self, graph, tmp_path):
+        path = str(tmp_path / "sample.py")
+        # return self.name, in the body of Person.get_name
+        assert graph.nodes_at(path, 12, 9) == [_id(graph, "get_name", "Method"), _id(graph, "Person", "Class")]
+        # class Person:
+        assert graph.node_at(path, 6, 7) == _id(graph, "Person", "Class")
+        assert graph.node_at(path, 22, 5) == _id(graph, "greet")
+
+    def test_outside_every_symbol(self, graph, tmp_path):
+        assert graph.node_at(str(tmp_path / "sample.py"), 1, 1) is None
+        assert graph.node_at(str(tmp_path / "missing.py"), 12, 9) is None
+
+    def test_index_follows_updates(self, graph, tmp_path):
+        path = str(tmp_path / "sample.py")
+        assert graph.node_at(path, 12, 9) == _id(graph, "get_name", "Method")
+        graph.update_file(path, "\n" * 11 + "def moved():\n    return 1\n")
+        assert graph.node_at(path, 12, 9) == _id(graph, "moved")
+
+    def test_columns_and_identical_spans(self):
+        struct = CodeNode("Class:a.rs:S:1", "Class", "S", "a.rs", 1, 3, {"column": 5, "end_column": 2})
+        field = CodeNode("Field:a.rs:x:2", "Field", "x", "a.rs", 2, 2, {"column": 5, "end_column": 11})
+        # Same span as the field; a method outranks a field
+        method = CodeNode("Method:a.rs:x:2", "Method", "x", "a.rs", 2, 2, {"column": 5, "end_column": 11})
+        graph = CodeGraph.from_records([struct, field, method], [])
+
+        assert graph.nodes_at("a.rs", 2, 7) == [method.node_id, field.node_id, struct.node_id]
+        assert graph.nodes_at("a.rs", 2, 11) == [struct.node_id]
+        assert graph.node_at("a.rs", 1, 4) is None
+        assert graph.node_at("a.rs", 3, 1) == struct.node_id
+        assert graph.node_at("a.rs", 3, 2) is None
+
+
+def _contents(graph):
+    """Everything that makes two graphs equal, in a comparable form."""
+    nodes = {n.node_id: (n.node_type, n.name, n.file_path, n.line_no, n.end_line_no,
+                         n.properties, n.code_snippet) for n in graph.nodes.values()}
+    relations = sorted((relation_key(r), json.dumps(r.properties, sort_keys=True))
+                       for r in graph.relations)
+    return nodes, relations
+
+
+class TestJson:
+
+    @pytest.fixture
+    def graph(self, tmp_path):
+        with open(FIXTURE_PY, encoding="utf-8") as f:
+            _write(tmp_path, "sample.py", f.read())
+        # One call into a function that does not exist, to carry an Unresolved node
+        _write(tmp_path, "app.py", "from sample import missing\n\n\ndef run():\n    missing()\n")
+        return CodeGraph.from_directory(str(tmp_path))
+
+    def test_round_trip(self, graph):
+        restored = CodeGraph.from_json(graph.to_json())
+        assert _contents(restored) == _contents(graph)
+        assert any(n.node_type == "Unresolved" for n in restored.nodes.values())
+
+    def test_document_shape(self, graph):
+        document = json.loads(graph.to_json())
+        assert document["schema_version"] == JSON_SCHEMA_VERSION
+        greet = next(n for n in document["nodes"] if n["name"] == "greet")
+        assert set(greet) >= {"id", "kind", "name", "file", "span"}
+        assert greet["kind"] == "Function" and greet["span"]["start_line"] == graph.nodes[greet["id"]].line_no
+        assert set(document["edges"][0]) >= {"source", "target", "kind"}
+        assert [n["id"] for n in document["nodes"]] == sorted(n["id"] for n in document["nodes"])
+
+    def test_visibility(self, tmp_path):
+        _write(tmp_path, "shop.py", "def _price():\n    pass\n\n\nclass Cart:\n"
+                                    "    def __init__(self):\n        pass\n\n"
+                                    "    def _total(self):\n        pass\n\n"
+                                    "    def __lock(self):\n        pass\n")
+        document = json.loads(CodeGraph.from_directory(str(tmp_path)).to_json())
+        visibility = {n["name"]: n["visibility"] for n in document["nodes"]}
+        assert visibility == {"shop.py": None, "_price": "Private", "Cart": "Public",
+                              "__init__": "Public", "_total": "Protected", "__lock": "Private"}
+
+    def test_same_input_gives_same_document(self, graph, tmp_path):
+        assert CodeGraph.from_directory(str(tmp_path)).to_json() == graph.to_json()
+
+    def test_rejects_other_schema_versions(self, graph):
+        document = json.loads(graph.to_json())
+        document["schema_version"] = JSON_SCHEMA_VERSION + 1
+        with pytest.raises(ValueError, match="expected one of 1, 2"):
+            CodeGraph.from_json(json.dumps(document))
+
+    def test_reads_version_1(self, graph):
+        document = json.loads(graph.to_json())
+        # Version 1 nodes had no top-level visibility
+        document["schema_version"] = 1
+        for node in document["nodes"]:
+            del node["visibility"]
+        restored = CodeGraph.from_json(json.dumps(document))
+        assert _contents(restored) == _contents(graph)
+        assert json.loads(restored.to_json())["schema_version"] == JSON_SCHEMA_VERSION
+
+
+class TestDiff:
+
+    @staticmethod
+    def _graph(sources):
+        graph = CodeGraph()
+        graph.add_sources(sources)
+        return graph
+
+    def test_identical_sources(self):
+        sources = {"models.py": MODELS, "app.py": APP}
+        assert self._graph(sources).diff(self._graph(sources)).is_empty()
+
+    def test_moved_symbols_are_not_changes(self):
+        moved = "\n\n" + MODELS
+        diff = self._graph({"models.py": MODELS}).diff(self._graph({"models.py": moved}))
+        assert diff.is_empty()
+
+    def test_added_removed_and_changed(self):
+        before = self._graph({"models.py": MODELS, "app.py": APP})
+        after = self._graph({"models.py": MODELS.replace("def helper():", 'def helper():\n    """Help."""'),
+                             "app.py": APP.replace("    run()\n", "")})
+        diff = before.diff(after)
+
+        assert diff.added_nodes == [] and diff.removed_nodes == []
+        changed, = diff.changed_nodes
+        assert changed["name"] == "helper"
+        assert changed["changes"] == {"doc": {"before": None, "after": "Help."},
+                                      "documented": {"before": False, "after": True}}
+        removed, = diff.removed_edges
+        assert (removed["type"], before.nodes[removed["target"]].name) == ("CALLS", "run")
+
+        reverse = after.diff(before)
+        assert [e["type"] for e in reverse.added_edges] == ["CALLS"]
+
+    def test_renamed_symbol_keeps_its_edges(self):
+        before = self._graph({"models.py": MODELS, "app.py": APP})
+        after = self._graph({"models.py": MODELS, "app.py": APP.replace("run", "execute")})
+        diff = before.diff(after)
+
+        renamed, = diff.renamed_nodes
+        assert (renamed["before"]["name"], renamed["after"]["name"]) == ("run", "execute")
+        assert diff.added_nodes == diff.removed_nodes == []
+        # main now calls execute, but through the same edge
+        assert diff.added_edges == diff.removed_edges == diff.caller_changes == diff.changed_nodes == []
+
+    def test_deleted_symbol_loses_its_callers(self):
+        before = self._graph({"models.py": MODELS, "app.py": APP})
+        after = self._graph({"models.py": MODELS,
+                             "app.py": APP.replace("def run():\n    return helper()\n\n\n", "").replace("    run()\n", "")})
+        diff = before.diff(after)
+
+        assert [n["name"] for n in diff.removed_nodes] == ["run"] and diff.renamed_nodes == []
+        assert [(c["name"], c["gained"], [n["name"] for n in c["lost"]]) for c in diff.caller_changes] == \
+            [("run", [], ["main"]), ("helper", [], ["run"])]
+
+    def test_move_only_is_a_span_change(self):
+        helper = "\ndef helper():\n    return 1\n"
+        reordered = MODELS.replace(helper, "") + "\n" + helper
+        diff = self._graph({"models.py": MODELS}).diff(self._graph({"models.py": reordered}))
+
+        # Greeter and its methods keep their order; helper moved past them
+        moved, = diff.changed_nodes
+        assert (moved["name"], moved["changes"]["span"]["before"]["start_line"]) == ("helper", 2)
+        assert list(moved["changes"]) == ["span"] and moved["line_no"] > 10
+        assert diff.added_nodes == diff.removed_nodes == diff.renamed_nodes == []
+
+    def test_symbol_moved_to_another_file(self):
+        helper = "\ndef helper():\n    return 1\n"
+        before = self._graph({"models.py": MODELS, "app.py": APP})
+        after = self._graph({"models.py": MODELS.replace(helper, ""), "utils.py": helper,
+                             "app.py": APP.replace("from models", "from utils")})
+        diff = before.diff(after)
+
+        renamed, = diff.renamed_nodes
+        assert (renamed["before"]["file_path"], renamed["after"]["file_path"]) == ("models.py", "utils.py")
+        assert [n["name"] for n in diff.added_nodes] == ["utils.py"]
+        # run still calls helper, wherever it lives
+        assert all(e["type"] != "CALLS" for e in diff.added_edges + diff.removed_edges)
+
+    def test_new_file(self):
+        diff = self._graph({"models.py": MODELS}).diff(self._graph({"models.py": MODELS, "loop.py": LOOP}))
+        assert sorted(n["name"] for n in diff.added_nodes) == ["loop.py", "ping", "pong", "recurse"]
+        assert diff.to_dict()["summary"]["added_nodes"] == 4
+
+
+SETTINGS = '''
+from models import helper
+
+LIMIT = helper()
+
+
+class Config:
+    default = 1
+
+    def load(self):
+        value = helper()
+        return value + self.default
+
+
+def main():
+    total = helper()
+    return total + LIMIT
+'''
+
+
+class TestIncludeKinds:
+
+    @staticmethod
+    def _graph(include_kinds=None):
+        graph = CodeGraph(include_kinds=include_kinds)
+        graph.add_sources({"models.py": MODELS, "settings.py": SETTINGS})
+        return graph
+
+    def test_default_keeps_every_node(self):
+        assert {"GlobalVariable", "ClassVariable"} <= {n.node_type for n in self._graph().nodes.values()}
+
+    def test_excluding_variables_keeps_the_call_edges(self):
+        full = self._graph()
+        small = self._graph(["Function", "Method", "Class"])
+        assert {n.node_type for n in small.nodes.values()} == {"File", "Function", "Method", "Class"}
+        assert _calls(small) == _calls(full)
+        assert {("load", "helper", False), ("main", "helper", False)} <= _calls(small)
+        # No relation is left dangling or pointing at a node from itself
+        assert all(r.source_id in small.nodes and r.target_id in small.nodes and r.source_id != r.target_id
+                   for r in small.relations)
+
+    def test_edges_move_to_the_nearest_kept_ancestor(self):
+        graph = self._graph(["Function", "Method"])
+        load = _id(graph, "load", "Method")
+        # Config is left out: the file now defines its method
+        assert [r.source_id for r in graph.relations
+                if r.relation_type == "DEFINES" and r.target_id == load] == ["file:settings.py"]
+        assert not any(n.node_type == "Class" for n in graph.nodes.values())
+        assert ("load", "helper", False) in _calls(graph)
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -0,0 +1,237 @@
+"""
+Tests for codegraph.toml: layering, unknown keys, type errors and the
+settings reaching the parsers.
+
+Uses the legacy Python parser so no optional parsing backends are needed.
+"""
+
+import io
+import os
+import re
+import sys
+from contextlib import redirect_stdout
+from unittest import mock
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.config import CONFIG_FILE, ConfigError, find_config, load_config, tomllib
+from src.graph.code_graph import CodeGraph
+
+
+CONFIG = '''# Project settings
+[paths]
+include = ["src/**"]
+exclude = ["**/generated/**"]
+max_file_size = 2048
+
+[languages]
+enabled = ["python", "rust"]
+disabled = ["rust"]
+colour = "blue"
+
+[languages.python]
+init_reexports = true
+
+[languages.go]
+strict = true
+
+[storage]
+backend = "sqlite"
+neo4j_password = "secret"
+'''
+
+
+@pytest.fixture
+def config_path(tmp_path):
+    path = tmp_path / CONFIG_FILE
+    path.write_text(CONFIG, encoding="utf-8")
+    return path
+
+
+def _load(path, **environ):
+    return load_config(str(path), environ=environ)
+
+
+class TestLoadConfig:
+
+    def test_file_values(self, config_path):
+        config = _load(config_path)
+        assert config.get("paths", "include") == ["src/**"]
+        assert config.get("paths", "max_file_size") == 2048
+        assert config.get("storage", "backend") == "sqlite"
+        assert config.languages == ["python"]
+        assert config.language_options == {"rust": {"index_private": True},
+                                           "python": {"init_reexports": True}}
+
+    def test_unknown_keys_warn_with_their_line(self, config_path):
+        config = _load(config_path)
+        assert config.warnings == [
+            f"{config_path}:10: unknown key 'languages.colour'",
+            f"{config_path}:15: unknown table 'languages.go'",
+            f"{config_path}:20: unknown key 'storage.neo4j_password'",
+        ]
+
+    def test_layers(self, config_path):
+        config = _load(config_path, STORAGE_BACKEND="neo4j", SQLITE_DB_PATH="env.db",
+                       EXCLUDE_PATTERNS="*.min.js")
+        # The file beats the environment, which beats the defaults
+        assert (config.get("storage", "backend"), config.get("storage", "db_path")) == ("sqlite", "env.db")
+
+        config.override({("storage", "backend"): "neo4j", ("storage", "db_path"): None,
+                         ("paths", "exclude"): ["build/"]})
+        assert (config.get("storage", "backend"), config.get("storage", "db_path")) == ("neo4j", "env.db")
+        # Exclude patterns add up across the layers
+        assert config.get("paths", "exclude") == ["*.min.js", "**/generated/**", "build/"]
+
+    def test_max_traversal_depth(self, tmp_path):
+        path = tmp_path / CONFIG_FILE
+        path.write_text("", encoding="utf-8")
+        assert _load(path).max_traversal_depth == 10
+        assert _load(path, MAX_TRAVERSAL_DEPTH="4").max_traversal_depth == 4
+        path.write_text("[queries]\nmax_traversal_depth = 25\n", encoding="utf-8")
+        assert _load(path, MAX_TRAVERSAL_DEPTH="4").max_traversal_depth == 25
+
+    def test_disabled_languages_are_excluded_by_extension(self, config_path):
+        exclude = _load(config_path).exclude
+        assert {"*.rs", "*.js", "*.go", "*.h"} <= set(exclude) and "*.py" not in exclude
+
+    def test_wrong_types_are_errors(self, tmp_path):
+        path = tmp_path / CONFIG_FILE
+        for text, message in [
+            ("[paths]\nmax_file_size = true\n", ":2: 'paths.max_file_size' must be a positive integer"),
+            ("[languages]\n\nenabled = ['cobol']\n", ":3: unknown language(s) cobol"),
+            ("[storage]\nbackend = 'redis'\n", ":2: 'storage.backend' must be one of neo4j, sqlite"),
+            ("[queries]\nmax_traversal_depth = 0\n", ":2: 'queries.max_traversal_depth' must be a positive integer"),
+            ("[paths\n", "Expected ']'"),
+        ]:
+            path.write_text(text, encoding="utf-8")
+            with pytest.raises(ConfigError, match=re.escape(message)):
+                _load(path)
+
+    def test_printed_config_reads_back(self, config_path):
+        config = _load(config_path)
+        printed = tomllib.loads(config.to_toml())
+        assert printed["paths"] == {"include": ["src/**"], "exclude": ["**/generated/**"],
+                                    "respect_gitignore": True, "max_file_size": 2048}
+        assert printed["languages"]["python"] == {"init_reexports": True}
+        # Unset values are left out
+        assert "neo4j_uri" not in printed["storage"]
+
+    def test_found_at_the_codebase_root(self, config_path, tmp_path):
+        assert find_config(str(tmp_path)) == str(config_path)
+        assert find_config(str(tmp_path / "missing")) is None
+
+
+class TestPathsConfig:
+
+    FLAT = '''include = ["src/**"]
+exclude = ["target/", "node_modules/"]
+max_file_size_kb = 4
+
+[language_overrides]
+"*.h" = "cpp"
+"legacy/*.h" = "c"
+'''
+
+    def test_top_level_keys_in_graph_mcp_toml(self, tmp_path):
+        path = tmp_path / ".graph-mcp.toml"
+        path.write_text(self.FLAT, encoding="utf-8")
+        assert find_config(str(tmp_path)) == str(path)
+        config = _load(path)
+        assert config.warnings == []
+        assert config.get("paths", "exclude") == ["target/", "node_modules/"]
+        assert config.max_file_size == 4096
+        assert config.language_overrides == {"*.h": "cpp", "legacy/*.h": "c"}
+        # A size in bytes wins over one in KiB
+        assert config.override({("paths", "max_file_size"): 100}).max_file_size == 100
+        # codegraph.toml comes first
+        (tmp_path / CONFIG_FILE).write_text("", encoding="utf-8")
+        assert find_config(str(tmp_path)) == str(tmp_path / CONFIG_FILE)
+
+    def test_overrides_read_back(self, tmp_path):
+        path = tmp_path / CONFIG_FILE
+        path.write_text(self.FLAT, encoding="utf-8")
+        printed = tomllib.loads(_load(path).to_toml())
+        assert printed["language_overrides"] == {"*.h": "cpp", "legacy/*.h": "c"}
+        assert printed["paths"]["max_file_size_kb"] == 4
+
+    def test_invalid_patterns_are_errors(self, tmp_path):
+        path = tmp_path / CONFIG_FILE
+        for text, message, key in [
+            ("[paths]\nexclude = ['src/[gen']\n", ":2: invalid pattern 'src/[gen' in 'paths.exclude': unclosed '['",
+             "paths.exclude"),
+            ("\ninclude = ['[z-a].py']\n", ":2: invalid pattern '[z-a].py' in 'paths.include': bad character range",
+             "paths.include"),
+            ("[language_overrides]\n'*.inc' = 'fortran'\n", ":2: unknown language fortran",
+             'language_overrides."*.inc"'),
+            ("[language_overrides]\n'!*.h' = 'c'\n", ":2: invalid pattern '!*.h' in 'language_overrides'",
+             'language_overrides."!*.h"'),
+        ]:
+            path.write_text(text, encoding="utf-8")
+            with pytest.raises(ConfigError, match=re.escape(message)) as raised:
+                _load(path)
+            assert (raised.value.location, raised.value.key) == (f"{path}:2", key)
+
+    def test_flag_and_environment_patterns_are_checked(self):
+        with pytest.raises(ConfigError, match="^command line: invalid pattern 'a\\[' in 'paths.exclude'"):
+            load_config(environ={}).override({("paths", "exclude"): ["a["]})
+        with pytest.raises(ConfigError, match="^EXCLUDE_PATTERNS: invalid pattern"):
+            load_config(environ={"EXCLUDE_PATTERNS": "*.min.js,b["})
+
+    def test_overrides_route_files_to_a_parser(self, tmp_path):
+        (tmp_path / "tool.pyw").write_text("def run():\n    pass\n", encoding="utf-8")
+        (tmp_path / "notes.txt").write_text("def nothing():\n    pass\n", encoding="utf-8")
+        graph = CodeGraph.from_directory(str(tmp_path), language_overrides={"*.pyw": "python"})
+        assert {node.name for node in graph.nodes.values() if node.node_type == "Function"} == {"run"}
+
+
+class TestPrintConfig:
+
+    def test_flags_override_the_file(self, config_path, tmp_path):
+        from src import main as main_module
+
+        argv = ["main.py", "--codebase-path", str(tmp_path), "--print-config",
+                "--storage", "neo4j", "--exclude", "dist/"]
+        output = io.StringIO()
+        with mock.patch.object(sys, "argv", argv), redirect_stdout(output):
+            main_module.main()
+
+        printed = tomllib.loads(output.getvalue())
+        assert output.getvalue().startswith(f"# Merged from {config_path}")
+        assert printed["storage"]["backend"] == "neo4j"
+        assert printed["paths"]["exclude"][-2:] == ["**/generated/**", "dist/"]
+
+
+class TestLanguageOptions:
+
+    @pytest.fixture
+    def package(self, tmp_path):
+        (tmp_path / "shapes").mkdir()
+        (tmp_path / "shapes" / "circle.py").write_text("class Circle:\n    pass\n", encoding="utf-8")
+        (tmp_path / "shapes" / "__init__.py").write_text(
+            "from .circle import Circle as Round\n\n\ndef helper():\n    from .circle import Circle\n",
+            encoding="utf-8")
+        (tmp_path / "app.py").write_text("from shapes import Round\n", encoding="utf-8")
+        return tmp_path
+
+    @staticmethod
+    def _imported(graph):
+        return {(r.properties.get("symbol"), graph.nodes[r.target_id].name)
+                for r in graph.relations if r.relation_type == "IMPORTS_DEFINITION"
+                and r.source_id.endswith("app.py")}
+
+    def test_init_reexports_resolve_to_the_definition(self, package):
+        graph = CodeGraph.from_directory(str(package), language_options={"python": {"init_reexports": True}})
+        assert self._imported(graph) == {("Round", "Circle")}
+        # Only top-level imports are re-exports
+        assert "Circle" not in graph._module_definitions["shapes"]
+
+    def test_init_reexports_off_by_default(self, package):
+        assert self._imported(CodeGraph.from_directory(str(package))) == set()
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
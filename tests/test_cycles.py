@@ -0,0 +1,208 @@
+"""
+Tests for dependency cycle detection.
+"""
+
+import os
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.ast_parser.parser import CodeNode, CodeRelation
+from src.graph.code_graph import CodeGraph
+from src.graph.cycles import (cycle_key, cycles_main, dependency_edges, find_cycles, new_cycles,
+                              strongly_connected_components)
+
+
+ORDERS = '''
+from customers import lookup
+
+
+def place(name):
+    return lookup(name)
+
+
+def ping(n):
+    return pong(n - 1)
+
+
+def pong(n):
+    return ping(n - 1) if n else 0
+'''
+
+CUSTOMERS = '''
+import orders
+
+
+def lookup(name):
+    return name
+
+
+def countdown(n):
+    return countdown(n - 1) if n else 0
+'''
+
+CYCLE_SAMPLE = os.path.join(os.path.dirname(os.path.abspath(__file__)), "fixtures", "cycle_sample")
+
+REPORTS = '''
+from orders import place
+
+
+def report():
+    return place("x")
+'''
+
+
+@pytest.fixture
+def graph(tmp_path):
+    for name, source in (("orders.py", ORDERS), ("customers.py", CUSTOMERS), ("reports.py", REPORTS)):
+        (tmp_path / name).write_text(source, encoding="utf-8")
+    return CodeGraph.from_directory(str(tmp_path))
+
+
+def _names(graph, node_ids):
+    return [graph.nodes[n].name for n in node_ids]
+
+
+def _chain(names, closing=True):
+    """A graph of functions calling each other in a line, optionally closed into a loop."""
+    nodes = [CodeNode(f"Function:x.py:{name}:{i}", "Function", name, "x.py", i) for i, name in enumerate(names)]
+    relations = [CodeRelation(a.node_id, b.node_id, "CALLS") for a, b in zip(nodes, nodes[1:])]
+    if closing:
+        relations.append(CodeRelation(nodes[-1].node_id, nodes[0].node_id, "CALLS"))
+    return CodeGraph.from_records(nodes, relations), nodes
+
+
+class TestFindCycles:
+
+    def test_module_cycle(self, graph):
+        cycles = find_cycles(graph, "module")
+        assert len(cycles) == 1
+        assert sorted(_names(graph, cycles[0].nodes)) == ["customers.py", "orders.py"]
+        # reports.py imports orders.py but nothing imports it back
+        assert len(cycles[0].back_edges) == 1
+        source, target, _ = cycles[0].back_edges[0]
+        assert {source, target} == set(cycles[0].nodes)
+
+    def test_function_cycle_ignores_self_recursion(self, graph):
+        cycles = find_cycles(graph, "function")
+        assert [sorted(_names(graph, c.nodes)) for c in cycles] == [["ping", "pong"]]
+
+    def test_cycle_order_and_back_edges(self):
+        graph, nodes = _chain(["a", "b", "c"])
+        cycle, = find_cycles(graph, "function")
+        assert cycle.nodes == [n.node_id for n in nodes]
+        assert cycle.back_edges == [(nodes[2].node_id, nodes[0].node_id, "CALLS")]
+        assert cycle.path == [n.node_id for n in nodes + nodes[:1]]
+
+    def test_path_is_the_shortest_loop(self):
+        graph, nodes = _chain(["a", "b", "c", "d"])
+        # A shortcut from b back to a beats the long way round
+        graph = CodeGraph.from_records(nodes, graph.relations + [CodeRelation(nodes[1].node_id, nodes[0].node_id,
+                                                                              "CALLS")])
+        cycle, = find_cycles(graph, "function")
+        assert len(cycle.nodes) == 4
+        assert cycle.path == [nodes[0].node_id, nodes[1].node_id, nodes[0].node_id]
+
+    def test_acyclic_graph(self):
+        graph, _ = _chain(["a", "b", "c"], closing=False)
+        assert find_cycles(graph, "function") == []
+        assert find_cycles(graph, "module") == []
+
+    def test_long_chain_does_not_recurse(self):
+        graph, _ = _chain([f"f{i}" for i in range(5000)])
+        edges = dependency_edges(graph, "function")
+        assert [len(c) for c in strongly_connected_components(edges)] == [5000]
+
+    def test_unknown_kind(self, graph):
+        with pytest.raises(ValueError):
+            find_cycles(graph, "class")
+
+    def test_self_loops(self, graph):
+        cycles = find_cycles(graph, "function", include_self_loops=True)
+        assert [_names(graph, c.nodes) for c in cycles] == [["countdown"], ["ping", "pong"]]
+        assert cycles[0].back_edges == [(cycles[0].nodes[0], cycles[0].nodes[0], "CALLS")]
+        assert cycles[0].path == cycles[0].nodes * 2
+        # No file here imports from itself
+        assert len(find_cycles(graph, "module", include_self_loops=True)) == 1
+
+    def test_min_size(self):
+        graph, _ = _chain(["a", "b", "c"])
+        assert len(find_cycles(graph, "function", min_size=3)) == 1
+        assert find_cycles(graph, "function", min_size=4) == []
+
+    def test_deterministic(self, graph):
+        first = find_cycles(graph, "function", include_self_loops=True)
+        assert first == find_cycles(CodeGraph.from_records(list(graph.nodes.values())[::-1],
+                                                           graph.relations[::-1]), "function",
+                                    include_self_loops=True)
+
+
+class TestModuleCycles:
+
+    @pytest.fixture
+    def sample(self):
+        return CodeGraph.from_directory(CYCLE_SAMPLE)
+
+    def _files(self, node_ids):
+        return [os.path.relpath(node_id[len("file:"):], CYCLE_SAMPLE) for node_id in node_ids]
+
+    def test_three_file_cycle(self, sample):
+        cycles = sample.module_cycles()
+        three, = [cycle for cycle in cycles if len(cycle.nodes) == 3]
+        assert sorted(self._files(three.nodes)) == ["billing.py", "ledger.py", "orders.py"]
+        assert self._files(three.path) == ["billing.py", "ledger.py", "orders.py", "billing.py"]
+        assert len(three.back_edges) == 1
+
+    def test_package_re_export_cycle(self, sample):
+        shop, = [cycle for cycle in sample.module_cycles() if len(cycle.nodes) == 2]
+        assert self._files(shop.path) == [os.path.join("shop", "__init__.py"), os.path.join("shop", "cart.py"),
+                                          os.path.join("shop", "__init__.py")]
+
+    def test_self_import(self):
+        # A file importing one of its own definitions back, e.g. through its package
+        file_node = CodeNode("file:pkg/__init__.py", "File", "__init__.py", "pkg/__init__.py", 0)
+        setup = CodeNode("Function:pkg/__init__.py:setup:4", "Function", "setup", "pkg/__init__.py", 4)
+        graph = CodeGraph.from_records([file_node, setup], [
+            CodeRelation(file_node.node_id, setup.node_id, "CONTAINS"),
+            CodeRelation(file_node.node_id, setup.node_id, "IMPORTS_DEFINITION")])
+
+        cycle, = graph.module_cycles()
+        assert cycle.nodes == [file_node.node_id]
+        assert cycle.path == [file_node.node_id, file_node.node_id]
+        assert find_cycles(graph, "module") == []
+
+
+class TestBaseline:
+
+    def test_cycle_key_is_relative(self, graph, tmp_path):
+        cycle, = find_cycles(graph, "module")
+        assert cycle_key(graph, cycle, str(tmp_path)) == ("customers.py", "orders.py")
+        cycle, = find_cycles(graph, "function")
+        assert cycle_key(graph, cycle, str(tmp_path)) == ("orders.py:ping", "orders.py:pong")
+
+    def test_new_cycles(self, graph, tmp_path):
+        cycles = find_cycles(graph, "function", include_self_loops=True)
+        fresh = new_cycles(graph, cycles, [["orders.py:pong", "orders.py:ping"]], str(tmp_path))
+        assert [_names(graph, c.nodes) for c in fresh] == [["countdown"]]
+
+    def test_cli_fails_on_new_cycles_only(self, graph, tmp_path, capsys):
+        baseline = tmp_path / "baseline.json"
+        args = ["--codebase-path", str(tmp_path), "--kind", "function"]
+        assert cycles_main(args + ["--write-baseline", str(baseline)]) == 0
+        assert cycles_main(args + ["--baseline", str(baseline)]) == 0
+
+        capsys.readouterr()
+        assert cycles_main(args + ["--baseline", str(baseline), "--include-self-loops"]) == 1
+        out = capsys.readouterr().out
+        assert "new cycle of 1: customers.py:countdown" in out
+        assert "new cycle of 2" not in out
+
+    def test_cli_unreadable_baseline(self, graph, tmp_path):
+        assert cycles_main(["--codebase-path", str(tmp_path), "--baseline", str(tmp_path / "missing.json")]) == 2
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -0,0 +1,254 @@
+"""
+Tests for unreferenced symbol (dead code) detection.
+"""
+
+import os
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.ast_parser.parser import CodeNode, CodeRelation
+from src.graph.code_graph import CodeGraph
+from src.graph.dead_code import (EntrypointRules, at_least, dead_code_main, find_unreachable, find_unreferenced,
+                                 find_unused)
+
+
+APP = '''
+import functools
+
+
+def _unused():
+    return 1
+
+
+def helper():
+    return 2
+
+
+def orphan():
+    return 3
+
+
+class Runner:
+    def __init__(self):
+        self.n = helper()
+
+    @functools.lru_cache
+    def cached(self):
+        return 1
+
+
+def main():
+    return helper()
+'''
+
+TEST_APP = '''
+from app import main
+
+
+def test_main():
+    assert main() == 2
+'''
+
+
+@pytest.fixture
+def app_dir(tmp_path):
+    (tmp_path / "app.py").write_text(APP, encoding="utf-8")
+    (tmp_path / "test_app.py").write_text(TEST_APP, encoding="utf-8")
+    return tmp_path
+
+
+def _names(dead):
+    return [(d.name, d.confidence) for d in dead]
+
+
+def _rust_graph():
+    """A trait, an impl of it, a derive, a test and a macro-registered handler."""
+    path = "/repo/src/lib.rs"
+
+    def node(name, kind, line, **properties):
+        return CodeNode(f"{kind}:{path}:{name}:{line}", kind, name, path, line, properties=properties)
+
+    shape = node("Shape", "Interface", 1, visibility="Public")
+    area = node("area", "Method", 2)
+    circle = node("Circle", "Class", 5, visibility="Private", attributes=["derive(Debug, Clone)"])
+    circle_area = node("area", "Method", 8, impl_trait="Shape", visibility="Private")
+    radius = node("radius", "Method", 11, visibility="Private")
+    check = node("check_area", "Function", 15, visibility="Private", attributes=["test"], is_test=True)
+    index = node("index", "Function", 20, visibility="Private", attributes=["get(\"/\")"])
+    nodes = [shape, area, circle, circle_area, radius, check, index]
+    relations = [
+        CodeRelation(shape.node_id, area.node_id, "DEFINES"),
+        CodeRelation(circle.node_id, circle_area.node_id, "DEFINES"),
+        CodeRelation(circle.node_id, radius.node_id, "DEFINES"),
+        CodeRelation(circle.node_id, shape.node_id, "IMPLEMENTS"),
+        CodeRelation(circle_area.node_id, radius.node_id, "CALLS"),
+    ]
+    return CodeGraph.from_records(nodes, relations)
+
+
+class TestFindUnreferenced:
+    def test_python_candidates(self, app_dir):
+        dead = find_unreferenced(CodeGraph.from_directory(str(app_dir)))
+
+        # main and test_main are exempt, __init__ is a dunder, cached is decorated
+        assert _names(dead) == [("_unused", "high"), ("orphan", "low"), ("Runner", "low")]
+        unused = dead[0]
+        assert (unused.kind, unused.line_no) == ("Function", 5)
+        assert unused.file_path.endswith("app.py")
+        assert unused.reason == "private and never referenced"
+
+    def test_allowlist_and_public_exemption(self, app_dir):
+        graph = CodeGraph.from_directory(str(app_dir))
+        assert _names(find_unreferenced(graph, allowlist=["^orph", "^_"])) == [("Runner", "low")]
+        assert _names(find_unreferenced(graph, exempt_public=True)) == [("_unused", "high")]
+
+    def test_trait_methods_derives_and_macros(self):
+        dead = find_unreferenced(_rust_graph())
+
+        # The trait is implemented, its methods and impl are dispatched to,
+        # derive is inert, test and route attributes register their items
+        assert _names(dead) == [("Circle", "high")]
+
+    def test_min_confidence(self, app_dir):
+        dead = find_unreferenced(CodeGraph.from_directory(str(app_dir)))
+        assert _names(at_least(dead, "medium")) == [("_unused", "high")]
+        with pytest.raises(ValueError, match="Unknown confidence"):
+            at_least(dead, "certain")
+
+
+def _sample_graph():
+    """The Rust multi-language sample after cross-file resolution, plus a test and a second file."""
+    def node(name, kind, line, path="/repo/src/sample.rs", **properties):
+        return CodeNode(f"{kind}:{path}:{name}:{line}", kind, name, path, line, properties=properties)
+
+    main = node("main", "Function", 1, path="/repo/src/main.rs", visibility="Private")
+    get_name = node("get_name", "Method", 14, visibility="Public")
+    greet = node("greet", "Function", 27, visibility="Public")
+    add = node("add", "Function", 31, visibility="Private")
+    # Only called from main.rs
+    shout = node("shout", "Function", 35, visibility="Crate")
+    check = node("check_add", "Function", 40, visibility="Private", attributes=["test"], is_test=True)
+    handler = node("handler", "Function", 5, path="/repo/web/api.ts", visibility="Private", export_type="named")
+    relations = [
+        CodeRelation(main.node_id, shout.node_id, "CALLS"),
+        CodeRelation(check.node_id, add.node_id, "CALLS"),
+    ]
+    return CodeGraph.from_records([main, get_name, greet, add, shout, check, handler], relations)
+
+
+class TestFindUnused:
+    def test_private_uncalled_functions(self):
+        # Without the test calling add, and without main calling shout
+        graph = _sample_graph()
+        unused = find_unused(CodeGraph.from_records(
+            [n for n in graph.nodes.values() if n.name != "check_add"], []))
+
+        # greet and get_name are public, main is an entry point, handler is exported
+        assert _names(unused) == [("add", "high"), ("shout", "medium")]
+
+    def test_calls_from_other_files_count(self):
+        assert _names(find_unused(_sample_graph())) == []
+
+    def test_include_public(self):
+        unused = find_unused(_sample_graph(), include_public=True)
+        assert _names(unused) == [("get_name", "low"), ("greet", "low")]
+
+    def test_entrypoint_rules_per_language(self):
+        rules = {"rust": EntrypointRules(names=["^main$"]),
+                 "typescript": EntrypointRules.from_dict({"names": ["^main$"], "exported": False})}
+        unused = find_unused(_sample_graph(), entrypoints=rules)
+        assert _names(unused) == [("check_add", "high"), ("handler", "high")]
+        with pytest.raises(ValueError, match="Unknown entrypoint rule"):
+            EntrypointRules.from_dict({"decorators": []})
+
+    def test_java_annotations_mark_entry_points(self):
+        path = "/repo/src/main/java/Shapes.java"
+        graph = CodeGraph.from_records([
+            CodeNode(f"Method:{path}:{name}:{line}", "Method", name, path, line,
+                     properties={"visibility": "Private", "annotations": annotations})
+            for name, line, annotations in (("setUp", 5, ["BeforeEach"]), ("checkArea", 10, ["Test"]),
+                                            ("helper", 15, ["Override"]))
+        ], [])
+        # A method without a caller may still be called through dynamic dispatch
+        assert _names(find_unused(graph)) == [("helper", "low")]
+
+
+def _crate_graph():
+    """A binary and library crate with a closed group of dead functions."""
+    def node(name, kind, line, file_name="main.rs", **properties):
+        path = f"/repo/src/{file_name}"
+        return CodeNode(f"{kind}:{path}:{name}:{line}", kind, name, path, line, properties=properties)
+
+    api = node("api", "Function", 1, "lib.rs", visibility="Public")
+    helper = node("helper", "Function", 5, "lib.rs", visibility="Private")
+    main = node("main", "Function", 1, visibility="Private")
+    run = node("run", "Function", 5, visibility="Crate")
+    point = node("Point", "Class", 10, visibility="Private")
+    fmt = node("fmt", "Method", 12, visibility="Private", impl_trait="fmt::Display")
+    ping = node("ping", "Function", 20, visibility="Private")
+    pong = node("pong", "Function", 24, visibility="Private")
+    check = node("check", "Function", 30, visibility="Private", attributes=["test"], is_test=True)
+    tested = node("only_tested", "Function", 35, visibility="Private")
+    maybe = node("maybe_called", "Function", 40, visibility="Private")
+    index = node("index", "Function", 45, visibility="Private", attributes=['get("/")'])
+    nodes = [api, helper, main, run, point, fmt, ping, pong, check, tested, maybe, index]
+    relations = [
+        CodeRelation(api.node_id, helper.node_id, "CALLS"),
+        CodeRelation(main.node_id, run.node_id, "CALLS"),
+        CodeRelation(run.node_id, point.node_id, "REFERENCES"),
+        CodeRelation(point.node_id, fmt.node_id, "DEFINES"),
+        CodeRelation(ping.node_id, pong.node_id, "CALLS"),
+        CodeRelation(pong.node_id, ping.node_id, "CALLS"),
+        CodeRelation(check.node_id, tested.node_id, "CALLS"),
+        # A call the parser could not resolve, e.g. through a macro
+        CodeRelation(run.node_id, "unresolved:maybe_called", "CALLS", {"raw_name": "maybe_called"}),
+    ]
+    return CodeGraph.from_records(nodes, relations)
+
+
+class TestFindUnreachable:
+    def test_unreached_symbols(self):
+        dead = find_unreachable(_crate_graph())
+
+        # fmt is reached through its Display type, index through its route
+        # attribute; the check test itself is never reported
+        assert _names(dead) == [("ping", "high"), ("pong", "high"), ("only_tested", "high"),
+                                ("maybe_called", "low")]
+        assert (dead[0].file_path, dead[0].line_no) == ("/repo/src/main.rs", 20)
+        assert "unresolved call" in dead[-1].reason
+
+    def test_tests_as_entry_points(self):
+        names = [d.name for d in find_unreachable(_crate_graph(), include_tests=True)]
+        assert names == ["ping", "pong", "maybe_called"]
+
+    def test_entry_points_are_configurable(self):
+        dead = find_unreachable(_crate_graph(), entry_names=["ping"], public_roots=[])
+        assert "ping" not in [d.name for d in dead]
+        assert {"main", "api", "helper"} <= {d.name for d in dead}
+
+    def test_no_entry_points(self):
+        graph = _crate_graph()
+        without_route = CodeGraph.from_records([n for n in graph.nodes.values() if n.name != "index"], [])
+        with pytest.raises(ValueError, match="No entry points"):
+            find_unreachable(without_route, entry_names=["start"], public_roots=["mod.rs"])
+
+
+class TestDeadCodeCli:
+    def test_exit_status(self, app_dir):
+        assert dead_code_main(["--codebase-path", str(app_dir)]) == 0
+        assert dead_code_main(["--codebase-path", str(app_dir), "--fail-on-dead-code"]) == 1
+        assert dead_code_main(["--codebase-path", str(app_dir), "--fail-on-dead-code",
+                               "--exempt-public", "--allow", "^_"]) == 0
+
+    def test_entry_points_without_rust(self, app_dir):
+        # Only Rust symbols are reported from the entry points
+        assert dead_code_main(["--codebase-path", str(app_dir), "--from-entry-points", "--fail-on-dead-code"]) == 0
+        assert dead_code_main(["--codebase-path", str(app_dir), "--from-entry-points", "--entry", "start"]) == 2
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -0,0 +1,179 @@
+"""
+Tests for doc comment capture: cleaning comment markers, the size cap, and
+the doc and documented properties set by the Python, JavaScript and
+TypeScript parsers.
+Rust doc comments are covered in test_rust_adapter.py.
+"""
+
+import os
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.ast_parser.parser import MAX_DOC_BYTES, cap_doc, clean_doc_comment, clean_docstring
+from src.ast_parser.multi_parser import MultiLanguageParser
+
+
+PYTHON_SOURCE = '''
+class Greeter:
+    """Says hello.
+
+    Keeps no state.
+    """
+
+    def greet(self, name):
+        r"""Greet \\name\\ by name."""
+        return "hi " + name
+
+
+def plain():
+    # A comment, not a docstring
+    return 1
+
+
+def formatted():
+    f"""Not a {docstring}"""
+
+
+def implicit():
+    "Joined " "docstring."
+
+
+def _internal():
+    return 2
+'''
+
+JS_SOURCE = '''
+/**
+ * Adds two numbers.
+ *
+ * @param {number} a first
+ * @param {number} b second
+ */
+export function add(a, b) {
+    return a + b;
+}
+
+/** Doubles a number. */
+const double = (x) => x * 2;
+
+/* A plain block comment */
+function untouched() {}
+
+/** Too far away. */
+
+function detached() {}
+
+class Counter {
+    /**
+     * Current value.
+     */
+    value() {
+        return 0;
+    }
+}
+'''
+
+TS_SOURCE = '''
+/** A user record. */
+export interface User {
+    name: string;
+}
+
+/**
+ * Loads users.
+ */
+export class UserService {}
+'''
+
+
+def _doc(nodes, name):
+    return next(n for n in nodes.values() if n.name == name).properties.get("doc")
+
+
+class TestCleaning:
+
+    def test_line_comments(self):
+        assert clean_doc_comment(["/// Adds two numbers.", "///", "/// # Examples", "///",
+                                  "///     add(1, 2)"]) == "Adds two numbers.\n\n# Examples\n\n    add(1, 2)"
+
+    def test_block_comment_with_stars(self):
+        assert clean_doc_comment(["/**\n * Greets.\n *\n *\n * More.\n */"]) == "Greets.\n\nMore."
+
+    def test_single_line_block(self):
+        assert clean_doc_comment(["/** One line. */"]) == "One line."
+
+    def test_plain_text_and_markers_mixed(self):
+        assert clean_doc_comment(["From an attribute.", "/// From a comment."]) == \
+            "From an attribute.\nFrom a comment."
+
+    def test_empty(self):
+        assert clean_doc_comment(["///", "/** */"]) is None
+
+    def test_docstring_is_dedented(self):
+        assert clean_docstring("Summary.\n\n    Details.\n    ") == "Summary.\n\nDetails."
+
+    def test_cap(self):
+        capped = cap_doc("é" * MAX_DOC_BYTES)
+        assert len(capped.encode("utf-8")) <= MAX_DOC_BYTES
+        assert capped.endswith("…")
+        assert cap_doc("short") == "short"
+
+
+class TestPythonDocstrings:
+
+    @pytest.fixture(params=[False, True], ids=["legacy", "ast-grep"])
+    def nodes(self, request):
+        if request.param:
+            pytest.importorskip("ast_grep_py")
+        parser = MultiLanguageParser(use_ast_grep=request.param, ast_grep_fallback=False)
+        nodes, _ = parser.parse_source("python", PYTHON_SOURCE, "greeter.py")
+        return nodes
+
+    def test_class_and_method(self, nodes):
+        assert _doc(nodes, "Greeter") == "Says hello.\n\nKeeps no state."
+        assert _doc(nodes, "greet") == "Greet \\name\\ by name."
+
+    def test_not_docstrings(self, nodes):
+        assert _doc(nodes, "plain") is None
+        assert _doc(nodes, "formatted") is None
+
+    def test_implicit_concatenation(self, nodes):
+        assert _doc(nodes, "implicit") == "Joined docstring."
+
+    def test_documented_flag_on_public_symbols(self, nodes):
+        documented = {n.name: n.properties.get("documented") for n in nodes.values() if n.node_type != "File"}
+        assert documented == {"Greeter": True, "greet": True, "plain": False, "formatted": False,
+                              "implicit": True, "_internal": None}
+
+
+class TestJSDoc:
+
+    @pytest.fixture(params=[False, True], ids=["tree-sitter", "ast-grep"])
+    def parser(self, request):
+        pytest.importorskip("ast_grep_py" if request.param else "tree_sitter_typescript")
+        return MultiLanguageParser(use_ast_grep=request.param, ast_grep_fallback=False)
+
+    def test_functions_and_methods(self, parser):
+        nodes, _ = parser.parse_source("javascript", JS_SOURCE, "math.js")
+        assert _doc(nodes, "add") == "Adds two numbers.\n\n@param {number} a first\n@param {number} b second"
+        assert _doc(nodes, "double") == "Doubles a number."
+        assert _doc(nodes, "value") == "Current value."
+
+    def test_only_adjacent_jsdoc_blocks(self, parser):
+        nodes, _ = parser.parse_source("javascript", JS_SOURCE, "math.js")
+        assert _doc(nodes, "untouched") is None
+        assert _doc(nodes, "detached") is None
+        assert _doc(nodes, "Counter") is None
+
+    def test_typescript_declarations(self, parser):
+        nodes, _ = parser.parse_source("typescript", TS_SOURCE, "users.ts")
+        assert _doc(nodes, "User") == "A user record."
+        assert _doc(nodes, "UserService") == "Loads users."
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -0,0 +1,100 @@
+"""
+Tests for finding duplicated function bodies: token normalization, exact
+and fuzzy grouping.
+"""
+
+import os
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.ast_parser.parser import CodeNode
+from src.graph.duplicates import (FunctionBody, bounded_distance, find_duplicates, function_bodies,
+                                  normalized_tokens)
+
+
+def _body(name, tokens, file_path="a.rs", line_no=1):
+    return FunctionBody(f"Function:{file_path}:{name}:{line_no}", name, file_path, line_no, tokens)
+
+
+LOOP = "for x in xs { if x > 0 { total += x; } }".split()
+
+
+class TestNormalizedTokens:
+
+    def test_names_literals_and_comments(self):
+        first = normalized_tokens('fn area(w: f64) -> f64 { // width\n  let s = "px"; w * 2.0 }', "rust")
+        second = normalized_tokens('fn size(h: f64) -> f64 {\n  /* height */ let t = "em";\n  h * 3.5\n}', "rust")
+        assert first == second
+        assert first[:3] == ["fn", "ID", "("] and "STR" in first and "NUM" in first
+
+    def test_comment_markers_by_language(self):
+        assert normalized_tokens('s = "a # b"  # note\n', "python") == ["ID", "=", "STR"]
+        assert normalized_tokens("#[inline] x // note\n", "rust") == ["#", "[", "ID", "]", "ID"]
+
+    def test_python_docstrings_are_strings(self):
+        assert normalized_tokens('def f():\n    """Doc\n    more."""\n    pass\n', "python") == \
+            ["def", "ID", "(", ")", ":", "STR", "pass"]
+
+
+class TestBoundedDistance:
+
+    def test_within_and_beyond_the_limit(self):
+        edited = LOOP[:3] + ["y"] + LOOP[4:] + [";"]
+        assert bounded_distance(LOOP, edited, 5) == 2
+        assert bounded_distance(LOOP, edited, 1) is None
+        assert bounded_distance(LOOP, LOOP + ["a"] * 3, 2) is None
+
+
+class TestFindDuplicates:
+
+    def test_exact_groups(self):
+        bodies = [_body("a", LOOP), _body("b", LOOP, "b.rs"), _body("c", LOOP + ["x"]), _body("d", ["x"] * 12)]
+        groups = find_duplicates(bodies, min_tokens=5)
+        assert [[m.name for m in g.members] for g in groups] == [["a", "b"]]
+        assert groups[0].to_dict()["members"][1] == {"id": "Function:b.rs:b:1", "file": "b.rs",
+                                                     "function_name": "b", "line": 1, "tokens": len(LOOP)}
+
+    def test_fuzzy_links_similar_bodies(self):
+        near = LOOP[:-1] + ["return", "total", "}"]
+        bodies = [_body("a", LOOP), _body("b", LOOP, line_no=9), _body("c", near), _body("d", ["x"] * 30)]
+        group, = find_duplicates(bodies, mode="fuzzy", threshold=0.8, min_tokens=5)
+        # Members by file and line: c and a share a line, b is further down
+        assert [m.name for m in group.members] == ["a", "c", "b"]
+        # Two tokens added, giving a body of seventeen
+        assert round(group.similarity, 6) == round(1 - 2 / 17, 6)
+        # A stricter threshold leaves only the exact copies
+        strict, = find_duplicates(bodies, mode="fuzzy", threshold=0.95, min_tokens=5)
+        assert sorted(m.name for m in strict.members) == ["a", "b"]
+
+    def test_short_bodies_and_bad_arguments(self):
+        assert find_duplicates([_body("a", LOOP), _body("b", LOOP)], min_tokens=100) == []
+        with pytest.raises(ValueError, match="between 0 and 1"):
+            find_duplicates([], mode="fuzzy", threshold=2)
+        with pytest.raises(ValueError, match="Unknown mode"):
+            find_duplicates([], mode="ast")
+
+
+class TestFunctionBodies:
+
+    def test_bodies_are_read_by_line_span(self):
+        text = "def f(a):\n    return a + 1\n\n\ndef g(b):\n    return b + 2\n"
+        nodes = [CodeNode("Function:m.py:f:1", "Function", "f", "m.py", 1, 2),
+                 CodeNode("Function:m.py:g:5", "Function", "g", "m.py", 5, 6),
+                 CodeNode("Function:gone.py:h:1", "Function", "h", "gone.py", 1, 2)]
+        reads = []
+
+        def read_text(path):
+            reads.append(path)
+            return text if path == "m.py" else None
+
+        bodies = function_bodies(nodes, read_text)
+        assert [b.name for b in bodies] == ["f", "g"] and bodies[0].tokens == bodies[1].tokens
+        assert reads == ["gone.py", "m.py"]
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
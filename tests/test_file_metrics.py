@@ -0,0 +1,119 @@
+"""
+Tests for line counting and the per-file size metrics.
+"""
+
+import os
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.ast_parser.line_metrics import count_lines
+from src.ast_parser.parser import ASTParser
+from src.graph.file_metrics import file_metrics, match_glob, metrics_summary
+
+
+def _counts(total, blank, comment, code):
+    return {"total_lines": total, "blank_lines": blank, "comment_lines": comment, "code_lines": code}
+
+
+PYTHON = '''# Helpers
+import os
+
+
+def main():
+    """Entry point.
+
+    # not a comment
+    """
+    return os.getcwd()  # trailing comment
+'''
+
+RUST = '''//! Crate docs
+/* a block
+   comment */
+fn main() { /* inline */ }
+
+/// Doc comment
+fn url() -> &'static str { "http://example.com" }
+'''
+
+
+class TestCountLines:
+    def test_python(self):
+        assert count_lines(PYTHON, "python") == _counts(10, 3, 1, 6)
+
+    def test_c_style(self):
+        assert count_lines(RUST, "rust") == _counts(7, 1, 4, 2)
+
+    def test_crlf_is_one_line_ending(self):
+        assert count_lines(PYTHON.replace("\n", "\r\n"), "python") == count_lines(PYTHON, "python")
+        assert count_lines("a = 1\r\n\r\nb = 2\r\n", "python") == _counts(3, 1, 0, 2)
+
+    def test_cr_line_endings(self):
+        assert count_lines("a = 1\r\rb = 2", "python") == _counts(3, 1, 0, 2)
+
+    def test_last_line_without_newline(self):
+        assert count_lines("x = 1\ny = 2", "python") == _counts(2, 0, 0, 2)
+        assert count_lines("", "python") == _counts(0, 0, 0, 0)
+
+    def test_comment_markers_in_strings(self):
+        source = 'const url = "http://x"; // link\nconst s = `\n// inside a template\n`;\n'
+        assert count_lines(source, "javascript") == _counts(4, 0, 0, 4)
+
+    def test_parser_keeps_counts_on_the_file_node(self):
+        nodes, _ = ASTParser().parse_source(PYTHON.replace("\n", "\r\n"), "app.py")
+        file_node = nodes["file:app.py"]
+        assert {k: file_node.properties[k] for k in _counts(0, 0, 0, 0)} == _counts(10, 3, 1, 6)
+
+
+FILES = [
+    {"file_path": "/repo/src/app.py", "properties": _counts(10, 3, 1, 6)},
+    {"file_path": "/repo/src/lib.rs", "properties": _counts(20, 4, 6, 10)},
+    {"file_path": "/repo/old.py", "properties": {}},
+]
+SYMBOLS = [
+    {"type": "Function", "file_path": "/repo/src/app.py", "complexity": 2, "documented": True},
+    {"type": "Method", "file_path": "/repo/src/app.py", "complexity": 5, "documented": False},
+    {"type": "Class", "file_path": "/repo/src/app.py", "documented": True},
+    {"type": "Function", "file_path": "/repo/src/lib.rs", "complexity": 8},
+    {"type": "Field", "file_path": "/repo/src/lib.rs"},
+    {"type": "Function", "file_path": "/repo/old.py"},
+]
+
+
+class TestFileMetrics:
+    def test_per_file_and_totals(self):
+        report = file_metrics(FILES, SYMBOLS)
+
+        assert report["files"][1] == {"file_path": "/repo/src/app.py", **_counts(10, 3, 1, 6),
+                                      "function_count": 2, "struct_count": 1, "avg_complexity": 3.5,
+                                      "doc_coverage_ratio": 0.6667}
+        assert report["files"][0]["total_lines"] is None
+        # lib.rs has no public function or struct with a known doc
+        assert report["files"][2]["doc_coverage_ratio"] is None
+        assert report["totals"] == {"files": 3, **_counts(30, 7, 7, 16),
+                                    "function_count": 4, "struct_count": 1, "avg_complexity": 5.0,
+                                    "doc_coverage_ratio": 0.6667}
+
+    def test_selected_paths(self):
+        report = file_metrics(FILES, SYMBOLS, ["/repo/src/lib.rs"])
+        assert [row["file_path"] for row in report["files"]] == ["/repo/src/lib.rs"]
+        assert report["totals"]["avg_complexity"] == 8.0
+
+    def test_glob(self):
+        paths = [f["file_path"] for f in FILES]
+        assert match_glob(paths, "src/*.py") == ["/repo/src/app.py"]
+        assert match_glob(paths, "*.py") == ["/repo/old.py", "/repo/src/app.py"]
+
+    def test_summary(self):
+        summary = metrics_summary(iter(FILES), iter(SYMBOLS))
+        assert summary["totals"]["files"] == 3
+        assert summary["by_language"]["python"]["function_count"] == 3
+        assert summary["by_language"]["rust"]["code_lines"] == 10
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
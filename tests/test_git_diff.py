@@ -0,0 +1,156 @@
+"""
+Tests for diffing the code graphs of two git commits.
+"""
+
+import os
+import shutil
+import subprocess
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.graph.code_graph import CodeGraph
+from src.graph.git_diff import GitError, changed_files, diff_commits, graph_at_revision, read_sources, resolve_ref
+
+pytestmark = pytest.mark.skipif(shutil.which("git") is None, reason="git is not installed")
+
+
+FIRST = '''
+def helper():
+    return 1
+
+
+def main():
+    return helper()
+'''
+
+# helper moved below main, main changed, extra added
+SECOND = '''
+def main():
+    return helper() + 1
+
+
+def helper():
+    return 1
+
+
+def extra():
+    return main()
+'''
+
+
+def _git(repo, *args):
+    subprocess.run(["git", "-C", str(repo), *args], check=True, capture_output=True)
+
+
+def _commit(repo, files, message):
+    for name, content in files.items():
+        path = repo / name
+        path.parent.mkdir(parents=True, exist_ok=True)
+        if isinstance(content, bytes):
+            path.write_bytes(content)
+        else:
+            path.write_text(content, encoding="utf-8")
+    _git(repo, "add", "-A")
+    _git(repo, "commit", "-q", "-m", message)
+
+
+@pytest.fixture
+def repo(tmp_path):
+    _git(tmp_path, "init", "-q")
+    _git(tmp_path, "config", "user.email", "dev@example.com")
+    _git(tmp_path, "config", "user.name", "Dev")
+    _commit(tmp_path, {"pkg/app.py": FIRST, "pkg/logo.py": b"\x89PNG\x00\x00", "README.md": "# app\n"}, "first")
+    _commit(tmp_path, {"pkg/app.py": SECOND, "pkg/big.py": "x = '%s'\n" % ("a" * 600)}, "second")
+    return tmp_path
+
+
+def _names(nodes):
+    return sorted(n["name"] for n in nodes)
+
+
+class TestDiffCommits:
+    def test_added_and_changed_symbols(self, repo):
+        diff = diff_commits(str(repo), "HEAD~1", "HEAD")
+
+        assert _names(diff.added_nodes) == ["big.py", "extra", "x"]
+        assert diff.removed_nodes == []
+        # main's body changed, helper only moved below it
+        moved, changed = diff.changed_nodes
+        assert (changed["name"], changed["line_no"], changed["before_line_no"]) == ("main", 2, 6)
+        assert set(changed["changes"]) == {"body_hash"}
+        assert (moved["name"], moved["changes"]["span"]) == \
+            ("helper", {"before": {"start_line": 2, "end_line": 3}, "after": {"start_line": 6, "end_line": 7}})
+        calls = [e for e in diff.added_edges if e["type"] == "CALLS"]
+        assert [(e["source"].split(":")[2], e["target"].split(":")[2]) for e in calls] == [("extra", "main")]
+
+    def test_reverse_diff_removes(self, repo):
+        diff = diff_commits(str(repo), "HEAD", "HEAD~1")
+        assert _names(diff.removed_nodes) == ["big.py", "extra", "x"]
+        assert [e["type"] for e in diff.removed_edges if e["type"] == "CALLS"] == ["CALLS"]
+
+    def test_skipped_files_are_noted(self, repo):
+        diff = diff_commits(str(repo), "HEAD~1", "HEAD", max_file_bytes=500)
+        assert diff.skipped == [
+            {"file": "pkg/big.py", "reason": "larger than 500 bytes", "refs": ["HEAD"]},
+            {"file": "pkg/logo.py", "reason": "binary", "refs": ["HEAD~1", "HEAD"]},
+        ]
+        assert "big.py" not in _names(diff.added_nodes)
+
+    def test_only_source_files_are_read(self, repo):
+        sources, skipped = read_sources(str(repo), resolve_ref(str(repo), "HEAD"))
+        assert sorted(sources) == ["pkg/app.py", "pkg/big.py"]
+        assert [note["file"] for note in skipped] == ["pkg/logo.py"]
+
+    def test_unknown_ref(self, repo):
+        with pytest.raises(GitError, match="Not a commit"):
+            diff_commits(str(repo), "no-such-branch")
+
+
+class TestChangedFiles:
+    def test_between_commits_and_in_the_working_tree(self, repo):
+        assert changed_files(str(repo), "HEAD~1", "HEAD") == ["pkg/app.py", "pkg/big.py"]
+        assert changed_files(str(repo), "HEAD") == []
+        (repo / "README.md").unlink()
+        (repo / "pkg" / "app.py").write_text(FIRST, encoding="utf-8")
+        assert changed_files(str(repo), "HEAD") == ["README.md", "pkg/app.py"]
+
+    def test_unknown_ref(self, repo):
+        with pytest.raises(GitError, match="Not a commit"):
+            changed_files(str(repo), "no-such-branch")
+
+
+def _function(graph, name):
+    return next(n for n in graph.nodes.values() if n.node_type == "Function" and n.name == name)
+
+
+class TestGraphAtRevision:
+    def test_callers_at_an_older_commit(self, repo):
+        before, after = CodeGraph.from_git(str(repo), "HEAD~1"), CodeGraph.from_git(str(repo))
+        assert before.callers(_function(before, "main").node_id) == []
+        assert [s.node_id for s in after.callers(_function(after, "main").node_id)] == \
+            [_function(after, "extra").node_id]
+
+    def test_files_of_each_commit(self, repo):
+        before, skipped = graph_at_revision(str(repo), "HEAD~1")
+        # Paths are relative to the repository root; big.py only exists later
+        assert sorted(before.files) == ["pkg/app.py"]
+        assert sorted(CodeGraph.from_git(str(repo), "HEAD").files) == ["pkg/app.py", "pkg/big.py"]
+        assert skipped == [{"file": "pkg/logo.py", "reason": "binary"}]
+
+    def test_ids_match_across_commits(self, repo):
+        first = CodeGraph.from_git(str(repo), resolve_ref(str(repo), "HEAD~1"))
+        again = CodeGraph.from_git(str(repo), "HEAD~1")
+        assert set(first.nodes) == set(again.nodes)
+        assert "file:pkg/app.py" in CodeGraph.from_git(str(repo)).nodes
+
+    def test_unknown_revision(self, repo):
+        with pytest.raises(GitError, match="Not a commit"):
+            CodeGraph.from_git(str(repo), "HEAD~5")
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
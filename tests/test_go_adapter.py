@@ -0,0 +1,187 @@
+"""
+Tests for GoAdapter.
+
+Covers the multi-language sample (structs, interfaces, package-level
+variables, methods attached to their receiver type, multi-value and named
+results, calls and implicit interface satisfaction) and a package split over
+several files, where receivers, callees and satisfied interfaces live in
+other files.
+"""
+
+import os
+import sys
+import json
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+pytest.importorskip("ast_grep_py")
+
+from src.ast_parser.multi_parser import MultiLanguageParser
+from src.ast_parser.adapters.go_adapter import GoAdapter
+from src.graph.code_graph import CodeGraph
+from tests.helpers import edge_names, find_node, node_names
+
+
+FIXTURES = os.path.abspath(os.path.join(os.path.dirname(__file__), "fixtures"))
+SAMPLE_GO = os.path.join(FIXTURES, "multi_lang_sample", "sample.go")
+GO_SAMPLE_DIR = os.path.join(FIXTURES, "go_sample")
+
+
+class TestSampleGo:
+    """sample.go from the multi-language fixtures."""
+
+    @pytest.fixture
+    def parsed(self):
+        return GoAdapter().parse_file(SAMPLE_GO, build_index=True)
+
+    def test_node_count(self, parsed):
+        nodes, _ = parsed
+        # File, Person, Greeter + Greeter.Greet, 5 functions, 4 methods, 2 package vars
+        assert len(nodes) == 15
+        assert node_names(nodes, "Function") == {"NewPerson", "Greet", "Add", "Divide", "Split"}
+        assert node_names(nodes, "Interface") == {"Greeter"}
+        assert node_names(nodes, "GlobalVariable") == {"DefaultName", "MaxAge"}
+
+    def test_receiver_edge(self, parsed):
+        nodes, relations = parsed
+        person = find_node(nodes, "Class", "Person")
+        methods = {nodes[r.target_id].name for r in relations
+                   if r.relation_type == "DEFINES" and r.source_id == person.node_id}
+        assert methods == {"GetName", "SetName", "GetAge", "Greet"}
+        assert find_node(nodes, "Method", "GetName").properties["method_of"] == "Person"
+
+    def test_pointer_and_value_receivers(self, parsed):
+        nodes, _ = parsed
+        receivers = {n.name: n.properties["receiver"] for n in nodes.values()
+                     if n.node_type == "Method" and n.properties["method_of"] == "Person"}
+        assert receivers == {"GetName": "pointer", "SetName": "pointer", "GetAge": "pointer",
+                             "Greet": "value"}
+
+    def test_interface_methods(self, parsed):
+        nodes, relations = parsed
+        greeter = find_node(nodes, "Interface", "Greeter")
+        declared = {nodes[r.target_id].name for r in relations
+                    if r.relation_type == "DEFINES" and r.source_id == greeter.node_id}
+        assert declared == {"Greet"}
+        assert greeter.properties["method_set"] == ["Greet() string"]
+        assert greeter.properties["embeds"] == []
+
+    def test_method_set_lists_embedded_interfaces(self):
+        source = ("package io\n\ntype ReadCloser interface {\n\tio.Reader\n"
+                  "\tClose() error\n\tRead(p []byte)   (n int, err error)\n}\n")
+        nodes, _ = GoAdapter().parse_source(source, "io.go")
+        read_closer = find_node(nodes, "Interface", "ReadCloser")
+        assert read_closer.properties["method_set"] == ["Close() error", "Read(p []byte) (n int, err error)"]
+        assert read_closer.properties["embeds"] == ["io.Reader"]
+
+    def test_package_variables(self, parsed):
+        nodes, _ = parsed
+        file_node = find_node(nodes, "File", "sample.go")
+        assert file_node.properties["package"] == "main"
+        assert find_node(nodes, "GlobalVariable", "DefaultName").properties["kind"] == "var"
+        assert find_node(nodes, "GlobalVariable", "MaxAge").properties["kind"] == "const"
+
+    def test_visibility_follows_capitalization(self, parsed):
+        nodes, _ = parsed
+        assert {n.properties["visibility"] for n in nodes.values() if n.node_type != "File"} == {"Public"}
+        unexported, _ = GoAdapter().parse_source("package main\n\nfunc add(a, b int) int { return a + b }\n",
+                                                 "add.go")
+        assert find_node(unexported, "Function", "add").properties["visibility"] == "Private"
+
+    def test_multiple_and_named_results(self, parsed):
+        nodes, _ = parsed
+        divide = find_node(nodes, "Function", "Divide")
+        assert divide.properties["returns"] == ["int", "int"]
+        assert "named_results" not in divide.properties
+        assert json.loads(divide.properties["args"]) == [{"name": "a", "type": "int"},
+                                                         {"name": "b", "type": "int"}]
+
+        split = find_node(nodes, "Function", "Split")
+        assert split.properties["returns"] == ["int", "int", "error"]
+        assert split.properties["named_results"] == ["x", "y", "err"]
+        assert find_node(nodes, "Method", "SetName").properties["returns"] == []
+
+    def test_complexity(self):
+        source = ("package main\n\nfunc sign(x int, strict bool) int {\n"
+                  "\tif x > 0 && strict {\n\t\treturn 1\n\t}\n"
+                  "\tswitch {\n\tcase x < 0:\n\t\treturn -1\n\tdefault:\n\t}\n"
+                  "\tfor i := 0; i < x; i++ {\n\t}\n\treturn 0\n}\n")
+        nodes, _ = GoAdapter().parse_source(source, "sign.go")
+        # if, &&, the case, default and the for loop
+        assert find_node(nodes, "Function", "sign").properties["complexity"] == 6
+        assert find_node(GoAdapter().parse_source("package main\n\nfunc add(a, b int) int { return a + b }\n",
+                                              "add.go")[0], "Function", "add").properties["complexity"] == 1
+
+    def test_calls(self, parsed):
+        nodes, relations = parsed
+        calls = edge_names(nodes, relations, "CALLS")
+        # Split -> Add directly, Person.Greet -> GetName through the receiver
+        assert ("Split", "Add") in calls
+        assert ("Greet", "GetName") in calls
+        # Neither append nor the builtin len becomes an edge
+        assert all(target not in ("len", "append") for _, target in calls)
+
+    def test_person_satisfies_greeter(self):
+        graph = CodeGraph(use_ast_grep=True, ast_grep_languages=['go'], ast_grep_fallback=False)
+        graph.add_file(SAMPLE_GO)
+        satisfies = [r for r in graph.relations if r.relation_type == "SATISFIES"]
+        assert [(graph.nodes[r.source_id].name, graph.nodes[r.target_id].name) for r in satisfies] \
+            == [("Person", "Greeter")]
+        assert satisfies[0].properties["methods"] == ["Greet"]
+        # fmt.Printf stays visible as an unresolved call
+        assert any(r.relation_type == "CALLS" and r.properties.get("raw_name") == "fmt.Printf"
+                   for r in graph.relations)
+
+
+class TestCrossFileReceivers:
+    """Methods in stack_ops.go on the generic Stack declared in stack.go."""
+
+    @pytest.fixture
+    def parsed(self):
+        parser = MultiLanguageParser(
+            use_ast_grep=True,
+            ast_grep_languages=['go'],
+            ast_grep_fallback=False
+        )
+        return parser.parse_directory(GO_SAMPLE_DIR, build_index=True)
+
+    def test_methods_attach_to_type_in_other_file(self, parsed):
+        nodes, relations = parsed
+        stack = find_node(nodes, "Class", "Stack")
+        methods = {nodes[r.target_id].name for r in relations
+                   if r.relation_type == "DEFINES" and r.source_id == stack.node_id}
+        assert methods == {"Push", "Len", "Pop"}
+
+    def test_grouped_variables(self, parsed):
+        nodes, _ = parsed
+        assert node_names(nodes, "GlobalVariable") == {"ErrEmpty", "minCap", "maxCap"}
+        assert find_node(nodes, "GlobalVariable", "maxCap").properties["type"] == "int"
+
+    def test_named_results_on_generic_receiver(self, parsed):
+        nodes, _ = parsed
+        pop = find_node(nodes, "Method", "Pop")
+        assert pop.properties["returns"] == ["T", "bool"]
+        assert pop.properties["named_results"] == ["item", "ok"]
+
+    def test_satisfaction_across_files(self, parsed):
+        nodes, relations = parsed
+        # Sized only needs Len; Drainer also needs Drain, which Stack lacks
+        assert edge_names(nodes, relations, "SATISFIES") == {("Stack", "Sized")}
+
+    def test_calls_across_files(self, parsed):
+        nodes, relations = parsed
+        calls = edge_names(nodes, relations, "CALLS")
+        assert ("Size", "clamp") in calls
+        assert ("Pop", "Len") in calls
+        # s.Len() on the Sized parameter goes to the interface method
+        size = find_node(nodes, "Function", "Size")
+        sized_len = next(n for n in nodes.values()
+                         if n.name == "Len" and n.properties.get("method_of") == "Sized")
+        assert any(r.source_id == size.node_id and r.target_id == sized_len.node_id
+                   for r in relations if r.relation_type == "CALLS")
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
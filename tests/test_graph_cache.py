@@ -0,0 +1,122 @@
+"""
+Tests for the SQLite graph cache used to skip parsing on later starts.
+
+Uses the legacy Python parser so no optional parsing backends are needed.
+"""
+
+import logging
+import os
+import sqlite3
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.graph.cache import GraphCache, SCHEMA_VERSION
+from src.graph.code_graph import CodeGraph
+from src.graph.incremental import reindex
+from tests.helpers import graph_shape
+
+
+SAMPLE = '''
+def greet(name):
+    return "Hello, " + name
+
+
+class Greeter:
+    def greet_all(self, names):
+        return [greet(n) for n in names]
+'''
+
+APP = '''
+from sample import greet
+
+
+def welcome():
+    greet("world")
+'''
+
+
+def _write(directory, name, content, mtime_ns=1_000_000_000):
+    path = os.path.join(str(directory), name)
+    with open(path, "w", encoding="utf-8") as f:
+        f.write(content)
+    os.utime(path, ns=(mtime_ns, mtime_ns))
+    return path
+
+
+@pytest.fixture
+def codebase(tmp_path):
+    root = tmp_path / "src"
+    root.mkdir()
+    sample = _write(root, "sample.py", SAMPLE)
+    _write(root, "app.py", APP)
+    cache = GraphCache(str(tmp_path / "graph.db"))
+    cache.save(CodeGraph.from_directory(str(root)))
+    return root, sample, cache
+
+
+class TestGraphCache:
+
+    def test_round_trip_restores_the_graph_without_parsing(self, codebase):
+        root, _, cache = codebase
+        graph = CodeGraph()
+        result = reindex(graph, str(root), cache.load())
+
+        assert (result.stats.skipped, result.stats.reparsed, result.stats.added) == (2, 0, 0)
+        assert graph_shape(graph) == graph_shape(CodeGraph.from_directory(str(root)))
+
+    def test_second_pass_edges_keep_their_owner(self, codebase):
+        root, sample, cache = codebase
+        stored = cache.load()
+        app = os.path.join(str(root), "app.py")
+        assert any(r.relation_type == "CALLS" for r in stored[app].state.resolved_relations)
+        assert stored[sample].size == len(SAMPLE.encode("utf-8"))
+
+    def test_only_changed_files_are_reparsed(self, codebase):
+        root, sample, cache = codebase
+        # Same mtime, different size: the key changed
+        _write(root, "sample.py", SAMPLE.replace("Hello", "Hi"))
+
+        graph = CodeGraph()
+        result = reindex(graph, str(root), cache.load())
+
+        assert (result.stats.skipped, result.stats.reparsed) == (1, 1)
+        assert graph_shape(graph) == graph_shape(CodeGraph.from_directory(str(root)))
+
+    def test_save_replaces_previous_contents(self, codebase):
+        root, sample, cache = codebase
+        os.remove(sample)
+        cache.save(CodeGraph.from_directory(str(root)))
+        assert list(cache.load()) == [os.path.join(str(root), "app.py")]
+
+    def test_corrupt_cache_falls_back_to_full_parse(self, codebase, caplog):
+        root, _, cache = codebase
+        with open(cache.path, "wb") as f:
+            f.write(b"not a database" * 100)
+
+        with caplog.at_level(logging.WARNING, logger="src.graph.cache"):
+            assert cache.load() == {}
+        assert "unreadable graph cache" in caplog.text
+
+        # The next save rebuilds it
+        cache.save(CodeGraph.from_directory(str(root)))
+        assert len(cache.load()) == 2
+
+    def test_schema_version_mismatch_is_ignored(self, codebase, caplog):
+        _, _, cache = codebase
+        with sqlite3.connect(cache.path) as conn:
+            conn.execute("UPDATE meta SET value = ? WHERE key = 'schema_version'", (str(SCHEMA_VERSION + 1),))
+
+        with caplog.at_level(logging.WARNING, logger="src.graph.cache"):
+            assert cache.load() == {}
+        assert "schema version" in caplog.text
+
+    def test_missing_cache_is_empty(self, tmp_path):
+        assert GraphCache(str(tmp_path / "none.db")).load() == {}
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
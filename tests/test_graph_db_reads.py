@@ -0,0 +1,195 @@
+"""
+Tests for the Neo4j read queries behind the graph MCP tools.
+
+The Neo4j driver is replaced by a recorded session: each test queues the
+rows the server would return, and the queries and parameters the database
+sent are checked along with what it makes of those rows. The same tools
+run against a live server in test_mcp_graph_tools when NEO4J_TEST_URI is set.
+"""
+
+import os
+import sys
+from unittest.mock import MagicMock, patch
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.neo4j_storage.graph_db import Neo4jDatabase
+
+
+class Record(dict):
+    """A result row, read by key or through ``data()`` like a driver record."""
+
+    def data(self):
+        return dict(self)
+
+
+class Result(list):
+    """The rows of one query; iterating it fetches them one at a time."""
+
+    def __init__(self, rows, fetched):
+        super().__init__(Record(row) for row in rows)
+        self.fetched = fetched
+
+    def __iter__(self):
+        for record in super().__iter__():
+            self.fetched.append(record)
+            yield record
+
+    def data(self):
+        return [record.data() for record in self]
+
+
+@pytest.fixture
+def db():
+    """Neo4jDatabase over a recorded session.
+
+    ``db.results`` holds the rows to return, one list per query, ``db.queries``
+    the (query, parameters) sent and ``db.fetched`` the rows read so far.
+    """
+    with patch("src.neo4j_storage.graph_db.GraphDatabase") as graph_database:
+        database = Neo4jDatabase(uri="bolt://test", user="u", password="p")
+    database.results, database.queries, database.fetched = [], [], []
+
+    def run(query, params=None):
+        database.queries.append((" ".join(query.split()), params))
+        return Result(database.results.pop(0) if database.results else [], database.fetched)
+
+    session = MagicMock()
+    session.run.side_effect = run
+    graph_database.driver.return_value.session.return_value.__enter__.return_value = session
+    database.driver = graph_database.driver.return_value
+    return database
+
+
+def _summary(node_id, name, node_type="Function", file_path="app.py", line_no=1):
+    return {"id": node_id, "name": name, "type": node_type, "file_path": file_path, "line_no": line_no}
+
+
+class TestSymbols:
+    def test_qualified_member(self, db):
+        db.results.append([_summary("f:Person.get_name", "get_name", "Method")])
+        assert [n["id"] for n in db.find_nodes_by_symbol("Person.get_name", limit=5)] == ["f:Person.get_name"]
+        [(query, params)] = db.queries
+        assert params == {"symbol": "Person.get_name", "owner": "Person", "member": "get_name",
+                          "fuzzy": False, "limit": 5}
+        assert "MATCH (o:Base {name: $owner})-[:DEFINES]->(n)" in query
+        assert "toLower(n.name) CONTAINS toLower($symbol)" in query
+        assert query.endswith("ORDER BY n.file_path, n.line_no LIMIT $limit")
+
+    def test_bare_name(self, db):
+        db.find_nodes_by_symbol("main", fuzzy=True)
+        [(_, params)] = db.queries
+        assert (params["owner"], params["member"], params["fuzzy"], params["limit"]) == ("", "main", True, 20)
+
+    def test_symbols_drop_unset_flags(self, db):
+        db.results.append([
+            dict(_summary("f:main", "main"), owner=None, project=None, deprecated=False),
+            dict(_summary("f:old", "old"), owner="Legacy", project="core", deprecated=True),
+        ])
+        symbols = list(db.get_symbols(["Function", "Method"]))
+        assert [sorted(set(s) - {"id", "name", "type", "file_path", "line_no"}) for s in symbols] == [
+            ["owner"], ["deprecated", "owner", "project"]]
+        [(query, params)] = db.queries
+        assert params == {"types": ["Function", "Method"]}
+        assert "WHERE NOT n:File AND NOT n:Unresolved AND n.name IS NOT NULL" in query
+        assert "OPTIONAL MATCH (owner:Base)-[:DEFINES]->(n)" in query
+
+    def test_symbols_of_every_type(self, db):
+        list(db.get_symbols())
+        assert db.queries[0][1] == {"types": None}
+
+
+class TestCallEdges:
+    @pytest.mark.parametrize("direction, match, caller", [
+        ("callers", "MATCH (other:Base)-[r:CALLS]->(n:Base) WHERE n.id IN $ids", "other"),
+        ("callees", "MATCH (n:Base)-[r:CALLS]->(other:Base) WHERE n.id IN $ids", "n"),
+    ])
+    def test_direction(self, db, direction, match, caller):
+        list(db.get_call_edges(("f:helper",), direction))
+        [(query, params)] = db.queries
+        assert params == {"ids": ["f:helper"]}
+        assert query.startswith(match)
+        assert f"RETURN {caller}.id AS caller_id" in query
+        assert f"{caller}.file_path AS call_file" in query
+        assert query.endswith("ORDER BY caller_id, callee_id, call_line, call_site")
+
+    def test_unknown_direction(self, db):
+        with pytest.raises(ValueError, match="Unknown call direction"):
+            list(db.get_call_edges(["f:helper"], "sideways"))
+        assert db.queries == []
+
+    def test_edges_are_streamed(self, db):
+        db.results.append([{"caller_id": f"f:{i}", "callee_id": "f:helper", "node": _summary(f"f:{i}", str(i)),
+                            "call_file": "app.py", "call_line": i, "call_site": "helper()", "await_call": None}
+                           for i in range(10)])
+        edges = db.get_call_edges(["f:helper"])
+        assert db.queries == []
+        assert next(edges)["caller_id"] == "f:0"
+        assert len(db.fetched) == 1
+        edges.close()
+
+
+class TestUsages:
+    def test_references(self, db):
+        row = {"target_id": "f:helper", "relation": "REFERENCES", "access": "Read",
+               "node": _summary("f:main", "main"), "line_no": 3, "snippet": "helper"}
+        db.results.append([row])
+        assert list(db.get_references(["f:helper"])) == [row]
+        [(query, params)] = db.queries
+        assert params == {"ids": ["f:helper"]}
+        assert query.startswith("MATCH (src:Base)-[r:REFERENCES|CALLS]->(n:Base) WHERE n.id IN $ids")
+        assert query.endswith("ORDER BY node.file_path, line_no, node.id, target_id, relation, access")
+
+    def test_usages_of_given_types(self, db):
+        list(db.get_usages(["c:Shape"], ("EXTENDS", "INSTANTIATES")))
+        [(query, params)] = db.queries
+        assert params == {"ids": ["c:Shape"], "types": ["EXTENDS", "INSTANTIATES"]}
+        assert "WHERE n.id IN $ids AND type(r) IN $types" in query
+        assert "coalesce(r.line_no, src.line_no) AS line_no" in query
+
+
+class TestImplementations:
+    def test_implementations(self, db):
+        row = {"target_id": "t:Display", "trait_path": "fmt::Display", "node": _summary("s:Person", "Person"),
+               "methods": [], "blanket": False, "for_type": None, "bounds": None}
+        db.results.append([row])
+        assert db.get_implementations(["t:Display"]) == [row]
+        [(query, params)] = db.queries
+        assert params == {"ids": ["t:Display"]}
+        assert query.startswith("MATCH (impl:Base)-[r:IMPLEMENTS]->(t:Base) WHERE t.id IN $ids")
+        assert "OPTIONAL MATCH (impl)-[:DEFINES]->(m:Base)-[:IMPLEMENTS]->(:Base)<-[:DEFINES]-(t)" in query
+
+    def test_implementors_by_path(self, db):
+        db.find_implementors("std::fmt::Display")
+        [(query, params)] = db.queries
+        assert params == {"name": "std::fmt::Display", "base": "Display"}
+        assert "WHERE NOT impl:Method" in query
+        assert "OR $name ENDS WITH ('::' + r.trait_path)" in query
+
+
+class TestFiles:
+    def test_file_symbols(self, db):
+        db.results += [[_summary("f:main", "main")], [{"source": "file:app.py", "target": "f:main"}]]
+        outline = db.get_file_symbols("app.py")
+        assert outline == {"nodes": [_summary("f:main", "main")],
+                           "defines": [{"source": "file:app.py", "target": "f:main"}]}
+        (nodes_query, nodes_params), (defines_query, defines_params) = db.queries
+        assert nodes_params == defines_params == {"path": "app.py"}
+        assert nodes_query.startswith("MATCH (n:Base {file_path: $path}) WHERE NOT n:File")
+        assert defines_query.startswith("MATCH (a:Base)-[:DEFINES]->(b:Base {file_path: $path})")
+
+    def test_relative_path(self, db):
+        db.results.append([{"file_path": "src/app.py"}, {"file_path": "tests/app.py"}])
+        assert db.find_file_paths("./app.py") == ["src/app.py", "tests/app.py"]
+        assert db.queries[0][1] == {"path": "./app.py", "suffix": "/app.py"}
+
+    def test_exact_path_wins(self, db):
+        db.results.append([{"file_path": "app.py"}, {"file_path": "src/app.py"}])
+        assert db.find_file_paths("app.py") == ["app.py"]
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -0,0 +1,402 @@
+"""
+Tests for the DOT, GraphML, JSON Lines and JSON exporters.
+
+The exported text is parsed back with a small DOT reader, an XML parser
+for GraphML or json for JSON Lines, so the node and edge counts can be
+compared with the graph.
+When Graphviz is installed the output is also rendered to check that dot
+accepts it without warnings. The export command is run on the
+multi_lang_sample fixture to lock in the format.
+"""
+
+import io
+import json
+import os
+import re
+import shutil
+import subprocess
+import sys
+from xml.etree import ElementTree
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.ast_parser.parser import CodeNode, CodeRelation
+from src.graph.code_graph import CodeGraph
+from src.graph.export import (DotExporter, DotOptions, ExportTooLargeError, GraphMlExporter, JsonExporter,
+                               JsonLinesExporter, export_main, parse_node_kinds, select_subgraph)
+from src.graph.module_graph import ModuleGraph
+
+
+FIXTURE_DIR = os.path.join(os.path.dirname(os.path.abspath(__file__)), "fixtures", "multi_lang_sample")
+FIXTURE_PY = os.path.join(FIXTURE_DIR, "sample.py")
+
+APP = '''
+from sample import greet
+
+
+def welcome():
+    greet("world")
+'''
+
+TOKEN = re.compile(r'"(?:[^"\\]|\\.)*"|->|[{}\[\];=,]|[A-Za-z_][A-Za-z0-9_]*')
+
+
+def parse_dot(text):
+    """Return (node ids, edges) declared in DOT text written one statement per line."""
+    nodes, edges = set(), []
+    for line in text.splitlines():
+        tokens = TOKEN.findall(line)
+        if not tokens or not tokens[0].startswith('"'):
+            continue
+        if len(tokens) > 2 and tokens[1] == "->":
+            edges.append((tokens[0], tokens[2]))
+        elif tokens[1] == "[":
+            nodes.add(tokens[0])
+    return nodes, edges
+
+
+GRAPHML = "{http://graphml.graphdrawing.org/xmlns}"
+
+
+def parse_graphml(text):
+    """Return ({node id: attributes}, [(source, target, attributes)]) of a GraphML document, by attribute name."""
+    root = ElementTree.fromstring(text.encode("utf-8"))
+    names = {key.get("id"): key.get("attr.name") for key in root.iter(f"{GRAPHML}key")}
+
+    def data(element):
+        return {names[d.get("key")]: d.text for d in element.iter(f"{GRAPHML}data")}
+
+    nodes = {node.get("id"): data(node) for node in root.iter(f"{GRAPHML}node")}
+    edges = [(edge.get("source"), edge.get("target"), data(edge)) for edge in root.iter(f"{GRAPHML}edge")]
+    return nodes, edges
+
+
+def graphml_types(text):
+    """Return {(element, attribute name): attr.type} of the keys a GraphML document declares."""
+    root = ElementTree.fromstring(text.encode("utf-8"))
+    return {(key.get("for"), key.get("attr.name")): key.get("attr.type") for key in root.iter(f"{GRAPHML}key")}
+
+
+def parse_jsonl(text):
+    """Return (node records, edge records) of a JSON Lines export."""
+    records = [json.loads(line) for line in text.splitlines()]
+    return [r for r in records if r["type"] == "node"], [r for r in records if r["type"] == "edge"]
+
+
+@pytest.fixture
+def graph(tmp_path):
+    with open(FIXTURE_PY, encoding="utf-8") as f:
+        (tmp_path / "sample.py").write_text(f.read(), encoding="utf-8")
+    (tmp_path / "app.py").write_text(APP, encoding="utf-8")
+    return CodeGraph.from_directory(str(tmp_path))
+
+
+class TestDotExporter:
+
+    def test_round_trip_counts(self, graph):
+        dot = DotExporter().to_dot(graph)
+        nodes, edges = parse_dot(dot)
+
+        assert len(nodes) == len(graph.nodes)
+        assert len(edges) == len(graph.relations)
+        assert dot.startswith('digraph "codebase" {')
+        assert dot.count("{") == dot.count("}")
+
+    def test_clusters_group_nodes_by_file(self, graph):
+        dot = DotExporter().to_dot(graph)
+        assert dot.count("subgraph \"cluster_") == len(graph.files)
+
+        flat = DotExporter().to_dot(graph, DotOptions(cluster_by_file=False))
+        assert "subgraph" not in flat
+        assert parse_dot(flat)[0] == parse_dot(dot)[0]
+
+    def test_file_glob_and_node_types_select_a_subgraph(self, graph):
+        opts = DotOptions(file_glob="*/sample.py", node_types=["Class", "Method"])
+        nodes, edges = parse_dot(DotExporter().to_dot(graph, opts))
+
+        expected_nodes, expected_relations = select_subgraph(graph, opts)
+        assert len(nodes) == len(expected_nodes) > 0
+        assert len(edges) == len(expected_relations) > 0
+        assert all(graph.nodes[n].node_type in ("Class", "Method") for n in expected_nodes)
+        assert all(graph.nodes[n].file_path.endswith("sample.py") for n in expected_nodes)
+
+    def test_edge_labels_toggle(self, graph):
+        assert 'label="DEFINES"' in DotExporter().to_dot(graph)
+        assert 'label="DEFINES"' not in DotExporter().to_dot(graph, DotOptions(edge_labels=False))
+
+    def test_edges_are_styled_by_type(self, graph):
+        dot = DotExporter().to_dot(graph, DotOptions(edge_labels=False))
+        calls = [r for r in graph.relations if r.relation_type == "CALLS"]
+        assert calls
+        for relation in calls:
+            assert f'"{relation.source_id}" -> "{relation.target_id}" [color="blue"];' in dot
+        assert "arrowhead=diamond" in dot
+
+    def test_edge_types_and_path_prefix(self, graph, tmp_path):
+        opts = DotOptions(edge_types=["CALLS"])
+        _, relations = select_subgraph(graph, opts)
+        assert relations and all(r.relation_type == "CALLS" for r in relations)
+
+        nodes, _ = select_subgraph(graph, DotOptions(path_prefix=str(tmp_path / "app.py")))
+        assert nodes and {n.name for n in nodes.values() if n.node_type == "Function"} == {"welcome"}
+        # Prefixes match whole path components, so "app" is not "app.py"
+        assert select_subgraph(graph, DotOptions(path_prefix=str(tmp_path / "app")))[0] == {}
+        assert len(select_subgraph(graph, DotOptions(path_prefix=str(tmp_path)))[0]) \
+            == len([n for n in graph.nodes.values() if n.file_path])
+
+    def test_max_nodes_fails_loudly(self, graph):
+        with pytest.raises(ExportTooLargeError, match="more than the limit of 2"):
+            DotExporter().to_dot(graph, DotOptions(max_nodes=2))
+        assert DotExporter().to_dot(graph, DotOptions(max_nodes=len(graph.nodes)))
+
+    def test_ids_are_escaped(self):
+        node = CodeNode('Function:a "b".py:f:1', "Function", 'say "hi"\\', 'a "b".py', 1)
+        graph = CodeGraph.from_records([node], [CodeRelation(node.node_id, node.node_id, "CALLS")])
+        dot = DotExporter().to_dot(graph)
+
+        assert '"Function:a \\"b\\".py:f:1"' in dot
+        nodes, edges = parse_dot(dot)
+        assert len(nodes) == 1 and len(edges) == 1
+
+    @pytest.mark.skipif(shutil.which("dot") is None, reason="Graphviz is not installed")
+    def test_graphviz_renders_without_warnings(self, graph):
+        result = subprocess.run(["dot", "-Tsvg"], input=DotExporter().to_dot(graph),
+                                capture_output=True, text=True)
+        assert result.returncode == 0
+        assert result.stderr == ""
+
+
+class TestGraphMlExporter:
+
+    def test_round_trip_counts(self, graph):
+        nodes, edges = parse_graphml(GraphMlExporter().render(graph))
+
+        assert set(nodes) == set(graph.nodes)
+        assert len(edges) == len(graph.relations)
+        assert all(source in nodes and target in nodes for source, target, _ in edges)
+
+    def test_node_and_edge_attributes(self, graph):
+        nodes, edges = parse_graphml(GraphMlExporter().render(graph))
+        welcome = next(n for n in graph.nodes.values() if n.name == "welcome")
+
+        assert nodes[welcome.node_id].items() >= {"kind": "Function", "name": "welcome",
+                                                  "file": welcome.file_path, "line": str(welcome.line_no)}.items()
+        calls = [attributes for source, _, attributes in edges if source == welcome.node_id]
+        assert calls == [{"kind": "CALLS", "call_site": 'greet("world")', "original_name": "greet",
+                          "line_no": "6"}]
+
+    def test_properties_are_typed(self, graph):
+        text = GraphMlExporter().render(graph)
+        nodes, _ = parse_graphml(text)
+        welcome = next(n for n in graph.nodes.values() if n.name == "welcome")
+
+        types = graphml_types(text)
+        assert types[("node", "complexity")] == "long"
+        assert types[("node", "documented")] == "boolean"
+        assert types[("node", "qualified_name")] == "string"
+        assert types[("edge", "line_no")] == "long"
+        assert (nodes[welcome.node_id]["documented"], nodes[welcome.node_id]["args"]) == ("false", "[]")
+
+    def test_mixed_property_types(self):
+        nodes = [CodeNode(f"Function:a.py:f{i}:{i}", "Function", f"f{i}", "a.py", i, properties=properties)
+                 for i, properties in enumerate([{"size": 1, "tag": 1}, {"size": 2.5, "tag": "x"},
+                                                 {"kind": "shadowed", "labels": ["a", "b"]}])]
+        text = GraphMlExporter().render(CodeGraph.from_records(nodes, []))
+        types = graphml_types(text)
+
+        # Numbers widen to double, anything else to string; a property cannot replace kind
+        assert (types[("node", "size")], types[("node", "tag")]) == ("double", "string")
+        assert types[("node", "kind")] == "string"
+        parsed, _ = parse_graphml(text)
+        assert parsed[nodes[2].node_id]["kind"] == "Function"
+        assert json.loads(parsed[nodes[2].node_id]["labels"]) == ["a", "b"]
+
+    def test_networkx_reads_typed_attributes(self, graph):
+        networkx = pytest.importorskip("networkx")
+        loaded = networkx.read_graphml(io.BytesIO(GraphMlExporter().render(graph).encode("utf-8")))
+        welcome = next(n for n in graph.nodes.values() if n.name == "welcome")
+
+        assert (loaded.number_of_nodes(), loaded.number_of_edges()) == (len(graph.nodes), len(graph.relations))
+        attributes = loaded.nodes[welcome.node_id]
+        assert (attributes["complexity"], attributes["documented"], attributes["line"]) == (1, False, 5)
+
+    def test_filters_apply(self, graph):
+        opts = DotOptions(file_glob="*/sample.py", node_types=["Class", "Method"])
+        nodes, edges = parse_graphml(GraphMlExporter().render(graph, opts))
+        expected_nodes, expected_relations = select_subgraph(graph, opts)
+
+        assert set(nodes) == set(expected_nodes)
+        assert len(edges) == len(expected_relations) > 0
+        with pytest.raises(ExportTooLargeError):
+            GraphMlExporter().render(graph, DotOptions(max_nodes=2))
+
+    def test_text_is_escaped(self):
+        node = CodeNode('Function:a "b".py:f:1', "Function", 'say "<hi>" & \x00bye', 'a "b".py', 1)
+        graph = CodeGraph.from_records([node], [CodeRelation(node.node_id, node.node_id, "CALLS",
+                                                             {"call_site": "f('<&>')"})])
+        nodes, edges = parse_graphml(GraphMlExporter().render(graph))
+
+        # NUL cannot appear in XML at all, so it is dropped
+        assert nodes[node.node_id]["name"] == 'say "<hi>" & bye'
+        assert edges == [(node.node_id, node.node_id, {"kind": "CALLS", "call_site": "f('<&>')"})]
+
+    def test_generic_names_are_escaped(self):
+        node = CodeNode("Class:lib.rs:Vec<Person>:3", "Class", "Vec<Person>", "lib.rs", 3,
+                        properties={"type": "HashMap<&str, Vec<Person>>"})
+        graph = CodeGraph.from_records([node], [CodeRelation(node.node_id, node.node_id, "USES_TYPE")])
+        nodes, edges = parse_graphml(GraphMlExporter().render(graph))
+
+        assert (nodes[node.node_id]["name"], nodes[node.node_id]["type"]) == \
+            ("Vec<Person>", "HashMap<&str, Vec<Person>>")
+        assert [(source, target) for source, target, _ in edges] == [(node.node_id, node.node_id)]
+
+    def test_writes_as_it_goes(self, graph):
+        class Recorder(io.StringIO):
+            writes = 0
+
+            def write(self, text):
+                Recorder.writes += 1
+                return super().write(text)
+
+        stream = Recorder()
+        GraphMlExporter().write(graph, stream)
+        # One element at a time, not one write of the finished document
+        assert Recorder.writes > len(graph.nodes) + len(graph.relations)
+        assert parse_graphml(stream.getvalue())[0].keys() == graph.nodes.keys()
+
+    def test_module_graph(self, graph):
+        modules = ModuleGraph.from_code_graph(graph)
+        nodes, edges = parse_graphml(GraphMlExporter().render(modules))
+
+        assert set(nodes) == set(modules.files)
+        assert {(s, t, a["weight"]) for s, t, a in edges} == {(e.source, e.target, str(e.weight))
+                                                              for e in modules.edges}
+        assert all(a["kind"] == "DEPENDS_ON" for _, _, a in edges)
+
+
+class TestJsonLinesExporter:
+
+    def test_round_trip_counts(self, graph):
+        nodes, edges = parse_jsonl(JsonLinesExporter().render(graph))
+
+        assert {n["id"] for n in nodes} == set(graph.nodes)
+        assert len(edges) == len(graph.relations)
+        assert {(e["source"], e["target"], e["kind"]) for e in edges} == \
+            {(r.source_id, r.target_id, r.relation_type) for r in graph.relations}
+
+    def test_records_keep_every_property(self, graph):
+        nodes, edges = parse_jsonl(JsonLinesExporter().render(graph))
+        welcome = next(n for n in graph.nodes.values() if n.name == "welcome")
+
+        record, = [n for n in nodes if n["id"] == welcome.node_id]
+        assert (record["kind"], record["name"], record["span"]["start_line"]) == ("Function", "welcome", 5)
+        assert record["properties"] == welcome.properties
+        call, = [e for e in edges if e["source"] == welcome.node_id]
+        assert call["properties"]["call_site"] == 'greet("world")'
+
+    def test_filters_apply(self, graph):
+        opts = DotOptions(file_glob="*/sample.py", node_types=["Class", "Method"], edge_types=["DEFINES"])
+        nodes, edges = parse_jsonl(JsonLinesExporter().render(graph, opts))
+        expected_nodes, expected_relations = select_subgraph(graph, opts)
+
+        assert {n["id"] for n in nodes} == set(expected_nodes)
+        assert len(edges) == len(expected_relations) > 0
+        assert {e["kind"] for e in edges} == {"DEFINES"}
+
+    def test_one_write_per_record(self, graph):
+        stream = io.StringIO()
+        writes = []
+        stream.write = lambda text, write=stream.write: writes.append(text) or write(text)
+        JsonLinesExporter().write(graph, stream)
+        assert len(writes) == 2 * (len(graph.nodes) + len(graph.relations))
+
+    def test_module_graph(self, graph):
+        modules = ModuleGraph.from_code_graph(graph)
+        nodes, edges = parse_jsonl(JsonLinesExporter().render(modules))
+
+        assert [n["id"] for n in nodes] == modules.files
+        assert {(e["source"], e["target"], e["properties"]["weight"]) for e in edges} == \
+            {(e.source, e.target, e.weight) for e in modules.edges}
+
+
+class TestJsonExporter:
+
+    def test_snapshot_round_trips(self, graph):
+        restored = CodeGraph.from_json(JsonExporter().render(graph))
+        assert restored.nodes.keys() == graph.nodes.keys()
+        assert graph.diff(restored).is_empty()
+
+    def test_filters_apply(self, graph):
+        opts = DotOptions(file_glob="*/app.py")
+        restored = CodeGraph.from_json(JsonExporter().render(graph, opts))
+        expected_nodes, expected_relations = select_subgraph(graph, opts)
+        assert restored.nodes.keys() == expected_nodes.keys()
+        assert len(restored.relations) == len(expected_relations) > 0
+
+    def test_module_graph_is_refused(self, graph):
+        with pytest.raises(ValueError, match="module graph"):
+            JsonExporter().render(ModuleGraph.from_code_graph(graph))
+
+
+class TestExportCommand:
+
+    def test_node_kinds(self):
+        assert parse_node_kinds("function,struct") == ["Function", "Class"]
+        assert parse_node_kinds("Method, variable") == ["Method", "Variable", "ClassVariable",
+                                                        "LocalVariable", "GlobalVariable"]
+        with pytest.raises(ValueError):
+            parse_node_kinds("widget")
+
+    def test_fixture_round_trip(self, tmp_path):
+        out = tmp_path / "sample.dot"
+        assert export_main(["--codebase-path", FIXTURE_DIR, "--path", "sample.py",
+                            "--kinds", "class,method,function", "--edges", "CALLS,DEFINES",
+                            "-o", str(out)]) == 0
+        dot = out.read_text(encoding="utf-8")
+        nodes, edges = parse_dot(dot)
+
+        assert '"Person\\nClass"' in dot
+        assert '"get_name\\nMethod"' in dot
+        assert '"greet\\nFunction"' in dot
+        assert all(node.startswith(('"Class:', '"Method:', '"Function:')) for node in nodes)
+        assert all(FIXTURE_PY in node for node in nodes)
+
+        labels = {(s.split(":")[-2], t.split(":")[-2]) for s, t in edges}
+        assert ("find_home", "greet") in labels
+        assert ("Person", "get_name") in labels
+        assert 'label="CALLS", color="blue"' in dot
+        assert 'label="CONTAINS"' not in dot
+
+    def test_graphml_format(self, tmp_path):
+        out = tmp_path / "sample.graphml"
+        assert export_main(["--codebase-path", FIXTURE_DIR, "--path", "sample.py", "--format", "graphml",
+                            "--kinds", "class,method,function", "-o", str(out)]) == 0
+        nodes, _ = parse_graphml(out.read_text(encoding="utf-8"))
+
+        assert {"Person", "get_name", "greet"} <= {attributes["name"] for attributes in nodes.values()}
+        assert all(attributes["file"] == FIXTURE_PY for attributes in nodes.values())
+
+    @pytest.mark.parametrize("export_format", ["graphml", "jsonl"])
+    def test_fixture_counts_match_the_graph(self, tmp_path, export_format):
+        out = tmp_path / f"fixture.{export_format}"
+        assert export_main(["--codebase-path", FIXTURE_DIR, "--format", export_format, "-o", str(out)]) == 0
+        graph = CodeGraph.from_directory(FIXTURE_DIR, use_ast_grep=False,
+                                         ast_grep_languages=["python", "javascript", "typescript"])
+        text = out.read_text(encoding="utf-8")
+        nodes, edges = parse_graphml(text) if export_format == "graphml" else parse_jsonl(text)
+
+        assert (len(nodes), len(edges)) == (len(graph.nodes), len(graph.relations)) != (0, 0)
+
+    def test_max_nodes_exit_code(self, tmp_path, capsys):
+        out = tmp_path / "sample.dot"
+        assert export_main(["--codebase-path", FIXTURE_DIR, "--path", "sample.py",
+                            "--max-nodes", "1", "-o", str(out)]) == 1
+        assert "more than the limit of 1" in capsys.readouterr().err
+        assert not out.exists()
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -0,0 +1,132 @@
+"""
+Tests for impact analysis: what transitively depends on changed symbols.
+"""
+
+import os
+import sys
+import time
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.ast_parser.parser import CodeNode, CodeRelation
+from src.graph.code_graph import UNRESOLVED_PREFIX, CodeGraph
+from src.graph.impact import impact_of, symbols_in_files
+
+
+def _node(name, file_path="lib.rs", node_type="Function", line_no=1):
+    return CodeNode(f"{node_type}:{file_path}:{name}:{line_no}", node_type, name, file_path, line_no)
+
+
+@pytest.fixture
+def graph():
+    # Config is used as a type by load and parse, which main calls both of;
+    # Settings implements Config's trait and a test imports main
+    config = _node("Config", node_type="Class")
+    get = _node("get", node_type="Method", line_no=3)
+    load = _node("load", line_no=10)
+    parse = _node("parse", "parse.rs", line_no=1)
+    main = _node("main", "main.rs", line_no=1)
+    cli = _node("cli", "bin.rs", line_no=1)
+    settings = _node("Settings", "settings.rs", "Class")
+    test = CodeNode("file:tests/it.rs", "File", "it.rs", "tests/it.rs", 0)
+    nodes = [config, get, load, parse, main, cli, settings, test]
+    relations = [
+        CodeRelation(config.node_id, get.node_id, "DEFINES"),
+        CodeRelation(load.node_id, config.node_id, "USES_TYPE"),
+        CodeRelation(parse.node_id, get.node_id, "CALLS"),
+        CodeRelation(main.node_id, load.node_id, "CALLS"),
+        CodeRelation(main.node_id, parse.node_id, "CALLS"),
+        CodeRelation(cli.node_id, main.node_id, "CALLS"),
+        CodeRelation(settings.node_id, config.node_id, "IMPLEMENTS"),
+        CodeRelation(test.node_id, main.node_id, "IMPORTS_RUST"),
+        CodeRelation(main.node_id, main.node_id, "CALLS"),
+        CodeRelation(main.node_id, UNRESOLVED_PREFIX + "Config", "USES_TYPE"),
+    ]
+    return CodeGraph.from_records(nodes, relations)
+
+
+def _affected(report):
+    return {s.name: s.depth for s in report.symbols}
+
+
+class TestImpactOf:
+
+    def test_dependents_by_depth(self, graph):
+        report = impact_of(graph, ["Class:lib.rs:Config:1"])
+        # parse calls a method Config defines, so it depends on Config
+        assert _affected(report) == {"load": 1, "parse": 1, "Settings": 1, "main": 2, "cli": 3, "it.rs": 3}
+        assert [s.name for s in report.symbols if s.direct] == ["load", "Settings", "parse"]
+
+    def test_diamond_lists_each_symbol_once(self, graph):
+        report = impact_of(graph, ["Class:lib.rs:Config:1"])
+        assert [s.name for s in report.symbols].count("main") == 1
+        main = next(s for s in report.symbols if s.name == "main")
+        assert [(step["name"], step["relation"]) for step in main.path] == \
+            [("Config", None), ("load", "USES_TYPE"), ("main", "CALLS")]
+
+    def test_depth_limit_and_relation_types(self, graph):
+        assert _affected(impact_of(graph, ["Class:lib.rs:Config:1"], max_depth=1)) == \
+            {"load": 1, "parse": 1, "Settings": 1}
+        assert _affected(impact_of(graph, ["Function:main.rs:main:1"], relation_types=["IMPORTS"])) == \
+            {"it.rs": 1}
+
+    def test_frontier_where_the_depth_stopped(self, graph):
+        report = impact_of(graph, ["Class:lib.rs:Config:1"], max_depth=1)
+        # main depends on load and parse; Settings has no dependents
+        assert report.frontier == ["Function:lib.rs:load:10", "Function:parse.rs:parse:1"]
+        assert report.to_dict()["truncated"] is True
+        assert impact_of(graph, ["Class:lib.rs:Config:1"]).frontier == []
+
+    def test_counts_survive_truncation(self, graph):
+        listed = impact_of(graph, ["Class:lib.rs:Config:1"]).to_dict(limit=2)
+        assert (listed["total"], listed["direct"], listed["transitive"], listed["truncated"]) == (6, 3, 3, True)
+        assert sum(len(f["symbols"]) for f in listed["files"]) == 2
+
+    def test_unknown_seeds_are_ignored(self, graph):
+        report = impact_of(graph, ["Function:missing.rs:gone:1"])
+        assert (report.seeds, report.symbols) == ([], [])
+
+    def test_dependency_paths(self, graph):
+        report = impact_of(graph, ["Class:lib.rs:Config:1"])
+        # main is reached through load and through parse, and passes both routes on
+        assert {s.name: s.paths for s in report.symbols} == \
+            {"load": 1, "parse": 1, "Settings": 1, "main": 2, "cli": 2, "it.rs": 2}
+        assert impact_of(graph, ["Class:lib.rs:Config:1"], max_depth=2).symbols[-1].paths == 2
+
+    def test_large_graph(self):
+        # A chain of 200 layers of 100 functions, each calling two in the layer below
+        nodes = [_node(f"f{layer}_{i}", f"m{layer}.rs", line_no=i + 1) for layer in range(200) for i in range(100)]
+        relations = [CodeRelation(nodes[(layer + 1) * 100 + i].node_id, nodes[layer * 100 + (i + k) % 100].node_id,
+                                  "CALLS") for layer in range(199) for i in range(100) for k in (0, 1)]
+        graph = CodeGraph.from_records(nodes, relations)
+        start = time.perf_counter()
+        report = impact_of(graph, [nodes[0].node_id], max_depth=10)
+        assert time.perf_counter() - start < 2.0
+        assert len(report.symbols) == len({s.id for s in report.symbols}) == sum(range(2, 12))
+
+
+class TestChangedFiles:
+
+    def test_files_ranked_by_paths(self, graph):
+        seeds = symbols_in_files(graph, ["lib.rs"])
+        assert sorted(seeds) == ["Class:lib.rs:Config:1", "Function:lib.rs:load:10", "Method:lib.rs:get:3"]
+        ranked = impact_of(graph, seeds).to_ranked_dict()
+        # load is a seed now, so main depends directly on lib.rs
+        assert [(f["file_path"], f["impact"], f["paths"]) for f in ranked["files"]] == [
+            ("main.rs", "direct", 2), ("bin.rs", "transitive", 2), ("tests/it.rs", "transitive", 2),
+            ("parse.rs", "direct", 1), ("settings.rs", "direct", 1)]
+        assert ranked["direct_files"] == ["main.rs", "parse.rs", "settings.rs"]
+        assert ranked["transitive_files"] == ["bin.rs", "tests/it.rs"]
+        assert [s["name"] for s in ranked["symbols"]][:3] == ["main", "cli", "it.rs"]
+
+    def test_limit_keeps_file_counts(self, graph):
+        ranked = impact_of(graph, symbols_in_files(graph, ["lib.rs"])).to_ranked_dict(limit=1)
+        assert (ranked["total"], len(ranked["symbols"]), len(ranked["files"]), ranked["truncated"]) == (5, 1, 5, True)
+        assert ranked["symbols"][0]["file_path"] == "main.rs"
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
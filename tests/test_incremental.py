@@ -0,0 +1,214 @@
+"""
+Tests for incremental re-indexing from stored file states.
+
+Uses the legacy Python parser so no optional parsing backends are needed.
+"""
+
+import json
+import os
+import sys
+from unittest.mock import MagicMock
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.ast_parser.diagnostics import ParseDiagnostics
+from src.graph.code_graph import CodeGraph, FileState, relation_key
+from src.graph.incremental import StoredFile, reindex
+from tests.helpers import graph_shape
+
+
+FIXTURE_PY = os.path.join(os.path.dirname(os.path.abspath(__file__)),
+                          "fixtures", "multi_lang_sample", "sample.py")
+
+APP = '''
+from sample import greet
+
+
+def welcome():
+    greet("world")
+'''
+
+
+def _write(directory, name, content, mtime_ns=None):
+    path = os.path.join(str(directory), name)
+    with open(path, "w", encoding="utf-8") as f:
+        f.write(content)
+    if mtime_ns is not None:
+        os.utime(path, ns=(mtime_ns, mtime_ns))
+    return path
+
+
+def _stored(graph):
+    """What a previous run would have saved, round-tripped through JSON."""
+    return {
+        path: StoredFile(path, state.content_hash, state.mtime_ns,
+                         FileState.from_dict(json.loads(json.dumps(state.to_dict()))))
+        for path, state in graph.files.items()
+    }
+
+
+@pytest.fixture
+def codebase(tmp_path):
+    with open(FIXTURE_PY, encoding="utf-8") as f:
+        sample_source = f.read()
+    sample = _write(tmp_path, "sample.py", sample_source, mtime_ns=1_000_000_000)
+    app = _write(tmp_path, "app.py", APP, mtime_ns=1_000_000_000)
+    return tmp_path, sample, app, sample_source
+
+
+class TestFileState:
+
+    def test_round_trip(self, codebase):
+        root, sample, _, _ = codebase
+        state = CodeGraph.from_directory(str(root)).files[sample]
+        restored = FileState.from_dict(json.loads(json.dumps(state.to_dict())))
+
+        assert set(restored.nodes) == set(state.nodes)
+        assert [relation_key(r) for r in restored.owned_relations()] == \
+            [relation_key(r) for r in state.owned_relations()]
+        assert restored.pending_imports == state.pending_imports
+
+    def test_file_node_carries_hash_and_mtime(self, codebase):
+        root, sample, _, _ = codebase
+        state = CodeGraph.from_directory(str(root)).files[sample]
+        file_node = state.nodes[f"file:{sample}"]
+        assert file_node.properties["content_hash"] == state.content_hash
+        assert file_node.properties["mtime_ns"] == 1_000_000_000
+
+
+class TestReindex:
+
+    def test_unchanged_tree_parses_nothing(self, codebase):
+        root, _, _, _ = codebase
+        previous = CodeGraph.from_directory(str(root))
+
+        graph = CodeGraph()
+        result = reindex(graph, str(root), _stored(previous))
+
+        assert (result.stats.skipped, result.stats.reparsed, result.stats.deleted) == (2, 0, 0)
+        assert result.deltas == []
+        assert graph_shape(graph) == graph_shape(previous)
+
+    def test_changed_file_is_reparsed_and_callers_go_dangling(self, codebase):
+        root, sample, _, sample_source = codebase
+        stored = _stored(CodeGraph.from_directory(str(root)))
+        _write(root, "sample.py", sample_source.replace("def greet(", "def shout("),
+               mtime_ns=2_000_000_000)
+
+        graph = CodeGraph()
+        result = reindex(graph, str(root), stored)
+
+        assert (result.stats.skipped, result.stats.reparsed) == (1, 1)
+        [delta] = result.deltas
+        # app.py was not parsed, but its call into greet was re-resolved
+        assert any(r.properties.get("raw_name") == "sample.greet" for r in delta.added_relations)
+        assert graph_shape(graph) == graph_shape(CodeGraph.from_directory(str(root)))
+
+    def test_touched_file_with_same_contents_is_skipped(self, codebase):
+        root, sample, _, sample_source = codebase
+        stored = _stored(CodeGraph.from_directory(str(root)))
+        _write(root, "sample.py", sample_source, mtime_ns=3_000_000_000)
+
+        graph = CodeGraph()
+        result = reindex(graph, str(root), stored)
+
+        assert result.stats.skipped == 2
+        assert result.touched_files == [sample]
+        assert graph.files[sample].mtime_ns == 3_000_000_000
+        assert graph.nodes[f"file:{sample}"].properties["mtime_ns"] == 3_000_000_000
+
+    def test_added_and_deleted_files(self, codebase):
+        root, sample, _, _ = codebase
+        stored = _stored(CodeGraph.from_directory(str(root)))
+        os.remove(sample)
+        _write(root, "extra.py", "def extra():\n    pass\n")
+
+        graph = CodeGraph()
+        result = reindex(graph, str(root), stored)
+
+        assert (result.stats.added, result.stats.deleted, result.stats.skipped) == (1, 1, 1)
+        assert f"file:{sample}" in result.deltas[0].removed_nodes
+        assert graph_shape(graph) == graph_shape(CodeGraph.from_directory(str(root)))
+
+    def test_files_without_stored_state_are_rebuilt(self, codebase):
+        root, sample, app, _ = codebase
+        stored = {path: StoredFile(path) for path in (sample, app)}
+
+        graph = CodeGraph()
+        result = reindex(graph, str(root), stored)
+
+        assert sorted(result.stale_files) == sorted([sample, app])
+        assert result.stats.reparsed == 2
+        assert graph_shape(graph) == graph_shape(CodeGraph.from_directory(str(root)))
+
+    def test_threaded_reparse_gives_the_same_deltas(self, codebase):
+        root, sample, app, _ = codebase
+        _write(root, "extra.py", "from sample import greet\n\n\ndef extra():\n    greet()\n")
+        stored = {path: StoredFile(path) for path in (sample, app)}
+
+        serial, threaded = CodeGraph(), CodeGraph()
+        expected = reindex(serial, str(root), dict(stored))
+        result = reindex(threaded, str(root), dict(stored), jobs=4)
+
+        def delta_shape(delta):
+            return (delta.added_nodes, delta.removed_nodes, delta.updated_nodes, delta.files,
+                    [relation_key(r) for r in delta.added_relations],
+                    [relation_key(r) for r in delta.removed_relations])
+
+        assert [delta_shape(d) for d in result.deltas] == [delta_shape(d) for d in expected.deltas]
+        assert threaded.to_json() == serial.to_json()
+
+
+class TestIndexIncremental:
+    """CodebaseKnowledgeGraph._index_incremental against a mocked database."""
+
+    @pytest.fixture
+    def kg(self):
+        from src.main import CodebaseKnowledgeGraph
+        kg = CodebaseKnowledgeGraph.__new__(CodebaseKnowledgeGraph)
+        kg.db = MagicMock()
+        kg.cache = None
+        kg.diagnostics = ParseDiagnostics()
+        kg._generate_embeddings = lambda nodes: None
+        return kg
+
+    @staticmethod
+    def _records(graph):
+        return [{"file_path": path, "content_hash": state.content_hash, "mtime_ns": state.mtime_ns,
+                 "graph_state": json.dumps(state.to_dict())}
+                for path, state in graph.files.items()]
+
+    def test_writes_only_the_changed_file(self, kg, codebase):
+        root, sample, _, sample_source = codebase
+        kg.db.get_file_states.return_value = self._records(CodeGraph.from_directory(str(root)))
+        _write(root, "sample.py", sample_source + "\n\ndef later():\n    pass\n",
+               mtime_ns=2_000_000_000)
+
+        kg._index_incremental(str(root), CodeGraph())
+
+        assert (kg.index_stats.skipped, kg.index_stats.reparsed) == (1, 1)
+        upserted = kg.db.upsert_nodes.call_args[0][0]
+        assert all(n["properties"]["file_path"] == sample for n in upserted)
+        assert any(n["properties"]["name"] == "later" for n in upserted)
+        saved = [s for call in kg.db.set_file_states.call_args_list for s in call[0][0]]
+        assert f"file:{sample}" in {s["id"] for s in saved}
+        kg.db.delete_nodes_by_file.assert_not_called()
+
+    def test_legacy_files_are_dropped_first(self, kg, codebase):
+        root, sample, app, _ = codebase
+        kg.db.get_file_states.return_value = [
+            {"file_path": path, "content_hash": None, "mtime_ns": None, "graph_state": None}
+            for path in (sample, app)
+        ]
+
+        kg._index_incremental(str(root), CodeGraph())
+
+        assert sorted(kg.db.delete_nodes_by_file.call_args[0][0]) == sorted([sample, app])
+        assert kg.index_stats.reparsed == 2
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -0,0 +1,272 @@
+"""
+Tests for JavaAdapter.
+
+Covers tests/fixtures/java_sample, two packages of a small Spring-style
+program: a class extending an abstract class of its package and
+implementing an interface that extends another, an anonymous class in a
+field initializer and a nested builder, a controller carrying annotations
+and importing the model through single-type, wildcard and static imports,
+and a record and a nested enum implementing an interface of the other
+package; sample.java adds @Deprecated members and method calls through
+parameters, locals, fields and an import. this.field accesses are checked
+on the Person of tests/fixtures/multi_lang_sample as well.
+"""
+
+import os
+import sys
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+pytest.importorskip("ast_grep_py")
+
+from src.ast_parser.multi_parser import MultiLanguageParser
+from src.ast_parser.adapters.java_adapter import JavaAdapter
+from tests.helpers import edge_names, find_node, node_names
+
+
+JAVA_SAMPLE_DIR = os.path.abspath(os.path.join(os.path.dirname(__file__), "fixtures", "java_sample"))
+MODEL_DIR = os.path.join(JAVA_SAMPLE_DIR, "com", "example", "model")
+WEB_DIR = os.path.join(JAVA_SAMPLE_DIR, "com", "example", "web")
+DOG_JAVA = os.path.join(MODEL_DIR, "Dog.java")
+CONTROLLER_JAVA = os.path.join(WEB_DIR, "DogController.java")
+SAMPLE_JAVA = os.path.join(WEB_DIR, "sample.java")
+PERSON_JAVA = os.path.abspath(os.path.join(os.path.dirname(__file__), "fixtures", "multi_lang_sample",
+                                           "Sample.java"))
+
+
+def _accesses(nodes, relations, field):
+    """(accessor name, access, line) of the REFERENCES edges into a field."""
+    return sorted((nodes[r.source_id].name, r.properties["access"], r.properties["line_no"]) for r in relations
+                  if r.relation_type == "REFERENCES" and r.target_id == field.node_id)
+
+
+def _calls(nodes, relations, file_path):
+    """(caller, callee's type, callee) of the CALLS edges leaving a file."""
+    return {(nodes[r.source_id].name, nodes[r.target_id].properties.get("method_of"), nodes[r.target_id].name)
+            for r in relations if r.relation_type == "CALLS" and r.source_id in nodes and r.target_id in nodes
+            and nodes[r.source_id].file_path == file_path}
+
+
+def _defined_by(nodes, relations, definer):
+    return {nodes[r.target_id].name for r in relations
+            if r.relation_type == "DEFINES" and r.source_id == definer.node_id}
+
+
+class TestDogFile:
+    """Dog.java on its own."""
+
+    @pytest.fixture
+    def parsed(self):
+        return JavaAdapter().parse_file(DOG_JAVA, build_index=True)
+
+    def test_package(self, parsed):
+        nodes, relations = parsed
+        package = find_node(nodes, "Package", "com.example.model")
+        assert nodes[f"file:{DOG_JAVA}"].properties["package"] == "com.example.model"
+        assert (f"file:{DOG_JAVA}", package.node_id) in {(r.source_id, r.target_id) for r in relations
+                                                         if r.relation_type == "CONTAINS"}
+
+    def test_members(self, parsed):
+        nodes, relations = parsed
+        dog = find_node(nodes, "Class", "Dog")
+        assert _defined_by(nodes, relations, dog) == {
+            "Dog", "sound", "getName", "owner", "compareTo", "BY_NAME", "Builder", "Dog$1",
+        }
+        constructor = next(n for n in nodes.values() if n.node_type == "Method" and n.name == "Dog")
+        assert constructor.properties["method_kind"] == "constructor"
+        assert find_node(nodes, "Field", "owner").properties == {"field_of": "Dog", "visibility": "Private",
+                                                             "type": "String"}
+
+    def test_nested_and_anonymous_classes(self, parsed):
+        nodes, relations = parsed
+        builder = find_node(nodes, "Class", "Builder")
+        assert builder.properties["visibility"] == "Public"
+        assert _defined_by(nodes, relations, builder) == {"name", "build"}
+
+        anonymous = find_node(nodes, "Class", "Dog$1")
+        assert (anonymous.line_no, anonymous.properties["anonymous"], anonymous.properties["base"]) == \
+            (6, True, "Comparator")
+        assert _defined_by(nodes, relations, anonymous) == {"compare"}
+        # Only top-level types are contained by the file
+        contained = {nodes[r.target_id].name for r in relations
+                     if r.relation_type == "CONTAINS" and r.target_id in nodes}
+        assert contained == {"com.example.model", "Dog"}
+
+    def test_field_accesses_through_this(self, parsed):
+        nodes, relations = parsed
+        owner = find_node(nodes, "Field", "owner")
+        assert _accesses(nodes, relations, owner) == [("Dog", "Write", 17)]
+        # The builder's this is the builder; a bare name is not resolved
+        builder_name = find_node(nodes, "Field", "name")
+        assert builder_name.properties["field_of"] == "Builder"
+        assert _accesses(nodes, relations, builder_name) == [("name", "Write", 44)]
+
+    def test_annotations(self, parsed):
+        nodes, _ = parsed
+        assert find_node(nodes, "Method", "sound").properties["annotations"] == ["Override"]
+        assert "annotations" not in find_node(nodes, "Class", "Dog").properties
+
+
+class TestControllerFile:
+    """DogController.java on its own."""
+
+    @pytest.fixture
+    def parsed(self):
+        return JavaAdapter().parse_file(CONTROLLER_JAVA, build_index=True)
+
+    def test_annotations(self, parsed):
+        nodes, _ = parsed
+        assert find_node(nodes, "Class", "DogController").properties["annotations"] == \
+            ["RestController", "RequestMapping"]
+        assert find_node(nodes, "Field", "service").properties["annotations"] == ["Autowired"]
+        # Parameter annotations belong to the parameter
+        assert find_node(nodes, "Method", "find").properties["annotations"] == ["GetMapping"]
+
+    def test_visibility(self, parsed):
+        nodes, _ = parsed
+        visibility = {n.name: n.properties["visibility"] for n in nodes.values()
+                      if n.node_type in ("Class", "Enum", "Method", "Field")}
+        assert visibility["find"] == "Public"
+        assert visibility["service"] == "Private"
+        assert visibility["sorted"] == "Crate"
+        assert visibility["Status"] == "Crate"
+
+    def test_enum_and_local_anonymous_class(self, parsed):
+        nodes, relations = parsed
+        status = find_node(nodes, "Enum", "Status")
+        assert _defined_by(nodes, relations, status) == {"ACTIVE", "RETIRED", "getName"}
+        assert find_node(nodes, "Variant", "ACTIVE").properties["variant_of"] == "Status"
+
+        # The Runnable created inside sorted() belongs to the controller
+        controller = find_node(nodes, "Class", "DogController")
+        assert {"Status", "DogController$1"} <= _defined_by(nodes, relations, controller)
+        assert find_node(nodes, "Class", "DogController$1").properties["base"] == "Runnable"
+
+    def test_imports_are_queued(self):
+        adapter = JavaAdapter()
+        adapter.parse_file(CONTROLLER_JAVA, build_index=True)
+        assert [(p["path"], p["static"], p["wildcard"]) for p in adapter.pending_imports
+                if p["type"] == "IMPORTS_JAVA"] == [
+            ("com.example.model.Dog", False, False),
+            ("com.example.model", False, True),
+            ("com.example.model.Dog.BY_NAME", True, False),
+            ("java.util.List", False, False),
+        ]
+
+
+class TestSampleFile:
+    """sample.java on its own."""
+
+    @pytest.fixture
+    def adapter(self):
+        adapter = JavaAdapter()
+        adapter.parse_file(SAMPLE_JAVA, build_index=True)
+        return adapter
+
+    def test_deprecated(self, adapter):
+        nodes = adapter.nodes
+        assert {n.name for n in nodes.values() if n.properties.get("deprecated")} == {"lookup", "Intake"}
+        assert find_node(nodes, "Method", "lookup").properties["annotations"] == ["Deprecated"]
+        assert "deprecated" not in find_node(nodes, "Method", "run").properties
+
+    def test_calls_within_the_file(self, adapter):
+        # The anonymous Runnable reaches adopt() of its enclosing class
+        assert _calls(adapter.nodes, adapter.relations, SAMPLE_JAVA) == {
+            ("adopt", "Shelter", "lookup"), ("run", "Shelter", "adopt"), ("run", "Intake", "record")}
+
+    def test_other_calls_are_queued(self, adapter):
+        queued = [p for p in adapter.pending_imports if p["type"] in ("CALLS_MEMBER", "CALLS_UNRESOLVED")]
+        # Receivers typed by a local and a parameter, var taking the type it creates, and by fields;
+        # the nested class's calls are collected while its enclosing class is parsed
+        assert [(p.get("type_name"), p.get("member_name"), p["line_no"]) for p in queued
+                if p["type"] == "CALLS_MEMBER"] == [
+            ("Builder", "name", 42), ("Dog", "getName", 42), ("DogService", "find", 20), ("List", "remove", 26)]
+        builder = next(p for p in queued if p.get("type_name") == "Builder")
+        assert (builder["definition"], builder["module_hints"][0]) == ("Dog.Builder", "com.example.model.Dog")
+        assert [p["raw_name"] for p in queued if p["type"] == "CALLS_UNRESOLVED"] == [
+            "builder.name(dog.getName()).build().sound", "builder.name(dog.getName()).build"]
+
+
+class TestPersonFields:
+    """Sample.java of the multi-language sample."""
+
+    def test_name_references(self):
+        nodes, relations = JavaAdapter().parse_file(PERSON_JAVA, build_index=True)
+        name = find_node(nodes, "Field", "name")
+        assert (name.properties["field_of"], name.properties["type"]) == ("Person", "String")
+        assert _accesses(nodes, relations, name) == [("Person", "Write", 10), ("getName", "Read", 15),
+                                                     ("setName", "Write", 19)]
+        assert _accesses(nodes, relations, find_node(nodes, "Field", "age")) == [("Person", "Write", 11),
+                                                                             ("getAge", "Read", 23)]
+
+
+class TestJavaSample:
+    """The whole sample, with imports and supertypes resolved across files."""
+
+    @pytest.fixture
+    def parsed(self):
+        parser = MultiLanguageParser(use_ast_grep=True, ast_grep_languages=['java'], ast_grep_fallback=False)
+        return parser.parse_directory(JAVA_SAMPLE_DIR, build_index=True)
+
+    def test_inheritance(self, parsed):
+        nodes, relations = parsed
+        assert edge_names(nodes, relations, "EXTENDS") == {("Dog", "Animal"), ("Pet", "Named")}
+        implements = {(nodes[r.source_id].name, nodes[r.target_id].name) for r in relations
+                      if r.relation_type == "IMPLEMENTS" and nodes[r.source_id].node_type != "Method"}
+        # Comparable is not in the sample, so it stays unlinked
+        assert implements == {("Dog", "Pet"), ("DogView", "Named"), ("Status", "Named")}
+
+    def test_methods_implement_interface_methods(self, parsed):
+        nodes, relations = parsed
+        named_method = next(n for n in nodes.values()
+                            if n.name == "getName" and n.properties.get("method_of") == "Named")
+        implementers = {n.properties["method_of"] for n in nodes.values()
+                        for r in relations if r.relation_type == "IMPLEMENTS"
+                        and r.source_id == n.node_id and r.target_id == named_method.node_id}
+        assert implementers == {"DogView", "Status"}
+        pet_owner = next(n for n in nodes.values() if n.name == "owner" and n.node_type == "Method"
+                         and n.properties.get("method_of") == "Pet")
+        assert any(r.relation_type == "IMPLEMENTS" and r.target_id == pet_owner.node_id for r in relations)
+
+    def test_imports(self, parsed):
+        nodes, relations = parsed
+        imported = {(nodes[r.target_id].node_type, nodes[r.target_id].name) for r in relations
+                    if r.relation_type == "IMPORTS" and r.source_id == f"file:{CONTROLLER_JAVA}"}
+        # Dog by name (and by its static member), every file of the model package by .*
+        assert imported == {("Class", "Dog"), ("File", "Animal.java"), ("File", "Named.java"),
+                            ("File", "Pet.java"), ("File", "Dog.java")}
+        service_imports = {nodes[r.target_id].name for r in relations
+                           if r.relation_type == "IMPORTS"
+                           and r.source_id == f"file:{os.path.join(WEB_DIR, 'DogService.java')}"}
+        assert service_imports == {"Dog", "Named"}
+
+    def test_calls_resolved_through_imports(self, parsed):
+        nodes, relations = parsed
+        # DogService by the package, Dog and its nested Builder by the import
+        assert _calls(nodes, relations, SAMPLE_JAVA) == {
+            ("adopt", "Shelter", "lookup"), ("run", "Shelter", "adopt"), ("run", "Intake", "record"),
+            ("lookup", "DogService", "find"), ("record", "Builder", "name"), ("record", "Dog", "getName")}
+        assert ("find", "DogService", "find") in _calls(nodes, relations, CONTROLLER_JAVA)
+
+    def test_javadoc(self, parsed):
+        nodes, _ = parsed
+        named = find_node(nodes, "Interface", "Named")
+        assert (named.properties["doc"], named.properties["documented"]) == ("Anything with a name.", True)
+        get_name = next(n for n in nodes.values()
+                        if n.name == "getName" and n.properties.get("method_of") == "Named")
+        assert get_name.properties["documented"] is False
+        # Private and package-private symbols are not flagged
+        assert "documented" not in find_node(nodes, "Field", "service").properties
+        assert "documented" not in find_node(nodes, "Method", "sorted").properties
+
+    def test_packages(self, parsed):
+        nodes, _ = parsed
+        assert {n.name for n in nodes.values() if n.node_type == "Package"} == \
+            {"com.example.model", "com.example.web"}
+        assert node_names(nodes, "Field", os.path.join(WEB_DIR, "DogService.java")) == {"name", "owner"}
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -0,0 +1,220 @@
+"""
+Tests for the LSP gateway: raw JSON-RPC messages go through a pipe to a
+server answering from the python_sample fixture, and the responses are read
+back from another pipe.
+"""
+
+import json
+import os
+import sys
+import threading
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.graph.code_graph import CodeGraph
+from src.graph.shared import SharedGraph
+from src.lsp.server import (INVALID_REQUEST, METHOD_NOT_FOUND, PARSE_ERROR, SERVER_NOT_INITIALIZED, LspServer,
+                            path_to_uri, read_message)
+
+FIXTURE_DIR = os.path.join(os.path.dirname(os.path.abspath(__file__)), "fixtures", "python_sample")
+SHAPES = path_to_uri(os.path.join(FIXTURE_DIR, "shapes.py"))
+BASE = path_to_uri(os.path.join(FIXTURE_DIR, "base.py"))
+
+
+class Client:
+    """An LSP server on a thread, spoken to through two pipes."""
+
+    def __init__(self, graph):
+        requests_read, requests_write = os.pipe()
+        responses_read, responses_write = os.pipe()
+        self._requests = os.fdopen(requests_write, "wb")
+        self._responses = os.fdopen(responses_read, "rb")
+        self.server = LspServer(SharedGraph(graph), FIXTURE_DIR)
+        self.exit_code = None
+
+        def serve():
+            with os.fdopen(requests_read, "rb") as reader, os.fdopen(responses_write, "wb") as writer:
+                self.exit_code = self.server.serve(reader, writer)
+
+        self._thread = threading.Thread(target=serve, daemon=True)
+        self._thread.start()
+        self._next_id = 0
+
+    def send_raw(self, body: bytes) -> None:
+        self._requests.write(b"Content-Length: %d\r\n\r\n" % len(body) + body)
+        self._requests.flush()
+
+    def notify(self, method, params=None):
+        self.send_raw(json.dumps({"jsonrpc": "2.0", "method": method, "params": params}).encode("utf-8"))
+
+    def request(self, method, params=None):
+        self._next_id += 1
+        self.send_raw(json.dumps({"jsonrpc": "2.0", "id": self._next_id, "method": method,
+                                  "params": params}).encode("utf-8"))
+        response = read_message(self._responses)
+        assert response["id"] == self._next_id
+        return response
+
+    def initialize(self, capabilities=None):
+        response = self.request("initialize", {"processId": None, "rootUri": path_to_uri(FIXTURE_DIR),
+                                               "capabilities": capabilities or {}})
+        self.notify("initialized", {})
+        return response
+
+    def close(self):
+        self._requests.close()
+        self._thread.join(5)
+        self._responses.close()
+
+
+@pytest.fixture(scope="module")
+def graph():
+    return CodeGraph.from_directory(FIXTURE_DIR)
+
+
+@pytest.fixture
+def client(graph):
+    client = Client(graph)
+    client.initialize()
+    yield client
+    client.close()
+
+
+def _at(uri, line, character):
+    """Params of a request at a 1-based line and 0-based character."""
+    return {"textDocument": {"uri": uri}, "position": {"line": line - 1, "character": character}}
+
+
+def _lines(locations):
+    return [(os.path.basename(l["uri"]), l["range"]["start"]["line"] + 1) for l in locations]
+
+
+class TestLifecycle:
+
+    def test_initialize_and_shutdown(self, graph):
+        client = Client(graph)
+        result = client.initialize()["result"]
+        assert result["capabilities"] == {"positionEncoding": "utf-16", "definitionProvider": True,
+                                          "referencesProvider": True, "workspaceSymbolProvider": True}
+        assert client.request("shutdown") == {"jsonrpc": "2.0", "id": 2, "result": None}
+        assert client.request("workspace/symbol", {"query": "x"})["error"]["code"] == INVALID_REQUEST
+        client.notify("exit")
+        client.close()
+        assert client.exit_code == 0
+
+    def test_exit_without_shutdown(self, graph):
+        client = Client(graph)
+        client.initialize()
+        client.notify("exit")
+        client.close()
+        assert client.exit_code == 1
+
+    def test_requests_before_initialize(self, graph):
+        client = Client(graph)
+        assert client.request("workspace/symbol", {"query": "x"})["error"]["code"] == SERVER_NOT_INITIALIZED
+        client.close()
+
+    def test_negotiated_encoding(self, graph):
+        client = Client(graph)
+        result = client.initialize({"general": {"positionEncodings": ["utf-32", "utf-16"]}})["result"]
+        assert result["capabilities"]["positionEncoding"] == "utf-32"
+        client.close()
+
+    def test_unknown_method_and_bad_json(self, client):
+        assert client.request("textDocument/hover", _at(SHAPES, 1, 0))["error"]["code"] == METHOD_NOT_FOUND
+        client.send_raw(b"{not json")
+        assert read_message(client._responses)["error"]["code"] == PARSE_ERROR
+        # The server keeps answering after a bad message
+        assert "result" in client.request("workspace/symbol", {"query": "Circle"})
+
+
+class TestDefinition:
+
+    def test_call_in_the_same_file(self, client):
+        # return describe(self)
+        result = client.request("textDocument/definition", _at(SHAPES, 46, 20))["result"]
+        assert result == {"uri": SHAPES, "range": {"start": {"line": 49, "character": 4},
+                                                   "end": {"line": 49, "character": 12}}}
+
+    def test_base_class_in_another_file(self, client):
+        # class Circle(base.Shape, Named):
+        shape = client.request("textDocument/definition", _at(SHAPES, 16, 19))["result"]
+        assert (shape["uri"], shape["range"]["start"]) == (BASE, {"line": 3, "character": 6})
+        named = client.request("textDocument/definition", _at(SHAPES, 16, 26))["result"]
+        assert (named["uri"], named["range"]["start"]["line"]) == (BASE, 8)
+
+    def test_imported_name(self, client):
+        # from base import Named
+        result = client.request("textDocument/definition", _at(SHAPES, 3, 18))["result"]
+        assert (result["uri"], result["range"]["start"]["line"]) == (BASE, 8)
+
+    def test_field_access_and_declaration(self, client):
+        # return self._radius
+        field = client.request("textDocument/definition", _at(SHAPES, 22, 21))["result"]
+        assert (field["uri"], field["range"]["start"]["line"]) == (SHAPES, 17)
+        # class Circle(...): the cursor on a declaration names it
+        circle = client.request("textDocument/definition", _at(SHAPES, 16, 8))["result"]
+        assert circle["range"]["start"] == {"line": 15, "character": 6}
+
+    def test_nothing_there(self, client):
+        assert client.request("textDocument/definition", _at(SHAPES, 5, 0))["result"] is None
+        unknown = path_to_uri(os.path.join(FIXTURE_DIR, "missing.py"))
+        assert client.request("textDocument/definition", _at(unknown, 1, 0))["result"] is None
+
+
+class TestReferences:
+
+    def test_field_reads_and_writes(self, client):
+        params = _at(SHAPES, 22, 21)
+        params["context"] = {"includeDeclaration": False}
+        result = client.request("textDocument/references", params)["result"]
+        assert _lines(result) == [("shapes.py", 18), ("shapes.py", 22), ("shapes.py", 26),
+                                  ("shapes.py", 30), ("shapes.py", 42)]
+        assert result[1]["range"] == {"start": {"line": 21, "character": 20}, "end": {"line": 21, "character": 27}}
+
+    def test_class_used_across_files(self, client):
+        params = _at(BASE, 9, 7)
+        params["context"] = {"includeDeclaration": True}
+        result = client.request("textDocument/references", params)["result"]
+        # The declaration first, then the import and base class list of shapes.py and square.py's alias
+        assert _lines(result) == [("base.py", 9), ("square.py", 5), ("shapes.py", 3), ("shapes.py", 16)]
+        assert result[1]["range"]["start"] == {"line": 4, "character": 22}
+
+
+class TestWorkspaceSymbol:
+
+    def test_prefix_matches_first(self, client):
+        result = client.request("workspace/symbol", {"query": "rad"})["result"]
+        # The property getter and setter, then the field only containing the query
+        assert [(s["name"], s["kind"]) for s in result] == [("radius", 6), ("radius", 6), ("_radius", 8)]
+        assert {s["containerName"] for s in result} == {"shapes.Circle"}
+        assert client.request("workspace/symbol", {"query": "RADIUS"})["result"] == result
+
+    def test_kinds_and_locations(self, client):
+        square, = client.request("workspace/symbol", {"query": "Square"})["result"]
+        assert square["kind"] == 5
+        assert square["location"]["uri"] == path_to_uri(os.path.join(FIXTURE_DIR, "geometry", "square.py"))
+        assert square["location"]["range"]["start"] == {"line": 4, "character": 6}
+        assert square["containerName"] == "square"
+
+
+class TestSharedGraph:
+
+    def test_writer_waits_for_readers(self, graph):
+        shared, order = SharedGraph(graph), []
+        with shared.read():
+            writer = threading.Thread(target=lambda: shared.replace(CodeGraph()) or order.append("replaced"))
+            writer.start()
+            writer.join(0.1)
+            order.append("read")
+        writer.join(5)
+        assert order == ["read", "replaced"]
+        with shared.read() as current:
+            assert current is not graph
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
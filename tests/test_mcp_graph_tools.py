@@ -0,0 +1,1820 @@
+"""Tests for the graph MCP tools, run against a Neo4j server (see NEO4J_TEST_URI) and the SQLite backend."""
+
+import asyncio
+import json
+import os
+import shutil
+import subprocess
+import sys
+from unittest.mock import MagicMock, patch
+from xml.etree import ElementTree
+
+import pytest
+
+sys.path.append(os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+# Keep the real mcp package out of the import path
+sys.modules.setdefault('mcp', MagicMock())
+sys.modules.setdefault('mcp.server', MagicMock())
+sys.modules['mcp.server.fastmcp'] = MagicMock()
+sys.modules['mcp.server.models'] = MagicMock()
+
+from src.mcp import server as server_module  # noqa: E402
+from src.config import DEFAULT_MAX_TRAVERSAL_DEPTH, load_config  # noqa: E402
+from src.graph.code_graph import CodeGraph, content_hash  # noqa: E402
+from src.mcp.streaming import STREAM_LOGGER  # noqa: E402
+from src.neo4j_storage.graph_db import Neo4jDatabase  # noqa: E402
+from src.neo4j_storage.sqlite_db import SQLiteDatabase  # noqa: E402
+
+
+class RecordingMCP:
+    """FastMCP stand-in that keeps registered tools so tests can call them."""
+
+    def __init__(self, *args, **kwargs):
+        self.tools = {}
+        self.resources = {}
+
+    def tool(self, *args, **kwargs):
+        def decorator(func):
+            self.tools[func.__name__] = func
+            return func
+        return decorator
+
+    def prompt(self, *args, **kwargs):
+        return lambda func: func
+
+    def resource(self, uri, *args, **kwargs):
+        def decorator(func):
+            self.resources[uri] = func
+            return func
+        return decorator
+
+
+def _file_paths(nodes, empty_files=()):
+    """Files holding the nodes, plus the indexed files that declare nothing."""
+    return sorted({node["file_path"] for node in nodes if node["type"] != "Unresolved"} | set(empty_files))
+
+
+def _file_node(path, properties=None):
+    return {"id": f"file:{path}", "name": os.path.basename(path), "type": "File", "file_path": path,
+            "line_no": None, **(properties or {})}
+
+
+def _node(name, file_path="app.py", line_no=1, node_type="Function"):
+    return {
+        "id": f"{node_type}:{file_path}:{name}:{line_no}",
+        "name": name,
+        "type": node_type,
+        "file_path": file_path,
+        "line_no": line_no,
+    }
+
+
+HELPER = dict(_node("helper", "util.py", 1), visibility="Public")
+LOAD = dict(_node("load", "app.py", 5), complexity=3)
+MAIN = dict(_node("main", "app.py", 10), complexity=12)
+CLI = _node("cli", "cli.py", 3)
+# recurse() calls itself and ping/pong call each other
+RECURSE = _node("recurse", "loop.py", 1)
+PING = _node("ping", "loop.py", 5)
+PONG = _node("pong", "loop.py", 9)
+
+# The Person struct from the multi-language sample, plus a compound assignment
+NAME = dict(_node("name", "sample.rs", 5, "Field"), visibility="Private")
+AGE = _node("age", "sample.rs", 6, "Field")
+GET_NAME = dict(_node("get_name", "sample.rs", 14, "Method"), complexity=1)
+SET_NAME = _node("set_name", "sample.rs", 18, "Method")
+BIRTHDAY = dict(_node("birthday", "sample.rs", 26, "Method"), complexity=7)
+PERSON = dict(_node("Person", "sample.rs", 4, "Class"), end_line_no=7, visibility="Public",
+              doc="A person with a name and an age.")
+
+# Shape is implemented by Circle next to it and by Square in another file
+SHAPE = _node("Shape", "shapes.rs", 4, "Interface")
+SHAPE_AREA = _node("area", "shapes.rs", 5, "Method")
+CIRCLE = _node("Circle", "shapes.rs", 12, "Class")
+CIRCLE_AREA = _node("area", "shapes.rs", 17, "Method")
+SQUARE = _node("Square", "square.rs", 4, "Class")
+SQUARE_AREA = _node("area", "square.rs", 9, "Method")
+# impl fmt::Display for Person, a trait from a crate that was not indexed
+DISPLAY = {"id": "unresolved:fmt::Display", "name": "fmt::Display", "type": "Unresolved",
+           "file_path": "", "line_no": 0}
+
+NODES = [HELPER, LOAD, MAIN, CLI, RECURSE, PING, PONG, NAME, AGE, GET_NAME, SET_NAME, BIRTHDAY, PERSON,
+         SHAPE, SHAPE_AREA, CIRCLE, CIRCLE_AREA, SQUARE, SQUARE_AREA]
+CALLS = [
+    (LOAD["id"], HELPER["id"], 6, "value = helper()"),
+    (MAIN["id"], LOAD["id"], 11, "data = load()"),
+    (MAIN["id"], HELPER["id"], 12, "helper()"),
+    (CLI["id"], MAIN["id"], 4, "main()"),
+    (RECURSE["id"], RECURSE["id"], 2, "return recurse(n - 1)"),
+    (PING["id"], PONG["id"], 6, "pong()"),
+    (PONG["id"], PING["id"], 10, "ping()"),
+]
+REFERENCES = [
+    (GET_NAME["id"], NAME["id"], "Read", 15, "&self.name"),
+    (SET_NAME["id"], NAME["id"], "Write", 19, "self.name = name;"),
+    (BIRTHDAY["id"], AGE["id"], "ReadWrite", 27, "self.age += 1;"),
+]
+# Listed out of source order on purpose
+DEFINES = [(PERSON["id"], m["id"]) for m in (BIRTHDAY, SET_NAME, GET_NAME, AGE, NAME)] + [
+    (SHAPE["id"], SHAPE_AREA["id"]),
+    (CIRCLE["id"], CIRCLE_AREA["id"]),
+    (SQUARE["id"], SQUARE_AREA["id"]),
+]
+IMPLEMENTS = [
+    (SQUARE["id"], SHAPE["id"], "Shape"),
+    (CIRCLE["id"], SHAPE["id"], "Shape"),
+    (SQUARE_AREA["id"], SHAPE_AREA["id"], None),
+    (CIRCLE_AREA["id"], SHAPE_AREA["id"], None),
+    (PERSON["id"], DISPLAY["id"], "fmt::Display"),
+]
+
+
+def _store(db, nodes, calls, references=(), defines=(), empty_files=(), implements=(), file_properties=None):
+    """Write a graph through the backend's own batches: the nodes, their files and the edges between them."""
+    files = [_file_node(path, (file_properties or {}).get(path)) for path in _file_paths(nodes, empty_files)]
+    # A field's declared type is stored as its type property
+    db.upsert_nodes([{"labels": ["Base", node["type"]],
+                      "properties": {("type" if k == "field_type" else k): v for k, v in node.items() if k != "type"}}
+                     for node in files + list(nodes)])
+    db.batch_create_relationships(
+        [{"start_node_id": a, "end_node_id": b, "type": "CALLS", "properties": {"line_no": line_no, "call_site": site}}
+         for a, b, line_no, site in calls]
+        + [{"start_node_id": a, "end_node_id": b, "type": "REFERENCES",
+            "properties": {"access": access, "line_no": line_no, "call_site": site}}
+           for a, b, access, line_no, site in references]
+        + [{"start_node_id": a, "end_node_id": b, "type": "DEFINES", "properties": {}} for a, b in defines]
+        + [{"start_node_id": a, "end_node_id": b, "type": "IMPLEMENTS",
+            "properties": {"trait_path": path} if path else {}} for a, b, path in implements]
+    )
+    return db
+
+
+def _sqlite_db(*args, **kwargs):
+    """The graph in an in-memory SQLite database."""
+    return _store(SQLiteDatabase(":memory:"), *args, **kwargs)
+
+
+# Neo4j server the neo4j backend runs against, e.g. a container started with
+#   docker run -d -p 7687:7687 -e NEO4J_AUTH=neo4j/password neo4j:5
+# Every test deletes all of its data. Unset, the neo4j backend is skipped.
+NEO4J_TEST_URI = os.environ.get("NEO4J_TEST_URI")
+_neo4j = []
+
+
+def _neo4j_db(*args, **kwargs):
+    """The graph in the test Neo4j server, emptied first."""
+    if not NEO4J_TEST_URI:
+        pytest.skip("NEO4J_TEST_URI is not set")
+    if not _neo4j:
+        db = Neo4jDatabase(uri=NEO4J_TEST_URI, user=os.environ.get("NEO4J_TEST_USER", "neo4j"),
+                           password=os.environ.get("NEO4J_TEST_PASSWORD", "password"))
+        if not db.verify_connection():
+            pytest.skip(f"Neo4j is not reachable at {NEO4J_TEST_URI}")
+        db.create_schema_constraints()
+        _neo4j.append(db)
+    _neo4j[0].clear_database()
+    return _store(_neo4j[0], *args, **kwargs)
+
+
+@pytest.fixture(params=["neo4j", "sqlite"])
+def backend(request):
+    return request.param
+
+
+def _db(backend, *args, **kwargs):
+    """The graph from the arguments of _store, held by the given backend."""
+    return _neo4j_db(*args, **kwargs) if backend == "neo4j" else _sqlite_db(*args, **kwargs)
+
+
+def _make_tools(db, config=None):
+    with patch.object(server_module, 'FastMCP', RecordingMCP), \
+         patch.object(server_module, 'Neo4jDatabase', return_value=db), \
+         patch.object(server_module, 'get_embedding_provider'), \
+         patch.object(server_module, 'CodeEmbedder'):
+        mcp_server = server_module.CodebaseKnowledgeGraphMCP(
+            neo4j_uri="mock_uri", neo4j_user="mock_user", neo4j_password="mock_pass", config=config
+        )
+    return mcp_server.mcp.tools
+
+
+@pytest.fixture
+def tools(backend):
+    return _make_tools(_db(backend, NODES + [DISPLAY], CALLS, REFERENCES, DEFINES,
+                                   empty_files=["src/empty.py"], implements=IMPLEMENTS))
+
+
+def _call(tools, name, **kwargs):
+    return json.loads(asyncio.run(tools[name](**kwargs)))
+
+
+class TestFindCallers:
+    def test_direct_callers_with_call_site(self, tools):
+        result = _call(tools, "find_callers", symbol="helper", depth=1)
+
+        assert result["depth"] == 1
+        assert [t["id"] for t in result["targets"]] == [HELPER["id"]]
+        by_name = {c["name"]: c for c in result["callers"]}
+        assert set(by_name) == {"load", "main"}
+        assert by_name["load"]["calls"] == HELPER["id"]
+        assert by_name["load"]["call_site"] == {
+            "file_path": "app.py", "line_no": 6, "snippet": "value = helper()",
+        }
+        assert all(c["depth"] == 1 for c in result["callers"])
+        # main has a caller of its own, one level further than depth
+        assert result["frontier"] == [MAIN["id"]]
+        assert result["truncated"] is True
+
+    def test_transitive_callers_report_shortest_depth(self, tools):
+        result = _call(tools, "find_callers", symbol="helper", depth=3)
+
+        depths = {c["name"]: c["depth"] for c in result["callers"]}
+        # main calls helper directly, so it is not repeated at depth 2 via load
+        assert depths == {"load": 1, "main": 1, "cli": 2}
+
+    def test_cycles_terminate(self, tools):
+        result = _call(tools, "find_callers", symbol="recurse", depth=10)
+        assert result["callers"] == []
+
+        result = _call(tools, "find_callers", symbol="ping", depth=10)
+        assert [(c["name"], c["depth"]) for c in result["callers"]] == [("pong", 1)]
+
+    def test_depth_defaults_to_the_configured_one(self, tools):
+        assert _call(tools, "find_callers", symbol="helper")["depth"] == DEFAULT_MAX_TRAVERSAL_DEPTH
+        assert _call(tools, "find_callers", symbol="helper", depth=50)["depth"] == 50
+        assert _call(tools, "find_callers", symbol="helper", depth=0)["depth"] == 1
+
+    def test_limit_truncates(self, tools):
+        result = _call(tools, "find_callers", symbol="helper", depth=3, limit=2)
+        assert len(result["callers"]) == 2
+        assert result["truncated"] is True
+
+    def test_configured_depth_can_be_overridden(self, backend):
+        config = load_config(environ={"MAX_TRAVERSAL_DEPTH": "2"})
+        tools = _make_tools(_db(backend, NODES, CALLS, REFERENCES, DEFINES), config)
+
+        assert _call(tools, "find_callers", symbol="helper")["depth"] == 2
+        assert _call(tools, "find_callers", symbol="helper", depth=5)["depth"] == 5
+        assert _call(tools, "call_path", source="cli", target="helper")["max_depth"] == 2
+        assert _call(tools, "impact_of", symbol="helper")["max_depth"] == 2
+        assert _call(tools, "impact_of", symbol="helper", max_depth=5)["max_depth"] == 5
+
+    def test_paginate_from_continues_past_the_frontier(self, tools):
+        first = _call(tools, "find_callers", symbol="helper", depth=1)
+        more = _call(tools, "find_callers", paginate_from=first["frontier"])
+
+        assert [t["id"] for t in more["targets"]] == [MAIN["id"]]
+        assert [(c["name"], c["depth"]) for c in more["callers"]] == [("cli", 1)]
+        assert more["truncated"] is False and more["frontier"] == []
+
+    def test_paginate_from_unknown_node(self, tools):
+        assert "error" in _call(tools, "find_callers", paginate_from=["missing"])
+        assert "error" in _call(tools, "find_callers")
+
+    def test_unknown_symbol(self, tools):
+        result = _call(tools, "find_callers", symbol="missing")
+        assert "error" in result
+
+    def test_qualified_name_picks_one_of_same_named_methods(self, backend):
+        circle_area = dict(CIRCLE_AREA, qualified_name="crate::shapes::Circle::area")
+        square_area = dict(SQUARE_AREA, qualified_name="crate::square::Square::area")
+        tools = _make_tools(_db(backend, [CIRCLE, circle_area, SQUARE, square_area], []))
+
+        assert len(_call(tools, "find_callers", symbol="area")["targets"]) == 2
+        result = _call(tools, "find_callers", symbol="crate::square::Square::area")
+        assert [t["id"] for t in result["targets"]] == [SQUARE_AREA["id"]]
+
+
+class TestDirectCalls:
+    def test_callers_with_call_sites(self, tools):
+        result = _call(tools, "get_callers", symbol="helper")
+
+        assert [(c["name"], c["calls"], c["call_site"]["line_no"]) for c in result["callers"]] == [
+            ("load", HELPER["id"], 6), ("main", HELPER["id"], 12),
+        ]
+        assert result["callers"][0]["call_site"] == {"file_path": "app.py", "line_no": 6, "column": None,
+                                                     "snippet": "value = helper()"}
+        assert result["truncated"] is False
+
+    def test_callees(self, tools):
+        result = _call(tools, "get_callees", symbol="main")
+        assert [(c["name"], c["called_from"], c["call_site"]["snippet"]) for c in result["callees"]] == [
+            ("load", MAIN["id"], "data = load()"), ("helper", MAIN["id"], "helper()"),
+        ]
+
+    def test_recursion_appears_in_both_directions(self, tools):
+        assert [c["id"] for c in _call(tools, "get_callers", symbol="recurse")["callers"]] == [RECURSE["id"]]
+        assert [c["id"] for c in _call(tools, "get_callees", symbol="recurse")["callees"]] == [RECURSE["id"]]
+
+    def test_unresolved_calls_by_raw_name(self, backend):
+        placeholder = {"id": "unresolved:utils.helper", "name": "utils.helper", "type": "Unresolved",
+                       "file_path": "", "line_no": 0}
+        old = _node("old", "legacy.py", 4)
+        tools = _make_tools(_db(backend, [HELPER, LOAD, old, placeholder], [
+            (LOAD["id"], HELPER["id"], 6, "value = helper()"),
+            (old["id"], placeholder["id"], 5, "return utils.helper()"),
+        ]))
+
+        assert [c["name"] for c in _call(tools, "get_callers", symbol="helper")["callers"]] == ["load"]
+        result = _call(tools, "get_callers", symbol="helper", include_unresolved=True)
+        assert [(c["name"], c["unresolved"], c["raw_name"]) for c in result["callers"]] == [
+            ("load", False, None), ("old", True, "utils.helper"),
+        ]
+        assert _call(tools, "get_callees", symbol="old")["callees"] == []
+        [callee] = _call(tools, "get_callees", symbol="old", include_unresolved=True)["callees"]
+        assert (callee["id"], callee["raw_name"]) == (placeholder["id"], "utils.helper")
+
+    def test_limit_and_unknown_symbol(self, tools):
+        result = _call(tools, "get_callers", symbol="helper", limit=1)
+        assert len(result["callers"]) == 1 and result["truncated"] is True
+        assert _call(tools, "get_callees", symbol="nope")["error"] == "Symbol not found: nope"
+
+
+class TestFindReferences:
+    def test_reads_and_writes(self, tools):
+        result = _call(tools, "find_references", symbol_id=NAME["id"])
+
+        tagged = {(r["source"]["name"], r["kind"]) for r in result["references"]}
+        assert tagged == {("get_name", "Read"), ("set_name", "Write")}
+        write = next(r for r in result["references"] if r["kind"] == "Write")
+        assert (write["file_path"], write["line_no"], write["snippet"]) == \
+            ("sample.rs", 19, "self.name = name;")
+
+    def test_compound_assignment_is_read_and_write(self, tools):
+        result = _call(tools, "find_references", symbol_id=AGE["id"])
+        assert sorted(r["kind"] for r in result["references"]) == ["Read", "Write"]
+        assert {r["line_no"] for r in result["references"]} == {27}
+
+    def test_calls_are_tagged(self, tools):
+        result = _call(tools, "find_references", symbol_id=HELPER["id"])
+        assert {(r["source"]["name"], r["kind"]) for r in result["references"]} == \
+            {("load", "Call"), ("main", "Call")}
+
+    def test_unknown_symbol(self, tools):
+        assert "error" in _call(tools, "find_references", symbol_id="Field:missing.rs:x:1")
+
+    def test_public_only_keeps_public_symbols(self, tools):
+        result = _call(tools, "find_references", symbol_id="helper", public_only=True)
+        assert [t["id"] for t in result["targets"]] == [HELPER["id"]]
+        assert len(result["references"]) == 2
+
+    def test_public_only_skips_private_symbols(self, tools):
+        assert "error" in _call(tools, "find_references", symbol_id=NAME["id"], public_only=True)
+        # Nodes without a recorded visibility are not public either
+        assert "error" in _call(tools, "find_references", symbol_id=AGE["id"], public_only=True)
+
+
+class TestGetCallGraph:
+    def test_both_directions(self, tools):
+        result = _call(tools, "get_call_graph", symbol="load")
+
+        assert result["depth"] == DEFAULT_MAX_TRAVERSAL_DEPTH
+        assert result["roots"] == [LOAD["id"]]
+        depths = {n["name"]: n["depth"] for n in result["nodes"]}
+        # callers get negative depths, callees positive ones
+        assert depths == {"load": 0, "main": -1, "cli": -2, "helper": 1}
+        edges = {(e["source"], e["target"]) for e in result["edges"]}
+        assert edges == {
+            (MAIN["id"], LOAD["id"]),
+            (CLI["id"], MAIN["id"]),
+            (LOAD["id"], HELPER["id"]),
+        }
+
+    def test_callees_edges_carry_call_site(self, tools):
+        result = _call(tools, "get_call_graph", symbol="main", direction="callees")
+
+        sites = {(e["source"], e["target"]): e["call_site"] for e in result["edges"]}
+        assert sites[(MAIN["id"], HELPER["id"])] == {"file_path": "app.py", "line_no": 12, "snippet": "helper()"}
+        # helper is reached directly and through load; both edges are kept
+        assert (LOAD["id"], HELPER["id"]) in {(e["source"], e["target"]) for e in result["edges"]}
+
+    def test_cycle_edges_are_reported_once(self, tools):
+        result = _call(tools, "get_call_graph", symbol="ping", direction="callees", depth=10)
+        assert {n["name"] for n in result["nodes"]} == {"ping", "pong"}
+        assert {(e["source"], e["target"]) for e in result["edges"]} == {
+            (PING["id"], PONG["id"]), (PONG["id"], PING["id"]),
+        }
+
+    def test_depth_limit(self, tools):
+        result = _call(tools, "get_call_graph", symbol="helper", direction="callers", depth=1)
+        assert {n["name"] for n in result["nodes"]} == {"helper", "load", "main"}
+        assert result["truncated"] is True and result["frontier"] == [MAIN["id"]]
+
+        more = _call(tools, "get_call_graph", direction="callers", depth=1, paginate_from=result["frontier"])
+        assert more["roots"] == [MAIN["id"]]
+        assert {n["name"] for n in more["nodes"]} == {"main", "cli"}
+        assert more["truncated"] is False
+
+    def test_invalid_direction(self, tools):
+        assert "error" in _call(tools, "get_call_graph", symbol="helper", direction="sideways")
+
+
+class FakeContext:
+    """Records the log and progress notifications a tool sends."""
+
+    def __init__(self):
+        self.messages = []
+        self.progress = []
+
+    async def log(self, level, message, logger_name=None):
+        self.messages.append((level, message, logger_name))
+
+    async def report_progress(self, progress, total=None, message=None):
+        self.progress.append((progress, total, message))
+
+
+class TestStreaming:
+    def _records(self, text):
+        return [json.loads(line) for line in text.splitlines()]
+
+    def test_call_graph_without_context_returns_json_lines(self, tools):
+        text = asyncio.run(tools["get_call_graph"](symbol="load", stream=True))
+        records = self._records(text)
+
+        assert records[0] == {"type": "begin", "schema": 1, "tool": "get_call_graph", "symbol": "load",
+                              "direction": "both", "depth": DEFAULT_MAX_TRAVERSAL_DEPTH, "roots": [LOAD["id"]]}
+        nodes = [n for r in records if r["type"] == "nodes" for n in r["items"]]
+        edges = [e for r in records if r["type"] == "edges" for e in r["items"]]
+        assert nodes == _call(tools, "get_call_graph", symbol="load")["nodes"]
+        assert len(edges) == 3
+        assert records[-1] == {"type": "end", "nodes": 4, "edges": 3, "lines": len(records), "truncated": False,
+                               "frontier": []}
+
+    def test_context_receives_records_and_result_is_the_end(self, tools):
+        ctx = FakeContext()
+        result = asyncio.run(tools["get_call_graph"](symbol="load", stream=True, ctx=ctx))
+
+        sent = [json.loads(message) for _, message, _ in ctx.messages]
+        assert [r["type"] for r in sent] == ["begin", "nodes", "edges", "end"]
+        assert {name for _, _, name in ctx.messages} == {STREAM_LOGGER}
+        assert self._records(result) == [sent[-1]]
+
+    def test_query_nodes(self, tools):
+        records = self._records(asyncio.run(tools["query_nodes"](query="name:load", stream=True)))
+        assert [r["type"] for r in records] == ["begin", "nodes", "end"]
+        assert (records[0]["total"], records[-1]["nodes"]) == (1, 1)
+
+    def test_errors_before_streaming_stay_json(self, tools):
+        assert "error" in _call(tools, "get_call_graph", symbol="missing", stream=True)
+
+
+class TestExport:
+    def test_dot_export(self, tools):
+        result = _call(tools, "export")
+
+        # Every node, the unresolved trait and the indexed files, with every edge between them
+        assert result["nodes"] == len(NODES) + 1 + len(_file_paths(NODES, ["src/empty.py"]))
+        assert result["edges"] == len(CALLS) + len(REFERENCES) + len(DEFINES) + len(IMPLEMENTS)
+        assert result["content"].startswith('digraph "codebase" {')
+        assert f'"{MAIN["id"]}" -> "{LOAD["id"]}" [label="CALLS", color="blue"];' in result["content"]
+
+    def test_filters(self, tools):
+        result = _call(tools, "export", file_glob="loop.py", edge_labels=False)
+        # The three functions of loop.py and its File node
+        assert (result["nodes"], result["edges"]) == (4, 3)
+        assert '[label="CALLS"]' not in result["content"]
+
+    def test_edge_types_and_node_cap(self, tools):
+        assert _call(tools, "export", edge_types=["DEFINES"])["edges"] == len(DEFINES)
+        assert "more than the limit of 2" in _call(tools, "export", max_nodes=2)["error"]
+
+    def test_graphml_export(self, tools):
+        result = _call(tools, "export", format="graphml", file_glob="loop.py")
+        root = ElementTree.fromstring(result["content"].encode("utf-8"))
+        namespace = {"g": "http://graphml.graphdrawing.org/xmlns"}
+        assert (result["nodes"], result["edges"]) == (4, 3)
+        assert len(root.findall(".//g:node", namespace)) == 4
+        assert len(root.findall(".//g:edge", namespace)) == 3
+
+    def test_json_export(self, tools):
+        result = _call(tools, "export", format="json", file_glob="loop.py")
+        snapshot = CodeGraph.from_json(result["content"])
+        assert (len(snapshot.nodes), len(snapshot.relations)) == (result["nodes"], result["edges"]) == (4, 3)
+
+    def test_unsupported_format(self, tools):
+        assert "error" in _call(tools, "export", format="png")
+
+
+class TestCallPath:
+    def test_direct_caller_is_one_hop(self, tools):
+        result = _call(tools, "call_path", source="load", target="helper")
+        assert [n["name"] for n in result["path"]] == ["load", "helper"]
+        assert result["hops"] == 1
+
+    def test_shortest_path(self, tools):
+        result = _call(tools, "call_path", source="cli", target="helper")
+        # cli -> main -> helper beats cli -> main -> load -> helper
+        assert [n["name"] for n in result["path"]] == ["cli", "main", "helper"]
+        assert result["path"][0]["file_path"] == "cli.py"
+
+    def test_unreachable(self, tools):
+        result = _call(tools, "call_path", source="helper", target="main")
+        assert result["path"] is None and result["hops"] is None
+        # Nothing was left unwalked
+        assert (result["truncated"], result["frontier"]) == (False, [])
+        assert _call(tools, "call_path", source="ping", target="helper")["path"] is None
+
+    def test_max_depth(self, tools):
+        assert _call(tools, "call_path", source="cli", target="helper", max_depth=1)["path"] is None
+        assert _call(tools, "call_path", source="cli", target="helper", max_depth=50)["max_depth"] == 50
+        assert _call(tools, "call_path", source="cli", target="helper")["max_depth"] == DEFAULT_MAX_TRAVERSAL_DEPTH
+
+    def test_paginate_from_continues_past_the_frontier(self, tools):
+        first = _call(tools, "call_path", source="cli", target="helper", max_depth=1)
+        assert (first["path"], first["truncated"], first["frontier"]) == (None, True, [MAIN["id"]])
+
+        more = _call(tools, "call_path", target="helper", max_depth=1, paginate_from=first["frontier"])
+        assert [n["name"] for n in more["path"]] == ["main", "helper"]
+        assert (more["truncated"], more["frontier"]) == (False, [])
+        assert "error" in _call(tools, "call_path", target="helper")
+
+    def test_unknown_symbol(self, tools):
+        assert "error" in _call(tools, "call_path", source="missing", target="helper")
+        assert "error" in _call(tools, "call_path", source="cli", target="missing")
+
+
+class TestCallHierarchy:
+    def test_callers_tree(self, tools):
+        result = _call(tools, "call_hierarchy", symbol="helper", direction="callers")
+
+        (root,) = result["hierarchies"]
+        assert (root["id"], root["qualified_name"]) == (HELPER["id"], "helper")
+        assert "callees" not in root
+        load, main = root["callers"]
+        assert (load["name"], load["call_site"], load["calls"]) == \
+            ("load", {"file_path": "app.py", "line_no": 6, "column": None, "snippet": "value = helper()"}, 1)
+        # main calls helper directly, so it is expanded there and only referenced under load
+        (ref,) = load["children"]
+        assert (ref["id"], ref["ref"], ref["call_site"]["line_no"]) == (MAIN["id"], True, 11)
+        assert "children" not in ref
+        assert [(c["name"], c["children"]) for c in main["children"]] == [("cli", [])]
+        assert (result["node_count"], result["truncated"]) == (5, False)
+
+    def test_both_directions(self, tools):
+        (root,) = _call(tools, "call_hierarchy", symbol="load")["hierarchies"]
+        assert [c["name"] for c in root["callers"]] == ["main"]
+        assert [c["name"] for c in root["callees"]] == ["helper"]
+
+    def test_recursion_is_marked(self, tools):
+        (root,) = _call(tools, "call_hierarchy", symbol="recurse", direction="callees")["hierarchies"]
+        assert [(c["id"], c.get("cycle")) for c in root["callees"]] == [(RECURSE["id"], True)]
+        (root,) = _call(tools, "call_hierarchy", symbol="ping", direction="callees", max_depth=10)["hierarchies"]
+        (pong,) = root["callees"]
+        assert [(c["name"], c.get("cycle"), "children" in c) for c in pong["children"]] == [("ping", True, False)]
+
+    def test_depth_limit_and_node_cap(self, tools):
+        (root,) = _call(tools, "call_hierarchy", symbol="helper", direction="callers", max_depth=1)["hierarchies"]
+        assert [("children" in c) for c in root["callers"]] == [False, False]
+
+        result = _call(tools, "call_hierarchy", symbol="helper", direction="callers", max_nodes=2)
+        assert [c["name"] for c in result["hierarchies"][0]["callers"]] == ["load"]
+        assert (result["node_count"], result["truncated"]) == (2, True)
+
+    def test_paginate_from_continues_past_the_frontier(self, tools):
+        first = _call(tools, "call_hierarchy", symbol="helper", direction="callers", max_depth=1)
+        # load's caller main is listed already; main's caller cli is not
+        assert (first["truncated"], first["frontier"]) == (True, [MAIN["id"]])
+
+        more = _call(tools, "call_hierarchy", direction="callers", max_depth=1, paginate_from=first["frontier"])
+        (root,) = more["hierarchies"]
+        assert (root["id"], [c["name"] for c in root["callers"]]) == (MAIN["id"], ["cli"])
+        assert (more["truncated"], more["frontier"]) == (False, [])
+
+    def test_errors(self, tools):
+        assert "error" in _call(tools, "call_hierarchy", symbol="helper", direction="sideways")
+        assert "error" in _call(tools, "call_hierarchy", symbol="missing")
+
+
+class TestFindCycles:
+    def test_function_cycles(self, tools):
+        result = _call(tools, "find_cycles", kind="function")
+
+        # recurse() only calls itself, which is not a cycle between members
+        assert result["count"] == 1
+        cycle, = result["cycles"]
+        assert cycle["size"] == 2
+        assert [n["name"] for n in cycle["nodes"]] == ["ping", "pong"]
+        assert cycle["path"] == [PING["id"], PONG["id"], PING["id"]]
+        assert cycle["back_edges"] == [{"source": PONG["id"], "target": PING["id"], "type": "CALLS"}]
+
+    def test_self_loops_and_min_size(self, tools):
+        result = _call(tools, "find_cycles", kind="function", include_self_loops=True)
+        assert [([n["name"] for n in c["nodes"]], c["back_edges"]) for c in result["cycles"]] == [
+            (["ping", "pong"], [{"source": PONG["id"], "target": PING["id"], "type": "CALLS"}]),
+            (["recurse"], [{"source": RECURSE["id"], "target": RECURSE["id"], "type": "CALLS"}]),
+        ]
+        assert _call(tools, "find_cycles", kind="function", include_self_loops=True, min_size=2)["count"] == 1
+        assert _call(tools, "find_cycles", kind="function", min_size=3)["cycles"] == []
+
+    def test_module_cycles(self, tools):
+        # The sample graph has no imports
+        assert _call(tools, "find_cycles")["cycles"] == []
+
+    def test_invalid_kind(self, tools):
+        assert "error" in _call(tools, "find_cycles", kind="class")
+
+
+class TestGetFileOutline:
+    def test_nested_symbols_in_source_order(self, tools):
+        result = _call(tools, "get_file_outline", file_path="sample.rs")
+
+        assert result["file_path"] == "sample.rs"
+        person, = result["symbols"]
+        assert (person["kind"], person["name"], person["visibility"]) == ("Class", "Person", "Public")
+        assert (person["start_line"], person["end_line"]) == (4, 7)
+        assert person["doc"] == "A person with a name and an age."
+        assert [(c["kind"], c["name"]) for c in person["children"]] == [
+            ("Field", "name"), ("Field", "age"),
+            ("Method", "get_name"), ("Method", "set_name"), ("Method", "birthday"),
+        ]
+        assert all(c["children"] == [] for c in person["children"])
+
+    def test_high_complexity_keeps_enclosing_types(self, tools):
+        result = _call(tools, "get_file_outline", file_path="sample.rs", high_complexity=5)
+
+        person, = result["symbols"]
+        assert person["complexity"] is None
+        assert [(c["name"], c["complexity"]) for c in person["children"]] == [("birthday", 7)]
+
+    def test_high_complexity_drops_unmeasured_symbols(self, tools):
+        assert _call(tools, "get_file_outline", file_path="loop.py", high_complexity=1)["symbols"] == []
+
+    def test_top_level_functions(self, tools):
+        result = _call(tools, "get_file_outline", file_path="loop.py")
+        assert [(s["name"], s["start_line"]) for s in result["symbols"]] == [
+            ("recurse", 1), ("ping", 5), ("pong", 9),
+        ]
+
+    def test_relative_path(self, tools):
+        assert _call(tools, "get_file_outline", file_path="empty.py")["file_path"] == "src/empty.py"
+
+    def test_indexed_but_empty(self, tools):
+        result = _call(tools, "get_file_outline", file_path="src/empty.py")
+        assert "error" not in result
+        assert result["symbols"] == []
+
+    def test_not_indexed(self, tools):
+        result = _call(tools, "get_file_outline", file_path="missing.py")
+        assert result["error"] == "File not indexed: missing.py"
+
+    def test_exported_only(self, backend):
+        greet = dict(_node("greet", "sample.ts", 6), export_type="named", export_name="greet")
+        whisper = dict(_node("whisper", "sample.ts", 13), export_type="named", export_name="quiet")
+        default = dict(_node("default", "sample.ts", 32), export_type="default", export_name="default")
+        tools = _make_tools(_db(backend, [greet, whisper, default, _node("internal", "sample.ts", 25)],
+                                        [], [], []))
+
+        assert len(_call(tools, "get_file_outline", file_path="sample.ts")["symbols"]) == 4
+        result = _call(tools, "get_file_outline", file_path="sample.ts", exported_only=True)
+        assert [(s["name"], s["export_type"], s["export_name"]) for s in result["symbols"]] == [
+            ("greet", "named", "greet"), ("whisper", "named", "quiet"), ("default", "default", "default"),
+        ]
+
+    def test_enum_variants_and_field_types(self, backend):
+        venue = _node("Venue", "model.rs", 3, "Enum")
+        remote = _node("Remote", "model.rs", 5, "Variant")
+        host = dict(_node("host", "model.rs", 5, "Field"), field_type="Person")
+        tools = _make_tools(_db(backend, [venue, remote, host], [],
+                                defines=[(venue["id"], remote["id"]), (remote["id"], host["id"])]))
+
+        venue_entry, = _call(tools, "get_file_outline", file_path="model.rs")["symbols"]
+        assert venue_entry["field_type"] is None
+        remote_entry, = venue_entry["children"]
+        assert [(c["kind"], c["name"], c["field_type"]) for c in remote_entry["children"]] == [
+            ("Field", "host", "Person"),
+        ]
+
+
+class TestSymbolAtPosition:
+    def test_innermost_symbol_and_enclosing(self, tools):
+        result = _call(tools, "symbol_at_position", file_path="sample.rs", line=5, column=5)
+
+        assert (result["file_path"], result["line"], result["column"]) == ("sample.rs", 5, 5)
+        assert result["symbol"]["id"] == NAME["id"]
+        assert [s["id"] for s in result["enclosing"]] == [PERSON["id"]]
+
+    def test_symbol_on_its_own_line(self, tools):
+        result = _call(tools, "symbol_at_position", file_path="sample.rs", line=14, column=9)
+        assert (result["symbol"]["name"], result["symbol"]["type"]) == ("get_name", "Method")
+        assert result["enclosing"] == []
+
+    def test_no_symbol(self, tools):
+        result = _call(tools, "symbol_at_position", file_path="sample.rs", line=30, column=1)
+        assert result["symbol"] is None and result["enclosing"] == []
+
+    def test_not_indexed(self, tools):
+        result = _call(tools, "symbol_at_position", file_path="missing.py", line=1)
+        assert result["error"] == "File not indexed: missing.py"
+
+    def test_utf16_column(self, backend, tmp_path):
+        source = tmp_path / "accents.rs"
+        source.write_text('struct Café; /* 😀 */ struct Point { x: i32 }\n', encoding="utf-8")
+        point = dict(_node("Point", str(source), 1, "Class"), end_line_no=1, column=22, end_column=45)
+        cafe = dict(_node("Café", str(source), 1, "Class"), end_line_no=1, column=1, end_column=13)
+        tools = _make_tools(_db(backend, [point, cafe], []))
+
+        # UTF-16 column 23 is character 22, where Point starts
+        result = _call(tools, "symbol_at_position", file_path="accents.rs", line=1, column=23,
+                       position_encoding="utf-16")
+        assert result["symbol"]["name"] == "Point"
+        assert result["position_encoding"] == "utf-16"
+        # Counted in characters, Point starts at 22 and column 21 is the space before it
+        result = _call(tools, "symbol_at_position", file_path="accents.rs", line=1, column=22)
+        assert result["symbol"]["name"] == "Point"
+        result = _call(tools, "symbol_at_position", file_path="accents.rs", line=1, column=21)
+        assert result["symbol"] is None
+        # UTF-8 byte 25 is still the space before Point
+        result = _call(tools, "symbol_at_position", file_path="accents.rs", line=1, column=25,
+                       position_encoding="utf-8")
+        assert result["symbol"] is None
+
+    def test_unknown_encoding(self, tools):
+        result = _call(tools, "symbol_at_position", file_path="sample.rs", line=5, position_encoding="latin-1")
+        assert result["error"] == "Unknown position encoding: latin-1"
+
+
+class TestGetSourceSnippet:
+    SOURCE = "import os\n\n\ndef load():\n    return os.getcwd()\n\n\ndef save():\n    pass\n"
+
+    @pytest.fixture
+    def snippet_tools(self, backend, tmp_path):
+        path = tmp_path / "io.py"
+        path.write_bytes(self.SOURCE.encode("utf-8"))
+        start = self.SOURCE.index("def load")
+        load = dict(_node("load", str(path), 4), end_line_no=5, column=1, end_column=23,
+                    start_byte=start, end_byte=start + len("def load():\n    return os.getcwd()"))
+        # Indexed without byte offsets
+        save = dict(_node("save", str(path), 8), end_line_no=9)
+        tools = _make_tools(_db(backend, [load, save], [],
+                                file_properties={str(path): {"content_hash": content_hash(path.read_bytes())}}))
+        return tools, path
+
+    def test_exact_span_with_context(self, snippet_tools):
+        tools, path = snippet_tools
+        result = _call(tools, "get_source_snippet", symbol="load", context_lines=2)
+
+        [snippet] = result["snippets"]
+        assert snippet["source"] == "def load():\n    return os.getcwd()"
+        assert snippet["span"] == {"start_line": 4, "start_column": 1, "end_line": 5, "end_column": 23,
+                                   "start_byte": 12, "end_byte": 46}
+        assert (snippet["context_before"], snippet["context_after"]) == (["", ""], ["", ""])
+        assert (snippet["stale"], snippet["file_path"]) == (False, str(path))
+
+    def test_whole_lines_without_byte_offsets(self, snippet_tools):
+        tools, _ = snippet_tools
+        [snippet] = _call(tools, "get_source_snippet", symbol="save")["snippets"]
+        assert snippet["source"] == "def save():\n    pass"
+
+    def test_changed_file_is_stale_not_an_error(self, snippet_tools):
+        tools, path = snippet_tools
+        path.write_bytes(self.SOURCE.replace("os.getcwd()", "os.getcwd() ").encode("utf-8"))
+        [snippet] = _call(tools, "get_source_snippet", symbol="load")["snippets"]
+
+        assert snippet["stale"] is True and "warning" in snippet
+        assert snippet["source"] == "def load():\n    return os.getcwd()"
+
+    def test_missing_file_and_unknown_symbol(self, snippet_tools):
+        tools, path = snippet_tools
+        path.unlink()
+        [snippet] = _call(tools, "get_source_snippet", symbol="load")["snippets"]
+        assert snippet["error"].startswith("Cannot read")
+        assert "error" in _call(tools, "get_source_snippet", symbol="missing")
+
+
+class TestSearchSymbols:
+    def test_ranked_exact_then_word_start(self, tools):
+        result = _call(tools, "search_symbols", pattern="name")
+
+        ranked = [(r["name"], r["match_kind"]) for r in result["results"]]
+        assert ranked == [("name", "exact"), ("get_name", "boundary"), ("set_name", "boundary")]
+        assert result["results"][0]["score"] == 1.0
+        assert result["results"][1]["score"] > 0.7
+        assert result["total"] == 3 and result["truncated"] is False
+
+    def test_prefix_ignores_case(self, tools):
+        result = _call(tools, "search_symbols", pattern="pers")
+        assert [(r["name"], r["match_kind"]) for r in result["results"]] == [("Person", "prefix")]
+
+    def test_regex_ranks_below_plain_matches(self, tools):
+        result = _call(tools, "search_symbols", pattern="^p[io]ng$")
+        assert [(r["name"], r["match_kind"]) for r in result["results"]] == [("ping", "regex"), ("pong", "regex")]
+        assert all(r["score"] == 0.4 for r in result["results"])
+
+    def test_regex_literal_with_flags(self, tools):
+        result = _call(tools, "search_symbols", pattern="/^(?<verb>GET|SET)_/i")
+        assert {r["name"] for r in result["results"]} == {"get_name", "set_name"}
+
+    def test_kind_and_file_glob(self, tools):
+        result = _call(tools, "search_symbols", pattern="area", kind="method", file_glob="s*.rs")
+        assert {r["file_path"] for r in result["results"]} == {"shapes.rs", "square.rs"}
+        assert all(r["type"] == "Method" for r in result["results"])
+
+        result = _call(tools, "search_symbols", pattern="a", kind="struct", file_glob="square.*")
+        assert [r["name"] for r in result["results"]] == ["Square"]
+
+    def test_plain_text_only(self, tools):
+        result = _call(tools, "search_symbols", pattern="^p[io]ng$", use_regex=False)
+        assert result["results"] == []
+
+    def test_limit(self, tools):
+        result = _call(tools, "search_symbols", pattern="a", limit=2)
+        assert len(result["results"]) == 2
+        assert result["truncated"] is True and result["total"] > 2
+
+    def test_invalid_regex_is_a_structured_error(self, tools):
+        result = _call(tools, "search_symbols", pattern="get_(name")
+        assert result["code"] == "invalid_pattern"
+        assert result["pattern"] == "get_(name"
+        assert result["position"] == 4
+        assert "missing )" in result["message"]
+
+    def test_unknown_kind(self, tools):
+        result = _call(tools, "search_symbols", pattern="a", kind="gadget")
+        assert result["error"].startswith("Unknown node kind: gadget")
+
+    def test_high_complexity(self, tools):
+        result = _call(tools, "search_symbols", pattern="a", high_complexity=5)
+        assert [(r["name"], r["complexity"]) for r in result["results"]] == [("main", 12), ("birthday", 7)]
+
+    def test_fuzzy_and_owner_qualified(self, tools):
+        result = _call(tools, "search_symbols", pattern="getname")
+        assert [(r["name"], r["match_kind"]) for r in result["results"]] == [("get_name", "boundary")]
+
+        result = _call(tools, "search_symbols", pattern="prsn")
+        assert [(r["name"], r["match_kind"]) for r in result["results"]][0] == ("Person", "fuzzy")
+
+        result = _call(tools, "search_symbols", pattern="Person.nam")
+        assert [(r["name"], r["owner"], r["match_kind"]) for r in result["results"]][0] == \
+            ("name", "Person", "prefix")
+
+    def test_exact_kinds_and_path_prefix(self, tools):
+        result = _call(tools, "search_symbols", pattern="area", exact=True, kinds="method", path_prefix="square.rs")
+        assert [(r["name"], r["file_path"]) for r in result["results"]] == [("area", "square.rs")]
+        assert _call(tools, "search_symbols", pattern="Area", exact=True)["results"] == []
+
+
+class TestComplexityHistogram:
+    def test_buckets_and_hotspots(self, backend):
+        with patch.object(server_module, 'FastMCP', RecordingMCP), \
+             patch.object(server_module, 'Neo4jDatabase', return_value=_db(backend, NODES, CALLS)), \
+             patch.object(server_module, 'get_embedding_provider'), \
+             patch.object(server_module, 'CodeEmbedder'):
+            mcp_server = server_module.CodebaseKnowledgeGraphMCP(
+                neo4j_uri="mock_uri", neo4j_user="mock_user", neo4j_password="mock_pass"
+            )
+        result = json.loads(mcp_server.mcp.resources["complexity://histogram"]())
+
+        assert (result["total"], result["max"], result["mean"]) == (4, 12, 5.75)
+        assert {b["label"]: b["count"] for b in result["buckets"]} == {
+            "simple": 2, "moderate": 1, "complex": 1, "very_complex": 0, "untestable": 0,
+        }
+        assert [h["name"] for h in result["hotspots"]] == ["main", "birthday", "load", "get_name"]
+
+
+class TestFileMetrics:
+    APP_LINES = {"total_lines": 20, "blank_lines": 4, "comment_lines": 2, "code_lines": 14}
+    SAMPLE_LINES = {"total_lines": 30, "blank_lines": 5, "comment_lines": 5, "code_lines": 20}
+
+    @pytest.fixture
+    def metrics_tools(self, backend):
+        return _make_tools(_db(backend, NODES, CALLS, REFERENCES, DEFINES, empty_files=["src/empty.py"],
+                               file_properties={"app.py": self.APP_LINES, "sample.rs": self.SAMPLE_LINES}))
+
+    def test_single_file(self, metrics_tools):
+        result = _call(metrics_tools, "get_file_metrics", path="app.py")
+
+        [row] = result["files"]
+        assert row == {"file_path": "app.py", **self.APP_LINES,
+                       "function_count": 2, "struct_count": 0, "avg_complexity": 7.5, "doc_coverage_ratio": None}
+        assert result["totals"] == {"files": 1, **self.APP_LINES,
+                                    "function_count": 2, "struct_count": 0, "avg_complexity": 7.5,
+                                    "doc_coverage_ratio": None}
+
+    def test_glob(self, metrics_tools):
+        result = _call(metrics_tools, "get_file_metrics", path="*.rs")
+
+        assert [row["file_path"] for row in result["files"]] == ["sample.rs", "shapes.rs", "square.rs"]
+        sample = result["files"][0]
+        assert (sample["function_count"], sample["struct_count"], sample["avg_complexity"]) == (3, 1, 4.0)
+        # shapes.rs and square.rs were indexed without line counts
+        assert result["files"][1]["total_lines"] is None
+        assert result["totals"]["total_lines"] == 30
+        assert result["totals"]["struct_count"] == 3
+
+    def test_no_match_is_an_error(self, metrics_tools):
+        assert "error" in _call(metrics_tools, "get_file_metrics", path="*.go")
+
+    def test_summary_resource(self, backend):
+        db = _db(backend, NODES, CALLS, file_properties={"app.py": self.APP_LINES, "sample.rs": self.SAMPLE_LINES})
+        with patch.object(server_module, 'FastMCP', RecordingMCP), \
+             patch.object(server_module, 'Neo4jDatabase', return_value=db), \
+             patch.object(server_module, 'get_embedding_provider'), \
+             patch.object(server_module, 'CodeEmbedder'):
+            mcp_server = server_module.CodebaseKnowledgeGraphMCP(
+                neo4j_uri="mock_uri", neo4j_user="mock_user", neo4j_password="mock_pass"
+            )
+        summary = json.loads(mcp_server.mcp.resources["metrics://summary"]())
+
+        assert summary["totals"]["files"] == 7
+        assert summary["totals"]["code_lines"] == 34
+        assert summary["totals"]["function_count"] == 13
+        assert sorted(summary["by_language"]) == ["python", "rust"]
+        assert summary["by_language"]["python"]["avg_complexity"] == 7.5
+
+
+class TestUndocumentedSymbols:
+    NODES = [
+        dict(_node("parse", "lib.rs", 3), visibility="Public", documented=True, doc="Parses it."),
+        dict(_node("render", "lib.rs", 9), visibility="Public", documented=False),
+        dict(_node("helper", "lib.rs", 15), visibility="Private"),
+        dict(_node("Config", "src/config.rs", 2, "Class"), visibility="Public", documented=False),
+        # Go doc comments are not read, so Go symbols carry no flag
+        dict(_node("Serve", "server.go", 7), visibility="Public"),
+    ]
+
+    @pytest.fixture
+    def doc_tools(self, backend):
+        return _make_tools(_db(backend, self.NODES, []))
+
+    def test_undocumented_public_symbols_and_ratio(self, doc_tools):
+        result = _call(doc_tools, "get_undocumented_symbols")
+
+        assert [(s["name"], s["type"], s["file_path"], s["line_no"]) for s in result["symbols"]] == [
+            ("render", "Function", "lib.rs", 9), ("Config", "Class", "src/config.rs", 2),
+        ]
+        assert (result["documented"], result["total"], result["doc_coverage_ratio"]) == (1, 3, 0.3333)
+        assert "meets_threshold" not in result
+
+    def test_file_glob_and_kinds(self, doc_tools):
+        result = _call(doc_tools, "get_undocumented_symbols", file_glob="src/*.rs")
+        assert [s["name"] for s in result["symbols"]] == ["Config"]
+        result = _call(doc_tools, "get_undocumented_symbols", kinds="function")
+        assert [s["name"] for s in result["symbols"]] == ["render"]
+        assert result["doc_coverage_ratio"] == 0.5
+
+    def test_threshold(self, doc_tools):
+        assert _call(doc_tools, "get_undocumented_symbols", min_coverage=0.5)["meets_threshold"] is False
+        assert _call(doc_tools, "get_undocumented_symbols", kinds="function", min_coverage=0.5)["meets_threshold"]
+
+    def test_unknown_kind(self, doc_tools):
+        assert _call(doc_tools, "get_undocumented_symbols", kinds="gadget")["error"].startswith("Unknown node kind")
+
+    def test_file_metrics_ratio(self, doc_tools):
+        result = _call(doc_tools, "get_file_metrics", path="*.rs")
+        assert [(row["file_path"], row["doc_coverage_ratio"]) for row in result["files"]] == \
+            [("lib.rs", 0.5), ("src/config.rs", 0.0)]
+        assert result["totals"]["doc_coverage_ratio"] == 0.3333
+
+
+class TestQueryNodes:
+    NODES = [
+        dict(_node("parse", "src/lib.rs", 3), visibility="Public"),
+        dict(_node("helper", "src/lib.rs", 15), visibility="Private"),
+        dict(_node("Config", "src/config.rs", 2, "Class"), visibility="Public"),
+        dict(_node("main", "app.py", 1), visibility="Public"),
+    ]
+
+    @pytest.fixture
+    def query_tools(self, backend):
+        return _make_tools(_db(backend, self.NODES, []))
+
+    def test_public_functions_in_files(self, query_tools):
+        result = _call(query_tools, "query_nodes", query='kind:function AND visibility:public AND file:"src/*.rs"')
+        assert [(r["name"], r["file_path"]) for r in result["results"]] == [("parse", "src/lib.rs")]
+        assert result["filter"] == "kind:function AND visibility:public AND file:src/*.rs"
+        assert (result["total"], result["truncated"]) == (1, False)
+
+    def test_or_and_not(self, query_tools):
+        result = _call(query_tools, "query_nodes", query="NOT visibility:private AND (kind:class OR name:m*)")
+        assert [r["name"] for r in result["results"]] == ["main", "Config"]
+
+    def test_limit(self, query_tools):
+        result = _call(query_tools, "query_nodes", query="file:*", limit=1)
+        assert (len(result["results"]), result["total"], result["truncated"]) == (1, 4, True)
+
+    def test_deprecated(self, backend):
+        legacy = dict(_node("load", "src/lib.rs", 9), visibility="Public", deprecated=True)
+        tools = _make_tools(_db(backend, self.NODES + [legacy], []))
+        result = _call(tools, "query_nodes", query="deprecated:true")
+        assert [r["id"] for r in result["results"]] == [legacy["id"]]
+        result = _call(tools, "query_nodes", query="file:src/lib.rs AND deprecated:false")
+        assert [r["name"] for r in result["results"]] == ["parse", "helper"]
+
+    def test_malformed_query_is_a_structured_error(self, query_tools):
+        result = _call(query_tools, "query_nodes", query="kind:function AND")
+        assert (result["code"], result["position"]) == ("invalid_query", 17)
+        assert "Unexpected end of query" in result["error"]
+
+
+class TestModuleGraphResource:
+    def test_weighted_file_edges(self, backend):
+        db = _db(backend, NODES + [DISPLAY], CALLS, REFERENCES, DEFINES,
+                 empty_files=["src/empty.py"], implements=IMPLEMENTS)
+        with patch.object(server_module, 'FastMCP', RecordingMCP), \
+             patch.object(server_module, 'Neo4jDatabase', return_value=db), \
+             patch.object(server_module, 'get_embedding_provider'), \
+             patch.object(server_module, 'CodeEmbedder'):
+            mcp_server = server_module.CodebaseKnowledgeGraphMCP(
+                neo4j_uri="mock_uri", neo4j_user="mock_user", neo4j_password="mock_pass"
+            )
+        result = json.loads(mcp_server.mcp.resources["graph://modules"]())
+
+        assert result["files"] == ["app.py", "cli.py", "loop.py", "sample.rs", "shapes.rs", "square.rs",
+                                   "src/empty.py", "util.py"]
+        # Person implementing the unresolved fmt::Display is not a file dependency
+        assert result["edges"] == [
+            {"source": "app.py", "target": "util.py", "weight": 2, "relations": {"CALLS": 2}},
+            {"source": "square.rs", "target": "shapes.rs", "weight": 2, "relations": {"IMPLEMENTS": 2}},
+            {"source": "cli.py", "target": "app.py", "weight": 1, "relations": {"CALLS": 1}},
+        ]
+        assert result["members"] == []
+
+    def test_workspace_members(self, backend):
+        members = {"app.py": "web", "cli.py": "web", "util.py": "shared"}
+        db = _db(backend, NODES, CALLS, file_properties={path: {"workspace_member": member}
+                                                         for path, member in members.items()})
+        with patch.object(server_module, 'FastMCP', RecordingMCP), \
+             patch.object(server_module, 'Neo4jDatabase', return_value=db), \
+             patch.object(server_module, 'get_embedding_provider'), \
+             patch.object(server_module, 'CodeEmbedder'):
+            mcp_server = server_module.CodebaseKnowledgeGraphMCP(
+                neo4j_uri="mock_uri", neo4j_user="mock_user", neo4j_password="mock_pass"
+            )
+        result = json.loads(mcp_server.mcp.resources["graph://modules"]())
+
+        assert result["members"] == [{"name": "shared", "files": ["util.py"]},
+                                     {"name": "web", "files": ["app.py", "cli.py"]}]
+
+
+class TestFindUsages:
+    def test_calls(self, tools):
+        result = _call(tools, "find_usages", symbol="helper")
+
+        assert result["truncated"] is False
+        assert [(u["file"], u["line"], u["usage_kind"], u["context_snippet"]) for u in result["usages"]] == [
+            ("app.py", 6, "call", "value = helper()"),
+            ("app.py", 12, "call", "helper()"),
+        ]
+        assert all(set(u) >= {"file", "line", "column", "context_snippet", "usage_kind"} for u in result["usages"])
+
+    def test_field_accesses_and_inheritance(self, tools):
+        name = _call(tools, "find_usages", symbol=NAME["id"])
+        assert [(u["line"], u["usage_kind"]) for u in name["usages"]] == [(15, "field_access"), (19, "field_access")]
+
+        shape = _call(tools, "find_usages", symbol="Shape")
+        assert {(u["source"]["name"], u["usage_kind"]) for u in shape["usages"]} == {
+            ("Circle", "inherit"), ("Square", "inherit"),
+        }
+
+    def test_fuzzy_match(self, tools):
+        result = _call(tools, "find_usages", symbol="NAME", fuzzy=True)
+        assert {t["name"] for t in result["targets"]} == {"name", "get_name", "set_name"}
+        assert len(result["usages"]) == 2
+
+    def test_truncated(self, tools):
+        result = _call(tools, "find_usages", symbol="p", fuzzy=True, limit=3)
+        assert len(result["usages"]) == 3
+        assert result["truncated"] is True
+
+    def test_unknown_symbol(self, tools):
+        assert _call(tools, "find_usages", symbol="nothing") == {"error": "Symbol not found: nothing"}
+
+
+class TestRenameImpact:
+    def test_patches_from_stored_call_sites(self, tools):
+        result = _call(tools, "rename_impact", old_name="helper", new_name="fetch")
+
+        assert [t["id"] for t in result["targets"]] == [HELPER["id"]]
+        # The sample files are not on disk, so only the source lines stored on the edges can be patched
+        assert [(p["file"], p["line"], p["old_text"], p["proposed_new_text"], p["kind"]) for p in result["patches"]] == [
+            ("app.py", 6, "value = helper()", "value = fetch()", "reference"),
+            ("app.py", 12, "helper()", "fetch()", "reference"),
+        ]
+        assert [(u["file"], u["usage_kind"]) for u in result["unlocated"]] == [("util.py", "definition")]
+        assert (result["warnings"], result["collisions"], result["truncated"]) == ([], [], False)
+
+    def test_source_files_are_searched(self, backend, tmp_path):
+        util, app = str(tmp_path / "util.py"), str(tmp_path / "app.py")
+        (tmp_path / "util.py").write_text('def helper():\n    return "helper"\n', encoding="utf-8")
+        (tmp_path / "app.py").write_text('# uses helper\n\n\n\ndef load():\n    value = helper()\n'
+                                         '    return helper() or value\n', encoding="utf-8")
+        helper, load = _node("helper", util, 1), dict(_node("load", app, 5), end_line_no=7)
+        tools = _make_tools(_db(backend, [helper, load], [(load["id"], helper["id"], 6, "value = helper()")]))
+
+        result = _call(tools, "rename_impact", old_name="helper", new_name="fetch")
+        assert [(p["file"], p["line"], p["proposed_new_text"], p["inferred"]) for p in result["patches"]] == [
+            (app, 6, "    value = fetch()", False),
+            (app, 7, "    return fetch() or value", True),
+            (util, 1, "def fetch():", False),
+        ]
+        assert [(w["file"], w["line"], w["kind"]) for w in result["warnings"]] == [
+            (app, 1, "comment"), (util, 2, "string_literal"),
+        ]
+
+    def test_collision_in_the_same_type(self, tools):
+        result = _call(tools, "rename_impact", old_name="get_name", new_name="Person.set_name")
+        assert result["new_name"] == "set_name"
+        assert [c["id"] for c in result["collisions"]] == [SET_NAME["id"]]
+        # Top-level functions only collide within their file
+        assert _call(tools, "rename_impact", old_name="helper", new_name="load")["collisions"] == []
+
+    def test_file_filter(self, tools):
+        result = _call(tools, "rename_impact", old_name="area", new_name="surface", file="square.rs")
+        assert [t["id"] for t in result["targets"]] == [SQUARE_AREA["id"]]
+
+    def test_errors(self, tools):
+        assert _call(tools, "rename_impact", old_name="nothing", new_name="x") == \
+            {"error": "Symbol not found: nothing"}
+        assert _call(tools, "rename_impact", old_name="helper", new_name="2fast") == \
+            {"error": "Invalid identifier: 2fast"}
+
+
+class TestPlanRename:
+    @staticmethod
+    def _project(backend, tmp_path, unresolved_call=False):
+        util, app = str(tmp_path / "util.py"), str(tmp_path / "app.py")
+        (tmp_path / "util.py").write_text('def helper():\n    return "helper"\n', encoding="utf-8")
+        (tmp_path / "app.py").write_text('# uses helper\n\n\n\ndef load():\n    value = helper()\n'
+                                         + ('    return obj.helper()\n' if unresolved_call else
+                                            '    return helper() or "helper"\n'), encoding="utf-8")
+        helper, load = _node("helper", util, 1), dict(_node("load", app, 5), end_line_no=7)
+        # obj.helper() on a receiver the parser could not type
+        unresolved = {"id": "unresolved:obj.helper", "name": "obj.helper", "type": "Unresolved",
+                      "file_path": "", "line_no": 0}
+        calls = [(load["id"], helper["id"], 6, "value = helper()")]
+        if unresolved_call:
+            calls.append((load["id"], unresolved["id"], 7, "obj.helper()"))
+        tools = _make_tools(_db(backend, [helper, load, unresolved], calls))
+        return tools, util, app, helper, load
+
+    def test_edit_spans(self, backend, tmp_path):
+        tools, util, app, helper, _ = self._project(backend, tmp_path)
+        result = _call(tools, "plan_rename", symbol_id=helper["id"], new_name="fetch")
+
+        # The second call shares the edge of the first; comments and strings are left alone
+        assert [(e["file"], e["span"]["start"]["line"], e["span"]["start"]["column"], e["kind"])
+                for e in result["edits"]] == [
+            (app, 6, 13, "reference"), (app, 7, 12, "reference"), (util, 1, 5, "definition"),
+        ]
+        assert result["edits"][0]["span"]["end"] == {"line": 6, "column": 19}
+        assert (result["files"], result["total"]) == ([app, util], 3)
+
+    def test_unresolved_references_refuse(self, backend, tmp_path):
+        tools, _, app, helper, _ = self._project(backend, tmp_path, unresolved_call=True)
+        result = _call(tools, "plan_rename", symbol_id="helper", new_name="fetch")
+
+        assert result["code"] == "unresolved_references"
+        assert result["symbol_id"] == helper["id"]
+        assert [(b["file"], b["line"], b["reason"], b["text"]) for b in result["blockers"]] == [
+            (app, 7, "unresolved_reference", "obj.helper"),
+        ]
+
+    def test_errors(self, tools):
+        assert _call(tools, "plan_rename", symbol_id="nothing", new_name="x") == \
+            {"error": "Symbol not found: nothing"}
+        assert _call(tools, "plan_rename", symbol_id="helper", new_name="2fast") == \
+            {"error": "Invalid identifier: 2fast"}
+        assert _call(tools, "plan_rename", symbol_id="area", new_name="surface")["error"] == \
+            "Ambiguous symbol: area"
+
+
+class TestFindImplementations:
+    def test_types_implementing_a_trait(self, tools):
+        result = _call(tools, "find_implementations", trait_id="Shape")
+
+        assert [t["id"] for t in result["targets"]] == [SHAPE["id"]]
+        assert result["count"] == 2
+        assert [(i["node"]["name"], i["node"]["file_path"]) for i in result["implementations"]] == [
+            ("Circle", "shapes.rs"), ("Square", "square.rs"),
+        ]
+        circle, square = result["implementations"]
+        assert [m["id"] for m in circle["methods"]] == [CIRCLE_AREA["id"]]
+        assert [m["id"] for m in square["methods"]] == [SQUARE_AREA["id"]]
+
+    def test_methods_implementing_a_trait_method(self, tools):
+        result = _call(tools, "find_implementations", trait_id=SHAPE_AREA["id"])
+        assert {i["node"]["id"] for i in result["implementations"]} == {CIRCLE_AREA["id"], SQUARE_AREA["id"]}
+
+    def test_unknown_trait(self, tools):
+        assert _call(tools, "find_implementations", trait_id="Drawable") == {"error": "Symbol not found: Drawable"}
+
+    def test_trait_that_was_not_indexed_matches_by_path(self, tools):
+        result = _call(tools, "find_implementations", trait_id="std::fmt::Display")
+        assert [t["id"] for t in result["targets"]] == [DISPLAY["id"]]
+        assert [(i["node"]["id"], i["trait_path"]) for i in result["implementations"]] == \
+            [(PERSON["id"], "fmt::Display")]
+
+    def test_traits_implemented_by_a_type(self, tools):
+        result = _call(tools, "find_implementations", trait_id="Person", direction="implemented")
+        assert result["direction"] == "implemented"
+        assert [(i["node"]["name"], i["trait_path"]) for i in result["implementations"]] == \
+            [("fmt::Display", "fmt::Display")]
+
+        result = _call(tools, "find_implementations", trait_id=CIRCLE_AREA["id"], direction="implemented")
+        assert [i["node"]["id"] for i in result["implementations"]] == [SHAPE_AREA["id"]]
+
+    def test_invalid_direction(self, tools):
+        assert _call(tools, "find_implementations", trait_id="Shape", direction="sideways") == \
+            {"error": "Invalid direction: sideways"}
+
+
+class TestFindImplementors:
+    def test_local_trait_by_name_and_path(self, tools):
+        for name in ("Shape", "crate::shapes::Shape"):
+            result = _call(tools, "find_implementors", trait_name=name)
+            assert result["count"] == 2
+            assert [(i["name"], i["file_path"], i["line_no"]) for i in result["implementors"]] == [
+                ("Circle", "shapes.rs", 12), ("Square", "square.rs", 4),
+            ]
+            assert {i["trait"]["id"] for i in result["implementors"]} == {SHAPE["id"]}
+
+    def test_external_trait_matches_any_qualification(self, tools):
+        for name in ("Display", "fmt::Display", "std::fmt::Display"):
+            result = _call(tools, "find_implementors", trait_name=name)
+            person, = result["implementors"]
+            assert (person["id"], person["end_line_no"], person["trait_path"]) == \
+                (PERSON["id"], 7, "fmt::Display")
+
+    def test_unknown_trait_has_no_implementors(self, tools):
+        assert _call(tools, "find_implementors", trait_name="Debug") == \
+            {"trait": "Debug", "count": 0, "implementors": []}
+
+
+class TestGetTypeHierarchy:
+    def test_trait_and_its_implementors(self, tools):
+        result = _call(tools, "get_type_hierarchy", type_name="Shape")
+        hierarchy, = result["hierarchies"]
+        assert (hierarchy["root"], hierarchy["supertypes"], hierarchy["subtypes"]) == \
+            (SHAPE["id"], [], ["Circle", "Square"])
+        # The area methods implementing Shape.area are not types
+        assert [(e["source"], e["type"]) for e in hierarchy["edges"]] == \
+            [(CIRCLE["id"], "IMPLEMENTS"), (SQUARE["id"], "IMPLEMENTS")]
+        assert hierarchy["edges"][0]["properties"]["trait_path"] == "Shape"
+
+    def test_external_supertype(self, tools):
+        hierarchy, = _call(tools, "get_type_hierarchy", type_name="Person")["hierarchies"]
+        assert hierarchy["supertypes"] == ["fmt::Display"]
+        assert hierarchy["nodes"][1]["type"] == "Unresolved"
+
+    def test_generic_bounds(self, backend):
+        labeled = dict(_node("Labeled", "shapes.rs", 20, "Class"), generic_bounds='{"T": ["Shape", "Clone"]}')
+        tools = _make_tools(_db(backend, [SHAPE, labeled], []))
+        hierarchy, = _call(tools, "get_type_hierarchy", type_name="Labeled")["hierarchies"]
+        assert hierarchy["generic_bounds"] == {"T": ["Shape", "Clone"]}
+
+    def test_functions_are_not_types(self, tools):
+        assert _call(tools, "get_type_hierarchy", type_name="helper") == {"error": "Type not found: helper"}
+
+    def test_paginate_from_continues_past_the_frontier(self, backend):
+        base, mid, leaf = (_node(name, "types.rs", line, "Interface")
+                           for line, name in enumerate(("Base", "Mid", "Leaf"), 1))
+        tools = _make_tools(_db(backend, [base, mid, leaf], [],
+                                implements=[(leaf["id"], mid["id"], "Mid"), (mid["id"], base["id"], "Base")]))
+        first = _call(tools, "get_type_hierarchy", type_name="Leaf", max_depth=1)
+        assert first["hierarchies"][0]["supertypes"] == ["Mid"]
+        assert (first["truncated"], first["frontier"]) == (True, [mid["id"]])
+
+        more = _call(tools, "get_type_hierarchy", max_depth=1, paginate_from=first["frontier"])
+        hierarchy, = more["hierarchies"]
+        assert (hierarchy["supertypes"], hierarchy["subtypes"]) == (["Base"], ["Leaf"])
+        assert (more["truncated"], more["frontier"]) == (False, [])
+
+
+
+class TestGetIndexStatus:
+    BROKEN = {"parse_error": "Syntax error at 'def load(:'", "parse_error_kind": "SyntaxError",
+              "parse_error_line": 5, "parse_error_column": 9}
+
+    def test_flagged_files(self, backend):
+        tools = _make_tools(_db(backend, NODES, CALLS, empty_files=["src/empty.py"],
+                                file_properties={"app.py": self.BROKEN,
+                                                 "src/empty.py": {"parse_error": "Could not read file: gone",
+                                                                  "parse_error_kind": "IoError"}}))
+        result = _call(tools, "get_index_status")
+        assert (result["files_indexed"], result["files_with_parse_errors"], result["complete"]) == (8, 2, False)
+        assert result["parse_errors"] == [
+            {"file_path": "app.py", "message": "Syntax error at 'def load(:'", "kind": "SyntaxError",
+             "line": 5, "column": 9},
+            {"file_path": "src/empty.py", "message": "Could not read file: gone", "kind": "IoError",
+             "line": None, "column": None}]
+        # Started on its own, the server knows nothing of the run
+        assert result["run"] is None
+
+    def test_complete_index(self, tools):
+        result = _call(tools, "get_index_status")
+        assert (result["files_with_parse_errors"], result["complete"], result["parse_errors"]) == (0, True, [])
+
+    def test_run_summary(self):
+        from src.ast_parser.diagnostics import FILE_TOO_LARGE, IO_ERROR, ParseDiagnostics
+
+        diagnostics = ParseDiagnostics()
+        diagnostics.record("app.py", [], partial=False)
+        diagnostics.skip("gone.py", IO_ERROR, "Could not read file: gone")
+        diagnostics.skip("big.py", FILE_TOO_LARGE, "Larger than 10 bytes")
+        with patch.object(server_module, 'FastMCP', RecordingMCP), \
+             patch.object(server_module, 'Neo4jDatabase', return_value=_sqlite_db(NODES, CALLS)), \
+             patch.object(server_module, 'get_embedding_provider'), \
+             patch.object(server_module, 'CodeEmbedder'):
+            mcp_server = server_module.CodebaseKnowledgeGraphMCP(
+                neo4j_uri="mock_uri", neo4j_user="mock_user", neo4j_password="mock_pass", diagnostics=diagnostics
+            )
+        run = _call(mcp_server.mcp.tools, "get_index_status")["run"]
+        assert (run["files_indexed"], run["failed_files"], run["skipped_by_size"]) == (1, ["gone.py"], ["big.py"])
+
+
+@pytest.mark.skipif(shutil.which("git") is None, reason="git is not installed")
+class TestDiffCommits:
+    def _repo(self, tmp_path):
+        def git(*args):
+            subprocess.run(["git", "-C", str(tmp_path), *args], check=True, capture_output=True)
+
+        git("init", "-q")
+        git("config", "user.email", "dev@example.com")
+        git("config", "user.name", "Dev")
+        for content in ("def main():\n    return 1\n",
+                        "def main():\n    return 2\n\n\ndef extra():\n    return main()\n"):
+            (tmp_path / "app.py").write_text(content, encoding="utf-8")
+            git("add", "-A")
+            git("commit", "-q", "-m", "change")
+        return str(tmp_path)
+
+    def test_symbols_changed_between_commits(self, tools, tmp_path):
+        result = _call(tools, "diff_commits", base="HEAD~1", repo_path=self._repo(tmp_path))
+
+        assert result["head"]["ref"] == "HEAD" and len(result["head"]["commit"]) == 40
+        assert [n["name"] for n in result["added_nodes"]] == ["extra"]
+        assert [(n["name"], list(n["changes"])) for n in result["changed_nodes"]] == [("main", ["body_hash"])]
+        assert result["summary"]["removed_nodes"] == 0
+
+    def test_unknown_ref_is_an_error(self, tools, tmp_path):
+        result = _call(tools, "diff_commits", base="no-such-branch", repo_path=self._repo(tmp_path))
+        assert "no-such-branch" in result["error"]
+
+    def test_progress_notifications(self, tools, tmp_path):
+        ctx = FakeContext()
+        result = json.loads(asyncio.run(tools["diff_commits"](base="HEAD~1", repo_path=self._repo(tmp_path),
+                                                              ctx=ctx)))
+
+        assert [n["name"] for n in result["added_nodes"]] == ["extra"]
+        # Each commit: its one file, then resolution starting and finishing
+        assert [(progress, total) for progress, total, _ in ctx.progress] == [(1, 1)] * 6
+        assert [message.split()[0] for _, _, message in ctx.progress] == ["Parsed", "Resolving", "Resolved"] * 2
+
+    def test_callers_at_a_revision(self, tools, tmp_path):
+        repo = self._repo(tmp_path)
+        result = _call(tools, "get_callers", symbol="main", revision="HEAD", repo_path=repo)
+        assert [(c["name"], c["file_path"]) for c in result["callers"]] == [("extra", "app.py")]
+        assert len(result["revision"]["commit"]) == 40
+        # main had no callers a commit earlier, and extra did not exist
+        assert _call(tools, "get_callers", symbol="main", revision="HEAD~1", repo_path=repo)["callers"] == []
+        assert "error" in _call(tools, "get_callees", symbol="extra", revision="HEAD~1", repo_path=repo)
+
+
+class TestGraphDiff:
+    BEFORE = "def helper():\n    return 1\n\n\ndef main():\n    return helper()\n"
+
+    def _snapshot(self, source):
+        graph = CodeGraph()
+        graph.add_sources({"app.py": source})
+        return graph.to_json()
+
+    def test_inline_and_file_snapshots(self, tools, tmp_path):
+        path = tmp_path / "after.json"
+        path.write_text(self._snapshot(self.BEFORE.replace("helper", "assist")), encoding="utf-8")
+        result = _call(tools, "graph_diff", before=self._snapshot(self.BEFORE), after=str(path))
+
+        assert [(r["before"]["name"], r["after"]["name"]) for r in result["renamed_nodes"]] == [("helper", "assist")]
+        assert result["summary"]["renamed_nodes"] == 1
+        assert result["added_nodes"] == result["removed_nodes"] == result["caller_changes"] == []
+
+    def test_caller_changes(self, tools):
+        after = self.BEFORE.replace("return helper()", "return 2")
+        result = _call(tools, "graph_diff", before=self._snapshot(self.BEFORE), after=self._snapshot(after))
+        assert [(c["name"], [n["name"] for n in c["lost"]]) for c in result["caller_changes"]] == \
+            [("helper", ["main"])]
+
+    def test_invalid_snapshot(self, tools, tmp_path):
+        assert "Invalid snapshot" in _call(tools, "graph_diff", before="{}", after="{}")["error"]
+        missing = str(tmp_path / "missing.json")
+        assert "Invalid snapshot" in _call(tools, "graph_diff", before=missing, after=missing)["error"]
+
+
+class TestGetPublicApi:
+    def test_public_symbols_and_edges(self, tools):
+        result = _call(tools, "get_public_api")
+        assert [(i["kind"], i["name"]) for i in result["items"]] == [("Class", "Person"), ("Function", "helper")]
+        assert result["count"] == 2
+        assert result["items"][0]["doc"] == "A person with a name and an age."
+        assert result["edges"] == []
+
+    def test_snapshot_diff(self, backend, tools, tmp_path):
+        snapshot = str(tmp_path / "api.json")
+        assert _call(tools, "get_public_api", save_snapshot=snapshot)["saved_snapshot"] == snapshot
+
+        unchanged = _call(tools, "get_public_api", diff_against=snapshot)
+        assert (unchanged["added"], unchanged["removed"], unchanged["suggested_bump"]) == ([], [], "patch")
+
+        narrowed = _make_tools(_db(backend, [dict(PERSON, visibility="Crate"), HELPER], []))
+        diff = _call(narrowed, "get_public_api", diff_against=snapshot)
+        assert [i["name"] for i in diff["removed"]] == ["Person"]
+        assert diff["suggested_bump"] == "major"
+
+    def test_missing_snapshot_is_an_error(self, tools, tmp_path):
+        assert "error" in _call(tools, "get_public_api", diff_against=str(tmp_path / "missing.json"))
+
+
+class TestParseDiagnostics:
+    BROKEN = os.path.join(os.path.dirname(os.path.abspath(__file__)), "fixtures", "broken_sample", "inventory.py")
+
+    def test_broken_file(self, tools):
+        result = _call(tools, "parse_diagnostics", path=self.BROKEN)
+
+        assert (result["files_parsed"], result["files_with_errors"], result["errors"]) == (1, 1, 1)
+        [diagnostics] = result["files"]
+        assert diagnostics["partial"] is True
+        assert diagnostics["errors"][0]["line_no"] == 17
+
+    def test_buffer(self, tools):
+        result = _call(tools, "parse_diagnostics", path="buffer.py", source="def ok():\n    return 1\n")
+        assert (result["files_parsed"], result["errors"], result["files"]) == (1, 0, [])
+
+    def test_missing_path_is_an_error(self, tools, tmp_path):
+        assert "error" in _call(tools, "parse_diagnostics", path=str(tmp_path / "missing.py"))
+
+    @staticmethod
+    def _resource(diagnostics):
+        with patch.object(server_module, 'FastMCP', RecordingMCP), \
+             patch.object(server_module, 'Neo4jDatabase', return_value=_sqlite_db(NODES, CALLS)), \
+             patch.object(server_module, 'get_embedding_provider'), \
+             patch.object(server_module, 'CodeEmbedder'):
+            mcp_server = server_module.CodebaseKnowledgeGraphMCP(
+                neo4j_uri="mock_uri", neo4j_user="mock_user", neo4j_password="mock_pass", diagnostics=diagnostics
+            )
+        return json.loads(mcp_server.mcp.resources["diagnostics://parse"]())
+
+    def test_indexing_diagnostics_resource(self):
+        from src.ast_parser.diagnostics import FILE_TOO_LARGE, ParseDiagnostics, ParseError
+
+        diagnostics = ParseDiagnostics()
+        diagnostics.record("app.py", [ParseError("Syntax error at 'def'", 3, 5)], partial=True)
+        diagnostics.skip("big.py", FILE_TOO_LARGE, "Larger than 10 bytes")
+        result = self._resource(diagnostics)
+
+        assert (result["files_parsed"], result["files_failed"], result["complete"]) == (1, 1, False)
+        assert result["diagnostics"] == [
+            {"file": "app.py", "line": 3, "column": 5, "error_kind": "SyntaxError", "message": "Syntax error at 'def'"},
+            {"file": "big.py", "line": None, "column": None, "error_kind": "FileTooLarge",
+             "message": "Larger than 10 bytes"},
+        ]
+
+    def test_resource_without_an_indexing_run(self):
+        assert "error" in self._resource(None)
+
+
+class TestFindUnreferencedSymbols:
+    @pytest.fixture
+    def dead(self, backend):
+        nodes = [
+            MAIN, LOAD, HELPER, CLI,
+            dict(_node("unused", "app.py", 20), visibility="Private"),
+            dict(_node("legacy", "app.py", 30), visibility="Public"),
+            dict(_node("route", "app.py", 40), decorators='["app.get(\'/\')"]'),
+            dict(_node("handler", "app.py", 50), visibility="Private", attributes='["tokio::main"]'),
+            dict(_node("Tidy", "app.py", 60, "Class"), visibility="Private", attributes='["derive(Debug)"]'),
+        ]
+        return _make_tools(_db(backend, nodes, CALLS[:4]))
+
+    def test_unreferenced_symbols_by_confidence(self, dead):
+        result = _call(dead, "find_unreferenced_symbols")
+        # cli is only reached from outside; main is an entry point; route and
+        # handler are registered by a decorator and an attribute macro
+        assert [(s["name"], s["confidence"]) for s in result["symbols"]] == [
+            ("unused", "high"), ("Tidy", "high"), ("cli", "medium"), ("legacy", "low"),
+        ]
+        assert result["symbols"][0]["kind"] == "Function"
+        assert (result["symbols"][0]["file_path"], result["symbols"][0]["line_no"]) == ("app.py", 20)
+        assert result["symbols"][3]["reason"].startswith("public")
+
+    def test_allowlist_and_public_exemption(self, dead):
+        result = _call(dead, "find_unreferenced_symbols", allowlist=["^cl"], exempt_public=True,
+                       min_confidence="medium")
+        assert [s["name"] for s in result["symbols"]] == ["unused", "Tidy"]
+        assert "error" in _call(dead, "find_unreferenced_symbols", allowlist=["("])
+        assert "error" in _call(dead, "find_unreferenced_symbols", min_confidence="certain")
+
+
+class TestFindTestsFor:
+    @pytest.fixture
+    def covered(self, backend):
+        nodes = [MAIN, LOAD, HELPER, CLI,
+                 dict(_node("test_load", "test_app.py", 3), is_test=True),
+                 dict(_node("test_main", "test_app.py", 8), is_test=True)]
+        calls = CALLS[:4] + [(nodes[4]["id"], LOAD["id"], 4, "load()"), (nodes[5]["id"], MAIN["id"], 9, "main()")]
+        return _make_tools(_db(backend, nodes, calls))
+
+    def test_direct_and_transitive_tests(self, covered):
+        result = _call(covered, "find_tests_for", symbol="helper")
+        # helper <- load <- test_load; helper <- main <- test_main, cli is no test
+        assert [(t["name"], t["depth"], t["direct"]) for t in result["tests"]] == [
+            ("test_load", 2, False), ("test_main", 2, False)]
+        assert result["tests"][0]["via"] == [HELPER["id"], LOAD["id"]]
+        assert (result["symbol"], result["total"], result["direct"]) == ("helper", 2, 0)
+
+        result = _call(covered, "find_tests_for", symbol="load", max_depth=1)
+        assert [(t["name"], t["file_path"], t["line_no"]) for t in result["tests"]] == [("test_load", "test_app.py", 3)]
+        assert _call(covered, "find_tests_for", symbol="helper", max_depth=1)["tests"] == []
+
+    def test_paginate_from_continues_past_the_frontier(self, covered):
+        first = _call(covered, "find_tests_for", symbol="helper", max_depth=1)
+        # load and main have callers of their own, tests among them
+        assert first["truncated"] is True
+        assert sorted(first["frontier"]) == sorted([LOAD["id"], MAIN["id"]])
+
+        more = _call(covered, "find_tests_for", max_depth=1, paginate_from=first["frontier"])
+        assert [(t["name"], t["depth"]) for t in more["tests"]] == [("test_load", 1), ("test_main", 1)]
+        # cli has no callers left
+        assert (more["truncated"], more["frontier"]) == (False, [])
+
+    def test_unknown_symbol(self, covered):
+        assert "error" in _call(covered, "find_tests_for", symbol="missing")
+
+
+class TestFindUnused:
+    @pytest.fixture
+    def unused(self, backend):
+        nodes = [
+            MAIN, LOAD, HELPER, CLI,
+            dict(_node("greet", "sample.rs", 27), visibility="Public"),
+            dict(_node("add", "sample.rs", 31), visibility="Private"),
+            dict(_node("check_add", "sample.rs", 40), visibility="Private", attributes='["test"]'),
+        ]
+        return _make_tools(_db(backend, nodes, CALLS[:4]))
+
+    def test_private_unused_functions(self, unused):
+        result = _call(unused, "find_unused")
+        # greet is public, main and the #[test] function are entry points
+        assert [(s["name"], s["file_path"], s["line_no"]) for s in result["symbols"]] == [
+            ("add", "sample.rs", 31), ("cli", "cli.py", 3),
+        ]
+        assert [s["name"] for s in _call(unused, "find_unused", include_public=True)["symbols"]] == \
+            ["add", "cli", "greet"]
+
+    def test_entrypoint_rules(self, unused):
+        result = _call(unused, "find_unused", entrypoints={"python": {"names": ["^main$", "^cli$"]},
+                                                            "rust": {"names": ["^main$"]}})
+        assert [s["name"] for s in result["symbols"]] == ["add", "check_add"]
+        assert "error" in _call(unused, "find_unused", entrypoints={"rust": {"decorators": []}})
+        assert "error" in _call(unused, "find_unused", entrypoints={"rust": {"names": ["("]}})
+
+
+class TestFindDeadCode:
+    @pytest.fixture
+    def crate(self, backend):
+        main = dict(_node("main", "main.rs", 1), visibility="Private")
+        run = dict(_node("run", "main.rs", 5), visibility="Private")
+        orphan = dict(_node("orphan", "main.rs", 9), visibility="Private")
+        check = dict(_node("check", "main.rs", 13), visibility="Private", attributes='["test"]', is_test=True)
+        return _make_tools(_db(backend, [main, run, orphan, check], [
+            (main["id"], run["id"], 2, "run();"),
+            (check["id"], orphan["id"], 14, "orphan();"),
+        ]))
+
+    def test_unreachable_from_main(self, crate):
+        result = _call(crate, "find_dead_code")
+        assert [(s["name"], s["file_path"], s["line_no"], s["confidence"]) for s in result["symbols"]] == [
+            ("orphan", "main.rs", 9, "high"),
+        ]
+        assert _call(crate, "find_dead_code", include_tests=True)["symbols"] == []
+
+    def test_no_entry_points_is_an_error(self, crate):
+        assert "error" in _call(crate, "find_dead_code", entry_points=["start"])
+
+
+class TestFindSyncInAsync:
+    @pytest.fixture
+    def service(self, backend):
+        handle = dict(_node("handle", "src/server.rs", 1), is_async=True)
+        fetch = dict(_node("fetch", "src/server.rs", 10), is_async=True)
+        load = dict(_node("read_config", "src/config.rs", 1), is_async=False)
+        connect = dict(_node("connect", "src/net/client.rs", 1), is_async=False)
+        startup = dict(_node("startup", "src/main.rs", 1), is_async=False)
+        return _make_tools(_db(backend, [handle, fetch, load, connect, startup], [
+            (handle["id"], load["id"], 2, "let config = read_config();"),
+            (handle["id"], connect["id"], 3, "let client = connect(&config);"),
+            (handle["id"], fetch["id"], 4, "let page = fetch(client);"),
+            (startup["id"], load["id"], 2, "read_config();"),
+        ]))
+
+    def test_blocking_calls_from_async_functions(self, service):
+        result = _call(service, "find_sync_in_async")
+        # fetch is async itself and startup is not async
+        assert [(h["function"], h["callee"], h["line_no"], h["reasons"]) for h in result["hazards"]] == [
+            ("handle", "read_config", 2, ["name contains 'read'"]),
+            ("handle", "connect", 3, ["in module 'net'"]),
+        ]
+        assert result["hazards"][0]["call_site"] == "let config = read_config();"
+        assert (result["count"], result["truncated"]) == (2, False)
+        assert _call(service, "find_sync_in_async", limit=1)["truncated"] is True
+
+
+class TestImpactOf:
+    def test_direct_and_transitive_dependents(self, tools):
+        result = _call(tools, "impact_of", symbol="helper")
+        assert (result["total"], result["direct"], result["transitive"]) == (3, 2, 1)
+        assert [(f["file_path"], [(s["name"], s["depth"]) for s in f["symbols"]]) for f in result["files"]] == [
+            ("app.py", [("load", 1), ("main", 1)]), ("cli.py", [("cli", 2)])]
+        cli = result["files"][1]["symbols"][0]
+        assert [(step["name"], step["relation"]) for step in cli["path"]] == \
+            [("helper", None), ("main", "CALLS"), ("cli", "CALLS")]
+
+    def test_depth_and_limit(self, tools):
+        result = _call(tools, "impact_of", symbol="helper", max_depth=1)
+        assert (result["total"], result["truncated"], result["frontier"]) == (2, True, [MAIN["id"]])
+        more = _call(tools, "impact_of", max_depth=1, paginate_from=result["frontier"])
+        assert [s["name"] for f in more["files"] for s in f["symbols"]] == ["cli"]
+        assert more["frontier"] == []
+        result = _call(tools, "impact_of", symbol="helper", limit=1)
+        assert (result["total"], result["truncated"]) == (3, True)
+        assert sum(len(f["symbols"]) for f in result["files"]) == 1
+
+    def test_types_stand_for_their_members(self, tools):
+        result = _call(tools, "impact_of", symbol="Shape")
+        affected = {(s["name"], s["type"]) for f in result["files"] for s in f["symbols"]}
+        assert affected == {("Circle", "Class"), ("Square", "Class"), ("area", "Method")}
+        assert result["total"] == 4
+
+    def test_file_paths(self, tools):
+        result = _call(tools, "impact_of", file_paths=["util.py"])
+        assert result["total"] == 3 and HELPER["id"] in result["seeds"]
+        assert "error" in _call(tools, "impact_of", file_paths=["missing.py"])
+        assert "error" in _call(tools, "impact_of")
+
+
+class TestChangeImpact:
+    def test_ranked_by_dependency_paths(self, tools):
+        result = _call(tools, "change_impact", file_paths=["util.py"])
+        assert (result["changed_files"], result["total"]) == (["util.py"], 3)
+        # main calls helper directly and through load, and cli calls main
+        assert [(s["name"], s["paths"], s["depth"]) for s in result["symbols"]] == \
+            [("main", 2, 1), ("cli", 2, 2), ("load", 1, 1)]
+        assert [(f["file_path"], f["impact"], f["paths"]) for f in result["files"]] == \
+            [("app.py", "direct", 3), ("cli.py", "transitive", 2)]
+        assert (result["direct_files"], result["transitive_files"]) == (["app.py"], ["cli.py"])
+
+    def test_depth_and_errors(self, tools):
+        assert _call(tools, "change_impact", file_paths=["util.py"], depth=1)["transitive_files"] == []
+        assert "error" in _call(tools, "change_impact", file_paths=["missing.py"])
+        assert "error" in _call(tools, "change_impact")
+
+    @pytest.mark.skipif(shutil.which("git") is None, reason="git is not installed")
+    def test_files_changed_since_a_ref(self, tools, tmp_path):
+        def git(*args):
+            subprocess.run(["git", "-C", str(tmp_path), *args], check=True, capture_output=True)
+
+        git("init", "-q")
+        git("config", "user.email", "dev@example.com")
+        git("config", "user.name", "Dev")
+        (tmp_path / "util.py").write_text("def helper():\n    return 1\n", encoding="utf-8")
+        (tmp_path / "notes.txt").write_text("todo\n", encoding="utf-8")
+        git("add", "-A")
+        git("commit", "-q", "-m", "first")
+        (tmp_path / "util.py").write_text("def helper():\n    return 2\n", encoding="utf-8")
+        (tmp_path / "notes.txt").write_text("done\n", encoding="utf-8")
+
+        result = _call(tools, "change_impact", base_ref="HEAD", repo_path=str(tmp_path))
+        assert (result["changed_files"], result["unindexed"]) == (["util.py"], ["notes.txt"])
+        assert result["direct_files"] == ["app.py"]
+        assert "no-such-branch" in _call(tools, "change_impact", base_ref="no-such-branch",
+                                         repo_path=str(tmp_path))["error"]
+
+
+class TestFindDuplicates:
+    SOURCE = """def total(items):
+    result = 0
+    for item in items:
+        if item > 0:
+            result += item
+    return result
+
+
+def summed(values):
+    # The same loop under other names
+    acc = 0
+    for v in values:
+        if v > 0:
+            acc += v
+    return acc
+
+
+def scaled(values):
+    acc = 0
+    for v in values:
+        if v > 0:
+            acc += v * 2
+    return acc
+"""
+
+    @pytest.fixture
+    def copies(self, backend, tmp_path):
+        source = tmp_path / "copies.py"
+        source.write_text(self.SOURCE, encoding="utf-8")
+        nodes = [dict(_node(name, str(source), line_no), end_line_no=end_line_no)
+                 for name, line_no, end_line_no in (("total", 1, 6), ("summed", 9, 15), ("scaled", 18, 23))]
+        return _make_tools(_db(backend, nodes, []))
+
+    def test_exact_copies(self, copies):
+        result = _call(copies, "find_duplicates", min_tokens=5)
+        group, = result["groups"]
+        assert [(m["function_name"], m["line"]) for m in group["members"]] == [("total", 1), ("summed", 9)]
+        assert (result["functions"], group["similarity"]) == (3, 1.0)
+
+    def test_fuzzy_copies(self, copies):
+        result = _call(copies, "find_duplicates", mode="fuzzy", threshold=0.8, min_tokens=5)
+        assert [m["function_name"] for m in result["groups"][0]["members"]] == ["total", "summed", "scaled"]
+        assert result["groups"][0]["similarity"] < 1.0
+        # Two edits apart, scaled is not linked when fewer are computed
+        assert _call(copies, "find_duplicates", mode="fuzzy", threshold=0.8, min_tokens=5,
+                     max_distance=1)["groups"][0]["size"] == 2
+
+    def test_invalid_arguments(self, copies):
+        assert "error" in _call(copies, "find_duplicates", mode="approximate")
+        assert "error" in _call(copies, "find_duplicates", mode="fuzzy", threshold=1.5)
+
+
+class TestGraphMetrics:
+    def test_fan_in_fan_out_and_complexity(self, tools):
+        result = _call(tools, "get_metrics", symbol="main")
+        assert [(m["name"], m["fan_in"], m["fan_out"], m["complexity"]) for m in result["metrics"]] == \
+            [("main", 1, 2, 12)]
+
+    def test_fan_in_counts_call_sites(self, tools):
+        helper = _call(tools, "get_metrics", symbol=HELPER["id"])["metrics"][0]
+        assert (helper["fan_in"], helper["fan_out"], helper["complexity"]) == (2, 0, None)
+        recurse = _call(tools, "get_metrics", symbol="recurse")["metrics"][0]
+        assert (recurse["fan_in"], recurse["fan_out"]) == (1, 1)
+
+    def test_only_functions_have_metrics(self, tools):
+        assert "error" in _call(tools, "get_metrics", symbol="Person")
+
+    def test_top_complex(self, tools):
+        result = _call(tools, "top_complex", n=3)
+        assert [(f["name"], f["complexity"], f["fan_in"]) for f in result["functions"]] == \
+            [("main", 12, 1), ("birthday", 7, 0), ("load", 3, 1)]
+        assert len(_call(tools, "top_complex", n=1)["functions"]) == 1
+
+
+def _pages(tools, name, **kwargs):
+    """Follow next_cursor until the last page; returns the pages."""
+    pages, cursor = [], None
+    while True:
+        result = _call(tools, name, cursor=cursor, **kwargs)
+        pages.append(result)
+        cursor = result["next_cursor"]
+        if cursor is None:
+            return pages
+
+
+class TestPagination:
+    def test_references_page_through_everything(self, tools):
+        full = _call(tools, "find_references", symbol_id=AGE["id"])
+        pages = _pages(tools, "find_references", symbol_id=AGE["id"], limit=1)
+
+        assert full["next_cursor"] is None
+        assert [len(p["references"]) for p in pages] == [1, 1]
+        assert [r for p in pages for r in p["references"]] == full["references"]
+
+    def test_callers_page_in_walk_order(self, tools):
+        full = _call(tools, "find_callers", symbol="helper", depth=3)
+        pages = _pages(tools, "find_callers", symbol="helper", depth=3, limit=2)
+
+        assert [len(p["callers"]) for p in pages] == [2, 1]
+        assert [c["id"] for p in pages for c in p["callers"]] == [c["id"] for c in full["callers"]]
+        assert pages[-1]["truncated"] is False
+
+    def test_interleaved_queries_keep_their_place(self, tools):
+        first = _call(tools, "find_callers", symbol="helper", depth=3, limit=1)
+        _call(tools, "find_references", symbol_id=NAME["id"], limit=1)
+        _call(tools, "find_callers", symbol="ping", depth=3, limit=1)
+        second = _call(tools, "find_callers", symbol="helper", depth=3, limit=1,
+                       cursor=first["next_cursor"])
+
+        assert second["callers"][0]["id"] != first["callers"][0]["id"]
+
+    def test_cursor_from_another_query_is_rejected(self, tools):
+        cursor = _call(tools, "find_callers", symbol="helper", depth=3, limit=1)["next_cursor"]
+        assert "error" in _call(tools, "find_callers", symbol="helper", depth=2, cursor=cursor)
+        assert "error" in _call(tools, "find_references", symbol_id=HELPER["id"], cursor=cursor)
+        assert "error" in _call(tools, "find_callers", symbol="helper", cursor="not-a-cursor")
+
+    def test_only_one_page_is_read(self):
+        pulled = []
+
+        def references(node_ids):
+            for i in range(1000):
+                pulled.append(i)
+                yield {"target_id": NAME["id"], "relation": "REFERENCES", "access": "Read",
+                       "node": GET_NAME, "line_no": i, "snippet": "&self.name"}
+
+        page, next_cursor = server_module.paginate(references([NAME["id"]]), 10, None, "scope")
+        assert len(page) == 10 and next_cursor is not None
+        assert len(pulled) == 11
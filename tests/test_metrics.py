@@ -0,0 +1,135 @@
+"""
+Tests for per-function metrics: fan-in, fan-out and cyclomatic complexity.
+
+Uses the legacy Python parser so no optional parsing backends are needed.
+"""
+
+import ast
+import os
+import sys
+import textwrap
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.ast_parser.complexity import python_complexity
+from src.ast_parser.parser import CodeNode, CodeRelation
+from src.graph.code_graph import UNRESOLVED_PREFIX, CodeGraph
+from src.graph.metrics import top_complex
+
+SAMPLE = os.path.join(os.path.dirname(os.path.abspath(__file__)), "fixtures", "multi_lang_sample", "sample.py")
+
+BRANCHY = '''
+def classify(items, strict):
+    if not items:
+        return None
+    elif strict and len(items) > 1 or items[0] is None:
+        pass
+    else:
+        pass
+    for item in items:
+        while item:
+            item -= 1
+    try:
+        total = sum(x for x in items if x > 0)
+    except TypeError:
+        total = 0
+    except ValueError:
+        total = -1
+    match total:
+        case 0:
+            pick = lambda v: v if v else 0
+        case _:
+            pass
+
+    def inner(value):
+        return value if value else None
+
+    class Local:
+        def method(self):
+            if self:
+                return 1
+    return total
+'''
+
+
+def _complexity(source):
+    return python_complexity(ast.parse(textwrap.dedent(source)).body[0])
+
+
+def _by_name(graph, name):
+    return next(node for node in graph.nodes.values() if node.name == name)
+
+
+@pytest.fixture
+def sample_graph():
+    graph = CodeGraph()
+    graph.add_file(SAMPLE)
+    return graph
+
+
+class TestComplexity:
+
+    def test_straight_line_function(self):
+        assert _complexity("def add(a, b):\n    return a + b\n") == 1
+
+    def test_decision_points(self):
+        # if, elif, `and`, `or`, for, while, the generator's for and if, two
+        # excepts, two cases and the lambda's conditional; inner and Local
+        # are counted on their own
+        assert _complexity(BRANCHY) == 14
+
+    def test_nested_functions_are_measured_alone(self):
+        graph = CodeGraph()
+        graph.add_sources({"branchy.py": BRANCHY})
+        assert _by_name(graph, "inner").properties["complexity"] == 2
+        assert _by_name(graph, "classify").properties["complexity"] == 14
+
+
+class TestFunctionMetrics:
+
+    def test_add_is_simple_and_uncalled(self, sample_graph):
+        metrics = sample_graph.metrics(_by_name(sample_graph, "add").node_id)
+        assert (metrics.complexity, metrics.fan_in, metrics.fan_out) == (1, 0, 0)
+
+    def test_calls_between_functions(self, sample_graph):
+        greet = sample_graph.metrics(_by_name(sample_graph, "greet").node_id)
+        find_home = sample_graph.metrics(_by_name(sample_graph, "find_home").node_id)
+        assert (greet.fan_in, greet.fan_out) == (1, 0)
+        # greet and person.get_name(), behind a conditional expression
+        assert (find_home.fan_out, find_home.complexity) == (2, 2)
+
+    def test_only_functions_and_methods(self, sample_graph):
+        assert sample_graph.metrics(_by_name(sample_graph, "Person").node_id) is None
+        assert sample_graph.metrics("Function:missing") is None
+
+
+class TestTopComplex:
+
+    @pytest.fixture
+    def graph(self):
+        def function(name, line_no, complexity=None):
+            properties = {} if complexity is None else {"complexity": complexity}
+            return CodeNode(f"Function:a.py:{name}:{line_no}", "Function", name, "a.py", line_no,
+                            properties=properties)
+        nodes = [function("parse", 1, 6), function("emit", 10, 6), function("main", 20, 9),
+                 function("stub", 30)]
+        parse, emit, main, _ = (node.node_id for node in nodes)
+        relations = [CodeRelation(main, parse, "CALLS"), CodeRelation(main, emit, "CALLS"),
+                     CodeRelation(emit, parse, "CALLS"), CodeRelation(main, parse, "CALLS"),
+                     CodeRelation(main, UNRESOLVED_PREFIX + "print", "CALLS")]
+        return CodeGraph.from_records(nodes, relations)
+
+    def test_ordered_by_complexity_then_fan_in(self, graph):
+        assert [(m.name, m.complexity, m.fan_in) for m in top_complex(graph, 5)] == \
+            [("main", 9, 0), ("parse", 6, 3), ("emit", 6, 1)]
+        assert [m.name for m in top_complex(graph, 1)] == ["main"]
+
+    def test_fan_out_counts_distinct_resolved_callees(self, graph):
+        assert top_complex(graph, 1)[0].fan_out == 2
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
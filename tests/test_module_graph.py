@@ -0,0 +1,120 @@
+"""
+Tests for the file-level module graph and its DOT export.
+"""
+
+import os
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.ast_parser.parser import CodeNode, CodeRelation
+from src.graph.code_graph import CodeGraph
+from src.graph.export import DotExporter, DotOptions, ExportTooLargeError
+from src.graph.module_graph import ModuleGraph
+
+
+def _function(name, file_path, line_no=1):
+    return CodeNode(f"Function:{file_path}:{name}", "Function", name, file_path, line_no)
+
+
+MAIN = _function("main", "app.py")
+RUN = _function("run", "app.py", 5)
+LOAD = _function("load", "store.py")
+SAVE = _function("save", "store.py", 5)
+HELPER = _function("helper", "util.py")
+
+
+@pytest.fixture
+def graph():
+    return CodeGraph.from_records(
+        [MAIN, RUN, LOAD, SAVE, HELPER],
+        [
+            CodeRelation(MAIN.node_id, LOAD.node_id, "CALLS"),
+            CodeRelation(MAIN.node_id, SAVE.node_id, "CALLS"),
+            CodeRelation(RUN.node_id, SAVE.node_id, "REFERENCES"),
+            CodeRelation(MAIN.node_id, RUN.node_id, "CALLS"),
+            CodeRelation(LOAD.node_id, HELPER.node_id, "CALLS"),
+            CodeRelation(HELPER.node_id, "unresolved:json.dumps", "CALLS",
+                         {"raw_name": "json.dumps", "unresolved": True}),
+        ],
+    )
+
+
+class TestModuleGraph:
+    def test_cross_file_references_are_collapsed_and_weighted(self, graph):
+        modules = ModuleGraph.from_code_graph(graph)
+
+        assert modules.files == ["app.py", "store.py", "util.py"]
+        assert [(e.source, e.target, e.weight) for e in modules.edges] == [
+            ("app.py", "store.py", 3), ("store.py", "util.py", 1),
+        ]
+        assert modules.edges[0].relations == {"CALLS": 2, "REFERENCES": 1}
+
+    def test_neighbours(self, graph):
+        modules = ModuleGraph.from_code_graph(graph)
+        assert [e.target for e in modules.dependencies("store.py")] == ["util.py"]
+        assert [e.source for e in modules.dependents("store.py")] == ["app.py"]
+
+    def test_to_dict(self, graph):
+        assert ModuleGraph.from_code_graph(graph).to_dict()["edges"][1] == {
+            "source": "store.py", "target": "util.py", "weight": 1, "relations": {"CALLS": 1},
+        }
+
+    def test_parsed_imports_and_calls(self, tmp_path):
+        (tmp_path / "orders.py").write_text("from customers import lookup\n\n\ndef place(name):\n"
+                                            "    return lookup(name)\n", encoding="utf-8")
+        (tmp_path / "customers.py").write_text("def lookup(name):\n    return name\n", encoding="utf-8")
+        modules = ModuleGraph.from_code_graph(CodeGraph.from_directory(str(tmp_path)))
+
+        [edge] = modules.edges
+        assert (os.path.basename(edge.source), os.path.basename(edge.target)) == ("orders.py", "customers.py")
+        assert edge.relations["CALLS"] == 1
+
+
+class TestWorkspaceMembers:
+    @pytest.fixture
+    def modules(self, graph):
+        files = [CodeNode(f"file:{file_path}", "File", file_path, file_path, 0, properties={"workspace_member": member})
+                 for file_path, member in (("app.py", "web"), ("store.py", "web"), ("util.py", "shared"))]
+        return ModuleGraph.from_code_graph(CodeGraph.from_records(list(graph.nodes.values()) + files,
+                                                                  graph.relations))
+
+    def test_files_grouped_by_member(self, modules):
+        assert modules.members == {"shared": ["util.py"], "web": ["app.py", "store.py"]}
+        assert modules.to_dict()["members"] == [{"name": "shared", "files": ["util.py"]},
+                                                {"name": "web", "files": ["app.py", "store.py"]}]
+
+    def test_members_are_dot_clusters(self, modules):
+        dot = DotExporter().to_dot(modules)
+        assert '  subgraph "cluster_1" {\n    label="web";\n    "app.py";\n    "store.py";\n  }' in dot
+        assert '"app.py" -> "store.py"' in dot
+
+
+class TestModuleDot:
+    def test_files_and_weighted_edges(self, graph):
+        dot = DotExporter().to_dot(ModuleGraph.from_code_graph(graph))
+
+        assert '  "app.py";' in dot
+        assert '"app.py" -> "store.py" [label="3", weight=3, penwidth=5.00];' in dot
+        assert '"store.py" -> "util.py" [label="1", weight=1, penwidth=2.33];' in dot
+        assert "unresolved" not in dot
+
+    def test_filters(self, graph):
+        modules = ModuleGraph.from_code_graph(graph)
+
+        dot = DotExporter().to_dot(modules, DotOptions(edge_types=["REFERENCES"], edge_labels=False))
+        assert '"app.py" -> "store.py" [weight=1, penwidth=5.00];' in dot
+        assert '"store.py" -> "util.py"' not in dot
+
+        dot = DotExporter().to_dot(modules, DotOptions(file_glob="*s*.py"))
+        assert '"util.py"' not in dot and '"app.py" -> "store.py"' not in dot
+
+        with pytest.raises(ExportTooLargeError, match="3 files"):
+            DotExporter().to_dot(modules, DotOptions(max_nodes=2))
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
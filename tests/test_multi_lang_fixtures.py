@@ -0,0 +1,212 @@
+"""
+Fixture harness for the multi-language sample directory.
+
+Every file in tests/fixtures/multi_lang_sample/ describes the same small
+program (a Person type plus a couple of free functions) in a different
+language; PersonCard.tsx adds a React component importing people.ts, and
+sample.ts imports it through both ES imports and CommonJS require(). These
+tests parse the fixtures through MultiLanguageParser and check that each
+language produces the expected graph shape.
+"""
+
+import os
+import sys
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.ast_parser.parser import ASTParser
+from src.ast_parser.multi_parser import MultiLanguageParser
+from tests.helpers import node_names
+
+
+FIXTURE_DIR = os.path.abspath(os.path.join(os.path.dirname(__file__), "fixtures", "multi_lang_sample"))
+SAMPLE_PY = os.path.join(FIXTURE_DIR, "sample.py")
+PEOPLE_TS = os.path.join(FIXTURE_DIR, "people.ts")
+PERSON_CARD_TSX = os.path.join(FIXTURE_DIR, "PersonCard.tsx")
+SAMPLE_TS = os.path.join(FIXTURE_DIR, "sample.ts")
+
+
+def _edge_targets(nodes, relations, relation_type, source_path):
+    """(node type, name) of every target of one file's edges of a given type."""
+    return {
+        (nodes[r.target_id].node_type, nodes[r.target_id].name) for r in relations
+        if r.relation_type == relation_type and r.source_id == f"file:{source_path}"
+    }
+
+
+class TestPythonFixture:
+    """Python support in the multi-language pipeline (legacy and ast-grep paths)."""
+
+    @pytest.fixture(params=[False, True], ids=["legacy", "ast-grep"])
+    def parsed(self, request):
+        """Parse sample.py through MultiLanguageParser with each backend."""
+        if request.param:
+            pytest.importorskip("ast_grep_py")
+        parser = MultiLanguageParser(
+            use_ast_grep=request.param,
+            ast_grep_languages=['python'],
+            ast_grep_fallback=False
+        )
+        return parser.parse_file(SAMPLE_PY, build_index=True)
+
+    def test_module_level_functions(self, parsed):
+        nodes, _ = parsed
+        assert node_names(nodes, "Function") == {"greet", "add", "find_home"}
+
+    def test_class_and_methods(self, parsed):
+        nodes, relations = parsed
+        assert node_names(nodes, "Class") == {"Person"}
+        assert node_names(nodes, "Method") == {"__init__", "get_name", "set_name", "get_age"}
+
+        class_id = next(nid for nid, n in nodes.items() if n.node_type == "Class")
+        defined = {
+            nodes[r.target_id].name for r in relations
+            if r.relation_type == "DEFINES" and r.source_id == class_id
+            and r.target_id in nodes and nodes[r.target_id].node_type == "Method"
+        }
+        assert defined == {"__init__", "get_name", "set_name", "get_age"}
+
+    def test_file_contains_top_level_definitions(self, parsed):
+        nodes, relations = parsed
+        file_id = f"file:{SAMPLE_PY}"
+        assert file_id in nodes
+        contained = {nodes[r.target_id].name for r in relations
+                     if r.relation_type == "CONTAINS" and r.source_id == file_id and r.target_id in nodes}
+        assert {"Person", "greet", "add", "find_home"} <= contained
+
+    def test_imports_are_queued_for_resolution(self):
+        """Both `import x` and `from x import y` are recorded for the second pass."""
+        parser = ASTParser()
+        parser.parse_file(SAMPLE_PY, build_index=True)
+        kinds = {(p["type"], p["imported_module"]) for p in parser.pending_imports
+                 if p["type"] in ("IMPORTS_MODULE", "IMPORTS_SYMBOL")}
+        assert ("IMPORTS_MODULE", "os") in kinds
+        assert ("IMPORTS_SYMBOL", "typing") in kinds
+
+
+class TestTypeScriptFixture:
+    """TypeScript and TSX support in the multi-language pipeline (legacy and ast-grep paths)."""
+
+    @pytest.fixture(params=[False, True], ids=["legacy", "ast-grep"])
+    def parsed(self, request):
+        """Parse the TypeScript fixtures together and resolve their imports."""
+        if request.param:
+            pytest.importorskip("ast_grep_py")
+        parser = MultiLanguageParser(
+            use_ast_grep=request.param,
+            ast_grep_languages=['typescript'],
+            ast_grep_fallback=False
+        )
+        parser.parse_file(PEOPLE_TS, build_index=True)
+        parser.parse_file(PERSON_CARD_TSX, build_index=True)
+        parser.parse_file(SAMPLE_TS, build_index=True)
+        parser._process_pending_imports()
+        return parser.nodes, parser.relations
+
+    def test_type_declarations(self, parsed):
+        nodes, _ = parsed
+        assert node_names(nodes, "Interface", PEOPLE_TS) == {"Named"}
+        assert node_names(nodes, "TypeAlias", PEOPLE_TS) == {"PersonId"}
+        assert node_names(nodes, "Enum", PEOPLE_TS) == {"Role"}
+        assert node_names(nodes, "Interface", PERSON_CARD_TSX) == {"PersonCardProps"}
+
+        role = next(n for n in nodes.values() if n.node_type == "Enum")
+        assert role.properties["members"] == ["Admin", "Guest"]
+        assert role.properties["is_const"] is True
+        alias = next(n for n in nodes.values() if n.node_type == "TypeAlias")
+        assert alias.properties["value"] == "string | number"
+
+    def test_classes_and_functions(self, parsed):
+        nodes, _ = parsed
+        assert node_names(nodes, "Class", PEOPLE_TS) == {"Entity", "Person"}
+        assert {"getName", "setName", "getAge", "describe"} <= node_names(nodes, "Method", PEOPLE_TS)
+        assert node_names(nodes, "Function", PEOPLE_TS) == {"greet", "add"}
+
+        add = next(n for n in nodes.values() if n.name == "add" and n.node_type == "Function")
+        assert add.properties["function_style"] == "arrow"
+        assert add.properties["exported"] is True
+
+    def test_react_components_are_functions(self, parsed):
+        nodes, _ = parsed
+        functions = {n.name: n for n in nodes.values()
+                     if n.node_type == "Function" and n.file_path == PERSON_CARD_TSX}
+        assert set(functions) == {"PersonCard", "PersonBadge", "formatAge"}
+        assert functions["PersonCard"].properties.get("react_component") is True
+        assert functions["PersonBadge"].properties.get("react_component") is True
+        assert "react_component" not in functions["formatAge"].properties
+        assert functions["PersonCard"].properties["export_type"] == "default"
+
+    def test_import_edges(self, parsed):
+        nodes, relations = parsed
+        assert _edge_targets(nodes, relations, "IMPORTS", PERSON_CARD_TSX) == {("Class", "Person"), ("Function", "greet")}
+        # Re-exports get their own edge type instead of counting as direct use
+        assert _edge_targets(nodes, relations, "REEXPORTS", PERSON_CARD_TSX) == {("File", "people.ts"), ("Enum", "Role")}
+        edges = [r for r in relations if r.source_id == f"file:{PERSON_CARD_TSX}"
+                 and r.relation_type in ("IMPORTS", "REEXPORTS")]
+        assert all(r.properties["full_module_path"] == "./people" for r in edges)
+        assert not any(r.properties.get("require") for r in edges)
+
+        # Package imports (react, events) have no file in the graph and stay unresolved
+        assert not any(r.relation_type == "IMPORTS" and r.source_id == f"file:{PEOPLE_TS}" for r in relations)
+
+    def test_require_and_import_graph(self, parsed):
+        nodes, relations = parsed
+        assert _edge_targets(nodes, relations, "IMPORTS", SAMPLE_TS) == {
+            ("Class", "Person"), ("Enum", "Role"),
+            # import people = require(...), const { greet } = require(...), require(...).add
+            ("File", "people.ts"), ("Function", "greet"), ("Function", "add"),
+        }
+        assert _edge_targets(nodes, relations, "REEXPORTS", SAMPLE_TS) == {("Interface", "Named"), ("TypeAlias", "PersonId")}
+
+        required = {nodes[r.target_id].name for r in relations
+                    if r.source_id == f"file:{SAMPLE_TS}" and r.properties.get("require")}
+        assert required == {"people.ts", "greet", "add"}
+        # "path" is a package and has no file to point at
+        assert all(nodes[r.target_id].file_path == PEOPLE_TS for r in relations
+                   if r.source_id == f"file:{SAMPLE_TS}" and r.relation_type in ("IMPORTS", "REEXPORTS"))
+
+    def test_sample_declarations(self, parsed):
+        nodes, _ = parsed
+        assert node_names(nodes, "Interface", SAMPLE_TS) == {"Team"}
+        assert node_names(nodes, "TypeAlias", SAMPLE_TS) == {"Roster"}
+        assert node_names(nodes, "Class", SAMPLE_TS) == {"TeamBuilder"}
+        assert node_names(nodes, "Function", SAMPLE_TS) == {"welcome", "headcount", "admins"}
+
+
+class TestFixtureDirectory:
+    """Directory-level parsing of the whole multi-language sample."""
+
+    def test_legacy_mode_collects_python_fixture(self):
+        parser = MultiLanguageParser(use_ast_grep=False)
+        nodes, _ = parser.parse_directory(FIXTURE_DIR, build_index=True)
+        assert node_names(nodes, "File") == {"sample.py", "people.ts", "PersonCard.tsx", "sample.ts"}
+        assert "Person" in node_names(nodes, "Class", SAMPLE_PY)
+        assert "PersonCard" in node_names(nodes, "Function", PERSON_CARD_TSX)
+
+    @pytest.mark.parametrize("filename,language", [
+        ("sample.py", "python"),
+        ("sample.rs", "rust"),
+        ("sample.go", "go"),
+        ("sample.rb", "ruby"),
+        ("Sample.java", "java"),
+        ("sample.cpp", "cpp"),
+        ("people.ts", "typescript"),
+    ])
+    def test_every_fixture_yields_person(self, filename, language):
+        """Each language fixture declares a Person type and free functions."""
+        pytest.importorskip("ast_grep_py")
+        parser = MultiLanguageParser(
+            use_ast_grep=True,
+            ast_grep_languages=[language],
+            ast_grep_fallback=False
+        )
+        file_path = os.path.join(FIXTURE_DIR, filename)
+        nodes, _ = parser.parse_file(file_path, build_index=True)
+        assert f"file:{file_path}" in nodes
+        assert "Person" in node_names(nodes, "Class", file_path)
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -0,0 +1,117 @@
+"""
+Tests for filter expressions over symbols.
+"""
+
+import os
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.graph.node_query import And, Not, Or, Predicate, QueryError, parse_query, query_nodes
+
+
+def _symbol(name, file_path="src/lib.rs", line_no=1, node_type="Function", visibility="Public"):
+    return {"id": f"{node_type}:{file_path}:{name}:{line_no}", "name": name, "type": node_type,
+            "file_path": file_path, "line_no": line_no, "visibility": visibility}
+
+
+SYMBOLS = [
+    _symbol("parse", "src/lib.rs", 3),
+    _symbol("_helper", "src/lib.rs", 9, visibility="Private"),
+    _symbol("Config", "src/config.rs", 1, "Class"),
+    _symbol("Mode", "src/config.rs", 12, "Enum", visibility="Crate"),
+    _symbol("area", "src/shapes.rs", 5, "Method"),
+    _symbol("main", "app/main.py", 1),
+]
+
+
+def _names(query):
+    return [symbol["name"] for symbol in query_nodes(SYMBOLS, query)]
+
+
+class TestParse:
+    def test_and_binds_tighter_than_or(self):
+        assert parse_query("kind:struct OR kind:enum AND name:M*") == \
+            Or((Predicate("kind", "struct"), And((Predicate("kind", "enum"), Predicate("name", "M*")))))
+
+    def test_not_binds_tighter_than_and(self):
+        assert parse_query("NOT kind:struct AND name:C*") == \
+            And((Not(Predicate("kind", "struct")), Predicate("name", "C*")))
+
+    def test_parentheses_and_lowercase_keywords(self):
+        assert parse_query("not (kind:struct or kind:enum)") == \
+            Not(Or((Predicate("kind", "struct"), Predicate("kind", "enum"))))
+
+    def test_quoted_value(self):
+        assert parse_query(r'name:"a b" AND file:"src/\"q\".rs"') == \
+            And((Predicate("name", "a b"), Predicate("file", 'src/"q".rs')))
+
+    def test_round_trip(self):
+        text = 'NOT (kind:struct OR name:"a b") AND (file:x.rs OR visibility:public)'
+        assert str(parse_query(text)) == text
+        assert parse_query(str(parse_query(text))) == parse_query(text)
+
+
+class TestEvaluate:
+    def test_conjunction_with_file_glob(self):
+        assert _names('kind:function AND visibility:public AND file:"src/*.rs"') == ["parse"]
+
+    def test_precedence(self):
+        # A struct, or an enum named M*; grouping the OR first would drop Config
+        assert _names("kind:struct OR kind:enum AND name:M*") == ["Config", "Mode"]
+        assert _names("(kind:struct OR kind:enum) AND name:C*") == ["Config"]
+
+    def test_negation(self):
+        assert _names("file:src/lib.rs AND NOT name:_*") == ["parse"]
+        assert _names("NOT NOT visibility:crate") == ["Mode"]
+        assert _names("NOT (visibility:public OR visibility:private)") == ["Mode"]
+
+    def test_kind_list_and_node_type(self):
+        assert _names("kind:function,method") == ["main", "parse", "_helper", "area"]
+        assert _names("kind:Class") == ["Config"]
+
+    def test_name_is_exact_without_glob(self):
+        assert _names("name:pars") == []
+        assert _names("name:parse") == ["parse"]
+
+    def test_file_path_matches_suffix(self):
+        assert _names("file:config.rs") == ["Config", "Mode"]
+        assert _names("file:fig.rs") == []
+
+    def test_deprecated(self):
+        symbols = SYMBOLS + [dict(_symbol("parse_v1", "src/lib.rs", 20), deprecated=True)]
+        assert [s["name"] for s in query_nodes(symbols, "deprecated:true")] == ["parse_v1"]
+        assert [s["name"] for s in query_nodes(symbols, "file:src/lib.rs AND deprecated:False")] == \
+            ["parse", "_helper"]
+
+
+class TestErrors:
+    @pytest.mark.parametrize("query, message, position", [
+        ("", "Empty query", 0),
+        ("kind:function AND", "Unexpected end of query", 17),
+        ("kind:function name:x", "Expected AND, OR or the end of the query, found another term", 14),
+        ("(kind:function", "Expected ')'", 14),
+        ("kind:function)", "Expected AND, OR or the end of the query, found ')'", 13),
+        ("AND kind:function", "Expected field:value or '(', found AND", 0),
+        ("kind:function OR OR name:x", "Expected field:value or '(', found OR", 17),
+        ("parse", "Expected field:value, found 'parse'", 0),
+        ("color:red", "Unknown field 'color' (expected one of kind, name, file, visibility)", 0),
+        ("name:", "Missing value for name", 5),
+        ('name:"abc', "Unterminated string", 5),
+        ("kind:gadget", "Unknown node kind: gadget", 5),
+        ("visibility:secret", "Unknown visibility: secret", 11),
+        ("deprecated:yes", "Expected true or false for deprecated, found yes", 11),
+    ])
+    def test_malformed(self, query, message, position):
+        with pytest.raises(QueryError) as raised:
+            parse_query(query)
+        assert raised.value.message.startswith(message)
+        assert raised.value.to_dict() == {"code": "invalid_query", "message": raised.value.message,
+                                          "query": query, "position": position}
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -8,13 +8,15 @@ import sys
 import tempfile
 import shutil
 from pathlib import Path
-from unittest.mock import patch
+from unittest.mock import MagicMock, patch
 import pytest
 
 # Add src to path for imports
 sys.path.insert(0, os.path.abspath(os.path.join(os.path.dirname(__file__), "..")))
 
+from src.ast_parser.diagnostics import ParseDiagnostics
 from src.parallel.pool_manager import get_processing_pool, ProcessingPoolManager
+from tests.helpers import parse_shape
 
 
 class TestParallelIntegration:
@@ -238,6 +240,117 @@ class TestPoolManagerIntegration:
         assert "Error processing 5" in errors[0]
 
 
+def _write_package(root, count):
+    """Modules that each call into the previous one, so the second pass has work to do."""
+    for i in range(count):
+        body = f"def function_{i}():\n    return {i}\n\n\nclass Class_{i}:\n    def method_{i}(self):\n        pass\n"
+        if i:
+            body = f"from module_{i - 1} import function_{i - 1}\n\n\n" + body + \
+                f"\n\ndef caller_{i}():\n    function_{i - 1}()\n"
+        (Path(root) / f"module_{i}.py").write_text(body)
+    return sorted(str(p) for p in Path(root).glob("*.py"))
+
+
+class TestParallelParsing:
+    """Per-file parse tasks on a worker pool, merged into a single graph."""
+
+    def test_completion_order_does_not_change_the_graph(self, tmp_path):
+        from src.parallel.parse_worker import merge_results, parse_file_task
+
+        files = _write_package(tmp_path, 12)
+        sequential = merge_results([parse_file_task(f) for f in files])
+
+        # A process pool also checks that tasks and results pickle
+        with ProcessingPoolManager(max_workers=4, force_executor_type="process") as pool:
+            futures = [pool.submit(parse_file_task, f) for f in reversed(files)]
+            parallel = merge_results([f.result() for f in futures])
+
+        assert parse_shape(*parallel) == parse_shape(*sequential)
+        relation_types = {r.relation_type for r in parallel[1]}
+        # Per-file relations are kept alongside the resolved imports and calls
+        assert {"CONTAINS", "DEFINES", "CALLS", "IMPORTS_DEFINITION"} <= relation_types
+
+    def test_failing_file_is_reported_not_raised(self, tmp_path):
+        from src.ast_parser.parser import ASTParser
+        from src.parallel.parse_worker import merge_results, parse_file_task
+
+        files = _write_package(tmp_path, 3)
+        original = ASTParser.parse_file
+
+        def parse_file(parser, file_path, build_index=False):
+            if file_path == files[1]:
+                raise RuntimeError("parser blew up")
+            return original(parser, file_path, build_index=build_index)
+
+        with patch.object(ASTParser, "parse_file", parse_file):
+            results = [parse_file_task(f) for f in files]
+
+        assert [r.error for r in results] == [None, "RuntimeError: parser blew up", None]
+        nodes, _ = merge_results(results)
+        # The failed file is kept as its File node alone, flagged with the error
+        assert {n.file_path for n in nodes.values() if n.node_type != "File"} == {files[0], files[2]}
+        assert nodes[f"file:{files[1]}"].properties["parse_error"] == "RuntimeError: parser blew up"
+
+    def test_jobs_option_drives_the_pool(self, tmp_path):
+        from src.main import CodebaseKnowledgeGraph
+
+        files = _write_package(tmp_path, 8)
+        kg = CodebaseKnowledgeGraph.__new__(CodebaseKnowledgeGraph)
+        kg.use_ast_grep, kg.ast_grep_languages, kg.ast_grep_fallback = False, ["python"], True
+        kg.respect_gitignore, kg.exclude = True, []
+        kg.include, kg.max_file_size = [], None
+        kg.language_options, kg.language_overrides = {}, {}
+        kg.diagnostics = ParseDiagnostics()
+
+        kg.jobs = 2
+        # Falling back to sequential parsing would hide a broken pool
+        with patch("src.main.get_processing_pool", wraps=get_processing_pool) as pool, \
+                patch.object(kg, "_process_directory_with_routing", side_effect=AssertionError):
+            parallel = kg._process_files_parallel(files, str(tmp_path))
+        pool.assert_called_once_with(max_workers=2)
+
+        kg.jobs = 1
+        assert parse_shape(*parallel) == parse_shape(*kg._process_directory_with_routing(str(tmp_path)))
+
+    def test_code_graph_threads_build_the_serial_graph(self, tmp_path):
+        import time
+        from src.graph.code_graph import CodeGraph
+
+        files = _write_package(tmp_path, 12)
+        serial = CodeGraph.from_directory(str(tmp_path))
+
+        # Early files finish last, so completion order is the reverse of path order
+        original = CodeGraph._parse
+
+        def slow_parse(graph, file_path, source):
+            time.sleep(0.002 * (len(files) - files.index(file_path)))
+            return original(graph, file_path, source)
+
+        with patch.object(CodeGraph, "_parse", slow_parse):
+            threaded = CodeGraph.from_directory(str(tmp_path), jobs=4)
+
+        assert list(threaded.files) == list(serial.files)
+        assert threaded.to_json() == serial.to_json()
+        assert parse_shape(threaded.nodes, threaded.relations) == parse_shape(serial.nodes, serial.relations)
+
+    def test_graph_mode_passes_jobs_to_the_code_graph(self, tmp_path):
+        from src.graph.code_graph import CodeGraph
+        from src.main import CodebaseKnowledgeGraph
+
+        files = _write_package(tmp_path, 3)
+        kg = CodebaseKnowledgeGraph.__new__(CodebaseKnowledgeGraph)
+        kg.cache, kg.jobs, kg.db = None, 3, MagicMock()
+        kg.diagnostics = ParseDiagnostics()
+        graph = CodeGraph()
+
+        with patch.object(graph, "add_directory", wraps=graph.add_directory) as add_directory, \
+                patch.object(kg, "_generate_embeddings"), patch.object(kg, "_store_file_states"):
+            nodes, _ = kg._index_full(str(tmp_path), files, graph, use_parallel=True)
+
+        assert {n.file_path for n in nodes.values()} == set(files)
+        add_directory.assert_called_once_with(str(tmp_path), jobs=3)
+
+
 class TestAdaptiveStrategy:
     """Test the adaptive strategy for choosing execution mode."""
 
@@ -0,0 +1,122 @@
+"""
+Tests for the per-file parse cache behind CodeGraph.with_cache_dir.
+
+Uses the legacy Python parser so no optional parsing backends are needed.
+"""
+
+import os
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.graph.code_graph import CodeGraph
+from src.graph.parse_cache import ParseCache
+from tests.helpers import graph_shape
+
+
+SAMPLE = '''
+def greet(name):
+    return "Hello, " + name
+'''
+
+APP = '''
+from sample import greet
+
+
+def welcome():
+    greet("world")
+'''
+
+
+def _write(directory, name, content):
+    path = os.path.join(str(directory), name)
+    with open(path, "w", encoding="utf-8") as f:
+        f.write(content)
+    return path
+
+
+def _build(root, cache_dir, **options):
+    graph = CodeGraph(**options).with_cache_dir(str(cache_dir))
+    graph.add_directory(str(root))
+    return graph
+
+
+@pytest.fixture
+def codebase(tmp_path):
+    root = tmp_path / "src"
+    root.mkdir()
+    _write(root, "sample.py", SAMPLE)
+    _write(root, "app.py", APP)
+    return root, tmp_path / "cache"
+
+
+class TestParseCache:
+
+    def test_second_build_reparses_nothing(self, codebase):
+        root, cache_dir = codebase
+        first = _build(root, cache_dir)
+        assert (first.parse_cache.hits, first.parse_cache.misses) == (0, 2)
+
+        second = _build(root, cache_dir)
+        assert (second.parse_cache.hits, second.parse_cache.misses) == (2, 0)
+        assert graph_shape(second) == graph_shape(first)
+
+    def test_cross_file_edges_are_resolved_again(self, codebase):
+        root, cache_dir = codebase
+        _build(root, cache_dir)
+        # The caller is unchanged and comes from the cache; its call must follow the renamed callee
+        _write(root, "sample.py", SAMPLE.replace("def greet", "def salute"))
+
+        graph = _build(root, cache_dir)
+        assert (graph.parse_cache.hits, graph.parse_cache.misses) == (1, 1)
+        assert graph_shape(graph) == graph_shape(CodeGraph.from_directory(str(root)))
+
+    def test_other_options_use_another_version(self, codebase):
+        root, cache_dir = codebase
+        first = _build(root, cache_dir)
+        other = _build(root, cache_dir, ast_grep_languages=["python"])
+
+        assert other.parse_cache.version != first.parse_cache.version
+        assert other.parse_cache.hits == 0
+        # Each option set keeps its entries, so switching back parses nothing
+        assert sorted(os.listdir(str(cache_dir))) == sorted([first.parse_cache.version, other.parse_cache.version])
+        assert _build(root, cache_dir).parse_cache.hits == 2
+
+    def test_older_parsers_are_pruned(self, codebase):
+        root, cache_dir = codebase
+        current = _build(root, cache_dir).parse_cache.version
+        fingerprint = current.split("-")[1]
+        stale = [current.replace(fingerprint, "0" * 16), "v1-" + "0" * 16]
+        other_options = current[:-16] + "f" * 16
+        for name in stale + [other_options]:
+            os.makedirs(os.path.join(str(cache_dir), name))
+
+        graph = _build(root, cache_dir)
+        assert sorted(os.listdir(str(cache_dir))) == sorted([graph.parse_cache.version, other_options])
+
+    def test_corrupt_entry_is_reparsed(self, codebase):
+        root, cache_dir = codebase
+        first = _build(root, cache_dir)
+        for directory, _, names in os.walk(first.parse_cache.path):
+            for name in names:
+                _write(directory, name, "{not json")
+
+        graph = _build(root, cache_dir)
+        assert (graph.parse_cache.hits, graph.parse_cache.misses) == (0, 2)
+        assert graph_shape(graph) == graph_shape(first)
+
+    def test_sources_in_memory_bypass_the_cache(self, tmp_path):
+        graph = CodeGraph().with_cache_dir(str(tmp_path / "cache"))
+        graph.add_sources({"sample.py": SAMPLE})
+        assert (graph.parse_cache.hits, graph.parse_cache.misses) == (0, 0)
+        assert not os.path.exists(str(tmp_path / "cache" / graph.parse_cache.version))
+
+    def test_version_names_the_format(self, tmp_path):
+        assert ParseCache(str(tmp_path)).version.startswith("v1-")
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -0,0 +1,292 @@
+"""
+Tests for syntax error diagnostics and the declarations recovered around them,
+and for files that cannot be parsed at all being flagged instead of aborting a run.
+"""
+
+import os
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.ast_parser.diagnostics import (ENCODING_ERROR, FILE_TOO_LARGE, IO_ERROR, UNSUPPORTED_LANGUAGE,
+                                       ParseDiagnostics, ParseError, ParseFailure, ParseReport)
+from src.ast_parser.multi_parser import MultiLanguageParser
+from src.ast_parser.parser import ASTParser, parse_python
+from src.graph.code_graph import CodeGraph, FileState
+from src.parallel.parse_worker import parse_file_task
+
+
+BROKEN_DIR = os.path.abspath(os.path.join(os.path.dirname(__file__), "fixtures", "broken_sample"))
+BROKEN_PY = os.path.join(BROKEN_DIR, "inventory.py")
+BROKEN_JS = os.path.join(BROKEN_DIR, "cart.js")
+
+
+def _names(nodes):
+    return sorted(node.name for node in nodes.values() if node.node_type != "File")
+
+
+class TestParsePython:
+    def test_clean_source(self):
+        tree, errors = parse_python("def ok():\n    return 1\n")
+        assert [node.name for node in tree.body] == ["ok"]
+        assert errors == []
+
+    def test_broken_block_is_dropped(self):
+        tree, errors = parse_python("def a():\n    return 1\n\n\ndef b(:\n    pass\n\n\ndef c():\n    return 3\n")
+
+        assert [node.name for node in tree.body] == ["a", "c"]
+        [error] = errors
+        assert error.line_no == 5
+
+    def test_decorator_goes_with_its_block(self):
+        tree, errors = parse_python("@wrap\ndef a(:\n    pass\n\n\ndef b():\n    pass\n")
+        assert [node.name for node in tree.body] == ["b"]
+        assert len(errors) == 1
+
+    def test_several_broken_blocks(self):
+        tree, errors = parse_python("def a(:\n    pass\n\n\nx = 1\n\n\nclass B(:\n    pass\n")
+        assert [type(node).__name__ for node in tree.body] == ["Assign"]
+        assert [error.line_no for error in errors] == [1, 8]
+
+
+class TestASTParser:
+    def test_fixture_keeps_surrounding_declarations(self):
+        parser = ASTParser()
+        nodes, _ = parser.parse_file(BROKEN_PY)
+
+        assert {"Item", "is_low", "report", "__init__"} <= set(_names(nodes))
+        assert "restock" not in _names(nodes)
+        [error] = parser.parse_errors
+        assert (error.line_no, error.column) == (17, 16)
+
+    def test_errors_are_reset_per_file(self, tmp_path):
+        clean = tmp_path / "clean.py"
+        clean.write_text("def ok():\n    return 1\n", encoding="utf-8")
+        parser = ASTParser()
+        parser.parse_file(BROKEN_PY)
+        parser.parse_file(str(clean))
+        assert parser.parse_errors == []
+
+    def test_unreadable_file(self, tmp_path):
+        parser = ASTParser()
+        assert parser.parse_file(str(tmp_path / "missing.py")) == ({}, [])
+        [error] = parser.parse_errors
+        assert error.message.startswith("Could not read file") and error.line_no is None
+        assert error.kind == IO_ERROR
+
+    def test_undecodable_file(self, tmp_path):
+        latin = tmp_path / "latin.py"
+        latin.write_bytes(b"NAME = '\xe9t\xe9'\n")
+        parser = ASTParser()
+        assert parser.parse_file(str(latin)) == ({}, [])
+        assert [error.kind for error in parser.parse_errors] == [ENCODING_ERROR]
+
+    @pytest.mark.parametrize("encoding", ["utf-8-sig", "utf-16", "utf-16-le"])
+    def test_transcoded_file(self, tmp_path, encoding):
+        path = tmp_path / "greet.py"
+        path.write_bytes("def greet():\n    return 'héllo'\n".encode(encoding))
+        parser = ASTParser()
+        assert _names(parser.parse_file(str(path))[0]) == ["greet"]
+        assert parser.parse_errors == []
+
+
+class TestParseReport:
+    def test_counts_and_replacement(self):
+        report = ParseReport()
+        report.add("a.py", [ParseError("bad", 1, 1, 1, 2), ParseError("worse", 3, 1, 3, 2)], partial=True)
+        report.add("b.py", [], partial=False)
+        assert report.to_dict()["files_parsed"] == 2
+        assert (report.to_dict()["files_with_errors"], report.error_count) == (1, 2)
+
+        report.add("a.py", [], partial=False)
+        assert report.to_dict() == {"files_parsed": 2, "files_with_errors": 0, "errors": 0, "files": []}
+
+    def test_multi_language_parser(self):
+        parser = MultiLanguageParser()
+        parser.parse_file(BROKEN_PY)
+
+        diagnostics = parser.report.files[BROKEN_PY]
+        assert diagnostics.partial is True
+        assert [error.line_no for error in diagnostics.errors] == [17]
+
+
+class TestCodeGraphReport:
+    def test_fixture(self):
+        graph = CodeGraph()
+        graph.add_file(BROKEN_PY)
+        report = graph.parse_report().to_dict()
+
+        assert (report["files_parsed"], report["files_with_errors"], report["errors"]) == (1, 1, 1)
+        assert report["files"][0]["partial"] is True
+        assert {"Item", "is_low", "report"} <= {node.name for node in graph.nodes.values()}
+
+    def test_fixing_the_file_clears_the_error(self):
+        graph = CodeGraph()
+        graph.add_sources({"app.py": "def broken(:\n    pass\n"})
+        assert graph.parse_report().files["app.py"].partial is False
+
+        graph.update_file("app.py", "def fixed():\n    pass\n")
+        assert graph.parse_report().files == {}
+
+    def test_errors_survive_serialization(self):
+        graph = CodeGraph()
+        graph.add_file(BROKEN_PY)
+        state = FileState.from_dict(graph.files[BROKEN_PY].to_dict())
+        assert state.parse_errors == graph.files[BROKEN_PY].parse_errors
+
+    def test_file_nodes_are_flagged(self, tmp_path):
+        graph = CodeGraph()
+        graph.add_sources({"broken.py": "def broken(:\n    pass\n", "ok.py": "def ok():\n    pass\n"})
+        graph.add_file(BROKEN_PY)
+        graph.add_file(str(tmp_path / "gone.py"))
+
+        # Nothing was recovered from broken.py or gone.py: their File nodes stand alone
+        assert set(graph.files["broken.py"].nodes) == {"file:broken.py"}
+        assert graph.nodes["file:broken.py"].properties["parse_error_line"] == 1
+        assert graph.nodes[f"file:{BROKEN_PY}"].properties["parse_error_line"] == 17
+        gone = graph.nodes[f"file:{tmp_path / 'gone.py'}"].properties
+        assert (gone["parse_error_kind"], "parse_error_line" in gone) == (IO_ERROR, False)
+        assert "parse_error" not in graph.nodes["file:ok.py"].properties
+
+        graph.update_file("broken.py", "def fixed():\n    pass\n")
+        assert "parse_error" not in graph.nodes["file:broken.py"].properties
+
+
+class TestTreeSitterParsers:
+    def test_python_adapter_keeps_surrounding_declarations(self):
+        pytest.importorskip("ast_grep_py")
+        from src.ast_parser.adapters.python_adapter import PythonAstGrepAdapter
+
+        parser = PythonAstGrepAdapter()
+        nodes, _ = parser.parse_file(BROKEN_PY)
+
+        assert {"Item", "is_low", "report"} <= set(_names(nodes))
+        assert parser.parse_errors
+        assert all(17 <= error.line_no <= 18 for error in parser.parse_errors)
+
+    def test_typescript_parser_keeps_surrounding_declarations(self):
+        from src.ast_parser.typescript_parser import TypeScriptParser
+
+        parser = TypeScriptParser()
+        nodes, _ = parser.parse_file(BROKEN_JS)
+
+        assert {"total", "Cart"} <= set(_names(nodes))
+        assert parser.parse_errors
+        assert all(error.line_no == 8 for error in parser.parse_errors)
+
+
+class TestParseDiagnostics:
+    def test_counts_and_replacement(self):
+        diagnostics = ParseDiagnostics()
+        diagnostics.record("a.py", [ParseError("bad", 2, 5)], partial=True)
+        diagnostics.record("b.py", [ParseError("Could not read file: gone", kind=IO_ERROR)], partial=False)
+        diagnostics.skip("c.java", UNSUPPORTED_LANGUAGE, "No enabled parser for java")
+        result = diagnostics.to_dict()
+
+        # a.py kept its declarations, so only b.py and c.java are missing from the graph
+        assert (result["files_parsed"], result["files_failed"], result["complete"]) == (1, 2, False)
+        assert [(d["file"], d["line"], d["column"], d["error_kind"]) for d in result["diagnostics"]] == [
+            ("a.py", 2, 5, "SyntaxError"), ("b.py", None, None, "IoError"), ("c.java", None, None, UNSUPPORTED_LANGUAGE)]
+
+        diagnostics.record("b.py", [], partial=False)
+        diagnostics.forget("c.java")
+        diagnostics.record("a.py", [], partial=False)
+        assert diagnostics.to_dict() == {"files_parsed": 2, "files_failed": 0, "complete": True, "diagnostics": []}
+
+    def test_kinds_survive_serialization(self):
+        error = ParseError("Could not read file: bad byte", kind=ENCODING_ERROR)
+        assert ParseError.from_dict(error.to_dict()) == error
+        # States saved before kinds existed hold syntax errors
+        assert ParseError.from_dict({"message": "bad", "line_no": 1}).kind == "SyntaxError"
+
+    def test_failed_parse_task(self, tmp_path):
+        result = parse_file_task(str(tmp_path / "gone.py"))
+        assert result.error is not None and result.error_kind == IO_ERROR
+        assert result.nodes[f"file:{tmp_path / 'gone.py'}"].properties["parse_error_kind"] == IO_ERROR
+
+    def test_summary(self):
+        diagnostics = ParseDiagnostics()
+        diagnostics.record("a.py", [ParseError("bad", 2, 5)], partial=True)
+        diagnostics.record("b.py", [ParseError("Could not read file: gone", kind=IO_ERROR)], partial=False)
+        diagnostics.record("c.py", [], partial=False)
+        diagnostics.skip("big.py", FILE_TOO_LARGE, "Larger than 10 bytes")
+        diagnostics.skip("Main.java", UNSUPPORTED_LANGUAGE, "No enabled parser for java")
+        assert diagnostics.summary() == {"files_indexed": 2, "files_failed": 1, "failed_files": ["b.py"],
+                                         "files_with_errors": ["a.py"], "skipped_by_size": ["big.py"],
+                                         "skipped_unsupported": ["Main.java"]}
+
+    def test_strict_stops_at_the_first_failure(self):
+        diagnostics = ParseDiagnostics(strict=True)
+        diagnostics.record("ok.py", [], partial=False)
+        diagnostics.skip("big.py", FILE_TOO_LARGE, "Larger than 10 bytes")
+        with pytest.raises(ParseFailure, match="a.py: bad"):
+            diagnostics.record("a.py", [ParseError("bad", 2, 5)], partial=True)
+        with pytest.raises(ParseFailure):
+            diagnostics.skip("b.py", IO_ERROR, "Could not read file: gone")
+
+
+class TestIndexingDiagnostics:
+    """CodebaseKnowledgeGraph collecting the diagnostics of a run, legacy parsers only."""
+
+    @pytest.fixture
+    def kg(self):
+        from src.main import CodebaseKnowledgeGraph
+
+        kg = CodebaseKnowledgeGraph.__new__(CodebaseKnowledgeGraph)
+        kg.use_ast_grep, kg.ast_grep_languages, kg.ast_grep_fallback = False, ["python"], True
+        kg.respect_gitignore, kg.exclude, kg.include = True, [], []
+        kg.max_file_size = 200
+        kg.language_options, kg.language_overrides = {}, {}
+        kg.diagnostics = ParseDiagnostics()
+        return kg
+
+    def test_run_over_a_mixed_directory(self, kg, tmp_path):
+        (tmp_path / "ok.py").write_text("def ok():\n    return 1\n", encoding="utf-8")
+        (tmp_path / "broken.py").write_text("def broken(:\n    pass\n", encoding="utf-8")
+        (tmp_path / "huge.py").write_text("X = 1\n" * 100, encoding="utf-8")
+        (tmp_path / "Main.java").write_text("class Main {}\n", encoding="utf-8")
+        (tmp_path / "notes.md").write_text("# Notes\n", encoding="utf-8")
+
+        files = kg._collect_source_files(str(tmp_path))
+        for file_path in files:
+            kg._parse_logged(file_path)
+        result = kg.diagnostics.to_dict()
+
+        assert sorted(os.path.basename(f) for f in files) == ["broken.py", "ok.py"]
+        assert (result["files_parsed"], result["files_failed"]) == (1, 3)
+        assert [(os.path.basename(d["file"]), d["line"], d["error_kind"]) for d in result["diagnostics"]] == [
+            ("Main.java", None, UNSUPPORTED_LANGUAGE), ("broken.py", 1, "SyntaxError"), ("huge.py", None, FILE_TOO_LARGE)]
+
+    def test_binary_and_transcoded_files(self, kg, tmp_path):
+        (tmp_path / "clip.ts").write_bytes(b"G\x40\x00\x10\x00\x00\xb0\x0d\x00\x01" * 20)
+        (tmp_path / "wide.py").write_bytes("def wide():\n    return 1\n".encode("utf-16"))
+
+        files = kg._collect_source_files(str(tmp_path))
+        for file_path in files:
+            kg._parse_logged(file_path)
+
+        # The binary file is left out without a diagnostic
+        assert [os.path.basename(f) for f in files] == ["wide.py"]
+        assert kg.diagnostics.summary()["files_indexed"] == 1
+        assert kg.diagnostics.to_dict()["diagnostics"] == []
+
+    def test_strict_run_stops_at_a_broken_file(self, kg, tmp_path):
+        (tmp_path / "broken.py").write_text("def broken(:\n    pass\n", encoding="utf-8")
+        kg.diagnostics = ParseDiagnostics(strict=True)
+        with pytest.raises(ParseFailure, match="broken.py"):
+            kg._parse_logged(str(tmp_path / "broken.py"))
+
+    def test_graph_files(self, kg, tmp_path):
+        graph = CodeGraph()
+        graph.add_sources({"app.py": "def broken(:\n    pass\n\n\ndef ok():\n    pass\n"})
+        kg._record_graph_files(graph, ["app.py", "removed.py"])
+        result = kg.diagnostics.to_dict()
+        assert (result["files_parsed"], result["files_failed"]) == (1, 0)
+        assert [(d["file"], d["line"]) for d in result["diagnostics"]] == [("app.py", 1)]
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
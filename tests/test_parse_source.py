@@ -0,0 +1,89 @@
+"""
+Tests for parsing in-memory buffers through MultiLanguageParser.parse_source.
+"""
+
+import os
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.ast_parser.multi_parser import MultiLanguageParser
+from tests.helpers import parse_shape
+
+
+SAMPLE_PY = os.path.abspath(os.path.join(os.path.dirname(__file__),
+                                         "fixtures", "multi_lang_sample", "sample.py"))
+
+HELPERS = '''
+def shout(text):
+    return text.upper()
+'''
+
+CALLER = '''
+from helpers import shout
+
+
+def greet(name):
+    return shout(name)
+'''
+
+
+class TestParseSource:
+
+    def test_matches_file_based_parse(self):
+        with open(SAMPLE_PY, encoding="utf-8") as f:
+            source = f.read()
+        from_file = MultiLanguageParser().parse_file(SAMPLE_PY, build_index=True)
+        from_source = MultiLanguageParser().parse_source("python", source, SAMPLE_PY, build_index=True)
+        assert parse_shape(*from_source) == parse_shape(*from_file)
+
+    def test_virtual_path_is_not_read(self, tmp_path):
+        virtual_path = str(tmp_path / "unsaved" / "buffer.py")
+        nodes, _ = MultiLanguageParser().parse_source("auto", HELPERS, virtual_path)
+        shout = next(n for n in nodes.values() if n.name == "shout")
+        assert shout.file_path == virtual_path and shout.line_no == 2
+        assert not os.path.exists(virtual_path)
+
+    def test_auto_detects_from_virtual_path(self):
+        parser = MultiLanguageParser()
+        nodes, _ = parser.parse_source(None, HELPERS, "untitled.py")
+        assert "file:untitled.py" in nodes
+        # No extension to go by
+        assert parser.parse_source("auto", HELPERS, "untitled") == ({}, [])
+
+    def test_explicit_language_overrides_extension(self):
+        nodes, _ = MultiLanguageParser().parse_source("python", HELPERS, "Untitled-1")
+        assert {n.name for n in nodes.values() if n.node_type == "Function"} == {"shout"}
+
+    def test_unknown_language(self):
+        with pytest.raises(ValueError):
+            MultiLanguageParser().parse_source("cobol", HELPERS, "a.cbl")
+
+    def test_reparsing_a_buffer_is_idempotent(self):
+        parser = MultiLanguageParser()
+        parser.parse_source("python", HELPERS, "helpers.py", build_index=True)
+        parser.parse_source("python", CALLER, "caller.py", build_index=True)
+        parser._process_pending_imports()
+        first = parse_shape(parser.nodes, parser.relations)
+
+        parser.parse_source("python", CALLER, "caller.py", build_index=True)
+        parser.parse_source("python", HELPERS, "helpers.py", build_index=True)
+        parser._process_pending_imports()
+        assert parse_shape(parser.nodes, parser.relations) == first
+        assert len(parser.pending_imports) == len({str(p) for p in parser.pending_imports})
+
+    def test_reparse_replaces_old_content(self):
+        parser = MultiLanguageParser()
+        parser.parse_source("python", HELPERS, "buffer.py", build_index=True)
+        parser.parse_source("python", "def whisper(text):\n    return text.lower()\n", "buffer.py",
+                            build_index=True)
+        names = {n.name for n in parser.nodes.values() if n.node_type == "Function"}
+        assert names == {"whisper"}
+        assert not any("shout" in r.source_id or "shout" in r.target_id for r in parser.relations)
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -0,0 +1,177 @@
+"""
+Tests for .gitignore handling and exclude globs during directory walks.
+"""
+
+import os
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.ast_parser.multi_parser import MultiLanguageParser
+from src.ast_parser.language_detector import detect_language, parsed_extension
+from src.ast_parser.path_filter import PathFilter, compile_pattern, pattern_error
+from src.graph.code_graph import CodeGraph
+from src.graph.incremental import StoredFile, reindex
+
+
+def _tree(root, files):
+    for rel_path, content in files.items():
+        path = root / rel_path
+        path.parent.mkdir(parents=True, exist_ok=True)
+        path.write_text(content, encoding="utf-8")
+
+
+def _walk(root, **options):
+    path_filter = PathFilter(**options)
+    found = sorted(os.path.relpath(p, str(root)).replace(os.sep, "/") for p in path_filter.walk(str(root)))
+    return found, path_filter.skipped
+
+
+@pytest.fixture
+def project(tmp_path):
+    _tree(tmp_path, {
+        ".gitignore": "# build output\ntarget/\nnode_modules/\n*.log\n/local.py\n",
+        "main.py": "",
+        "local.py": "",
+        "debug.log": "",
+        "target/gen.py": "",
+        "node_modules/lib/index.js": "",
+        "src/app.py": "",
+        "src/local.py": "",
+        "src/generated/schema.py": "",
+        "web/app.min.js": "",
+        "web/app.js": "",
+        "web/.gitignore": "*.js\n!app.js\n",
+        "web/vendor.js": "",
+    })
+    return tmp_path
+
+
+class TestPathFilter:
+
+    def test_gitignore_rules(self, project):
+        found, skipped = _walk(project)
+        assert found == [".gitignore", "main.py", "src/app.py", "src/generated/schema.py",
+                         "src/local.py", "web/.gitignore", "web/app.js"]
+        # target/, node_modules/, local.py, debug.log, app.min.js, vendor.js
+        assert skipped == 6
+
+    def test_exclude_globs(self, project):
+        found, _ = _walk(project, exclude=["**/generated/**", "*.min.js"])
+        assert "src/generated/schema.py" not in found
+        assert "web/app.min.js" not in found
+        assert "src/app.py" in found
+
+    def test_gitignore_can_be_turned_off(self, project):
+        found, skipped = _walk(project, respect_gitignore=False, exclude=["*.log"])
+        assert "target/gen.py" in found and "node_modules/lib/index.js" in found
+        assert "debug.log" not in found
+        assert skipped == 1
+
+    def test_include_globs_narrow_the_walk(self, project):
+        found, _ = _walk(project, include=["src/**", "*.js"])
+        assert found == ["src/app.py", "src/generated/schema.py", "src/local.py", "web/app.js"]
+
+    def test_files_over_the_size_limit_are_skipped(self, project):
+        (project / "src" / "app.py").write_text("x = 1\n" * 100, encoding="utf-8")
+        found, skipped = _walk(project, max_file_size=100)
+        assert "src/app.py" not in found and "main.py" in found
+        assert skipped == 7
+
+    def test_pattern_syntax(self):
+        anchored = compile_pattern("/build")
+        assert anchored.matches("build", True) and not anchored.matches("src/build", True)
+        nested = compile_pattern("docs/*.md")
+        assert nested.matches("docs/a.md", False) and not nested.matches("docs/x/a.md", False)
+        dir_only = compile_pattern("out/")
+        assert dir_only.matches("a/out", True) and not dir_only.matches("a/out", False)
+        scoped = compile_pattern("*.py", base="pkg")
+        assert scoped.matches("pkg/x/a.py", False) and not scoped.matches("a.py", False)
+        assert compile_pattern("# comment") is None
+        assert compile_pattern("   ") is None
+
+    def test_invalid_patterns(self):
+        assert pattern_error("**/gen/**") is None and pattern_error("[!ab]*.py") is None
+        assert pattern_error("src/[gen") == "unclosed '[' at position 5"
+        assert pattern_error("[z-a].c") == "bad character range z-a"
+
+    def test_language_overrides(self):
+        overrides = {"vendor/*.h": "c", "*.h": "cpp", "*.mts": "typescript"}
+        assert detect_language("/repo/include/a.h", overrides) == "cpp"
+        assert detect_language("/repo/vendor/a.h", overrides) == "c"
+        assert detect_language("/repo/include/a.h") == "c"
+        # A header is parsed as its override's own extension; other files keep theirs
+        assert parsed_extension("/repo/vendor/a.h", overrides) == ".c"
+        assert parsed_extension("/repo/src/app.mts", overrides) == ".ts"
+        assert parsed_extension("/repo/src/app.ts", overrides) == ".ts"
+
+
+class TestDirectoryWalks:
+
+    def test_ignored_files_produce_no_nodes(self, project):
+        parser = MultiLanguageParser(use_ast_grep=False)
+        files = {os.path.relpath(p, str(project)).replace(os.sep, "/")
+                 for p in parser.collect_source_files(str(project))}
+        assert "target/gen.py" not in files and "local.py" not in files
+        assert "web/vendor.js" not in files and "web/app.js" in files
+
+        graph = CodeGraph.from_directory(str(project), exclude=["src/generated/", "web/"])
+        file_nodes = {os.path.relpath(n.file_path, str(project)).replace(os.sep, "/")
+                      for n in graph.nodes.values() if n.node_type == "File"}
+        assert "target/gen.py" not in file_nodes
+        assert "src/generated/schema.py" not in file_nodes
+        assert "src/app.py" in file_nodes
+
+    def test_newly_ignored_file_is_removed_incrementally(self, tmp_path):
+        _tree(tmp_path, {"keep.py": "def keep():\n    pass\n", "gen.py": "def gen():\n    pass\n"})
+        first = CodeGraph()
+        first.add_directory(str(tmp_path))
+        stored = {path: StoredFile(path, state.content_hash, state.mtime_ns, state)
+                  for path, state in first.files.items()}
+
+        (tmp_path / ".gitignore").write_text("gen.py\n", encoding="utf-8")
+        graph = CodeGraph()
+        result = reindex(graph, str(tmp_path), stored)
+
+        assert result.stats.deleted == 1 and result.stats.skipped == 1
+        assert all(not n.file_path.endswith("gen.py") for n in graph.nodes.values())
+        assert any(node_id.startswith("Function:") and node_id.endswith("gen.py:gen:1")
+                   for delta in result.deltas for node_id in delta.removed_nodes)
+
+
+FIXTURE_DIR = os.path.join(os.path.dirname(os.path.abspath(__file__)), "fixtures", "ignore_sample")
+
+
+class TestIgnoreFixture:
+    """generated/ is ignored at the root, scratch.py only inside lib/."""
+
+    def _files(self, graph):
+        return sorted(os.path.relpath(n.file_path, FIXTURE_DIR).replace(os.sep, "/")
+                      for n in graph.nodes.values() if n.node_type == "File")
+
+    def test_ignored_subdirectory_is_absent(self):
+        graph = CodeGraph.from_directory(FIXTURE_DIR)
+
+        # lib/.gitignore matches relative to lib/, so the top-level scratch.py stays
+        assert self._files(graph) == ["app.py", "lib/tools.py", "scratch.py", "util.py"]
+        assert all("generated" not in n.file_path and n.name != "draft" for n in graph.nodes.values())
+
+    def test_calls_into_ignored_files_are_unresolved(self):
+        graph = CodeGraph.from_directory(FIXTURE_DIR)
+        targets = {r.properties["original_name"]: r.target_id
+                   for r in graph.relations if r.relation_type == "CALLS"}
+
+        assert targets["build"].startswith("unresolved:")
+        assert targets["helper"].endswith("util.py:helper:1")
+        assert targets["notes"].endswith("scratch.py:notes:1")
+
+    def test_exclude_globs_add_to_gitignore(self):
+        graph = CodeGraph.from_directory(FIXTURE_DIR, exclude=["lib/", "scratch.py"])
+        assert self._files(graph) == ["app.py", "util.py"]
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
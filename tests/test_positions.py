@@ -0,0 +1,144 @@
+"""
+Tests for converting source columns between characters, UTF-16 code units
+and UTF-8 bytes, and for reading files in other encodings.
+"""
+
+import os
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.ast_parser.parser import CodeNode
+from src.ast_parser.positions import (LineIndex, SourceSpan, assign_spans, decode_source, decode_text, is_binary,
+                                     looks_binary, read_text, source_encoding)
+
+
+# "😀" is one character, two UTF-16 units and four bytes; "é" is one, one and two
+SOURCE = 'fn plain() {}\nlet s = "😀é"; let x = 1;\r\n'
+X_COLUMN = 'let s = "😀é"; let x = 1;'.index("let x") + 1
+
+
+class TestLineIndex:
+    def test_ascii_line_is_unchanged(self):
+        lines = LineIndex(SOURCE)
+        for encoding in ("utf-8", "utf-16", "utf-32"):
+            assert lines.encode_column(1, 4, encoding) == 4
+
+    def test_columns_after_non_ascii_text(self):
+        lines = LineIndex(SOURCE)
+        assert X_COLUMN == 15
+        assert lines.encode_column(2, X_COLUMN, "utf-16") == 16
+        assert lines.encode_column(2, X_COLUMN, "utf-8") == 19
+        assert lines.encode_column(2, X_COLUMN, "utf-32") == X_COLUMN
+
+    def test_round_trip(self):
+        lines = LineIndex(SOURCE)
+        for encoding in ("utf-8", "utf-16"):
+            for column in range(1, 30):
+                assert lines.decode_column(2, lines.encode_column(2, column, encoding), encoding) == column
+
+    def test_inside_a_surrogate_pair(self):
+        # The emoji is character 10, UTF-16 units 10 and 11
+        assert LineIndex(SOURCE).decode_column(2, 11, "utf-16") == 10
+
+    def test_carriage_return_is_not_a_column(self):
+        assert LineIndex(SOURCE).line(2).endswith(";")
+
+    def test_unknown_encoding(self):
+        with pytest.raises(ValueError, match="Unknown position encoding"):
+            LineIndex(SOURCE).encode_column(1, 2, "latin-1")
+
+
+class TestSourceSpan:
+    def test_span_in_each_encoding(self):
+        span = SourceSpan(2, X_COLUMN, 2, X_COLUMN + 5, LineIndex(SOURCE))
+        assert span.columns() == (15, 20)
+        assert span.utf16() == (16, 21)
+        assert span.utf8() == (19, 24)
+        assert span.to_dict("utf-16") == {"start": {"line": 2, "column": 16},
+                                          "end": {"line": 2, "column": 21}, "encoding": "utf-16"}
+
+    def test_lines_are_split_lazily(self):
+        lines = LineIndex(SOURCE)
+        span = SourceSpan(2, 1, 2, 3, lines)
+        assert lines._lines is None
+        span.utf16()
+        assert lines._lines is not None
+
+
+class TestAssignSpans:
+    def _slice(self, node, source=SOURCE):
+        return source.encode("utf-8")[node.properties["start_byte"]:node.properties["end_byte"]].decode("utf-8")
+
+    def test_recorded_columns_are_kept(self):
+        node = CodeNode("Variable:a.rs:x:2", "Variable", "x", "a.rs", 2, 2,
+                        {"column": X_COLUMN, "end_column": X_COLUMN + 9})
+        assign_spans([node], SOURCE)
+        assert (node.properties["start_byte"], node.properties["end_byte"]) == (18 + 14, 18 + 23)
+        assert self._slice(node) == "let x = 1"
+
+    def test_nodes_without_columns_span_whole_lines(self):
+        node = CodeNode("Variable:a.rs:s:2", "Variable", "s", "a.rs", 2, None, {})
+        assign_spans([node], SOURCE)
+        # The carriage return belongs to the line break, not the line
+        assert (node.properties["column"], node.properties["end_column"]) == (1, 25)
+        assert self._slice(node) == 'let s = "😀é"; let x = 1;'
+
+    def test_file_node_spans_the_text(self):
+        file_node = CodeNode("file:a.rs", "File", "a.rs", "a.rs", 0, None, {})
+        placeholder = CodeNode("unresolved:f", "Unresolved", "f", "", 0, None, {})
+        assign_spans([file_node, placeholder], SOURCE)
+        assert (file_node.properties["start_byte"], file_node.properties["end_byte"]) == (0, len(SOURCE.encode("utf-8")))
+        assert placeholder.properties == {}
+
+    def test_undecodable_bytes_keep_their_offsets(self):
+        data = b"x = '\xff'\ny = 2\n"
+        node = CodeNode("Variable:a.py:y:2", "Variable", "y", "a.py", 2, 2, {})
+        assign_spans([node], data.decode("utf-8", "surrogateescape"))
+        assert data[node.properties["start_byte"]:node.properties["end_byte"]] == b"y = 2"
+
+
+
+class TestEncodings:
+    TEXT = "def greet():\r\n    return 'héllo'\n"
+
+    @pytest.mark.parametrize("data, encoding", [
+        (TEXT.encode("utf-8"), None),
+        (TEXT.encode("utf-8-sig"), "utf-8-sig"),
+        (TEXT.encode("utf-16"), "utf-16"),
+        (TEXT.encode("utf-16-le"), "utf-16-le"),
+        (TEXT.encode("utf-16-be"), "utf-16-be"),
+        (TEXT.encode("utf-32"), "utf-32"),
+    ])
+    def test_transcoded_text(self, data, encoding):
+        assert source_encoding(data) == encoding
+        assert decode_text(data) == decode_source(data) == self.TEXT
+        assert not is_binary(data)
+
+    def test_read_text_reads_line_breaks_as_text_mode(self, tmp_path):
+        path = tmp_path / "greet.py"
+        path.write_bytes(self.TEXT.encode("utf-16"))
+        assert read_text(str(path)) == "def greet():\n    return 'héllo'\n"
+        assert not looks_binary(str(path))
+
+    def test_binary_files(self, tmp_path):
+        data = b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR" + bytes(range(256))
+        assert source_encoding(data) is None and is_binary(data)
+        path = tmp_path / "clip.ts"
+        path.write_bytes(data)
+        assert looks_binary(str(path))
+        assert not looks_binary(str(tmp_path / "missing.ts"))
+
+    def test_invalid_utf8_is_not_transcoded(self):
+        data = b"NAME = '\xe9t\xe9'\n"
+        with pytest.raises(UnicodeDecodeError):
+            decode_text(data)
+        # Spans still get text that encodes back to the same bytes
+        assert decode_source(data).encode("utf-8", "surrogateescape") == data
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -0,0 +1,69 @@
+"""
+Tests for the progress events of a graph build: one FileParsed per file and
+the resolution markers, whatever the number of threads, and a full channel
+that drops events without losing any parsed file.
+"""
+
+import os
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.graph.code_graph import CodeGraph
+from src.graph.progress import FileParsed, ProgressChannel, ResolutionFinished, ResolutionStarted
+
+FIXTURE_DIR = os.path.join(os.path.dirname(os.path.abspath(__file__)), "fixtures", "python_sample")
+
+
+def _paths():
+    return sorted(CodeGraph().collect_source_files(FIXTURE_DIR))
+
+
+class TestBuildWithProgress:
+
+    @pytest.mark.parametrize("jobs", [1, 3])
+    def test_one_event_per_file_and_resolution_markers(self, jobs):
+        paths, channel = _paths(), ProgressChannel()
+        graph = CodeGraph()
+        graph.build_with_progress(paths, channel, jobs=jobs)
+        events = channel.drain()
+
+        parsed = [e for e in events if isinstance(e, FileParsed)]
+        assert sorted(e.path for e in parsed) == paths
+        assert [e.index for e in parsed] == list(range(1, len(paths) + 1))
+        assert all(e.total == len(paths) and e.nodes == len(graph.files[e.path].nodes) for e in parsed)
+        assert events[len(parsed):] == [ResolutionStarted(len(paths)),
+                                        ResolutionFinished(len(paths), len(graph.relations))]
+        assert channel.dropped == 0
+
+    def test_same_graph_as_add_directory(self):
+        built, walked = CodeGraph(), CodeGraph()
+        built.build_with_progress(_paths(), ProgressChannel())
+        walked.add_directory(FIXTURE_DIR)
+        assert built.diff(walked).is_empty()
+
+    def test_full_channel_drops_events_but_not_files(self):
+        paths, channel = _paths(), ProgressChannel(maxsize=1)
+        graph = CodeGraph()
+        graph.build_with_progress(paths, channel)
+
+        # Only the last marker is left, having pushed out the first file's
+        # event and then the other marker; every file is still in the graph
+        assert channel.drain() == [ResolutionFinished(len(paths), len(graph.relations))]
+        assert channel.dropped == len(paths) + 1
+        assert sorted(graph.files) == paths
+
+    def test_sources_report_progress(self):
+        channel = ProgressChannel()
+        CodeGraph().add_sources({"b.py": "def b():\n    pass\n", "a.py": "import b\n"}, channel)
+        events = [e.to_dict() for e in channel.drain()]
+        assert [(e["event"], e.get("path"), e.get("index")) for e in events] == [
+            ("file_parsed", "a.py", 1), ("file_parsed", "b.py", 2),
+            ("resolution_started", None, None), ("resolution_finished", None, None)]
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
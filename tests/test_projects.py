@@ -0,0 +1,190 @@
+"""
+Tests for workspaces: several roots indexed into one graph as projects,
+resolved within each project and across them through the package map, and
+re-indexed one root at a time.
+
+Uses the legacy Python parser so no optional parsing backends are needed.
+"""
+
+import json
+import os
+import shutil
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.config import ConfigError, load_config
+from src.graph.code_graph import CodeGraph, FileState
+from src.graph.incremental import StoredFile, reindex
+from src.graph.node_query import query_nodes
+from src.graph.projects import ProjectMap
+from src.graph.symbol_search import SymbolIndex
+
+WORKSPACE = os.path.join(os.path.dirname(os.path.abspath(__file__)), "fixtures", "workspace_sample")
+SHOP = os.path.join(WORKSPACE, "shop")
+LEDGER = os.path.join(WORKSPACE, "ledger")
+CHECKOUT = os.path.join(SHOP, "orders", "checkout.py")
+ENTRIES = os.path.join(LEDGER, "ledger_api", "entries.py")
+
+
+def _build(roots, packages=None):
+    graph = CodeGraph().with_projects(ProjectMap(roots, packages))
+    graph.build_with_progress(sorted(p for root in roots for p in graph.collect_source_files(root)), None)
+    return graph
+
+
+def _imports(graph, file_path):
+    """(target file, symbol, cross_project) of a file's IMPORTS_DEFINITION edges."""
+    return sorted((graph.nodes[r.target_id].file_path, r.properties["symbol"], r.properties.get("cross_project", False))
+                  for r in graph.files[file_path].resolved_relations if r.relation_type == "IMPORTS_DEFINITION")
+
+
+@pytest.fixture(scope="module")
+def workspace():
+    return _build([SHOP, LEDGER], {"ledger_api": LEDGER})
+
+
+class TestProjectMap:
+
+    def test_projects_are_named_after_their_root(self):
+        projects = ProjectMap([SHOP, LEDGER, SHOP])
+        assert [(p.name, p.root) for p in projects.projects] == [("shop", SHOP), ("ledger", LEDGER)]
+        assert projects.project_of(CHECKOUT).name == "shop"
+        assert projects.project_of(os.path.join(WORKSPACE, "README.md")) is None
+
+    def test_nested_roots_take_the_deepest(self):
+        projects = ProjectMap([WORKSPACE, LEDGER])
+        assert projects.project_of(ENTRIES).name == "ledger"
+        assert projects.project_of(CHECKOUT).name == "workspace_sample"
+
+    def test_invalid_workspaces(self):
+        with pytest.raises(ValueError, match="both named 'orders'"):
+            ProjectMap([os.path.join(SHOP, "orders"), os.path.join(LEDGER, "orders")])
+        with pytest.raises(ValueError, match="not a workspace root"):
+            ProjectMap([SHOP], {"ledger_api": LEDGER})
+
+    def test_package_modules(self):
+        projects = ProjectMap([SHOP, LEDGER], {"ledger_api": LEDGER})
+        assert projects.package_modules(ENTRIES) == ["ledger_api.entries", "ledger_api/entries"]
+        # Outside the package's directory, paths start at the root
+        assert projects.package_modules(os.path.join(LEDGER, "orders", "models.py")) == [
+            "ledger_api.orders.models", "ledger_api/orders/models"]
+        assert projects.package_modules(CHECKOUT) == []
+
+
+class TestWorkspaceGraph:
+
+    def test_nodes_carry_their_project(self, workspace):
+        projects = {n.file_path: n.properties.get("project") for n in workspace.nodes.values() if n.file_path}
+        assert projects == {CHECKOUT: "shop", os.path.join(SHOP, "orders", "models.py"): "shop",
+                            ENTRIES: "ledger", os.path.join(LEDGER, "orders", "models.py"): "ledger"}
+
+    def test_modules_resolve_within_their_project(self, workspace):
+        # Both projects have a models.py; each import finds its own
+        assert _imports(workspace, ENTRIES) == [(os.path.join(LEDGER, "orders", "models.py"), "Order", False)]
+        assert (os.path.join(SHOP, "orders", "models.py"), "Order", False) in _imports(workspace, CHECKOUT)
+
+    def test_mapped_packages_resolve_across_projects(self, workspace):
+        assert _imports(workspace, CHECKOUT) == [(ENTRIES, "post", True),
+                                                 (os.path.join(SHOP, "orders", "models.py"), "Order", False)]
+
+    def test_unmapped_packages_stay_unresolved(self):
+        graph = _build([SHOP, LEDGER])
+        assert [symbol for _, symbol, _ in _imports(graph, CHECKOUT)] == ["Order"]
+
+    def test_changing_a_package_file_refreshes_its_importers(self, workspace, tmp_path):
+        shutil.copytree(WORKSPACE, str(tmp_path / "ws"))
+        shop, ledger = str(tmp_path / "ws" / "shop"), str(tmp_path / "ws" / "ledger")
+        graph = _build([shop, ledger], {"ledger_api": ledger})
+        entries = os.path.join(ledger, "ledger_api", "entries.py")
+        checkout = os.path.join(shop, "orders", "checkout.py")
+
+        graph.update_file(entries, "def record(entry):\n    return entry\n")
+        assert [symbol for _, symbol, _ in _imports(graph, checkout)] == ["Order"]
+        graph.update_file(entries, "def post(entry):\n    return entry\n")
+        assert (entries, "post", True) in _imports(graph, checkout)
+
+
+class TestPerRootReindex:
+
+    @staticmethod
+    def _stored(graph):
+        return {path: StoredFile(path, state.content_hash, state.mtime_ns,
+                                 FileState.from_dict(json.loads(json.dumps(state.to_dict()))))
+                for path, state in graph.files.items()}
+
+    def test_other_projects_are_kept(self, tmp_path):
+        shutil.copytree(WORKSPACE, str(tmp_path / "ws"))
+        shop, ledger = str(tmp_path / "ws" / "shop"), str(tmp_path / "ws" / "ledger")
+        stored = self._stored(_build([shop, ledger], {"ledger_api": ledger}))
+        # A removed ledger file is only noticed when the ledger is re-indexed
+        os.remove(os.path.join(ledger, "orders", "models.py"))
+        with open(os.path.join(shop, "orders", "models.py"), "a", encoding="utf-8") as f:
+            f.write("\n\nclass Refund:\n    pass\n")
+
+        projects = ProjectMap([shop, ledger], {"ledger_api": ledger})
+        graph = CodeGraph().with_projects(projects)
+        result = reindex(graph, [shop], stored, keep_outside=True)
+        assert (result.stats.reparsed, result.stats.deleted) == (1, 0)
+        assert os.path.join(ledger, "orders", "models.py") in graph.files
+        assert any(n.name == "Refund" and n.properties["project"] == "shop" for n in graph.nodes.values())
+        entries = os.path.join(ledger, "ledger_api", "entries.py")
+        assert graph.nodes[f"file:{entries}"].properties["project"] == "ledger"
+
+        result = reindex(CodeGraph().with_projects(projects), [ledger], stored, keep_outside=True)
+        assert (result.stats.reparsed, result.stats.deleted) == (0, 1)
+
+    def test_without_keep_outside_other_roots_are_deleted(self, tmp_path):
+        shutil.copytree(WORKSPACE, str(tmp_path / "ws"))
+        shop, ledger = str(tmp_path / "ws" / "shop"), str(tmp_path / "ws" / "ledger")
+        stored = self._stored(_build([shop, ledger]))
+        graph = CodeGraph().with_projects(ProjectMap([shop, ledger]))
+        result = reindex(graph, shop, stored)
+        assert result.stats.deleted == 2
+        assert all(path.startswith(shop) for path in graph.files)
+
+
+class TestProjectFilters:
+
+    @staticmethod
+    def _symbols(graph):
+        return [{"id": n.node_id, "name": n.name, "type": n.node_type, "file_path": n.file_path,
+                 "line_no": n.line_no, "project": n.properties.get("project")}
+                for n in graph.nodes.values() if n.node_type not in ("File", "Unresolved")]
+
+    def test_query_project_field(self, workspace):
+        found = query_nodes(self._symbols(workspace), "kind:class AND project:ledger")
+        assert [(s["name"], s["file_path"]) for s in found] == [("Order", os.path.join(LEDGER, "orders", "models.py"))]
+        assert len(query_nodes(self._symbols(workspace), "name:Order AND project:*")) == 2
+
+    def test_search_by_project(self, workspace):
+        index = SymbolIndex(self._symbols(workspace))
+        assert len(index.search("Order").matches) == 2
+        shop, = index.search("Order", project="shop").matches
+        assert shop.symbol["file_path"] == os.path.join(SHOP, "orders", "models.py")
+
+
+class TestWorkspaceConfig:
+
+    def test_roots_and_packages_are_relative_to_the_file(self, tmp_path):
+        path = tmp_path / "codegraph.toml"
+        path.write_text('[workspace]\nroots = ["shop", "../ledger"]\n\n'
+                        '[workspace.packages]\nledger_api = "../ledger"\n"@acme/ui" = "shop"\n')
+        config = load_config(str(path), environ={})
+        assert config.workspace_roots == [str(tmp_path / "shop"), os.path.join(os.path.dirname(tmp_path), "ledger")]
+        assert config.workspace_packages == {"ledger_api": os.path.join(os.path.dirname(tmp_path), "ledger"),
+                                             "@acme/ui": str(tmp_path / "shop")}
+        assert config.warnings == []
+
+    def test_invalid_package_root(self, tmp_path):
+        path = tmp_path / "codegraph.toml"
+        path.write_text("[workspace.packages]\nledger_api = 1\n")
+        with pytest.raises(ConfigError, match=":2: 'workspace.packages.ledger_api' must be the path"):
+            load_config(str(path), environ={})
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -0,0 +1,104 @@
+"""
+Tests for the public API surface and its snapshot diff.
+"""
+
+import os
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.graph.public_api import diff_api, load_snapshot, public_api, save_snapshot
+
+
+def _symbol(name, node_type, visibility, line_no, file_path="/repo/src/lib.rs", doc=None):
+    return {"id": f"{node_type}:{file_path}:{name}:{line_no}", "name": name, "type": node_type,
+            "file_path": file_path, "line_no": line_no, "visibility": visibility, "doc": doc}
+
+
+PERSON = _symbol("Person", "Class", "Public", 4, doc="A person.")
+NAME = _symbol("name", "Field", "Private", 5)
+NEW = _symbol("new", "Method", "Public", 10)
+VALIDATE = _symbol("validate", "Method", "Crate", 14)
+# Same method name on another type
+POINT_NEW = _symbol("new", "Method", "Public", 3, file_path="/repo/src/point.rs")
+POINT = _symbol("Point", "Class", "Public", 1, file_path="/repo/src/point.rs")
+HELPER = _symbol("helper", "Function", "Private", 20)
+
+SYMBOLS = [PERSON, NAME, NEW, VALIDATE, POINT, POINT_NEW, HELPER]
+RELATIONSHIPS = [
+    {"source": PERSON["id"], "target": NAME["id"], "type": "DEFINES"},
+    {"source": PERSON["id"], "target": NEW["id"], "type": "DEFINES"},
+    {"source": PERSON["id"], "target": VALIDATE["id"], "type": "DEFINES"},
+    {"source": POINT["id"], "target": POINT_NEW["id"], "type": "DEFINES"},
+    {"source": NEW["id"], "target": VALIDATE["id"], "type": "CALLS"},
+    {"source": NEW["id"], "target": HELPER["id"], "type": "CALLS"},
+]
+
+
+class TestPublicApi:
+    def test_only_public_items(self):
+        api = public_api(SYMBOLS, RELATIONSHIPS, root="/repo")
+        assert [(i["file_path"], i["qualified_name"]) for i in api["items"]] == [
+            ("src/lib.rs", "Person"), ("src/lib.rs", "Person.new"),
+            ("src/point.rs", "Point"), ("src/point.rs", "Point.new"),
+        ]
+        assert api["items"][0]["doc"] == "A person."
+        # Edges to private or crate items are dropped
+        assert {(e["source"], e["target"]) for e in api["edges"]} == {
+            (PERSON["id"], NEW["id"]), (POINT["id"], POINT_NEW["id"]),
+        }
+
+    def test_crate_items_on_request(self):
+        api = public_api(SYMBOLS, RELATIONSHIPS, include_crate=True, path_prefix="/repo/src/lib")
+        assert [i["qualified_name"] for i in api["items"]] == ["Person", "Person.new", "Person.validate"]
+        assert {"source": NEW["id"], "target": VALIDATE["id"], "type": "CALLS"} in api["edges"]
+
+
+class TestDiff:
+    def test_round_trip_through_a_snapshot(self, tmp_path):
+        path = str(tmp_path / "api.json")
+        api = public_api(SYMBOLS, RELATIONSHIPS, root="/repo")
+        save_snapshot(api, path)
+
+        assert load_snapshot(path) == api["items"]
+        assert diff_api(load_snapshot(path), api["items"]) == \
+            {"added": [], "removed": [], "changed": [], "suggested_bump": "patch"}
+
+    def test_moved_code_is_not_a_change(self):
+        before = public_api(SYMBOLS, RELATIONSHIPS, root="/repo")["items"]
+        moved = [dict(s, line_no=s["line_no"] + 50) for s in SYMBOLS]
+        after = public_api(moved, RELATIONSHIPS, root="/repo")["items"]
+        assert diff_api(before, after)["suggested_bump"] == "patch"
+
+    def test_removed_and_narrowed_are_breaking(self):
+        before = public_api(SYMBOLS, RELATIONSHIPS, include_crate=True)["items"]
+        symbols = [dict(s, visibility="Crate") if s is POINT_NEW else s for s in SYMBOLS if s is not NEW]
+        after = public_api(symbols, RELATIONSHIPS, include_crate=True)["items"]
+
+        diff = diff_api(before, after)
+        assert [i["qualified_name"] for i in diff["removed"]] == ["Person.new"]
+        assert [(c["qualified_name"], c["visibility_before"], c["visibility"]) for c in diff["changed"]] == \
+            [("Point.new", "Public", "Crate")]
+        assert diff["suggested_bump"] == "major"
+
+    def test_added_items_are_a_minor_bump(self):
+        before = public_api([s for s in SYMBOLS if s is not POINT_NEW], RELATIONSHIPS)["items"]
+        diff = diff_api(before, public_api(SYMBOLS, RELATIONSHIPS)["items"])
+        assert [i["qualified_name"] for i in diff["added"]] == ["Point.new"]
+        assert diff["suggested_bump"] == "minor"
+
+    def test_not_a_snapshot(self, tmp_path):
+        path = tmp_path / "api.json"
+        path.write_text('{"schema_version": 99, "items": []}')
+        with pytest.raises(ValueError, match="Unsupported public API snapshot version"):
+            load_snapshot(str(path))
+        path.write_text("not json")
+        with pytest.raises(ValueError, match="Not a public API snapshot"):
+            load_snapshot(str(path))
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
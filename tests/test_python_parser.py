@@ -0,0 +1,221 @@
+"""
+Tests for Python classes, decorators and nested functions.
+
+Both ASTParser and PythonAstGrepAdapter parse tests/fixtures/python_sample:
+method kinds from @property, @staticmethod and friends, closures as
+Function nodes defined by their enclosing function, async definitions and
+base classes named through an imported module (base.Shape), and the
+geometry package, whose relative imports resolve to files by path. Fields and
+their accesses through self are checked on the Person of
+tests/fixtures/multi_lang_sample and on a small inline class.
+"""
+
+import os
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.ast_parser.multi_parser import MultiLanguageParser
+from src.ast_parser.parser import ASTParser, python_method_kind
+from src.graph.code_graph import CodeGraph
+from src.graph.dead_code import find_unreferenced, find_unused
+from tests.helpers import find_node
+
+
+SAMPLE_DIR = os.path.abspath(os.path.join(os.path.dirname(__file__), "fixtures", "python_sample"))
+SHAPES_PY = os.path.join(SAMPLE_DIR, "shapes.py")
+GEOMETRY_DIR = os.path.join(SAMPLE_DIR, "geometry")
+PERSON_PY = os.path.abspath(os.path.join(os.path.dirname(__file__), "fixtures", "multi_lang_sample", "sample.py"))
+
+POINTS_PY = """class Point:
+    dims: int = 2
+    origin = None
+
+    def __init__(self, x: float, y: float, tags):
+        self.x = x
+        self.y: float = y
+        self.tags, self.count = tags, 0
+
+    def move(self, other):
+        self.x += other.x
+        self.tags[0] = other.tags
+        return self.norm()
+
+    @classmethod
+    def make(cls):
+        cls.origin = cls(0, 0, [])
+"""
+
+
+@pytest.fixture(params=["legacy", "ast-grep"])
+def parsed(request):
+    if request.param == "legacy":
+        return ASTParser().parse_directory(SAMPLE_DIR)
+    pytest.importorskip("ast_grep_py")
+    parser = MultiLanguageParser(use_ast_grep=True, ast_grep_languages=['python'], ast_grep_fallback=False)
+    return parser.parse_directory(SAMPLE_DIR, build_index=True)
+
+
+def _parse_source(parser, source, file_path):
+    if parser == "legacy":
+        return ASTParser().parse_source(source, file_path)
+    pytest.importorskip("ast_grep_py")
+    from src.ast_parser.adapters.python_adapter import PythonAstGrepAdapter
+    return PythonAstGrepAdapter().parse_source(source, file_path)
+
+
+def _defined_by(nodes, relations, definer):
+    return {nodes[r.target_id].name for r in relations
+            if r.relation_type == "DEFINES" and r.source_id == definer.node_id}
+
+
+def _callees(relations, caller):
+    """Names called by caller, resolved or not."""
+    return {r.target_id.split(":")[-2] for r in relations
+            if r.relation_type == "CALLS" and r.source_id == caller.node_id}
+
+
+class TestMethodKind:
+    def test_decorators(self):
+        assert python_method_kind([]) == "instance"
+        assert python_method_kind(["staticmethod"]) == "static"
+        assert python_method_kind(["abc.abstractmethod", "classmethod"]) == "class"
+        assert python_method_kind(["functools.cached_property"]) == "property"
+        assert python_method_kind(["radius.deleter"]) == "property"
+        # A bare setter is some other decorator
+        assert python_method_kind(["setter", "lru_cache(maxsize=None)"]) == "instance"
+
+
+class TestPythonSample:
+    def test_method_kinds(self, parsed):
+        nodes, _ = parsed
+        kinds = sorted((n.line_no, n.name, n.properties["method_kind"]) for n in nodes.values()
+                       if n.node_type == "Method" and n.file_path == SHAPES_PY)
+        assert kinds == [
+            (17, "__init__", "instance"),
+            (21, "radius", "property"),
+            (25, "radius", "property"),
+            (29, "diameter", "property"),
+            (33, "unit", "static"),
+            (37, "from_diameter", "class"),
+            (41, "area", "instance"),
+            (44, "render", "instance"),
+        ]
+
+    def test_decorators_are_recorded(self, parsed):
+        nodes, _ = parsed
+        setter = next(n for n in nodes.values() if n.name == "radius" and n.line_no == 25)
+        assert setter.properties["decorators"] == ["radius.setter"]
+        area = next(n for n in nodes.values() if n.name == "area" and n.file_path == SHAPES_PY)
+        assert area.properties["decorators"] == ["traced"]
+
+    def test_closures_belong_to_their_function(self, parsed):
+        nodes, relations = parsed
+        wrapper = find_node(nodes, "Function", "wrapper")
+        assert wrapper.properties["visibility"] == "Private"
+        assert wrapper.properties["decorators"] == ["wraps(func)"]
+        assert (wrapper.line_no, wrapper.end_line_no) == (10, 12)
+        assert _defined_by(nodes, relations, find_node(nodes, "Function", "traced")) == {"wrapper"}
+        assert _defined_by(nodes, relations, find_node(nodes, "Method", "render")) == {"outline"}
+        assert _defined_by(nodes, relations, find_node(nodes, "Function", "describe")) == {"label"}
+        # Closures are not contained by the file
+        assert not any(r.relation_type == "CONTAINS" and r.target_id == wrapper.node_id for r in relations)
+
+    def test_calls_inside_closures_are_their_own(self, parsed):
+        nodes, relations = parsed
+        assert _callees(relations, find_node(nodes, "Function", "wrapper")) == {"print", "func"}
+        assert _callees(relations, find_node(nodes, "Function", "traced")) == set()
+        assert _callees(relations, find_node(nodes, "Function", "outline")) == {"describe"}
+        assert _callees(relations, find_node(nodes, "Method", "render")) == {"outline"}
+
+    def test_bases_across_files(self, parsed):
+        nodes, relations = parsed
+        circle = find_node(nodes, "Class", "Circle")
+        bases = {nodes[r.target_id].name: r.properties.get("original_name") for r in relations
+                 if r.relation_type == "EXTENDS" and r.source_id == circle.node_id}
+        assert bases == {"Shape": "base.Shape", "Named": "Named"}
+
+
+    def test_relative_imports(self, parsed):
+        nodes, relations = parsed
+
+        def imports(file_name):
+            return sorted((r.relation_type, os.path.relpath(nodes[r.target_id].file_path, SAMPLE_DIR),
+                           nodes[r.target_id].name) for r in relations
+                          if r.relation_type.startswith("IMPORTS")
+                          and r.source_id == f"file:{os.path.join(GEOMETRY_DIR, file_name)}")
+
+        # from ..base import Shape, and the sibling module of from . import sides
+        assert imports("polygon.py") == [
+            ("IMPORTS_DEFINITION", "base.py", "Shape"),
+            ("IMPORTS_FROM", "base.py", "base.py"),
+            ("IMPORTS_FROM", os.path.join("geometry", "sides.py"), "sides.py"),
+        ]
+        assert ("IMPORTS_DEFINITION", "base.py", "Named") in imports("square.py")
+        assert ("IMPORTS_DEFINITION", os.path.join("geometry", "polygon.py"), "Polygon") in imports("square.py")
+
+    def test_package_classes(self, parsed):
+        nodes, relations = parsed
+        square = find_node(nodes, "Class", "Square")
+        bases = {nodes[r.target_id].name: r.properties.get("original_name") for r in relations
+                 if r.relation_type == "EXTENDS" and r.source_id == square.node_id}
+        assert bases == {"Polygon": "Polygon", "Named": "Label"}
+        polygon = find_node(nodes, "Class", "Polygon")
+        assert [nodes[r.target_id].name for r in relations
+                if r.relation_type == "EXTENDS" and r.source_id == polygon.node_id] == ["Shape"]
+        methods = {nodes[r.target_id].name: nodes[r.target_id].properties["method_kind"] for r in relations
+                   if r.relation_type == "DEFINES" and r.source_id == square.node_id}
+        assert methods == {"__init__": "instance", "corners": "static", "unit": "class"}
+
+
+def _accesses(nodes, relations, field):
+    """(accessor name, access) of the REFERENCES edges into a field."""
+    return sorted((nodes[r.source_id].name, r.properties["access"]) for r in relations
+                  if r.relation_type == "REFERENCES" and r.target_id == field.node_id)
+
+
+@pytest.mark.parametrize("parser", ["legacy", "ast-grep"])
+class TestFields:
+    def test_person_fields(self, parser):
+        with open(PERSON_PY, encoding="utf-8") as f:
+            nodes, relations = _parse_source(parser, f.read(), PERSON_PY)
+        person = find_node(nodes, "Class", "Person")
+        name = find_node(nodes, "Field", "name")
+        assert _defined_by(nodes, relations, person) >= {"name", "age"}
+        # The type comes from the annotated parameter assigned to the field
+        assert (name.line_no, name.properties) == (8, {"field_of": "Person", "type": "str", "visibility": "Public"})
+        assert _accesses(nodes, relations, name) == [("__init__", "Write"), ("get_name", "Read"),
+                                                     ("set_name", "Write")]
+        assert _accesses(nodes, relations, find_node(nodes, "Field", "age")) == [("__init__", "Write"),
+                                                                             ("get_age", "Read")]
+
+    def test_declarations(self, parser):
+        nodes, relations = _parse_source(parser, POINTS_PY, "points.py")
+        fields = {n.name: (n.line_no, n.properties["type"]) for n in nodes.values() if n.node_type == "Field"}
+        # origin is a class variable; tags is not annotated, and the tuple it is unpacked from is not its type
+        assert fields == {"dims": (2, "int"), "x": (6, "float"), "y": (7, "float"), "tags": (8, None),
+                          "count": (8, None)}
+
+    def test_access_kinds(self, parser):
+        nodes, relations = _parse_source(parser, POINTS_PY, "points.py")
+        move = find_node(nodes, "Method", "move")
+        references = sorted((nodes[r.target_id].name, r.properties["access"], r.properties["line_no"])
+                            for r in relations if r.relation_type == "REFERENCES" and r.source_id == move.node_id)
+        # other.x and other.tags are on an unknown receiver, and norm is no field
+        assert references == [("tags", "Write", 12), ("x", "ReadWrite", 11)]
+        make = find_node(nodes, "Method", "make")
+        assert not [r for r in relations if r.relation_type == "REFERENCES" and r.source_id == make.node_id]
+
+
+class TestClosuresAreNotDead:
+    def test_closures_are_exempt(self):
+        graph = CodeGraph.from_directory(SAMPLE_DIR)
+        assert not {"wrapper", "outline", "label"} & {d.name for d in find_unreferenced(graph)}
+        assert not {"wrapper", "outline", "label"} & {d.name for d in find_unused(graph)}
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -0,0 +1,134 @@
+"""
+Tests for fully qualified symbol names: module paths, separators by
+language, owners found through DEFINES edges or ``method_of``, and lookups
+that tell same-named methods of different types apart.
+"""
+
+import os
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.ast_parser.parser import CodeNode, CodeRelation
+from src.ast_parser.qualified_names import assign_qualified_names
+from src.graph.code_graph import CodeGraph
+
+FIXTURE_DIR = os.path.join(os.path.dirname(os.path.abspath(__file__)), "fixtures", "multi_lang_sample")
+
+SHOPS = '''
+class Shop:
+    def new(self):
+        return Shop()
+
+
+class Stock:
+    def new(self):
+        def fill():
+            pass
+        return fill
+'''
+
+
+def _qualified(nodes):
+    return {node.node_id: node.properties.get("qualified_name") for node in nodes.values()}
+
+
+def _file(file_path, **properties):
+    return CodeNode(f"file:{file_path}", "File", os.path.basename(file_path), file_path, 0, properties=properties)
+
+
+class TestAssignQualifiedNames:
+
+    def test_rust_module_path_and_impl_methods(self):
+        file_node = _file("src/person.rs", module_path="crate::person")
+        person = CodeNode("Class:src/person.rs:Person:4", "Class", "Person", "src/person.rs", 4)
+        new = CodeNode("Method:src/person.rs:new:10", "Method", "new", "src/person.rs", 10,
+                       properties={"method_of": "Person"})
+        # An impl of a type from another file: no DEFINES edge yet
+        render = CodeNode("Method:src/person.rs:render:20", "Method", "render", "src/person.rs", 20,
+                          properties={"method_of": "Card"})
+        nodes = {node.node_id: node for node in (file_node, person, new, render)}
+        relations = [CodeRelation(file_node.node_id, person.node_id, "CONTAINS"),
+                     CodeRelation(person.node_id, new.node_id, "DEFINES")]
+        assign_qualified_names(nodes, relations, "src/person.rs", "rust")
+
+        assert _qualified(nodes) == {
+            file_node.node_id: None,
+            person.node_id: "crate::person::Person",
+            new.node_id: "crate::person::Person::new",
+            render.node_id: "crate::person::Card::render",
+        }
+
+    def test_workspace_crate_module_wins(self):
+        file_node = _file("shapes/src/lib.rs", module_path="crate", qualified_module="shapes")
+        area = CodeNode("Function:shapes/src/lib.rs:area:1", "Function", "area", "shapes/src/lib.rs", 1)
+        nodes = {file_node.node_id: file_node, area.node_id: area}
+        assign_qualified_names(nodes, [], "shapes/src/lib.rs", "rust")
+        assert area.properties["qualified_name"] == "shapes::area"
+
+    def test_java_package(self):
+        path = "com/example/Person.java"
+        file_node = _file(path, package="com.example")
+        person = CodeNode(f"Class:{path}:Person:3", "Class", "Person", path, 3)
+        get_name = CodeNode(f"Method:{path}:getName:8", "Method", "getName", path, 8,
+                            properties={"method_of": "Person"})
+        nodes = {node.node_id: node for node in (file_node, person, get_name)}
+        assign_qualified_names(nodes, [CodeRelation(person.node_id, get_name.node_id, "DEFINES")], path, "java")
+        assert get_name.properties["qualified_name"] == "com.example.Person.getName"
+
+    def test_declarations_do_not_own(self):
+        # A C++ method declared in its class and defined outside it
+        file_node = _file("shape.cpp")
+        shape = CodeNode("Class:shape.cpp:Shape:1", "Class", "Shape", "shape.cpp", 1)
+        area = CodeNode("Method:shape.cpp:area:9", "Method", "area", "shape.cpp", 9,
+                        properties={"method_of": "Shape"})
+        nodes = {node.node_id: node for node in (file_node, shape, area)}
+        relations = [CodeRelation(shape.node_id, area.node_id, "DECLARED_BY")]
+        assign_qualified_names(nodes, relations, "shape.cpp", "cpp")
+        assert (shape.properties["qualified_name"], area.properties["qualified_name"]) == ("Shape", "Shape::area")
+
+
+class TestPythonNames:
+
+    @pytest.fixture
+    def graph(self):
+        graph = CodeGraph()
+        graph.add_file(os.path.join(FIXTURE_DIR, "sample.py"))
+        graph.add_sources({"shops.py": SHOPS})
+        return graph
+
+    def test_sample_methods_are_distinct(self, graph):
+        get_name, = graph.find_by_qualified_name("sample.Person.get_name")
+        init, = graph.find_by_qualified_name("sample.Person.__init__")
+        assert (get_name.name, init.name) == ("get_name", "__init__")
+        assert graph.find_by_qualified_name("sample.greet")[0].node_type == "Function"
+        assert graph.find_by_qualified_name("get_name") == []
+
+    def test_same_named_methods_of_two_classes(self, graph):
+        news = [node for node in graph.nodes.values() if node.name == "new"]
+        assert len(news) == 2
+        shop_new, = graph.find_by_qualified_name("shops.Shop.new")
+        stock_new, = graph.find_by_qualified_name("shops.Stock.new")
+        assert shop_new.line_no < stock_new.line_no
+        # A closure is named after the function defining it
+        assert graph.find_by_qualified_name("shops.Stock.new.fill")[0].node_type == "Function"
+
+
+class TestRustSample:
+
+    def test_person_methods(self):
+        pytest.importorskip("ast_grep_py")
+        graph = CodeGraph(use_ast_grep=True, ast_grep_languages=["rust"])
+        graph.add_file(os.path.join(FIXTURE_DIR, "sample.rs"))
+        qualified = sorted(node.properties["qualified_name"] for node in graph.nodes.values()
+                           if node.node_type == "Method")
+        assert "crate::sample::Person::new" in qualified and "crate::sample::Person::get_name" in qualified
+        new, = graph.find_by_qualified_name("crate::sample::Person::new")
+        assert new.properties["method_of"] == "Person"
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
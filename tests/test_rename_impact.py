@@ -0,0 +1,125 @@
+"""
+Tests for the rename impact preview.
+"""
+
+import os
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.graph.code_graph import CodeGraph
+from src.graph.rename_impact import occurrences, rename_impact
+
+
+UTIL = '''"""Helpers; helper() is the main one."""
+
+
+def helper(value):
+    # helper keeps the value as is
+    return value
+
+
+def assist():
+    return 1
+
+
+REGISTRY = {"helper": helper}
+'''
+
+APP = '''from util import helper
+
+
+def load():
+    data = helper(1)
+    print("calling helper")
+    return helper(data) + helper(0)
+'''
+
+
+@pytest.fixture
+def project(tmp_path):
+    (tmp_path / "util.py").write_text(UTIL, encoding="utf-8")
+    (tmp_path / "app.py").write_text(APP, encoding="utf-8")
+    graph = CodeGraph.from_directory(str(tmp_path))
+    helper = next(i for i, n in graph.nodes.items() if n.name == "helper" and n.node_type == "Function")
+    # What find_usages reports: the line of the use (or of the using symbol) and the using symbol
+    usages = []
+    for relation in graph.relations:
+        if relation.target_id == helper and relation.relation_type in ("CALLS", "IMPORTS_DEFINITION"):
+            source = graph.nodes[relation.source_id]
+            usages.append({"file": source.file_path,
+                           "line": relation.properties.get("line_no") or source.line_no,
+                           "usage_kind": "call" if relation.relation_type == "CALLS" else "import",
+                           "source": {"id": source.node_id}})
+    return tmp_path, graph, helper, usages
+
+
+def _read(path):
+    with open(path, encoding="utf-8") as f:
+        return f.read()
+
+
+class TestOccurrences:
+    def test_code_strings_and_comments(self):
+        lines = ['x = f"{name}" + name  # name', '/* name', 'name */ name']
+        assert occurrences(lines[:1], "name", "python") == {1: [(8, "string"), (17, "code"), (25, "comment")]}
+        assert occurrences(lines[1:], "name", "rust") == {1: [(4, "comment")], 2: [(1, "comment"), (9, "code")]}
+
+    def test_whole_words_only(self):
+        assert occurrences(["rename(name_of, $name, my_name)"], "name", "javascript") == {}
+
+
+class TestRenameImpact:
+    def test_patches(self, project):
+        root, graph, helper, usages = project
+        impact = rename_impact(graph, [helper], usages, "assist_value", _read)
+
+        patches = [(os.path.basename(p.file), p.line, p.kind, p.usage_kind, p.inferred, p.proposed_new_text)
+                   for p in impact.patches]
+        assert patches == [
+            # The import has no line in the graph, so app.py is searched for it
+            ("app.py", 1, "reference", "import", True, "from util import assist_value"),
+            ("app.py", 5, "reference", "call", False, "    data = assist_value(1)"),
+            # load calls helper three times, but the graph has one edge for them
+            ("app.py", 7, "reference", "call", True, "    return assist_value(data) + assist_value(0)"),
+            ("util.py", 4, "definition", None, False, "def assist_value(value):"),
+        ]
+        assert impact.patches[1].old_text == "    data = helper(1)"
+
+    def test_strings_comments_and_unlinked_code_are_warnings(self, project):
+        root, graph, helper, usages = project
+        impact = rename_impact(graph, [helper], usages, "assist_value", _read)
+
+        warnings = [(os.path.basename(w.file), w.line, w.kind) for w in impact.warnings]
+        assert warnings == [
+            ("app.py", 6, "string_literal"),
+            ("util.py", 1, "string_literal"),
+            ("util.py", 5, "comment"),
+            ("util.py", 13, "string_literal"),
+            # A reference the parser does not record
+            ("util.py", 13, "unlinked_code"),
+        ]
+        assert impact.warnings[-1].text == 'REGISTRY = {"helper": helper}'
+
+    def test_collisions(self, project):
+        _, graph, helper, usages = project
+        impact = rename_impact(graph, [helper], usages, "assist", _read)
+        assert [(c["name"], c["type"]) for c in impact.collisions] == [("assist", "Function")]
+        assert rename_impact(graph, [helper], usages, "load", _read).collisions == []
+
+    def test_unreadable_files_use_the_stored_line(self, project):
+        _, graph, helper, _ = project
+        usages = [{"file": "gone.py", "line": 3, "usage_kind": "call", "context_snippet": "return helper(x)"},
+                  {"file": "gone.py", "line": 0, "usage_kind": "import", "context_snippet": None}]
+        impact = rename_impact(graph, [helper], usages, "assist_value", lambda path: None)
+
+        assert [(p.file, p.old_text, p.proposed_new_text) for p in impact.patches] == \
+            [("gone.py", "return helper(x)", "return assist_value(x)")]
+        assert [u["usage_kind"] for u in impact.unlocated] == ["definition", "import"]
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -0,0 +1,117 @@
+"""
+Tests for rename plans: exact edit spans, and refusals when a reference
+could not be resolved.
+"""
+
+import os
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.graph.code_graph import CodeGraph
+from src.graph.rename_plan import RenameRefused, plan_rename
+
+
+PERSON = '''class Person:
+    """Holds a name; see get_name."""
+
+    def __init__(self, name: str):
+        self.name = name
+
+    def get_name(self) -> str:
+        # the name as given
+        return self.name
+
+
+def greet(person: Person) -> str:
+    return "Hello " + person.get_name()
+'''
+
+# Uses Person through receivers the parser does not type
+SHOW = '''def show(p):
+    print("name", p.name)
+    return p.get_name()
+'''
+
+
+@pytest.fixture
+def project(tmp_path):
+    (tmp_path / "person.py").write_text(PERSON, encoding="utf-8")
+    return tmp_path
+
+
+def _graph(root):
+    graph = CodeGraph.from_directory(str(root))
+    field = next(i for i, n in graph.nodes.items() if n.name == "name" and n.node_type == "Field")
+    method = next(i for i, n in graph.nodes.items() if n.name == "get_name")
+    return graph, field, method
+
+
+def _read(path):
+    with open(path, encoding="utf-8") as f:
+        return f.read()
+
+
+def _edits(plan):
+    return [(os.path.basename(e.file), e.line, e.column, e.kind, e.usage_kind) for e in plan.edits]
+
+
+class TestPlanRename:
+    def test_field(self, project):
+        graph, field, _ = _graph(project)
+        plan = plan_rename(graph, field, "full_name", _read)
+
+        # The parameter, the docstring and the comment are left alone
+        assert _edits(plan) == [
+            ("person.py", 5, 14, "definition", None),
+            ("person.py", 9, 21, "reference", "field_access"),
+        ]
+        data = plan.to_dict()
+        assert (data["old_name"], data["new_name"], data["total"]) == ("name", "full_name", 2)
+        assert data["edits"][1]["span"] == {"start": {"line": 9, "column": 21}, "end": {"line": 9, "column": 25}}
+
+    def test_method(self, project):
+        graph, _, method = _graph(project)
+        plan = plan_rename(graph, method, "name_of", _read)
+
+        assert _edits(plan) == [
+            ("person.py", 7, 9, "definition", None),
+            ("person.py", 13, 30, "reference", "call"),
+        ]
+        assert (plan.edits[1].old_text, plan.edits[1].new_text) == ("get_name", "name_of")
+
+    def test_untyped_field_access_is_refused(self, project):
+        (project / "show.py").write_text(SHOW, encoding="utf-8")
+        graph, field, _ = _graph(project)
+
+        with pytest.raises(RenameRefused) as refused:
+            plan_rename(graph, field, "full_name", _read)
+        data = refused.value.to_dict()
+        assert data["code"] == "unresolved_references"
+        # The string on the same line is not the blocker
+        assert [(os.path.basename(b["file"]), b["line"], b["reason"], b["text"]) for b in data["blockers"]] == [
+            ("show.py", 2, "untyped_access", 'print("name", p.name)'),
+        ]
+
+    def test_unresolved_call_is_refused(self, project):
+        (project / "show.py").write_text(SHOW, encoding="utf-8")
+        graph, _, method = _graph(project)
+
+        with pytest.raises(RenameRefused) as refused:
+            plan_rename(graph, method, "name_of", _read)
+        assert [(os.path.basename(b.file), b.line, b.reason) for b in refused.value.blockers] == [
+            ("show.py", 3, "unresolved_reference"),
+        ]
+
+    def test_unreadable_file_is_refused(self, project):
+        graph, field, _ = _graph(project)
+        with pytest.raises(RenameRefused) as refused:
+            plan_rename(graph, field, "full_name", lambda path: None)
+        assert {b.reason for b in refused.value.blockers} == {"unlocated_reference"}
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -0,0 +1,203 @@
+"""
+Tests for RubyAdapter.
+
+Covers the multi-language sample (a class with instance and class methods,
+a top-level function and a require) and a small Rails application, where
+models extend ApplicationRecord in another file, require each other and
+declare ActiveRecord associations.
+"""
+
+import os
+import sys
+import json
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+pytest.importorskip("ast_grep_py")
+
+from src.ast_parser.multi_parser import MultiLanguageParser
+from src.ast_parser.adapters.ruby_adapter import RubyAdapter, camelize, singularize, underscore
+from src.graph.code_graph import CodeGraph, UNRESOLVED_PREFIX
+from tests.helpers import edge_names, find_node, node_names
+
+
+FIXTURES = os.path.abspath(os.path.join(os.path.dirname(__file__), "fixtures"))
+SAMPLE_RB = os.path.join(FIXTURES, "multi_lang_sample", "sample.rb")
+RUBY_SAMPLE_DIR = os.path.join(FIXTURES, "ruby_sample")
+
+
+def _parse_directory(directory):
+    parser = MultiLanguageParser(use_ast_grep=True, ast_grep_languages=['ruby'], ast_grep_fallback=False)
+    return parser.parse_directory(directory, build_index=True)
+
+
+class TestSampleRuby:
+    """sample.rb from the multi-language fixtures."""
+
+    @pytest.fixture
+    def parsed(self):
+        return RubyAdapter().parse_file(SAMPLE_RB, build_index=True)
+
+    def test_node_and_edge_count(self, parsed):
+        nodes, relations = parsed
+        # File, Person, its 4 methods and the top-level add
+        assert len(nodes) == 7
+        assert node_names(nodes, "Class") == {"Person"}
+        assert node_names(nodes, "Method") == {"initialize", "from_json", "greet", "adult?"}
+        assert node_names(nodes, "Function") == {"add"}
+        assert len(relations) == 6
+        assert edge_names(nodes, relations, "CONTAINS") == {("sample.rb", "Person"), ("sample.rb", "add")}
+        assert edge_names(nodes, relations, "DEFINES") == {
+            ("Person", "initialize"), ("Person", "from_json"), ("Person", "greet"), ("Person", "adult?")}
+
+    def test_instance_and_class_methods(self, parsed):
+        nodes, _ = parsed
+        kinds = {n.name: n.properties["method_kind"] for n in nodes.values() if n.node_type == "Method"}
+        assert kinds == {"initialize": "instance", "from_json": "class", "greet": "instance", "adult?": "instance"}
+        assert find_node(nodes, "Method", "greet").properties["method_of"] == "Person"
+
+    def test_method_properties(self, parsed):
+        nodes, _ = parsed
+        initialize = find_node(nodes, "Method", "initialize")
+        assert json.loads(initialize.properties["args"]) == [
+            {"name": "name", "kind": "required"}, {"name": "age", "kind": "optional"}]
+        assert initialize.properties["visibility"] == "Private"
+        assert find_node(nodes, "Method", "greet").properties["visibility"] == "Public"
+        assert find_node(nodes, "Method", "adult?").properties["complexity"] == 2
+        assert (initialize.line_no, initialize.end_line_no) == (7, 10)
+
+    def test_require_is_pending(self):
+        adapter = RubyAdapter()
+        adapter.parse_file(SAMPLE_RB, build_index=True)
+        assert [(p["type"], p["path"], p["relative"], p["line_no"]) for p in adapter.pending_imports] == [
+            ("IMPORTS_RUBY", "json", False, 2)]
+
+    def test_visibility_keywords(self):
+        source = ("class Account\n  def balance; end\n\n  protected\n\n  def ledger; end\n\n"
+                  "  private\n\n  def audit; end\n  public def summary; end\n  def token; end\n"
+                  "  public :token\nend\n")
+        nodes, _ = RubyAdapter().parse_source(source, "account.rb")
+        visibility = {n.name: n.properties["visibility"] for n in nodes.values() if n.node_type == "Method"}
+        assert visibility == {"balance": "Public", "ledger": "Protected", "audit": "Private",
+                              "summary": "Public", "token": "Public"}
+
+    def test_nested_modules(self):
+        source = "module Billing\n  module Tax\n    class Rate\n    end\n  end\nend\nclass Billing::Invoice\nend\n"
+        nodes, relations = RubyAdapter().parse_source(source, "billing.rb")
+        assert node_names(nodes, "Module") == {"Billing", "Tax"}
+        assert edge_names(nodes, relations, "DEFINES") == {("Billing", "Tax"), ("Tax", "Rate")}
+        assert edge_names(nodes, relations, "CONTAINS") == {("billing.rb", "Billing"), ("billing.rb", "Invoice")}
+        assert find_node(nodes, "Class", "Invoice").properties["namespace"] == "Billing"
+
+
+class TestInflections:
+    """Model names derived from association names."""
+
+    def test_singularize(self):
+        assert [singularize(n) for n in ("posts", "categories", "addresses", "boxes", "branches",
+                                         "people", "line_items")] == [
+            "post", "category", "address", "box", "branch", "person", "line_item"]
+
+    def test_camelize_and_underscore(self):
+        assert camelize("line_item") == "LineItem"
+        assert underscore("ApplicationRecord") == "application_record"
+        assert underscore("HTTPClient") == "http_client"
+
+
+class TestRailsSample:
+    """The Rails-like application in fixtures/ruby_sample."""
+
+    @pytest.fixture
+    def parsed(self):
+        return _parse_directory(RUBY_SAMPLE_DIR)
+
+    def test_node_count(self, parsed):
+        nodes, _ = parsed
+        # 6 files, 6 classes, the Slugs module and 7 methods
+        assert len(nodes) == 20
+        assert node_names(nodes, "Class") == {"ApplicationRecord", "User", "Post", "Comment", "Category", "Formatter"}
+        assert node_names(nodes, "Module") == {"Slugs"}
+
+    def test_class_methods(self, parsed):
+        nodes, _ = parsed
+        kinds = {n.name: (n.properties["method_kind"], n.properties["visibility"])
+                 for n in nodes.values() if n.node_type == "Method"}
+        assert kinds == {
+            "by_email": ("class", "Public"), "gravatar": ("instance", "Public"),
+            "normalize_email": ("instance", "Private"), "published": ("class", "Public"),
+            "slug": ("instance", "Public"), "draft?": ("instance", "Private"), "format": ("class", "Public"),
+        }
+
+    def test_models_extend_application_record(self, parsed):
+        nodes, relations = parsed
+        assert edge_names(nodes, relations, "EXTENDS") == {
+            ("User", "ApplicationRecord"), ("Post", "ApplicationRecord"),
+            ("Comment", "ApplicationRecord"), ("Category", "ApplicationRecord")}
+
+    def test_requires(self, parsed):
+        nodes, relations = parsed
+        imports = [r for r in relations if r.relation_type == "IMPORTS"]
+        assert {(nodes[r.target_id].name, r.properties["require"]) for r in imports} == {
+            ("application_record.rb", "require_relative"), ("formatter.rb", "require")}
+        assert {r.properties["path"] for r in imports} == {"application_record", "slugs/formatter"}
+
+    def test_associations(self, parsed):
+        nodes, relations = parsed
+        associations = {(nodes[r.source_id].name, r.properties["association"], r.properties["name"],
+                         nodes[r.target_id].name)
+                        for r in relations if r.relation_type == "AR_ASSOCIATION"}
+        # has_one :profile names no model of the application, and polymorphic associations none at all
+        assert associations == {
+            ("User", "has_many", "posts", "Post"),
+            ("User", "has_many", "comments", "Comment"),
+            ("User", "has_many", "categories", "Category"),
+            ("Post", "belongs_to", "user", "User"),
+            ("Post", "belongs_to", "author", "User"),
+            ("Post", "has_many", "comments", "Comment"),
+            ("Post", "has_and_belongs_to_many", "categories", "Category"),
+            ("Category", "has_and_belongs_to_many", "posts", "Post"),
+        }
+        through = next(r for r in relations if r.relation_type == "AR_ASSOCIATION"
+                       and r.properties["name"] == "categories" and nodes[r.source_id].name == "User")
+        assert (through.properties["through"], through.properties["class_name"]) == ("posts", "Category")
+
+
+class TestRailsGraph:
+    """Placeholders the graph adds for what the application does not define."""
+
+    @pytest.fixture
+    def graph(self):
+        graph = CodeGraph(use_ast_grep=True, ast_grep_languages=['ruby'], ast_grep_fallback=False)
+        for root, _, files in os.walk(RUBY_SAMPLE_DIR):
+            for name in sorted(files):
+                graph.add_file(os.path.join(root, name))
+        return graph
+
+    def test_edge_counts(self, graph):
+        counts = {}
+        for relation in graph.relations:
+            counts[relation.relation_type] = counts.get(relation.relation_type, 0) + 1
+        # 3 requires, 1 of them to a gem; 10 associations, 2 of them unresolved
+        assert (counts["IMPORTS"], counts["AR_ASSOCIATION"], counts["EXTENDS"]) == (3, 10, 4)
+
+    def test_unresolved_placeholders(self, graph):
+        unresolved = {(r.relation_type, r.target_id[len(UNRESOLVED_PREFIX):]): r.properties
+                      for r in graph.relations if r.target_id.startswith(UNRESOLVED_PREFIX)}
+        assert set(unresolved) == {("IMPORTS", "digest"), ("AR_ASSOCIATION", "Profile"),
+                                   ("AR_ASSOCIATION", "Commentable")}
+        assert unresolved[("AR_ASSOCIATION", "Commentable")]["polymorphic"] is True
+        assert unresolved[("IMPORTS", "digest")]["require"] == "require"
+
+    def test_association_follows_a_new_model(self, graph, tmp_path):
+        profile = tmp_path / "profile.rb"
+        profile.write_text("class Profile < ApplicationRecord\n  belongs_to :user\nend\n", encoding="utf-8")
+        graph.add_file(str(profile))
+        targets = {graph.nodes[r.target_id].name for r in graph.relations
+                   if r.relation_type == "AR_ASSOCIATION" and r.properties["name"] in ("profile", "user")}
+        assert targets == {"Profile", "User"}
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
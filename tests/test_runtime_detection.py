@@ -3,6 +3,7 @@ Unit tests for runtime detection utilities.
 """
 
 import os
+import sys
 import pytest
 from unittest.mock import patch
 from src.utils.runtime_detection import (
@@ -12,6 +13,7 @@ from src.utils.runtime_detection import (
     get_optimal_worker_count,
     get_runtime_info,
     check_gil_reenablement,
+    peak_memory_mb,
 )
 
 
@@ -170,6 +172,15 @@ class TestRuntimeDetectionMocked:
         result = get_optimal_worker_count()
         # Should fall back to 4
         assert result == 4
+    
+    def test_peak_memory_mb(self):
+        """Test that peak memory is reported in megabytes where the platform allows."""
+        peak = peak_memory_mb()
+        if sys.platform == "win32":
+            assert peak is None
+        else:
+            # A Python process holds at least a few megabytes, and surely not a terabyte
+            assert 1 < peak < 1024 * 1024
 
 
 if __name__ == '__main__':
@@ -0,0 +1,1046 @@
+"""
+Tests for RustAdapter impl block handling.
+
+Covers inherent impls (methods attached to their struct), trait impls
+(IMPLEMENTS edges from types to traits and from impl methods to the trait
+methods they provide), impl blocks that live in a different file from the
+type or trait they refer to, self.field accesses, calls through paths
+and receivers, use declarations resolved against the crate's module tree,
+calls through those imports linked to the definitions in other files, and
+the visibility recorded from pub modifiers and private items left out on
+request, the cyclomatic complexity of
+functions, the doc comments in front of items, the fields of structs and
+enum variants with the USES_TYPE edges to the types they name, supertraits
+and generic bounds with the type hierarchy they give, crates
+of a workspace holding files at the same relative paths, async functions
+and awaited calls, and derives, macro_rules! definitions and the symbols
+named in macro arguments.
+"""
+
+import os
+import sys
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+pytest.importorskip("ast_grep_py")
+
+from src.ast_parser.multi_parser import MultiLanguageParser
+from src.ast_parser.adapters.rust_adapter import RustAdapter, crate_module_path, expand_use_tree
+from src.graph.code_graph import CodeGraph
+from src.graph.dead_code import find_unused
+from src.graph.outline import build_outline
+from src.graph.type_hierarchy import type_hierarchy
+from tests.helpers import find_node
+
+
+FIXTURES = os.path.abspath(os.path.join(os.path.dirname(__file__), "fixtures"))
+SAMPLE_RS = os.path.join(FIXTURES, "multi_lang_sample", "sample.rs")
+RUST_SAMPLE_DIR = os.path.join(FIXTURES, "rust_sample")
+RUST_CRATE_SRC = os.path.join(FIXTURES, "rust_crate", "src")
+RUST_WORKSPACE = os.path.join(FIXTURES, "rust_workspace")
+
+
+def _edges(relations, relation_type):
+    return {(r.source_id, r.target_id) for r in relations if r.relation_type == relation_type}
+
+
+class TestInherentImpl:
+    """impl Person { ... } in the multi-language sample."""
+
+    @pytest.fixture
+    def parsed(self):
+        return RustAdapter().parse_file(SAMPLE_RS, build_index=True)
+
+    def test_methods_defined_by_struct(self, parsed):
+        nodes, relations = parsed
+        person = find_node(nodes, "Class", "Person")
+        defines = _edges(relations, "DEFINES")
+        methods = {nodes[t].name for s, t in defines
+                   if s == person.node_id and nodes[t].node_type == "Method"}
+        assert methods == {"new", "get_name", "set_name", "get_age"}
+
+    def test_methods_are_not_free_functions(self, parsed):
+        nodes, _ = parsed
+        functions = {n.name for n in nodes.values() if n.node_type == "Function"}
+        assert functions == {"greet", "add"}
+
+    def test_method_of_property(self, parsed):
+        nodes, _ = parsed
+        for method in (n for n in nodes.values() if n.node_type == "Method"):
+            assert method.properties["method_of"] == "Person"
+            assert "impl_trait" not in method.properties
+
+
+class TestTraitImpls:
+    """Trait declarations and impl blocks spread across rust_sample/."""
+
+    @pytest.fixture
+    def parsed(self):
+        parser = MultiLanguageParser(
+            use_ast_grep=True,
+            ast_grep_languages=['rust'],
+            ast_grep_fallback=False
+        )
+        return parser.parse_directory(RUST_SAMPLE_DIR, build_index=True)
+
+    def test_trait_node_and_declared_methods(self, parsed):
+        nodes, relations = parsed
+        shape = find_node(nodes, "Interface", "Shape")
+        declared = {nodes[t].name: nodes[t] for s, t in _edges(relations, "DEFINES") if s == shape.node_id}
+        assert set(declared) == {"area", "describe"}
+        assert declared["area"].properties["has_default"] is False
+        assert declared["describe"].properties["has_default"] is True
+
+    def test_in_file_trait_impl(self, parsed):
+        nodes, relations = parsed
+        circle = find_node(nodes, "Class", "Circle")
+        shape = find_node(nodes, "Interface", "Shape")
+        assert (circle.node_id, shape.node_id) in _edges(relations, "IMPLEMENTS")
+
+    def test_cross_file_trait_impl(self, parsed):
+        nodes, relations = parsed
+        square = find_node(nodes, "Class", "Square")
+        shape = find_node(nodes, "Interface", "Shape")
+        assert (square.node_id, shape.node_id) in _edges(relations, "IMPLEMENTS")
+
+    def test_external_trait_left_unlinked(self, parsed):
+        nodes, relations = parsed
+        circle = find_node(nodes, "Class", "Circle")
+        targets = {t for s, t in _edges(relations, "IMPLEMENTS") if s == circle.node_id}
+        assert len(targets) == 1
+        fmt_method = find_node(nodes, "Method", "fmt")
+        assert fmt_method.properties["impl_trait"] == "Display"
+
+    def test_impl_methods_implement_trait_methods(self, parsed):
+        nodes, relations = parsed
+        shape_area = find_node(nodes, "Method", "area", "shapes.rs")
+        implementing = {(nodes[s].name, os.path.basename(nodes[s].file_path))
+                        for s, t in _edges(relations, "IMPLEMENTS") if t == shape_area.node_id}
+        # Circle's impl sits next to the trait, Square's is in another file
+        assert implementing == {("area", "shapes.rs"), ("area", "square.rs")}
+        implements = [r for r in relations if r.relation_type == "IMPLEMENTS"
+                      and r.target_id == shape_area.node_id]
+        assert sorted(r.properties["type"] for r in implements) == ["Circle", "Square"]
+
+    def test_external_trait_becomes_unresolved_in_code_graph(self):
+        graph = CodeGraph.from_directory(RUST_SAMPLE_DIR, use_ast_grep=True,
+                                         ast_grep_languages=['rust'], ast_grep_fallback=False)
+        circle = find_node(graph.nodes, "Class", "Circle")
+        targets = {graph.nodes[r.target_id].name: graph.nodes[r.target_id].node_type
+                   for r in graph.relations
+                   if r.relation_type == "IMPLEMENTS" and r.source_id == circle.node_id}
+        assert targets == {"Shape": "Interface", "fmt::Display": "Unresolved"}
+
+    def test_impl_blocks_in_other_files_attach_to_one_struct(self, parsed):
+        nodes, relations = parsed
+        circle = find_node(nodes, "Class", "Circle")
+        methods = {(nodes[t].name, os.path.basename(nodes[t].file_path))
+                   for s, t in _edges(relations, "DEFINES")
+                   if s == circle.node_id and nodes[t].node_type == "Method"}
+        assert methods == {
+            ("area", "shapes.rs"),
+            ("fmt", "shapes.rs"),
+            ("new", "circle_ext.rs"),
+            ("unit", "circle_ext.rs"),
+            ("diameter", "circle_ext.rs"),
+            ("circumference", "circle_ext.rs"),
+        }
+
+    def test_trait_impl_methods_carry_trait(self, parsed):
+        nodes, _ = parsed
+        area = find_node(nodes, "Method", "area", "square.rs")
+        assert area.properties == {"method_of": "Square", "impl_trait": "Shape", "visibility": "Public",
+                                   "complexity": 1, "is_async": False, "column": 5, "end_column": 6}
+
+    def test_blanket_impl(self):
+        source = """
+use std::fmt::Display;
+
+pub trait Summary {
+    fn summarize(&self) -> String;
+}
+
+impl<T: Display + ?Sized> Summary for T
+where
+    T: Send,
+{
+    fn summarize(&self) -> String { self.to_string() }
+}
+
+impl<T> Clone for Wrapper<T> {}
+"""
+        nodes, relations = RustAdapter().parse_source(source, "summary.rs")
+        summary = find_node(nodes, "Interface", "Summary")
+        blanket, = [r for r in relations if r.relation_type == "IMPLEMENTS" and r.target_id == summary.node_id]
+        # No type node to start from, so the edge leaves the file
+        assert blanket.source_id == "file:summary.rs"
+        assert blanket.properties == {"trait_path": "Summary", "blanket": True, "for_type": "T",
+                                      "bounds": "<T: Display + ?Sized> where\n    T: Send,", "line_no": 8}
+        # Its methods still implement the trait's, and belong to the file
+        provided = next(n for n in nodes.values() if n.properties.get("impl_trait") == "Summary")
+        declared = next(n for n in nodes.values() if n.name == "summarize" and n is not provided)
+        assert (provided.node_id, declared.node_id) in _edges(relations, "IMPLEMENTS")
+        assert ("file:summary.rs", provided.node_id) in _edges(relations, "CONTAINS")
+        # A generic impl for a named type is not a blanket impl
+        assert not any(r.properties.get("blanket") for r in relations if r.target_id != summary.node_id)
+
+
+def _accesses(nodes, relations, field):
+    """(accessor name, access) pairs for REFERENCES edges into a field node."""
+    return {(nodes[r.source_id].name, r.properties["access"])
+            for r in relations
+            if r.relation_type == "REFERENCES" and r.target_id == field.node_id}
+
+
+class TestFieldAccess:
+    """self.field reads and writes classified by syntactic context."""
+
+    def test_struct_fields_are_nodes(self):
+        nodes, relations = RustAdapter().parse_file(SAMPLE_RS, build_index=True)
+        person = find_node(nodes, "Class", "Person")
+        fields = {nodes[t].name: nodes[t] for s, t in _edges(relations, "DEFINES")
+                  if s == person.node_id and nodes[t].node_type == "Field"}
+        assert set(fields) == {"name", "age"}
+        assert fields["name"].properties == {"field_of": "Person", "type": "String", "visibility": "Private",
+                                             "column": 5, "end_column": 17}
+        assert (fields["name"].line_no, fields["name"].end_line_no) == (5, 5)
+
+    def test_read_and_write(self):
+        nodes, relations = RustAdapter().parse_file(SAMPLE_RS, build_index=True)
+        name = find_node(nodes, "Field", "name")
+        # new() builds the struct with shorthand fields, which is not an access
+        assert _accesses(nodes, relations, name) == {("get_name", "Read"), ("set_name", "Write")}
+        age = find_node(nodes, "Field", "age")
+        assert _accesses(nodes, relations, age) == {("get_age", "Read")}
+
+    def test_compound_assignment_and_cross_file_struct(self):
+        parser = MultiLanguageParser(
+            use_ast_grep=True,
+            ast_grep_languages=['rust'],
+            ast_grep_fallback=False
+        )
+        nodes, relations = parser.parse_directory(RUST_SAMPLE_DIR, build_index=True)
+
+        side = find_node(nodes, "Field", "side")
+        assert _accesses(nodes, relations, side) == {("area", "Read"), ("grow", "ReadWrite")}
+        # diameter() lives in circle_ext.rs, the field in shapes.rs
+        radius = find_node(nodes, "Field", "radius")
+        accessors = {(nodes[r.source_id].name, os.path.basename(nodes[r.source_id].file_path))
+                     for r in relations
+                     if r.relation_type == "REFERENCES" and r.target_id == radius.node_id}
+        assert accessors == {("area", "shapes.rs"), ("fmt", "shapes.rs"), ("diameter", "circle_ext.rs")}
+
+    def test_method_receivers(self):
+        source = ("struct Counter {\n    hits: u32,\n}\n\nimpl Counter {\n"
+                  "    fn bump(&mut self) {\n        self.hits += 1;\n    }\n\n"
+                  "    fn total(&self) -> u32 {\n        self.hits\n    }\n}\n\n"
+                  "struct Stack {\n    items: Vec<i32>,\n    name: String,\n    counter: Counter,\n}\n\n"
+                  "impl Stack {\n"
+                  "    fn push(&mut self, x: i32) {\n        self.items.push(x);\n    }\n\n"
+                  "    fn size(&self) -> usize {\n        self.items.len()\n    }\n\n"
+                  "    fn label(&self) -> String {\n        self.name.clone()\n    }\n\n"
+                  "    fn reset_top(&mut self) {\n        self.items[0].clear();\n    }\n\n"
+                  "    fn record(&mut self) {\n        self.counter.bump();\n    }\n\n"
+                  "    fn seen(&self) -> u32 {\n        self.counter.total()\n    }\n}\n")
+        nodes, relations = RustAdapter().parse_source(source, "stack.rs")
+        # Methods of std types are not indexed, so whether they write is not known
+        assert _accesses(nodes, relations, find_node(nodes, "Field", "items")) == {
+            ("push", "Unknown"), ("size", "Unknown"), ("reset_top", "Unknown")}
+        assert _accesses(nodes, relations, find_node(nodes, "Field", "name")) == {("label", "Unknown")}
+        # Counter::bump takes &mut self, Counter::total &self
+        assert _accesses(nodes, relations, find_node(nodes, "Field", "counter")) == {
+            ("record", "ReadWrite"), ("seen", "Read")}
+
+    def test_method_calls_on_self_are_not_field_accesses(self):
+        parser = MultiLanguageParser(
+            use_ast_grep=True,
+            ast_grep_languages=['rust'],
+            ast_grep_fallback=False
+        )
+        nodes, relations = parser.parse_directory(RUST_SAMPLE_DIR, build_index=True)
+        # describe() calls self.area(); no field is named area
+        describe = find_node(nodes, "Method", "describe")
+        assert not [r for r in relations
+                    if r.relation_type == "REFERENCES" and r.source_id == describe.node_id]
+
+
+class TestCalls:
+    """CALLS edges attributed to the enclosing function or method."""
+
+    @pytest.fixture
+    def parsed(self):
+        parser = MultiLanguageParser(
+            use_ast_grep=True,
+            ast_grep_languages=['rust'],
+            ast_grep_fallback=False
+        )
+        return parser.parse_directory(RUST_SAMPLE_DIR, build_index=True)
+
+    @staticmethod
+    def _calls(nodes, relations):
+        return {(nodes[r.source_id].name, nodes[r.target_id].name,
+                 os.path.basename(nodes[r.target_id].file_path))
+                for r in relations
+                if r.relation_type == "CALLS" and r.target_id in nodes and r.source_id in nodes}
+
+    def test_plain_calls_in_functions(self, parsed):
+        calls = self._calls(*parsed)
+        assert ("run", "total_area", "app.rs") in calls
+        # the closure body counts as part of run()
+        assert ("run", "helper", "app.rs") in calls
+
+    def test_calls_in_impl_methods_attach_to_the_method(self, parsed):
+        nodes, relations = parsed
+        calls = self._calls(nodes, relations)
+        assert ("unit", "new", "circle_ext.rs") in calls
+        assert ("circumference", "diameter", "circle_ext.rs") in calls
+        impl_level = [r for r in relations if r.relation_type == "CALLS"
+                      and nodes.get(r.source_id) is not None
+                      and nodes[r.source_id].node_type not in ("Function", "Method")]
+        assert impl_level == []
+
+    def test_associated_function_in_other_file(self, parsed):
+        nodes, relations = parsed
+        new_call = [r for r in relations if r.relation_type == "CALLS"
+                    and nodes[r.source_id].name == "run" and nodes[r.target_id].name == "new"]
+        assert len(new_call) == 1
+        assert os.path.basename(nodes[new_call[0].target_id].file_path) == "circle_ext.rs"
+        assert new_call[0].properties["call_site"] == "let circle = Circle::new(2.0);"
+        assert new_call[0].properties["line_no"] == 9
+
+    def test_module_path_call(self, parsed):
+        # greeting::greet(&owned) in welcome.rs
+        assert ("welcome", "greet", "greeting.rs") in self._calls(*parsed)
+
+    def test_method_calls_on_local_variables(self, parsed):
+        calls = self._calls(*parsed)
+        # let greeter = Greeter::new(); greeter.salute(name)
+        assert ("welcome", "salute", "greeting.rs") in calls
+        # greeter: &Greeter parameter
+        assert ("welcome_back", "salute", "greeting.rs") in calls
+        # let circle = Circle::new(2.0); circle.diameter()
+        assert ("run", "diameter", "circle_ext.rs") in calls
+
+    def test_unindexed_targets_become_unresolved(self):
+        graph = CodeGraph.from_directory(RUST_SAMPLE_DIR, use_ast_grep=True,
+                                         ast_grep_languages=['rust'], ast_grep_fallback=False)
+        welcome = find_node(graph.nodes, "Function", "welcome")
+        unresolved = {graph.nodes[r.target_id].name for r in graph.relations
+                      if r.relation_type == "CALLS" and r.source_id == welcome.node_id
+                      and graph.nodes[r.target_id].node_type == "Unresolved"}
+        assert unresolved == {"String::from", "owned.to_uppercase"}
+
+
+class TestUseDeclarations:
+    """use paths in rust_crate/, a lib.rs crate with mod.rs and inline modules."""
+
+    @pytest.fixture(scope="class")
+    def graph(self):
+        return CodeGraph.from_directory(RUST_CRATE_SRC, use_ast_grep=True,
+                                        ast_grep_languages=['rust'], ast_grep_fallback=False)
+
+    @staticmethod
+    def _imports(graph, file_name):
+        return {(r.properties["path"], graph.nodes[r.target_id].node_type, graph.nodes[r.target_id].name)
+                for r in graph.relations
+                if r.relation_type == "IMPORTS" and r.source_id.endswith(os.sep + file_name)}
+
+    def test_expand_use_tree(self):
+        assert expand_use_tree("crate::a::{self, B as C, d::{e, *}}") == [
+            (["crate", "a"], None, False),
+            (["crate", "a", "B"], "C", False),
+            (["crate", "a", "d", "e"], None, False),
+            (["crate", "a", "d"], None, True),
+        ]
+
+    def test_module_paths(self):
+        assert [crate_module_path(os.path.join(RUST_CRATE_SRC, *p)) for p in (
+            ("lib.rs",), ("person.rs",), ("geometry", "mod.rs"), ("geometry", "shapes.rs"),
+        )] == ["crate", "crate::person", "crate::geometry", "crate::geometry::shapes"]
+        # No crate root around the flat sample: siblings are modules of the crate
+        assert crate_module_path(os.path.join(RUST_SAMPLE_DIR, "shapes.rs")) == "crate::shapes"
+
+    def test_crate_rename_and_glob(self, graph):
+        assert self._imports(graph, "lib.rs") == {
+            ("geometry::shapes", "File", "shapes.rs"),
+            ("crate::person::Person", "Class", "Person"),
+            ("std::fmt", "Unresolved", "std::fmt"),
+        }
+        glob, = [r for r in graph.relations if r.relation_type == "IMPORTS" and r.properties.get("glob")]
+        assert glob.properties["module"] == "crate::geometry::shapes"
+        renamed, = [r for r in graph.relations if r.properties.get("alias") == "Human"]
+        assert renamed.properties["path"] == "crate::person::Person"
+
+    def test_super_group_and_self(self, graph):
+        assert self._imports(graph, "mod.rs") == {
+            ("super::person", "File", "person.rs"),
+            ("super::person::Person", "Class", "Person"),
+            ("super::person::adult", "Function", "adult"),
+        }
+
+    def test_inline_module_and_missing_item(self, graph):
+        imports = self._imports(graph, "shapes.rs")
+        assert ("self::corners::Corner", "Class", "Corner") in imports
+        assert ("self::corners::Missing", "Unresolved", "self::corners::Missing") in imports
+
+    def test_external_crates_are_tagged(self, graph):
+        external = [r for r in graph.relations if r.relation_type == "IMPORTS" and r.properties.get("unresolved")
+                    and r.properties.get("external_crate")]
+        assert [(r.properties["external_crate"], r.properties["raw_name"]) for r in external] == [("std", "std::fmt")]
+
+    @staticmethod
+    def _calls_from(graph, caller):
+        source = find_node(graph.nodes, "Function", caller)
+        return [r for r in graph.relations if r.relation_type == "CALLS" and r.source_id == source.node_id]
+
+    def test_glob_import_call_targets_the_definition(self, graph):
+        # perimeter(square) in lib.rs, brought in by `pub use geometry::shapes::*`
+        call, = self._calls_from(graph, "fence")
+        assert call.target_id == find_node(graph.nodes, "Function", "perimeter").node_id
+        assert graph.nodes[call.target_id].file_path.endswith(os.path.join("geometry", "shapes.rs"))
+
+    def test_renamed_and_module_path_calls(self, graph):
+        # is_adult(age) and person::adult(age) in geometry/mod.rs both reach person.rs
+        adult = find_node(graph.nodes, "Function", "adult")
+        calls = self._calls_from(graph, "can_vote")
+        assert [r.target_id for r in calls] == [adult.node_id, adult.node_id]
+        assert {r.properties["path"] for r in calls} == {"super::person::adult"}
+
+    def test_crate_path_call(self, graph):
+        call, = self._calls_from(graph, "voting_age")
+        assert call.target_id == find_node(graph.nodes, "Function", "can_vote").node_id
+        assert call.properties["path"] == "crate::geometry::can_vote"
+
+    def test_external_call_stays_unresolved_with_its_path(self, graph):
+        call, = self._calls_from(graph, "render")
+        assert graph.nodes[call.target_id].node_type == "Unresolved"
+        assert call.properties["unresolved"] is True
+        assert call.properties["raw_name"] == "std::fmt::format"
+
+
+
+class TestWorkspace:
+    """rust_workspace/: app and core-lib, and cli in a workspace nested in the first."""
+
+    @pytest.fixture(scope="class")
+    def graph(self):
+        return CodeGraph.from_directory(RUST_WORKSPACE, use_ast_grep=True,
+                                        ast_grep_languages=['rust'], ast_grep_fallback=False)
+
+    @staticmethod
+    def _file(graph, *parts):
+        return graph.nodes[f"file:{os.path.join(RUST_WORKSPACE, *parts)}"]
+
+    def test_members_and_qualified_modules(self, graph):
+        files = [("core", "src", "lib.rs"), ("core", "src", "person.rs"), ("tools", "cli", "src", "person.rs")]
+        assert [(self._file(graph, *f).properties["workspace_member"], self._file(graph, *f).properties["module_path"],
+                 self._file(graph, *f).properties["qualified_module"]) for f in files] == [
+            ("core-lib", "crate", "core_lib"),
+            ("core-lib", "crate::person", "core_lib::person"),
+            ("cli", "crate::person", "cli::person"),
+        ]
+
+    def test_crate_paths_stay_in_their_crate(self, graph):
+        lib = self._file(graph, "tools", "cli", "src", "lib.rs")
+        person, = [graph.nodes[r.target_id] for r in graph.relations
+                   if r.relation_type == "IMPORTS" and r.source_id == lib.node_id
+                   and r.properties["path"] == "crate::person::Person"]
+        assert person.file_path == os.path.join(RUST_WORKSPACE, "tools", "cli", "src", "person.rs")
+
+    def test_paths_into_another_member(self, graph):
+        main = self._file(graph, "app", "src", "main.rs")
+        imported = {graph.nodes[r.target_id].file_path for r in graph.relations
+                    if r.relation_type == "IMPORTS" and r.source_id == main.node_id}
+        assert imported == {os.path.join(RUST_WORKSPACE, "core", "src", "person.rs")}
+
+        caller = find_node(graph.nodes, "Function", "main")
+        version, = [graph.nodes[r.target_id] for r in graph.relations
+                    if r.relation_type == "CALLS" and r.source_id == caller.node_id
+                    and r.properties.get("path") == "core_lib::version"]
+        assert version.file_path == os.path.join(RUST_WORKSPACE, "core", "src", "lib.rs")
+
+
+class TestPositions:
+    """Spans recorded for sample.rs and looked up by position."""
+
+    @pytest.fixture(scope="class")
+    def graph(self):
+        graph = CodeGraph(use_ast_grep=True, ast_grep_languages=['rust'], ast_grep_fallback=False)
+        graph.add_file(SAMPLE_RS)
+        return graph
+
+    def test_struct_span(self, graph):
+        person = find_node(graph.nodes, "Class", "Person")
+        assert (person.line_no, person.end_line_no) == (4, 7)
+        assert (person.properties["column"], person.properties["end_column"]) == (1, 2)
+
+    def test_symbol_at_position(self, graph):
+        # On the name in `pub struct Person {`
+        assert graph.node_at(SAMPLE_RS, 4, 12) == find_node(graph.nodes, "Class", "Person").node_id
+        # `&self.name` in the body of get_name
+        assert graph.node_at(SAMPLE_RS, 15, 9) == find_node(graph.nodes, "Method", "get_name").node_id
+        # A field, and the indentation ahead of it, which is only inside the struct
+        name = find_node(graph.nodes, "Field", "name").node_id
+        assert graph.nodes_at(SAMPLE_RS, 5, 5) == [name, find_node(graph.nodes, "Class", "Person").node_id]
+        assert graph.node_at(SAMPLE_RS, 5, 2) == find_node(graph.nodes, "Class", "Person").node_id
+        # Between items
+        assert graph.node_at(SAMPLE_RS, 8, 1) is None
+
+    def test_utf16_columns_after_non_ascii_text(self):
+        source = 'struct Café; /* 😀 */ struct Point { x: i32 }\n'
+        nodes, _ = RustAdapter().parse_source(source, "accents.rs")
+        point = find_node(nodes, "Class", "Point")
+        # "é" takes one more byte than characters, the emoji three more bytes and one more UTF-16 unit
+        assert point.span.columns() == (22, 45)
+        assert point.span.utf16() == (23, 46)
+        assert point.span.utf8() == (26, 49)
+        assert find_node(nodes, "Class", "Café").span.utf16() == (1, 13)
+
+
+VISIBILITY_RS = """
+pub struct Account {
+    pub owner: String,
+    pub(crate) balance: u64,
+    pin: u16,
+}
+
+pub(crate) trait Audit {
+    fn audit(&self);
+}
+
+impl Account {
+    pub fn deposit(&mut self, amount: u64) {}
+    pub(super) fn reset(&mut self) {}
+    pub(self) fn check_pin(&self) -> bool { true }
+    fn log(&self) {}
+}
+
+impl Audit for Account {
+    fn audit(&self) {}
+}
+
+impl Clone for Account {
+    fn clone(&self) -> Self { todo!() }
+}
+
+pub fn open() -> Account { todo!() }
+fn helper() {}
+"""
+
+
+class TestVisibility:
+    """pub modifiers mapped to Public, Crate and Private."""
+
+    @pytest.fixture(scope="class")
+    def nodes(self):
+        nodes, _ = RustAdapter().parse_source(VISIBILITY_RS, "bank.rs")
+        return {(n.node_type, n.name): n.properties.get("visibility") for n in nodes.values()}
+
+    def test_items(self, nodes):
+        assert nodes[("Class", "Account")] == "Public"
+        assert nodes[("Interface", "Audit")] == "Crate"
+        assert (nodes[("Function", "open")], nodes[("Function", "helper")]) == ("Public", "Private")
+
+    def test_fields(self, nodes):
+        assert [nodes[("Field", f)] for f in ("owner", "balance", "pin")] == ["Public", "Crate", "Private"]
+
+    def test_inherent_methods(self, nodes):
+        assert [nodes[("Method", m)] for m in ("deposit", "reset", "check_pin", "log")] == \
+            ["Public", "Crate", "Private", "Private"]
+
+    def test_trait_methods_follow_the_trait(self, nodes):
+        # The declaration and the impl of Audit::audit share a key; both are Crate
+        assert nodes[("Method", "audit")] == "Crate"
+        # A foreign trait is reachable from anywhere
+        assert nodes[("Method", "clone")] == "Public"
+
+
+class TestIndexPrivate:
+    """index_private=False leaves private items out of the graph."""
+
+    def test_private_items_are_dropped(self):
+        nodes, relations = RustAdapter(index_private=False).parse_source(VISIBILITY_RS, "bank.rs")
+        names = {(n.node_type, n.name) for n in nodes.values() if n.node_type in ("Function", "Method")}
+        assert names == {("Function", "open"), ("Method", "deposit"), ("Method", "reset"),
+                         ("Method", "audit"), ("Method", "clone")}
+        # Fields are not items and stay, whatever their visibility
+        assert ("Field", "pin") in {(n.node_type, n.name) for n in nodes.values()}
+        assert all(r.source_id in nodes and r.target_id in nodes for r in relations
+                   if r.relation_type in ("CONTAINS", "DEFINES"))
+
+    def test_members_of_a_private_type_go_with_it(self):
+        adapter = RustAdapter(index_private=False)
+        nodes, _ = adapter.parse_source("struct Cache { pub size: u32 }\n"
+                                        "impl Cache { pub fn get(&self) { load(); } }\n", "cache.rs",
+                                        build_index=True)
+        assert [n.node_type for n in nodes.values()] == ["File"]
+        assert not [e for e in adapter.pending_imports if e["type"] != "IMPORTS_RUST"]
+        assert not any("Cache" in definitions for definitions in adapter.module_definitions.values())
+
+
+COMPLEXITY_RS = """
+fn straight() -> u32 { 1 }
+
+fn classify(n: i32, strict: bool) -> &'static str {
+    if n < 0 && strict {
+        "negative"
+    } else if n == 0 || !strict {
+        "zero"
+    } else {
+        match n {
+            1 => "one",
+            2 | 3 => "few",
+            _ => "many",
+        }
+    }
+}
+
+fn total(path: &str) -> Result<u32, std::io::Error> {
+    let text = std::fs::read_to_string(path)?;
+    let mut sum = 0;
+    for line in text.lines() {
+        while sum > 100 {
+            sum -= 1;
+        }
+        let add = |x: u32| if x > 1 { x } else { 0 };
+        sum += add(line.len() as u32);
+    }
+    fn nested(a: bool) -> bool { a && a }
+    Ok(sum)
+}
+
+trait Check {
+    fn required(&self) -> bool;
+    fn provided(&self) -> bool { if self.required() { true } else { false } }
+}
+
+struct Gate;
+
+impl Gate {
+    fn open(&self, key: Option<u8>) -> bool {
+        match key { Some(k) if k > 3 => true, _ => false }
+    }
+}
+"""
+
+
+class TestComplexity:
+    """Decision points counted into the complexity property."""
+
+    @pytest.fixture(scope="class")
+    def nodes(self):
+        nodes, _ = RustAdapter().parse_source(COMPLEXITY_RS, "complexity.rs")
+        return nodes
+
+    def test_straight_line_function(self, nodes):
+        assert find_node(nodes, "Function", "straight").properties["complexity"] == 1
+
+    def test_branches_operators_and_match_arms(self, nodes):
+        # if, else if, &&, || and three match arms
+        assert find_node(nodes, "Function", "classify").properties["complexity"] == 8
+
+    def test_loops_try_and_closures(self, nodes):
+        # ?, for, while and the if in the closure; nested fn items count on their own
+        assert find_node(nodes, "Function", "total").properties["complexity"] == 5
+
+    def test_methods(self, nodes):
+        assert find_node(nodes, "Method", "open").properties["complexity"] == 3
+        assert find_node(nodes, "Method", "provided").properties["complexity"] == 2
+        assert "complexity" not in find_node(nodes, "Method", "required").properties
+
+
+DOCS_RS = r"""
+//! Crate docs are not attached to an item
+
+/// A point in the plane.
+///
+/// Both coordinates are in metres.
+#[derive(Debug, Clone)]
+pub struct Point {
+    /// Horizontal offset.
+    pub x: f64,
+    // Not a doc comment
+    pub y: f64,
+}
+
+/**
+ * Distance between two points.
+ *
+ * Uses the Euclidean metric.
+ */
+pub fn distance(a: &Point, b: &Point) -> f64 { 0.0 }
+
+#[doc = "Set through an attribute."]
+#[doc = r#"With a "raw" second line."#]
+pub trait Shape {
+    /// Area of the shape.
+    fn area(&self) -> f64;
+}
+
+impl Point {
+    /// Point at the origin.
+    pub fn origin() -> Self { Point { x: 0.0, y: 0.0 } }
+}
+
+//// Four slashes make a plain comment
+fn undocumented() {}
+
+pub fn bare() {}
+"""
+
+
+class TestDocComments:
+    """Doc comments stored as the doc property of the item after them."""
+
+    @pytest.fixture(scope="class")
+    def nodes(self):
+        nodes, _ = RustAdapter().parse_source(DOCS_RS, "docs.rs")
+        return nodes
+
+    def test_line_comments_skip_attributes(self, nodes):
+        assert find_node(nodes, "Class", "Point").properties["doc"] == \
+            "A point in the plane.\n\nBoth coordinates are in metres."
+
+    def test_fields(self, nodes):
+        assert find_node(nodes, "Field", "x").properties["doc"] == "Horizontal offset."
+        assert "doc" not in find_node(nodes, "Field", "y").properties
+
+    def test_block_comment(self, nodes):
+        assert find_node(nodes, "Function", "distance").properties["doc"] == \
+            "Distance between two points.\n\nUses the Euclidean metric."
+
+    def test_doc_attributes(self, nodes):
+        assert find_node(nodes, "Interface", "Shape").properties["doc"] == \
+            'Set through an attribute.\nWith a "raw" second line.'
+
+    def test_methods(self, nodes):
+        assert find_node(nodes, "Method", "area").properties["doc"] == "Area of the shape."
+        assert find_node(nodes, "Method", "origin").properties["doc"] == "Point at the origin."
+
+    def test_plain_comments_are_not_docs(self, nodes):
+        assert "doc" not in find_node(nodes, "Function", "undocumented").properties
+
+    def test_documented_flag_on_public_functions_and_types(self, nodes):
+        flags = {(kind, name): find_node(nodes, kind, name).properties.get("documented") for kind, name in [
+            ("Class", "Point"), ("Function", "distance"), ("Interface", "Shape"), ("Method", "area"),
+            ("Method", "origin"), ("Function", "bare"), ("Function", "undocumented"), ("Field", "x"),
+        ]}
+        # Private items and fields are not flagged
+        assert flags == {("Class", "Point"): True, ("Function", "distance"): True, ("Interface", "Shape"): True,
+                         ("Method", "area"): True, ("Method", "origin"): True, ("Function", "bare"): False,
+                         ("Function", "undocumented"): None, ("Field", "x"): None}
+
+
+RUST_TYPES_DIR = os.path.join(FIXTURES, "rust_types")
+
+
+class TestStructFieldTypes:
+    """Fields of every struct and enum variant shape, and USES_TYPE edges to the types they name."""
+
+    @pytest.fixture(scope="class")
+    def parsed(self):
+        parser = MultiLanguageParser(
+            use_ast_grep=True,
+            ast_grep_languages=['rust'],
+            ast_grep_fallback=False
+        )
+        return parser.parse_directory(RUST_TYPES_DIR, build_index=True)
+
+    @staticmethod
+    def _fields(nodes, relations, definer):
+        return {nodes[t].name: nodes[t] for s, t in _edges(relations, "DEFINES")
+                if s == definer.node_id and nodes[t].node_type == "Field"}
+
+    @staticmethod
+    def _uses(nodes, relations, owner):
+        return {nodes[r.target_id].name: r.properties["fields"] for r in relations
+                if r.relation_type == "USES_TYPE" and r.source_id == owner.node_id}
+
+    def test_type_kinds(self, parsed):
+        nodes, _ = parsed
+        assert {name: find_node(nodes, "Class", name).properties["type_kind"]
+                for name in ("Team", "Pair", "Marker")} == \
+            {"Team": "struct", "Pair": "tuple_struct", "Marker": "unit_struct"}
+
+    def test_tuple_and_unit_struct_fields(self, parsed):
+        nodes, relations = parsed
+        fields = self._fields(nodes, relations, find_node(nodes, "Class", "Pair"))
+        assert {name: (f.properties["type"], f.properties["visibility"]) for name, f in fields.items()} == \
+            {"0": ("Person", "Public"), "1": ("u32", "Private")}
+        assert self._fields(nodes, relations, find_node(nodes, "Class", "Marker")) == {}
+
+    def test_enum_variants_and_their_fields(self, parsed):
+        nodes, relations = parsed
+        venue = find_node(nodes, "Enum", "Venue")
+        assert venue.properties["doc"] == "Where a team plays"
+        variants = {nodes[t].name: nodes[t].properties["type_kind"] for s, t in _edges(relations, "DEFINES")
+                    if s == venue.node_id}
+        assert variants == {"Unknown": "unit", "Hosted": "tuple", "Remote": "struct"}
+
+        remote = self._fields(nodes, relations, find_node(nodes, "Variant", "Remote"))
+        assert {name: f.properties["field_of"] for name, f in remote.items()} == \
+            {"host": "Venue::Remote", "url": "Venue::Remote"}
+        # Variant fields are as visible as the enum
+        assert remote["host"].properties["visibility"] == "Public"
+
+    def test_field_types_link_to_indexed_types(self, parsed):
+        nodes, relations = parsed
+        # Vec<Person> links to Person; String, Vec, Option and the parameter T do not link
+        assert self._uses(nodes, relations, find_node(nodes, "Class", "Team")) == \
+            {"Person": ["owner", "members"], "Address": ["home"]}
+        assert self._uses(nodes, relations, find_node(nodes, "Class", "Pair")) == {"Person": ["0"]}
+        assert self._uses(nodes, relations, find_node(nodes, "Enum", "Venue")) == \
+            {"Team": ["Hosted.0"], "Person": ["Remote.host"]}
+        assert self._uses(nodes, relations, find_node(nodes, "Class", "Person")) == {}
+
+    def test_outline_nests_variants_and_fields(self):
+        graph = CodeGraph(use_ast_grep=True, ast_grep_languages=['rust'], ast_grep_fallback=False)
+        graph.add_directory(RUST_TYPES_DIR)
+        nodes = [{"id": n.node_id, "type": n.node_type, "name": n.name, "line_no": n.line_no,
+                  "field_type": n.properties.get("type") if n.node_type == "Field" else None}
+                 for n in graph.nodes.values() if n.file_path.endswith("model.rs")]
+        defines = [{"source": r.source_id, "target": r.target_id}
+                   for r in graph.relations if r.relation_type == "DEFINES"]
+        venue = next(e for e in build_outline(nodes, defines) if e.name == "Venue")
+        remote = next(e for e in venue.children if e.name == "Remote")
+        assert [(e.name, e.field_type) for e in remote.children] == [("host", "Person"), ("url", "String")]
+
+
+RUST_TRAITS_DIR = os.path.join(FIXTURES, "rust_traits")
+
+
+class TestTypeHierarchy:
+    """Supertraits, generic bounds and the hierarchy of a type implementing two traits."""
+
+    @pytest.fixture(scope="class")
+    def graph(self):
+        return CodeGraph.from_directory(RUST_TRAITS_DIR, use_ast_grep=True,
+                                        ast_grep_languages=['rust'], ast_grep_fallback=False)
+
+    def test_supertraits(self, graph):
+        shape, solid = find_node(graph.nodes, "Interface", "Shape"), find_node(graph.nodes, "Interface", "Solid")
+        assert shape.properties["supertraits"] == ["Named", "fmt::Debug"]
+        extends = {(graph.nodes[r.source_id].name, graph.nodes[r.target_id].name, graph.nodes[r.target_id].node_type)
+                   for r in graph.relations if r.relation_type == "EXTENDS"}
+        # Solid's supertrait is declared in another file; fmt::Debug is not indexed
+        assert extends == {("Shape", "Named", "Interface"), ("Shape", "fmt::Debug", "Unresolved"),
+                           ("Solid", "Shape", "Interface")}
+        assert "generic_bounds" not in solid.properties
+
+    def test_generic_bounds_inline_and_in_where_clause(self, graph):
+        labeled = find_node(graph.nodes, "Class", "Labeled")
+        # The lifetime parameter and the lifetime bound are left out
+        assert labeled.properties["generic_bounds"] == {"T": ["Shape", "Clone"], "U": ["fmt::Display"]}
+
+    def test_hierarchy_of_a_type_with_two_trait_impls(self, graph):
+        circle = find_node(graph.nodes, "Class", "Circle")
+        hierarchy = type_hierarchy(graph, circle.node_id).to_dict()
+        # Its two impls and two derives, then Shape's std supertrait
+        assert hierarchy["supertypes"] == ["Named", "Shape", "Clone", "Debug", "fmt::Debug"]
+        assert hierarchy["subtypes"] == []
+        derived = [e for e in hierarchy["edges"] if e["properties"].get("derived")]
+        assert len(derived) == 2
+
+    def test_hierarchy_of_a_trait(self, graph):
+        shape = find_node(graph.nodes, "Interface", "Shape")
+        hierarchy = type_hierarchy(graph, shape.node_id, max_depth=1).to_dict()
+        assert (hierarchy["supertypes"], hierarchy["subtypes"]) == (["Named", "fmt::Debug"], ["Circle", "Solid"])
+
+
+class TestUnused:
+    """Functions nothing calls, in the resolved graph."""
+
+    @staticmethod
+    def _graph(path):
+        graph = CodeGraph(use_ast_grep=True, ast_grep_languages=['rust'], ast_grep_fallback=False)
+        if os.path.isdir(path):
+            graph.add_directory(path)
+        else:
+            graph.add_file(path)
+        return graph
+
+    def test_private_uncalled_function(self):
+        unused = find_unused(self._graph(SAMPLE_RS))
+        # greet and the Person methods are public
+        assert [(u.name, u.confidence) for u in unused] == [("add", "high")]
+
+    def test_include_public(self):
+        names = {u.name for u in find_unused(self._graph(SAMPLE_RS), include_public=True)}
+        assert {"add", "greet", "get_age"} <= names
+
+    def test_calls_from_other_files_count(self):
+        names = {u.name for u in find_unused(self._graph(RUST_SAMPLE_DIR), include_public=True)}
+        # greet and salute are only called from welcome.rs; fmt and area implement traits
+        assert not names & {"greet", "salute", "helper", "total_area", "fmt", "area"}
+        assert {"welcome", "welcome_back", "grow"} <= names
+
+
+ASYNC_RS = """
+pub struct Client;
+
+impl Client {
+    pub async fn get(&self, url: &str) -> String { String::new() }
+    pub fn close(&self) {}
+}
+
+pub trait Service {
+    async fn call(&self);
+}
+
+async fn fetch(client: &Client) -> String {
+    let page = client.get("/").await;
+    client.close();
+    page
+}
+
+fn main() {}
+"""
+
+
+class TestAsync:
+    """async fn and calls awaited on the spot."""
+
+    @pytest.fixture(scope="class")
+    def parsed(self):
+        return RustAdapter().parse_source(ASYNC_RS, "client.rs")
+
+    def test_is_async(self, parsed):
+        nodes, _ = parsed
+        flags = {(n.node_type, n.name): n.properties["is_async"] for n in nodes.values()
+                 if n.node_type in ("Function", "Method")}
+        assert flags == {("Method", "get"): True, ("Method", "close"): False, ("Method", "call"): True,
+                         ("Function", "fetch"): True, ("Function", "main"): False}
+
+    def test_awaited_calls_are_marked(self, parsed):
+        nodes, relations = parsed
+        fetch = find_node(nodes, "Function", "fetch")
+        calls = {nodes[r.target_id].name: r.properties.get("await_call", False)
+                 for r in relations if r.relation_type == "CALLS" and r.source_id == fetch.node_id}
+        assert calls == {"get": True, "close": False}
+
+
+MACROS_RS = r"""
+use crate::shapes::area;
+
+pub trait Describe {}
+
+#[derive(Debug, Clone, serde::Serialize, Describe)]
+pub struct Point { x: i32 }
+
+/// Squares its argument.
+#[macro_export]
+macro_rules! square {
+    ($x:expr) => { $x * $x };
+}
+
+macro_rules! twice { ($e:expr) => { $e; $e }; }
+
+fn helper() -> i32 { 1 }
+
+fn run(p: Point) {
+    let n = square!(helper());
+    println!("{} {}", area(2.0), p.x);
+    let v = vec![twice!(helper()), scaled!(n)];
+}
+"""
+
+SHAPES_RS = """
+pub fn area(r: f64) -> f64 { r * r }
+
+#[macro_export]
+macro_rules! scaled { ($e:expr) => { $e * 2 }; }
+"""
+
+DERIVE_RS = """
+use proc_macro::TokenStream;
+
+#[proc_macro_derive(Describe)]
+pub fn describe_derive(input: TokenStream) -> TokenStream { input }
+"""
+
+
+class TestMacros:
+    """#[derive(..)] lists, macro_rules! definitions and invocations, and paths in macro arguments."""
+
+    @pytest.fixture
+    def graph(self, tmp_path):
+        (tmp_path / "app.rs").write_text(MACROS_RS, encoding="utf-8")
+        (tmp_path / "shapes.rs").write_text(SHAPES_RS, encoding="utf-8")
+        (tmp_path / "derive.rs").write_text(DERIVE_RS, encoding="utf-8")
+        return CodeGraph.from_directory(str(tmp_path), use_ast_grep=True,
+                                        ast_grep_languages=['rust'], ast_grep_fallback=False)
+
+    @staticmethod
+    def _edges(graph, relation_type, source):
+        return [(graph.nodes[r.target_id], r.properties) for r in graph.relations
+                if r.relation_type == relation_type and r.source_id == source.node_id]
+
+    def test_derives_are_implements_edges(self):
+        adapter = RustAdapter()
+        nodes, relations = adapter.parse_source(MACROS_RS, "app.rs")
+        point, describe = find_node(nodes, "Class", "Point"), find_node(nodes, "Interface", "Describe")
+        local, = [r for r in relations if r.relation_type == "IMPLEMENTS"]
+        assert (local.source_id, local.target_id) == (point.node_id, describe.node_id)
+        assert local.properties == {"trait_path": "Describe", "derived": True}
+        # Traits outside the file are left to the second pass
+        queued = [e for e in adapter.pending_imports if e["type"] == "IMPLEMENTS"]
+        assert [e["trait_path"] for e in queued] == ["Debug", "Clone", "serde::Serialize"]
+        assert all(e["type_node_id"] == point.node_id and e["impl_properties"] == {"derived": True}
+                   for e in queued)
+
+    def test_std_derives_point_at_placeholders(self, graph):
+        point = find_node(graph.nodes, "Class", "Point")
+        targets = {target.name: target.node_type for target, properties in self._edges(graph, "IMPLEMENTS", point)
+                   if properties.get("derived")}
+        assert targets == {"Describe": "Interface", "Debug": "Unresolved", "Clone": "Unresolved",
+                           "serde::Serialize": "Unresolved"}
+
+    def test_macro_rules_are_nodes(self, graph):
+        square, twice = find_node(graph.nodes, "Macro", "square"), find_node(graph.nodes, "Macro", "twice")
+        assert (square.properties["visibility"], square.properties["rules"]) == ("Public", 1)
+        assert square.properties["doc"] == "Squares its argument."
+        assert twice.properties["visibility"] == "Private"
+
+    def test_invocations_call_their_macro(self, graph):
+        run = find_node(graph.nodes, "Function", "run")
+        called = {(target.name, os.path.basename(target.file_path), properties["line_no"])
+                  for target, properties in self._edges(graph, "CALLS", run) if properties.get("macro")}
+        # twice! and scaled! are nested in vec!'s arguments; scaled! is exported from another file
+        assert called == {("square", "app.rs", 20), ("twice", "app.rs", 22), ("scaled", "shapes.rs", 22)}
+        # Std macros are not indexed; their calls point at placeholders
+        placeholders = {(target.name, properties["line_no"]) for target, properties in self._edges(graph, "CALLS", run)
+                        if properties.get("macro") and target.node_type == "Unresolved"}
+        assert placeholders == {("println!", 21), ("vec!", 22)}
+
+    def test_derives_use_their_macro(self, graph):
+        point = find_node(graph.nodes, "Class", "Point")
+        targets = {properties["derive_path"]: target.node_type
+                   for target, properties in self._edges(graph, "DERIVE_USE", point)}
+        # The Describe derive macro is indexed, the others come from std and serde
+        assert targets == {"Describe": "Function", "Debug": "Unresolved", "Clone": "Unresolved",
+                           "serde::Serialize": "Unresolved"}
+        assert find_node(graph.nodes, "Function", "describe_derive").properties["derive_macro"] == "Describe"
+
+    def test_sample_person_derives(self):
+        graph = CodeGraph(use_ast_grep=True, ast_grep_languages=['rust'], ast_grep_fallback=False)
+        graph.add_file(SAMPLE_RS)
+        person, greet = find_node(graph.nodes, "Class", "Person"), find_node(graph.nodes, "Function", "greet")
+        assert {target.node_id for target, _ in self._edges(graph, "DERIVE_USE", person)} == \
+            {"unresolved:Debug", "unresolved:Clone"}
+        called = [(target.node_id, properties["line_no"]) for target, properties in self._edges(graph, "CALLS", greet)
+                  if properties.get("macro")]
+        assert called == [("unresolved:println!", 28)]
+
+    def test_macro_arguments_reference_symbols(self, graph):
+        run = find_node(graph.nodes, "Function", "run")
+        references = {(target.name, properties["via_macro"], properties["line_no"])
+                      for target, properties in self._edges(graph, "REFERENCES", run)
+                      if properties.get("confidence") == "low"}
+        # p.x is a field access and n a local; neither names an indexed symbol
+        assert references == {("helper", "square", 20), ("area", "println", 21), ("helper", "vec", 22)}
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -0,0 +1,119 @@
+"""
+Tests for cutting the source of symbols out of their files.
+
+Uses the legacy Python parser so no optional parsing backends are needed.
+"""
+
+import os
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.graph.code_graph import CodeGraph, content_hash
+from src.graph.snippets import extract_snippet
+
+
+SOURCE = '''import os
+
+
+class Greeter:
+    """Says hello."""
+
+    def greet(self, name):
+        return "héllo " + name
+
+
+def main():
+    Greeter().greet("world")
+'''
+
+
+def _node(graph, name):
+    return next(n for n in graph.nodes.values() if n.name == name)
+
+
+def _span(node):
+    return {"line_no": node.line_no, "end_line_no": node.end_line_no, **node.properties}
+
+
+@pytest.fixture
+def indexed(tmp_path):
+    path = tmp_path / "greeter.py"
+    path.write_text(SOURCE, encoding="utf-8")
+    return path, CodeGraph.from_directory(str(tmp_path))
+
+
+class TestExtractSnippet:
+
+    def test_exact_span(self, indexed):
+        path, graph = indexed
+        data = path.read_bytes()
+        snippet = extract_snippet(data, _span(_node(graph, "greet")), content_hash(data))
+
+        assert snippet["source"] == '    def greet(self, name):\n        return "héllo " + name'
+        assert snippet["stale"] is False and "warning" not in snippet
+        assert (snippet["context_before"], snippet["context_after"]) == ([], [])
+
+    def test_context_lines_stop_at_the_file_edges(self, indexed):
+        path, graph = indexed
+        data = path.read_bytes()
+        snippet = extract_snippet(data, _span(_node(graph, "main")), content_hash(data), context_lines=3)
+
+        assert snippet["context_before"] == ['        return "héllo " + name', "", ""]
+        # The file's last line is main's; its final newline does not start another
+        assert snippet["context_after"] == []
+
+    def test_changed_file_is_stale_but_still_answers(self, indexed):
+        path, graph = indexed
+        span = _span(_node(graph, "main"))
+        # Edits that keep the lines above the symbol change the hash but not where it is
+        path.write_text(SOURCE.replace("import os", "import os, sys") + "\n\ndef extra():\n    pass\n",
+                        encoding="utf-8")
+        snippet = extract_snippet(path.read_bytes(), span, _node(graph, "greeter.py").properties["content_hash"])
+
+        assert snippet["stale"] is True
+        assert "changed since it was indexed" in snippet["warning"]
+        # Cut by line and column, so the bytes added on line 1 do not shift it
+        assert snippet["source"] == 'def main():\n    Greeter().greet("world")'
+
+    def test_span_past_the_end_of_a_shortened_file(self, indexed):
+        path, graph = indexed
+        span = _span(_node(graph, "main"))
+        path.write_text("x = 1\n", encoding="utf-8")
+        snippet = extract_snippet(path.read_bytes(), span, "old hash")
+
+        assert (snippet["source"], snippet["stale"]) == ("", True)
+        assert "fewer than the symbol's start line" in snippet["warning"]
+
+    def test_transcoded_file(self, tmp_path):
+        path = tmp_path / "greeter.py"
+        path.write_bytes(SOURCE.encode("utf-16"))
+        graph = CodeGraph.from_directory(str(tmp_path))
+        data = path.read_bytes()
+        snippet = extract_snippet(data, _span(_node(graph, "greet")), content_hash(data))
+
+        # The offsets are into the UTF-8 text the file was transcoded to
+        assert snippet["source"] == '    def greet(self, name):\n        return "héllo " + name'
+        assert snippet["stale"] is False
+
+
+class TestSpansAfterReindex:
+
+    def test_update_file_refreshes_byte_offsets(self, indexed):
+        path, graph = indexed
+        edited = SOURCE.replace("import os\n", "import os\nimport sys\nimport json\n")
+        path.write_text(edited, encoding="utf-8")
+        graph.update_file(str(path))
+
+        data = path.read_bytes()
+        main = _node(graph, "main")
+        assert data[main.properties["start_byte"]:main.properties["end_byte"]] == \
+            b'def main():\n    Greeter().greet("world")'
+        assert extract_snippet(data, _span(main), content_hash(data))["stale"] is False
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -0,0 +1,172 @@
+"""
+Tests for the embedded SQLite graph store.
+"""
+
+import os
+import sys
+from unittest.mock import patch
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.neo4j_storage.sqlite_db import SQLiteDatabase
+from src.neo4j_storage.store import GraphStore
+
+
+def _node(node_id, node_type, name, file_path="app.py", line_no=1, **properties):
+    return {"labels": ["Base", node_type],
+            "properties": dict(properties, id=node_id, name=name, file_path=file_path, line_no=line_no)}
+
+
+def _rel(start, end, rel_type, **properties):
+    return {"start_node_id": start, "end_node_id": end, "type": rel_type, "properties": properties}
+
+
+@pytest.fixture
+def db():
+    db = SQLiteDatabase(":memory:")
+    db.upsert_nodes([
+        _node("file:app.py", "File", "app.py", line_no=None),
+        _node("f:main", "Function", "main", line_no=10),
+        _node("f:load", "Function", "load", line_no=5, docstring="Load the settings"),
+        _node("f:helper", "Function", "helper", "util.py", 1),
+        _node("c:Base", "Class", "Shape", "shapes.py", 1),
+        _node("c:Circle", "Class", "Circle", "shapes.py", 10),
+    ])
+    db.batch_create_relationships([
+        _rel("f:main", "f:load", "CALLS", line_no=11, call_site="load()"),
+        _rel("f:main", "f:helper", "CALLS", line_no=12, call_site="helper()"),
+        _rel("f:load", "f:helper", "CALLS", line_no=6, call_site="helper()"),
+        _rel("c:Circle", "c:Base", "EXTENDS"),
+        _rel("file:app.py", "f:main", "DEFINES"),
+    ])
+    return db
+
+
+class TestWrites:
+    def test_is_a_graph_store(self, db):
+        assert isinstance(db, GraphStore)
+        assert db.verify_connection()
+
+    def test_upsert_replaces_properties(self, db):
+        db.upsert_nodes([_node("f:load", "Function", "load", line_no=7)])
+        [load] = db.find_nodes_by_name("load")
+        assert load["line_no"] == 7
+        assert "docstring" not in load
+
+    def test_edges_need_both_endpoints(self, db):
+        db.batch_create_relationships([_rel("f:main", "f:missing", "CALLS")])
+        assert [n["name"] for n in db.find_related("CALLS", "out", {"name": "main"})] == ["load", "helper"]
+
+    def test_deleting_nodes_deletes_their_edges(self, db):
+        db.delete_nodes(["f:helper"])
+        assert db.find_nodes_by_name("helper") == []
+        assert [n["name"] for n in db.find_related("CALLS", "out", {"name": "main"})] == ["load"]
+
+        db.delete_nodes_by_file(["app.py"])
+        assert {n["id"] for n in db.get_graph()["nodes"]} == {"c:Base", "c:Circle"}
+
+    def test_delete_relationships_by_property(self, db):
+        db.delete_relationships([{"start_node_id": "f:main", "end_node_id": "f:helper", "type": "CALLS",
+                                  "properties": {"line_no": 99}}])
+        assert len(db.find_related("CALLS", "out", {"name": "main"})) == 2
+        db.delete_relationships([{"start_node_id": "f:main", "end_node_id": "f:helper", "type": "CALLS",
+                                  "properties": {"line_no": 12}}])
+        assert len(db.find_related("CALLS", "out", {"name": "main"})) == 1
+
+    def test_failed_batch_is_rolled_back(self, db):
+        with pytest.raises(RuntimeError):
+            with db.write_batch():
+                db.delete_nodes(["f:main"])
+                with db.write_batch():
+                    db.upsert_nodes([_node("f:new", "Function", "new")])
+                raise RuntimeError("boom")
+        assert db.find_nodes_by_name("main") and not db.find_nodes_by_name("new")
+
+    def test_file_states(self, db):
+        db.set_file_states([{"id": "file:app.py", "content_hash": "abc", "mtime_ns": 1, "graph_state": "{}"}])
+        assert db.get_file_states() == [
+            {"file_path": "app.py", "content_hash": "abc", "mtime_ns": 1, "graph_state": "{}"},
+        ]
+
+    def test_set_node_properties(self, db):
+        db.set_node_properties([{"id": "file:app.py", "properties": {"module_name": "app"}},
+                                {"id": "file:gone.py", "properties": {"module_name": "gone"}}])
+        node, = [n for n in db.find_nodes_by_name("app.py")]
+        assert (node["module_name"], node["file_path"]) == ("app", "app.py")
+
+    def test_persists_across_connections(self, tmp_path):
+        path = str(tmp_path / "graph" / "codegraph.db")
+        db = SQLiteDatabase(path)
+        db.upsert_nodes([_node("f:main", "Function", "main")])
+        db.close()
+
+        reopened = SQLiteDatabase(path)
+        assert [n["id"] for n in reopened.find_nodes_by_name("main")] == ["f:main"]
+        reopened.clear_database()
+        assert reopened.get_graph() == {"nodes": [], "relationships": []}
+
+
+class TestQueries:
+    def test_nodes_by_name_and_type(self, db):
+        assert [n["id"] for n in db.find_nodes_by_name("Circle", "Class")] == ["c:Circle"]
+        assert db.find_nodes_by_name("Circle", "Function") == []
+
+    def test_related_with_labels(self, db):
+        supers = db.find_related("EXTENDS", "out", {"name": "Circle"}, "Class", "Class")
+        subs = db.find_related("EXTENDS", "in", {"name": "Shape"}, "Class", "Class")
+        assert ([n["name"] for n in supers], [n["name"] for n in subs]) == (["Shape"], ["Circle"])
+        assert db.find_related("CALLS", "in", {"name": "helper"}, limit=1) != []
+        with pytest.raises(ValueError, match="Unknown direction"):
+            db.find_related("CALLS", "up", {"name": "helper"})
+        with pytest.raises(ValueError, match="Invalid property"):
+            db.find_related("CALLS", "in", {"name') OR 1=1 --": "helper"})
+
+    def test_call_edges_in_order(self, db):
+        callers = list(db.get_call_edges(["f:helper"], "callers"))
+        assert [(e["caller_id"], e["call_line"], e["node"]["name"]) for e in callers] == [
+            ("f:load", 6, "load"), ("f:main", 12, "main"),
+        ]
+        assert callers[0]["call_file"] == "app.py"
+
+    def test_call_edges_are_streamed(self, db):
+        db.batch_create_relationships([_rel("f:main", "f:helper", "CALLS", line_no=line, call_site="helper()")
+                                       for line in range(20, 30)])
+        with patch("src.neo4j_storage.sqlite_db.FETCH_SIZE", 1):
+            edges = db.get_call_edges(["f:helper"], "callers")
+            assert next(edges)["caller_id"] == "f:load"
+            # Stopping early leaves the store usable
+            edges.close()
+            assert [e["call_line"] for e in db.get_call_edges(["f:main"], "callees")] == [12, *range(20, 30), 11]
+
+    def test_text_search_ranks_names_first(self, db):
+        results = db.search_code_by_text("load")
+        assert [r["node"]["name"] for r in results] == ["load"]
+        # Twice for the name, once for the docstring
+        assert results[0]["score"] == 3.0
+        assert db.search_code_by_text("settings")[0]["node"]["id"] == "f:load"
+
+    def test_vector_search(self, db):
+        db.upsert_nodes([_node("f:a", "Function", "a", embedding=[1.0, 0.0]),
+                         _node("f:b", "Function", "b", embedding=[0.6, 0.8])])
+        results = db.search_code_by_vector([0.0, 1.0], "Function", limit=1)
+        assert results[0]["node"]["id"] == "f:b"
+        assert round(results[0]["score"], 6) == 0.8
+
+    def test_file_paths_and_symbols(self, db):
+        assert db.find_file_paths("app.py") == ["app.py"]
+        # Only File nodes count
+        assert db.find_file_paths("shapes.py") == []
+        outline = db.get_file_symbols("app.py")
+        assert [n["name"] for n in outline["nodes"]] == ["load", "main"]
+        assert outline["defines"] == [{"source": "file:app.py", "target": "f:main"}]
+
+    def test_cypher_is_not_supported(self, db):
+        with pytest.raises(NotImplementedError, match="neo4j storage backend"):
+            db.execute_cypher("MATCH (n) RETURN n")
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -0,0 +1,106 @@
+"""
+Tests for JSON Lines streaming of tool results.
+"""
+
+import asyncio
+import json
+import os
+import subprocess
+import sys
+import textwrap
+
+import pytest
+
+# Add project root to Python path
+ROOT = os.path.dirname(os.path.dirname(os.path.abspath(__file__)))
+sys.path.insert(0, ROOT)
+
+from src.mcp.streaming import BufferSink, JsonLinesStream, encode_record
+
+
+def _records(text):
+    return [json.loads(line) for line in text.splitlines()]
+
+
+def _stream(sections, batch_size=2, fail_after=None):
+    sink = BufferSink()
+    stream = JsonLinesStream(sink, batch_size=batch_size)
+
+    async def run():
+        await stream.begin("get_call_graph", symbol="load")
+        for kind, items in sections:
+            await stream.items(kind, items)
+        await stream.end(truncated=False)
+
+    asyncio.run(run())
+    return sink.text()
+
+
+class TestRecords:
+
+    def test_batches_and_end_counts(self):
+        text = _stream([("nodes", [{"id": n} for n in "abcde"]), ("edges", [{"source": "a", "target": "b"}])])
+        records = _records(text)
+
+        assert [r["type"] for r in records] == ["begin", "nodes", "nodes", "nodes", "edges", "end"]
+        assert records[0] == {"type": "begin", "schema": 1, "tool": "get_call_graph", "symbol": "load"}
+        assert [len(r["items"]) for r in records[1:4]] == [2, 2, 1]
+        assert records[-1] == {"type": "end", "nodes": 5, "edges": 1, "lines": 6, "truncated": False}
+
+    def test_newlines_in_values_stay_on_one_line(self):
+        line = encode_record({"type": "nodes", "items": [{"snippet": "a\nb\r\nc"}]})
+        assert line.count("\n") == 1 and line.endswith("\n")
+        assert json.loads(line)["items"][0]["snippet"] == "a\nb\r\nc"
+
+    def test_empty_result(self):
+        assert [r["type"] for r in _records(_stream([("nodes", [])]))] == ["begin", "end"]
+
+
+WRITER = textwrap.dedent('''
+    import asyncio, sys
+    sys.path.insert(0, {root!r})
+    from src.mcp.streaming import JsonLinesStream, file_sink
+
+    def nodes():
+        n = 0
+        while True:
+            n += 1
+            yield {{"id": f"Function:src/app.py:f{{n}}:{{n}}", "snippet": "x = 1\\n" * (n % 50)}}
+
+    async def main():
+        stream = JsonLinesStream(file_sink(sys.stdout), batch_size=7)
+        await stream.begin("query_nodes", query="kind:function")
+        await stream.items("nodes", nodes())
+
+    asyncio.run(main())
+''')
+
+
+class TestKilledMidStream:
+
+    def test_completed_lines_are_whole_records(self):
+        process = subprocess.Popen([sys.executable, "-c", WRITER.format(root=ROOT)],
+                                   stdout=subprocess.PIPE)
+        try:
+            received = b""
+            while received.count(b"\n") < 50:
+                chunk = process.stdout.read1(65536)
+                assert chunk, "writer exited before streaming"
+                received += chunk
+        finally:
+            process.kill()
+            received += process.stdout.read()
+            process.wait()
+
+        *lines, fragment = received.decode("utf-8").split("\n")
+        records = [json.loads(line) for line in lines]
+        assert records[0]["type"] == "begin"
+        assert all(r["type"] == "nodes" and len(r["items"]) == 7 for r in records[1:])
+        # Whatever follows the last newline is the start of an unfinished record, never a finished one
+        if fragment:
+            with pytest.raises(ValueError):
+                json.loads(fragment)
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
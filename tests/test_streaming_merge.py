@@ -0,0 +1,180 @@
+"""
+Tests for the memory-bounded merge: per-file results are written as they
+arrive, pending imports and cross-file relations spill to disk, and the
+stored graph is the one merge_results builds in memory.
+"""
+
+import json
+import os
+import sys
+from concurrent.futures import Future
+from contextlib import nullcontext
+from unittest.mock import patch
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.ast_parser.diagnostics import ParseDiagnostics
+from src.ast_parser.parser import CodeNode
+from src.neo4j_storage.sqlite_db import SQLiteDatabase
+from src.parallel.parse_worker import FileParseResult, merge_results, parse_file_task
+from src.parallel.streaming_merge import StreamingMerge, spill_threshold_for, symbol_stub
+
+FIXTURES = os.path.join(os.path.dirname(os.path.abspath(__file__)), "fixtures")
+
+
+def _fixture_files():
+    files = []
+    for sample in ("python_sample", "multi_lang_sample", "ts_exports"):
+        for root, _, names in os.walk(os.path.join(FIXTURES, sample)):
+            files += [os.path.join(root, name) for name in names if name.endswith((".py", ".ts", ".tsx", ".js"))]
+    return sorted(files)
+
+
+def _node_set(nodes):
+    return {(n.node_id, n.node_type, n.name, n.line_no, json.dumps(n.properties, sort_keys=True, default=str))
+            for n in nodes.values()}
+
+
+def _edge_set(relations):
+    return {(r.source_id, r.target_id, r.relation_type, json.dumps(r.properties, sort_keys=True, default=str))
+            for r in relations}
+
+
+def _stream(results, **options):
+    nodes, relations, written = {}, [], set()
+
+    def write_nodes(batch):
+        nodes.update(batch)
+        written.update(batch)
+
+    def write_relations(batch):
+        # Both ends of a relation between parsed nodes are written before it
+        assert all(r.source_id in written for r in batch if r.source_id in all_ids)
+        assert all(r.target_id in written for r in batch if r.target_id in all_ids)
+        relations.extend(batch)
+
+    all_ids = {node_id for result in results for node_id in result.nodes}
+    merge = StreamingMerge(write_nodes, write_relations, **options)
+    for result in results:
+        merge.add(result)
+    for node_id, module_name in merge.finish().items():
+        nodes[node_id].properties["module_name"] = module_name
+    return nodes, relations, merge.stats
+
+
+@pytest.fixture(scope="module")
+def results():
+    return [parse_file_task(path) for path in _fixture_files()]
+
+
+class TestStreamingMerge:
+
+    def test_same_graph_as_the_in_memory_merge(self, results):
+        expected_nodes, expected_relations = merge_results(results)
+        nodes, relations, stats = _stream(list(reversed(results)), flush_size=7)
+        assert _node_set(nodes) == _node_set(expected_nodes)
+        assert _edge_set(relations) == _edge_set(expected_relations)
+        assert (stats.files, stats.nodes, stats.spilled) == (len(results), len(expected_nodes), 0)
+        assert any(r.relation_type == "IMPORTS_DEFINITION" for r in relations)
+
+    def test_spilled_pending_imports_give_the_same_graph(self, results):
+        expected_nodes, expected_relations = merge_results(results)
+        nodes, relations, stats = _stream(results, spill_threshold=1, flush_size=1)
+        assert stats.spilled == stats.pending > 0
+        assert stats.deferred_spilled == stats.deferred > 0
+        assert _node_set(nodes) == _node_set(expected_nodes)
+        assert _edge_set(relations) == _edge_set(expected_relations)
+
+    def test_failed_files_are_skipped(self, results):
+        parsed = next(result for result in results if result.nodes)
+        nodes, _, stats = _stream([parsed, FileParseResult("broken.py", error="SyntaxError: boom")])
+        assert (stats.files, stats.failed) == (1, 1)
+        assert {n.file_path for n in nodes.values()} == {parsed.file_path}
+
+
+class TestStubs:
+
+    def test_stub_keeps_what_resolution_reads(self):
+        node = CodeNode("Method:a.cpp:area:3", "Method", "area", "a.cpp", 3, 9,
+                        properties={"method_of": "Shape", "signature": "()", "doc": "Area.", "complexity": 4})
+        node.code_snippet = "double area() { ... }"
+        stub = symbol_stub(node)
+        assert (stub.node_id, stub.name, stub.file_path, stub.line_no, stub.end_line_no) == \
+            ("Method:a.cpp:area:3", "area", "a.cpp", 3, 9)
+        assert stub.properties == {"method_of": "Shape", "signature": "()"}
+        assert stub.code_snippet == ""
+
+    def test_spill_threshold_from_a_memory_budget(self):
+        assert spill_threshold_for(1) == 1_000
+        assert spill_threshold_for(4096) == 1024 * 1024
+
+
+class TestStreamingIndex:
+
+    def _knowledge_graph(self, max_memory_mb=None):
+        from src.main import CodebaseKnowledgeGraph
+
+        kg = CodebaseKnowledgeGraph.__new__(CodebaseKnowledgeGraph)
+        kg.use_ast_grep, kg.ast_grep_languages, kg.ast_grep_fallback = False, ["python"], True
+        kg.respect_gitignore, kg.exclude = True, []
+        kg.include, kg.max_file_size = [], None
+        kg.language_options, kg.language_overrides = {}, {}
+        kg.cache, kg.jobs, kg.max_memory_mb = None, 1, max_memory_mb
+        kg.diagnostics = ParseDiagnostics()
+        kg.db = SQLiteDatabase(":memory:")
+        return kg
+
+    @staticmethod
+    def _stored(db):
+        nodes = {(row["id"], row["properties"]) for row in db._query("SELECT id, properties FROM nodes")}
+        edges = sorted((row["source"], row["target"], row["type"], row["properties"])
+                       for row in db._query("SELECT source, target, type, properties FROM edges"))
+        return nodes, edges
+
+    def test_database_matches_a_full_index(self):
+        root = os.path.join(FIXTURES, "python_sample")
+        full, streamed = self._knowledge_graph(), self._knowledge_graph(max_memory_mb=1)
+        files = full._collect_source_files(root)
+        with patch.object(full, "_generate_embeddings"), patch.object(streamed, "_generate_embeddings"):
+            full._index_full(root, files, None, use_parallel=False)
+            counts = streamed._index_streaming(files, use_parallel=False)
+        assert self._stored(streamed.db) == self._stored(full.db)
+        assert counts[0] == len(self._stored(full.db)[0])
+
+    def test_pool_holds_a_window_of_files(self):
+        kg = self._knowledge_graph()
+        submitted = []
+
+        class Pool:
+            max_workers = 2
+
+            def submit(self, fn, file_path, *args):
+                submitted.append(file_path)
+                future = Future()
+                future.set_result(FileParseResult(file_path))
+                return future
+
+        files = [f"f{i}.py" for i in range(10)]
+        with patch("src.main.get_processing_pool", return_value=nullcontext(Pool())):
+            results = kg._parse_in_pool(files)
+            first = next(results)
+            # Twice the workers, and one more per result taken
+            assert len(submitted) == 4
+            rest = list(results)
+        assert sorted(r.file_path for r in [first] + rest) == sorted(files)
+        assert submitted == files
+
+    def test_process_codebase_streams_when_given_a_budget(self):
+        kg = self._knowledge_graph(max_memory_mb=64)
+        with patch.object(kg, "_index_streaming", return_value=(3, 2)) as streaming, \
+                patch.object(kg, "_index_full", side_effect=AssertionError), \
+                patch.object(kg, "_create_search_indexes"), patch("src.main.log_runtime_info"):
+            assert kg.process_codebase(os.path.join(FIXTURES, "python_sample")) == (3, 2)
+        streaming.assert_called_once()
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
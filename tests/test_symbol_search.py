@@ -0,0 +1,164 @@
+"""
+Tests for ranked symbol search.
+"""
+
+import os
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.graph.symbol_search import PatternError, SymbolIndex, compile_pattern, fuzzy_score, search_symbols
+
+
+def _symbol(name, file_path="app.py", line_no=1, node_type="Function", owner=None):
+    symbol = {"id": f"{node_type}:{file_path}:{name}:{line_no}", "name": name, "type": node_type,
+              "file_path": file_path, "line_no": line_no}
+    if owner:
+        symbol["owner"] = owner
+    return symbol
+
+
+SYMBOLS = [
+    _symbol("parse"),
+    _symbol("parse_file", "parser.py", 3),
+    _symbol("parse_args", "cli.py", 8),
+    _symbol("reparse", "parser.py", 20),
+    _symbol("Parser", "parser.py", 1, "Class"),
+    _symbol("tokenize", "lexer.py", 4),
+]
+
+
+def _ranked(matches):
+    return [(m.symbol["name"], m.match_kind) for m in matches]
+
+
+class TestRanking:
+    def test_exact_then_prefix_then_substring(self):
+        assert _ranked(search_symbols(SYMBOLS, "parse")) == [
+            ("parse", "exact"),
+            ("Parser", "prefix"),
+            ("parse_args", "prefix"),
+            ("parse_file", "prefix"),
+            ("reparse", "substring"),
+        ]
+
+    def test_closer_prefix_scores_higher(self):
+        scores = {m.symbol["name"]: m.score for m in search_symbols(SYMBOLS, "parse")}
+        assert scores["parse"] == 1.0
+        assert scores["Parser"] > scores["parse_file"] > scores["reparse"]
+
+    def test_regex_matches_rank_last(self):
+        matches = search_symbols(SYMBOLS, "^(re)?parse$")
+        assert _ranked(matches) == [("parse", "regex"), ("reparse", "regex")]
+
+    def test_filters(self):
+        assert _ranked(search_symbols(SYMBOLS, "parse", node_types=["Class"])) == [("Parser", "prefix")]
+        assert [m.symbol["file_path"] for m in search_symbols(SYMBOLS, "parse", file_glob="parser.*")] == \
+            ["parser.py", "parser.py", "parser.py"]
+
+    def test_to_dict_carries_score_and_kind(self):
+        match = search_symbols(SYMBOLS, "tokenize")[0]
+        assert match.to_dict() == dict(SYMBOLS[-1], match_kind="exact", score=1.0)
+
+
+MEMBERS = [
+    _symbol("Person", "src/model/person.rs", 4, "Class"),
+    _symbol("name", "src/model/person.rs", 5, "Field", owner="Person"),
+    _symbol("get_name", "src/model/person.rs", 14, "Method", owner="Person"),
+    _symbol("getName", "web/ui/person.ts", 3, "Method", owner="PersonView"),
+    _symbol("name", "src/main.rs", 20, "LocalVariable"),
+    _symbol("name", "src/deep/nested/util.rs", 2, "Function"),
+]
+
+
+class TestFuzzy:
+    def test_word_starts_across_separators(self):
+        matches = search_symbols(MEMBERS, "getname")
+        # getName differs only in case, get_name needs the underscore skipped
+        assert _ranked(matches) == [("getName", "exact"), ("get_name", "boundary")]
+        assert matches[0].score == 0.95
+        assert 0.7 < matches[1].score < 0.8
+
+    def test_boundary_ranks_between_prefix_and_substring(self):
+        symbols = [_symbol(name) for name in ("prefilter", "parse_file", "profile", "HTTPServer", "reparse")]
+        assert _ranked(search_symbols(symbols, "pf")) == [
+            # Equal fuzzy scores go to the shorter name
+            ("parse_file", "boundary"), ("profile", "fuzzy"), ("prefilter", "fuzzy"),
+        ]
+        assert _ranked(search_symbols(symbols, "file", use_regex=False)) == [
+            ("parse_file", "boundary"), ("profile", "substring"), ("prefilter", "fuzzy"),
+        ]
+        assert _ranked(search_symbols(symbols, "hs")) == [("HTTPServer", "boundary")]
+
+    def test_subsequence(self):
+        symbols = [_symbol("Person", node_type="Class"), _symbol("prison"), _symbol("PersonRepository", node_type="Class"),
+                   _symbol("parse_args")]
+        assert _ranked(search_symbols(symbols, "prsn"))[:2] == [("Person", "fuzzy"), ("PersonRepository", "fuzzy")]
+
+    def test_word_starts_and_runs_score_higher(self):
+        assert fuzzy_score("get_name", "gn") > fuzzy_score("agent_list", "gn")
+        assert fuzzy_score("getName", "gena") > fuzzy_score("getName", "gtam")
+        assert fuzzy_score("get_name", "ngt") is None
+
+    def test_owner_qualified_pattern(self):
+        assert _ranked(search_symbols(MEMBERS, "Person.nam")) == [
+            ("name", "prefix"), ("get_name", "boundary"), ("getName", "boundary"),
+        ]
+        assert [m.symbol["owner"] for m in search_symbols(MEMBERS, "personview::getname")] == ["PersonView"]
+
+    def test_ties_prefer_types_then_shallow_files(self):
+        matches = search_symbols(MEMBERS, "name", use_regex=False)
+        assert [(m.symbol["type"], m.symbol["file_path"]) for m in matches if m.match_kind == "exact"] == [
+            ("Function", "src/deep/nested/util.rs"),
+            ("Field", "src/model/person.rs"),
+            ("LocalVariable", "src/main.rs"),
+        ]
+
+    def test_exact_and_case_sensitive(self):
+        assert _ranked(search_symbols(MEMBERS, "getname", exact=True)) == []
+        assert _ranked(search_symbols(MEMBERS, "getName", exact=True)) == [("getName", "exact")]
+        assert _ranked(search_symbols(MEMBERS, "getname", case_sensitive=True)) == [("get_name", "boundary")]
+
+    def test_path_prefix(self):
+        matches = search_symbols(MEMBERS, "name", path_prefix="src/model")
+        assert {m.symbol["file_path"] for m in matches} == {"src/model/person.rs"}
+
+
+class TestSymbolIndex:
+    def test_limit_keeps_the_total(self):
+        index = SymbolIndex(SYMBOLS + MEMBERS)
+        found = index.search("a", limit=2)
+        assert len(found.matches) == 2 and found.total == 10
+        assert found.matches == index.search("a").matches[:2]
+        assert index.search("r", node_types=["Class"], limit=1).total == 2
+
+    def test_names_are_shared_between_symbols(self):
+        index = SymbolIndex(MEMBERS + [{"id": "nameless", "name": None, "type": "Function"}])
+        assert len(index) == len(MEMBERS)
+        assert index.search("name", exact=True).total == 3
+
+
+class TestPatterns:
+    def test_regex_literal_flags(self):
+        assert compile_pattern("/^PARSE_/i").search("parse_file")
+        assert not compile_pattern("/^PARSE_/").search("parse_file")
+
+    def test_named_groups_and_lookbehind(self):
+        assert compile_pattern("(?<verb>parse)_(?<what>\\w+)").match("parse_file").group("what") == "file"
+        assert compile_pattern("(?<=re)parse").search("reparse")
+
+    def test_invalid_pattern(self):
+        with pytest.raises(PatternError) as info:
+            search_symbols(SYMBOLS, "parse[")
+        assert info.value.position == 5
+        assert info.value.to_dict()["code"] == "invalid_pattern"
+
+    def test_unknown_flag(self):
+        with pytest.raises(PatternError, match="unknown flag 'x'"):
+            compile_pattern("/parse/x")
+
+    def test_invalid_regex_ignored_without_regex(self):
+        assert _ranked(search_symbols(SYMBOLS, "parse[", use_regex=False)) == []
@@ -0,0 +1,188 @@
+"""
+Tests for test detection: test functions and files flagged is_test per
+language, the TESTS edges drawn from their calls, and the tests found for a
+symbol by walking calls back.
+
+Python goes through the legacy parser; the other languages use hand-built
+nodes so no optional parsing backends are needed.
+"""
+
+import os
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.ast_parser.parser import CodeNode, CodeRelation
+from src.ast_parser.testing import is_test_file_name, mark_tests, tests_relations
+from src.graph.code_graph import CodeGraph
+from src.graph.covering_tests import find_tests_for
+from src.parallel.parse_worker import merge_results, parse_file_task
+from src.parallel.streaming_merge import StreamingMerge
+
+SOURCES = {
+    "cart.py": "def total(items):\n    return sum(price(i) for i in items)\n\n\ndef price(item):\n    return item\n",
+    "test_cart.py": (
+        "from cart import total\n\n\n"
+        "def make():\n    return [1]\n\n\n"
+        "def test_total():\n    assert total(make()) == 1\n"
+    ),
+    "checks.py": (
+        "import unittest\nfrom cart import price\n\n\n"
+        "class PriceCase(unittest.TestCase):\n"
+        "    def test_price(self):\n        self.assertEqual(price(2), 2)\n\n"
+        "    def helper(self):\n        return price(3)\n"
+    ),
+}
+
+
+@pytest.fixture
+def sources(tmp_path):
+    return {str(tmp_path / name): source for name, source in SOURCES.items()}
+
+
+def _tests(graph):
+    return sorted(n.node_id for n in graph.nodes.values() if n.properties.get("is_test"))
+
+
+def _edges(relations):
+    return sorted((r.source_id, r.target_id, r.properties["line_no"]) for r in relations if r.relation_type == "TESTS")
+
+
+def _node(node_type, name, file_path, line_no, **properties):
+    return CodeNode(f"{node_type}:{file_path}:{name}:{line_no}", node_type, name, file_path, line_no,
+                    properties=properties)
+
+
+def _file(file_path):
+    return CodeNode(f"file:{file_path}", "File", os.path.basename(file_path), file_path, 0)
+
+
+class TestFileNames:
+
+    @pytest.mark.parametrize("path", ["tests/test_cart.py", "cart_test.py", "cart_test.go",
+                                      "cart.test.ts", "cart.spec.jsx", "cart.test.mjs"])
+    def test_test_files(self, path):
+        assert is_test_file_name(path)
+
+    @pytest.mark.parametrize("path", ["testing.py", "tests/cart.py", "contest.go", "cart.ts", "test_cart.rs"])
+    def test_other_files(self, path):
+        assert not is_test_file_name(path)
+
+
+class TestPython:
+
+    def test_test_functions_and_files(self, sources, tmp_path):
+        graph = CodeGraph()
+        graph.add_sources(sources)
+        checks, test_cart = str(tmp_path / "checks.py"), str(tmp_path / "test_cart.py")
+        # test_* of a test file and of a TestCase subclass; helpers and cart.py are not tests
+        assert _tests(graph) == sorted([f"file:{checks}", f"Method:{checks}:test_price:6",
+                                        f"file:{test_cart}", f"Function:{test_cart}:test_total:8"])
+
+    def test_tests_edges(self, sources, tmp_path):
+        graph = CodeGraph()
+        graph.add_sources(sources)
+        cart, checks, test_cart = (str(tmp_path / name) for name in ("cart.py", "checks.py", "test_cart.py"))
+        # make() is a helper of the test file: a call, not a TESTS edge
+        assert _edges(graph.relations) == [(f"Function:{test_cart}:test_total:8", f"Function:{cart}:total:1", 9),
+                                           (f"Method:{checks}:test_price:6", f"Function:{cart}:price:5", 7)]
+
+    def test_tests_edges_follow_updates(self, sources, tmp_path):
+        graph = CodeGraph()
+        graph.add_sources(sources)
+        checks, test_cart = str(tmp_path / "checks.py"), str(tmp_path / "test_cart.py")
+        graph.update_file(test_cart, "from cart import total\n\n\ndef check_total():\n    total([])\n")
+        # Still a test file, but without a test function calling into cart.py
+        assert _tests(graph) == sorted([f"file:{checks}", f"Method:{checks}:test_price:6", f"file:{test_cart}"])
+        assert [source for source, _, _ in _edges(graph.relations)] == [f"Method:{checks}:test_price:6"]
+
+    def test_parallel_merge_and_streaming_agree(self, sources):
+        for path, source in sources.items():
+            with open(path, "w", encoding="utf-8") as f:
+                f.write(source)
+        results = [parse_file_task(path) for path in sorted(sources)]
+        _, relations = merge_results(results)
+        graph = CodeGraph()
+        graph.add_sources(sources)
+        assert _edges(relations) == _edges(graph.relations)
+
+        streamed = []
+        merge = StreamingMerge(lambda nodes: None, streamed.extend)
+        for result in results:
+            merge.add(result)
+        merge.finish()
+        assert _edges(streamed) == _edges(relations)
+
+
+class TestOtherLanguages:
+
+    def test_rust_unit_tests_beside_the_code(self):
+        path = "src/cart.rs"
+        nodes = {n.node_id: n for n in [
+            _file(path),
+            _node("Function", "total", path, 1),
+            _node("Function", "total_is_zero", path, 10, attributes=["test"]),
+            _node("Function", "fetches", path, 15, attributes=["tokio::test(flavor = \"multi_thread\")"]),
+            _node("Function", "setup", path, 20, attributes=["cfg(test)"]),
+        ]}
+        relations = [CodeRelation(f"Function:{path}:total_is_zero:10", f"Function:{path}:total:1", "CALLS",
+                                  {"line_no": 11}),
+                     CodeRelation(f"Function:{path}:total_is_zero:10", f"Function:{path}:setup:20", "CALLS",
+                                  {"line_no": 12})]
+        assert mark_tests(path, nodes, relations)
+        assert sorted(n.name for n in nodes.values() if n.properties.get("is_test")) == \
+            ["cart.rs", "fetches", "total_is_zero"]
+        # The file is a test file, but its code is still what the test covers
+        assert _edges(tests_relations(relations, nodes)) == [
+            (f"Function:{path}:total_is_zero:10", f"Function:{path}:setup:20", 12),
+            (f"Function:{path}:total_is_zero:10", f"Function:{path}:total:1", 11)]
+
+    def test_go_test_functions(self):
+        path = "cart/cart_test.go"
+        nodes = {n.node_id: n for n in [_file(path), _node("Function", "TestTotal", path, 5),
+                                        _node("Function", "BenchmarkTotal", path, 10),
+                                        _node("Function", "Testify", path, 15),
+                                        _node("Method", "TestOnSuite", path, 20)]}
+        mark_tests(path, nodes, [])
+        assert sorted(n.name for n in nodes.values() if n.properties.get("is_test")) == \
+            ["BenchmarkTotal", "TestTotal", "cart_test.go"]
+        # Test names outside a _test.go file are not tests
+        other = {n.node_id: n for n in [_file("cart/cart.go"), _node("Function", "TestTotal", "cart/cart.go", 5)]}
+        assert not mark_tests("cart/cart.go", other, [])
+
+    def test_js_test_files(self):
+        path = "src/cart.spec.ts"
+        callback = _node("Function", "<anonymous>", path, 3)
+        nodes = {n.node_id: n for n in [_file(path), callback]}
+        total = _node("Function", "total", "src/cart.ts", 1)
+        mark_tests(path, nodes, [])
+        assert all(n.properties.get("is_test") for n in nodes.values())
+        graph_nodes = dict(nodes, **{total.node_id: total, "file:src/cart.ts": _file("src/cart.ts")})
+        calls = [CodeRelation(callback.node_id, total.node_id, "CALLS", {"line_no": 4}),
+                 CodeRelation(callback.node_id, total.node_id, "CALLS", {"line_no": 6}),
+                 CodeRelation(callback.node_id, "unresolved:expect", "CALLS", {"line_no": 4})]
+        # One edge per test and symbol, at the first call
+        assert _edges(tests_relations(calls, graph_nodes)) == [(callback.node_id, total.node_id, 4)]
+
+
+class TestFindTestsFor:
+
+    def test_tests_reaching_a_symbol(self, sources, tmp_path):
+        graph = CodeGraph()
+        graph.add_sources(sources)
+        cart, checks, test_cart = (str(tmp_path / name) for name in ("cart.py", "checks.py", "test_cart.py"))
+        report = find_tests_for(graph, [f"Function:{cart}:price:5"])
+        assert [(t.name, t.depth) for t in report.tests] == [("test_price", 1), ("test_total", 2)]
+        assert report.tests[1].via == [f"Function:{cart}:price:5", f"Function:{cart}:total:1"]
+        assert report.to_dict()["direct"] == 1
+
+        assert [t.id for t in find_tests_for(graph, [f"Function:{cart}:price:5"], max_depth=1).tests] == \
+            [f"Method:{checks}:test_price:6"]
+        assert find_tests_for(graph, [f"Function:{test_cart}:make:4"]).to_dict()["total"] == 1
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -0,0 +1,99 @@
+"""
+Tests for type hierarchies: supertypes and subtypes walked along IMPLEMENTS
+and EXTENDS edges, with the generic bounds of each type.
+"""
+
+import os
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.ast_parser.parser import CodeNode, CodeRelation
+from src.graph.code_graph import UNRESOLVED_PREFIX, CodeGraph
+from src.graph.type_hierarchy import type_hierarchy
+
+
+def _node(name, file_path="shapes.rs", node_type="Class", line_no=1, **properties):
+    return CodeNode(f"{node_type}:{file_path}:{name}:{line_no}", node_type, name, file_path, line_no,
+                    properties=properties)
+
+
+NAMED = _node("Named", node_type="Interface", line_no=1)
+SHAPE = _node("Shape", node_type="Interface", line_no=5)
+SHAPE_AREA = _node("area", node_type="Method", line_no=6)
+CIRCLE = _node("Circle", line_no=10)
+CIRCLE_AREA = _node("area", node_type="Method", line_no=14)
+SQUARE = _node("Square", "square.rs", line_no=3)
+LABELED = _node("Labeled", "labeled.rs", line_no=1, generic_bounds={"T": ["Shape", "Clone"], "U": []})
+DEBUG = CodeNode(UNRESOLVED_PREFIX + "fmt::Debug", "Unresolved", "fmt::Debug", "", 0)
+SUMMARY = CodeNode("file:summary.rs", "File", "summary.rs", "summary.rs", 0)
+
+
+@pytest.fixture
+def graph():
+    nodes = [NAMED, SHAPE, SHAPE_AREA, CIRCLE, CIRCLE_AREA, SQUARE, LABELED, DEBUG, SUMMARY]
+    relations = [
+        CodeRelation(SHAPE.node_id, NAMED.node_id, "EXTENDS", {"trait_path": "Named"}),
+        CodeRelation(SHAPE.node_id, DEBUG.node_id, "EXTENDS", {"trait_path": "fmt::Debug", "unresolved": True}),
+        CodeRelation(SHAPE.node_id, SHAPE_AREA.node_id, "DEFINES"),
+        CodeRelation(CIRCLE.node_id, SHAPE.node_id, "IMPLEMENTS", {"trait_path": "Shape"}),
+        CodeRelation(CIRCLE.node_id, NAMED.node_id, "IMPLEMENTS", {"trait_path": "Named"}),
+        CodeRelation(CIRCLE.node_id, DEBUG.node_id, "IMPLEMENTS", {"derived": True}),
+        CodeRelation(CIRCLE_AREA.node_id, SHAPE_AREA.node_id, "IMPLEMENTS", {"type": "Circle"}),
+        CodeRelation(SQUARE.node_id, SHAPE.node_id, "IMPLEMENTS", {"trait_path": "crate::shapes::Shape"}),
+        CodeRelation(SUMMARY.node_id, NAMED.node_id, "IMPLEMENTS", {"blanket": True, "for_type": "T"}),
+        CodeRelation(LABELED.node_id, SHAPE.node_id, "USES_TYPE"),
+    ]
+    return CodeGraph.from_records(nodes, relations)
+
+
+def _types(hierarchy):
+    return [(t.name, t.role, t.depth) for t in hierarchy.types]
+
+
+class TestTypeHierarchy:
+
+    def test_supertypes_of_a_type(self, graph):
+        hierarchy = type_hierarchy(graph, CIRCLE.node_id)
+        # Named is a direct supertype, so reaching it again through Shape changes nothing
+        assert _types(hierarchy) == [("Named", "supertype", 1), ("Shape", "supertype", 1),
+                                     ("fmt::Debug", "supertype", 1)]
+        data = hierarchy.to_dict()
+        assert data["supertypes"] == ["Named", "Shape", "fmt::Debug"]
+        assert data["subtypes"] == []
+        assert data["nodes"][0] == {"id": CIRCLE.node_id, "name": "Circle", "type": "Class",
+                                    "file_path": "shapes.rs", "line_no": 10, "role": "root", "depth": 0}
+        # The edges between the types, Shape's supertraits included; method edges are left out
+        assert [(e["source"], e["target"]) for e in data["edges"]] == [
+            (CIRCLE.node_id, NAMED.node_id), (CIRCLE.node_id, SHAPE.node_id), (CIRCLE.node_id, DEBUG.node_id),
+            (SHAPE.node_id, NAMED.node_id), (SHAPE.node_id, DEBUG.node_id)]
+        assert data["edges"][2]["properties"] == {"derived": True}
+
+    def test_subtypes_of_a_trait(self, graph):
+        hierarchy = type_hierarchy(graph, NAMED.node_id)
+        # Implementors and subtraits first, then what implements those, and a blanket impl's file
+        assert _types(hierarchy) == [("Circle", "subtype", 1), ("Shape", "subtype", 1),
+                                     ("summary.rs", "subtype", 1), ("Square", "subtype", 2)]
+        shallow = type_hierarchy(graph, NAMED.node_id, max_depth=1)
+        assert _types(shallow)[-1] == ("summary.rs", "subtype", 1)
+        # Shape's implementors lie past the limit
+        assert shallow.frontier == [SHAPE.node_id]
+        assert hierarchy.frontier == []
+
+    def test_both_directions(self, graph):
+        hierarchy = type_hierarchy(graph, SHAPE.node_id, max_depth=1)
+        assert _types(hierarchy) == [("Named", "supertype", 1), ("fmt::Debug", "supertype", 1),
+                                     ("Circle", "subtype", 1), ("Square", "subtype", 1)]
+
+    def test_generic_bounds(self, graph):
+        data = type_hierarchy(graph, LABELED.node_id).to_dict()
+        assert data["generic_bounds"] == {"T": ["Shape", "Clone"], "U": []}
+        assert data["nodes"][0]["generic_bounds"] == data["generic_bounds"]
+        assert (data["supertypes"], data["subtypes"], data["edges"]) == ([], [], [])
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -3,7 +3,7 @@ Unit tests for TypeScriptParser class.
 
 Tests the parsing of JavaScript and TypeScript files including:
 - Function declarations (standard, arrow, async)
-- Class declarations (with inheritance and methods)
+- Class declarations (with inheritance, implements clauses and methods)
 - Variable declarations (const, let, var)
 - Import/export statements
 """
@@ -388,6 +388,69 @@ function example(x) {
         self.assertIn("function example", func_node.code_snippet)
         self.assertIn("return x * 2", func_node.code_snippet)
 
+    def test_implements_clauses(self):
+        """Test that implements clauses link classes to their interfaces."""
+        content = """
+import { Serializable } from './serial';
+
+class Base {}
+
+export class User extends Base implements Named, Serializable, Comparable<User> {
+    name = "";
+}
+
+interface Named {
+    name: string;
+}
+"""
+        file_path = self._create_test_file("user.ts", content)
+        nodes, relations = self.parser.parse_file(file_path)
+
+        user = next(n for n in nodes.values() if n.node_type == "Class" and n.name == "User")
+        named = next(n for n in nodes.values() if n.node_type == "Interface" and n.name == "Named")
+        implements = [r for r in relations if r.relation_type == "IMPLEMENTS"]
+        self.assertEqual([(r.source_id, r.target_id, r.properties["trait_path"]) for r in implements],
+                         [(user.node_id, named.node_id, "Named")])
+
+        # Interfaces from other files wait for the second pass
+        pending = [p for p in self.parser.pending_imports if p["type"] == "IMPLEMENTS"]
+        self.assertEqual([(p["trait_name"], p["trait_path"], p["module_hints"]) for p in pending],
+                         [("Serializable", "Serializable", ["serial"]),
+                          ("Comparable", "Comparable<User>", [])])
+        self.assertTrue(all(p["type_node_id"] == user.node_id for p in pending))
+
+    def test_sample_module_exports(self):
+        """Test named, aliased and default exports of the sample module."""
+        fixture = os.path.join(os.path.dirname(__file__), "fixtures", "ts_exports", "sample.ts")
+        nodes, _ = self.parser.parse_file(fixture)
+
+        exports = sorted((n.node_type, n.name, n.properties["export_type"], n.properties["export_name"])
+                         for n in nodes.values() if n.properties.get("exported"))
+        self.assertEqual(exports, [
+            ("Class", "Greeter", "named", "Greeter"),
+            ("Function", "default", "default", "default"),
+            ("Function", "greet", "named", "greet"),
+            ("Function", "internalHelper", "named", "internalHelper"),
+            ("Function", "shout", "named", "shout"),
+            ("Function", "whisper", "named", "quiet"),
+        ])
+
+        # Arrow functions take the name of the const they are bound to
+        shout = next(n for n in nodes.values() if n.name == "shout")
+        self.assertEqual(shout.properties["function_style"], "arrow")
+        default = next(n for n in nodes.values() if n.name == "default")
+        self.assertEqual((default.line_no, default.properties["doc"]), (32, "Entry point of the module."))
+
+    def test_tsx_sample_skips_jsx(self):
+        """Test that a .tsx file parses with the JSX left out."""
+        fixture = os.path.join(os.path.dirname(__file__), "fixtures", "ts_exports", "sample.tsx")
+        nodes, _ = self.parser.parse_file(fixture)
+
+        functions = {n.name: n for n in nodes.values() if n.node_type == "Function"}
+        self.assertEqual(sorted(functions), ["App", "Button"])
+        self.assertEqual(functions["App"].properties["export_type"], "default")
+        self.assertEqual(functions["Button"].properties["export_type"], "named")
+
 
 if __name__ == '__main__':
     unittest.main()
@@ -0,0 +1,252 @@
+"""
+Tests for watch mode: polling change detection and incremental graph sync.
+
+Uses the legacy Python parser so no optional parsing backends are needed.
+"""
+
+import os
+import shutil
+import sys
+import threading
+from unittest.mock import MagicMock
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.graph.code_graph import CodeGraph, relation_key
+from src.graph.watcher import CREATED, DELETED, MODIFIED, FileChange, PollingWatcher, apply_changes, coalesce
+from tests.helpers import graph_shape
+
+
+FIXTURE_PY = os.path.join(os.path.dirname(os.path.abspath(__file__)),
+                          "fixtures", "multi_lang_sample", "sample.py")
+
+APP = '''
+from sample import greet
+
+
+def welcome():
+    greet("world")
+'''
+
+
+def _write(directory, name, content, mtime_ns=None):
+    path = os.path.join(str(directory), name)
+    with open(path, "w", encoding="utf-8") as f:
+        f.write(content)
+    if mtime_ns is not None:
+        # Filesystem timestamps can be coarse; set them explicitly
+        os.utime(path, ns=(mtime_ns, mtime_ns))
+    return path
+
+
+@pytest.fixture
+def codebase(tmp_path):
+    with open(FIXTURE_PY, encoding="utf-8") as f:
+        sample_source = f.read()
+    sample = _write(tmp_path, "sample.py", sample_source, mtime_ns=1_000_000_000)
+    app = _write(tmp_path, "app.py", APP, mtime_ns=1_000_000_000)
+    return tmp_path, sample, app, sample_source
+
+
+class TestPollingWatcher:
+
+    def test_detects_created_modified_and_deleted_files(self, codebase):
+        root, sample, app, _ = codebase
+        graph = CodeGraph()
+        watcher = PollingWatcher(str(root), graph.collect_source_files)
+        assert watcher.poll() == []
+
+        _write(root, "app.py", APP + "\n", mtime_ns=2_000_000_000)
+        new = _write(root, "extra.py", "x = 1\n")
+        os.remove(sample)
+
+        assert watcher.poll() == [
+            FileChange(app, MODIFIED),
+            FileChange(new, CREATED),
+            FileChange(sample, DELETED),
+        ]
+        assert watcher.poll() == []
+
+    def test_ignores_unsupported_files(self, codebase):
+        root, _, _, _ = codebase
+        watcher = PollingWatcher(str(root), CodeGraph().collect_source_files)
+        _write(root, "notes.txt", "hello")
+        assert watcher.poll() == []
+
+
+class TestDebounce:
+
+    def test_coalesce_merges_changes_per_path(self):
+        changes = [
+            FileChange("b.py", CREATED), FileChange("b.py", MODIFIED),
+            FileChange("c.py", CREATED), FileChange("c.py", DELETED),
+            FileChange("d.py", DELETED), FileChange("d.py", CREATED),
+            FileChange("e.py", MODIFIED), FileChange("e.py", DELETED),
+            FileChange("a.py", MODIFIED), FileChange("a.py", MODIFIED),
+        ]
+        assert coalesce(changes) == [
+            FileChange("e.py", DELETED),
+            FileChange("a.py", MODIFIED),
+            FileChange("b.py", CREATED),
+            FileChange("d.py", MODIFIED),
+        ]
+
+    def test_burst_of_saves_is_one_batch(self, codebase):
+        root, _, app, _ = codebase
+        watcher = PollingWatcher(str(root), CodeGraph().collect_source_files, interval=0, debounce=0.01)
+        polls = iter([[FileChange(app, MODIFIED)], [FileChange(app, MODIFIED)], [FileChange(app, MODIFIED)], []])
+        watcher.poll = lambda: next(polls)
+        stop = threading.Event()
+        batches = []
+
+        def on_changes(changes):
+            batches.append(changes)
+            stop.set()
+
+        watcher.run(on_changes, stop)
+        assert batches == [[FileChange(app, MODIFIED)]]
+
+
+class TestWatchSync:
+
+    def test_mutated_fixture_converges_to_full_rebuild(self, codebase):
+        root, sample, _, sample_source = codebase
+        graph = CodeGraph.from_directory(str(root))
+        watcher = PollingWatcher(str(root), graph.collect_source_files)
+
+        # Rename greet and insert a function above it, shifting every line
+        mutated = sample_source.replace("def greet(", "def shout(").replace("    greet(", "    shout(")
+        mutated = "def first():\n    return 0\n\n\n" + mutated
+        _write(root, "sample.py", mutated, mtime_ns=2_000_000_000)
+        apply_changes(graph, watcher.poll())
+
+        assert graph_shape(graph) == graph_shape(CodeGraph.from_directory(str(root)))
+        # welcome() in app.py still imports greet, which no longer exists
+        unresolved = [r for r in graph.relations
+                      if r.relation_type == "CALLS" and r.properties.get("unresolved")]
+        assert any(r.properties["raw_name"] == "sample.greet" for r in unresolved)
+
+    def test_edges_from_other_files_survive_an_unrelated_edit(self, codebase):
+        root, sample, app, sample_source = codebase
+        graph = CodeGraph.from_directory(str(root))
+        watcher = PollingWatcher(str(root), graph.collect_source_files)
+        incoming = {relation_key(r) for r in graph.files[app].owned_relations()}
+
+        # Appending code leaves greet's node id (and the edge into it) intact
+        _write(root, "sample.py", sample_source + "\n\ndef later():\n    pass\n",
+               mtime_ns=2_000_000_000)
+        [delta] = apply_changes(graph, watcher.poll())
+
+        assert not any(r.source_id.startswith("Function:" + app) for r in delta.removed_relations)
+        assert {relation_key(r) for r in graph.files[app].owned_relations()} == incoming
+        assert any(node_id.endswith(":later:" + str(len(sample_source.splitlines()) + 3))
+                   for node_id in delta.added_nodes)
+
+    def test_deleted_file_is_removed(self, codebase):
+        root, sample, _, _ = codebase
+        graph = CodeGraph.from_directory(str(root))
+        watcher = PollingWatcher(str(root), graph.collect_source_files)
+
+        os.remove(sample)
+        [delta] = apply_changes(graph, watcher.poll())
+
+        assert f"file:{sample}" in delta.removed_nodes
+        assert graph_shape(graph) == graph_shape(CodeGraph.from_directory(str(root)))
+
+
+class TestApplyChanges:
+
+    def test_rename_is_delete_and_add(self, codebase):
+        root, sample, _, _ = codebase
+        graph = CodeGraph.from_directory(str(root))
+        watcher = PollingWatcher(str(root), graph.collect_source_files)
+
+        renamed = os.path.join(str(root), "greetings.py")
+        os.rename(sample, renamed)
+        changes = watcher.poll()
+        assert set(changes) == {FileChange(sample, DELETED), FileChange(renamed, CREATED)}
+        apply_changes(graph, changes)
+
+        assert f"file:{sample}" not in graph.nodes
+        assert renamed in graph.files
+        assert graph_shape(graph) == graph_shape(CodeGraph.from_directory(str(root)))
+
+    def test_deleted_directory_cascades(self, codebase):
+        root, _, _, _ = codebase
+        package = os.path.join(str(root), "pkg")
+        os.makedirs(os.path.join(package, "sub"))
+        inner = _write(package, "inner.py", "def inner():\n    pass\n")
+        nested = _write(os.path.join(package, "sub"), "nested.py", "x = 1\n")
+        sibling = _write(root, "pkg_tools.py", "y = 2\n")
+        graph = CodeGraph.from_directory(str(root))
+
+        shutil.rmtree(package)
+        deltas = apply_changes(graph, [FileChange(package, DELETED)])
+
+        assert len(deltas) == 2
+        assert inner not in graph.files and nested not in graph.files
+        assert sibling in graph.files
+        assert graph_shape(graph) == graph_shape(CodeGraph.from_directory(str(root)))
+
+    def test_save_without_edits_is_not_reparsed(self, codebase):
+        root, sample, _, sample_source = codebase
+        graph = CodeGraph.from_directory(str(root))
+        watcher = PollingWatcher(str(root), graph.collect_source_files)
+
+        _write(root, "sample.py", sample_source, mtime_ns=2_000_000_000)
+        changes = watcher.poll()
+
+        assert changes == [FileChange(sample, MODIFIED)]
+        assert apply_changes(graph, changes) == []
+
+
+class TestApplyGraphDelta:
+    """CodebaseKnowledgeGraph.apply_graph_delta against a mocked database."""
+
+    @pytest.fixture
+    def kg(self):
+        from src.main import CodebaseKnowledgeGraph
+        kg = CodebaseKnowledgeGraph.__new__(CodebaseKnowledgeGraph)
+        kg.db = MagicMock()
+        kg._generate_embeddings = lambda nodes: None
+        return kg
+
+    def test_writes_delta(self, kg, codebase):
+        root, sample, _, sample_source = codebase
+        graph = CodeGraph.from_directory(str(root))
+        delta = graph.update_file(sample, sample_source.replace("def add(", "def plus("))
+
+        kg.apply_graph_delta(graph, delta)
+
+        deleted_ids = kg.db.delete_nodes.call_args[0][0]
+        assert any(":add:" in node_id for node_id in deleted_ids)
+        upserted = kg.db.upsert_nodes.call_args[0][0]
+        assert any(n["properties"]["name"] == "plus" for n in upserted)
+        assert all("embedding" not in node.properties for node in graph.nodes.values())
+        created = kg.db.batch_create_relationships.call_args[0][0]
+        assert len(created) == len(delta.added_relations)
+
+    def test_writes_in_one_transaction(self, kg, codebase):
+        root, sample, _, sample_source = codebase
+        graph = CodeGraph.from_directory(str(root))
+        delta = graph.update_file(sample, sample_source.replace("def add(", "def plus("))
+
+        kg.apply_graph_delta(graph, delta)
+
+        calls = [name for name, _, _ in kg.db.mock_calls]
+        enter, exit_ = calls.index("write_batch().__enter__"), calls.index("write_batch().__exit__")
+        for write in ("delete_relationships", "delete_nodes", "upsert_nodes", "batch_create_relationships"):
+            assert enter < calls.index(write) < exit_
+
+    def test_empty_delta_does_nothing(self, kg, codebase):
+        root, sample, _, _ = codebase
+        graph = CodeGraph.from_directory(str(root))
+        kg.apply_graph_delta(graph, graph.update_file(sample))
+        assert not kg.db.method_calls
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
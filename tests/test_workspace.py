@@ -0,0 +1,70 @@
+"""
+Tests for workspace member discovery.
+
+Covers tests/fixtures/rust_workspace, a virtual Cargo workspace of two
+crates with a second workspace nested in it, and manifests of the other
+kinds written to a temporary directory.
+"""
+
+import json
+import os
+import sys
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.ast_parser.workspace import find_workspace_member, manifest_name, workspace_properties
+
+
+RUST_WORKSPACE = os.path.abspath(os.path.join(os.path.dirname(__file__), "fixtures", "rust_workspace"))
+
+
+def _path(*parts):
+    return os.path.join(RUST_WORKSPACE, *parts)
+
+
+class TestCargoWorkspace:
+    def test_nearest_manifest_names_the_member(self):
+        core = find_workspace_member(_path("core", "src", "person.rs"))
+        assert (core.name, core.root, core.manifest) == ("core-lib", _path("core"), "Cargo.toml")
+        assert find_workspace_member(_path("tools", "cli", "src", "lib.rs")).name == "cli"
+
+    def test_virtual_manifest_is_named_after_its_directory(self):
+        assert manifest_name(_path("Cargo.toml")) is None
+        assert find_workspace_member(_path("tools", "README.md")).name == "tools"
+
+    def test_same_relative_paths_get_distinct_names(self):
+        core = workspace_properties(_path("core", "src", "lib.rs"))
+        cli = workspace_properties(_path("tools", "cli", "src", "lib.rs"))
+        assert (core, cli) == (
+            {"workspace_member": "core-lib", "qualified_module": "core-lib::src::lib"},
+            {"workspace_member": "cli", "qualified_module": "cli::src::lib"},
+        )
+
+    def test_outside_any_member(self, tmp_path):
+        assert workspace_properties(str(tmp_path / "loose.py")) == {}
+
+
+class TestOtherManifests:
+    def test_package_json_go_mod_and_pyproject(self, tmp_path):
+        for directory, manifest, text in (
+            ("web", "package.json", json.dumps({"name": "@acme/web", "version": "1.0.0"})),
+            ("svc", "go.mod", "module github.com/acme/svc\n\ngo 1.22\n"),
+            ("py", "pyproject.toml", '[build-system]\nname = "not-this"\n\n[project]\nname = "acme-py"\n'),
+            ("poetry", "pyproject.toml", '[tool.poetry]\nname = "acme-poetry"\n'),
+        ):
+            (tmp_path / directory).mkdir()
+            (tmp_path / directory / manifest).write_text(text, encoding="utf-8")
+        assert [find_workspace_member(str(tmp_path / d / "src" / "x")).name for d in ("web", "svc", "py", "poetry")] \
+            == ["@acme/web", "svc", "acme-py", "acme-poetry"]
+
+    def test_unreadable_manifest_falls_back_to_the_directory(self, tmp_path):
+        (tmp_path / "broken").mkdir()
+        (tmp_path / "broken" / "package.json").write_text("{not json", encoding="utf-8")
+        assert find_workspace_member(str(tmp_path / "broken" / "index.js")).name == "broken"
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
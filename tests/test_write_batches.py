@@ -0,0 +1,144 @@
+"""
+Tests for the batched graph writes.
+
+The Neo4j driver is replaced by a mock recording the statements run and
+their rows, and failing for rows marked ``bad``.
+"""
+
+import os
+import sys
+from unittest.mock import MagicMock, patch
+
+import pytest
+
+# Add project root to Python path
+sys.path.insert(0, os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+
+from src.neo4j_storage.batching import row_size, split_batches, write_batches
+from src.neo4j_storage.graph_db import Neo4jDatabase
+
+
+class FlakyWriter:
+    """Writes batches, failing the first `failures` attempts and every batch holding a bad row."""
+
+    def __init__(self, failures=0):
+        self.failures = failures
+        self.attempts = []
+        self.written = []
+
+    def __call__(self, batch):
+        self.attempts.append(list(batch))
+        if self.failures:
+            self.failures -= 1
+            raise ConnectionError("connection reset")
+        if any(row.get("bad") for row in batch):
+            raise ValueError("malformed row")
+        self.written.extend(batch)
+
+
+class TestSplitBatches:
+    def test_by_count(self):
+        assert [len(b) for b in split_batches(range(2500), batch_size=1000)] == [1000, 1000, 500]
+
+    def test_by_size(self):
+        rows = [{"embedding": [0.5] * 100} for _ in range(10)]
+        batches = list(split_batches(rows, batch_size=1000, max_bytes=3 * row_size(rows[0])))
+        assert [len(b) for b in batches] == [3, 3, 3, 1]
+
+    def test_oversized_row_is_sent_alone(self):
+        rows = [{"id": 1}, {"id": 2, "text": "x" * 100}, {"id": 3}]
+        assert [[r["id"] for r in b] for b in split_batches(rows, max_bytes=50)] == [[1], [2], [3]]
+
+
+class TestWriteBatches:
+    def test_retries_with_backoff(self):
+        writer, delays = FlakyWriter(failures=2), []
+        rows = [{"id": i} for i in range(5)]
+        failed = write_batches(rows, writer, batch_size=5, max_retries=3, backoff=0.5, sleep=delays.append)
+        assert (failed, writer.written, delays) == ([], rows, [0.5, 1.0])
+
+    def test_bad_row_falls_back_to_one_by_one(self):
+        writer, delays = FlakyWriter(), []
+        rows = [{"id": i, "bad": i == 3} for i in range(6)]
+        failed = write_batches(rows, writer, batch_size=4, max_retries=2, backoff=1, sleep=delays.append)
+
+        assert failed == [rows[3]]
+        # The siblings of the bad row are still written
+        assert [r["id"] for r in writer.written] == [0, 1, 2, 4, 5]
+        # First batch: 3 attempts, then its 4 rows alone; second batch at once
+        assert [len(a) for a in writer.attempts] == [4, 4, 4, 1, 1, 1, 1, 2]
+        assert delays == [1, 2]
+
+
+@pytest.fixture
+def db():
+    """Neo4jDatabase over a mock driver; ``db.statements`` holds (query, rows) of every write."""
+    with patch("src.neo4j_storage.graph_db.GraphDatabase") as graph_database:
+        database = Neo4jDatabase(uri="bolt://test", user="u", password="p", batch_size=2,
+                                 max_retries=1, retry_backoff=0)
+    database.statements = []
+
+    def run(query, params=None):
+        rows = (params or {}).get("rows", [])
+        if any((row.get("properties") or {}).get("bad") for row in rows):
+            raise ValueError("malformed row")
+        database.statements.append((" ".join(query.split()), rows))
+        return MagicMock()
+
+    session = MagicMock()
+    session.run.side_effect = run
+    graph_database.driver.return_value.session.return_value.__enter__.return_value = session
+    database.driver = graph_database.driver.return_value
+    return database
+
+
+def _node(node_id, *labels, **properties):
+    return {"labels": ["Base", *labels], "properties": {"id": node_id, **properties}}
+
+
+class TestNeo4jWrites:
+    def test_nodes_are_grouped_by_labels_and_batched(self, db):
+        db.batch_create_nodes([_node("a", "Function"), _node("b", "Class"), _node("c", "Function"),
+                               _node("d", "Function")])
+        assert [(query, [row["properties"]["id"] for row in rows]) for query, rows in db.statements] == [
+            ("UNWIND $rows AS row CREATE (n:Base:Function) SET n = row.properties", ["a", "c"]),
+            ("UNWIND $rows AS row CREATE (n:Base:Function) SET n = row.properties", ["d"]),
+            ("UNWIND $rows AS row CREATE (n:Base:Class) SET n = row.properties", ["b"]),
+        ]
+
+    def test_relationships_are_grouped_by_type(self, db):
+        db.batch_create_relationships([
+            {"start_node_id": "a", "end_node_id": "b", "type": "CALLS", "properties": None},
+            {"start_node_id": "a", "end_node_id": "c", "type": "DEFINES", "properties": {}},
+        ])
+        assert [(query.split("CREATE ")[1].split(" ")[0], len(rows)) for query, rows in db.statements] == \
+            [("(start)-[r:CALLS]->(end)", 1), ("(start)-[r:DEFINES]->(end)", 1)]
+
+    def test_malformed_node_does_not_drop_its_siblings(self, db):
+        db.batch_create_nodes([_node("a", "Function"), _node("b", "Function", bad=True), _node("c", "Function")])
+        assert [row["properties"]["id"] for _, rows in db.statements for row in rows] == ["a", "c"]
+
+    def test_upsert_labels(self, db):
+        db.upsert_nodes([_node("a", "Function"), _node("f")])
+        assert [query for query, _ in db.statements] == [
+            "UNWIND $rows AS row MERGE (n:Base {id: row.properties.id}) SET n = row.properties SET n:Function",
+            "UNWIND $rows AS row MERGE (n:Base {id: row.properties.id}) SET n = row.properties",
+        ]
+
+    def test_labels_and_types_are_checked(self, db):
+        with pytest.raises(ValueError):
+            db.batch_create_nodes([_node("a", "Function) DETACH DELETE (n")])
+        with pytest.raises(ValueError):
+            db.delete_relationships([{"start_node_id": "a", "end_node_id": "b", "type": "CALLS]-()", "properties": {}}])
+
+    def test_no_retry_inside_write_batch(self, db):
+        tx = MagicMock()
+        tx.run.side_effect = ValueError("malformed row")
+        db._local.tx = tx
+        with pytest.raises(ValueError):
+            db.upsert_nodes([_node("a", "Function"), _node("b", "Function"), _node("c", "Function")])
+        assert tx.run.call_count == 1
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])